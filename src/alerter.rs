@@ -3,8 +3,12 @@
 // =============================================================================
 //
 // Responsabilitati:
-//   1. Trimite alerte catre SIEM (ArcSight) prin UDP syslog
+//   1. Trimite alerte catre SIEM (ArcSight) prin UDP syslog — CEF sau ECS JSON
 //   2. Trimite notificari email catre echipa IT/Security
+//   3. Trimite alerte GELF 1.1 catre Graylog prin UDP (cu chunking)
+//   4. Trimite alerte JSON catre Microsoft Sentinel (Log Analytics, HTTPS)
+//   5. Destinatiile sunt instante `AlertSink` — sectiunile legacy si lista
+//      `alerting.sinks` (mai multe instante de acelasi tip)
 // CONCEPTE RUST EXPLICATE:
 //
 // 1. ASYNC/AWAIT (Asincronicitate)
@@ -34,19 +38,29 @@
 //
 // =============================================================================
 
-use crate::config::{AlertingConfig, DetectionConfig, EmailConfig, SubnetEntry};
-use crate::detector::{Alert, ScanType};
+use crate::classify;
+use crate::config::{
+    AlertingConfig, DetectionConfig, EmailConfig, GelfConfig, ProfileConfig, SensorConfig,
+    SentinelConfig, SiemConfig, SinkEntry, SinkKind, SubnetEntry, DEFAULT_PROFILE,
+};
+use crate::detector::{Alert, ScanType, SensorIdentity};
 use crate::display;
-use anyhow::{Context, Result};
+use crate::metrics::LastErrors;
+use crate::rollup::RollupSummary;
+use anyhow::{bail, Context, Result};
 use arc_swap::ArcSwap;
+use chrono::{DateTime, Local, SecondsFormat};
 use lettre::{
     message::header::ContentType, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
     AsyncTransport, Message, Tokio1Executor,
 };
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::future::Future;
 use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::net::UdpSocket;
 
 // =============================================================================
@@ -108,6 +122,17 @@ fn sanitize_cef_extension(input: &str) -> String {
         .replace(' ', "\\ ")
 }
 
+/// Intrarea senzorului (network.ingress_name): campul CEF standard
+/// deviceInboundInterface — SIEM-ul atribuie alerta segmentului corect.
+/// Gol cand alerta nu are intrare.
+fn cef_ingress_field(ingress: &str) -> String {
+    if ingress.is_empty() {
+        String::new()
+    } else {
+        format!(" deviceInboundInterface={}", sanitize_cef_extension(ingress))
+    }
+}
+
 fn sorted_ip_list(ips: &[IpAddr]) -> String {
     ips.iter()
         .copied()
@@ -128,6 +153,704 @@ fn sorted_ip_list_with_primary(ips: &[IpAddr], primary: IpAddr) -> String {
         .join(",")
 }
 
+/// Parametrii deja calculati pentru CEF, refolositi in documentul ECS.
+struct EcsContext<'a> {
+    sig_id: &'a str,
+    event_name: &'a str,
+    severity: u8,
+    message: &'a str,
+    hostnames: &'a HashMap<IpAddr, String>,
+}
+
+/// Construieste documentul ECS (Elastic Common Schema) pentru o alerta.
+///
+/// Campurile standard ECS permit alertelor sa intre direct in dashboard-urile
+/// si regulile Elastic existente, fara pipeline de transformare:
+///   - `event.*`   → kind=alert, categoria network/intrusion_detection
+///   - `source.*` / `destination.*` → IP-uri + hostname-uri din config
+///   - `threat.*`  → tehnica MITRE ATT&CK + indicatorul (IP-ul sursa)
+///   - `rule.*`    → SignatureID / nume (aceleasi ca in CEF) + policy_version
+///   - `observer.ingress.*` → intrarea senzorului (`network.ingress_name`)
+///   - `network.vlan.id` → VLAN-ul interfetei firewall (`network.interface_metadata`)
+///   - `observer.hostname` / `labels.*` → identitatea senzorului (`[sensor]`)
+///
+/// Detaliile specifice IDS-RS (porturi, destinatii, surse, activul din harta
+/// CMDB `ids_rs.asset`, interfata firewall-ului `ids_rs.interface`) stau in namespace-ul custom `ids_rs.*` — conventia ECS
+/// pentru campuri in afara schemei.
+///
+/// `network.transport` nu este emis: alerta agrega mai multe evenimente,
+/// posibil cu protocoale diferite — lista lor sta in `ids_rs.protocols`.
+///
+/// NOTA RUST: `serde_json::json!` construieste un `Value` dintr-o sintaxa
+/// asemanatoare JSON; `Option` devine `null`, iar `Vec` devine array.
+fn build_ecs_document(alert: &Alert, ctx: &EcsContext<'_>) -> serde_json::Value {
+    let (technique_id, technique_name) = match alert.scan_type {
+        ScanType::Fast
+        | ScanType::Slow
+        | ScanType::AcceptScan
+        | ScanType::DistributedScan
+        | ScanType::CoordinatedSubnet
+        | ScanType::CustomRule
+        | ScanType::SlowSweep => (Some("T1046"), Some("Network Service Discovery")),
+        ScanType::LateralMovement => (Some("T1021"), Some("Remote Services")),
+        ScanType::Beaconing => (Some("T1071"), Some("Application Layer Protocol")),
+        ScanType::Flood => (Some("T1498"), Some("Network Denial of Service")),
+        // Saturatia este o stare a senzorului, nu o tehnica a atacatorului.
+        ScanType::Saturation | ScanType::NoTraffic => (None, None),
+        // Fuzzing / sondarea portului de receptie al senzorului.
+        ScanType::MalformedInput => (Some("T1595"), Some("Active Scanning")),
+        // Capatul unui episod deja raportat — nu o tehnica noua.
+        ScanType::ScanEnded => (None, None),
+    };
+    let indicator_type = match alert.source_ip {
+        IpAddr::V4(_) => "ipv4-addr",
+        IpAddr::V6(_) => "ipv6-addr",
+    };
+
+    let mut doc = serde_json::json!({
+        "@timestamp": display::format_rfc3339(&alert.timestamp),
+        "ecs": { "version": "8.11.0" },
+        "message": ctx.message,
+        "event": {
+            "kind": "alert",
+            "category": ["network", "intrusion_detection"],
+            "type": ["info"],
+            "module": "ids-rs",
+            "dataset": "ids_rs.alert",
+            "code": ctx.sig_id,
+            "action": alert.scan_type.to_string(),
+            "severity": ctx.severity,
+        },
+        "rule": {
+            "id": ctx.sig_id,
+            "name": ctx.event_name,
+            "ruleset": "ids-rs",
+            "version": alert.policy_version,
+        },
+        "source": { "ip": alert.source_ip },
+        "threat": {
+            "indicator": {
+                "type": indicator_type,
+                "ip": alert.source_ip,
+                "description": ctx.event_name,
+            },
+        },
+        "ids_rs": {
+            "scan_type": alert.scan_type,
+            "unique_ports": alert.unique_ports,
+            "port_ranges": port_ranges_field(alert),
+            "unique_dests": alert.unique_dests,
+            "unique_sources": alert.unique_sources,
+            "time_to_threshold_ms": alert.time_to_threshold_ms,
+            "empty_probes": alert.empty_probes,
+            "promoted_from_slow": alert.promoted_from_slow,
+            "protocols": alert.protocols,
+            "multi_protocol": alert.multi_protocol,
+            "source_port_exhaustion": alert.source_port_exhaustion,
+            "detection_context": alert.detection_context,
+            "approx_port_count": alert.approx_port_count,
+            "weighted_score": alert.weighted_score,
+            "asset_criticality": alert.asset_criticality,
+            "asset": alert.asset,
+            "false_positive": alert.false_positive,
+            "tor_exit": alert.tor_exit,
+            "classification": alert.classification,
+            "classification_reasons": alert.classification_reasons,
+            "merged_count": alert.merged_count,
+            "profile": alert.profile,
+        },
+    });
+
+    // Cheia de idempotenta (alerting.idempotency_key): campul ECS standard event.id.
+    if !alert.idempotency_key.is_empty() {
+        doc["event"]["id"] = serde_json::json!(alert.idempotency_key);
+    }
+    if !alert.ingress.is_empty() {
+        doc["observer"] = serde_json::json!({
+            "ingress": { "interface": { "name": alert.ingress } },
+        });
+    }
+    if let Some(ref sensor) = alert.sensor {
+        // `observer` poate exista deja (ingress); indexarea completeaza obiectul.
+        doc["observer"]["hostname"] = serde_json::json!(sensor.hostname);
+        if !sensor.tags.is_empty() {
+            doc["labels"] = serde_json::json!(sensor.tags);
+        }
+    }
+    if !alert.interface.is_empty() {
+        doc["ids_rs"]["interface"] = serde_json::json!(alert.interface);
+    }
+    if let Some(vlan) = alert.vlan {
+        doc["network"] = serde_json::json!({ "vlan": { "id": vlan.to_string() } });
+    }
+    if let Some(name) = ctx.hostnames.get(&alert.source_ip) {
+        doc["source"]["domain"] = serde_json::json!(name);
+    }
+    if let Some(dst) = alert.dest_ip {
+        doc["destination"] = serde_json::json!({ "ip": dst });
+        if let Some(port) = alert.beacon_port {
+            doc["destination"]["port"] = serde_json::json!(port);
+        }
+        if let Some(name) = ctx.hostnames.get(&dst) {
+            doc["destination"]["domain"] = serde_json::json!(name);
+        }
+    }
+    if let (Some(id), Some(name)) = (technique_id, technique_name) {
+        doc["threat"]["framework"] = serde_json::json!("MITRE ATT&CK");
+        doc["threat"]["technique"] = serde_json::json!({ "id": [id], "name": [name] });
+    }
+
+    doc
+}
+
+// =============================================================================
+// Antet syslog — RFC 3164 (legacy) sau RFC 5424 (alerting.siem.rfc)
+// =============================================================================
+//
+//   3164:  <38>Oct 15 14:30:12 ids-rs CEF:0|...
+//   5424:  <38>1 2026-10-15T14:30:12.345+03:00 fw-sensor01 ids-rs 4242 1001
+//          [ids@32473 srcIp="192.168.11.34" scanType="Fast Scan" portCount="15"] CEF:0|...
+//
+// In 5424, campurile structurate (SD-PARAM) sunt interogabile direct in
+// rsyslog / syslog-ng ($.sd!ids@32473!srcIp), fara regex pe corpul CEF.
+
+/// SD-ID-ul elementului structurat. 32473 este numarul de intreprindere IANA
+/// rezervat pentru documentatie si exemple (RFC 5612).
+const SYSLOG_SD_ID: &str = "ids@32473";
+
+/// Numele masinii pentru campul HOSTNAME din 5424 (citit o singura data).
+/// "-" (NILVALUE) cand nu poate fi determinat.
+fn syslog_hostname() -> &'static str {
+    static HOSTNAME: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    HOSTNAME.get_or_init(|| {
+        std::fs::read_to_string("/proc/sys/kernel/hostname")
+            .ok()
+            .map(|h| h.trim().to_string())
+            // RFC 5424: HOSTNAME = 1..255 caractere ASCII tiparibile, fara spatii.
+            .filter(|h| !h.is_empty() && h.len() <= 255 && h.bytes().all(|b| b.is_ascii_graphic()))
+            .unwrap_or_else(|| "-".to_string())
+    })
+}
+
+/// Etichetele senzorului ca text "cheie=valoare,..." (SD 5424, CEF cs4).
+fn sensor_tags_text(tags: &std::collections::BTreeMap<String, String>) -> String {
+    tags.iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Identitatea senzorului din `[sensor]`: `hostname` configurat sau cel al
+/// masinii. `None` cand nu exista nici hostname, nici etichete.
+pub fn sensor_identity(cfg: &SensorConfig) -> Option<SensorIdentity> {
+    let hostname = if cfg.hostname.is_empty() {
+        syslog_hostname().to_string()
+    } else {
+        cfg.hostname.clone()
+    };
+    if hostname == "-" && cfg.tags.is_empty() {
+        return None;
+    }
+    Some(SensorIdentity {
+        hostname,
+        tags: cfg.tags.clone(),
+    })
+}
+
+/// Escape pentru valorile SD-PARAM: `"`, `\` si `]` sunt prefixate cu `\`.
+fn sanitize_sd_value(input: &str) -> String {
+    input
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+        .replace(['\n', '\r'], " ")
+}
+
+/// Antetul syslog care precede mesajul CEF (cu spatiul final inclus).
+/// `msg_id` = SignatureID-ul CEF; `sd` = perechile elementului structurat
+/// (folosite doar in 5424).
+fn syslog_header(
+    rfc: u16,
+    timestamp: &DateTime<Local>,
+    msg_id: &str,
+    sd: &[(&str, String)],
+) -> String {
+    if rfc != 5424 {
+        // RFC 3164 nu admite fractiuni de secunda — ordinea exacta este data
+        // de `rt` (epoch in milisecunde) din extensiile CEF.
+        return format!("<38>{} ids-rs ", timestamp.format("%b %e %H:%M:%S"));
+    }
+    let params: String = sd
+        .iter()
+        .map(|(k, v)| format!(" {}=\"{}\"", k, sanitize_sd_value(v)))
+        .collect();
+    format!(
+        "<38>1 {} {} ids-rs {} {} [{}{}] ",
+        timestamp.to_rfc3339_opts(SecondsFormat::Millis, false),
+        syslog_hostname(),
+        std::process::id(),
+        msg_id,
+        SYSLOG_SD_ID,
+        params
+    )
+}
+
+// =============================================================================
+// GELF 1.1 — Graylog Extended Log Format
+// =============================================================================
+//
+// Un mesaj GELF este un obiect JSON cu campuri obligatorii (`version`, `host`,
+// `short_message`) si campuri aditionale prefixate cu `_`. Valorile aditionale
+// trebuie sa fie string sau numar — listele sunt trimise ca string "a,b,c".
+//
+// Peste UDP, un mesaj mai mare decat o datagrama este impartit in chunk-uri:
+//
+//   [0x1e 0x0f][id mesaj: 8 octeti][secventa: 1][total: 1][date...]
+//
+// Graylog reasambleaza dupa id; maxim 128 de chunk-uri per mesaj.
+
+/// `host` din mesajele GELF — acelasi nume ca in antetul syslog CEF.
+const GELF_HOST: &str = "ids-rs";
+/// Magic-ul de la inceputul fiecarui chunk GELF.
+const GELF_CHUNK_MAGIC: [u8; 2] = [0x1e, 0x0f];
+/// Antet chunk: magic (2) + id mesaj (8) + secventa (1) + total (1).
+const GELF_CHUNK_HEADER_LEN: usize = 12;
+/// Limita protocolului GELF: un mesaj are cel mult 128 de chunk-uri.
+const GELF_MAX_CHUNKS: usize = 128;
+
+/// Severitatea CEF a alertei (aceeasi ca in SIEM/email), inclusiv escaladarea
+/// dupa criticitatea activului vizat (`[assets]`) si marcajul fals-pozitiv.
+fn alert_severity(alert: &Alert) -> u8 {
+    alert.effective_severity(display::scan_style(&alert.scan_type).severity.0)
+}
+
+/// Alerta atinge `min_severity` al destinatiei? Comparatia foloseste
+/// severitatea efectiva — o tinta critica poate ridica alerta peste prag.
+fn meets_min_severity(min_severity: u8, alert: &Alert) -> bool {
+    alert_severity(alert) >= min_severity
+}
+
+/// Copia alertei fara campurile `omit_fields` ale destinatiei (vezi
+/// `SINK_OMITTABLE_FIELDS`) — golite, deci omise din JSON si din text.
+fn without_fields(alert: &Alert, fields: &[String]) -> Alert {
+    let mut alert = alert.clone();
+    for field in fields {
+        match field.as_str() {
+            "summary" => alert.summary.clear(),
+            "sensor" => alert.sensor = None,
+            "asset" => {
+                alert.asset = None;
+                alert.asset_criticality = None;
+            }
+            "classification" => {
+                alert.classification = None;
+                alert.classification_reasons.clear();
+            }
+            "detection_context" => alert.detection_context = None,
+            "interface" => {
+                alert.interface.clear();
+                alert.vlan = None;
+            }
+            "ingress" => alert.ingress.clear(),
+            "profile" => alert.profile.clear(),
+            "tor_exit" => alert.tor_exit = false,
+            "scan_episode" => alert.scan_episode = None,
+            _ => {}
+        }
+    }
+    alert
+}
+
+/// Nivel syslog GELF din severitatea CEF: 2=critical, 3=error, 4=warning, 5=notice.
+fn gelf_level(severity: u8) -> u8 {
+    match severity {
+        9.. => 2,
+        8 => 3,
+        7 => 4,
+        _ => 5,
+    }
+}
+
+/// Rezumatul de o linie (`short_message`) al unei alerte: `alert.summary`
+/// (`alerting.summary_template`), cu formularea implicita ca rezerva.
+fn gelf_short_message(alert: &Alert) -> String {
+    if !alert.summary.is_empty() {
+        return alert.summary.clone();
+    }
+    let dst = alert
+        .dest_ip
+        .map(|ip| ip.to_string())
+        .unwrap_or_else(|| "N/A".to_string());
+    match alert.scan_type {
+        ScanType::Fast | ScanType::Slow | ScanType::AcceptScan => format!(
+            "{} de la {}: {} porturi unice",
+            alert.scan_type,
+            alert.source_ip,
+            alert.port_count_label()
+        ),
+        ScanType::LateralMovement => format!(
+            "{} de la {}: {} destinatii unice",
+            alert.scan_type,
+            alert.source_ip,
+            alert.unique_dests.len()
+        ),
+        ScanType::SlowSweep => format!(
+            "{} de la {}: portul {} pe {} destinatii",
+            alert.scan_type,
+            alert.source_ip,
+            alert.ports_text(",", false),
+            alert.unique_dests.len()
+        ),
+        ScanType::DistributedScan => format!(
+            "{} catre {}: {} surse unice",
+            alert.scan_type,
+            dst,
+            alert.unique_sources.len()
+        ),
+        ScanType::Beaconing => format!(
+            "{} {} → {}:{}",
+            alert.scan_type,
+            alert.source_ip,
+            dst,
+            alert.beacon_port.map(|p| p.to_string()).unwrap_or_else(|| "?".to_string())
+        ),
+        ScanType::Saturation => format!(
+            "{}: {} evictii LRU",
+            alert.scan_type,
+            alert.event_count.unwrap_or(0)
+        ),
+        ScanType::NoTraffic => format!(
+            "{}: niciun pachet in {}s",
+            alert.scan_type,
+            alert.silent_secs.unwrap_or(0)
+        ),
+        ScanType::MalformedInput => format!(
+            "{} de la {}: {} datagrame malformate",
+            alert.scan_type,
+            alert.source_ip,
+            alert.event_count.unwrap_or(0)
+        ),
+        ScanType::ScanEnded => format!(
+            "{} de la {}: {} porturi, {} destinatii in {}s",
+            alert.scan_type,
+            alert.source_ip,
+            alert.unique_ports.len(),
+            alert.unique_dests.len(),
+            alert.scan_episode.as_ref().map_or(0, |e| e.duration_secs)
+        ),
+        ScanType::Flood => format!(
+            "{} de la {}: {:.0} pachete/s",
+            alert.scan_type,
+            alert.source_ip,
+            alert.packet_rate.unwrap_or(0.0)
+        ),
+        ScanType::CustomRule => format!(
+            "{} '{}' de la {}: {} porturi unice",
+            alert.scan_type,
+            alert.rule_name,
+            alert.source_ip,
+            alert.unique_ports.len()
+        ),
+        ScanType::CoordinatedSubnet => format!(
+            "{} din {}: {} surse, {} porturi unice",
+            alert.scan_type,
+            alert.source_prefix.as_deref().unwrap_or("?"),
+            alert.unique_sources.len(),
+            alert.unique_ports.len()
+        ),
+    }
+}
+
+/// Documentul trimis catre Sentinel: alerta in forma JSON din `/api/alerts`
+/// (campuri plate, devin coloane `<camp>_s` / `_d` / `_b` in `<log_type>_CL`)
+/// plus severitatea efectiva si numele regulii.
+fn build_sentinel_document(alert: &Alert) -> Result<serde_json::Value> {
+    let mut doc = serde_json::to_value(alert).context("Nu pot serializa alerta")?;
+    doc["severity"] = serde_json::json!(alert_severity(alert));
+    doc["rule"] = serde_json::json!(alert.scan_type.to_string());
+    if let Some(ranges) = port_ranges_field(alert) {
+        doc["port_ranges"] = serde_json::json!(ranges);
+    }
+    Ok(doc)
+}
+
+/// `port_ranges` din documentele JSON ("1-1024,3389"): doar cu
+/// `output.port_ranges` si o lista de porturi nevida.
+fn port_ranges_field(alert: &Alert) -> Option<String> {
+    (crate::display::port_ranges_enabled() && !alert.unique_ports.is_empty())
+        .then(|| alert.ports_text(",", true))
+}
+
+// =============================================================================
+// CloudEvents 1.0 — plic JSON (alerting.siem.format = "cloudevents")
+// =============================================================================
+//
+//   {"specversion":"1.0","type":"ids-rs.alert.fast-scan","source":"ids-rs/fw-sensor01",
+//    "id":"1792044163525-7","time":"2026-10-15T14:30:12.345+03:00",
+//    "subject":"192.168.11.34","datacontenttype":"application/json","data":{...}}
+//
+// `data` este acelasi document ca la Sentinel (alerta JSON din `/api/alerts`
+// plus `severity` si `rule`), deci broker-ul / functia primesc toate campurile
+// structurate. `type` permite filtrarea pe tip de alerta fara a citi `data`.
+
+/// Prefixul atributului `type` (ex: "ids-rs.alert.fast-scan", "ids-rs.rollup").
+const CLOUDEVENTS_TYPE_PREFIX: &str = "ids-rs";
+
+/// Plicul CloudEvents 1.0 (modul structurat, JSON) in jurul lui `data`.
+///
+/// `id` trebuie sa fie unic per `source`: pentru alerte, `Alert::delivery_key`
+/// (id-ul alertei prefixat cu momentul ei, epoch ms).
+fn build_cloudevent(
+    event_type: &str,
+    id: &str,
+    time: &DateTime<Local>,
+    subject: Option<String>,
+    data: serde_json::Value,
+) -> serde_json::Value {
+    let mut event = serde_json::json!({
+        "specversion": "1.0",
+        "type": format!("{}.{}", CLOUDEVENTS_TYPE_PREFIX, event_type),
+        "source": format!("ids-rs/{}", syslog_hostname()),
+        "id": id,
+        "time": display::format_rfc3339(time),
+        "datacontenttype": "application/json",
+        "data": data,
+    });
+    if let Some(subject) = subject {
+        event["subject"] = serde_json::json!(subject);
+    }
+    event
+}
+
+/// Alerta ca CloudEvent: `type` = "ids-rs.alert.<tip-kebab>", `subject` = IP sursa.
+/// Cu `[sensor]`, `source` poarta hostname-ul configurat al senzorului.
+fn build_alert_cloudevent(alert: &Alert) -> Result<serde_json::Value> {
+    let kind = alert.scan_type.to_string().to_ascii_lowercase().replace(' ', "-");
+    let mut event = build_cloudevent(
+        &format!("alert.{}", kind),
+        &alert.delivery_key(),
+        &alert.timestamp,
+        Some(alert.source_ip.to_string()),
+        build_sentinel_document(alert)?,
+    );
+    if let Some(ref sensor) = alert.sensor {
+        event["source"] = serde_json::json!(format!("ids-rs/{}", sensor.hostname));
+    }
+    Ok(event)
+}
+
+/// Pauza dintre doua incercari Sentinel, inmultita cu numarul incercarii.
+const SENTINEL_RETRY_BACKOFF: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// POST-ul unei alerte catre Log Analytics. `TimeGenerated` = `timestamp` alertei.
+///
+/// La eroare se reincearca de `max_retries` ori, cu aceeasi cheie de
+/// idempotenta. Dupa o incercare cu rezultat necunoscut (cererea scrisa, fara
+/// raspuns), documentele urmatoare poarta `possible_duplicate = true` si
+/// `delivery_attempt` — dublura este recunoscuta in Sentinel, nu numarata.
+async fn send_sentinel_alert(cfg: &SentinelConfig, alert: &Alert) -> Result<()> {
+    let key = (!alert.idempotency_key.is_empty()).then_some(alert.idempotency_key.as_str());
+    deliver_sentinel(alert, cfg.max_retries, SENTINEL_RETRY_BACKOFF, |body| async move {
+        let mut uncertain = false;
+        let result = crate::sentinel::post_logs(cfg, &body, "timestamp", key, &mut uncertain).await;
+        (result, uncertain)
+    })
+    .await
+}
+
+/// Bucla de reincercari Sentinel, separata de transport: `post` primeste
+/// corpul cererii si intoarce (rezultat, livrare posibila). Cel mult
+/// `1 + max_retries` incercari, cu pauza `backoff × incercare` intre ele.
+async fn deliver_sentinel<F, Fut>(alert: &Alert, max_retries: u32, backoff: Duration, mut post: F) -> Result<()>
+where
+    F: FnMut(Vec<u8>) -> Fut,
+    Fut: Future<Output = (Result<()>, bool)>,
+{
+    let mut possibly_delivered = false;
+    let mut attempt = 1;
+    loop {
+        let doc = sentinel_attempt_document(alert, attempt, possibly_delivered)?;
+        let (result, uncertain) = post(serde_json::to_vec(&[doc])?).await;
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt > max_retries => return Err(e),
+            Err(e) => {
+                possibly_delivered |= uncertain;
+                display::log_warning(&format!(
+                    "Sentinel: incercarea {} esuata ({}{:#}) — reincerc",
+                    attempt,
+                    if uncertain { "livrare posibila, " } else { "" },
+                    e
+                ));
+                tokio::time::sleep(backoff * attempt).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Documentul incercarii `attempt`: dupa o incercare cu rezultat necunoscut
+/// (`possibly_delivered`) poarta `possible_duplicate` si `delivery_attempt`.
+fn sentinel_attempt_document(alert: &Alert, attempt: u32, possibly_delivered: bool) -> Result<serde_json::Value> {
+    let mut doc = build_sentinel_document(alert)?;
+    if possibly_delivered {
+        doc["possible_duplicate"] = serde_json::json!(true);
+        doc["delivery_attempt"] = serde_json::json!(attempt);
+    }
+    Ok(doc)
+}
+
+/// Construieste mesajul GELF 1.1 pentru o alerta.
+///
+/// Folosit de destinatia `[alerting.gelf]` (UDP) si de `display.format = "gelf"`
+/// (stdout) — ambele produc exact acelasi document.
+pub fn build_gelf_message(alert: &Alert) -> serde_json::Value {
+    let severity = alert_severity(alert);
+    let mut msg = serde_json::json!({
+        "version": "1.1",
+        "host": GELF_HOST,
+        "short_message": gelf_short_message(alert),
+        // GELF: secunde UNIX cu fractiune zecimala (milisecunde).
+        "timestamp": alert.timestamp.timestamp_millis() as f64 / 1000.0,
+        "level": gelf_level(severity),
+        "_source_ip": alert.source_ip.to_string(),
+        "_scan_type": alert.scan_type.to_string(),
+        "_severity": severity,
+        "_policy_version": alert.policy_version,
+    });
+    if !alert.idempotency_key.is_empty() {
+        msg["_idempotency_key"] = serde_json::json!(alert.idempotency_key);
+    }
+    if !alert.ingress.is_empty() {
+        msg["_ingress"] = serde_json::json!(alert.ingress);
+    }
+    if !alert.interface.is_empty() {
+        msg["_interface"] = serde_json::json!(alert.interface);
+    }
+    if let Some(vlan) = alert.vlan {
+        msg["_vlan"] = serde_json::json!(vlan);
+    }
+    if !alert.profile.is_empty() {
+        msg["_profile"] = serde_json::json!(alert.profile);
+    }
+    if !alert.protocols.is_empty() {
+        msg["_protocols"] = serde_json::json!(alert.protocols.join(","));
+    }
+    if alert.tor_exit {
+        msg["_tor_exit"] = serde_json::json!(1);
+    }
+    // Identitatea senzorului ([sensor]): `host` ramane "ids-rs" (aplicatia),
+    // senzorul si etichetele lui sunt campuri aditionale.
+    if let Some(ref sensor) = alert.sensor {
+        msg["_sensor"] = serde_json::json!(sensor.hostname);
+        for (key, value) in &sensor.tags {
+            msg[format!("_sensor_{}", key)] = serde_json::json!(value);
+        }
+    }
+    if alert.multi_protocol {
+        msg["_multi_protocol"] = serde_json::json!(1);
+    }
+    if alert.source_port_exhaustion.is_some() {
+        msg["_source_port_exhaustion"] = serde_json::json!(1);
+    }
+    if alert.promoted_from_slow {
+        msg["_promoted_from_slow"] = serde_json::json!(1);
+    }
+    if let Some(ref ctx) = alert.detection_context {
+        msg["_detection_rule"] = serde_json::json!(ctx.rule);
+        msg["_detection_observed"] = serde_json::json!(ctx.observed);
+        msg["_detection_threshold"] = serde_json::json!(ctx.threshold);
+        msg["_detection_window_secs"] = serde_json::json!(ctx.window_secs);
+        if let Some(score) = ctx.weighted_count {
+            msg["_detection_weighted_count"] = serde_json::json!(score);
+        }
+    }
+    if let Some(class) = alert.classification {
+        msg["_classification"] = serde_json::json!(class.as_str());
+        msg["_classification_reasons"] = serde_json::json!(alert.classification_reasons.join("; "));
+    }
+    if let Some(dst) = alert.dest_ip {
+        msg["_dest_ip"] = serde_json::json!(dst.to_string());
+    }
+    if !alert.unique_ports.is_empty() {
+        msg["_port_count"] = serde_json::json!(alert.port_count());
+        msg["_unique_ports"] =
+            serde_json::json!(alert.ports_text(",", crate::display::siem_port_ranges()));
+    }
+    if let Some(score) = alert.weighted_score {
+        msg["_weighted_score"] = serde_json::json!(score);
+    }
+    if !alert.unique_dests.is_empty() {
+        msg["_dest_count"] = serde_json::json!(alert.unique_dests.len());
+        msg["_unique_dests"] = serde_json::json!(sorted_ip_list(&alert.unique_dests));
+    }
+    if !alert.unique_sources.is_empty() {
+        msg["_source_count"] = serde_json::json!(alert.unique_sources.len());
+        msg["_unique_sources"] = serde_json::json!(sorted_ip_list(&alert.unique_sources));
+    }
+    if let Some(port) = alert.beacon_port {
+        msg["_dest_port"] = serde_json::json!(port);
+    }
+    if let Some(criticality) = alert.asset_criticality {
+        msg["_asset_criticality"] = serde_json::json!(criticality.to_string());
+    }
+    if let Some(asset) = &alert.asset {
+        msg["_asset_name"] = serde_json::json!(asset.name);
+        if !asset.owner.is_empty() {
+            msg["_asset_owner"] = serde_json::json!(asset.owner);
+        }
+    }
+    msg
+}
+
+/// Id unic pe 8 octeti pentru chunk-urile unui mesaj GELF.
+///
+/// Timpul (ns) XOR un contor rotit: doua mesaje in aceeasi nanosecunda
+/// primesc totusi id-uri diferite, fara dependenta de un generator random.
+fn gelf_message_id() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    nanos ^ COUNTER.fetch_add(1, Ordering::Relaxed).rotate_left(32)
+}
+
+/// Imparte un payload GELF in datagrame de cel mult `chunk_size` octeti.
+///
+/// Un payload care incape intr-o datagrama este trimis ca atare (fara antet).
+/// Peste `GELF_MAX_CHUNKS` chunk-uri mesajul este respins — Graylog l-ar
+/// arunca oricum.
+fn gelf_chunks(payload: &[u8], chunk_size: usize, message_id: u64) -> Result<Vec<Vec<u8>>> {
+    if payload.len() <= chunk_size {
+        return Ok(vec![payload.to_vec()]);
+    }
+    let data_per_chunk = chunk_size - GELF_CHUNK_HEADER_LEN;
+    let total = payload.len().div_ceil(data_per_chunk);
+    if total > GELF_MAX_CHUNKS {
+        bail!(
+            "Mesaj GELF prea mare: {} octeti → {} chunk-uri (maxim {})",
+            payload.len(),
+            total,
+            GELF_MAX_CHUNKS
+        );
+    }
+    Ok(payload
+        .chunks(data_per_chunk)
+        .enumerate()
+        .map(|(seq, data)| {
+            let mut datagram = Vec::with_capacity(GELF_CHUNK_HEADER_LEN + data.len());
+            datagram.extend_from_slice(&GELF_CHUNK_MAGIC);
+            datagram.extend_from_slice(&message_id.to_be_bytes());
+            // `total <= 128` → secventa si totalul incap intr-un octet.
+            datagram.push(seq as u8);
+            datagram.push(total as u8);
+            datagram.extend_from_slice(data);
+            datagram
+        })
+        .collect())
+}
+
 fn comma_to_display_list(value: &str) -> String {
     value.replace(',', ", ")
 }
@@ -143,6 +866,13 @@ struct HtmlAlertBody<'a> {
     dst_location: &'a str,
     item_count: usize,
     timestamp: &'a str,
+    ingress: &'a str,
+    /// Interfata firewall-ului ("eth1.100 (VLAN 100)"), "N/A" daca lipseste.
+    interface: &'a str,
+    /// Activul vizat din harta CMDB ("nume — proprietar"), "N/A" daca lipseste.
+    asset: &'a str,
+    /// Sursa este nod de iesire Tor (`enrichment.tor_exit_list_path`).
+    tor_exit: bool,
     items: &'a str,
     footer: &'a str,
     count_label: &'a str,
@@ -163,9 +893,9 @@ struct HtmlAlertBody<'a> {
 /// `src_ip`, `dst_ip`, `item_count`, `timestamp` provin din `IpAddr`, `usize`,
 /// `DateTime` formatate => safe (nu contin `<`, `>`, `&`).
 ///
-/// `src_hostname`, `dst_hostname`, `src_location`, `dst_location`, `items` vin
-/// din configuratie (admin-controlled): `[network.hostnames]`, `[network.subnets]`
-/// si liste de IP-uri/porturi. Nu sunt escape-ate pentru ca:
+/// `src_hostname`, `dst_hostname`, `src_location`, `dst_location`, `ingress`,
+/// `items` vin din configuratie (admin-controlled): `[network.hostnames]`,
+/// `[network.subnets]`, `network.ingress_name` si liste de IP-uri/porturi. Nu sunt escape-ate pentru ca:
 ///   1. Sursa lor este admin-ul (incredere implicita la nivel de deploy)
 ///   2. Pana acum, hostname-urile sunt scrise manual in config.toml
 ///
@@ -176,13 +906,19 @@ struct HtmlAlertBody<'a> {
 /// care randeaza HTML (Thunderbird, Outlook web, etc.).
 ///
 /// `email_footer` este HTML-escapeat pentru ca poate contine ASCII art cu `<>&`.
+/// `asset` vine dintr-un export CMDB (alt sistem, ex: "R&D") — escapeat la fel.
+/// `interface` vine din log-ul firewall-ului (sursa netrustata) — escapeat.
 fn build_html_body(data: &HtmlAlertBody<'_>) -> String {
     // HTML-escape pentru campuri care pot contine caractere speciale (footer ASCII art).
-    let footer_safe = data
-        .footer
-        .replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;");
+    let escape = |value: &str| {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let footer_safe = escape(data.footer);
+    let asset_safe = escape(data.asset);
+    let interface_safe = escape(data.interface);
 
     let template = r#"<!DOCTYPE html>
 <html lang="ro">
@@ -251,6 +987,10 @@ fn build_html_body(data: &HtmlAlertBody<'_>) -> String {
       <tr><td>IP Destinatie</td><td>__DST_IP__ __DST_HOST__ __DST_LOC__</td></tr>
       <tr><td>__COUNT_LABEL__</td><td>__PORT_COUNT__</td></tr>
       <tr><td>Timestamp</td><td>__TIMESTAMP__</td></tr>
+      <tr><td>Intrare senzor</td><td>__INGRESS__</td></tr>
+      <tr><td>Interfata firewall</td><td>__INTERFACE__</td></tr>
+      <tr><td>Activ vizat</td><td>__ASSET__</td></tr>
+      <tr><td>Nod de iesire Tor</td><td>__TOR_EXIT__</td></tr>
     </table>
   </div>
 
@@ -320,6 +1060,10 @@ fn build_html_body(data: &HtmlAlertBody<'_>) -> String {
         .replace("__DST_LOC__", &dst_loc_display)
         .replace("__PORT_COUNT__", &data.item_count.to_string())
         .replace("__TIMESTAMP__", data.timestamp)
+        .replace("__INGRESS__", data.ingress)
+        .replace("__INTERFACE__", &interface_safe)
+        .replace("__ASSET__", &asset_safe)
+        .replace("__TOR_EXIT__", if data.tor_exit { "DA" } else { "nu" })
         .replace("__PORTS__", data.items)
         .replace("__COUNT_LABEL__", data.count_label)
         .replace("__LIST_LABEL__", data.list_label)
@@ -358,6 +1102,171 @@ fn build_mailer(cfg: &EmailConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>>
     Ok(mailer)
 }
 
+// =============================================================================
+// AlertSink — destinatiile alertelor (`alerting.sinks` + sectiunile legacy)
+// =============================================================================
+//
+// Fiecare destinatie activa este o instanta `AlertSink`, construita o data din
+// `AlertingConfig::sink_entries()` (la pornire si la SIGHUP). `send_alert`
+// parcurge lista in ordine — doua intrari de acelasi tip sunt doua instante
+// independente, fiecare cu propriile setari, prag si campuri omise.
+//
+// NOTA RUST: trait-ul contine o metoda async, deci nu poate fi folosit direct
+// ca `dyn AlertSink` cu `async fn`. Returnam explicit un `Pin<Box<dyn Future>>`
+// — exact ce genereaza crate-ul `async-trait`, fara dependenta in plus.
+
+/// Future-ul returnat de o livrare; imprumuta sink-ul, alerter-ul si alerta.
+type SinkFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// O destinatie de alertare (o intrare din `sink_entries()`).
+pub trait AlertSink: Send + Sync {
+    /// Numele instantei ("siem" pentru sectiunea legacy, `name` pentru o intrare).
+    /// Cheia slotului ei din `LastErrors`.
+    fn name(&self) -> &str;
+    /// Tipul destinatiei, din `ALERT_SINKS`.
+    fn kind(&self) -> &'static str;
+    /// Severitatea efectiva minima a alertelor livrate.
+    fn min_severity(&self) -> u8;
+    /// Campurile alertei omise pentru aceasta destinatie.
+    fn omit_fields(&self) -> &[String];
+    /// Livreaza o alerta.
+    fn send<'a>(&'a self, alerter: &'a Alerter, alert: &'a Alert) -> SinkFuture<'a>;
+    /// Livreaza raportul agregat (`output.rollup_sink`). Implicit: nesuportat.
+    fn send_rollup<'a>(&'a self, _alerter: &'a Alerter, _summary: &'a RollupSummary) -> SinkFuture<'a> {
+        let kind = self.kind();
+        Box::pin(async move { bail!("destinatia {} nu suporta rollup", kind) })
+    }
+}
+
+/// Syslog UDP (CEF / ECS / CloudEvents).
+struct SiemSink {
+    name: String,
+    omit_fields: Vec<String>,
+    config: SiemConfig,
+}
+
+/// SMTP, cu transportul construit o singura data per instanta.
+struct EmailSink {
+    name: String,
+    omit_fields: Vec<String>,
+    config: EmailConfig,
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+/// Graylog GELF peste UDP.
+struct GelfSink {
+    name: String,
+    omit_fields: Vec<String>,
+    config: GelfConfig,
+}
+
+/// Microsoft Sentinel (Log Analytics Data Collector API).
+struct SentinelSink {
+    name: String,
+    omit_fields: Vec<String>,
+    config: SentinelConfig,
+}
+
+impl AlertSink for SiemSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn kind(&self) -> &'static str {
+        "siem"
+    }
+    fn min_severity(&self) -> u8 {
+        self.config.min_severity
+    }
+    fn omit_fields(&self) -> &[String] {
+        &self.omit_fields
+    }
+    fn send<'a>(&'a self, alerter: &'a Alerter, alert: &'a Alert) -> SinkFuture<'a> {
+        Box::pin(alerter.send_siem_alert(&self.config, alert))
+    }
+    fn send_rollup<'a>(&'a self, alerter: &'a Alerter, summary: &'a RollupSummary) -> SinkFuture<'a> {
+        Box::pin(alerter.send_rollup_siem(&self.config, summary))
+    }
+}
+
+impl AlertSink for EmailSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn kind(&self) -> &'static str {
+        "email"
+    }
+    fn min_severity(&self) -> u8 {
+        self.config.min_severity
+    }
+    fn omit_fields(&self) -> &[String] {
+        &self.omit_fields
+    }
+    fn send<'a>(&'a self, alerter: &'a Alerter, alert: &'a Alert) -> SinkFuture<'a> {
+        Box::pin(alerter.send_email_alert(&self.config, &self.mailer, alert))
+    }
+    fn send_rollup<'a>(&'a self, alerter: &'a Alerter, summary: &'a RollupSummary) -> SinkFuture<'a> {
+        Box::pin(alerter.send_rollup_email(&self.config, &self.mailer, summary))
+    }
+}
+
+impl AlertSink for GelfSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn kind(&self) -> &'static str {
+        "gelf"
+    }
+    fn min_severity(&self) -> u8 {
+        self.config.min_severity
+    }
+    fn omit_fields(&self) -> &[String] {
+        &self.omit_fields
+    }
+    fn send<'a>(&'a self, alerter: &'a Alerter, alert: &'a Alert) -> SinkFuture<'a> {
+        Box::pin(alerter.send_gelf_alert(&self.config, alert))
+    }
+}
+
+impl AlertSink for SentinelSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn kind(&self) -> &'static str {
+        "sentinel"
+    }
+    fn min_severity(&self) -> u8 {
+        self.config.min_severity
+    }
+    fn omit_fields(&self) -> &[String] {
+        &self.omit_fields
+    }
+    fn send<'a>(&'a self, _alerter: &'a Alerter, alert: &'a Alert) -> SinkFuture<'a> {
+        Box::pin(send_sentinel_alert(&self.config, alert))
+    }
+}
+
+/// Construieste instantele `AlertSink` din configurare, in ordinea livrarii.
+/// Esueaza doar daca transportul SMTP al unei destinatii email nu poate fi creat.
+fn build_sinks(cfg: &AlertingConfig) -> Result<Vec<Box<dyn AlertSink>>> {
+    cfg.sink_entries()
+        .into_iter()
+        .map(|entry| {
+            let SinkEntry { name, omit_fields, kind, .. } = entry;
+            let sink: Box<dyn AlertSink> = match kind {
+                SinkKind::Siem(config) => Box::new(SiemSink { name, omit_fields, config }),
+                SinkKind::Email(config) => {
+                    let mailer = build_mailer(&config)
+                        .with_context(|| format!("destinatia email {:?}", name))?;
+                    Box::new(EmailSink { name, omit_fields, config, mailer })
+                }
+                SinkKind::Gelf(config) => Box::new(GelfSink { name, omit_fields, config }),
+                SinkKind::Sentinel(config) => Box::new(SentinelSink { name, omit_fields, config }),
+            };
+            Ok(sink)
+        })
+        .collect()
+}
+
 /// Componenta de alertare - trimite notificari catre SIEM si email.
 ///
 /// NOTA RUST: Acest struct DETINE (owns) configurarea. Clonarea s-a facut
@@ -369,17 +1278,24 @@ pub struct Alerter {
     config: ArcSwap<AlertingConfig>,
     /// Configurare detectie (praguri, ferestre de timp) — citita pentru mesaje alerta.
     detection: ArcSwap<DetectionConfig>,
-    /// Transport SMTP pre-construit (None daca email dezactivat).
+    /// Destinatiile active, in ordinea livrarii (vezi `build_sinks`); fiecare
+    /// destinatie email isi detine transportul SMTP pre-construit.
     /// ArcSwap permite rebuild-ul la reload (schimbare SMTP server/port/credentials).
-    mailer: ArcSwap<Option<AsyncSmtpTransport<Tokio1Executor>>>,
+    sinks: ArcSwap<Vec<Box<dyn AlertSink>>>,
     /// Mapping IP → hostname pentru afisare in alerte SIEM (shost=/dhost=) si email.
     hostnames: ArcSwap<HashMap<IpAddr, String>>,
     /// Mapping subnet CIDR → locatie (etaj, zona) pentru context fizic in alerte.
     subnets: ArcSwap<Vec<SubnetEntry>>,
+    /// Ultima eroare per instanta de destinatie — golita la urmatorul succes.
+    errors: Arc<LastErrors>,
+    /// Sink-urile permise per profil de detectie (`[profiles.<nume>].sinks`).
+    /// Profilul "default" si profilurile necunoscute nu sunt restrictionate.
+    profile_sinks: ArcSwap<HashMap<String, Vec<String>>>,
 }
 
 impl Alerter {
-    /// Initializeaza Alerter-ul si construieste transportul SMTP (daca email e activat).
+    /// Initializeaza Alerter-ul si construieste destinatiile (transportul SMTP
+    /// al fiecarei destinatii email).
     ///
     /// Returneaza `Result<Self>` deoarece construirea transportului SMTP poate esua
     /// (hostname invalid, port gresit, etc.). Erorile de configurare sunt detectate
@@ -389,26 +1305,26 @@ impl Alerter {
         detection: DetectionConfig,
         hostnames: HashMap<IpAddr, String>,
         subnets: Vec<SubnetEntry>,
+        errors: Arc<LastErrors>,
     ) -> Result<Self> {
-        // Construim mailer-ul O SINGURA DATA la startup, nu la fiecare alerta.
-        let mailer = if config.email.enabled {
-            Some(build_mailer(&config.email)?)
-        } else {
-            None
-        };
+        // Construim destinatiile O SINGURA DATA la startup, nu la fiecare alerta.
+        let sinks = build_sinks(&config)?;
+        errors.set_sinks(sinks.iter().map(|s| s.name()));
         Ok(Self {
             config: ArcSwap::from_pointee(config),
             detection: ArcSwap::from_pointee(detection),
-            mailer: ArcSwap::from_pointee(mailer),
+            sinks: ArcSwap::from_pointee(sinks),
             hostnames: ArcSwap::from_pointee(hostnames),
             subnets: ArcSwap::from_pointee(subnets),
+            errors,
+            profile_sinks: ArcSwap::from_pointee(HashMap::new()),
         })
     }
 
     /// Actualizeaza configurarea alerter-ului la runtime (hot reload SIGHUP #16).
     ///
-    /// Rebuild-ul mailer-ului este necesar daca se schimba SMTP server/port/TLS/credentials.
-    /// Daca rebuild-ul esueaza, pastram mailer-ul vechi si logam eroarea.
+    /// Destinatiile sunt reconstruite (lista, setarile si transportul SMTP se pot
+    /// schimba). Daca rebuild-ul esueaza, pastram destinatiile vechi si logam eroarea.
     pub fn update_config(
         &self,
         new_alerting: AlertingConfig,
@@ -416,33 +1332,61 @@ impl Alerter {
         new_hostnames: HashMap<IpAddr, String>,
         new_subnets: Vec<SubnetEntry>,
     ) {
-        // Rebuild mailer daca email e activat in noua configurare.
-        let new_mailer = if new_alerting.email.enabled {
-            match build_mailer(&new_alerting.email) {
-                Ok(m) => Some(m),
-                Err(e) => {
-                    display::log_error(&format!(
-                        "SIGHUP: rebuild SMTP esuat, pastrez mailer-ul vechi: {:#}",
-                        e
-                    ));
-                    self.config.store(Arc::new(new_alerting));
-                    self.detection.store(Arc::new(new_detection));
-                    self.hostnames.store(Arc::new(new_hostnames));
-                    self.subnets.store(Arc::new(new_subnets));
-                    return;
-                }
+        match build_sinks(&new_alerting) {
+            Ok(sinks) => {
+                self.errors.set_sinks(sinks.iter().map(|s| s.name()));
+                self.sinks.store(Arc::new(sinks));
             }
-        } else {
-            None
-        };
+            Err(e) => display::log_error(&format!(
+                "SIGHUP: rebuild destinatii esuat, pastrez destinatiile vechi: {:#}",
+                e
+            )),
+        }
 
         self.config.store(Arc::new(new_alerting));
         self.detection.store(Arc::new(new_detection));
-        self.mailer.store(Arc::new(new_mailer));
         self.hostnames.store(Arc::new(new_hostnames));
         self.subnets.store(Arc::new(new_subnets));
     }
 
+    /// Inlocuieste doar configurarea de detectie (`PUT /config/detection`,
+    /// `set-threshold` pe socket-ul de control): ferestrele si pragurile din
+    /// textul alertelor urmeaza detectorul, nu config.toml.
+    pub fn update_detection(&self, detection: DetectionConfig) {
+        self.detection.store(Arc::new(detection));
+    }
+
+    #[cfg(test)]
+    pub(crate) fn detection(&self) -> Arc<DetectionConfig> {
+        self.detection.load_full()
+    }
+
+    /// Actualizeaza sink-urile permise ale profilurilor (pornire si SIGHUP).
+    pub fn set_profiles(&self, profiles: &BTreeMap<String, ProfileConfig>) {
+        let sinks = profiles
+            .iter()
+            .map(|(name, p)| (name.clone(), p.sinks.clone()))
+            .collect();
+        self.profile_sinks.store(Arc::new(sinks));
+    }
+
+    /// Destinatia `sink` este activa pentru profilul alertei, familia IP a
+    /// sursei, clasa si severitatea alertei (`min_severity` al destinatiei).
+    /// Listele de rutare pot numi instanta sau tipul ei (toate instantele).
+    fn routes_to(&self, cfg: &AlertingConfig, sink: &dyn AlertSink, alert: &Alert) -> bool {
+        let names = [sink.name(), sink.kind()];
+        let profile_allows = self
+            .profile_sinks
+            .load()
+            .get(&alert.profile)
+            .is_none_or(|sinks| sinks.iter().any(|s| names.contains(&s.as_str())));
+        profile_allows
+            && names.iter().any(|n| cfg.routing.routes_to(n, &alert.source_ip))
+            && (!alert.tor_exit || names.iter().any(|n| cfg.routing.routes_tor_exit(n)))
+            && names.iter().any(|n| classify::routes_to(&cfg.classification, n, alert))
+            && meets_min_severity(sink.min_severity(), alert)
+    }
+
     /// Trimite alerta catre toate destinatiile configurate.
     ///
     /// NOTA RUST - ASYNC si ERROR HANDLING:
@@ -454,18 +1398,27 @@ impl Alerter {
     /// Daca SIEM-ul e down, inca vrem sa trimitem email (si invers).
     /// Pattern: "log and continue" vs "fail fast".
     ///
+    /// Destinatiile sunt filtrate de `alerting.routing` dupa familia IP a
+    /// sursei (IPv4/IPv6), de `sinks` al profilului care a produs alerta, de
+    /// clasa alertei (`alerting.classification`) si de `min_severity` al
+    /// fiecarei destinatii — o destinatie activata dar nerutata e sarita.
+    /// O destinatie cu `omit_fields` primeste o copie a alertei fara acele campuri.
+    ///
     pub async fn send_alert(&self, alert: &Alert) {
         let cfg = self.config.load();
-        if cfg.siem.enabled {
-            if let Err(e) = self.send_siem_alert(alert).await {
-                display::log_error(&format!("Eroare trimitere alerta SIEM: {:#}", e));
+        let sinks = self.sinks.load();
+        for sink in sinks.iter() {
+            if !self.routes_to(&cfg, sink.as_ref(), alert) {
+                continue;
             }
-        }
-
-        let mailer_guard = self.mailer.load();
-        if let Some(ref mailer) = **mailer_guard {
-            if let Err(e) = self.send_email_alert(alert, mailer).await {
-                display::log_error(&format!("Eroare trimitere email: {:#}", e));
+            let result = if sink.omit_fields().is_empty() {
+                sink.send(self, alert).await
+            } else {
+                sink.send(self, &without_fields(alert, sink.omit_fields())).await
+            };
+            self.errors.observe_sink(sink.name(), &result);
+            if let Err(e) = result {
+                display::log_error(&format!("Eroare trimitere alerta {}: {:#}", sink.name(), e));
             }
         }
     }
@@ -483,7 +1436,7 @@ impl Alerter {
     /// In realitate, UDP send este aproape instant (nu asteapta confirmare),
     /// dar Rust/tokio ne forțează sa tratam ca async - consistenta API.
     ///
-    async fn send_siem_alert(&self, alert: &Alert) -> Result<()> {
+    async fn send_siem_alert(&self, siem: &SiemConfig, alert: &Alert) -> Result<()> {
         // Formatam mesajul in format CEF peste Syslog RFC 3164 pentru ArcSight.
         //
         // Structura completa:
@@ -507,9 +1460,9 @@ impl Alerter {
                 "1001",
                 "Fast Port Scan Detected",
                 format!(
-                    "Fast Scan detectat: {} porturi unice in {} secunde",
-                    alert.unique_ports.len(),
-                    det.fast_scan.time_window_secs,
+                    "Fast Scan detectat: {} porturi unice in {}",
+                    alert.port_count_label(),
+                    display::format_window(Duration::from_secs(det.fast_scan.time_window_secs)),
                 ),
                 7u8,
             ),
@@ -517,9 +1470,9 @@ impl Alerter {
                 "1002",
                 "Slow Port Scan Detected",
                 format!(
-                    "Slow Scan detectat: {} porturi unice in {} minute",
-                    alert.unique_ports.len(),
-                    det.slow_scan.time_window_mins,
+                    "Slow Scan detectat: {} porturi unice in {}",
+                    alert.port_count_label(),
+                    display::format_window(Duration::from_secs(det.slow_scan.time_window_mins * 60)),
                 ),
                 6u8,
             ),
@@ -527,9 +1480,9 @@ impl Alerter {
                 "1003",
                 "Accept Port Scan Detected",
                 format!(
-                    "Accept Scan detectat: {} porturi deschise accesate in {} secunde",
-                    alert.unique_ports.len(),
-                    det.accept_scan.time_window_secs,
+                    "Accept Scan detectat: {} porturi deschise accesate in {}",
+                    alert.port_count_label(),
+                    display::format_window(Duration::from_secs(det.accept_scan.time_window_secs)),
                 ),
                 5u8,
             ),
@@ -537,23 +1490,34 @@ impl Alerter {
                 "1004",
                 "Lateral Movement Detected",
                 format!(
-                    "Lateral Movement detectat: {} destinatii unice in {} secunde",
+                    "Lateral Movement detectat: {} destinatii unice in {}",
                     alert.unique_dests.len(),
-                    det.lateral_movement.time_window_secs,
+                    display::format_window(Duration::from_secs(det.lateral_movement.time_window_secs)),
                 ),
                 8u8,
             ),
+            ScanType::SlowSweep => (
+                "1012",
+                "Slow Service Sweep Detected",
+                format!(
+                    "Slow Sweep detectat: portul {} sondat pe {} destinatii in {}",
+                    alert.ports_text(",", false),
+                    alert.unique_dests.len(),
+                    display::format_window(Duration::from_secs(det.slow_sweep.time_window_secs)),
+                ),
+                6u8,
+            ),
             ScanType::DistributedScan => (
                 "1005",
                 "Distributed Port Scan Detected",
                 format!(
-                    "Distributed Scan detectat: {} surse unice → tinta {} in {} secunde",
+                    "Distributed Scan detectat: {} surse unice → tinta {} in {}",
                     alert.unique_sources.len(),
                     alert
                         .dest_ip
                         .map(|ip| ip.to_string())
                         .unwrap_or_else(|| "N/A".to_string()),
-                    det.distributed_scan.time_window_secs,
+                    display::format_window(Duration::from_secs(det.distributed_scan.time_window_secs)),
                 ),
                 7u8,
             ),
@@ -569,6 +1533,104 @@ impl Alerter {
                 ),
                 9u8,
             ),
+            ScanType::Saturation => (
+                "1007",
+                "IDS Tracking Table Saturated",
+                format!(
+                    "Saturatie tracking: {} evictii LRU in {} ({:.1} evictii/s) — posibil flood cu IP-uri spoofed, detectie degradata",
+                    alert.event_count.unwrap_or(0),
+                    display::format_window(Duration::from_secs(det.saturation_alert.time_window_secs)),
+                    alert.eviction_rate.unwrap_or(0.0),
+                ),
+                8u8,
+            ),
+            ScanType::CustomRule => (
+                "1010",
+                "Custom Detection Rule Matched",
+                format!(
+                    "Regula '{}' declansata: {} porturi unice",
+                    alert.rule_name,
+                    alert.unique_ports.len(),
+                ),
+                6u8,
+            ),
+            ScanType::NoTraffic => (
+                "1009",
+                "IDS Sensor Received No Traffic",
+                format!(
+                    "Senzor fara trafic: niciun pachet in {} — mirror/tap sau forwarding syslog posibil cazut, senzorul poate fi orb",
+                    display::format_window(Duration::from_secs(alert.silent_secs.unwrap_or(0))),
+                ),
+                6u8,
+            ),
+            ScanType::MalformedInput => (
+                "1013",
+                "Malformed Input Sent To Sensor",
+                format!(
+                    "Datagrame malformate: {} in {} de la expeditorul {} — posibil fuzzing / sondare a senzorului sau forwarder configurat gresit",
+                    alert.event_count.unwrap_or(0),
+                    display::format_window(Duration::from_secs(det.malformed_input.time_window_secs)),
+                    alert.source_ip,
+                ),
+                5u8,
+            ),
+            ScanType::ScanEnded => {
+                let (alerts, duration) = alert
+                    .scan_episode
+                    .as_ref()
+                    .map_or((0, 0), |e| (e.alerts, e.duration_secs));
+                (
+                    "1014",
+                    "Port Scan Ended",
+                    format!(
+                        "Scanare incheiata: {} porturi, {} destinatii, {} alerte in {} — fara activitate de {}",
+                        alert.unique_ports.len(),
+                        alert.unique_dests.len(),
+                        alerts,
+                        display::format_window(Duration::from_secs(duration)),
+                        display::format_window(Duration::from_secs(alert.silent_secs.unwrap_or(0))),
+                    ),
+                    3u8,
+                )
+            }
+            ScanType::Flood => (
+                "1011",
+                "Packet Flood Detected",
+                format!(
+                    "Flood detectat: {} pachete ({:.0} pachete/s, prag {}/s) — indicator DoS",
+                    alert.event_count.unwrap_or(0),
+                    alert.packet_rate.unwrap_or(0.0),
+                    det.flood.packets_per_sec_threshold,
+                ),
+                7u8,
+            ),
+            ScanType::CoordinatedSubnet => (
+                "1008",
+                "Coordinated Subnet Scan Detected",
+                format!(
+                    "Coordinated Subnet Scan detectat: {} surse din {} → {} porturi unice in {}",
+                    alert.unique_sources.len(),
+                    alert.source_prefix.as_deref().unwrap_or("?"),
+                    alert.unique_ports.len(),
+                    display::format_window(Duration::from_secs(
+                        det.coordinated_subnet.time_window_secs
+                    )),
+                ),
+                7u8,
+            ),
+        };
+
+        // Tinta clasificata in [assets]: severitatea creste indiferent de
+        // numarul de porturi (critical → 10, high → +1). Marcajul fals-pozitiv
+        // al unui operator o coboara (FALSE_POSITIVE_SEVERITY).
+        let cef_severity = alert.effective_severity(cef_severity);
+
+        // Fast Scan promovat din Slow Scan: marcat in `msg` (sloturile cs/flex
+        // sunt ocupate); campul dedicat exista in ECS/GELF/JSON.
+        let scan_label = if alert.promoted_from_slow {
+            format!("{} (promovat din Slow Scan)", scan_label)
+        } else {
+            scan_label
         };
 
         // Pentru Lateral Movement, campul cs1 contine destinatiile unice (IP-uri).
@@ -576,7 +1638,7 @@ impl Alerter {
         // si sortate. `alert.source_ip` este inclus explicit ca fallback defensiv,
         // chiar daca detectorul il include deja in `unique_sources`.
         let (cs1_label, cs1_value, cnt) = match alert.scan_type {
-            ScanType::LateralMovement => {
+            ScanType::LateralMovement | ScanType::SlowSweep => {
                 let dest_list = sorted_ip_list(&alert.unique_dests);
                 ("ContactedHosts", dest_list, alert.unique_dests.len())
             }
@@ -599,14 +1661,30 @@ impl Alerter {
                     cnt,
                 )
             }
+            ScanType::Saturation => (
+                "EvictionsPerSec",
+                format!("{:.3}", alert.eviction_rate.unwrap_or(0.0)),
+                alert.event_count.unwrap_or(0),
+            ),
+            // Niciun eveniment de numarat: cnt=0, durata linistii in cs1.
+            ScanType::NoTraffic => (
+                "SilentSecs",
+                alert.silent_secs.unwrap_or(0).to_string(),
+                0,
+            ),
+            ScanType::Flood => (
+                "PacketsPerSec",
+                format!("{:.1}", alert.packet_rate.unwrap_or(0.0)),
+                alert.event_count.unwrap_or(0),
+            ),
+            ScanType::MalformedInput => (
+                "WindowSecs",
+                det.malformed_input.time_window_secs.to_string(),
+                alert.event_count.unwrap_or(0),
+            ),
             _ => {
-                let port_list = alert
-                    .unique_ports
-                    .iter()
-                    .map(|p| p.to_string())
-                    .collect::<Vec<_>>()
-                    .join(",");
-                ("ScannedPorts", port_list, alert.unique_ports.len())
+                let port_list = alert.ports_text(",", crate::display::siem_port_ranges());
+                ("ScannedPorts", port_list, alert.port_count())
             }
         };
 
@@ -634,6 +1712,33 @@ impl Alerter {
         // Mesajul campului msg: descriere + lista valori (porturi sau IP-uri).
         let msg_text = format!("{} | {}: {}", scan_label, cs1_label.to_lowercase(), cs1_msg);
 
+        // Format CloudEvents: plicul JSON in jurul alertei structurate.
+        if siem.format == "cloudevents" {
+            let event = build_alert_cloudevent(alert)?;
+            return self
+                .send_siem_payload(siem, event.to_string().as_bytes(), &alert.scan_type.to_string())
+                .await;
+        }
+
+        // Format ECS (alerting.siem.format = "ecs"): acelasi continut, forma JSON
+        // Elastic. Sanitizarea CEF nu se aplica — serde_json escapeaza singur.
+        if siem.format == "ecs" {
+            let hn = self.hostnames.load();
+            let doc = build_ecs_document(
+                alert,
+                &EcsContext {
+                    sig_id,
+                    event_name,
+                    severity: cef_severity,
+                    message: &msg_text,
+                    hostnames: &hn,
+                },
+            );
+            return self
+                .send_siem_payload(siem, doc.to_string().as_bytes(), &alert.scan_type.to_string())
+                .await;
+        }
+
         // Sanitizare anti-injection pentru event_name (camp header CEF, separator '|').
         let event_name_safe = sanitize_cef_header(event_name);
 
@@ -651,12 +1756,18 @@ impl Alerter {
             Some(name) => format!(" shost={}", sanitize_cef_extension(name)),
             None => String::new(),
         };
-        let dhost_field = match alert.dest_ip {
-            Some(ref ip) => match hn.get(ip) {
-                Some(name) => format!(" dhost={}", sanitize_cef_extension(name)),
-                None => String::new(),
-            },
-            None => String::new(),
+        // Fara hostname static, numele activului din harta CMDB
+        // (enrichment.asset_map_path) tine loc de dhost.
+        let dest_asset_name = alert
+            .asset
+            .as_ref()
+            .filter(|a| !a.name.is_empty() && alert.dest_ip.is_some())
+            .map(|a| a.name.as_str());
+        let dhost_field = match alert.dest_ip.and_then(|ip| hn.get(&ip).map(String::as_str)) {
+            Some(name) => format!(" dhost={}", sanitize_cef_extension(name)),
+            None => dest_asset_name
+                .map(|name| format!(" dhost={}", sanitize_cef_extension(name)))
+                .unwrap_or_default(),
         };
 
         // Campurile cs2/cs3 — locatie fizica (etaj/zona) din subnet mapping.
@@ -684,6 +1795,14 @@ impl Alerter {
                 " cs4Label=TargetAddress cs4={}",
                 sanitize_cef_extension(&ip.to_string())
             ),
+            // Coordinated Subnet Scan: cs4 = blocul sursa agregat.
+            (ScanType::CoordinatedSubnet, _) => match alert.source_prefix {
+                Some(ref prefix) => format!(
+                    " cs4Label=SourcePrefix cs4={}",
+                    sanitize_cef_extension(prefix)
+                ),
+                None => String::new(),
+            },
             _ => String::new(),
         };
 
@@ -704,19 +1823,152 @@ impl Alerter {
             String::new()
         };
 
-        let syslog_ts = alert.timestamp.format("%b %e %H:%M:%S");
+        // Criticitatea activului vizat — `cat` (deviceEventCategory) permite
+        // filtrare directa in SIEM, separat de severitatea deja escaladata.
+        // Proprietarul activului (harta CMDB) in cs6 — omis pentru Beaconing,
+        // care foloseste deja cs6 (EventCount).
+        // Sursa nod de iesire Tor: categoria `tor-exit`, alaturi de cea a activului.
+        let categories: Vec<String> = alert
+            .asset_criticality
+            .map(|c| format!("asset-{}", c))
+            .into_iter()
+            .chain(alert.tor_exit.then(|| "tor-exit".to_string()))
+            .collect();
+        let mut asset_field = if categories.is_empty() {
+            String::new()
+        } else {
+            format!(" cat={}", categories.join(","))
+        };
+        if let Some(asset) = alert.asset.as_ref().filter(|a| !a.owner.is_empty()) {
+            if !matches!(alert.scan_type, ScanType::Beaconing) {
+                asset_field.push_str(&format!(
+                    " cs6Label=AssetOwner cs6={}",
+                    sanitize_cef_extension(&asset.owner)
+                ));
+            }
+        }
+
+        // Fast/Slow cu sonde UDP goale: flexString2 marcheaza alerta pentru
+        // filtrare in SIEM (campul lipseste cand nu se aplica).
+        let empty_probes_field = if alert.empty_probes {
+            " flexString2Label=EmptyProbes flexString2=true"
+        } else {
+            ""
+        };
+
+        // Lista de porturi plafonata (max_ports_per_ip): `cnt` este aproximativ,
+        // iar flexNumber1 il marcheaza explicit pentru corelari in SIEM.
+        let approx_ports_field = alert
+            .approx_port_count
+            .map(|n| format!(" flexNumber1Label=ApproxPortCount flexNumber1={}", n))
+            .unwrap_or_default();
+
+        // Ponderi per port (detection.port_weights): cfp1 = scorul comparat cu
+        // pragul, alaturi de `cnt` (numarul brut de porturi).
+        let weighted_field = alert
+            .weighted_score
+            .map(|w| format!(" cfp1Label=WeightedScore cfp1={:.1}", w))
+            .unwrap_or_default();
+
+        // Alerta contopita de corelare (alerting.correlation_secs): flexNumber2
+        // = numarul alertelor detectate in fereastra.
+        let merged_field = alert
+            .merged_count
+            .map(|n| format!(" flexNumber2Label=MergedAlerts flexNumber2={}", n))
+            .unwrap_or_default();
+
+        let ingress_field = cef_ingress_field(&alert.ingress);
+
+        // Interfata firewall-ului (network.interface_metadata): cs5 — omisa
+        // pentru Beaconing, care foloseste deja cs5 (CV); VLAN-ul in cn2.
+        let mut interface_field = String::new();
+        if !alert.interface.is_empty() && !matches!(alert.scan_type, ScanType::Beaconing) {
+            interface_field.push_str(&format!(
+                " cs5Label=FirewallInterface cs5={}",
+                sanitize_cef_extension(&alert.interface)
+            ));
+        }
+        if let Some(vlan) = alert.vlan {
+            interface_field.push_str(&format!(" cn2Label=Vlan cn2={}", vlan));
+        }
+
+        // Profilul de detectie ([profiles.<nume>]): deviceFacility — "facilitatea"
+        // care a generat evenimentul. Omis pentru profilul implicit.
+        let profile_field = if alert.profile.is_empty() || alert.profile == DEFAULT_PROFILE {
+            String::new()
+        } else {
+            format!(" deviceFacility={}", sanitize_cef_extension(&alert.profile))
+        };
+
+        // Protocoalele scanate: campul CEF standard `proto` pentru un singur
+        // protocol; mai multe (multi_protocol, incredere ridicata) → cn3.
+        let protocol_field = match alert.protocols.as_slice() {
+            [] => String::new(),
+            [single] => format!(" proto={}", single.to_uppercase()),
+            many => format!(" cn3Label=ProtocolCount cn3={}", many.len()),
+        };
+
+        // Clasa alertei (alerting.classification): campul CEF standard `reason`
+        // — SIEM-ul separa zgomotul de atacurile tintite fara parsarea `msg`.
+        let class_field = alert
+            .classification
+            .map(|c| format!(" reason={}", c))
+            .unwrap_or_default();
+
+        // Cheia de idempotenta (alerting.idempotency_key): campul CEF standard
+        // externalId — acelasi la orice re-trimitere a alertei.
+        let key_field = if alert.idempotency_key.is_empty() {
+            String::new()
+        } else {
+            format!(" externalId={}", sanitize_cef_extension(&alert.idempotency_key))
+        };
+
+        // Senzorul ([sensor]): campul CEF standard deviceHostName (dvchost).
+        // Etichetele in cs4 — omise pentru Distributed Scan / Coordinated
+        // Subnet, care folosesc deja cs4 (TargetAddress / SourcePrefix).
+        let mut sensor_field = String::new();
+        if let Some(ref sensor) = alert.sensor {
+            sensor_field.push_str(&format!(" dvchost={}", sanitize_cef_extension(&sensor.hostname)));
+            if !sensor.tags.is_empty() && distributed_target_field.is_empty() {
+                sensor_field.push_str(&format!(
+                    " cs4Label=SensorTags cs4={}",
+                    sanitize_cef_extension(&sensor_tags_text(&sensor.tags))
+                ));
+            }
+        }
+
+        // Antetul syslog (alerting.siem.rfc); in 5424 cu campurile cheie ca
+        // structured data.
+        let mut sd = vec![
+            ("srcIp", alert.source_ip.to_string()),
+            ("scanType", alert.scan_type.to_string()),
+            ("portCount", alert.port_count().to_string()),
+            ("severity", cef_severity.to_string()),
+            ("policyVersion", alert.policy_version.clone()),
+        ];
+        if let Some(dst) = alert.dest_ip {
+            sd.insert(1, ("dstIp", dst.to_string()));
+        }
+        if let Some(ref sensor) = alert.sensor {
+            sd.push(("sensor", sensor.hostname.clone()));
+            if !sensor.tags.is_empty() {
+                sd.push(("sensorTags", sensor_tags_text(&sensor.tags)));
+            }
+        }
+        let header = syslog_header(siem.rfc, &alert.timestamp, sig_id, &sd);
         let rt_ms = alert.timestamp.timestamp_millis();
 
         let message = format!(
-            "<38>{syslog_ts} ids-rs CEF:0|IDS-RS|Network Scanner Detector|1.0\
+            "{header}CEF:0|IDS-RS|Network Scanner Detector|1.0\
              |{sig_id}|{event_name}|{sev}\
-             |rt={rt_ms} src={src}{shost}{src_loc}{dst}{dhost}{dst_loc}{distributed_target}{beaconing_extra} cnt={cnt} act=alert \
-             msg={msg} cs1Label={cs1label} cs1={cs1}",
+             |rt={rt_ms}{key} src={src}{shost}{src_loc}{dst}{dhost}{dst_loc}{distributed_target}{beaconing_extra} cnt={cnt} act=alert \
+             msg={msg} cs1Label={cs1label} cs1={cs1} flexString1Label=PolicyVersion flexString1={policy}{empty_probes}{approx_ports}{weighted}{merged}{asset}{ingress}{interface}{profile}{protocol}{class}{sensor}",
             sev = cef_severity,
-            syslog_ts = syslog_ts,
+            header = header,
             sig_id = sig_id,
             event_name = event_name_safe,
             rt_ms = rt_ms,
+            key = key_field,
             src = alert.source_ip,
             shost = shost_field,
             src_loc = src_location_field,
@@ -729,20 +1981,178 @@ impl Alerter {
             msg = sanitize_cef_extension(&msg_text),
             cs1label = cs1_label,
             cs1 = sanitize_cef_extension(&cs1_value),
+            policy = sanitize_cef_extension(&alert.policy_version),
+            empty_probes = empty_probes_field,
+            approx_ports = approx_ports_field,
+            weighted = weighted_field,
+            merged = merged_field,
+            asset = asset_field,
+            ingress = ingress_field,
+            interface = interface_field,
+            profile = profile_field,
+            protocol = protocol_field,
+            class = class_field,
+            sensor = sensor_field,
         );
 
+        self.send_siem_payload(siem, message.as_bytes(), &alert.scan_type.to_string())
+            .await
+    }
+
+    /// Trimite un payload (CEF sau ECS) catre SIEM prin UDP.
+    /// `label` apare doar in confirmarea din consola (tipul alertei / "Rollup").
+    async fn send_siem_payload(&self, siem: &SiemConfig, payload: &[u8], label: &str) -> Result<()> {
         // Cream un socket UDP efemer (port 0 = OS alege automat).
         let socket = UdpSocket::bind("0.0.0.0:0")
             .await
             .context("Nu pot crea socket UDP pentru SIEM")?;
 
-        let cfg = self.config.load();
-        let dest = format!("{}:{}", cfg.siem.host, cfg.siem.port);
+        let dest = format!("{}:{}", siem.host, siem.port);
         socket
-            .send_to(message.as_bytes(), &dest)
+            .send_to(payload, &dest)
             .await
             .with_context(|| format!("Nu pot trimite catre SIEM {}", dest))?;
 
+        display::log_alert_sent(&dest, label);
+        Ok(())
+    }
+
+    /// Trimite raportul agregat periodic (`output.rollup_sink` = "siem" / "email")
+    /// catre prima destinatie activa de acel tip (sectiunea legacy, apoi
+    /// `alerting.sinks`). Erorile sunt logate si inregistrate in slotul
+    /// instantei, ca la alerte.
+    pub async fn send_rollup(&self, summary: &RollupSummary, sink: &str) {
+        let sinks = self.sinks.load();
+        let Some(target) = sinks.iter().find(|s| s.kind() == sink) else {
+            display::log_error(&format!("Eroare trimitere rollup: nicio destinatie {} activa", sink));
+            return;
+        };
+        let result = target.send_rollup(self, summary).await;
+        self.errors.observe_sink(target.name(), &result);
+        if let Err(e) = result {
+            display::log_error(&format!("Eroare trimitere rollup ({}): {:#}", target.name(), e));
+        }
+    }
+
+    /// Rollup catre SIEM: ECS (`event.kind = "metric"`, detaliile in
+    /// `ids_rs.rollup`), CloudEvent `ids-rs.rollup` (raportul in `data`) sau
+    /// CEF cu SigID dedicat 1900, severitate 1.
+    async fn send_rollup_siem(&self, siem: &SiemConfig, summary: &RollupSummary) -> Result<()> {
+        if siem.format == "cloudevents" {
+            let event = build_cloudevent(
+                "rollup",
+                &format!("rollup-{}", summary.period_end.timestamp_millis()),
+                &summary.period_end,
+                None,
+                serde_json::to_value(summary).context("Nu pot serializa rollup-ul")?,
+            );
+            return self.send_siem_payload(siem, event.to_string().as_bytes(), "Rollup").await;
+        }
+        if siem.format == "ecs" {
+            let doc = serde_json::json!({
+                "@timestamp": display::format_rfc3339(&summary.period_end),
+                "ecs": { "version": "8.11.0" },
+                "message": format!("IDS-RS rollup {}: {} alerte", summary.interval, summary.total_alerts),
+                "event": {
+                    "kind": "metric",
+                    "category": ["intrusion_detection"],
+                    "type": ["info"],
+                    "module": "ids-rs",
+                    "dataset": "ids_rs.rollup",
+                    "start": display::format_rfc3339(&summary.period_start),
+                    "end": display::format_rfc3339(&summary.period_end),
+                },
+                "ids_rs": { "rollup": summary },
+            });
+            return self.send_siem_payload(siem, doc.to_string().as_bytes(), "Rollup").await;
+        }
+
+        let join = |items: Vec<String>| items.join(",");
+        let by_type = join(
+            summary
+                .alerts_by_type
+                .iter()
+                .map(|(t, n)| format!("{}:{}", t, n))
+                .collect(),
+        );
+        let sources = join(summary.top_sources.iter().map(|s| format!("{}:{}", s.ip, s.alerts)).collect());
+        let ports = join(summary.top_ports.iter().map(|p| format!("{}:{}", p.port, p.alerts)).collect());
+        let header = syslog_header(
+            siem.rfc,
+            &summary.period_end,
+            "1900",
+            &[("alerts", summary.total_alerts.to_string())],
+        );
+        let message = format!(
+            "{header}CEF:0|IDS-RS|Network Scanner Detector|1.0|1900|Alert Rollup|1\
+             |start={start} end={end} cnt={cnt} msg={msg} cs1Label=AlertsByType cs1={by_type} \
+             cs2Label=TopSources cs2={sources} cs3Label=TopPorts cs3={ports} \
+             cn1Label=PeakTrackedIps cn1={peak}",
+            start = summary.period_start.timestamp_millis(),
+            end = summary.period_end.timestamp_millis(),
+            cnt = summary.total_alerts,
+            msg = sanitize_cef_extension(&format!("Rollup {}", summary.interval)),
+            by_type = sanitize_cef_extension(&by_type),
+            sources = sanitize_cef_extension(&sources),
+            ports = sanitize_cef_extension(&ports),
+            peak = summary.peak_tracked_ips,
+        );
+        self.send_siem_payload(siem, message.as_bytes(), "Rollup").await
+    }
+
+    /// Rollup prin email: text simplu (`RollupSummary::to_text`), un mesaj
+    /// per destinatar — acelasi transport SMTP ca alertele.
+    async fn send_rollup_email(
+        &self,
+        cfg: &EmailConfig,
+        mailer: &AsyncSmtpTransport<Tokio1Executor>,
+        summary: &RollupSummary,
+    ) -> Result<()> {
+        let subject = format!(
+            "IDS-RS rollup {} — {} alerte ({})",
+            summary.interval,
+            summary.total_alerts,
+            summary.period_start.format("%Y-%m-%d %H:%M")
+        );
+        for recipient in &cfg.to {
+            let email = Message::builder()
+                .from(
+                    cfg.from
+                        .parse()
+                        .with_context(|| format!("Adresa 'from' invalida: {}", cfg.from))?,
+                )
+                .to(recipient
+                    .parse()
+                    .with_context(|| format!("Adresa destinatar invalida: {}", recipient))?)
+                .subject(&subject)
+                .header(ContentType::TEXT_PLAIN)
+                .body(summary.to_text())
+                .context("Nu pot construi mesajul email")?;
+            mailer
+                .send(email)
+                .await
+                .with_context(|| format!("Nu pot trimite email catre {}", recipient))?;
+        }
+        display::log_alert_sent("Email", "Rollup");
+        Ok(())
+    }
+
+    /// Trimite o alerta GELF 1.1 catre Graylog prin UDP (chunked daca e nevoie).
+    async fn send_gelf_alert(&self, gelf: &GelfConfig, alert: &Alert) -> Result<()> {
+        let payload = build_gelf_message(alert).to_string();
+        let datagrams = gelf_chunks(payload.as_bytes(), gelf.chunk_size, gelf_message_id())?;
+
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .await
+            .context("Nu pot crea socket UDP pentru GELF")?;
+        let dest = format!("{}:{}", gelf.host, gelf.port);
+        for datagram in &datagrams {
+            socket
+                .send_to(datagram, &dest)
+                .await
+                .with_context(|| format!("Nu pot trimite catre GELF {}", dest))?;
+        }
+
         display::log_alert_sent(&dest, &format!("{}", alert.scan_type));
         Ok(())
     }
@@ -763,11 +2173,10 @@ impl Alerter {
     ///
     async fn send_email_alert(
         &self,
-        alert: &Alert,
+        cfg: &EmailConfig,
         mailer: &AsyncSmtpTransport<Tokio1Executor>,
+        alert: &Alert,
     ) -> Result<()> {
-        let alert_cfg = self.config.load();
-        let cfg = &alert_cfg.email;
 
         // Pentru Lateral Movement si Distributed Scan, subject-ul si lista arata diferit.
         let (subject, item_count, list_display) = match alert.scan_type {
@@ -800,6 +2209,19 @@ impl Alerter {
                 );
                 (subj, count, list)
             }
+            ScanType::SlowSweep => {
+                let dest_list = sorted_ip_list(&alert.unique_dests);
+                let count = alert.unique_dests.len();
+                let list = comma_to_display_list(&dest_list);
+                let subj = format!(
+                    "\u{1F534} [{}][SWEEP SERVICIU] IDS-RS {} port {} pe {} destinatii",
+                    alert.scan_type,
+                    alert.source_ip,
+                    alert.ports_text(",", false),
+                    count
+                );
+                (subj, count, list)
+            }
             ScanType::Beaconing => {
                 // Pentru Beaconing, "lista" arata flow-ul + statisticile.
                 let count = alert.event_count.unwrap_or(0);
@@ -823,14 +2245,100 @@ impl Alerter {
                 );
                 (subj, count, list)
             }
-            _ => {
+            ScanType::Saturation => {
+                let count = alert.event_count.unwrap_or(0);
+                let det = self.detection.load();
+                let list = format!(
+                    "Evictii LRU: {} in {} | rata: {:.1} evictii/s | max_tracked_ips: {}",
+                    count,
+                    display::format_window(Duration::from_secs(det.saturation_alert.time_window_secs)),
+                    alert.eviction_rate.unwrap_or(0.0),
+                    det.max_tracked_ips
+                );
+                let subj = format!(
+                    "\u{1F534} [{}][SATURATIE SENZOR] IDS-RS {:.1} evictii/s",
+                    alert.scan_type,
+                    alert.eviction_rate.unwrap_or(0.0)
+                );
+                (subj, count, list)
+            }
+            ScanType::NoTraffic => {
+                let secs = alert.silent_secs.unwrap_or(0);
+                let window = display::format_window(Duration::from_secs(secs));
+                let list = format!(
+                    "Niciun pachet primit in {} — verificati mirror-ul / tap-ul si forwarding-ul syslog",
+                    window
+                );
+                let subj = format!(
+                    "\u{1F534} [{}][SENZOR ORB] IDS-RS fara trafic de {}",
+                    alert.scan_type, window
+                );
+                (subj, secs as usize, list)
+            }
+            ScanType::Flood => {
+                let count = alert.event_count.unwrap_or(0);
+                let rate = alert.packet_rate.unwrap_or(0.0);
+                let list = format!(
+                    "Pachete: {} | rata: {:.0} pachete/s | prag: {} pachete/s mediat pe {}",
+                    count,
+                    rate,
+                    self.detection.load().flood.packets_per_sec_threshold,
+                    display::format_window(Duration::from_secs(
+                        self.detection.load().flood.time_window_secs
+                    )),
+                );
+                let subj = format!(
+                    "\u{1F534} [{}][FLOOD] IDS-RS {} — {:.0} pachete/s",
+                    alert.scan_type, alert.source_ip, rate
+                );
+                (subj, count, list)
+            }
+            ScanType::MalformedInput => {
+                let count = alert.event_count.unwrap_or(0);
+                let list = format!(
+                    "Datagrame fara nicio linie parsabila: {} in {} de la {} — verificati expeditorul (fuzzing, sondare sau forwarder configurat gresit)",
+                    count,
+                    display::format_window(Duration::from_secs(
+                        self.detection.load().malformed_input.time_window_secs
+                    )),
+                    alert.source_ip,
+                );
+                let subj = format!(
+                    "\u{1F534} [{}][INTRARE MALFORMATA] IDS-RS {} — {} datagrame",
+                    alert.scan_type, alert.source_ip, count
+                );
+                (subj, count, list)
+            }
+            ScanType::ScanEnded => {
                 let count = alert.unique_ports.len();
-                let list = alert
-                    .unique_ports
-                    .iter()
-                    .map(|p| p.to_string())
-                    .collect::<Vec<_>>()
-                    .join(", ");
+                let list = alert.ports_text(", ", crate::display::port_ranges_enabled());
+                let duration = alert.scan_episode.as_ref().map_or(0, |e| e.duration_secs);
+                let subj = format!(
+                    "\u{1F534} [{}][SCANARE INCHEIATA] IDS-RS {} — {} porturi in {}",
+                    alert.scan_type,
+                    alert.source_ip,
+                    count,
+                    display::format_window(Duration::from_secs(duration))
+                );
+                (subj, count, list)
+            }
+            ScanType::CoordinatedSubnet => {
+                let source_list = sorted_ip_list(&alert.unique_sources);
+                let count = alert.unique_sources.len();
+                let list = comma_to_display_list(&source_list);
+                let prefix = alert.source_prefix.as_deref().unwrap_or("?");
+                let subj = format!(
+                    "\u{1F534} [{}][BLOC COORDONAT] IDS-RS {} — {} surse, {} porturi",
+                    alert.scan_type,
+                    prefix,
+                    count,
+                    alert.unique_ports.len()
+                );
+                (subj, count, list)
+            }
+            _ => {
+                let count = alert.port_count();
+                let list = alert.ports_text(", ", crate::display::port_ranges_enabled());
                 let subj = format!(
                     "\u{1F534} [{}][SCANARE RETEA] IDS-RS {} {} porturi",
                     alert.scan_type, alert.source_ip, count
@@ -839,6 +2347,20 @@ impl Alerter {
             }
         };
 
+        // Rezumatul comun (`alerting.summary_template`) devine subiectul — aceeasi
+        // formulare ca in GELF si JSON. Formularea specifica tipului ramane rezerva.
+        let subject = if alert.summary.is_empty() {
+            subject
+        } else {
+            format!("\u{1F534} IDS-RS {}", alert.summary)
+        };
+        // Clasa alertei (alerting.classification) in subiect — filtrabila in
+        // regulile clientului de email.
+        let subject = match alert.classification {
+            Some(class) => format!("{} [{}]", subject, class),
+            None => subject,
+        };
+
         // Severitate afisata in email — paralela cu severitatea CEF din send_siem_alert.
         // Fast=7=RIDICATA, Slow=6=MEDIE, AcceptScan=5=MEDIE-MICA, LateralMovement=8=CRITICA.
         let severity = match alert.scan_type {
@@ -847,7 +2369,25 @@ impl Alerter {
             ScanType::AcceptScan => "MEDIE-MICA",
             ScanType::LateralMovement => "CRITICA",
             ScanType::DistributedScan => "RIDICATA",
+            ScanType::CoordinatedSubnet => "RIDICATA",
             ScanType::Beaconing => "CRITICA",
+            ScanType::Saturation => "CRITICA",
+            ScanType::NoTraffic => "MEDIE",
+            ScanType::CustomRule => "MEDIE",
+            ScanType::Flood => "RIDICATA",
+            ScanType::SlowSweep => "MEDIE",
+            ScanType::MalformedInput => "MEDIE-MICA",
+            ScanType::ScanEnded => "SCAZUTA",
+        };
+
+        // Tinta clasificata in [assets] sau marcaj fals-pozitiv: eticheta
+        // urmeaza severitatea efectiva.
+        let severity = if alert.asset_criticality.is_some() || alert.false_positive {
+            display::severity_label(
+                alert.effective_severity(display::scan_style(&alert.scan_type).severity.0),
+            )
+        } else {
+            severity
         };
 
         let dest_ip_display = match alert.dest_ip {
@@ -871,20 +2411,35 @@ impl Alerter {
             None => String::new(),
         };
 
-        let timestamp = alert.timestamp.format("%Y-%m-%d %H:%M:%S");
+        let timestamp = display::format_timestamp(&alert.timestamp);
+        let asset_display = match &alert.asset {
+            Some(asset) if !asset.owner.is_empty() => format!("{} — {}", asset.name, asset.owner),
+            Some(asset) => asset.name.clone(),
+            None => "N/A".to_string(),
+        };
+        let interface_display = if alert.interface.is_empty() {
+            "N/A".to_string()
+        } else {
+            alert.interface_label()
+        };
 
         // Etichete dinamice in email: adapteaza "Porturi scanate" / "Porturi detectate"
         // la tipul de alerta. Lateral Movement arata destinatii, Distributed arata surse.
         let (count_label, list_label) = match alert.scan_type {
             ScanType::LateralMovement => ("Destinatii contactate", "Destinatii detectate"),
+            ScanType::SlowSweep => ("Destinatii sondate", "Destinatii detectate"),
             ScanType::DistributedScan => ("Surse atacatoare", "Surse detectate"),
+            ScanType::CoordinatedSubnet => ("Surse din bloc", "Surse detectate"),
             ScanType::Beaconing => ("Calluri C2", "Detalii beacon"),
+            ScanType::Saturation => ("Evictii LRU", "Detalii saturatie"),
+            ScanType::NoTraffic => ("Secunde fara trafic", "Detalii"),
+            ScanType::Flood => ("Pachete in fereastra", "Detalii flood"),
+            ScanType::MalformedInput => ("Datagrame malformate", "Detalii"),
             _ => ("Porturi scanate", "Porturi detectate"),
         };
 
         let scan_type = alert.scan_type.to_string();
         let source_ip = alert.source_ip.to_string();
-        let timestamp = timestamp.to_string();
 
         let html_body = build_html_body(&HtmlAlertBody {
             scan_type: &scan_type,
@@ -897,6 +2452,10 @@ impl Alerter {
             dst_location: &dst_location,
             item_count,
             timestamp: &timestamp,
+            ingress: if alert.ingress.is_empty() { "N/A" } else { &alert.ingress },
+            interface: &interface_display,
+            asset: &asset_display,
+            tor_exit: alert.tor_exit,
             items: &list_display,
             footer: &cfg.email_footer,
             count_label,
@@ -940,7 +2499,12 @@ impl Alerter {
 
 #[cfg(test)]
 mod tests {
-    use super::{sanitize_cef_extension, sanitize_cef_header};
+    use super::{
+        build_alert_cloudevent, build_ecs_document, build_gelf_message, build_sentinel_document, build_sinks, cef_ingress_field,
+        gelf_chunks, meets_min_severity, sanitize_cef_extension, sanitize_cef_header, sensor_identity,
+        sensor_tags_text, syslog_header, syslog_hostname, without_fields, AlertingConfig, EcsContext,
+        SensorConfig,
+    };
 
     #[test]
     fn test_sanitize_header_newline() {
@@ -1001,4 +2565,538 @@ mod tests {
         let output = sanitize_cef_header(input);
         assert_eq!(output, "a\\\\\\|b");
     }
+
+    #[test]
+    fn test_ecs_document_fields() {
+        use crate::detector::{Alert, ScanType};
+        use std::collections::HashMap;
+        use std::net::IpAddr;
+
+        let src: IpAddr = "10.0.0.5".parse().unwrap();
+        let dst: IpAddr = "10.0.0.1".parse().unwrap();
+        let alert = Alert {
+            unique_ports: vec![22, 80],
+            ingress: "tap-dmz".to_string(),
+            interface: "eth1.100".to_string(),
+            vlan: Some(100),
+            ..Alert::new(ScanType::Fast, src, Some(dst))
+        };
+        let mut hostnames = HashMap::new();
+        hostnames.insert(src, "ws-42".to_string());
+
+        let doc = build_ecs_document(
+            &alert,
+            &EcsContext {
+                sig_id: "1001",
+                event_name: "Fast Port Scan Detected",
+                severity: 7,
+                message: "Fast Scan detectat",
+                hostnames: &hostnames,
+            },
+        );
+
+        assert_eq!(doc["source"]["ip"], "10.0.0.5");
+        assert_eq!(doc["source"]["domain"], "ws-42");
+        assert_eq!(doc["destination"]["ip"], "10.0.0.1");
+        assert_eq!(doc["event"]["kind"], "alert");
+        assert_eq!(doc["event"]["category"][1], "intrusion_detection");
+        assert_eq!(doc["threat"]["indicator"]["type"], "ipv4-addr");
+        assert_eq!(doc["threat"]["technique"]["id"][0], "T1046");
+        assert_eq!(doc["ids_rs"]["unique_ports"][1], 80);
+        assert_eq!(doc["observer"]["ingress"]["interface"]["name"], "tap-dmz");
+        assert_eq!(doc["ids_rs"]["interface"], "eth1.100");
+        assert_eq!(doc["network"]["vlan"]["id"], "100");
+        // Precizia implicita: milisecunde (".fff" inainte de offset-ul de fus orar).
+        let ts = doc["@timestamp"].as_str().unwrap();
+        let frac = ts.split('.').nth(1).unwrap();
+        assert_eq!(frac.find(|c: char| !c.is_ascii_digit()), Some(3));
+    }
+
+    #[test]
+    fn test_gelf_message_fields() {
+        use crate::detector::{Alert, AssetCriticality, ScanType};
+        use std::net::IpAddr;
+
+        let src: IpAddr = "10.0.0.5".parse().unwrap();
+        let dst: IpAddr = "10.0.0.1".parse().unwrap();
+        let alert = Alert {
+            unique_ports: vec![22, 80, 443],
+            asset_criticality: Some(AssetCriticality::Critical),
+            ..Alert::new(ScanType::Fast, src, Some(dst))
+        };
+
+        let msg = build_gelf_message(&alert);
+        assert_eq!(msg["version"], "1.1");
+        assert_eq!(msg["host"], "ids-rs");
+        assert_eq!(msg["_source_ip"], "10.0.0.5");
+        assert_eq!(msg["_scan_type"], "Fast Scan");
+        assert_eq!(msg["_unique_ports"], "22,80,443");
+        assert_eq!(msg["_asset_criticality"], "critical");
+        // Fara intrare (alerta construita in afara listener-ului): camp omis.
+        assert!(msg.get("_ingress").is_none());
+        // Activ critic → severitate 10 → nivel syslog 2 (critical).
+        assert_eq!(msg["level"], 2);
+        assert!(msg["short_message"].as_str().unwrap().contains("3 porturi"));
+    }
+
+    #[test]
+    fn test_sentinel_document_fields() {
+        use crate::detector::{Alert, AssetCriticality, ScanType};
+
+        let alert = Alert {
+            unique_ports: vec![22, 80],
+            asset_criticality: Some(AssetCriticality::Critical),
+            ..Alert::new(ScanType::Fast, "10.0.0.5".parse().unwrap(), None)
+        };
+        let doc = build_sentinel_document(&alert).unwrap();
+        assert_eq!(doc["source_ip"], "10.0.0.5");
+        assert_eq!(doc["rule"], "Fast Scan");
+        // Tinta critica: severitatea efectiva, nu cea a tipului.
+        assert_eq!(doc["severity"], 10);
+        // `time-generated-field` indica acest camp.
+        assert!(doc["timestamp"].is_string());
+    }
+
+    #[test]
+    fn test_idempotency_key_in_payloads() {
+        use crate::detector::{Alert, ScanType};
+        use std::collections::HashMap;
+
+        let mut alert = Alert::new(ScanType::Fast, "10.0.0.5".parse().unwrap(), None);
+        // Optiune oprita: niciun payload nu poarta cheia.
+        assert!(build_gelf_message(&alert).get("_idempotency_key").is_none());
+        assert!(build_sentinel_document(&alert).unwrap().get("idempotency_key").is_none());
+
+        alert.idempotency_key = alert.delivery_key();
+        let key = alert.idempotency_key.clone();
+        let hostnames = HashMap::new();
+        let ecs = build_ecs_document(
+            &alert,
+            &EcsContext {
+                sig_id: "1001",
+                event_name: "Fast Port Scan Detected",
+                severity: 7,
+                message: "Fast Scan detectat",
+                hostnames: &hostnames,
+            },
+        );
+        assert_eq!(ecs["event"]["id"], key.as_str());
+        assert_eq!(build_gelf_message(&alert)["_idempotency_key"], key.as_str());
+        assert_eq!(build_sentinel_document(&alert).unwrap()["idempotency_key"], key.as_str());
+        // CloudEvents: `id` si cheia coincid — aceeasi alerta, acelasi identificator.
+        assert_eq!(build_alert_cloudevent(&alert).unwrap()["id"], key.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_sentinel_retries_mark_possible_duplicates() {
+        use super::deliver_sentinel;
+        use crate::detector::{Alert, ScanType};
+        use std::sync::Mutex;
+
+        let alert = Alert::new(ScanType::Fast, "10.0.0.5".parse().unwrap(), None);
+        // Fiecare incercare: documentul trimis si rezultatul scriptat.
+        let run = |outcomes: Vec<(bool, bool)>, max_retries: u32| {
+            let sent: Mutex<Vec<serde_json::Value>> = Mutex::new(Vec::new());
+            let alert = &alert;
+            async move {
+                let result = deliver_sentinel(alert, max_retries, std::time::Duration::ZERO, |body| {
+                    let docs: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+                    let mut sent = sent.lock().unwrap();
+                    sent.push(docs[0].clone());
+                    let (ok, uncertain) = outcomes[sent.len() - 1];
+                    let result = if ok { Ok(()) } else { Err(anyhow::anyhow!("esec scriptat")) };
+                    async move { (result, uncertain) }
+                })
+                .await;
+                (result, sent.into_inner().unwrap())
+            }
+        };
+
+        // Respinsa cu raspuns (rezultat cunoscut), apoi reusita: fara marcaj.
+        let (result, sent) = run(vec![(false, false), (true, false)], 2).await;
+        assert!(result.is_ok());
+        assert_eq!(sent.len(), 2);
+        assert!(sent.iter().all(|d| d.get("possible_duplicate").is_none()));
+
+        // Rezultat necunoscut la incercarea 1: incercarile urmatoare sunt marcate.
+        let (result, sent) = run(vec![(false, true), (false, false), (true, false)], 2).await;
+        assert!(result.is_ok());
+        assert!(sent[0].get("possible_duplicate").is_none());
+        assert_eq!(sent[1]["possible_duplicate"], true);
+        assert_eq!(sent[1]["delivery_attempt"], 2);
+        assert_eq!(sent[2]["delivery_attempt"], 3);
+
+        // max_retries limiteaza incercarile la 1 + max_retries; ultima eroare se intoarce.
+        let (result, sent) = run(vec![(false, false); 5], 1).await;
+        assert!(result.is_err());
+        assert_eq!(sent.len(), 2);
+        let (result, sent) = run(vec![(false, true); 5], 0).await;
+        assert!(result.is_err());
+        assert_eq!(sent.len(), 1);
+    }
+
+    #[test]
+    fn test_sensor_identity_in_payloads() {
+        use crate::detector::{Alert, ScanType};
+        use std::collections::HashMap;
+
+        let cfg: SensorConfig = toml::from_str(
+            r#"
+            hostname = "fw-sensor-07"
+            tags = { datacenter = "us-east", segment = "dmz" }
+            "#,
+        )
+        .unwrap();
+        let alert = Alert {
+            sensor: sensor_identity(&cfg),
+            ingress: "tap-dmz".to_string(),
+            ..Alert::new(ScanType::Fast, "10.0.0.5".parse().unwrap(), None)
+        };
+
+        let json = serde_json::to_value(&alert).unwrap();
+        assert_eq!(json["sensor"]["hostname"], "fw-sensor-07");
+        assert_eq!(json["sensor"]["tags"]["segment"], "dmz");
+
+        let hostnames = HashMap::new();
+        let ecs = build_ecs_document(
+            &alert,
+            &EcsContext {
+                sig_id: "1001",
+                event_name: "Fast Port Scan Detected",
+                severity: 7,
+                message: "Fast Scan detectat",
+                hostnames: &hostnames,
+            },
+        );
+        assert_eq!(ecs["observer"]["hostname"], "fw-sensor-07");
+        assert_eq!(ecs["observer"]["ingress"]["interface"]["name"], "tap-dmz");
+        assert_eq!(ecs["labels"]["datacenter"], "us-east");
+
+        let gelf = build_gelf_message(&alert);
+        assert_eq!(gelf["_sensor"], "fw-sensor-07");
+        assert_eq!(gelf["_sensor_datacenter"], "us-east");
+        assert_eq!(build_alert_cloudevent(&alert).unwrap()["source"], "ids-rs/fw-sensor-07");
+        assert_eq!(
+            sensor_tags_text(&alert.sensor.as_ref().unwrap().tags),
+            "datacenter=us-east,segment=dmz"
+        );
+
+        // Fara hostname configurat: hostname-ul masinii.
+        let auto = sensor_identity(&SensorConfig::default());
+        assert_eq!(
+            auto.map(|s| s.hostname).unwrap_or_else(|| "-".to_string()),
+            syslog_hostname()
+        );
+    }
+
+    #[test]
+    fn test_ingress_tag_in_outputs() {
+        use crate::detector::{Alert, ScanType};
+
+        let alert = Alert {
+            ingress: "tap dmz=1".to_string(),
+            ..Alert::new(ScanType::Slow, "10.0.0.5".parse().unwrap(), None)
+        };
+        assert_eq!(cef_ingress_field(&alert.ingress), " deviceInboundInterface=tap\\ dmz\\=1");
+        assert_eq!(serde_json::to_value(&alert).unwrap()["ingress"], "tap dmz=1");
+        assert_eq!(build_gelf_message(&alert)["_ingress"], "tap dmz=1");
+
+        // Fara intrare: campul lipseste din CEF si din JSON.
+        let untagged = Alert::new(ScanType::Slow, "10.0.0.5".parse().unwrap(), None);
+        assert_eq!(cef_ingress_field(&untagged.ingress), "");
+        assert!(serde_json::to_value(&untagged).unwrap().get("ingress").is_none());
+    }
+
+    #[test]
+    fn test_alert_cloudevent_envelope() {
+        use crate::detector::{Alert, ScanType};
+
+        let alert = Alert {
+            unique_ports: vec![22, 80],
+            ..Alert::new(ScanType::Fast, "10.0.0.5".parse().unwrap(), None)
+        };
+        let event = build_alert_cloudevent(&alert).unwrap();
+        assert_eq!(event["specversion"], "1.0");
+        assert_eq!(event["type"], "ids-rs.alert.fast-scan");
+        assert!(event["source"].as_str().unwrap().starts_with("ids-rs/"));
+        assert_eq!(
+            event["id"],
+            format!("{}-{}", alert.timestamp.timestamp_millis(), alert.id)
+        );
+        assert_eq!(event["subject"], "10.0.0.5");
+        assert_eq!(event["datacontenttype"], "application/json");
+        // `data` = alerta structurata, aceeasi ca documentul Sentinel.
+        assert_eq!(event["data"]["source_ip"], "10.0.0.5");
+        assert_eq!(event["data"]["unique_ports"][1], 80);
+        assert_eq!(event["data"]["rule"], "Fast Scan");
+        assert!(event["time"].as_str().unwrap().starts_with(&alert.timestamp.format("%Y-%m-%dT").to_string()));
+    }
+
+    #[test]
+    fn test_gelf_chunking() {
+        // Sub limita: o singura datagrama, fara antet de chunk.
+        let small = vec![b'a'; 100];
+        let chunks = gelf_chunks(&small, 512, 7).unwrap();
+        assert_eq!(chunks, vec![small]);
+
+        // 1000 octeti, 512 per datagrama → 500 octeti date per chunk → 2 chunk-uri.
+        let big = vec![b'b'; 1000];
+        let chunks = gelf_chunks(&big, 512, 0x0102030405060708).unwrap();
+        assert_eq!(chunks.len(), 2);
+        for (seq, chunk) in chunks.iter().enumerate() {
+            assert_eq!(&chunk[..2], &[0x1e, 0x0f]);
+            assert_eq!(&chunk[2..10], &[1, 2, 3, 4, 5, 6, 7, 8]);
+            assert_eq!(chunk[10], seq as u8);
+            assert_eq!(chunk[11], 2);
+        }
+        let reassembled: Vec<u8> = chunks.iter().flat_map(|c| c[12..].to_vec()).collect();
+        assert_eq!(reassembled, big);
+
+        // Peste 128 de chunk-uri: respins.
+        assert!(gelf_chunks(&vec![0u8; 129 * 500], 512, 1).is_err());
+    }
+
+    #[test]
+    fn test_min_severity_per_sink() {
+        use crate::detector::{Alert, AssetCriticality, ScanType};
+
+        let cfg: AlertingConfig = toml::from_str(
+            r#"
+            [siem]
+            enabled = true
+            host = "127.0.0.1"
+            port = 514
+            [email]
+            enabled = true
+            smtp_server = "smtp.local"
+            smtp_port = 25
+            smtp_tls = false
+            from = "ids@local"
+            to = ["soc@local"]
+            username = ""
+            password = ""
+            min_severity = 8
+            "#,
+        )
+        .unwrap();
+
+        // Slow Scan (severitate 6): doar SIEM-ul, email-ul (pager) nu.
+        let slow = Alert::new(ScanType::Slow, "10.0.0.5".parse().unwrap(), None);
+        let entries = cfg.sink_entries();
+        let (siem, email) = (entries[0].kind.min_severity(), entries[1].kind.min_severity());
+        assert!(meets_min_severity(siem, &slow));
+        assert!(!meets_min_severity(email, &slow));
+
+        // Aceeasi scanare spre un activ critic este escaladata peste prag.
+        let critical = Alert {
+            asset_criticality: Some(AssetCriticality::Critical),
+            ..slow.clone()
+        };
+        assert!(meets_min_severity(email, &critical));
+    }
+
+    #[test]
+    fn test_sink_list_with_legacy_sections() {
+        use crate::detector::{Alert, AssetCriticality, ScanType};
+
+        let cfg: AlertingConfig = toml::from_str(
+            r#"
+            [siem]
+            enabled = true
+            host = "10.0.0.1"
+            port = 514
+            [email]
+            enabled = false
+            smtp_server = "smtp.local"
+            smtp_port = 25
+            smtp_tls = false
+            from = "ids@local"
+            to = ["soc@local"]
+            username = ""
+            password = ""
+            [[sinks]]
+            type = "siem"
+            name = "siem-backup"
+            host = "10.0.0.9"
+            port = 1514
+            format = "ecs"
+            min_severity = 7
+            omit_fields = ["sensor", "asset"]
+            [[sinks]]
+            type = "gelf"
+            host = "10.0.0.20"
+            [[sinks]]
+            type = "gelf"
+            enabled = false
+            host = "10.0.0.21"
+            "#,
+        )
+        .unwrap();
+
+        // Sectiunea legacy este prima instanta; lista urmeaza, fara intrarea dezactivata.
+        let sinks = build_sinks(&cfg).unwrap();
+        let names: Vec<&str> = sinks.iter().map(|s| s.name()).collect();
+        assert_eq!(names, ["siem", "siem-backup", "gelf-2"]);
+        assert_eq!(sinks[1].kind(), "siem");
+        assert_eq!(sinks[1].min_severity(), 7);
+        assert_eq!(sinks[2].kind(), "gelf");
+        assert!(cfg.sink_names().contains(&"gelf-3".to_string()));
+
+        // Tip necunoscut: respins la parsare.
+        assert!(toml::from_str::<AlertingConfig>("[[sinks]]\ntype = \"webhook\"\n").is_err());
+
+        // `omit_fields` goleste campurile numite, restul alertei ramane.
+        let mut alert = Alert::new(ScanType::Fast, "10.0.0.5".parse().unwrap(), None);
+        alert.asset_criticality = Some(AssetCriticality::Critical);
+        alert.profile = "dmz".to_string();
+        let stripped = without_fields(&alert, sinks[1].omit_fields());
+        assert_eq!(stripped.asset_criticality, None);
+        assert_eq!(stripped.profile, "dmz");
+        assert_eq!(stripped.source_ip, alert.source_ip);
+    }
+
+    #[test]
+    fn test_syslog_header_3164_and_5424() {
+        use chrono::{Local, TimeZone};
+        let ts = Local.with_ymd_and_hms(2026, 10, 15, 14, 30, 12).unwrap();
+
+        assert_eq!(syslog_header(3164, &ts, "1001", &[]), "<38>Oct 15 14:30:12 ids-rs ");
+
+        let sd = [
+            ("srcIp", "10.0.0.5".to_string()),
+            ("scanType", "Fast Scan".to_string()),
+            ("note", r#"a"b]c\d"#.to_string()),
+        ];
+        let header = syslog_header(5424, &ts, "1001", &sd);
+        assert!(header.starts_with("<38>1 2026-10-15T14:30:12.000"));
+        let fields: Vec<&str> = header.splitn(7, ' ').collect();
+        assert_eq!(fields[3], "ids-rs");
+        assert_eq!(fields[4], std::process::id().to_string());
+        assert_eq!(fields[5], "1001");
+        // Valorile SD-PARAM: ghilimele, `]` si backslash escapate.
+        assert_eq!(
+            fields[6],
+            r#"[ids@32473 srcIp="10.0.0.5" scanType="Fast Scan" note="a\"b\]c\\d"] "#
+        );
+    }
+
+    /// Alerter cu destinatiile din `alerting` (TOML), fara hostnames / subnets.
+    fn alerter_with(alerting: &str) -> super::Alerter {
+        let detection = crate::config::AppConfig::load("config.toml").unwrap().detection;
+        super::Alerter::new(
+            toml::from_str(alerting).unwrap(),
+            detection,
+            Default::default(),
+            Vec::new(),
+            std::sync::Arc::new(crate::metrics::LastErrors::new()),
+        )
+        .unwrap()
+    }
+
+    /// Pentru fiecare destinatie activa: (nume, alerta rutata catre ea?).
+    fn routed(alerter: &super::Alerter, alert: &crate::detector::Alert) -> Vec<(String, bool)> {
+        let cfg = alerter.config.load();
+        alerter
+            .sinks
+            .load()
+            .iter()
+            .map(|sink| (sink.name().to_string(), alerter.routes_to(&cfg, sink.as_ref(), alert)))
+            .collect()
+    }
+
+    const SIEM_EMAIL_GELF: &str = r#"
+        [siem]
+        enabled = true
+        host = "127.0.0.1"
+        port = 514
+        [email]
+        enabled = true
+        smtp_server = "smtp.local"
+        smtp_port = 25
+        smtp_tls = false
+        from = "ids@local"
+        to = ["soc@local"]
+        username = ""
+        password = ""
+        [gelf]
+        enabled = true
+        host = "127.0.0.1"
+        "#;
+
+    // Transportul SMTP al destinatiei email cere un runtime tokio.
+    #[tokio::test]
+    async fn test_profile_sinks_restrict_routing() {
+        use crate::config::{AppConfig, ProfileConfig};
+        use crate::detector::{Alert, ScanType};
+
+        let alerter = alerter_with(SIEM_EMAIL_GELF);
+        let detection = AppConfig::load("config.toml").unwrap().detection;
+        let mut profiles = std::collections::BTreeMap::new();
+        profiles.insert(
+            "paging".to_string(),
+            ProfileConfig {
+                sinks: vec!["email".to_string()],
+                detection,
+            },
+        );
+        alerter.set_profiles(&profiles);
+
+        let mut alert = Alert::new(ScanType::Fast, "203.0.113.7".parse().unwrap(), None);
+        alert.profile = "paging".to_string();
+        let expected = |siem, email, gelf| {
+            vec![("siem".to_string(), siem), ("email".to_string(), email), ("gelf".to_string(), gelf)]
+        };
+        assert_eq!(routed(&alerter, &alert), expected(false, true, false));
+
+        // Profilul implicit nu este restrictionat.
+        alert.profile = crate::config::DEFAULT_PROFILE.to_string();
+        assert_eq!(routed(&alerter, &alert), expected(true, true, true));
+    }
+
+    #[test]
+    fn test_routing_by_ip_family() {
+        use crate::detector::{Alert, ScanType};
+
+        const SIEM_GELF: &str = r#"
+            [siem]
+            enabled = true
+            host = "127.0.0.1"
+            port = 514
+            [email]
+            enabled = false
+            smtp_server = "smtp.local"
+            smtp_port = 25
+            smtp_tls = false
+            from = "ids@local"
+            to = ["soc@local"]
+            username = ""
+            password = ""
+            [gelf]
+            enabled = true
+            host = "127.0.0.1"
+            "#;
+        let v4 = Alert::new(ScanType::Fast, "203.0.113.7".parse().unwrap(), None);
+        let v6 = Alert::new(ScanType::Fast, "2001:db8::7".parse().unwrap(), None);
+        let pairs = |siem, gelf| vec![("siem".to_string(), siem), ("gelf".to_string(), gelf)];
+
+        // IPv4 → SIEM, IPv6 → GELF.
+        let alerter = alerter_with(&format!("{}\n[routing]\nipv4 = [\"siem\"]\nipv6 = [\"gelf\"]\n", SIEM_GELF));
+        assert_eq!(routed(&alerter, &v4), pairs(true, false));
+        assert_eq!(routed(&alerter, &v6), pairs(false, true));
+
+        // Familie fara lista: implicit toate destinatiile. GELF lipseste din
+        // lista IPv4 — nu primeste alertele IPv4.
+        let alerter = alerter_with(&format!("{}\n[routing]\nipv4 = [\"siem\"]\n", SIEM_GELF));
+        let routing = &alerter.config.load().routing;
+        assert_eq!(routing.ipv6, ["siem", "email", "gelf", "sentinel"]);
+        assert!(routing.tor_exit.is_empty());
+        assert_eq!(routed(&alerter, &v4), pairs(true, false));
+        assert_eq!(routed(&alerter, &v6), pairs(true, true));
+
+        // Fara [routing]: ambele familii catre toate destinatiile.
+        let alerter = alerter_with(SIEM_GELF);
+        assert_eq!(alerter.config.load().routing.ipv4, crate::config::ALERT_SINKS);
+        assert_eq!(routed(&alerter, &v4), pairs(true, true));
+        assert_eq!(routed(&alerter, &v6), pairs(true, true));
+    }
 }
@@ -0,0 +1,172 @@
+// =============================================================================
+// alertfile.rs - Arhiva de Alerte intr-un Fisier (output.file_path)
+// =============================================================================
+//
+// Un singur fisier care este in acelasi timp lizibil (grep, less) si parsabil
+// de program. `output.file_format` alege ce se scrie per alerta:
+//
+//   "text" → o linie lizibila:
+//     2026-10-15 06:42:10.343 [CRITICA 8] Fast Scan 192.168.11.34 → 10.0.0.5 | 20 porturi: 22, 80, ... | id=42
+//   "json" → documentul alertei pe o linie (acelasi ca `GET /api/alerts`)
+//   "both" → linia text, urmata de documentul JSON indentat cu 4 spatii
+//
+// In formatul "both" linia text incepe mereu in coloana 0, iar JSON-ul este
+// indentat — `grep -v '^ '` pastreaza doar rezumatele, iar un consumator
+// citeste blocul indentat de dupa fiecare linie ca un document JSON.
+//
+// Consola (`display::log_alert`) nu depinde de acest fisier. Fisierul este
+// deschis in mod append si redeschis la fiecare SIGHUP — compatibil cu
+// logrotate (`postrotate kill -HUP`). O eroare de scriere pierde doar
+// alerta curenta; urmatoarea reincearca.
+//
+// =============================================================================
+
+use crate::detector::Alert;
+use crate::display;
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Indentarea documentului JSON in formatul "both".
+const JSON_INDENT: &str = "    ";
+
+pub struct AlertFile {
+    path: PathBuf,
+    file: File,
+    text: bool,
+    json: bool,
+}
+
+impl AlertFile {
+    /// Deschide (append) fisierul, creandu-l daca lipseste. `format` a fost
+    /// validat de `AppConfig::validate()`.
+    pub fn open(path: &str, format: &str) -> Result<Self> {
+        let path = PathBuf::from(path);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Nu pot deschide fisierul de alerte: {:?}", path))?;
+        Ok(Self {
+            path,
+            file,
+            text: format != "json",
+            json: format != "text",
+        })
+    }
+
+    /// Adauga alerta. Intrarea este scrisa dintr-un singur `write_all` —
+    /// un cititor concurent nu vede o linie text fara JSON-ul ei.
+    pub fn write(&mut self, alert: &Alert) -> Result<()> {
+        let entry = self.render(alert)?;
+        self.file
+            .write_all(entry.as_bytes())
+            .with_context(|| format!("Nu pot scrie in fisierul de alerte: {:?}", self.path))
+    }
+
+    fn render(&self, alert: &Alert) -> Result<String> {
+        let mut out = String::new();
+        if self.text {
+            out.push_str(&text_line(alert));
+            out.push('\n');
+        }
+        match (self.text, self.json) {
+            (_, false) => {}
+            (false, true) => {
+                out.push_str(&serde_json::to_string(alert)?);
+                out.push('\n');
+            }
+            (true, true) => {
+                for line in serde_json::to_string_pretty(alert)?.lines() {
+                    out.push_str(JSON_INDENT);
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Linia lizibila: moment, severitate, tip, sursa → tinta, porturi, rezumat, id.
+fn text_line(alert: &Alert) -> String {
+    let severity = alert.effective_severity(display::scan_style(&alert.scan_type).severity.0);
+    let mut line = format!(
+        "{} [{} {}] {} {}",
+        display::format_timestamp(&alert.timestamp),
+        display::severity_label(severity),
+        severity,
+        alert.scan_type,
+        alert.source_ip
+    );
+    if let Some(dest) = alert.dest_ip {
+        line.push_str(&format!(" → {}", dest));
+    }
+    if !alert.unique_ports.is_empty() {
+        line.push_str(&format!(
+            " | {} porturi: {}",
+            alert.port_count_label(),
+            alert.ports_text(", ", display::port_ranges_enabled())
+        ));
+    }
+    if !alert.summary.is_empty() {
+        line.push_str(&format!(" | {}", alert.summary));
+    }
+    line.push_str(&format!(" | id={}", alert.id));
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::ScanType;
+
+    #[test]
+    fn test_alert_file_formats() {
+        let dir = std::env::temp_dir();
+        let path = |format: &str| {
+            dir.join(format!("ids-rs-alerts-{}-{}.log", format, std::process::id()))
+        };
+        let alert = Alert {
+            unique_ports: vec![22, 80, 443],
+            ..Alert::new(ScanType::Fast, "10.0.0.9".parse().unwrap(), Some("10.0.0.1".parse().unwrap()))
+        };
+
+        let mut written = Vec::new();
+        for format in ["text", "json", "both"] {
+            let p = path(format);
+            let _ = std::fs::remove_file(&p);
+            let mut file = AlertFile::open(p.to_str().unwrap(), format).unwrap();
+            file.write(&alert).unwrap();
+            file.write(&alert).unwrap();
+            written.push(std::fs::read_to_string(&p).unwrap());
+            std::fs::remove_file(&p).unwrap();
+        }
+
+        // text: o linie per alerta, fara JSON.
+        let text: Vec<&str> = written[0].lines().collect();
+        assert_eq!(text.len(), 2);
+        assert!(text[0].contains("Fast Scan 10.0.0.9 → 10.0.0.1 | 3 porturi: 22, 80, 443"));
+        assert!(text[0].ends_with(&format!("id={}", alert.id)));
+
+        // json: JSON Lines.
+        for line in written[1].lines() {
+            let json: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(json["source_ip"], "10.0.0.9");
+        }
+        assert_eq!(written[1].lines().count(), 2);
+
+        // both: linia text in coloana 0, apoi blocul JSON indentat.
+        let summaries: Vec<&str> = written[2].lines().filter(|l| !l.starts_with(' ')).collect();
+        assert_eq!(summaries, text);
+        let block: String = written[2]
+            .lines()
+            .skip(1)
+            .take_while(|l| l.starts_with(JSON_INDENT))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let json: serde_json::Value = serde_json::from_str(&block).unwrap();
+        assert_eq!(json["unique_ports"], serde_json::json!([22, 80, 443]));
+    }
+}
@@ -0,0 +1,241 @@
+// =============================================================================
+// assetmap.rs - Context de Activ din CMDB (enrichment.asset_map_path)
+// =============================================================================
+//
+// `[assets]` clasifica subnete dupa criticitate, dar alerta ramane un IP gol.
+// SOC-ul vrea sa vada imediat "scanare a serverului de baze de date critic al
+// echipei X". Harta de active este un export din CMDB, CSV sau JSON:
+//
+//   CSV:   ip,name,criticality,owner
+//          10.0.5.10,db-prod-01,critical,Echipa DBA
+//          10.0.20.0/24,app-farm,high,Echipa Aplicatii
+//
+//   JSON:  [{"ip": "10.0.5.10", "name": "db-prod-01",
+//            "criticality": "critical", "owner": "Echipa DBA"}]
+//
+// O alerta a carei tinta (dst, sau o destinatie Lateral Movement) este in
+// harta primeste `asset` = {name, criticality, owner}. Criticitatea "critical"
+// / "high" escaladeaza severitatea exact ca `[assets]` (nivelul maxim dintre
+// cele doua surse); "medium" / "low" / "" doar eticheteaza alerta.
+//
+// Cand mai multe intrari acopera acelasi IP, castiga cea mai specifica
+// (prefixul cel mai lung) — un /32 din CMDB bate subnetul /24 din jur.
+//
+// =============================================================================
+
+use crate::detector::{Alert, AssetCriticality, WhitelistEntry};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::path::Path;
+
+/// Contextul unui activ, atasat alertei (`asset`).
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetInfo {
+    pub name: String,
+    /// `None` pentru "medium" / "low" / "" — fara escaladare.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub criticality: Option<AssetCriticality>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub owner: String,
+}
+
+/// O intrare din formatul JSON.
+#[derive(Deserialize)]
+struct JsonEntry {
+    #[serde(alias = "cidr")]
+    ip: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    criticality: String,
+    #[serde(default)]
+    owner: String,
+}
+
+/// Harta IP/CIDR → activ. Goala cand `asset_map_path` nu este setat.
+#[derive(Default)]
+pub struct AssetMap {
+    /// Sortate descrescator dupa lungimea prefixului: prima potrivire este
+    /// cea mai specifica.
+    entries: Vec<(WhitelistEntry, AssetInfo)>,
+}
+
+impl AssetMap {
+    /// Incarca harta; formatul este ales dupa extensie (`.csv` / `.json`).
+    /// Cale goala = harta goala.
+    pub fn load(path: &str) -> Result<Self> {
+        if path.is_empty() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Nu pot citi harta de active: {:?}", path))?;
+        let parsed = if is_json(path) {
+            Self::parse_json(&content)
+        } else {
+            Self::parse_csv(&content)
+        };
+        parsed.with_context(|| format!("Harta de active invalida: {:?}", path))
+    }
+
+    fn parse_csv(content: &str) -> Result<Self> {
+        let mut entries = Vec::new();
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            // Antetul exportului CMDB (prima coloana "ip" / "cidr").
+            if entries.is_empty() && matches!(fields[0], "ip" | "cidr") {
+                continue;
+            }
+            if fields.len() > 4 {
+                bail!(
+                    "linia {}: {} coloane (maxim 4: ip,name,criticality,owner — \
+                     virgulele din nume nu sunt suportate, foloseste JSON)",
+                    i + 1,
+                    fields.len()
+                );
+            }
+            let field = |n: usize| fields.get(n).copied().unwrap_or("");
+            let entry = make_entry(field(0), field(1), field(2), field(3))
+                .with_context(|| format!("linia {}", i + 1))?;
+            entries.push(entry);
+        }
+        Ok(Self::from_entries(entries))
+    }
+
+    fn parse_json(content: &str) -> Result<Self> {
+        let raw: Vec<JsonEntry> = serde_json::from_str(content)?;
+        let entries = raw
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                make_entry(&e.ip, &e.name, &e.criticality, &e.owner)
+                    .with_context(|| format!("intrarea {}", i + 1))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::from_entries(entries))
+    }
+
+    fn from_entries(mut entries: Vec<(WhitelistEntry, AssetInfo)>) -> Self {
+        // sort_by_key este stabil: la prefix egal ramane ordinea din fisier.
+        entries.sort_by_key(|(entry, _)| std::cmp::Reverse(entry.prefix_len()));
+        Self { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Activul cel mai specific care contine `ip`.
+    pub fn lookup(&self, ip: &IpAddr) -> Option<&AssetInfo> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| entry.matches(ip))
+            .map(|(_, info)| info)
+    }
+
+    /// Activul vizat de alerta: tinta (`dest_ip`) daca este cunoscuta, altfel
+    /// cea mai critica destinatie Lateral Movement din harta.
+    pub fn lookup_alert(&self, alert: &Alert) -> Option<&AssetInfo> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        alert
+            .dest_ip
+            .as_ref()
+            .and_then(|ip| self.lookup(ip))
+            .or_else(|| {
+                alert
+                    .unique_dests
+                    .iter()
+                    .filter_map(|ip| self.lookup(ip))
+                    .max_by_key(|info| info.criticality)
+            })
+    }
+}
+
+fn is_json(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+fn make_entry(
+    ip: &str,
+    name: &str,
+    criticality: &str,
+    owner: &str,
+) -> Result<(WhitelistEntry, AssetInfo)> {
+    let Some(entry) = WhitelistEntry::parse(ip) else {
+        bail!("IP/CIDR invalid: {:?}", ip);
+    };
+    let criticality = match criticality.to_ascii_lowercase().as_str() {
+        "critical" => Some(AssetCriticality::Critical),
+        "high" => Some(AssetCriticality::High),
+        "medium" | "low" | "" => None,
+        other => bail!(
+            "criticitate necunoscuta {:?} (valide: critical, high, medium, low)",
+            other
+        ),
+    };
+    Ok((
+        entry,
+        AssetInfo {
+            name: name.to_string(),
+            criticality,
+            owner: owner.to_string(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::ScanType;
+
+    #[test]
+    fn test_asset_map_csv_json_and_most_specific_match() {
+        let map = AssetMap::parse_csv(
+            "ip,name,criticality,owner\n\
+             # export CMDB\n\
+             10.0.5.0/24,db-farm,high,Echipa DBA\n\
+             10.0.5.10,db-prod-01,Critical,Echipa DBA\n\
+             10.0.9.1,printer,low\n",
+        )
+        .unwrap();
+        assert_eq!(map.len(), 3);
+
+        // /32 bate /24 indiferent de ordinea din fisier.
+        let db = map.lookup(&"10.0.5.10".parse().unwrap()).unwrap();
+        assert_eq!(db.name, "db-prod-01");
+        assert_eq!(db.criticality, Some(AssetCriticality::Critical));
+        assert_eq!(map.lookup(&"10.0.5.11".parse().unwrap()).unwrap().name, "db-farm");
+        // "low": etichetat, fara escaladare.
+        let printer = map.lookup(&"10.0.9.1".parse().unwrap()).unwrap();
+        assert_eq!(printer.criticality, None);
+        assert!(printer.owner.is_empty());
+
+        // Lateral Movement: cea mai critica destinatie.
+        let alert = Alert {
+            unique_dests: vec!["10.0.9.1".parse().unwrap(), "10.0.5.10".parse().unwrap()],
+            ..Alert::new(ScanType::LateralMovement, "10.0.0.7".parse().unwrap(), None)
+        };
+        assert_eq!(map.lookup_alert(&alert).unwrap().name, "db-prod-01");
+
+        let json = AssetMap::parse_json(
+            r#"[{"cidr": "fd00::/64", "name": "lab-v6", "criticality": "medium"}]"#,
+        )
+        .unwrap();
+        assert_eq!(json.lookup(&"fd00::5".parse().unwrap()).unwrap().name, "lab-v6");
+
+        assert!(AssetMap::parse_csv("10.0.0.1,x,urgent").is_err());
+        assert!(AssetMap::parse_csv("nu-e-ip,x").is_err());
+    }
+}
@@ -0,0 +1,269 @@
+// =============================================================================
+// classify.rs - Zgomot de Fond vs Atac Tintit (alerting.classification)
+// =============================================================================
+//
+// Un senzor expus la Internet vede zilnic sute de scanari "de fond": scannere
+// de cercetare, botnet-uri care maturau tot spatiul IPv4. Ele declanseaza
+// aceleasi reguli ca o recunoastere tintita si ingroapa alertele importante.
+//
+// Clasificatorul eticheteaza fiecare alerta pe porturi `noise` sau `targeted`
+// dupa un scor simplu, cu motivele explicite in alerta:
+//
+//   +2  cel putin jumatate din porturile sondate sunt servicii expuse
+//       (`service_ports`) — scanarea stie ce ascultam
+//   +3  sursa este in `hostile_sources` (IOC-uri cunoscute)
+//   -3  sursa este in `noise_sources` (scannere de cercetare)
+//   +1  scanare selectiva: cel mult `selective_max_ports` porturi
+//   -1  sweep larg: cel putin 10 × `selective_max_ports` porturi
+//   +1  tinta este un activ critic (`[assets]` / harta CMDB)
+//   +1  sursa a scanat mai multe protocoale (`multi_protocol`)
+//   +1  sursa isi epuiza porturile sursa (`source_port_exhaustion`)
+//   +N  sursa este nod de iesire Tor (`tor_exit_score`, implicit 1)
+//
+// Scor >= `targeted_min_score` → `targeted`, altfel `noise`. Fiecare clasa
+// are destinatiile ei (`noise_sinks` / `targeted_sinks`): zgomotul merge
+// intr-un sink de prioritate scazuta, atacurile tintite la paging.
+//
+// Doar regulile cu lista de porturi sunt clasificate (Fast / Slow / Accept
+// Scan, Slow Sweep, Coordinated Subnet, reguli custom). Lateral Movement si Beaconing
+// sunt interne prin natura lor, Distributed Scan, Saturation si No Traffic
+// nu au un atacator unic, iar Flood si Malformed Input nu au lista de
+// porturi — raman neclasificate si rutate normal.
+//
+// Clasificarea ruleaza la emitere (dupa corelare): o alerta contopita este
+// judecata pe reuniunea porturilor.
+//
+// =============================================================================
+
+use crate::config::ClassificationConfig;
+use crate::detector::{Alert, ScanType, WhitelistEntry};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Clasa unei alerte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertClass {
+    Noise,
+    Targeted,
+}
+
+impl AlertClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertClass::Noise => "noise",
+            AlertClass::Targeted => "targeted",
+        }
+    }
+}
+
+impl std::fmt::Display for AlertClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Clasificatorul construit din `[alerting.classification]` (listele de surse
+/// parsate o singura data, nu la fiecare alerta).
+pub struct Classifier {
+    enabled: bool,
+    service_ports: HashSet<u16>,
+    noise_sources: Vec<WhitelistEntry>,
+    hostile_sources: Vec<WhitelistEntry>,
+    selective_max_ports: usize,
+    targeted_min_score: i32,
+    tor_exit_score: i32,
+}
+
+impl Classifier {
+    /// Intrarile invalide au fost respinse deja de `AppConfig::validate()`.
+    pub fn new(cfg: &ClassificationConfig) -> Self {
+        let parse = |list: &[String]| -> Vec<WhitelistEntry> {
+            list.iter().filter_map(|s| WhitelistEntry::parse(s)).collect()
+        };
+        Self {
+            enabled: cfg.enabled,
+            service_ports: cfg.service_ports.iter().copied().collect(),
+            noise_sources: parse(&cfg.noise_sources),
+            hostile_sources: parse(&cfg.hostile_sources),
+            selective_max_ports: cfg.selective_max_ports,
+            targeted_min_score: cfg.targeted_min_score,
+            tor_exit_score: cfg.tor_exit_score,
+        }
+    }
+
+    /// Completeaza `classification` si `classification_reasons` ale alertei.
+    /// Nu face nimic cand clasificarea este dezactivata sau tipul nu se clasifica.
+    pub fn classify(&self, alert: &mut Alert) {
+        if !self.enabled || !classifiable(&alert.scan_type) {
+            return;
+        }
+        let mut score = 0;
+        let mut reasons = Vec::new();
+
+        // Potrivire cu serviciile expuse (doar cand lista este configurata).
+        let ports = &alert.unique_ports;
+        if !self.service_ports.is_empty() && !ports.is_empty() {
+            let hits = ports.iter().filter(|p| self.service_ports.contains(p)).count();
+            if hits * 2 >= ports.len() {
+                score += 2;
+                reasons.push(format!(
+                    "{}/{} porturi sunt servicii expuse (+2)",
+                    hits,
+                    ports.len()
+                ));
+            }
+        }
+
+        // Reputatia sursei.
+        let ip = &alert.source_ip;
+        if self.hostile_sources.iter().any(|e| e.matches(ip)) {
+            score += 3;
+            reasons.push("sursa in hostile_sources (+3)".to_string());
+        }
+        if self.noise_sources.iter().any(|e| e.matches(ip)) {
+            score -= 3;
+            reasons.push("sursa in noise_sources (-3)".to_string());
+        }
+
+        // Selectivitatea: numarul real de porturi, nu lista plafonata.
+        let count = alert.port_count();
+        if count <= self.selective_max_ports {
+            score += 1;
+            reasons.push(format!(
+                "scanare selectiva: {} porturi <= {} (+1)",
+                count, self.selective_max_ports
+            ));
+        } else if count >= self.selective_max_ports.saturating_mul(10) {
+            score -= 1;
+            reasons.push(format!("sweep larg: {} porturi (-1)", count));
+        }
+
+        if let Some(criticality) = alert.asset_criticality {
+            score += 1;
+            reasons.push(format!("tinta este activ {} (+1)", criticality));
+        }
+
+        if alert.multi_protocol {
+            score += 1;
+            reasons.push(format!("multi-protocol: {} (+1)", alert.protocols.join(", ")));
+        }
+
+        if let Some(run) = alert.source_port_exhaustion {
+            score += 1;
+            reasons.push(format!(
+                "epuizare porturi sursa: {} porturi in {}-{} (+1)",
+                run.ports, run.low, run.high
+            ));
+        }
+
+        if alert.tor_exit && self.tor_exit_score != 0 {
+            score += self.tor_exit_score;
+            reasons.push(format!("sursa este nod de iesire Tor ({:+})", self.tor_exit_score));
+        }
+
+        let class = if score >= self.targeted_min_score {
+            AlertClass::Targeted
+        } else {
+            AlertClass::Noise
+        };
+        reasons.push(format!("scor {} (prag targeted {})", score, self.targeted_min_score));
+        alert.classification = Some(class);
+        alert.classification_reasons = reasons;
+    }
+}
+
+/// Tipurile cu lista de porturi si atacator unic.
+fn classifiable(scan_type: &ScanType) -> bool {
+    matches!(
+        scan_type,
+        ScanType::Fast
+            | ScanType::Slow
+            | ScanType::AcceptScan
+            | ScanType::SlowSweep
+            | ScanType::CoordinatedSubnet
+            | ScanType::CustomRule
+    )
+}
+
+/// Clasa alertei permite destinatia `sink`? O alerta neclasificata trece.
+pub fn routes_to(cfg: &ClassificationConfig, sink: &str, alert: &Alert) -> bool {
+    let sinks = match alert.classification {
+        None => return true,
+        Some(AlertClass::Noise) => &cfg.noise_sinks,
+        Some(AlertClass::Targeted) => &cfg.targeted_sinks,
+    };
+    sinks.iter().any(|s| s == sink)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fast(ip: &str, ports: &[u16]) -> Alert {
+        Alert {
+            unique_ports: ports.to_vec(),
+            ..Alert::new(ScanType::Fast, ip.parse().unwrap(), None)
+        }
+    }
+
+    #[test]
+    fn test_classify_noise_vs_targeted_and_routing() {
+        let cfg: ClassificationConfig = toml::from_str(
+            r#"
+            enabled = true
+            service_ports = [22, 443, 3389]
+            noise_sources = ["198.51.100.0/24"]
+            hostile_sources = ["203.0.113.66"]
+            noise_sinks = ["gelf"]
+            targeted_sinks = ["siem", "email"]
+            "#,
+        )
+        .unwrap();
+        let classifier = Classifier::new(&cfg);
+
+        // Putine porturi, toate servicii expuse: 2 + 1 = 3 → targeted.
+        let mut a = fast("192.0.2.10", &[22, 443, 3389, 8080]);
+        classifier.classify(&mut a);
+        assert_eq!(a.classification, Some(AlertClass::Targeted));
+        assert!(a.classification_reasons[0].starts_with("3/4 porturi"));
+
+        // Sweep larg de la un scanner de cercetare: -3 - 1 → noise.
+        let wide: Vec<u16> = (1..=200).collect();
+        let mut b = fast("198.51.100.7", &wide);
+        classifier.classify(&mut b);
+        assert_eq!(b.classification, Some(AlertClass::Noise));
+        assert!(b.classification_reasons.iter().any(|r| r.contains("noise_sources")));
+
+        // Sursa ostila: targeted chiar pe porturi neexpuse (3 - 1 + 0).
+        let mut c = fast("203.0.113.66", &(1000..1200).collect::<Vec<u16>>());
+        classifier.classify(&mut c);
+        assert_eq!(c.classification, Some(AlertClass::Targeted));
+
+        // Lateral Movement nu este clasificat.
+        let mut d = Alert::new(ScanType::LateralMovement, "10.0.0.1".parse().unwrap(), None);
+        classifier.classify(&mut d);
+        assert_eq!(d.classification, None);
+
+        // Rutare: fiecare clasa doar catre destinatiile ei.
+        assert!(routes_to(&cfg, "gelf", &b));
+        assert!(!routes_to(&cfg, "siem", &b));
+        assert!(routes_to(&cfg, "email", &a));
+        assert!(!routes_to(&cfg, "gelf", &a));
+        assert!(routes_to(&cfg, "gelf", &d));
+
+        // Nod de iesire Tor: +1 implicit trece o scanare larga neutra peste prag.
+        let mut tor = fast("192.0.2.77", &[22, 443, 3389, 8080]);
+        tor.tor_exit = true;
+        classifier.classify(&mut tor);
+        assert!(tor.classification_reasons.iter().any(|r| r == "sursa este nod de iesire Tor (+1)"));
+        assert!(tor.classification_reasons.last().unwrap().starts_with("scor 4"));
+
+        // Dezactivat: alertele raman neclasificate.
+        let off = Classifier::new(&ClassificationConfig::default());
+        let mut e = fast("203.0.113.66", &[22]);
+        off.classify(&mut e);
+        assert_eq!(e.classification, None);
+        assert!(e.classification_reasons.is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+// =============================================================================
+// cli.rs - Argumente Linie de Comanda
+// =============================================================================
+//
+// Parsare minimala a argumentelor, fara dependente externe (clap etc.):
+//
+//   ids-rs [config.toml] [--legend] [--max-runtime <durata>]
+//          [--explain-suppressions] [--diagnostics-report] [--help]
+//
+//   config.toml            → cale fisier configurare (pozitional, implicit "config.toml")
+//   --legend               → afiseaza legenda culori/simboluri si iese
+//   --max-runtime <durata> → oprire gratioasa dupa durata data (ex: 30m, 2h)
+//   --explain-suppressions → logheaza de ce o alerta posibila a fost suprimata
+//   --diagnostics-report   → contabilitatea pachetelor la fiecare ciclu si la oprire
+//   --help, -h             → afiseaza utilizarea si iese
+//
+// NOTA RUST: `std::env::args()` returneaza un iterator de `String`.
+// `.skip(1)` sare peste numele executabilului (argv[0]). Parsarea este
+// separata intr-o functie care primeste un iterator generic, astfel incat
+// poate fi testata fara a depinde de argumentele reale ale procesului.
+//
+// =============================================================================
+
+use anyhow::{bail, Context, Result};
+use std::time::Duration;
+
+/// Textul afisat la `--help` sau la un argument necunoscut.
+pub const USAGE: &str = "\
+Utilizare: ids-rs [config.toml] [optiuni]
+
+Optiuni:
+  --legend                Afiseaza legenda culori/simboluri pentru alerte si log-uri, apoi iese
+  --max-runtime <durata>  Opreste gratios senzorul dupa durata data (ex: 90s, 30m, 2h, 1d)
+  --explain-suppressions  Logheaza alertele suprimate si motivul (whitelist, cooldown, sub prag)
+  --diagnostics-report    Afiseaza unde s-au dus pachetele (primite, parsate, dropate pe motive,
+                          alerte) la fiecare ciclu de statistici si la oprire
+  -h, --help              Afiseaza acest mesaj";
+
+/// Argumentele parsate din linia de comanda.
+#[derive(Debug)]
+pub struct CliArgs {
+    /// Calea catre fisierul de configurare. Implicit: "config.toml".
+    pub config_path: String,
+    /// `--legend`: afiseaza legenda si iese (nu incarca configurarea).
+    pub legend: bool,
+    /// `--max-runtime <durata>`: dupa expirare se declanseaza aceeasi oprire
+    /// gratioasa ca la Ctrl+C/SIGTERM (golire coada alerte + sumar final).
+    pub max_runtime: Option<Duration>,
+    /// `--explain-suppressions`: mod audit — fiecare alerta suprimata
+    /// (whitelist, cooldown, sub prag, exceptii) este logata cu motivul.
+    pub explain_suppressions: bool,
+    /// `--diagnostics-report`: contabilitatea pachetelor (primite, parsate,
+    /// dropate pe motive, alerte) la fiecare ciclu de statistici si la oprire.
+    pub diagnostics_report: bool,
+    /// `--help` / `-h`: afiseaza utilizarea si iese.
+    pub help: bool,
+}
+
+impl CliArgs {
+    /// Parseaza argumentele procesului curent.
+    pub fn from_env() -> Result<Self> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Parseaza o secventa de argumente (fara argv[0]).
+    ///
+    /// `I: IntoIterator<Item = String>` accepta atat `env::args()` cat si
+    /// un `Vec<String>` construit in teste.
+    fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self> {
+        let mut cli = CliArgs {
+            config_path: "config.toml".to_string(),
+            legend: false,
+            max_runtime: None,
+            explain_suppressions: false,
+            diagnostics_report: false,
+            help: false,
+        };
+        let mut config_seen = false;
+
+        // NOTA RUST: iteram manual (`while let ... = args.next()`) in loc de
+        // `for`, pentru ca `--max-runtime` consuma si argumentul urmator.
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--legend" => cli.legend = true,
+                "--max-runtime" => {
+                    let Some(value) = args.next() else {
+                        bail!("--max-runtime necesita o durata (ex: 30m)\n\n{}", USAGE);
+                    };
+                    cli.max_runtime = Some(parse_duration(&value)?);
+                }
+                flag if flag.starts_with("--max-runtime=") => {
+                    cli.max_runtime = Some(parse_duration(&flag["--max-runtime=".len()..])?);
+                }
+                "--explain-suppressions" => cli.explain_suppressions = true,
+                "--diagnostics-report" => cli.diagnostics_report = true,
+                "--help" | "-h" => cli.help = true,
+                flag if flag.starts_with('-') => {
+                    bail!("Argument necunoscut: {}\n\n{}", flag, USAGE);
+                }
+                _ => {
+                    if config_seen {
+                        bail!("Mai multe fisiere de configurare specificate: {}\n\n{}", arg, USAGE);
+                    }
+                    cli.config_path = arg;
+                    config_seen = true;
+                }
+            }
+        }
+
+        Ok(cli)
+    }
+}
+
+/// Parseaza o durata de forma `<numar>[s|m|h|d]`. Fara sufix = secunde.
+///
+/// Exemple: "90" → 90s, "45s", "30m", "2h", "1d". Durata zero este respinsa
+/// (un `--max-runtime 0` ar opri senzorul imediat — aproape sigur o greseala).
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.char_indices().last() {
+        Some((i, 's')) => (&value[..i], 1),
+        Some((i, 'm')) => (&value[..i], 60),
+        Some((i, 'h')) => (&value[..i], 3600),
+        Some((i, 'd')) => (&value[..i], 86400),
+        _ => (value, 1),
+    };
+
+    let amount: u64 = digits
+        .parse()
+        .with_context(|| format!("Durata invalida: '{}' (ex: 90s, 30m, 2h, 1d)", value))?;
+    if amount == 0 {
+        bail!("Durata trebuie sa fie > 0: '{}'", value);
+    }
+
+    let secs = amount
+        .checked_mul(multiplier)
+        .with_context(|| format!("Durata prea mare: '{}'", value))?;
+    Ok(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs> {
+        CliArgs::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_default_config_path() {
+        let cli = parse(&[]).unwrap();
+        assert_eq!(cli.config_path, "config.toml");
+        assert!(!cli.legend);
+    }
+
+    #[test]
+    fn test_legend_with_config_path() {
+        let cli = parse(&["/etc/ids-rs/config.toml", "--legend"]).unwrap();
+        assert_eq!(cli.config_path, "/etc/ids-rs/config.toml");
+        assert!(cli.legend);
+        assert!(!cli.explain_suppressions);
+        assert!(parse(&["--explain-suppressions"]).unwrap().explain_suppressions);
+        assert!(!cli.diagnostics_report);
+        assert!(parse(&["--diagnostics-report", "a.toml"]).unwrap().diagnostics_report);
+    }
+
+    #[test]
+    fn test_unknown_flag_rejected() {
+        assert!(parse(&["--legned"]).is_err());
+        assert!(parse(&["a.toml", "b.toml"]).is_err());
+    }
+
+    #[test]
+    fn test_max_runtime_parsing() {
+        let cli = parse(&["--max-runtime", "30m"]).unwrap();
+        assert_eq!(cli.max_runtime, Some(Duration::from_secs(1800)));
+        let cli = parse(&["--max-runtime=2h", "a.toml"]).unwrap();
+        assert_eq!(cli.max_runtime, Some(Duration::from_secs(7200)));
+        assert_eq!(cli.config_path, "a.toml");
+        assert!(parse(&[]).unwrap().max_runtime.is_none());
+
+        assert_eq!(parse_duration("90").unwrap(), Duration::from_secs(90));
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("10x").is_err());
+        assert!(parse(&["--max-runtime"]).is_err());
+    }
+}
@@ -35,8 +35,8 @@
 // =============================================================================
 
 use anyhow::{Context, Result};
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::net::IpAddr;
 use std::path::Path;
 
@@ -60,6 +60,207 @@ pub struct AppConfig {
     /// config.toml, dashboard-ul este dezactivat (enabled = false).
     #[serde(default = "default_web_dashboard")]
     pub web_dashboard: WebDashboardConfig,
+
+    /// Configurare endpoint metrici Prometheus. Retrocompatibil: daca lipseste
+    /// din config.toml, serverul de metrici este dezactivat (enabled = false).
+    #[serde(default = "default_metrics")]
+    pub metrics: MetricsConfig,
+
+    /// Configurare afisare consola. Retrocompatibil: daca lipseste din
+    /// config.toml, afisarea ramane neschimbata (fara coalescing).
+    #[serde(default = "default_display")]
+    pub display: DisplayConfig,
+
+    /// Fisier cu secrete `NUME=valoare` (permisiuni 600), referite in orice
+    /// camp string ca `${NUME}`. Cale relativa = fata de directorul config-ului.
+    /// Interpolarea are loc in `AppConfig::load`, inainte de deserializare.
+    #[serde(default)]
+    pub secrets_file: Option<String>,
+
+    /// Criticitatea activelor tinta (`[assets]`). Retrocompatibil: daca
+    /// lipseste din config.toml, nicio alerta nu este escaladata.
+    #[serde(default)]
+    pub assets: AssetsConfig,
+
+    /// Context de activ din CMDB (`[enrichment]`). Retrocompatibil: daca
+    /// lipseste din config.toml, alertele nu sunt imbogatite.
+    #[serde(default)]
+    pub enrichment: EnrichmentConfig,
+
+    /// Feedback fals-pozitiv (`POST /alerts/{id}/false-positive`).
+    /// Retrocompatibil: daca lipseste din config.toml, feedback-ul e dezactivat.
+    #[serde(default = "default_false_positives")]
+    pub false_positives: FalsePositivesConfig,
+
+    /// Iesiri locale suplimentare (`[output]`). Retrocompatibil: daca lipseste
+    /// din config.toml, nicio iesire suplimentara nu este activa.
+    #[serde(default)]
+    pub output: OutputConfig,
+
+    /// Socket de control local (`[control]`). Retrocompatibil: daca lipseste
+    /// din config.toml, socket-ul nu este creat.
+    #[serde(default)]
+    pub control: ControlConfig,
+
+    /// Profiluri de detectie suplimentare (`[profiles.<nume>]`), evaluate pe
+    /// ACELASI flux de evenimente ca `[detection]` (profilul "default").
+    /// Fiecare are pragurile si sink-urile proprii. Retrocompatibil: lipsa
+    /// sectiunii = un singur profil.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+
+    /// Dependinte asteptate inainte de pornirea detectiei (`[startup]`).
+    /// Retrocompatibil: lipsa sectiunii = pornire imediata.
+    #[serde(default)]
+    pub startup: StartupConfig,
+
+    /// Gruparea alertelor in incidente (`[incidents]`), expuse prin
+    /// `GET /incidents`. Retrocompatibil: lipsa sectiunii = dezactivat.
+    #[serde(default)]
+    pub incidents: IncidentsConfig,
+
+    /// Identitatea senzorului (`[sensor]`): hostname si etichete atasate
+    /// fiecarei alerte. Retrocompatibil: lipsa sectiunii = hostname detectat
+    /// automat, fara etichete.
+    #[serde(default)]
+    pub sensor: SensorConfig,
+
+    /// Avertismente de depreciere gasite de `load` (ex: `time_window_secs`
+    /// in loc de `window`). Nu provin din fisier; afisate la pornire si SIGHUP.
+    #[serde(skip)]
+    pub deprecations: Vec<String>,
+}
+
+/// Numele profilului implicit — sectiunea `[detection]`.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Un profil de detectie suplimentar (`[profiles.<nume>]`).
+///
+/// `detection` NU trebuie repetat integral: `AppConfig::load` suprapune
+/// `[profiles.<nume>.detection]` peste `[detection]` (cheie cu cheie, pe
+/// tabele imbricate), deci profilul contine doar diferentele:
+///
+/// ```toml
+/// [profiles.paging]
+/// sinks = ["email"]
+/// [profiles.paging.detection.fast_scan]
+/// port_threshold = 100
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfileConfig {
+    /// Sink-urile catre care merg alertele profilului (subset din
+    /// `ALERT_SINKS`, filtrat in continuare de `alerting.routing`).
+    /// Implicit: toate. Lista goala = doar consola / dashboard.
+    #[serde(default = "default_profile_sinks")]
+    pub sinks: Vec<String>,
+    /// `[detection]` cu suprapunerea profilului aplicata.
+    pub detection: DetectionConfig,
+}
+
+fn default_profile_sinks() -> Vec<String> {
+    ALERT_SINKS.iter().map(|s| s.to_string()).collect()
+}
+
+/// Suprapune recursiv `overlay` peste `base`: tabelele se combina cheie cu
+/// cheie, orice alta valoare (inclusiv liste) este inlocuita integral.
+fn merge_toml(base: &mut toml::Value, overlay: &toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
+/// Regulile `[detection.<regula>]` cu fereastra de timp si campul vechi in
+/// care `window` este convertit (singurul citit de restul codului).
+const WINDOW_TIERS: [(&str, &str); 12] = [
+    ("fast_scan", "time_window_secs"),
+    ("slow_scan", "time_window_mins"),
+    ("accept_scan", "time_window_secs"),
+    ("lateral_movement", "time_window_secs"),
+    ("distributed_scan", "time_window_secs"),
+    ("coordinated_subnet", "time_window_secs"),
+    ("flood", "time_window_secs"),
+    ("slow_sweep", "time_window_secs"),
+    ("malformed_input", "time_window_secs"),
+    ("beaconing", "time_window_secs"),
+    ("saturation_alert", "time_window_secs"),
+    ("source_port_exhaustion", "time_window_secs"),
+];
+
+/// Converteste `window = "10s"` / `"5m"` / `"1h"` din regulile tabelei
+/// `detection` (calea `path`, pentru mesaje) in campul vechi al regulii.
+///
+/// Campurile vechi scrise direct raman acceptate o versiune, cu un
+/// avertisment de depreciere in `deprecations`. Ambele in aceeasi regula =
+/// eroare (nu ghicim care a fost intentia).
+fn normalize_windows(
+    detection: &mut toml::Value,
+    path: &str,
+    deprecations: &mut Vec<String>,
+) -> Result<()> {
+    let toml::Value::Table(tiers) = detection else {
+        return Ok(());
+    };
+    for (tier, legacy) in WINDOW_TIERS {
+        let Some(toml::Value::Table(table)) = tiers.get_mut(tier) else {
+            continue;
+        };
+        let key = format!("{}.{}", path, tier);
+        let Some(window) = table.remove("window") else {
+            if let Some(old) = table.get(legacy).and_then(|v| v.as_integer()) {
+                let secs = if legacy == "time_window_mins" { old.saturating_mul(60) } else { old };
+                deprecations.push(format!(
+                    "{}.{} este depreciat — foloseste window = \"{}\" (campul vechi va fi eliminat in versiunea urmatoare)",
+                    key,
+                    legacy,
+                    human_duration(secs)
+                ));
+            }
+            continue;
+        };
+        if table.contains_key(legacy) {
+            anyhow::bail!("{}: `window` si `{}` impreuna — pastreaza doar `window`", key, legacy);
+        }
+        let text = window
+            .as_str()
+            .with_context(|| format!("{}.window trebuie sa fie text (ex: \"10s\", \"5m\", \"1h\")", key))?;
+        let secs = crate::cli::parse_duration(text)
+            .with_context(|| format!("{}.window", key))?
+            .as_secs();
+        let value = if legacy == "time_window_mins" {
+            if secs % 60 != 0 {
+                anyhow::bail!(
+                    "{}.window = {:?}: fereastra Slow Scan se exprima in minute intregi (ex: \"5m\")",
+                    key,
+                    text
+                );
+            }
+            secs / 60
+        } else {
+            secs
+        };
+        let value = i64::try_from(value).with_context(|| format!("{}.window prea mare", key))?;
+        table.insert(legacy.to_string(), toml::Value::Integer(value));
+    }
+    Ok(())
+}
+
+/// Secunde → cea mai scurta forma `window` exacta: 3600 → "1h", 300 → "5m".
+fn human_duration(secs: i64) -> String {
+    match secs {
+        s if s > 0 && s % 3600 == 0 => format!("{}h", s / 3600),
+        s if s > 0 && s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{}s", s),
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -80,6 +281,63 @@ pub struct NetworkConfig {
     #[serde(default = "default_udp_burst_size")]
     pub udp_burst_size: u64,
 
+    /// Fereastra de deduplicare (milisecunde) pentru evenimente identice.
+    /// Cu SPAN/mirror bidirectional sau doi forwarderi, acelasi log poate
+    /// ajunge de doua ori si dubleaza contoarele. 0 = dezactivat.
+    #[serde(default)]
+    pub dedup_window_ms: u64,
+
+    /// Raport periodic (o data pe minut) al liniilor care nu au putut fi
+    /// parsate: numar + un exemplu hex al octetilor. O rata mare si constanta
+    /// indica aproape sigur un `parser` gresit pentru feed-ul primit.
+    /// Implicit: false.
+    #[serde(default)]
+    pub parse_error_report: bool,
+
+    /// Fisier "dead letter": pachetele cu linii respinse de parser sunt adaugate
+    /// ca JSON (hex + motiv), pentru diagnostic fara tcpdump. Volum limitat:
+    /// pachet trunchiat, maxim de inregistrari/s, rotatie la `deadletter_max_bytes`.
+    /// Implicit: dezactivat.
+    #[serde(default)]
+    pub deadletter_file: Option<String>,
+
+    /// Dimensiunea maxima (octeti) a fisierului dead letter inainte de rotatie.
+    /// Implicit: 10 MiB.
+    #[serde(default = "default_deadletter_max_bytes")]
+    pub deadletter_max_bytes: u64,
+
+    /// Numarul de fisiere rotite pastrate (`fisier.1` ... `fisier.N`).
+    /// 0 = fara istoric (fisierul activ este golit la rotatie). Implicit: 2.
+    #[serde(default = "default_deadletter_max_files")]
+    pub deadletter_max_files: u32,
+
+    /// Sensurile de trafic vizibile senzorului: "both" (implicit) sau
+    /// "inbound_only" (tap-uri care vad doar traficul de intrare). In modul
+    /// "inbound_only" regulile care au nevoie de ambele sensuri — Accept Scan
+    /// si Beaconing — sunt oprite si marcate ca indisponibile.
+    #[serde(default = "default_traffic_direction")]
+    pub traffic_direction: String,
+
+    /// Numele intrarii (interfata / port / tap) pe care asculta senzorul,
+    /// copiat in campul `ingress` al fiecarei alerte. Atribuie alerta
+    /// segmentului de retea corect cand mai multe feed-uri ajung la SIEM.
+    /// Gol (implicit) = derivat din socket: "udp/<listen_address>:<listen_port>".
+    #[serde(default)]
+    pub ingress_name: String,
+
+    /// Namespace-ul de retea (Linux) in care se creeaza socket-ul de receptie:
+    /// un nume din `/run/netns` (`ip netns add`) sau o cale (`/proc/<pid>/ns/net`).
+    /// Necesita CAP_SYS_ADMIN. Gol (implicit) = namespace-ul de lansare.
+    #[serde(default)]
+    pub netns: String,
+
+    /// Copiaza in alerta interfata firewall-ului pe care a fost observat
+    /// evenimentul declansator (`interface`) si VLAN-ul derivat din numele
+    /// sub-interfetei (`vlan`, ex: `eth1.100` → 100), cand log-ul le contine.
+    /// Implicit: false (alertele nu poarta interfata).
+    #[serde(default)]
+    pub interface_metadata: bool,
+
     /// Mapping static IP → hostname (ex: "10.0.1.10" = "srv-dc01").
     /// Folosit pentru afisare in alerte CLI, email si SIEM (shost=/dhost= in CEF).
     /// Reteaua fiind izolata, nu avem DNS extern — hostname-urile sunt configurate manual.
@@ -91,12 +349,67 @@ pub struct NetworkConfig {
     /// (etaj, cladire, zona) pe langa IP si hostname.
     #[serde(default)]
     pub subnets: HashMap<String, String>,
+
+    /// Sursa reala din spatele unui NAT / proxy, citita dintr-un camp al
+    /// log-ului (`[network.nat_source]`). Implicit: dezactivat.
+    #[serde(default)]
+    pub nat_source: NatSourceConfig,
+}
+
+/// Atribuirea alertelor clientului real cand traficul trece printr-un NAT.
+///
+/// Unele exportoare de flow-uri / firewall-uri includ clientul original intr-un
+/// camp separat (ex: CEF `cs6=203.0.113.7`, LEA `original_src:"203.0.113.7"`).
+/// Cand `field` este setat si campul contine un IP valid, acesta inlocuieste
+/// sursa evenimentului INAINTE de detectie — fara optiune, tot traficul extern
+/// pare sa vina de la IP-ul NAT, care depaseste mereu pragurile.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct NatSourceConfig {
+    /// Numele campului cu sursa reala (cheia `cheie=valoare` din CEF sau
+    /// `cheie: valoare` / `cheie:"valoare"` din Gaia / LEA). Gol = dezactivat.
+    #[serde(default)]
+    pub field: String,
+
+    /// IP-uri / CIDR-uri ale dispozitivelor NAT. Cand lista nu este goala,
+    /// campul este folosit DOAR daca sursa din log este unul din ele — un
+    /// atacator din afara NAT-ului nu isi poate alege identitatea scriind campul.
+    /// Gol = orice sursa.
+    #[serde(default)]
+    pub nat_devices: Vec<String>,
 }
 
 fn default_udp_burst_size() -> u64 {
     10_000
 }
 
+fn default_deadletter_max_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_deadletter_max_files() -> u32 {
+    2
+}
+
+fn default_traffic_direction() -> String {
+    "both".to_string()
+}
+
+impl NetworkConfig {
+    /// True pentru `traffic_direction = "inbound_only"`.
+    pub fn inbound_only(&self) -> bool {
+        self.traffic_direction == "inbound_only"
+    }
+
+    /// Eticheta `ingress` a alertelor produse de listener-ul UDP.
+    pub fn ingress_label(&self) -> String {
+        if self.ingress_name.is_empty() {
+            format!("udp/{}:{}", self.listen_address, self.listen_port)
+        } else {
+            self.ingress_name.clone()
+        }
+    }
+}
+
 /// Configurare detectie - contine sub-structuri pentru fiecare tip de scan.
 ///
 /// NOTA RUST: Structurile imbricate (nested) se mapeaza pe sectiuni TOML
@@ -108,7 +421,7 @@ fn default_udp_burst_size() -> u64 {
 ///   - Daca lipseste din fisier, serde apeleaza functia specificata pentru valoare default
 ///   - Retrocompatibil: configuratii vechi fara campul nou continua sa functioneze
 ///   - Functiile de default trebuie sa returneze acelasi tip ca si campul
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DetectionConfig {
     pub alert_cooldown_secs: u64,
 
@@ -125,6 +438,72 @@ pub struct DetectionConfig {
     #[serde(default = "default_max_tracked_ips")]
     pub max_tracked_ips: usize,
 
+    /// Plafonul de porturi DISTINCTE stocate per IP sursa (drop si accept,
+    /// separat). Dupa plafon, porturile noi nu mai sunt memorate individual —
+    /// sunt doar numarate (contor saturat), iar alertele raporteaza totalul
+    /// aproximativ (`approx_port_count`). Limiteaza memoria per intrare la
+    /// scanari extreme (toate cele 65535 porturi). 0 = fara plafon (implicit).
+    #[serde(default)]
+    pub max_ports_per_ip: usize,
+
+    /// Interval (secunde) pentru compactarea memoriei de tracking: Vec-urile
+    /// per IP ramase supra-alocate dupa un burst sunt micsorate, iar map-urile
+    /// cu factor de incarcare scazut isi elibereaza capacitatea. Ruleaza in
+    /// task-ul de cleanup, deci granularitatea efectiva este `cleanup.interval_secs`.
+    /// 0 = dezactivat (implicit).
+    #[serde(default)]
+    pub compact_interval_secs: u64,
+
+    /// Declanseaza o trecere de cleanup dupa N pachete UDP procesate, pe langa
+    /// timer-ul `cleanup.interval_secs` (primul declansator castiga). Pe senzorii
+    /// cu burst-uri, memoria ramane limitata de activitate, nu doar de timp.
+    /// 0 = dezactivat (implicit, doar timer).
+    #[serde(default)]
+    pub cleanup_every_n_packets: u64,
+
+    /// Pauza (secunde) dupa care starea de numarare a unui IP se reseteaza:
+    /// daca sursa tace mai mult de atat, hit-urile acumulate (porturi,
+    /// destinatii) sunt sterse si o scanare reluata porneste de la zero.
+    /// Diferit de `cleanup.max_entry_age_secs` (TTL de memorie): aici evitam
+    /// alertele tarzii declansate de acumulari vechi, in cadrul ferestrei.
+    /// 0 = dezactivat (implicit).
+    #[serde(default)]
+    pub inactivity_reset_secs: u64,
+
+    /// Numarul de ferestre SEPARATE in care un IP trebuie sa depaseasca pragul
+    /// (Fast / Slow / Accept Scan) inainte de alerta. Prima depasire doar
+    /// "armeaza" o stare de asteptare; alerta pleaca la a N-a depasire, daca
+    /// toate cad in `confirm_secs` de la prima. O depasire izolata expira
+    /// tacut. Creste precizia in schimbul latentei. 1 = alerta imediata (implicit).
+    #[serde(default = "default_confirm_windows")]
+    pub confirm_windows: u32,
+
+    /// Intervalul (secunde) in care trebuie sa apara depasirile de confirmare,
+    /// masurat de la prima depasire. Folosit doar cand `confirm_windows > 1`.
+    #[serde(default = "default_confirm_secs")]
+    pub confirm_secs: u64,
+
+    /// Precedenta intre Fast Scan si Slow Scan pentru ACELASI IP. O scanare
+    /// agresiva care continua atinge ambele praguri — doua alerte pentru
+    /// acelasi comportament. Valori (`RULE_PRECEDENCES`):
+    ///   "none" — regulile alerteaza independent (implicit, retrocompatibil)
+    ///   "fast" — cat timp o alerta Fast Scan e activa (in cooldown), Slow Scan
+    ///            este suprimat pentru acel IP
+    ///   "slow" — invers: o alerta Slow Scan activa suprima Fast Scan
+    #[serde(default = "default_rule_precedence")]
+    pub rule_precedence: String,
+
+    /// Numararea porturilor cand aceeasi sursa scaneaza mai multe protocoale
+    /// (Fast / Slow / Accept Scan). Valori (`PROTOCOL_TRACKING_MODES`):
+    ///   "combined" — toate protocoalele la un loc (implicit, retrocompatibil);
+    ///                alertele unei surse cu TCP si UDP sunt marcate
+    ///                `multi_protocol` (incredere ridicata)
+    ///   "separate" — fiecare pereche (sursa, protocol) isi atinge singura
+    ///                pragul: 8 porturi TCP + 8 UDP nu mai fac 16; cooldown-ul
+    ///                si depasirile plafonului sunt tot per (sursa, protocol)
+    #[serde(default = "default_per_protocol_tracking")]
+    pub per_protocol_tracking: String,
+
     /// Lista de IP-uri si subrețele excluse din detecție.
     /// Accepta IP-uri individuale ("10.0.1.10") si CIDR ("10.0.2.0/24").
     /// IP-urile din whitelist nu genereaza alerte (trafic legitim cunoscut).
@@ -155,6 +534,31 @@ pub struct DetectionConfig {
     #[serde(default = "default_distributed_scan")]
     pub distributed_scan: DistributedScanConfig,
 
+    /// Scanare coordonata din acelasi bloc de retea (surse din acelasi prefix).
+    /// Retrocompatibil: daca lipseste din config.toml, detectia e dezactivata.
+    #[serde(default = "default_coordinated_subnet")]
+    pub coordinated_subnet: CoordinatedSubnetConfig,
+
+    /// Cerinte comune regulilor orizontale (o sursa / un bloc → multe tinte).
+    /// Retrocompatibil: daca lipseste din config.toml, nu se aplica nimic.
+    #[serde(default)]
+    pub sweep: SweepConfig,
+
+    /// Flood volumetric (pachete/secunda per sursa), independent de porturi.
+    /// Retrocompatibil: daca lipseste din config.toml, detectia e dezactivata.
+    #[serde(default = "default_flood")]
+    pub flood: FloodConfig,
+
+    /// Acelasi port sondat pe multe hosturi, oricat de lent (Slow Sweep).
+    /// Retrocompatibil: daca lipseste din config.toml, detectia e dezactivata.
+    #[serde(default = "default_slow_sweep")]
+    pub slow_sweep: SlowSweepConfig,
+
+    /// Volum de datagrame malformate de la acelasi expeditor (fuzzing / sondare
+    /// a portului de receptie). Retrocompatibil: lipsa sectiunii = dezactivat.
+    #[serde(default = "default_malformed_input")]
+    pub malformed_input: MalformedInputConfig,
+
     /// Configurare praguri dinamice / adaptive (#35).
     /// Retrocompatibil: daca lipseste din config.toml, pragurile raman statice.
     #[serde(default = "default_dynamic_threshold")]
@@ -164,6 +568,86 @@ pub struct DetectionConfig {
     /// Retrocompatibil: daca lipseste din config.toml, detectia e dezactivata.
     #[serde(default = "default_beaconing")]
     pub beaconing: BeaconingConfig,
+
+    /// Alerta de saturatie a tabelei de tracking (LRU eviction sub presiune).
+    /// Retrocompatibil: daca lipseste din config.toml, alerta e dezactivata.
+    #[serde(default = "default_saturation_alert")]
+    pub saturation_alert: SaturationAlertConfig,
+
+    /// Alerta operationala "senzor fara trafic": niciun pachet primit timp de
+    /// atatea secunde (mirror / tap / forwarding syslog cazut). Proprietate a
+    /// senzorului — verificata doar pe `[detection]`, nu per profil.
+    /// 0 (implicit) = dezactivat; minim 60 cand este activ.
+    #[serde(default)]
+    pub no_traffic_alert_secs: u64,
+
+    /// Evenimentul "scanare incheiata": o sursa care a depasit un prag de
+    /// scanare si apoi tace atatea secunde primeste o alerta Scan Ended cu
+    /// totalurile si durata episodului. 0 (implicit) = dezactivat; minim 10
+    /// cand este activ (verificarea ruleaza la 5 secunde).
+    #[serde(default)]
+    pub scan_end_silence_secs: u64,
+
+    /// Director cu reguli de detectie suplimentare, cate una per fisier `.toml`
+    /// (vezi `rules.rs`). Verificat periodic — fisierele noi / modificate sunt
+    /// aplicate fara SIGHUP. Evaluat doar pe `[detection]`, nu per profil.
+    /// Gol (implicit) = dezactivat.
+    #[serde(default)]
+    pub rules_dir: String,
+
+    /// Avertizare provizorie la o fractiune din pragul Fast / Slow Scan.
+    /// Retrocompatibil: daca lipseste din config.toml, avertizarea e dezactivata.
+    #[serde(default = "default_early_warning")]
+    pub early_warning: EarlyWarningConfig,
+
+    /// Semnaturi payload ale scanerelor autorizate (allowlist pe continut).
+    /// Retrocompatibil: daca lipseste din config.toml, lista e goala (inactiv).
+    #[serde(default = "default_trusted_signatures")]
+    pub trusted_signatures: TrustedSignaturesConfig,
+
+    /// Ponderare sonde UDP fara payload in Fast/Slow Scan.
+    /// Retrocompatibil: daca lipseste din config.toml, ponderarea e dezactivata.
+    #[serde(default = "default_empty_udp_probes")]
+    pub empty_udp_probes: EmptyUdpProbesConfig,
+
+    /// Epuizarea porturilor sursa (connect scan agresiv) ca amplificator
+    /// de incredere pentru Fast Scan si Accept Scan.
+    /// Retrocompatibil: daca lipseste din config.toml, detectia e dezactivata.
+    #[serde(default = "default_source_port_exhaustion")]
+    pub source_port_exhaustion: SourcePortExhaustionConfig,
+
+    /// Ponderi per port la pragurile Fast/Slow/Accept Scan: port → pondere.
+    /// Un port absent conteaza 1.0. Cheile sunt string-uri (cheile TOML nu
+    /// pot fi numere), validate ca porturi in `validate()`.
+    ///
+    /// NOTA RUST: `BTreeMap` (nu `HashMap`) — serializarea pentru
+    /// `policy_version` trebuie sa aiba ordine determinista a cheilor.
+    #[serde(default)]
+    pub port_weights: BTreeMap<String, f64>,
+}
+
+impl DetectionConfig {
+    /// Versiunea politicii de detectie: hash scurt (8 hex) al sectiunii
+    /// `[detection]` EFECTIVE — dupa aplicarea valorilor implicite serde.
+    ///
+    /// Inclus in fiecare alerta (`policy_version`): dupa un reload SIGHUP
+    /// hash-ul se schimba, iar analistii pot corela o schimbare de
+    /// comportament cu o schimbare de politica.
+    ///
+    /// NOTA RUST: serializam structura in JSON (ordinea campurilor = ordinea
+    /// declararii, deci determinista) si aplicam FNV-1a pe 64 biti. Nu folosim
+    /// `DefaultHasher`: algoritmul lui nu este garantat stabil intre versiuni
+    /// Rust, iar senzorii din flota trebuie sa produca acelasi hash.
+    pub fn policy_version(&self) -> String {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let serialized = serde_json::to_string(self).unwrap_or_default();
+        let hash = serialized.bytes().fold(FNV_OFFSET, |h, b| {
+            (h ^ b as u64).wrapping_mul(FNV_PRIME)
+        });
+        format!("{:016x}", hash)[..8].to_string()
+    }
 }
 
 fn default_max_hits_per_ip() -> usize {
@@ -174,6 +658,35 @@ fn default_max_tracked_ips() -> usize {
     100_000
 }
 
+fn default_confirm_windows() -> u32 {
+    1
+}
+
+fn default_confirm_secs() -> u64 {
+    600
+}
+
+/// Pragul minim pentru `detection.no_traffic_alert_secs` (cand este activ).
+const MIN_NO_TRAFFIC_ALERT_SECS: u64 = 60;
+
+/// Pragul minim pentru `detection.scan_end_silence_secs` (cand este activ):
+/// de doua ori intervalul verificarii din bucla principala.
+const MIN_SCAN_END_SILENCE_SECS: u64 = 10;
+
+/// Valorile acceptate pentru `detection.rule_precedence`.
+pub const RULE_PRECEDENCES: [&str; 3] = ["none", "fast", "slow"];
+
+fn default_rule_precedence() -> String {
+    "none".to_string()
+}
+
+/// Valorile acceptate pentru `detection.per_protocol_tracking`.
+pub const PROTOCOL_TRACKING_MODES: [&str; 2] = ["combined", "separate"];
+
+fn default_per_protocol_tracking() -> String {
+    "combined".to_string()
+}
+
 /// Exceptii detectie — reducerea fals-pozitivelor pentru scenarii specifice.
 ///
 /// Diferenta fata de whitelist:
@@ -184,7 +697,7 @@ fn default_max_tracked_ips() -> usize {
 ///   - Scannere autorizate (Nessus, OpenVAS) → nu declanseaza Lateral Movement
 ///   - Porturi interne cu fan-out (SMB 445, WinRM 5985) → nu conteaza la Lateral
 ///   - Servere populare (DNS 53, NTP 123) → nu declanseaza Distributed Scan
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct DetectionExceptions {
     /// IP-uri autorizate sa faca scanning. NU declanseaza Lateral Movement.
     #[serde(default)]
@@ -209,7 +722,7 @@ pub struct DetectionExceptions {
     pub authorized_beaconing_sources: Vec<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct FastScanConfig {
     /// Numar de porturi unice peste care se declanseaza alerta.
     pub port_threshold: usize,
@@ -217,11 +730,23 @@ pub struct FastScanConfig {
     pub time_window_secs: u64,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SlowScanConfig {
     pub port_threshold: usize,
     /// Fereastra de timp in minute (convertita in secunde la utilizare).
     pub time_window_mins: u64,
+    /// Fractiunea (0.0 - 1.0) din porturile ferestrei ANTERIOARE (vazute intre
+    /// 1× si 2× fereastra in urma, absente din cea curenta) adaugata la scor.
+    /// Fereastra glisanta uita complet un port dupa `window`: o scanare mai
+    /// lenta ramane sub prag la nesfarsit. 0.0 = dezactivat (implicit).
+    #[serde(default)]
+    pub carry_over: f64,
+    /// O sursa cu alerta Slow Scan activa (in cooldown) care atinge criteriile
+    /// Fast Scan este promovata: alerta Fast Scan marcata `promoted_from_slow`,
+    /// emisa si peste `rule_precedence = "slow"`; Slow Scan nu mai alerteaza
+    /// pentru aceeasi scanare. false (implicit) = regulile raman separate.
+    #[serde(default)]
+    pub promote_to_fast: bool,
 }
 
 /// Configurare detectie Accept Scan (scanare porturi DESCHISE).
@@ -237,7 +762,7 @@ pub struct SlowScanConfig {
 ///
 /// Pragurile implicite sunt mai conservative decat Fast Scan deoarece
 /// traficul accepted este mai "normal" si am vrea sa evitam false positives.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AcceptScanConfig {
     /// Numarul de porturi ACCEPTATE unice care declanseaza alerta.
     pub port_threshold: usize,
@@ -269,7 +794,7 @@ fn default_accept_scan() -> AcceptScanConfig {
 ///
 /// Valori implicite: 5 destinatii in 60 secunde, dezactivat implicit
 /// pentru retrocompatibilitate (config-uri vechi nu au sectiunea).
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct LateralMovementConfig {
     /// Activare/dezactivare detectie. Implicit: false (retrocompatibil).
     #[serde(default)]
@@ -312,7 +837,7 @@ fn default_lateral_movement() -> LateralMovementConfig {
 ///
 /// Valori implicite: 5 surse unice in 60 secunde, dezactivat implicit
 /// pentru retrocompatibilitate (config-uri vechi nu au sectiunea).
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct DistributedScanConfig {
     /// Activare/dezactivare detectie. Implicit: false (retrocompatibil).
     #[serde(default)]
@@ -342,6 +867,217 @@ fn default_distributed_scan() -> DistributedScanConfig {
     }
 }
 
+/// Configurare detectie Coordinated Subnet Scan — surse din acelasi prefix.
+///
+/// Mai multe IP-uri din acelasi /24, fiecare sub pragul Fast / Slow Scan, pot
+/// fi un singur actor care isi imparte scanarea intre adrese. Drop-urile sunt
+/// agregate dupa prefixul sursei (`prefix_len_v4` / `prefix_len_v6`); alerta
+/// pleaca cand prefixul are cel putin `min_sources` surse distincte care, IMPREUNA,
+/// au vizat cel putin `port_threshold` porturi unice in `time_window_secs`.
+///
+/// Perspectiva complementara lui Distributed Scan: acolo cheia este tinta
+/// (N surse oarecare → 1 tinta), aici blocul sursa (1 retea → orice tinte).
+///
+/// Valori implicite: dezactivat, /24 si /64, 3 surse, 20 porturi in 300 secunde.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CoordinatedSubnetConfig {
+    /// Activare/dezactivare detectie. Implicit: false (opt-in).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Lungimea prefixului IPv4 dupa care se grupeaza sursele (8-31).
+    #[serde(default = "default_coordinated_prefix_v4")]
+    pub prefix_len_v4: u8,
+
+    /// Lungimea prefixului IPv6 dupa care se grupeaza sursele (16-127).
+    #[serde(default = "default_coordinated_prefix_v6")]
+    pub prefix_len_v6: u8,
+
+    /// Numarul minim de surse distincte din prefix (>= 2 — o singura sursa
+    /// este deja acoperita de Fast / Slow Scan).
+    #[serde(default = "default_coordinated_min_sources")]
+    pub min_sources: usize,
+
+    /// Porturi unice vizate de intregul prefix care declanseaza alerta.
+    #[serde(default = "default_coordinated_port_threshold")]
+    pub port_threshold: usize,
+
+    /// Fereastra de timp (secunde) in care se agrega drop-urile prefixului.
+    #[serde(default = "default_coordinated_time_window")]
+    pub time_window_secs: u64,
+}
+
+/// Cerinte pentru regulile orizontale: Lateral Movement (1 sursa → N tinte)
+/// si Coordinated Subnet Scan (1 bloc → orice tinte).
+///
+/// Un client legitim care deschide multe conexiuni catre UN server (sau mai
+/// multi clienti din acelasi bloc NAT catre acelasi server) este o scanare
+/// verticala, nu un sweep. `min_distinct_targets` cere un numar minim de IP-uri
+/// destinatie distincte in fereastra regulii inainte de alerta.
+///
+/// Distributed Scan nu este afectat: prin definitie are O singura tinta.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SweepConfig {
+    /// IP-uri destinatie distincte necesare. 0 sau 1 = fara cerinta (implicit).
+    /// Pentru Lateral Movement actioneaza ca prag minim peste
+    /// `unique_dest_threshold`.
+    #[serde(default)]
+    pub min_distinct_targets: usize,
+}
+
+/// Configurare detectie Flood — rata de pachete a unei surse, oricate porturi.
+///
+/// Regulile pe porturi (Fast / Slow / Accept Scan) numara porturi DISTINCTE:
+/// o sursa care trimite zeci de mii de pachete catre 1-2 porturi nu le
+/// declanseaza niciodata, desi este un indicator DoS clasic. Regula numara
+/// TOATE pachetele sursei (drop si accept) intr-o fereastra fixa de
+/// `time_window_secs`; alerta pleaca cand rata medie din fereastra atinge
+/// `packets_per_sec_threshold` (adica la `prag × fereastra` pachete).
+///
+/// Valori implicite: dezactivat, 1000 pachete/s mediate pe 10 secunde.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FloodConfig {
+    /// Activare/dezactivare detectie. Implicit: false (opt-in).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Rata medie (pachete/secunda) a unei surse care declanseaza alerta.
+    #[serde(default = "default_flood_pps")]
+    pub packets_per_sec_threshold: u64,
+
+    /// Fereastra (secunde) peste care se mediaza rata. O fereastra mai lunga
+    /// ignora rafalele scurte; una de 1s alerteaza la primul varf.
+    #[serde(default = "default_flood_time_window")]
+    pub time_window_secs: u64,
+}
+
+fn default_flood_pps() -> u64 {
+    1000
+}
+fn default_flood_time_window() -> u64 {
+    10
+}
+
+fn default_flood() -> FloodConfig {
+    FloodConfig {
+        enabled: false,
+        packets_per_sec_threshold: default_flood_pps(),
+        time_window_secs: default_flood_time_window(),
+    }
+}
+
+/// Configurare detectie Slow Sweep — un singur serviciu sondat host cu host.
+///
+/// Un atacator care cauta un serviciu anume (ex: SMB/445) in toata reteaua,
+/// cate un host la cateva minute, scapa tuturor celorlalte reguli:
+///   - Fast / Slow Scan numara porturi DISTINCTE — aici este unul singur
+///   - Lateral Movement are fereastra scurta si numara doar accept-uri
+///
+/// Regula urmareste per (sursa, port destinatie) destinatiile distincte intr-o
+/// fereastra LUNGA si alerteaza cand acelasi port a fost sondat pe cel putin
+/// `unique_dest_threshold` hosturi, indiferent de viteza. Numara drop si accept.
+/// `exceptions.authorized_scanners` nu declanseaza regula.
+///
+/// Valori implicite: dezactivat, 20 de hosturi in 1 ora.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SlowSweepConfig {
+    /// Activare/dezactivare detectie. Implicit: false (opt-in).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Numarul de destinatii distincte pe acelasi port care declanseaza alerta.
+    #[serde(default = "default_slow_sweep_dest_threshold")]
+    pub unique_dest_threshold: usize,
+
+    /// Fereastra de timp in secunde in care se numara destinatiile.
+    #[serde(default = "default_slow_sweep_time_window")]
+    pub time_window_secs: u64,
+}
+
+fn default_slow_sweep_dest_threshold() -> usize {
+    20
+}
+fn default_slow_sweep_time_window() -> u64 {
+    3600
+}
+
+fn default_slow_sweep() -> SlowSweepConfig {
+    SlowSweepConfig {
+        enabled: false,
+        unique_dest_threshold: default_slow_sweep_dest_threshold(),
+        time_window_secs: default_slow_sweep_time_window(),
+    }
+}
+
+/// Configurare alerta Malformed Input — expeditor care trimite multe
+/// datagrame neparsabile catre portul de receptie.
+///
+/// O datagrama este malformata cand nu contine nicio linie acceptata de parser:
+/// goala (0 octeti sau doar spatii), binara sau text in alt format. Fiecare
+/// este numarata ca eroare de parsare indiferent de optiune; cu optiunea
+/// activa, `threshold` datagrame malformate de la acelasi expeditor UDP in
+/// fereastra declanseaza o alerta — cineva sondeaza sau face fuzzing pe
+/// senzor. Un forwarder legitim care trimite si alte tipuri de log-uri
+/// depaseste pragul la fel de usor: se creste pragul sau se filtreaza la sursa.
+///
+/// Valori implicite: dezactivat, 100 de datagrame in 60 secunde.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MalformedInputConfig {
+    /// Activare/dezactivare alerta. Implicit: false (opt-in).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Datagrame malformate de la acelasi expeditor care declanseaza alerta.
+    #[serde(default = "default_malformed_input_threshold")]
+    pub threshold: usize,
+
+    /// Fereastra de timp in secunde in care se numara datagramele.
+    #[serde(default = "default_malformed_input_time_window")]
+    pub time_window_secs: u64,
+}
+
+fn default_malformed_input_threshold() -> usize {
+    100
+}
+fn default_malformed_input_time_window() -> u64 {
+    60
+}
+
+fn default_malformed_input() -> MalformedInputConfig {
+    MalformedInputConfig {
+        enabled: false,
+        threshold: default_malformed_input_threshold(),
+        time_window_secs: default_malformed_input_time_window(),
+    }
+}
+
+fn default_coordinated_prefix_v4() -> u8 {
+    24
+}
+fn default_coordinated_prefix_v6() -> u8 {
+    64
+}
+fn default_coordinated_min_sources() -> usize {
+    3
+}
+fn default_coordinated_port_threshold() -> usize {
+    20
+}
+fn default_coordinated_time_window() -> u64 {
+    300
+}
+
+fn default_coordinated_subnet() -> CoordinatedSubnetConfig {
+    CoordinatedSubnetConfig {
+        enabled: false,
+        prefix_len_v4: default_coordinated_prefix_v4(),
+        prefix_len_v6: default_coordinated_prefix_v6(),
+        min_sources: default_coordinated_min_sources(),
+        port_threshold: default_coordinated_port_threshold(),
+        time_window_secs: default_coordinated_time_window(),
+    }
+}
+
 /// Configurare detectie Beaconing C2 (#24) — trafic periodic catre un host C2.
 ///
 /// Algoritm: Coefficient of Variation (CV) pe intervalele intre conexiuni
@@ -349,7 +1085,7 @@ fn default_distributed_scan() -> DistributedScanConfig {
 /// CV apropiat de 0 (intervale aproape identice); traficul uman/bursty are CV mare.
 ///
 /// Valori implicite: dezactivat (retrocompatibil cu config-uri vechi).
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BeaconingConfig {
     /// Activare/dezactivare detectie. Implicit: false.
     #[serde(default)]
@@ -376,6 +1112,19 @@ pub struct BeaconingConfig {
     /// Interval maxim mediu acceptat (secunde). Mai mare = trafic prea rar pentru a fi util.
     #[serde(default = "default_beaconing_max_interval")]
     pub max_interval_secs: u64,
+
+    /// Toleranta la jitter (procent din intervalul median). 0 = dezactivat
+    /// (doar testul CV). Cu valoare > 0, un interval este "consistent" daca
+    /// se abate de la mediana cu cel mult `jitter_percent`%, iar testul CV
+    /// este inlocuit de `min_consistent_intervals` — un beacon ratat (interval
+    /// dublu) nu mai strica decizia, cum se intampla cu CV-ul.
+    #[serde(default)]
+    pub jitter_percent: f64,
+
+    /// Numarul minim de intervale consistente (in toleranta de jitter) inainte
+    /// de alerta. Folosit doar cand `jitter_percent > 0`.
+    #[serde(default = "default_beaconing_min_consistent_intervals")]
+    pub min_consistent_intervals: usize,
 }
 
 fn default_beaconing_min_events() -> usize {
@@ -394,6 +1143,10 @@ fn default_beaconing_max_interval() -> u64 {
     3600
 }
 
+fn default_beaconing_min_consistent_intervals() -> usize {
+    6
+}
+
 fn default_beaconing() -> BeaconingConfig {
     BeaconingConfig {
         enabled: false,
@@ -402,32 +1155,256 @@ fn default_beaconing() -> BeaconingConfig {
         cv_threshold: default_beaconing_cv_threshold(),
         min_interval_secs: default_beaconing_min_interval(),
         max_interval_secs: default_beaconing_max_interval(),
+        jitter_percent: 0.0,
+        min_consistent_intervals: default_beaconing_min_consistent_intervals(),
     }
 }
 
-/// Configurare praguri dinamice / adaptive (#35).
+/// Configurare alerta de saturatie — evictii LRU sub presiune.
 ///
-/// Foloseste EWMA (Exponentially Weighted Moving Average) pentru a calcula
-/// un baseline al traficului normal si adapta automat pragurile de detectie.
-///
-/// Formula: prag_efectiv = max(static * min_ratio, min(static * max_ratio,
-///          baseline_mean + sensitivity_multiplier * baseline_stddev))
+/// Cand `last_seen` atinge `max_tracked_ips`, fiecare IP nou forteaza evictia
+/// celui mai vechi IP urmarit. O rata mare de evictii inseamna fie un flood de
+/// IP-uri spoofed, fie o retea mult mai mare decat limita configurata — in
+/// ambele cazuri detectia este degradata (scanerii lenti sunt evictati inainte
+/// sa atinga pragul). Alerta transforma acest failure mode intern intr-un
+/// semnal de securitate actionabil.
 ///
-/// Pragurile statice din config servesc ca limite de siguranta (floor/ceiling).
-/// Se aplica doar la Fast Scan, Slow Scan si Accept Scan.
-/// Lateral Movement si Distributed Scan raman cu praguri statice.
-#[derive(Debug, Clone, Deserialize)]
-pub struct DynamicThresholdConfig {
-    /// Activare/dezactivare praguri dinamice. Implicit: false (retrocompatibil).
+/// Valori implicite: dezactivat, 1000 evictii in 60 secunde.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SaturationAlertConfig {
+    /// Activare/dezactivare alerta. Implicit: false (retrocompatibil).
     #[serde(default)]
     pub enabled: bool,
 
-    /// Factor de smoothing EWMA (0.0 - 1.0). Mai mare = adaptare mai rapida.
-    /// 0.1 = conservator (baseline stabil), 0.3 = moderat, 0.5 = rapid.
-    #[serde(default = "default_ewma_alpha")]
-    pub ewma_alpha: f64,
+    /// Numarul de evictii in fereastra de timp care declanseaza alerta.
+    #[serde(default = "default_saturation_evictions_threshold")]
+    pub evictions_threshold: usize,
 
-    /// Cate deviatii standard peste medie seteaza pragul.
+    /// Fereastra de timp (secunde) in care se numara evictiile.
+    #[serde(default = "default_saturation_time_window")]
+    pub time_window_secs: u64,
+}
+
+fn default_saturation_evictions_threshold() -> usize {
+    1000
+}
+fn default_saturation_time_window() -> u64 {
+    60
+}
+
+fn default_saturation_alert() -> SaturationAlertConfig {
+    SaturationAlertConfig {
+        enabled: false,
+        evictions_threshold: default_saturation_evictions_threshold(),
+        time_window_secs: default_saturation_time_window(),
+    }
+}
+
+/// Avertizare provizorie (partiala) pentru scanarile in curs.
+///
+/// O scanare lenta catre un activ sensibil poate dura ore pana la prag. Cand
+/// scorul Fast / Slow Scan al unei surse atinge `threshold_fraction` din prag
+/// (fara sa-l depaseasca), se afiseaza un avertisment PROVIZORIU la nivel
+/// `log_warning` — nu o alerta: nu ajunge la SIEM / email / GELF / Sentinel,
+/// dashboard, corelare sau rollup. Alerta completa urmeaza normal la prag.
+///
+/// Valori implicite: dezactivat, 50% din prag, maxim un avertisment per IP
+/// sursa la 300 secunde, pentru orice tinta.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EarlyWarningConfig {
+    /// Activare/dezactivare avertizare. Implicit: false (retrocompatibil).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Fractiunea din prag la care se emite avertismentul, in (0, 1).
+    #[serde(default = "default_early_warning_fraction")]
+    pub threshold_fraction: f64,
+
+    /// Intervalul minim (secunde) intre doua avertismente pentru acelasi IP sursa.
+    #[serde(default = "default_early_warning_cooldown")]
+    pub cooldown_secs: u64,
+
+    /// Doar pentru tinte clasificate critical / high (`[assets]` sau harta
+    /// CMDB din `enrichment.asset_map_path`). Implicit: false (orice tinta).
+    #[serde(default)]
+    pub assets_only: bool,
+}
+
+fn default_early_warning_fraction() -> f64 {
+    0.5
+}
+fn default_early_warning_cooldown() -> u64 {
+    300
+}
+
+fn default_early_warning() -> EarlyWarningConfig {
+    EarlyWarningConfig {
+        enabled: false,
+        threshold_fraction: default_early_warning_fraction(),
+        cooldown_secs: default_early_warning_cooldown(),
+        assets_only: false,
+    }
+}
+
+/// Semnaturi de scanere autorizate — allowlist bazat pe continutul log-ului.
+///
+/// Complement la whitelist-ul pe IP pentru surse cu IP-uri variabile
+/// (vendor de pentest, servicii de scanare externe) care se anunta printr-un
+/// marker in payload (User-Agent, header custom, string in probe). Cand log-ul
+/// brut contine un pattern, sursa este tratata ca whitelisted timp de
+/// `suppress_secs` — pachetele ulterioare fara marker nu genereaza alerte.
+///
+/// Potrivirea este substring exact (case-sensitive) pe `raw_log` — parserele
+/// pastreaza linia originala completa, inclusiv campurile de payload/URL.
+///
+/// Valori implicite: fara pattern-uri (inactiv), suprimare 3600 secunde.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TrustedSignaturesConfig {
+    /// Pattern-uri cautate in log-ul brut. Lista goala = functionalitate inactiva.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+
+    /// Cat timp (secunde) ramane sursa suprimata dupa ultimul match.
+    #[serde(default = "default_trusted_suppress_secs")]
+    pub suppress_secs: u64,
+}
+
+fn default_trusted_suppress_secs() -> u64 {
+    3600
+}
+
+fn default_trusted_signatures() -> TrustedSignaturesConfig {
+    TrustedSignaturesConfig {
+        patterns: Vec::new(),
+        suppress_secs: default_trusted_suppress_secs(),
+    }
+}
+
+/// Sonde UDP goale — datagrame fara payload trimise pe porturi diferite.
+///
+/// Tehnica clasica de scanare UDP: scannerul trimite o datagrama goala doar
+/// ca sa vada daca portul raspunde (ICMP port unreachable = inchis). Traficul
+/// UDP legitim (DNS, NTP, syslog, SNMP) transporta aproape mereu payload.
+///
+/// Firewall-ul raporteaza octetii pachetului CU antete (`packet_bytes`), nu
+/// lungimea payload-ului: o datagrama goala are exact antetele IP + UDP (28
+/// octeti IPv4, 48 IPv6 — `LogEvent::is_empty_udp_datagram`). Fiecare port
+/// unic vizat astfel conteaza de `weight` ori la pragurile Fast/Slow Scan, iar
+/// alerta rezultata este marcata `empty_probes: true`. Log-urile fara octeti
+/// (`packet_bytes` absent sau 0 — nenumarati) sunt numarate normal (pondere 1).
+///
+/// Valori implicite: dezactivat, pondere 2.0.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EmptyUdpProbesConfig {
+    /// Activare/dezactivare ponderare. Implicit: false (retrocompatibil).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// De cate ori conteaza un port vizat de o sonda UDP goala (>= 1.0).
+    /// 1.0 = doar marcare alerta, fara efect asupra pragurilor.
+    #[serde(default = "default_empty_udp_probe_weight")]
+    pub weight: f64,
+}
+
+fn default_empty_udp_probe_weight() -> f64 {
+    2.0
+}
+
+fn default_empty_udp_probes() -> EmptyUdpProbesConfig {
+    EmptyUdpProbesConfig {
+        enabled: false,
+        weight: default_empty_udp_probe_weight(),
+    }
+}
+
+/// Epuizarea porturilor sursa — semnatura unui connect scan agresiv.
+///
+/// Un connect scan complet deschide o conexiune noua pentru fiecare sonda,
+/// iar kernel-ul ii aloca de fiecare data urmatorul port efemer: sursa
+/// parcurge rapid un interval mare si aproape contiguu de porturi sursa.
+/// Semnalul este ortogonal numararii porturilor destinatie — un scanner care
+/// loveste doar cateva porturi pe multe conexiuni il produce la fel.
+///
+/// Cand parserul raporteaza portul sursa (Gaia / LEA `s_port`, CEF `spt`)
+/// si sursa a folosit cel putin `min_source_ports` porturi distincte in
+/// `time_window_secs`, cu densitatea `distincte / (max - min + 1)` de cel
+/// putin `min_density`, pragurile Fast Scan si Accept Scan ale sursei sunt
+/// inmultite cu `threshold_factor`, iar alerta este marcata
+/// `source_port_exhaustion`. Log-urile fara port sursa nu contribuie.
+///
+/// Valori implicite: dezactivat, 100 porturi, densitate 0.4 (kernel-urile
+/// Linux recente aloca din 2 in 2), in 10 secunde, factor 0.5.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SourcePortExhaustionConfig {
+    /// Activare/dezactivare detectie. Implicit: false (opt-in).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Porturi sursa distincte necesare in fereastra.
+    #[serde(default = "default_exhaustion_min_source_ports")]
+    pub min_source_ports: usize,
+
+    /// Fractiunea (0.0 - 1.0] din intervalul [min, max] acoperita de porturile
+    /// vazute. 1.0 = strict contiguu; porturile aleatoare dau valori mici.
+    #[serde(default = "default_exhaustion_min_density")]
+    pub min_density: f64,
+
+    /// Fereastra de timp in secunde in care se numara porturile sursa.
+    #[serde(default = "default_exhaustion_time_window")]
+    pub time_window_secs: u64,
+
+    /// Multiplicatorul (0.0 - 1.0] aplicat pragurilor Fast / Accept Scan cat
+    /// timp tiparul este prezent. 1.0 = doar marcare alerta.
+    #[serde(default = "default_exhaustion_threshold_factor")]
+    pub threshold_factor: f64,
+}
+
+fn default_exhaustion_min_source_ports() -> usize {
+    100
+}
+fn default_exhaustion_min_density() -> f64 {
+    0.4
+}
+fn default_exhaustion_time_window() -> u64 {
+    10
+}
+fn default_exhaustion_threshold_factor() -> f64 {
+    0.5
+}
+
+fn default_source_port_exhaustion() -> SourcePortExhaustionConfig {
+    SourcePortExhaustionConfig {
+        enabled: false,
+        min_source_ports: default_exhaustion_min_source_ports(),
+        min_density: default_exhaustion_min_density(),
+        time_window_secs: default_exhaustion_time_window(),
+        threshold_factor: default_exhaustion_threshold_factor(),
+    }
+}
+
+/// Configurare praguri dinamice / adaptive (#35).
+///
+/// Foloseste EWMA (Exponentially Weighted Moving Average) pentru a calcula
+/// un baseline al traficului normal si adapta automat pragurile de detectie.
+///
+/// Formula: prag_efectiv = max(static * min_ratio, min(static * max_ratio,
+///          baseline_mean + sensitivity_multiplier * baseline_stddev))
+///
+/// Pragurile statice din config servesc ca limite de siguranta (floor/ceiling).
+/// Se aplica doar la Fast Scan, Slow Scan si Accept Scan.
+/// Lateral Movement si Distributed Scan raman cu praguri statice.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DynamicThresholdConfig {
+    /// Activare/dezactivare praguri dinamice. Implicit: false (retrocompatibil).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Factor de smoothing EWMA (0.0 - 1.0). Mai mare = adaptare mai rapida.
+    /// 0.1 = conservator (baseline stabil), 0.3 = moderat, 0.5 = rapid.
+    #[serde(default = "default_ewma_alpha")]
+    pub ewma_alpha: f64,
+
+    /// Cate deviatii standard peste medie seteaza pragul.
     /// 3.0 = conservator (doar outlier-i clari), 2.0 = mai sensibil.
     #[serde(default = "default_sensitivity_multiplier")]
     pub sensitivity_multiplier: f64,
@@ -479,102 +1456,1326 @@ fn default_dynamic_threshold() -> DynamicThresholdConfig {
 pub struct AlertingConfig {
     pub siem: SiemConfig,
     pub email: EmailConfig,
-}
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct SiemConfig {
-    pub enabled: bool,
-    pub host: String,
-    pub port: u16,
-}
+    /// Rutare alerte pe familia de adrese a sursei (IPv4 vs IPv6).
+    #[serde(default = "default_alert_routing")]
+    pub routing: AlertRoutingConfig,
 
-/// Configurare email.
-///
-/// NOTA RUST: `Vec<String>` permite lista dinamica de destinatari.
-/// Fiecare String este owned (detinut) de Vec, care la randul lui
-/// este owned de EmailConfig. Cand EmailConfig este dropat, tot
-/// lantul de ownership este dealocat automat - zero memory leaks.
-#[derive(Debug, Clone, Deserialize)]
-pub struct EmailConfig {
-    pub enabled: bool,
-    pub smtp_server: String,
-    pub smtp_port: u16,
-    pub smtp_tls: bool,
-    pub from: String,
-    pub to: Vec<String>,
-    pub username: String,
-    pub password: String,
+    /// Destinatie GELF (Graylog) peste UDP. Retrocompatibil: daca lipseste
+    /// din config.toml, destinatia este dezactivata.
+    #[serde(default = "default_gelf")]
+    pub gelf: GelfConfig,
 
-    /// Footer personalizabil pentru email-urile de alerta.
-    /// Poate contine banner ASCII al echipei, disclaimer, etc.
-    /// Afisat intre separatoarele ========== din footer-ul email-ului.
-    #[serde(default = "default_email_footer")]
-    pub email_footer: String,
-}
+    /// Destinatie Microsoft Sentinel (Log Analytics HTTP Data Collector API).
+    /// Retrocompatibil: daca lipseste din config.toml, destinatia este dezactivata.
+    #[serde(default = "default_sentinel")]
+    pub sentinel: SentinelConfig,
 
-fn default_email_footer() -> String {
-    "\
-   ____  ____  ____  ____       _       ____\n\
-  / ___|| ___|| __ )|___ \\     / \\     |  _ \\\n\
-  \\___ \\|___ \\|  _ \\  __) |   / _ \\    | | | |\n\
-   ___) |___) | |_) |/ __/   / ___ \\ _ | |_| |\n\
-  |____/|____/|____/|_____| /_/   \\_(_)|____/\n\
-\n\
-  Generat automat de S5B2 A.D. | Nu raspundeti la acest email"
-        .to_string()
+    /// Clasificare zgomot de fond vs atac tintit, cu rutare separata.
+    /// Retrocompatibil: daca lipseste din config.toml, alertele nu sunt clasificate.
+    #[serde(default)]
+    pub classification: ClassificationConfig,
+
+    /// Fereastra de corelare (secunde): alertele Fast / Slow / Accept Scan cu
+    /// aceeasi pereche (IP sursa, tip) sosite in acest interval sunt contopite
+    /// intr-una singura (reuniunea porturilor), emisa la expirarea ferestrei.
+    /// Adauga pana la `correlation_secs` latenta. 0 = dezactivat (implicit).
+    #[serde(default)]
+    pub correlation_secs: u64,
+
+    /// Sablonul campului `summary` (rezumat de o linie) al fiecarei alerte,
+    /// cu substitutie de campuri `{nume}` (vezi `SUMMARY_FIELDS`). Randat o
+    /// singura data, la construirea alertei — consola GELF, email, JSON-ul
+    /// `/api/alerts` si FIFO-ul folosesc acelasi text.
+    #[serde(default = "default_summary_template")]
+    pub summary_template: String,
+
+    /// Ataseaza fiecarei alerte pe prag obiectul `detection_context`: valoarea
+    /// observata, pragul efectiv, fereastra, scorul ponderat si timpul pana la
+    /// prag. Retrocompatibil: implicit false — alertele raman ca inainte.
+    #[serde(default)]
+    pub detection_context: bool,
+
+    /// Fiecare alerta primeste o cheie de idempotenta stabila (`idempotency_key`,
+    /// derivata din momentul si id-ul alertei), aceeasi la orice re-trimitere:
+    /// CEF `externalId`, ECS `event.id`, GELF `_idempotency_key`, JSON, antetul
+    /// HTTP `Idempotency-Key` (Sentinel). Implicit false — payload-uri neschimbate.
+    #[serde(default)]
+    pub idempotency_key: bool,
+
+    /// Destinatii suplimentare, in ordine: `[[alerting.sinks]]` cu `type`,
+    /// `name` si setarile tipului (aceleasi chei ca sectiunea lui). Permite mai
+    /// multe instante de acelasi tip (doua SIEM-uri, trei GELF). Sectiunile
+    /// `[alerting.siem]`, `[alerting.email]` ... raman valide si sunt primele
+    /// instante (vezi `sink_entries`). Gol (implicit) = doar sectiunile.
+    #[serde(default)]
+    pub sinks: Vec<SinkEntry>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct CleanupConfig {
-    pub interval_secs: u64,
-    pub max_entry_age_secs: u64,
+impl AlertingConfig {
+    /// Toate destinatiile active, in ordinea livrarii: sectiunile legacy
+    /// activate (numite dupa tip: "siem", "email", "gelf", "sentinel"),
+    /// apoi intrarile active din `alerting.sinks`, cu numele implicit completat.
+    ///
+    /// Shim de compatibilitate: o configuratie fara `alerting.sinks` produce
+    /// exact destinatiile de dinainte.
+    pub fn sink_entries(&self) -> Vec<SinkEntry> {
+        let legacy = [
+            (self.siem.enabled, SinkKind::Siem(self.siem.clone())),
+            (self.email.enabled, SinkKind::Email(self.email.clone())),
+            (self.gelf.enabled, SinkKind::Gelf(self.gelf.clone())),
+            (self.sentinel.enabled, SinkKind::Sentinel(self.sentinel.clone())),
+        ];
+        let mut entries: Vec<SinkEntry> = legacy
+            .into_iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, kind)| SinkEntry {
+                name: kind.type_name().to_string(),
+                enabled: true,
+                omit_fields: Vec::new(),
+                kind,
+            })
+            .collect();
+        entries.extend(self.sinks.iter().enumerate().filter(|(_, e)| e.enabled).map(|(i, e)| e.resolved(i)));
+        entries
+    }
+
+    /// Numele acceptate in listele de destinatii (`routing`, `classification`,
+    /// `profiles.<nume>.sinks`): tipurile (`ALERT_SINKS` — toate instantele
+    /// tipului) si numele intrarilor din `alerting.sinks`.
+    pub fn sink_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = ALERT_SINKS.iter().map(|s| s.to_string()).collect();
+        names.extend(self.sinks.iter().enumerate().map(|(i, e)| e.resolved(i).name));
+        names
+    }
 }
 
-/// Configurare Web Dashboard (#25) — server HTTP embedded, read-only.
+/// Tipurile de destinatii (sink-uri) de alertare. Un tip in `routing`,
+/// `classification` sau `profiles.<nume>.sinks` inseamna toate instantele lui.
+pub const ALERT_SINKS: [&str; 4] = ["siem", "email", "gelf", "sentinel"];
+
+/// Campurile alertei care pot fi omise per destinatie (`omit_fields`).
+pub const SINK_OMITTABLE_FIELDS: [&str; 10] = [
+    "summary",
+    "sensor",
+    "asset",
+    "classification",
+    "detection_context",
+    "interface",
+    "ingress",
+    "profile",
+    "tor_exit",
+    "scan_episode",
+];
+
+/// O destinatie din `alerting.sinks` (`[[alerting.sinks]]`).
 ///
-/// Serveste o pagina HTML cu graf D3.js force-directed al retelei si
-/// endpoint-uri JSON pentru alerte si graf. Task tokio separat, fara
-/// impact asupra detectiei.
+/// ```toml
+/// [[alerting.sinks]]
+/// type = "siem"
+/// name = "siem-backup"
+/// host = "10.0.0.9"
+/// port = 514
+/// format = "ecs"
+/// min_severity = 7
+/// omit_fields = ["asset", "sensor"]
+/// ```
 ///
-/// Valori implicite: dezactivat, port 8080, bind 127.0.0.1 (doar local),
-/// 1000 alerte in buffer-ul circular.
+/// NOTA RUST: `#[serde(flatten)]` preia cheile ramase (dupa `name`, `enabled`,
+/// `omit_fields`) in `SinkKind`, un enum "internally tagged" — cheia `type`
+/// alege varianta, restul cheilor sunt campurile ei.
 #[derive(Debug, Clone, Deserialize)]
-pub struct WebDashboardConfig {
-    /// Activare/dezactivare dashboard web. Implicit: false (retrocompatibil).
+pub struct SinkEntry {
+    /// Numele instantei, unic. Implicit: "<type>-<pozitia in lista>" (de la 1).
     #[serde(default)]
+    pub name: String,
+
+    /// Implicit: true — o intrare in lista este activa.
+    #[serde(default = "default_sink_enabled")]
     pub enabled: bool,
 
-    /// Portul HTTP pe care asculta dashboard-ul. Implicit: 8080.
-    #[serde(default = "default_web_port")]
-    pub port: u16,
+    /// Campuri ale alertei omise in tot ce primeste aceasta destinatie
+    /// (vezi `SINK_OMITTABLE_FIELDS`). Gol (implicit) = alerta completa.
+    #[serde(default)]
+    pub omit_fields: Vec<String>,
 
-    /// Adresa de bind. "127.0.0.1" = doar local (securitate).
-    /// "0.0.0.0" = toate interfetele (accesibil din retea).
-    #[serde(default = "default_web_bind")]
-    pub bind: String,
+    #[serde(flatten)]
+    pub kind: SinkKind,
+}
 
-    /// Numarul maxim de alerte pastrate in buffer-ul circular. Implicit: 1000.
-    #[serde(default = "default_web_max_alerts")]
-    pub max_alerts: usize,
+/// Tipul unei destinatii si setarile lui (`type = "siem"` ...).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SinkKind {
+    Siem(SiemConfig),
+    Email(EmailConfig),
+    Gelf(GelfConfig),
+    Sentinel(SentinelConfig),
 }
 
-fn default_web_port() -> u16 {
-    8080
+impl SinkKind {
+    /// Numele tipului, din `ALERT_SINKS`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            SinkKind::Siem(_) => "siem",
+            SinkKind::Email(_) => "email",
+            SinkKind::Gelf(_) => "gelf",
+            SinkKind::Sentinel(_) => "sentinel",
+        }
+    }
+
+    /// Severitatea CEF minima a alertelor livrate (`min_severity` al tipului).
+    pub fn min_severity(&self) -> u8 {
+        match self {
+            SinkKind::Siem(c) => c.min_severity,
+            SinkKind::Email(c) => c.min_severity,
+            SinkKind::Gelf(c) => c.min_severity,
+            SinkKind::Sentinel(c) => c.min_severity,
+        }
+    }
+
+    /// Erorile setarilor tipului; `prefix` = "alerting.siem" sau
+    /// "alerting.sinks.<nume>". Verificarile de conectare doar cand este activ.
+    fn validation_errors(&self, prefix: &str, errors: &mut Vec<String>) {
+        if self.min_severity() > 10 {
+            errors.push(format!(
+                "{}.min_severity = {}: severitatea CEF este intre 0 si 10",
+                prefix,
+                self.min_severity()
+            ));
+        }
+        match self {
+            SinkKind::Siem(siem) => {
+                if siem.enabled {
+                    if siem.port == 0 {
+                        errors.push(format!("{}.port = 0 este invalid", prefix));
+                    }
+                    if siem.host.is_empty() {
+                        errors.push(format!("{}.host nu poate fi gol cand SIEM este activat", prefix));
+                    }
+                }
+                if !matches!(siem.format.as_str(), "cef" | "ecs" | "cloudevents") {
+                    errors.push(format!(
+                        "{}.format = {:?} este invalid. Valori acceptate: \"cef\", \"ecs\", \"cloudevents\"",
+                        prefix, siem.format
+                    ));
+                }
+                if !matches!(siem.rfc, 3164 | 5424) {
+                    errors.push(format!(
+                        "{}.rfc = {} este invalid. Valori acceptate: 3164, 5424",
+                        prefix, siem.rfc
+                    ));
+                } else if siem.rfc == 5424 && siem.format != "cef" {
+                    errors.push(format!(
+                        "{}.rfc = 5424 se aplica doar formatului \"cef\" — documentele {:?} nu au antet syslog",
+                        prefix, siem.format
+                    ));
+                }
+            }
+            SinkKind::Gelf(gelf) if gelf.enabled => {
+                if gelf.port == 0 {
+                    errors.push(format!("{}.port = 0 este invalid", prefix));
+                }
+                if gelf.host.is_empty() {
+                    errors.push(format!("{}.host nu poate fi gol cand GELF este activat", prefix));
+                }
+                if !(512..=8192).contains(&gelf.chunk_size) {
+                    errors.push(format!(
+                        "{}.chunk_size = {} este invalid: trebuie in [512, 8192] (limita Graylog)",
+                        prefix, gelf.chunk_size
+                    ));
+                }
+            }
+            SinkKind::Email(email) if email.enabled => {
+                if email.smtp_port == 0 {
+                    errors.push(format!("{}.smtp_port = 0 este invalid", prefix));
+                }
+                if email.smtp_server.is_empty() {
+                    errors.push(format!(
+                        "{}.smtp_server nu poate fi gol cand email este activat",
+                        prefix
+                    ));
+                }
+                if email.from.is_empty() {
+                    errors.push(format!("{}.from nu poate fi gol cand email este activat", prefix));
+                }
+                if email.to.is_empty() {
+                    errors.push(format!(
+                        "{}.to nu poate fi goala: adauga cel putin un destinatar",
+                        prefix
+                    ));
+                }
+            }
+            SinkKind::Sentinel(sentinel) if sentinel.enabled => {
+                if !is_guid(&sentinel.workspace_id) {
+                    errors.push(format!(
+                        "{}.workspace_id = {:?} este invalid: trebuie GUID \
+                         (xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx)",
+                        prefix, sentinel.workspace_id
+                    ));
+                }
+                if let Err(e) = crate::sentinel::decode_shared_key(&sentinel.shared_key) {
+                    errors.push(format!("{}.shared_key: {}", prefix, e));
+                }
+                // Regula Azure: doar litere, cifre si '_', maxim 100 caractere.
+                if sentinel.log_type.is_empty()
+                    || sentinel.log_type.len() > 100
+                    || !sentinel
+                        .log_type
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    errors.push(format!(
+                        "{}.log_type = {:?} este invalid: 1-100 caractere, \
+                         doar litere, cifre si '_'",
+                        prefix, sentinel.log_type
+                    ));
+                }
+                if sentinel.endpoint_domain.is_empty()
+                    || !sentinel
+                        .endpoint_domain
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+                {
+                    errors.push(format!(
+                        "{}.endpoint_domain = {:?} nu este un nume DNS valid",
+                        prefix, sentinel.endpoint_domain
+                    ));
+                }
+                if sentinel.max_retries > 5 {
+                    errors.push(format!(
+                        "{}.max_retries = {} invalid: maxim 5 (reincercarile \
+                         blocheaza livrarea alertelor urmatoare)",
+                        prefix, sentinel.max_retries
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        match self {
+            SinkKind::Siem(c) => c.enabled = enabled,
+            SinkKind::Email(c) => c.enabled = enabled,
+            SinkKind::Gelf(c) => c.enabled = enabled,
+            SinkKind::Sentinel(c) => c.enabled = enabled,
+        }
+    }
 }
-fn default_web_bind() -> String {
-    "127.0.0.1".to_string()
+
+impl SinkEntry {
+    /// Intrarea `index` din `alerting.sinks` cu numele implicit completat si
+    /// `enabled` al tipului aliniat la cel al intrarii (cheia din tip nu conteaza).
+    fn resolved(&self, index: usize) -> SinkEntry {
+        let mut entry = self.clone();
+        if entry.name.is_empty() {
+            entry.name = format!("{}-{}", entry.kind.type_name(), index + 1);
+        }
+        entry.kind.set_enabled(entry.enabled);
+        entry
+    }
 }
-fn default_web_max_alerts() -> usize {
-    1000
+
+fn default_sink_enabled() -> bool {
+    true
 }
 
-fn default_web_dashboard() -> WebDashboardConfig {
-    WebDashboardConfig {
-        enabled: false,
-        port: default_web_port(),
-        bind: default_web_bind(),
-        max_alerts: default_web_max_alerts(),
-    }
+/// Lungimea maxima a numelui unei intrari din `alerting.sinks`.
+const SINK_NAME_MAX_LEN: usize = 64;
+
+/// Campurile acceptate in `alerting.summary_template`.
+pub const SUMMARY_FIELDS: [&str; 10] = [
+    "type",
+    "source_ip",
+    "dest_ip",
+    "count",
+    "unit",
+    "duration",
+    "ports",
+    "detail",
+    "policy",
+    "id",
+];
+
+fn default_summary_template() -> String {
+    "{type} de la {source_ip} — {detail}".to_string()
+}
+
+/// GUID in forma canonica 8-4-4-4-12 (cifre hexa).
+fn is_guid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(g, len)| g.len() == len && g.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Numele campurilor `{nume}` dintr-un sablon, in ordine.
+/// Eroare la o acolada neinchisa sau la un camp gol (`{}`).
+pub fn template_placeholders(template: &str) -> std::result::Result<Vec<&str>, String> {
+    let mut fields = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("acolada '{{' neinchisa in \"{}\"", template))?;
+        let name = &after[..end];
+        if name.is_empty() || name.contains('{') {
+            return Err(format!("camp invalid \"{{{}}}\" in \"{}\"", name, template));
+        }
+        fields.push(name);
+        rest = &after[end + 1..];
+    }
+    Ok(fields)
+}
+
+/// Rutarea alertelor in functie de familia IP a sursei.
+///
+/// Scanarile IPv6 pot fi gestionate de alta echipa: alertele cu sursa IPv6
+/// pot merge catre alt set de destinatii decat cele IPv4. Fiecare lista
+/// contine nume din `ALERT_SINKS`; o destinatie trebuie sa fie si activata
+/// (`enabled = true`) in sectiunea ei ca sa primeasca alerte.
+///
+/// Implicit: ambele familii merg catre toate destinatiile (comportament
+/// identic cu cel fara sectiunea `[alerting.routing]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertRoutingConfig {
+    /// Destinatii pentru alertele cu sursa IPv4. Implicit: toate (`ALERT_SINKS`).
+    #[serde(default = "default_route_sinks")]
+    pub ipv4: Vec<String>,
+
+    /// Destinatii pentru alertele cu sursa IPv6. Implicit: toate (`ALERT_SINKS`).
+    #[serde(default = "default_route_sinks")]
+    pub ipv6: Vec<String>,
+
+    /// Destinatii pentru alertele din noduri de iesire Tor (`tor_exit`),
+    /// aplicate peste familia IP. Gol (implicit) = rutare normala.
+    #[serde(default)]
+    pub tor_exit: Vec<String>,
+}
+
+impl AlertRoutingConfig {
+    /// Verifica daca alertele cu sursa `ip` trebuie trimise catre `sink`.
+    pub fn routes_to(&self, sink: &str, ip: &IpAddr) -> bool {
+        let sinks = match ip {
+            IpAddr::V4(_) => &self.ipv4,
+            IpAddr::V6(_) => &self.ipv6,
+        };
+        sinks.iter().any(|s| s == sink)
+    }
+
+    /// Alertele `tor_exit` au voie catre `sink`? Lista goala = orice destinatie.
+    pub fn routes_tor_exit(&self, sink: &str) -> bool {
+        self.tor_exit.is_empty() || self.tor_exit.iter().any(|s| s == sink)
+    }
+}
+
+fn default_route_sinks() -> Vec<String> {
+    ALERT_SINKS.iter().map(|s| s.to_string()).collect()
+}
+
+fn default_alert_routing() -> AlertRoutingConfig {
+    AlertRoutingConfig {
+        ipv4: default_route_sinks(),
+        ipv6: default_route_sinks(),
+        tor_exit: Vec::new(),
+    }
+}
+
+/// Clasificarea alertelor in `noise` (zgomot de fond al Internetului) si
+/// `targeted` (scanare focalizata pe serviciile noastre) — vezi `classify.rs`.
+///
+/// Fiecare factor adauga sau scade puncte; scorul >= `targeted_min_score`
+/// inseamna `targeted`. Fiecare clasa are propria lista de destinatii,
+/// aplicata peste `[alerting.routing]` si `sinks` al profilului.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClassificationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Porturile pe care activele noastre asculta efectiv (servicii expuse).
+    /// O scanare care nimereste aceste porturi stie ce cauta.
+    #[serde(default)]
+    pub service_ports: Vec<u16>,
+
+    /// Surse cu reputatie de zgomot (scannere de cercetare: Shodan, Censys ...).
+    /// IP-uri sau CIDR-uri.
+    #[serde(default)]
+    pub noise_sources: Vec<String>,
+
+    /// Surse cu reputatie ostila (IOC-uri cunoscute). IP-uri sau CIDR-uri.
+    #[serde(default)]
+    pub hostile_sources: Vec<String>,
+
+    /// O scanare cu cel mult atatea porturi este selectiva (stie ce cauta).
+    /// Implicit: 10.
+    #[serde(default = "default_selective_max_ports")]
+    pub selective_max_ports: usize,
+
+    /// Scorul minim pentru `targeted`. Implicit: 2.
+    #[serde(default = "default_targeted_min_score")]
+    pub targeted_min_score: i32,
+
+    /// Puncte adaugate cand sursa este nod de iesire Tor (`tor_exit`,
+    /// din `enrichment.tor_exit_list_path`). Implicit: 1.
+    #[serde(default = "default_tor_exit_score")]
+    pub tor_exit_score: i32,
+
+    /// Destinatiile alertelor `noise` (ex: doar "gelf", prioritate scazuta).
+    /// Implicit: toate (`ALERT_SINKS`).
+    #[serde(default = "default_route_sinks")]
+    pub noise_sinks: Vec<String>,
+
+    /// Destinatiile alertelor `targeted` (ex: "siem", "email" — paging).
+    /// Implicit: toate (`ALERT_SINKS`).
+    #[serde(default = "default_route_sinks")]
+    pub targeted_sinks: Vec<String>,
+}
+
+fn default_selective_max_ports() -> usize {
+    10
+}
+fn default_targeted_min_score() -> i32 {
+    2
+}
+fn default_tor_exit_score() -> i32 {
+    1
+}
+
+impl Default for ClassificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            service_ports: Vec::new(),
+            noise_sources: Vec::new(),
+            hostile_sources: Vec::new(),
+            selective_max_ports: default_selective_max_ports(),
+            targeted_min_score: default_targeted_min_score(),
+            tor_exit_score: default_tor_exit_score(),
+            noise_sinks: default_route_sinks(),
+            targeted_sinks: default_route_sinks(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SiemConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+
+    /// Forma documentului trimis: "cef" (CEF peste syslog, ArcSight),
+    /// "ecs" (JSON cu campuri Elastic Common Schema: `source.ip`,
+    /// `event.category`, `threat.indicator` ...) sau "cloudevents" (plic
+    /// CloudEvents 1.0 JSON cu alerta in `data`). Transportul ramane UDP.
+    /// Implicit: "cef".
+    #[serde(default = "default_siem_format")]
+    pub format: String,
+
+    /// Antetul syslog al mesajelor CEF: 3164 (BSD, legacy — implicit) sau
+    /// 5424 (timestamp RFC 3339, hostname, app-name, MSGID si structured data
+    /// `[ids@32473 srcIp=".." scanType=".." portCount=".."]`). Documentele
+    /// JSON (ECS, CloudEvents) sunt trimise brut, fara antet syslog.
+    #[serde(default = "default_siem_rfc")]
+    pub rfc: u16,
+
+    /// Severitatea CEF minima (0-10, ca in `cn`/`severity`) a alertelor livrate
+    /// acestei destinatii. 0 (implicit) = toate alertele.
+    #[serde(default)]
+    pub min_severity: u8,
+}
+
+fn default_siem_format() -> String {
+    "cef".to_string()
+}
+fn default_siem_rfc() -> u16 {
+    3164
+}
+
+/// Configurare destinatie GELF 1.1 (Graylog Extended Log Format) peste UDP.
+///
+/// Mesajele mai mari decat `chunk_size` sunt impartite in chunk-uri GELF
+/// (antet de 12 octeti: magic 0x1e 0x0f, id mesaj, numar secventa, total),
+/// maxim 128 de chunk-uri per mesaj — limita protocolului.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GelfConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub host: String,
+    /// Portul input-ului "GELF UDP" din Graylog. Implicit: 12201.
+    #[serde(default = "default_gelf_port")]
+    pub port: u16,
+    /// Dimensiunea maxima a unei datagrame (octeti, cu antetul de chunk).
+    /// Implicit: 1420 — incape intr-un MTU Ethernet fara fragmentare IP.
+    #[serde(default = "default_gelf_chunk_size")]
+    pub chunk_size: usize,
+
+    /// Severitatea CEF minima (0-10, ca in `cn`/`severity`) a alertelor livrate
+    /// acestei destinatii. 0 (implicit) = toate alertele.
+    #[serde(default)]
+    pub min_severity: u8,
+}
+
+fn default_gelf_port() -> u16 {
+    12201
+}
+fn default_gelf_chunk_size() -> usize {
+    1420
+}
+
+fn default_gelf() -> GelfConfig {
+    GelfConfig {
+        enabled: false,
+        host: String::new(),
+        port: default_gelf_port(),
+        chunk_size: default_gelf_chunk_size(),
+        min_severity: 0,
+    }
+}
+
+/// Destinatie Microsoft Sentinel: fiecare alerta este trimisa prin HTTPS
+/// (POST `/api/logs`) in tabela custom `<log_type>_CL` a workspace-ului.
+///
+/// `shared_key` este cheia primara/secundara a workspace-ului (base64). Se
+/// recomanda `"${SENTINEL_SHARED_KEY}"` cu `secrets_file`, nu valoarea in clar.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SentinelConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// ID-ul workspace-ului Log Analytics (GUID).
+    #[serde(default)]
+    pub workspace_id: String,
+    #[serde(default)]
+    pub shared_key: String,
+    /// Numele tabelei custom (fara sufixul `_CL`). Implicit: "IdsRsAlert".
+    #[serde(default = "default_sentinel_log_type")]
+    pub log_type: String,
+    /// Domeniul endpoint-ului: `<workspace_id>.<endpoint_domain>`.
+    /// Implicit: "ods.opinsights.azure.com" (Azure public).
+    #[serde(default = "default_sentinel_endpoint_domain")]
+    pub endpoint_domain: String,
+
+    /// Severitatea CEF minima (0-10, ca in `cn`/`severity`) a alertelor livrate
+    /// acestei destinatii. 0 (implicit) = toate alertele.
+    #[serde(default)]
+    pub min_severity: u8,
+
+    /// Reincercari dupa un POST esuat (0-5), cu aceeasi cheie de idempotenta.
+    /// 0 (implicit) = o singura incercare, ca inainte.
+    #[serde(default)]
+    pub max_retries: u32,
+}
+
+fn default_sentinel_log_type() -> String {
+    "IdsRsAlert".to_string()
+}
+fn default_sentinel_endpoint_domain() -> String {
+    "ods.opinsights.azure.com".to_string()
+}
+
+fn default_sentinel() -> SentinelConfig {
+    SentinelConfig {
+        enabled: false,
+        workspace_id: String::new(),
+        shared_key: String::new(),
+        log_type: default_sentinel_log_type(),
+        endpoint_domain: default_sentinel_endpoint_domain(),
+        min_severity: 0,
+        max_retries: 0,
+    }
+}
+
+/// Configurare email.
+///
+/// NOTA RUST: `Vec<String>` permite lista dinamica de destinatari.
+/// Fiecare String este owned (detinut) de Vec, care la randul lui
+/// este owned de EmailConfig. Cand EmailConfig este dropat, tot
+/// lantul de ownership este dealocat automat - zero memory leaks.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmailConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    pub smtp_server: String,
+    pub smtp_port: u16,
+    pub smtp_tls: bool,
+    pub from: String,
+    pub to: Vec<String>,
+    pub username: String,
+    pub password: String,
+
+    /// Footer personalizabil pentru email-urile de alerta.
+    /// Poate contine banner ASCII al echipei, disclaimer, etc.
+    /// Afisat intre separatoarele ========== din footer-ul email-ului.
+    #[serde(default = "default_email_footer")]
+    pub email_footer: String,
+
+    /// Severitatea CEF minima (0-10, ca in `cn`/`severity`) a alertelor livrate
+    /// acestei destinatii. 0 (implicit) = toate alertele.
+    #[serde(default)]
+    pub min_severity: u8,
+}
+
+fn default_email_footer() -> String {
+    "\
+   ____  ____  ____  ____       _       ____\n\
+  / ___|| ___|| __ )|___ \\     / \\     |  _ \\\n\
+  \\___ \\|___ \\|  _ \\  __) |   / _ \\    | | | |\n\
+   ___) |___) | |_) |/ __/   / ___ \\ _ | |_| |\n\
+  |____/|____/|____/|_____| /_/   \\_(_)|____/\n\
+\n\
+  Generat automat de S5B2 A.D. | Nu raspundeti la acest email"
+        .to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CleanupConfig {
+    pub interval_secs: u64,
+    pub max_entry_age_secs: u64,
+}
+
+/// Configurare Web Dashboard (#25) — server HTTP embedded, read-only.
+///
+/// Serveste o pagina HTML cu graf D3.js force-directed al retelei si
+/// endpoint-uri JSON pentru alerte si graf. Task tokio separat, fara
+/// impact asupra detectiei.
+///
+/// Valori implicite: dezactivat, port 8080, bind 127.0.0.1 (doar local),
+/// 1000 alerte in buffer-ul circular.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebDashboardConfig {
+    /// Activare/dezactivare dashboard web. Implicit: false (retrocompatibil).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Portul HTTP pe care asculta dashboard-ul. Implicit: 8080.
+    #[serde(default = "default_web_port")]
+    pub port: u16,
+
+    /// Adresa de bind. "127.0.0.1" = doar local (securitate).
+    /// "0.0.0.0" = toate interfetele (accesibil din retea).
+    #[serde(default = "default_web_bind")]
+    pub bind: String,
+
+    /// Numarul maxim de alerte pastrate in buffer-ul circular. Implicit: 1000.
+    #[serde(default = "default_web_max_alerts")]
+    pub max_alerts: usize,
+
+    /// Permite `PUT /config/detection` — ajustarea pragurilor la runtime,
+    /// doar in memorie. Implicit: false (dashboard-ul ramane read-only).
+    #[serde(default)]
+    pub allow_config_updates: bool,
+}
+
+fn default_web_port() -> u16 {
+    8080
+}
+fn default_web_bind() -> String {
+    "127.0.0.1".to_string()
+}
+fn default_web_max_alerts() -> usize {
+    1000
+}
+
+fn default_web_dashboard() -> WebDashboardConfig {
+    WebDashboardConfig {
+        enabled: false,
+        port: default_web_port(),
+        bind: default_web_bind(),
+        max_alerts: default_web_max_alerts(),
+        allow_config_updates: false,
+    }
+}
+
+/// Configurare server metrici Prometheus.
+///
+/// Server HTTP separat de dashboard (port dedicat, format text Prometheus)
+/// pentru scrape din Prometheus / Grafana Agent. Expune atat contoare
+/// operationale cat si — optional — pragurile si regulile active, pentru
+/// detectia config drift intre senzori.
+///
+/// Valori implicite: dezactivat, port 9184, bind 127.0.0.1, export ruleset activ.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MetricsConfig {
+    /// Activare/dezactivare server metrici. Implicit: false (retrocompatibil).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Portul HTTP pe care asculta serverul de metrici. Implicit: 9184.
+    #[serde(default = "default_metrics_port")]
+    pub port: u16,
+
+    /// Adresa de bind. "127.0.0.1" = doar local (securitate).
+    #[serde(default = "default_metrics_bind")]
+    pub bind: String,
+
+    /// Exporta pragurile de detectie si regulile active ca gauge-uri
+    /// (`ids_fast_scan_threshold`, `ids_rule_enabled{rule="slow"}` etc.).
+    /// Implicit: true.
+    #[serde(default = "default_metrics_export_ruleset")]
+    pub export_ruleset: bool,
+
+    /// Pagina HTML minimala la `GET /` (top talkers, alerte recente, contoare),
+    /// reimprospatata automat din `GET /stats`. Fara resurse externe —
+    /// functioneaza air-gapped. Implicit: false.
+    #[serde(default)]
+    pub web_ui: bool,
+
+    /// Histograma alertelor pe ora din zi (0-23) in `/stats`
+    /// (`alerts_by_hour`) si Prometheus (`ids_alerts_by_hour{hour="..."}`).
+    /// Arata cand au loc varfurile de scanare. Implicit: false.
+    #[serde(default)]
+    pub alerts_by_hour: bool,
+
+    /// Latenta per pachet (receptie → decizie de detectie) intr-o histograma:
+    /// Prometheus (`ids_detection_latency_seconds`), `/stats`
+    /// (`detection_latency`) si linia periodica `[STAT]` (p50/p95/p99).
+    /// Functioneaza si fara serverul de metrici. Implicit: false.
+    #[serde(default)]
+    pub latency_histogram: bool,
+}
+
+fn default_metrics_port() -> u16 {
+    9184
+}
+fn default_metrics_bind() -> String {
+    "127.0.0.1".to_string()
+}
+fn default_metrics_export_ruleset() -> bool {
+    true
+}
+
+fn default_metrics() -> MetricsConfig {
+    MetricsConfig {
+        enabled: false,
+        port: default_metrics_port(),
+        bind: default_metrics_bind(),
+        export_ruleset: default_metrics_export_ruleset(),
+        web_ui: false,
+        alerts_by_hour: false,
+        latency_histogram: false,
+    }
+}
+
+/// Configurare afisare consola (display.rs).
+///
+/// Coalescing mesaje repetate: in timpul unei "furtuni" de erori (ex: recv
+/// esuat in bucla) acelasi WARN poate fi afisat de mii de ori pe secunda,
+/// ingropand alertele reale. Cu `repeat_window_secs > 0`, un mesaj identic
+/// este afisat o singura data per fereastra; repetarile sunt numarate si
+/// rezumate ca "(repetat de N ori in ultimele Xs)".
+///
+/// Alertele nu trec niciodata prin coalescing; erorile doar la cerere.
+///
+/// Valori implicite: dezactivat (0), doar WARN cand este activat.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DisplayConfig {
+    /// Fereastra (secunde) de coalescing pentru mesaje identice. 0 = dezactivat.
+    #[serde(default)]
+    pub repeat_window_secs: u64,
+
+    /// Aplica coalescing si pentru mesajele INFO. Implicit: false.
+    #[serde(default)]
+    pub coalesce_info: bool,
+
+    /// Aplica coalescing si pentru mesajele ERR. Implicit: false — erorile
+    /// sunt afisate integral pentru diagnostic.
+    #[serde(default)]
+    pub coalesce_errors: bool,
+
+    /// Unitatea in care sunt afisate ferestrele de detectie in banner si
+    /// alerte (CLI, SIEM, email): "seconds" sau "minutes". Fast Scan este
+    /// configurat in secunde, Slow Scan in minute — fara normalizare,
+    /// operatorul compara unitati diferite. Implicit: "seconds".
+    #[serde(default = "default_window_unit")]
+    pub window_unit: String,
+
+    /// Afiseaza in alertele Fast/Slow/Accept Scan din consola timpul scurs
+    /// de la primul port vizat pana la atingerea pragului. Implicit: false
+    /// (campul `time_to_threshold_ms` este oricum inclus in JSON-ul alertei).
+    #[serde(default)]
+    pub show_time_to_threshold: bool,
+
+    /// Precizia timestamp-urilor alertelor si log-urilor: "seconds",
+    /// "millis" sau "micros". Se aplica in consola, email si JSON (SIEM ECS,
+    /// API web). Implicit: "millis" — mai multe alerte in aceeasi secunda
+    /// raman ordonate neambiguu.
+    #[serde(default = "default_timestamp_precision")]
+    pub timestamp_precision: String,
+
+    /// Forma alertelor la stdout: "text" (bloc colorat) sau "gelf" (o linie
+    /// JSON GELF 1.1 per alerta, pentru colectoare care citesc stdout).
+    /// Celelalte mesaje (INFO, WARN, statistici) raman text. Implicit: "text".
+    #[serde(default = "default_display_format")]
+    pub format: String,
+
+    /// Elimina liniile goale decorative din jurul banner-ului si al
+    /// blocurilor de alerta (stream de log dens, fara inregistrari vide).
+    /// Implicit: false — spatierea aerisita pentru uz interactiv.
+    #[serde(default)]
+    pub compact_spacing: bool,
+
+    /// Adauga la linia periodica STAT un sparkline al alertelor pe ora din
+    /// zi (24 de caractere, ora 0 → 23). Implicit: false.
+    #[serde(default)]
+    pub stats_sparkline: bool,
+}
+
+fn default_display_format() -> String {
+    "text".to_string()
+}
+
+fn default_timestamp_precision() -> String {
+    "millis".to_string()
+}
+
+fn default_window_unit() -> String {
+    "seconds".to_string()
+}
+
+fn default_display() -> DisplayConfig {
+    DisplayConfig {
+        repeat_window_secs: 0,
+        coalesce_info: false,
+        coalesce_errors: false,
+        window_unit: default_window_unit(),
+        show_time_to_threshold: false,
+        timestamp_precision: default_timestamp_precision(),
+        format: default_display_format(),
+        compact_spacing: false,
+        stats_sparkline: false,
+    }
+}
+
+/// Niveluri de criticitate ale activelor (`[assets]`).
+///
+/// O alerta al carei IP tinta (sau una din destinatiile Lateral Movement)
+/// cade intr-unul din aceste subnete primeste `asset_criticality`, iar
+/// severitatea ei este escaladata indiferent de numarul de porturi:
+///   critical_cidrs → severitate 10
+///   high_cidrs     → severitatea tipului de alerta + 1 (max 10)
+///
+/// Daca un IP apare in ambele liste, "critical" are prioritate.
+/// Necesita IP-ul destinatie in log (campul `dst`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AssetsConfig {
+    /// Subnete CIDR cu active critice (ex: controllere de domeniu, baze de date).
+    #[serde(default)]
+    pub critical_cidrs: Vec<String>,
+
+    /// Subnete CIDR cu active importante (ex: servere de aplicatii).
+    #[serde(default)]
+    pub high_cidrs: Vec<String>,
+}
+
+/// Imbogatirea alertelor cu context extern (`[enrichment]`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EnrichmentConfig {
+    /// Harta de active exportata din CMDB: CSV (`ip,name,criticality,owner`)
+    /// sau JSON (array de obiecte), dupa extensie. IP sau CIDR per intrare.
+    /// Reincarcata la SIGHUP. Gol = dezactivat (implicit).
+    #[serde(default)]
+    pub asset_map_path: String,
+
+    /// Lista nodurilor de iesire Tor (un IP pe linie sau formatul
+    /// `exit-addresses` al Tor Project), actualizata de un job extern.
+    /// Verificata la 30s si reincarcata la schimbare. Gol = dezactivat.
+    #[serde(default)]
+    pub tor_exit_list_path: String,
+}
+
+/// Socket de control local (`[control]`): comenzi text pentru operatori,
+/// fara HTTP (vezi `control.rs`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ControlConfig {
+    /// Calea socket-ului Unix (ex: "/run/ids-rs/control.sock"), creat cu
+    /// permisiuni 600. Gol = dezactivat (implicit).
+    #[serde(default)]
+    pub socket_path: String,
+
+    /// Permite `set-threshold` — ajustarea pragurilor la runtime, doar in
+    /// memorie. Implicit: false (`get-thresholds` ramane disponibil).
+    #[serde(default)]
+    pub allow_threshold_updates: bool,
+}
+
+/// Asteptarea dependintelor la pornire (`[startup]`, vezi `startup.rs`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct StartupConfig {
+    /// Dependinte verificate inainte de initializarea componentelor:
+    /// "tcp:<host>:<port>" (conexiune TCP) sau "file:<cale>" (cale existenta).
+    /// Gol = pornire imediata (implicit).
+    #[serde(default)]
+    pub wait_for: Vec<String>,
+
+    /// Timpul maxim de asteptare pentru toate dependintele. Implicit: 60s.
+    #[serde(default = "default_startup_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Intervalul intre verificari (si timeout-ul conexiunii TCP). Implicit: 2s.
+    #[serde(default = "default_startup_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+
+    /// La expirare: "fail" (implicit, procesul se opreste) sau "continue"
+    /// (detectia porneste cu un WARN).
+    #[serde(default = "default_startup_on_timeout")]
+    pub on_timeout: String,
+}
+
+fn default_startup_timeout_secs() -> u64 {
+    60
+}
+fn default_startup_poll_interval_secs() -> u64 {
+    2
+}
+fn default_startup_on_timeout() -> String {
+    "fail".to_string()
+}
+
+impl Default for StartupConfig {
+    fn default() -> Self {
+        Self {
+            wait_for: Vec::new(),
+            timeout_secs: default_startup_timeout_secs(),
+            poll_interval_secs: default_startup_poll_interval_secs(),
+            on_timeout: default_startup_on_timeout(),
+        }
+    }
+}
+
+impl StartupConfig {
+    /// Citeste doar sectiunea `[startup]` din `path`, INAINTE de `AppConfig::load`.
+    ///
+    /// `load()` cere deja ca `secrets_file`, `output.replay_dir` si
+    /// `detection.rules_dir` sa existe — exact caile pe care `startup.wait_for`
+    /// le poate astepta (montate de un init-container). Asteptarea ruleaza deci
+    /// pe sectiunea citita aici; configurarea completa se incarca dupa ea.
+    ///
+    /// Sectiunea este citita inainte de interpolarea secretelor: referintele
+    /// `${...}` nu sunt permise in `[startup]` (secrets_file poate fi chiar una
+    /// din dependintele asteptate).
+    pub fn peek<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = std::fs::read_to_string(path.as_ref())
+            .with_context(|| format!("Nu pot citi fisierul: {:?}", path.as_ref()))?;
+        let raw: toml::Value =
+            toml::from_str(&content).context("Eroare la parsarea fisierului TOML")?;
+        let Some(section) = raw.get("startup") else {
+            return Ok(Self::default());
+        };
+        if crate::secrets::has_references(section) {
+            anyhow::bail!("[startup] nu poate contine referinte ${{...}} — este citit inainte de secrets_file");
+        }
+        let startup: StartupConfig = section
+            .clone()
+            .try_into()
+            .context("Eroare la parsarea sectiunii [startup]")?;
+        let errors = startup.validation_errors();
+        if !errors.is_empty() {
+            anyhow::bail!("[startup] contine erori de configurare:\n  - {}", errors.join("\n  - "));
+        }
+        Ok(startup)
+    }
+
+    /// Dependinte parsabile, interval si timeout nenule, `on_timeout` cunoscut.
+    pub fn validation_errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        for entry in &self.wait_for {
+            if let Err(e) = crate::startup::Dependency::parse(entry) {
+                errors.push(format!("startup.wait_for: {}", e));
+            }
+        }
+        if !self.wait_for.is_empty() {
+            if self.timeout_secs == 0 || self.poll_interval_secs == 0 {
+                errors.push(
+                    "startup.timeout_secs / poll_interval_secs = 0: dependintele nu ar fi verificate"
+                        .to_string(),
+                );
+            }
+            if !["fail", "continue"].contains(&self.on_timeout.as_str()) {
+                errors.push(format!(
+                    "startup.on_timeout = {:?} este invalid. Valori acceptate: \"fail\", \"continue\"",
+                    self.on_timeout
+                ));
+            }
+        }
+        errors
+    }
+}
+
+/// Gruparea alertelor in incidente (`[incidents]`, vezi `incidents.rs`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct IncidentsConfig {
+    /// Activare/dezactivare. Implicit: false.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Cheia campaniei: "source" (implicit — IP-ul sursa; Coordinated Subnet
+    /// dupa blocul agregat) sau "subnet" (blocul /24 IPv4, /64 IPv6 al sursei).
+    #[serde(default = "default_incidents_group_by")]
+    pub group_by: String,
+
+    /// Un incident fara alerte noi atata timp este inchis automat. Implicit: 3600s.
+    #[serde(default = "default_incidents_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+
+    /// Cate alerte (cele mai recente) pastreaza un incident. Implicit: 50.
+    #[serde(default = "default_incidents_max_alerts")]
+    pub max_alerts_per_incident: usize,
+
+    /// Plafonul incidentelor deschise; peste el, cel mai vechi inactiv este
+    /// inchis. Implicit: 1000.
+    #[serde(default = "default_incidents_max_open")]
+    pub max_open: usize,
+
+    /// O alerta Scan Ended (`detection.scan_end_silence_secs`) inchide
+    /// incidentul campaniei ei; urmatoarea alerta deschide unul nou. Implicit: true.
+    #[serde(default = "default_incidents_close_on_scan_end")]
+    pub close_on_scan_end: bool,
+}
+
+/// Valorile acceptate pentru `incidents.group_by`.
+pub const INCIDENT_GROUP_BY: [&str; 2] = ["source", "subnet"];
+
+fn default_incidents_group_by() -> String {
+    "source".to_string()
+}
+fn default_incidents_idle_timeout_secs() -> u64 {
+    3600
+}
+fn default_incidents_max_alerts() -> usize {
+    50
+}
+fn default_incidents_max_open() -> usize {
+    1000
+}
+fn default_incidents_close_on_scan_end() -> bool {
+    true
+}
+
+impl Default for IncidentsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            group_by: default_incidents_group_by(),
+            idle_timeout_secs: default_incidents_idle_timeout_secs(),
+            max_alerts_per_incident: default_incidents_max_alerts(),
+            max_open: default_incidents_max_open(),
+            close_on_scan_end: default_incidents_close_on_scan_end(),
+        }
+    }
+}
+
+/// Identitatea senzorului (`[sensor]`), atasata fiecarei alerte.
+///
+/// Cu mai multi senzori care trimit in acelasi SIEM, alerta trebuie sa spuna
+/// de unde vine: `hostname` (detectat automat din kernel, suprascriibil) si
+/// etichete libere `cheie = "valoare"` (ex: `datacenter = "us-east"`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SensorConfig {
+    /// Numele senzorului in alerte. Gol (implicit) = hostname-ul masinii
+    /// (/proc/sys/kernel/hostname).
+    #[serde(default)]
+    pub hostname: String,
+
+    /// Etichete de implementare, in ordinea cheilor. Cheile: litere, cifre,
+    /// `_`, `-`, maxim 32 caractere (devin nume de camp GELF si `labels.*`
+    /// ECS, unde un punct ar crea un obiect imbricat).
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+}
+
+/// Lungimea maxima a unei chei `sensor.tags`.
+pub const SENSOR_TAG_KEY_MAX_LEN: usize = 32;
+
+/// Feedback fals-pozitiv de la operatori (`[false_positives]`).
+///
+/// Un operator marcheaza o alerta din dashboard (`POST /alerts/{id}/false-positive`);
+/// perechea (IP sursa, tip alerta) este memorata si persistata in `file`. Alertele
+/// viitoare cu aceeasi pereche, in urmatoarele `period_hours`, sunt retrogradate
+/// (`action = "downgrade"`) sau suprimate (`action = "suppress"`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct FalsePositivesConfig {
+    /// Activare/dezactivare feedback. Implicit: false.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Fisierul JSON in care se persista marcajele (supravietuiesc restartului).
+    #[serde(default = "default_false_positives_file")]
+    pub file: String,
+
+    /// Cat timp (ore) ramane activ un marcaj. Implicit: 168 (7 zile).
+    #[serde(default = "default_false_positives_period_hours")]
+    pub period_hours: u64,
+
+    /// Ce se intampla cu alertele care se potrivesc: "downgrade" (severitate
+    /// redusa, campul `false_positive: true`) sau "suppress" (nu se emit).
+    #[serde(default = "default_false_positives_action")]
+    pub action: String,
+}
+
+/// Actiunile acceptate pentru `false_positives.action`.
+pub const FALSE_POSITIVE_ACTIONS: [&str; 2] = ["downgrade", "suppress"];
+
+fn default_false_positives_file() -> String {
+    "false_positives.json".to_string()
+}
+
+fn default_false_positives_period_hours() -> u64 {
+    168
+}
+
+fn default_false_positives_action() -> String {
+    "downgrade".to_string()
+}
+
+fn default_false_positives() -> FalsePositivesConfig {
+    FalsePositivesConfig {
+        enabled: false,
+        file: default_false_positives_file(),
+        period_hours: default_false_positives_period_hours(),
+        action: default_false_positives_action(),
+    }
+}
+
+/// Iesiri locale pentru consumatori de pe aceeasi masina (`[output]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct OutputConfig {
+    /// Named pipe (FIFO, creat cu `mkfifo`) in care fiecare alerta este
+    /// scrisa ca o linie JSON. Non-blocant: fara cititor sau cu pipe-ul plin,
+    /// alerta este pierduta si numarata. `None` = dezactivat.
+    #[serde(default)]
+    pub fifo_path: Option<String>,
+    /// Fisier de arhiva (append) in care este scrisa fiecare alerta, in
+    /// formatul `file_format`. Redeschis la SIGHUP (logrotate). `None` = dezactivat.
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Formatul fisierului `file_path`: "text" (o linie lizibila per alerta),
+    /// "json" (o linie JSON, implicit) sau "both" (linia text urmata de
+    /// documentul JSON indentat). Consola nu este afectata.
+    #[serde(default = "default_file_format")]
+    pub file_format: String,
+    /// Fiecare IP sursa primeste o culoare stabila (hash al adresei) in
+    /// alertele si mesajele de consola — acelasi actor, aceeasi culoare.
+    #[serde(default)]
+    pub colorize_ips: bool,
+    /// Raport agregat periodic (rollup): "hourly", "daily" sau "" (implicit,
+    /// dezactivat). La fiecare granita (ora / miezul noptii, ora locala) se
+    /// emite un rezumat: alerte per tip, top surse, top porturi, varful de
+    /// IP-uri urmarite — apoi contoarele se reseteaza.
+    #[serde(default)]
+    pub rollup_interval: String,
+    /// Destinatia rollup-ului: "log" (implicit, consola), "email" sau "siem".
+    #[serde(default = "default_rollup_sink")]
+    pub rollup_sink: String,
+    /// Director in care, la fiecare alerta, ultimele linii brute ale IP-ului
+    /// sursa sunt scrise ca fisier reluabil cu `tester.py replay` (vezi
+    /// `recorder.rs`). Directorul trebuie sa existe. `None` = dezactivat.
+    #[serde(default)]
+    pub replay_dir: Option<String>,
+    /// Linii pastrate per IP sursa (inel, cele mai vechi ies). Implicit: 200.
+    #[serde(default = "default_replay_max_lines")]
+    pub replay_max_lines: usize,
+    /// Fisiere replay scrise cel mult per proces. Implicit: 100.
+    #[serde(default = "default_replay_max_files")]
+    pub replay_max_files: usize,
+    /// Linia STAT periodica este omisa cat timp reteaua este linistita; la
+    /// fiecare `quiet_heartbeat_secs` apare un heartbeat "activ, linistit".
+    #[serde(default)]
+    pub suppress_empty_stats: bool,
+    /// Pragul de "liniste": cel mult atatea evenimente per ciclu de cleanup
+    /// (si nicio alerta). Implicit: 0 — orice eveniment conteaza ca activitate.
+    #[serde(default)]
+    pub quiet_max_events: u64,
+    /// Intervalul heartbeat-ului in perioadele linistite. Implicit: 3600s.
+    #[serde(default = "default_quiet_heartbeat_secs")]
+    pub quiet_heartbeat_secs: u64,
+    /// Porturile vizate afisate ca intervale ("1-1024, 3389, 8080-8090") in
+    /// consola, email, CEF `cs1`, GELF `_unique_ports` si rezumat; JSON-ul
+    /// (ECS, Sentinel, CloudEvents) primeste in plus campul `port_ranges`.
+    #[serde(default)]
+    pub port_ranges: bool,
+    /// Cu `port_ranges`: campurile text ale iesirilor SIEM (CEF `cs1`, GELF
+    /// `_unique_ports`) pastreaza porturile individuale, pentru consumatorii
+    /// care le parseaza. Array-ul `unique_ports` din JSON ramane mereu intreg.
+    #[serde(default)]
+    pub raw_ports: bool,
+    /// Aspectul liniilor de consola: "default" (implicit) sau "columnar" —
+    /// fiecare linie `log_*` aliniata in coloane fixe (timestamp | nivel | IP |
+    /// tip | numar | detalii), trunchiata la latimea terminalului.
+    #[serde(default = "default_output_style")]
+    pub style: String,
+}
+
+/// Valorile acceptate pentru `output.style`.
+pub const OUTPUT_STYLES: [&str; 2] = ["default", "columnar"];
+
+fn default_output_style() -> String {
+    "default".to_string()
+}
+
+/// Valorile acceptate pentru `output.rollup_interval` ("" = dezactivat).
+pub const ROLLUP_INTERVALS: [&str; 3] = ["", "hourly", "daily"];
+
+/// Valorile acceptate pentru `output.rollup_sink`.
+pub const ROLLUP_SINKS: [&str; 3] = ["log", "email", "siem"];
+
+/// Valorile acceptate pentru `output.file_format`.
+pub const FILE_FORMATS: [&str; 3] = ["text", "json", "both"];
+
+fn default_file_format() -> String {
+    "json".to_string()
+}
+
+fn default_rollup_sink() -> String {
+    "log".to_string()
+}
+
+fn default_replay_max_lines() -> usize {
+    200
+}
+fn default_replay_max_files() -> usize {
+    100
+}
+fn default_quiet_heartbeat_secs() -> u64 {
+    3600
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            fifo_path: None,
+            file_path: None,
+            file_format: default_file_format(),
+            colorize_ips: false,
+            rollup_interval: String::new(),
+            rollup_sink: default_rollup_sink(),
+            replay_dir: None,
+            replay_max_lines: default_replay_max_lines(),
+            replay_max_files: default_replay_max_files(),
+            suppress_empty_stats: false,
+            quiet_max_events: 0,
+            quiet_heartbeat_secs: default_quiet_heartbeat_secs(),
+            port_ranges: false,
+            raw_ports: false,
+            style: default_output_style(),
+        }
+    }
+}
+
+/// Rezolva o cale din config relativ la directorul fisierului de configurare.
+fn resolve_relative(config_path: &Path, value: &str) -> std::path::PathBuf {
+    let path = Path::new(value);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    config_path
+        .parent()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|| path.to_path_buf())
 }
 
 impl AppConfig {
@@ -609,63 +2810,456 @@ impl AppConfig {
         let content = std::fs::read_to_string(path.as_ref())
             .with_context(|| format!("Nu pot citi fisierul: {:?}", path.as_ref()))?;
 
-        // `toml::from_str` deserializeaza continutul TOML in structura noastra.
-        // Aceasta functioneaza datorita #[derive(Deserialize)] de pe AppConfig.
-        // serde mapeaza automat cheile TOML pe campurile structurii.
-        let config: AppConfig =
-            toml::from_str(&content).context("Eroare la parsarea fisierului TOML")?;
+        // `toml::from_str` deserializeaza continutul TOML in structura noastra.
+        // Aceasta functioneaza datorita #[derive(Deserialize)] de pe AppConfig.
+        // serde mapeaza automat cheile TOML pe campurile structurii.
+        //
+        // Parsam intai in arborele generic `toml::Value`: referintele `${NUME}`
+        // din `secrets_file` se inlocuiesc inainte de maparea pe AppConfig.
+        let mut raw: toml::Value =
+            toml::from_str(&content).context("Eroare la parsarea fisierului TOML")?;
+        let secrets_file = raw
+            .get("secrets_file")
+            .and_then(|v| v.as_str())
+            .map(|p| resolve_relative(path.as_ref(), p));
+        match secrets_file {
+            Some(secrets_path) => {
+                let secrets = crate::secrets::load_secrets(&secrets_path)?;
+                crate::secrets::interpolate(&mut raw, &secrets)?;
+            }
+            None if crate::secrets::has_references(&raw) => {
+                anyhow::bail!(
+                    "Configurarea contine referinte ${{...}} dar `secrets_file` nu este setat"
+                );
+            }
+            None => {}
+        }
+
+        // Ferestre `window = "5m"` → campurile vechi, inainte de suprapunerea
+        // profilurilor (fiecare profil isi poate scrie fereastra in orice forma).
+        let mut deprecations = Vec::new();
+        if let Some(detection) = raw.get_mut("detection") {
+            normalize_windows(detection, "detection", &mut deprecations)?;
+        }
+        if let Some(toml::Value::Table(profiles)) = raw.get_mut("profiles") {
+            for (name, profile) in profiles.iter_mut() {
+                if let Some(detection) = profile.get_mut("detection") {
+                    let path = format!("profiles.{}.detection", name);
+                    normalize_windows(detection, &path, &mut deprecations)?;
+                }
+            }
+        }
+
+        // Profiluri: `[profiles.<nume>.detection]` devine `[detection]` complet,
+        // cu diferentele profilului suprapuse.
+        if let Some(base) = raw.get("detection").cloned() {
+            if let Some(toml::Value::Table(profiles)) = raw.get_mut("profiles") {
+                for (_, profile) in profiles.iter_mut() {
+                    if let toml::Value::Table(table) = profile {
+                        let mut detection = base.clone();
+                        if let Some(overlay) = table.get("detection") {
+                            merge_toml(&mut detection, overlay);
+                        }
+                        table.insert("detection".to_string(), detection);
+                    }
+                }
+            }
+        }
+
+        let mut config: AppConfig = raw
+            .try_into()
+            .context("Eroare la parsarea fisierului TOML")?;
+        config.deprecations = deprecations;
+
+        // Numele parser-ului in forma canonica (" GAIA " → "gaia"): validarea,
+        // banner-ul si comparatia la SIGHUP vad doar aceasta forma.
+        config.network.parser = crate::parser::canonical_name(&config.network.parser);
+
+        // Validare semantica post-deserializare.
+        // serde verifica doar tipurile; validate() verifica logica si valorile.
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Valideaza o sectiune `[detection]` noua in contextul configuratiei curente.
+    ///
+    /// Folosit de `PUT /config/detection`: aceleasi reguli ca la incarcarea din
+    /// fisier, inclusiv verificarile incrucisate (ex: fereastra Slow Scan vs
+    /// `cleanup.max_entry_age_secs`). Returneaza sectiunea validata.
+    pub fn validate_detection(&self, detection: DetectionConfig) -> Result<DetectionConfig> {
+        let mut candidate = self.clone();
+        candidate.detection = detection;
+        candidate.validate()?;
+        Ok(candidate.detection)
+    }
+
+    /// Valideaza constrangerile semantice ale configuratiei.
+    ///
+    /// NOTA RUST: Colectam TOATE erorile intr-un Vec<String> inainte de a esua,
+    /// astfel utilizatorul vede dintr-o singura rulare tot ce trebuie corectat,
+    /// fara sa reporneasca aplicatia de N ori pentru fiecare eroare in parte.
+    ///
+    /// `anyhow::bail!` este echivalent cu `return Err(anyhow::anyhow!(...))`.
+    /// Macro-ul bail! accepta acelasi format ca println!, cu {} interpolation.
+    fn validate(&self) -> Result<()> {
+        let errors = self.validation_errors();
+
+        // Raportam toate erorile dintr-o singura data.
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            let listing = errors
+                .iter()
+                .enumerate()
+                .map(|(i, e)| format!("  {}. {}", i + 1, e))
+                .collect::<Vec<_>>()
+                .join("\n");
+            anyhow::bail!(
+                "config.toml contine {} erori de configurare:\n{}",
+                errors.len(),
+                listing
+            );
+        }
+    }
+
+    /// Toate erorile de validare (lista goala = configuratie valida).
+    fn validation_errors(&self) -> Vec<String> {
+        let mut errors: Vec<String> = Vec::new();
+
+        // --- Network ---
+
+        if self.network.listen_port == 0 {
+            errors.push(
+                "network.listen_port = 0: portul 0 lasa OS-ul sa aleaga aleatoriu la fiecare pornire"
+                    .to_string(),
+            );
+        }
+        if self.network.listen_address.is_empty() {
+            errors.push("network.listen_address nu poate fi gol".to_string());
+        }
+        if !crate::parser::PARSER_NAMES.contains(&self.network.parser.as_str()) {
+            errors.push(format!(
+                "network.parser = {:?} este invalid. Valori acceptate (fara diferente de majuscule): {}",
+                self.network.parser,
+                crate::parser::PARSER_NAMES.join(", ")
+            ));
+        }
+        let netns = &self.network.netns;
+        if !netns.is_empty() {
+            if !cfg!(target_os = "linux") {
+                errors.push("network.netns este suportat doar pe Linux".to_string());
+            } else if netns.trim() != netns || matches!(netns.as_str(), "." | "..") {
+                errors.push(format!(
+                    "network.netns = {:?} invalid: un nume din /run/netns sau o cale absoluta",
+                    netns
+                ));
+            }
+        }
+
+        if !matches!(self.network.traffic_direction.as_str(), "both" | "inbound_only") {
+            errors.push(format!(
+                "network.traffic_direction = {:?} este invalid. Valori acceptate: \"both\", \"inbound_only\"",
+                self.network.traffic_direction
+            ));
+        }
+        if self.network.ingress_name.chars().any(char::is_control) {
+            errors.push(format!(
+                "network.ingress_name = {:?} contine caractere de control",
+                self.network.ingress_name
+            ));
+        }
+        // Validare hostnames: cheile trebuie sa fie IP-uri valide.
+        for ip_str in self.network.hostnames.keys() {
+            if ip_str.parse::<std::net::IpAddr>().is_err() {
+                errors.push(format!(
+                    "network.hostnames: cheia \"{}\" nu este un IP valid",
+                    ip_str
+                ));
+            }
+        }
+
+        // Validare subnets: cheile trebuie sa fie CIDR valide.
+        for cidr_str in self.network.subnets.keys() {
+            if SubnetEntry::parse(cidr_str).is_none() {
+                errors.push(format!(
+                    "network.subnets: cheia \"{}\" nu este un CIDR valid (ex: \"10.10.1.0/24\")",
+                    cidr_str
+                ));
+            }
+        }
+
+        // Validare nat_source: nume de camp simplu, dispozitive IP / CIDR.
+        let nat = &self.network.nat_source;
+        if !nat.field.is_empty()
+            && !nat
+                .field
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+        {
+            errors.push(format!(
+                "network.nat_source.field = {:?} este invalid: doar litere, cifre, '_', '-', '.'",
+                nat.field
+            ));
+        }
+        for entry in &nat.nat_devices {
+            if SubnetEntry::parse_host_or_cidr(entry).is_none() {
+                errors.push(format!(
+                    "network.nat_source.nat_devices: \"{}\" nu este un IP sau CIDR valid",
+                    entry
+                ));
+            }
+        }
+        if nat.field.is_empty() && !nat.nat_devices.is_empty() {
+            errors.push(
+                "network.nat_source.nat_devices este setat, dar field este gol — optiunea nu are efect"
+                    .to_string(),
+            );
+        }
+
+        // Validare assets: fiecare intrare trebuie sa fie CIDR valid (/32 pentru un host).
+        for (key, list) in [
+            ("critical_cidrs", &self.assets.critical_cidrs),
+            ("high_cidrs", &self.assets.high_cidrs),
+        ] {
+            for cidr_str in list {
+                if SubnetEntry::parse(cidr_str).is_none() {
+                    errors.push(format!(
+                        "assets.{}: \"{}\" nu este un CIDR valid (ex: \"10.0.5.0/24\", \"10.0.5.10/32\")",
+                        key, cidr_str
+                    ));
+                }
+            }
+        }
+
+        // Corelarea intarzie fiecare alerta Fast/Slow/Accept cu pana la
+        // correlation_secs — peste un minut alertele nu mai sunt "live".
+        if self.alerting.correlation_secs > 60 {
+            errors.push(format!(
+                "alerting.correlation_secs ({}) prea mare: maxim 60 (latenta adaugata fiecarei alerte)",
+                self.alerting.correlation_secs
+            ));
+        }
+
+        // Sablonul rezumatului: fiecare camp trebuie sa fie cunoscut — un
+        // `{sursa}` scris gresit ar aparea literal in fiecare email.
+        let summary = &self.alerting.summary_template;
+        if summary.trim().is_empty() {
+            errors.push("alerting.summary_template nu poate fi gol".to_string());
+        } else {
+            match template_placeholders(summary) {
+                Ok(fields) => {
+                    for field in fields {
+                        if !SUMMARY_FIELDS.contains(&field) {
+                            errors.push(format!(
+                                "alerting.summary_template: camp necunoscut \"{{{}}}\" (acceptate: {})",
+                                field,
+                                SUMMARY_FIELDS.join(", ")
+                            ));
+                        }
+                    }
+                }
+                Err(e) => errors.push(format!("alerting.summary_template: {}", e)),
+            }
+        }
+
+        // Validare incidente.
+        let inc = &self.incidents;
+        if inc.enabled {
+            if !INCIDENT_GROUP_BY.contains(&inc.group_by.as_str()) {
+                errors.push(format!(
+                    "incidents.group_by = \"{}\": valori acceptate {:?}",
+                    inc.group_by, INCIDENT_GROUP_BY
+                ));
+            }
+            for (key, value) in [
+                ("idle_timeout_secs", inc.idle_timeout_secs),
+                ("max_alerts_per_incident", inc.max_alerts_per_incident as u64),
+                ("max_open", inc.max_open as u64),
+            ] {
+                if value == 0 {
+                    errors.push(format!("incidents.{} trebuie sa fie >= 1", key));
+                }
+            }
+        }
+
+        // Validare identitate senzor.
+        let sensor = &self.sensor;
+        if !sensor.hostname.is_empty()
+            && (sensor.hostname.len() > 255 || !sensor.hostname.chars().all(|c| c.is_ascii_graphic()))
+        {
+            errors.push(format!(
+                "sensor.hostname = {:?}: doar caractere ASCII tiparibile, fara spatii, maxim 255",
+                sensor.hostname
+            ));
+        }
+        for (key, value) in &sensor.tags {
+            let valid_key = !key.is_empty()
+                && key.len() <= SENSOR_TAG_KEY_MAX_LEN
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'));
+            if !valid_key {
+                errors.push(format!(
+                    "sensor.tags: cheia {:?} invalida (litere, cifre, '_', '-', maxim {} caractere)",
+                    key, SENSOR_TAG_KEY_MAX_LEN
+                ));
+            }
+            if value.chars().any(|c| c.is_control()) {
+                errors.push(format!(
+                    "sensor.tags.{}: valoarea contine caractere de control",
+                    key
+                ));
+            }
+        }
+
+        // Validare feedback fals-pozitiv.
+        let fp = &self.false_positives;
+        if fp.enabled {
+            if !FALSE_POSITIVE_ACTIONS.contains(&fp.action.as_str()) {
+                errors.push(format!(
+                    "false_positives.action = \"{}\": valori acceptate {:?}",
+                    fp.action, FALSE_POSITIVE_ACTIONS
+                ));
+            }
+            if fp.period_hours == 0 {
+                errors.push(
+                    "false_positives.period_hours = 0: marcajele ar expira imediat".to_string(),
+                );
+            }
+            if fp.file.trim().is_empty() {
+                errors.push(
+                    "false_positives.file este gol: marcajele trebuie persistate intr-un fisier"
+                        .to_string(),
+                );
+            }
+        }
 
-        // Validare semantica post-deserializare.
-        // serde verifica doar tipurile; validate() verifica logica si valorile.
-        config.validate()?;
+        if let Some(ref path) = self.output.fifo_path {
+            if path.trim().is_empty() {
+                errors.push(
+                    "output.fifo_path este gol (omiteti campul pentru dezactivare)"
+                        .to_string(),
+                );
+            }
+        }
+        if let Some(ref path) = self.output.file_path {
+            if path.trim().is_empty() {
+                errors.push(
+                    "output.file_path este gol (omiteti campul pentru dezactivare)"
+                        .to_string(),
+                );
+            }
+        }
+        if !FILE_FORMATS.contains(&self.output.file_format.as_str()) {
+            errors.push(format!(
+                "output.file_format = {:?} este invalid. Valori acceptate: {}",
+                self.output.file_format,
+                FILE_FORMATS.join(", ")
+            ));
+        }
+        if !OUTPUT_STYLES.contains(&self.output.style.as_str()) {
+            errors.push(format!(
+                "output.style = {:?} este invalid. Valori acceptate: {}",
+                self.output.style,
+                OUTPUT_STYLES.join(", ")
+            ));
+        }
 
-        Ok(config)
-    }
+        if let Some(ref dir) = self.output.replay_dir {
+            if !std::path::Path::new(dir).is_dir() {
+                errors.push(format!(
+                    "output.replay_dir = {:?} nu este un director existent",
+                    dir
+                ));
+            }
+            if self.output.replay_max_lines == 0 || self.output.replay_max_files == 0 {
+                errors.push(
+                    "output.replay_max_lines / replay_max_files = 0: nicio secventa nu ar fi inregistrata"
+                        .to_string(),
+                );
+            }
+        }
 
-    /// Valideaza constrangerile semantice ale configuratiei.
-    ///
-    /// NOTA RUST: Colectam TOATE erorile intr-un Vec<String> inainte de a esua,
-    /// astfel utilizatorul vede dintr-o singura rulare tot ce trebuie corectat,
-    /// fara sa reporneasca aplicatia de N ori pentru fiecare eroare in parte.
-    ///
-    /// `anyhow::bail!` este echivalent cu `return Err(anyhow::anyhow!(...))`.
-    /// Macro-ul bail! accepta acelasi format ca println!, cu {} interpolation.
-    fn validate(&self) -> Result<()> {
-        let mut errors: Vec<String> = Vec::new();
+        if self.output.suppress_empty_stats
+            && self.output.quiet_heartbeat_secs < self.cleanup.interval_secs
+        {
+            errors.push(format!(
+                "output.quiet_heartbeat_secs ({}) este mai mic decat cleanup.interval_secs ({}): \
+                 heartbeat-ul apare doar la un ciclu de statistici",
+                self.output.quiet_heartbeat_secs, self.cleanup.interval_secs
+            ));
+        }
 
-        // --- Network ---
+        // Validare startup: aceleasi reguli ca la `StartupConfig::peek`.
+        errors.extend(self.startup.validation_errors());
 
-        if self.network.listen_port == 0 {
+        // Directorul socket-ului de control trebuie sa existe — socket-ul
+        // este creat la pornire, directorul nu.
+        if !self.control.socket_path.is_empty() {
+            let parent = std::path::Path::new(&self.control.socket_path)
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(std::path::Path::new("."));
+            if !parent.is_dir() {
+                errors.push(format!(
+                    "control.socket_path = {:?}: directorul {} nu exista",
+                    self.control.socket_path,
+                    parent.display()
+                ));
+            }
+        } else if self.control.allow_threshold_updates {
             errors.push(
-                "network.listen_port = 0: portul 0 lasa OS-ul sa aleaga aleatoriu la fiecare pornire"
-                    .to_string(),
+                "control.allow_threshold_updates = true fara control.socket_path".to_string(),
             );
         }
-        if self.network.listen_address.is_empty() {
-            errors.push("network.listen_address nu poate fi gol".to_string());
-        }
-        if !matches!(self.network.parser.as_str(), "gaia" | "cef" | "gaia_cef") {
+
+        if !ROLLUP_INTERVALS.contains(&self.output.rollup_interval.as_str()) {
             errors.push(format!(
-                "network.parser = {:?} este invalid. Valori acceptate: \"gaia\", \"cef\", \"gaia_cef\"",
-                self.network.parser
+                "output.rollup_interval = {:?} este invalid. Valori acceptate: \"hourly\", \"daily\" (sau gol = dezactivat)",
+                self.output.rollup_interval
             ));
         }
-        // Validare hostnames: cheile trebuie sa fie IP-uri valide.
-        for ip_str in self.network.hostnames.keys() {
-            if ip_str.parse::<std::net::IpAddr>().is_err() {
+        if !self.output.rollup_interval.is_empty() {
+            if !ROLLUP_SINKS.contains(&self.output.rollup_sink.as_str()) {
                 errors.push(format!(
-                    "network.hostnames: cheia \"{}\" nu este un IP valid",
-                    ip_str
+                    "output.rollup_sink = {:?} este invalid. Valori acceptate: {}",
+                    self.output.rollup_sink,
+                    ROLLUP_SINKS.join(", ")
+                ));
+            } else if self.output.rollup_sink != "log"
+                && !self
+                    .alerting
+                    .sink_entries()
+                    .iter()
+                    .any(|e| e.kind.type_name() == self.output.rollup_sink)
+            {
+                errors.push(format!(
+                    "output.rollup_sink = {:?} necesita o destinatie {} activa \
+                     (alerting.{}.enabled = true sau o intrare in alerting.sinks)",
+                    self.output.rollup_sink, self.output.rollup_sink, self.output.rollup_sink
                 ));
             }
         }
 
-        // Validare subnets: cheile trebuie sa fie CIDR valide.
-        for cidr_str in self.network.subnets.keys() {
-            if SubnetEntry::parse(cidr_str).is_none() {
+        if let Some(ref file) = self.network.deadletter_file {
+            if file.trim().is_empty() {
+                errors.push(
+                    "network.deadletter_file este gol (omiteti campul pentru dezactivare)"
+                        .to_string(),
+                );
+            }
+            // Sub o inregistrare tipica (~4 KB la pachet trunchiat) rotatia ar fi continua.
+            if self.network.deadletter_max_bytes < 64 * 1024 {
                 errors.push(format!(
-                    "network.subnets: cheia \"{}\" nu este un CIDR valid (ex: \"10.10.1.0/24\")",
-                    cidr_str
+                    "network.deadletter_max_bytes ({}) prea mic: minim 65536",
+                    self.network.deadletter_max_bytes
+                ));
+            }
+            if self.network.deadletter_max_files > 10 {
+                errors.push(format!(
+                    "network.deadletter_max_files ({}) prea mare: maxim 10",
+                    self.network.deadletter_max_files
                 ));
             }
         }
@@ -686,6 +3280,15 @@ impl AppConfig {
                 self.network.udp_burst_size, self.network.udp_rate_limit
             ));
         }
+        // Dedup-ul tinteste copii sosite la milisecunde distanta. O fereastra
+        // de secunde ar elimina si repetarile legitime (retry-uri, beaconing).
+        if self.network.dedup_window_ms > 1000 {
+            errors.push(format!(
+                "network.dedup_window_ms = {}: fereastra > 1000 ms elimina si trafic \
+                 legitim repetat (retry-uri, beaconing), nu doar copiile mirror",
+                self.network.dedup_window_ms
+            ));
+        }
 
         // --- Detection ---
 
@@ -748,6 +3351,76 @@ impl AppConfig {
                     .to_string(),
             );
         }
+        // Plafonul trebuie sa lase pragurile sa fie atinse cu porturi reale:
+        // contorul de depasire este aproximativ si nu pastreaza lista porturilor.
+        let max_ports = self.detection.max_ports_per_ip;
+        if max_ports > 0 {
+            let highest_threshold = self
+                .detection
+                .fast_scan
+                .port_threshold
+                .max(self.detection.slow_scan.port_threshold)
+                .max(self.detection.accept_scan.port_threshold);
+            if max_ports < highest_threshold {
+                errors.push(format!(
+                    "detection.max_ports_per_ip ({}) trebuie sa fie >= cel mai mare prag de porturi ({})",
+                    max_ports, highest_threshold
+                ));
+            }
+        }
+        // Confirmare multi-fereastra: ferestrele trebuie sa fie disjuncte, deci
+        // N depasiri cer cel putin (N-1) ferestre ale regulii in `confirm_secs`.
+        // Fiecare regula are fereastra ei — Slow Scan (minute) e de obicei
+        // cea mai lunga si ar ramane altfel imposibil de confirmat.
+        let confirm_windows = self.detection.confirm_windows;
+        if confirm_windows == 0 || confirm_windows > 10 {
+            errors.push(format!(
+                "detection.confirm_windows ({}) trebuie sa fie intre 1 si 10",
+                confirm_windows
+            ));
+        } else if confirm_windows > 1 {
+            let rule_windows = [
+                ("fast_scan.time_window_secs", self.detection.fast_scan.time_window_secs),
+                ("slow_scan.time_window_mins x 60", self.detection.slow_scan.time_window_mins * 60),
+                ("accept_scan.time_window_secs", self.detection.accept_scan.time_window_secs),
+            ];
+            for (window_name, window_secs) in rule_windows {
+                let min_secs = window_secs * u64::from(confirm_windows - 1);
+                if self.detection.confirm_secs < min_secs {
+                    errors.push(format!(
+                        "detection.confirm_secs ({}) prea mic: {} depasiri in ferestre separate cer cel putin {}s ({} x {})",
+                        self.detection.confirm_secs,
+                        confirm_windows,
+                        min_secs,
+                        window_name,
+                        confirm_windows - 1
+                    ));
+                }
+            }
+        }
+        if !RULE_PRECEDENCES.contains(&self.detection.rule_precedence.as_str()) {
+            errors.push(format!(
+                "detection.rule_precedence = \"{}\" invalid: valori acceptate {}",
+                self.detection.rule_precedence,
+                RULE_PRECEDENCES.join(", ")
+            ));
+        }
+        if !PROTOCOL_TRACKING_MODES.contains(&self.detection.per_protocol_tracking.as_str()) {
+            errors.push(format!(
+                "detection.per_protocol_tracking = \"{}\" invalid: valori acceptate {}",
+                self.detection.per_protocol_tracking,
+                PROTOCOL_TRACKING_MODES.join(", ")
+            ));
+        }
+        // Cleanup-ul parcurge toate map-urile (O(n)); declansat prea des ar
+        // consuma CPU-ul receptiei exact in timpul burst-ului.
+        let every_n = self.detection.cleanup_every_n_packets;
+        if every_n > 0 && every_n < 1000 {
+            errors.push(format!(
+                "detection.cleanup_every_n_packets ({}) prea mic: minim 1000 (0 = dezactivat)",
+                every_n
+            ));
+        }
         if self.detection.max_tracked_ips == 0 {
             errors.push(
                 "detection.max_tracked_ips = 0: niciun IP nu poate fi urmarit, detectia devine imposibila"
@@ -778,6 +3451,13 @@ impl AppConfig {
                     .to_string(),
             );
         }
+        let carry_over = self.detection.slow_scan.carry_over;
+        if !(0.0..=1.0).contains(&carry_over) {
+            errors.push(format!(
+                "detection.slow_scan.carry_over = {} este invalid: trebuie sa fie intre 0.0 si 1.0",
+                carry_over
+            ));
+        }
         if self.detection.accept_scan.port_threshold == 0 {
             errors.push(
                 "detection.accept_scan.port_threshold = 0: orice pachet accept va declansa alerta Accept Scan"
@@ -823,38 +3503,283 @@ impl AppConfig {
             }
         }
 
+        // Validare Coordinated Subnet Scan (doar daca e activat).
+        let cs = &self.detection.coordinated_subnet;
+        if cs.enabled {
+            if !(8..=31).contains(&cs.prefix_len_v4) {
+                errors.push(format!(
+                    "detection.coordinated_subnet.prefix_len_v4 ({}) trebuie sa fie intre 8 si 31",
+                    cs.prefix_len_v4
+                ));
+            }
+            if !(16..=127).contains(&cs.prefix_len_v6) {
+                errors.push(format!(
+                    "detection.coordinated_subnet.prefix_len_v6 ({}) trebuie sa fie intre 16 si 127",
+                    cs.prefix_len_v6
+                ));
+            }
+            if cs.min_sources < 2 {
+                errors.push(format!(
+                    "detection.coordinated_subnet.min_sources ({}) trebuie sa fie >= 2 (o singura sursa = Fast/Slow Scan)",
+                    cs.min_sources
+                ));
+            }
+            if cs.port_threshold == 0 {
+                errors.push(
+                    "detection.coordinated_subnet.port_threshold = 0: orice drop va declansa alerta"
+                        .to_string(),
+                );
+            }
+            if cs.time_window_secs == 0 {
+                errors.push(
+                    "detection.coordinated_subnet.time_window_secs = 0: fereastra de timp zero face detectia imposibila"
+                        .to_string(),
+                );
+            }
+        }
+
+        // Validare Flood (doar daca e activat).
+        let flood = &self.detection.flood;
+        if flood.enabled {
+            if flood.packets_per_sec_threshold == 0 {
+                errors.push(
+                    "detection.flood.packets_per_sec_threshold = 0: orice pachet va declansa alerta"
+                        .to_string(),
+                );
+            }
+            if flood.time_window_secs == 0 {
+                errors.push(
+                    "detection.flood.time_window_secs = 0: fereastra de timp zero face detectia imposibila"
+                        .to_string(),
+                );
+            }
+        }
+
+        // Validare Slow Sweep (doar daca e activat).
+        let sw = &self.detection.slow_sweep;
+        if sw.enabled {
+            if sw.unique_dest_threshold < 2 {
+                errors.push(format!(
+                    "detection.slow_sweep.unique_dest_threshold = {} invalid: minim 2 (un sweep atinge mai multe hosturi)",
+                    sw.unique_dest_threshold
+                ));
+            }
+            if sw.time_window_secs == 0 {
+                errors.push(
+                    "detection.slow_sweep.time_window_secs = 0: fereastra de timp zero face detectia imposibila"
+                        .to_string(),
+                );
+            }
+        }
+
+        // Validare Malformed Input (doar daca e activat).
+        let mi = &self.detection.malformed_input;
+        if mi.enabled {
+            if mi.threshold == 0 {
+                errors.push(
+                    "detection.malformed_input.threshold = 0: orice datagrama malformata ar declansa alerta"
+                        .to_string(),
+                );
+            }
+            if mi.time_window_secs == 0 {
+                errors.push(
+                    "detection.malformed_input.time_window_secs = 0: fereastra de timp zero face detectia imposibila"
+                        .to_string(),
+                );
+            }
+        }
+
         // Validare Beaconing C2 (doar daca e activat).
         if self.detection.beaconing.enabled {
             let b = &self.detection.beaconing;
             if b.min_events < 3 {
                 errors.push(format!(
-                    "detection.beaconing.min_events = {} este prea mic: minim 3 pentru un calcul statistic relevant",
-                    b.min_events
+                    "detection.beaconing.min_events = {} este prea mic: minim 3 pentru un calcul statistic relevant",
+                    b.min_events
+                ));
+            }
+            if b.time_window_secs == 0 {
+                errors.push(
+                    "detection.beaconing.time_window_secs = 0: fereastra zero face detectia imposibila"
+                        .to_string(),
+                );
+            }
+            if b.cv_threshold <= 0.0 || b.cv_threshold > 1.0 {
+                errors.push(format!(
+                    "detection.beaconing.cv_threshold = {} este invalid: trebuie in (0.0, 1.0]",
+                    b.cv_threshold
+                ));
+            }
+            if b.min_interval_secs == 0 {
+                errors.push(
+                    "detection.beaconing.min_interval_secs = 0: trebuie > 0".to_string(),
+                );
+            }
+            if b.max_interval_secs <= b.min_interval_secs {
+                errors.push(format!(
+                    "detection.beaconing.max_interval_secs ({}) trebuie sa fie > min_interval_secs ({})",
+                    b.max_interval_secs, b.min_interval_secs
+                ));
+            }
+            if !(0.0..100.0).contains(&b.jitter_percent) {
+                errors.push(format!(
+                    "detection.beaconing.jitter_percent = {} este invalid: trebuie in [0, 100)",
+                    b.jitter_percent
+                ));
+            }
+            // Istoricul per flow este plafonat la 4 × min_events timestamp-uri
+            // (vezi detector.rs) → cel mult 4 × min_events − 1 intervale.
+            let max_intervals = b.min_events.saturating_mul(4).saturating_sub(1);
+            if b.jitter_percent > 0.0
+                && (b.min_consistent_intervals < 2 || b.min_consistent_intervals > max_intervals)
+            {
+                errors.push(format!(
+                    "detection.beaconing.min_consistent_intervals = {} este invalid: trebuie in [2, {}] (4 × min_events − 1)",
+                    b.min_consistent_intervals, max_intervals
+                ));
+            }
+        }
+
+        // Validare alerta de saturatie (doar daca e activata).
+        if self.detection.early_warning.enabled {
+            let ew = &self.detection.early_warning;
+            if !(ew.threshold_fraction > 0.0 && ew.threshold_fraction < 1.0) {
+                errors.push(format!(
+                    "detection.early_warning.threshold_fraction = {} este invalid: trebuie in (0, 1)",
+                    ew.threshold_fraction
+                ));
+            }
+            if ew.cooldown_secs == 0 {
+                errors.push(
+                    "detection.early_warning.cooldown_secs = 0: fara limita per IP, fiecare pachet \
+                     peste fractiune ar produce un avertisment"
+                        .to_string(),
+                );
+            }
+        }
+
+        if self.detection.saturation_alert.enabled {
+            if self.detection.saturation_alert.evictions_threshold == 0 {
+                errors.push(
+                    "detection.saturation_alert.evictions_threshold = 0: orice evictie va declansa alerta"
+                        .to_string(),
+                );
+            }
+            if self.detection.saturation_alert.time_window_secs == 0 {
+                errors.push(
+                    "detection.saturation_alert.time_window_secs = 0: fereastra zero face detectia imposibila"
+                        .to_string(),
+                );
+            }
+        }
+
+        // Un prag mic transforma orice pauza normala a unei legaturi linistite
+        // (noaptea, weekend) intr-o alarma falsa.
+        let silence = self.detection.no_traffic_alert_secs;
+        if silence > 0 && silence < MIN_NO_TRAFFIC_ALERT_SECS {
+            errors.push(format!(
+                "detection.no_traffic_alert_secs = {} este prea mic: minim {} (0 = dezactivat)",
+                silence, MIN_NO_TRAFFIC_ALERT_SECS
+            ));
+        }
+        let scan_end = self.detection.scan_end_silence_secs;
+        if scan_end > 0 && scan_end < MIN_SCAN_END_SILENCE_SECS {
+            errors.push(format!(
+                "detection.scan_end_silence_secs = {} este prea mic: minim {} (0 = dezactivat)",
+                scan_end, MIN_SCAN_END_SILENCE_SECS
+            ));
+        }
+
+        // Un fisier de regula invalid este doar ignorat (cu avertisment), dar
+        // un director lipsa inseamna aproape sigur o cale gresita.
+        let rules_dir = &self.detection.rules_dir;
+        if !rules_dir.is_empty() && !std::path::Path::new(rules_dir).is_dir() {
+            errors.push(format!(
+                "detection.rules_dir = {:?} nu exista sau nu este director",
+                rules_dir
+            ));
+        }
+
+        // Validare ponderare sonde UDP goale.
+        {
+            let w = self.detection.empty_udp_probes.weight;
+            if !w.is_finite() || !(1.0..=100.0).contains(&w) {
+                errors.push(format!(
+                    "detection.empty_udp_probes.weight = {} invalid: trebuie sa fie intre 1.0 si 100.0",
+                    w
+                ));
+            }
+        }
+
+        // Validare epuizare porturi sursa (doar daca e activata).
+        let spe = &self.detection.source_port_exhaustion;
+        if spe.enabled {
+            if spe.min_source_ports < 2 {
+                errors.push(format!(
+                    "detection.source_port_exhaustion.min_source_ports = {} invalid: minim 2 (densitatea cere un interval)",
+                    spe.min_source_ports
+                ));
+            }
+            // Porturile sursa sunt plafonate la max_hits_per_ip per IP: un prag
+            // mai mare nu ar putea fi atins niciodata.
+            if spe.min_source_ports > self.detection.max_hits_per_ip {
+                errors.push(format!(
+                    "detection.source_port_exhaustion.min_source_ports = {} depaseste detection.max_hits_per_ip = {}: pragul nu poate fi atins",
+                    spe.min_source_ports, self.detection.max_hits_per_ip
+                ));
+            }
+            if !spe.min_density.is_finite() || spe.min_density <= 0.0 || spe.min_density > 1.0 {
+                errors.push(format!(
+                    "detection.source_port_exhaustion.min_density = {} invalid: trebuie sa fie in (0.0, 1.0]",
+                    spe.min_density
+                ));
+            }
+            if spe.time_window_secs == 0 {
+                errors.push(
+                    "detection.source_port_exhaustion.time_window_secs = 0: fereastra de timp zero face detectia imposibila"
+                        .to_string(),
+                );
+            }
+            let f = spe.threshold_factor;
+            if !f.is_finite() || f <= 0.0 || f > 1.0 {
+                errors.push(format!(
+                    "detection.source_port_exhaustion.threshold_factor = {} invalid: trebuie sa fie in (0.0, 1.0]",
+                    f
+                ));
+            }
+        }
+
+        // Validare ponderi per port.
+        for (port, weight) in &self.detection.port_weights {
+            if !matches!(port.parse::<u16>(), Ok(p) if p > 0) {
+                errors.push(format!(
+                    "detection.port_weights: cheia {:?} nu este un port valid (1-65535)",
+                    port
+                ));
+            }
+            if !weight.is_finite() || !(0.0..=100.0).contains(weight) {
+                errors.push(format!(
+                    "detection.port_weights.{} = {} invalid: trebuie sa fie intre 0.0 si 100.0",
+                    port, weight
                 ));
             }
-            if b.time_window_secs == 0 {
+        }
+
+        // Validare semnaturi de scanere autorizate.
+        {
+            let ts = &self.detection.trusted_signatures;
+            if ts.patterns.iter().any(|p| p.trim().is_empty()) {
                 errors.push(
-                    "detection.beaconing.time_window_secs = 0: fereastra zero face detectia imposibila"
+                    "detection.trusted_signatures.patterns: pattern gol — ar potrivi ORICE log"
                         .to_string(),
                 );
             }
-            if b.cv_threshold <= 0.0 || b.cv_threshold > 1.0 {
-                errors.push(format!(
-                    "detection.beaconing.cv_threshold = {} este invalid: trebuie in (0.0, 1.0]",
-                    b.cv_threshold
-                ));
-            }
-            if b.min_interval_secs == 0 {
+            if !ts.patterns.is_empty() && ts.suppress_secs == 0 {
                 errors.push(
-                    "detection.beaconing.min_interval_secs = 0: trebuie > 0".to_string(),
+                    "detection.trusted_signatures.suppress_secs = 0: suprimarea ar expira imediat"
+                        .to_string(),
                 );
             }
-            if b.max_interval_secs <= b.min_interval_secs {
-                errors.push(format!(
-                    "detection.beaconing.max_interval_secs ({}) trebuie sa fie > min_interval_secs ({})",
-                    b.max_interval_secs, b.min_interval_secs
-                ));
-            }
         }
 
         // Validare Dynamic Threshold (doar daca e activat).
@@ -923,6 +3848,20 @@ impl AppConfig {
             ));
         }
 
+        // carry_over citeste hit-urile pana la 2× fereastra Slow Scan in urma.
+        if carry_over > 0.0
+            && self.cleanup.max_entry_age_secs >= slow_secs
+            && self.cleanup.max_entry_age_secs < slow_secs * 2
+        {
+            errors.push(format!(
+                "detection.slow_scan.carry_over = {} necesita cleanup.max_entry_age_secs >= {}s \
+                 (2× fereastra Slow Scan), configurat {}: fereastra anterioara ar fi stearsa prematur",
+                carry_over,
+                slow_secs * 2,
+                self.cleanup.max_entry_age_secs
+            ));
+        }
+
         // --- Web Dashboard ---
 
         if self.web_dashboard.enabled {
@@ -941,59 +3880,347 @@ impl AppConfig {
             }
         }
 
-        // --- Alerting: SIEM ---
+        // --- Metrics ---
 
-        if self.alerting.siem.enabled {
-            if self.alerting.siem.port == 0 {
-                errors.push("alerting.siem.port = 0 este invalid".to_string());
+        if self.metrics.enabled {
+            if self.metrics.port == 0 {
+                errors.push("metrics.port = 0 este invalid".to_string());
+            }
+            if self.metrics.bind.is_empty() {
+                errors.push(
+                    "metrics.bind nu poate fi gol cand serverul de metrici este activat"
+                        .to_string(),
+                );
             }
-            if self.alerting.siem.host.is_empty() {
-                errors
-                    .push("alerting.siem.host nu poate fi gol cand SIEM este activat".to_string());
+            if self.web_dashboard.enabled
+                && self.web_dashboard.port == self.metrics.port
+                && self.web_dashboard.bind == self.metrics.bind
+            {
+                errors.push(format!(
+                    "metrics.port = {} este deja folosit de web_dashboard",
+                    self.metrics.port
+                ));
             }
         }
 
-        // --- Alerting: Email ---
+        // --- Display ---
+
+        if !matches!(self.display.window_unit.as_str(), "seconds" | "minutes") {
+            errors.push(format!(
+                "display.window_unit = {:?} este invalid. Valori acceptate: \"seconds\", \"minutes\"",
+                self.display.window_unit
+            ));
+        }
+
+        if !matches!(
+            self.display.timestamp_precision.as_str(),
+            "seconds" | "millis" | "micros"
+        ) {
+            errors.push(format!(
+                "display.timestamp_precision = {:?} este invalid. Valori acceptate: \"seconds\", \"millis\", \"micros\"",
+                self.display.timestamp_precision
+            ));
+        }
+
+        if !matches!(self.display.format.as_str(), "text" | "gelf") {
+            errors.push(format!(
+                "display.format = {:?} este invalid. Valori acceptate: \"text\", \"gelf\"",
+                self.display.format
+            ));
+        }
 
-        if self.alerting.email.enabled {
-            if self.alerting.email.smtp_port == 0 {
-                errors.push("alerting.email.smtp_port = 0 este invalid".to_string());
+        // --- Alerting: destinatii (sectiunile legacy si alerting.sinks) ---
+        //
+        // Sectiunile legacy sunt verificate si dezactivate (formatul SIEM, ca
+        // inainte); intrarile din lista doar cand sunt active.
+        let legacy = [
+            SinkKind::Siem(self.alerting.siem.clone()),
+            SinkKind::Email(self.alerting.email.clone()),
+            SinkKind::Gelf(self.alerting.gelf.clone()),
+            SinkKind::Sentinel(self.alerting.sentinel.clone()),
+        ];
+        for kind in &legacy {
+            kind.validation_errors(&format!("alerting.{}", kind.type_name()), &mut errors);
+        }
+        let mut seen_names: Vec<String> = Vec::new();
+        for (i, entry) in self.alerting.sinks.iter().enumerate() {
+            let entry = entry.resolved(i);
+            let prefix = format!("alerting.sinks.{}", entry.name);
+            if entry.name.len() > SINK_NAME_MAX_LEN
+                || !entry
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                errors.push(format!(
+                    "alerting.sinks: numele {:?} este invalid: litere, cifre, '_', '-', maxim {} caractere",
+                    entry.name, SINK_NAME_MAX_LEN
+                ));
             }
-            if self.alerting.email.smtp_server.is_empty() {
-                errors.push(
-                    "alerting.email.smtp_server nu poate fi gol cand email este activat"
-                        .to_string(),
-                );
+            if ALERT_SINKS.contains(&entry.name.as_str()) {
+                errors.push(format!(
+                    "{}: numele este rezervat tipului (inseamna toate instantele lui in routing)",
+                    prefix
+                ));
+            } else if crate::metrics::Subsystem::ALL.iter().any(|s| s.as_str() == entry.name) {
+                errors.push(format!(
+                    "{}: numele este rezervat unui slot din last_errors (socket, config, deadletter)",
+                    prefix
+                ));
+            } else if seen_names.contains(&entry.name) {
+                errors.push(format!("{}: nume duplicat in alerting.sinks", prefix));
             }
-            if self.alerting.email.from.is_empty() {
-                errors.push(
-                    "alerting.email.from nu poate fi gol cand email este activat".to_string(),
-                );
+            seen_names.push(entry.name.clone());
+            for field in &entry.omit_fields {
+                if !SINK_OMITTABLE_FIELDS.contains(&field.as_str()) {
+                    errors.push(format!(
+                        "{}.omit_fields contine campul necunoscut '{}' (valide: {})",
+                        prefix,
+                        field,
+                        SINK_OMITTABLE_FIELDS.join(", ")
+                    ));
+                }
             }
-            if self.alerting.email.to.is_empty() {
-                errors.push(
-                    "alerting.email.to nu poate fi goala: adauga cel putin un destinatar"
-                        .to_string(),
-                );
+            if entry.enabled {
+                entry.kind.validation_errors(&prefix, &mut errors);
             }
         }
 
-        // Raportam toate erorile dintr-o singura data.
+        // --- Enrichment: harta de active ---
+        let asset_map = &self.enrichment.asset_map_path;
+        if !asset_map.is_empty() {
+            let ext = std::path::Path::new(asset_map)
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(str::to_ascii_lowercase);
+            if !matches!(ext.as_deref(), Some("csv" | "json")) {
+                errors.push(format!(
+                    "enrichment.asset_map_path = {:?}: extensia trebuie sa fie .csv sau .json",
+                    asset_map
+                ));
+            }
+        }
+
+        // --- Alerting: rutare IPv4/IPv6 ---
+        // Listele de destinatii accepta tipuri si numele din alerting.sinks.
+        let sink_names = self.alerting.sink_names();
+        for (family, sinks) in [
+            ("ipv4", &self.alerting.routing.ipv4),
+            ("ipv6", &self.alerting.routing.ipv6),
+            ("tor_exit", &self.alerting.routing.tor_exit),
+        ] {
+            for sink in sinks {
+                if !sink_names.contains(sink) {
+                    errors.push(format!(
+                        "alerting.routing.{} contine destinatia necunoscuta '{}' (valide: {})",
+                        family,
+                        sink,
+                        sink_names.join(", ")
+                    ));
+                }
+            }
+        }
+
+        // --- Alerting: clasificare zgomot / atac tintit ---
+        let class = &self.alerting.classification;
+        for (key, sinks) in [
+            ("noise_sinks", &class.noise_sinks),
+            ("targeted_sinks", &class.targeted_sinks),
+        ] {
+            for sink in sinks {
+                if !sink_names.contains(sink) {
+                    errors.push(format!(
+                        "alerting.classification.{} contine destinatia necunoscuta '{}' (valide: {})",
+                        key,
+                        sink,
+                        sink_names.join(", ")
+                    ));
+                }
+            }
+        }
+        for (key, list) in [
+            ("noise_sources", &class.noise_sources),
+            ("hostile_sources", &class.hostile_sources),
+        ] {
+            for entry in list {
+                if SubnetEntry::parse_host_or_cidr(entry).is_none() {
+                    errors.push(format!(
+                        "alerting.classification.{}: \"{}\" nu este un IP sau CIDR valid",
+                        key, entry
+                    ));
+                }
+            }
+        }
+        if class.selective_max_ports == 0 {
+            errors.push(
+                "alerting.classification.selective_max_ports = 0: nicio scanare nu ar fi selectiva"
+                    .to_string(),
+            );
+        }
+
+        // --- Profiluri de detectie ---
+        for (name, profile) in &self.profiles {
+            if name == DEFAULT_PROFILE {
+                errors.push(format!(
+                    "profiles.{}: numele este rezervat sectiunii [detection]",
+                    name
+                ));
+            }
+            for sink in &profile.sinks {
+                if !sink_names.contains(sink) {
+                    errors.push(format!(
+                        "profiles.{}.sinks contine destinatia necunoscuta '{}' (valide: {})",
+                        name,
+                        sink,
+                        sink_names.join(", ")
+                    ));
+                }
+            }
+        }
+        // Pragurile fiecarui profil: aceleasi reguli ca `[detection]`. Doar cand
+        // restul configuratiei este valid — altfel erorile ar aparea de doua ori.
         if errors.is_empty() {
-            Ok(())
-        } else {
-            let listing = errors
+            for (name, profile) in &self.profiles {
+                let mut candidate = self.clone();
+                candidate.profiles.clear();
+                candidate.detection = profile.detection.clone();
+                errors.extend(
+                    candidate
+                        .validation_errors()
+                        .into_iter()
+                        .map(|e| format!("profiles.{}: {}", name, e)),
+                );
+            }
+        }
+
+        // Suprapuneri intre liste: contradictiile sunt erori, restul avertismente.
+        errors.extend(self.list_overlaps().0);
+
+        errors
+    }
+}
+
+// =============================================================================
+// Suprapuneri intre listele de IP-uri / porturi
+// =============================================================================
+
+impl AppConfig {
+    /// Avertismente pentru suprapunerile ambigue dar rezolvabile (afisate la
+    /// pornire si la SIGHUP). Contradictiile sunt erori, in `validate()`.
+    pub fn overlap_warnings(&self) -> Vec<String> {
+        self.list_overlaps().1
+    }
+
+    /// Cauta aceeasi intrare in liste cu semnificatii diferite.
+    ///
+    /// Returneaza (erori, avertismente). Eroare = configuratie contradictorie,
+    /// fara o regula de rezolvare pe care operatorul sa o fi ales (acelasi
+    /// subnet cu doua etichete / doua niveluri de criticitate). Avertisment =
+    /// suprapunere rezolvata determinist, dar probabil neintentionata; mesajul
+    /// spune CUM se rezolva.
+    ///
+    /// Intrarile invalide sunt ignorate aici — sunt deja raportate de `validate()`.
+    fn list_overlaps(&self) -> (Vec<String>, Vec<String>) {
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        let det = &self.detection;
+        let exc = &det.exceptions;
+
+        // 1. Duplicate in aceeasi lista — inofensive, dar semn de copy-paste.
+        for (key, list) in [
+            ("detection.whitelist", &det.whitelist),
+            ("detection.exceptions.authorized_scanners", &exc.authorized_scanners),
+            ("detection.exceptions.authorized_beaconing_sources", &exc.authorized_beaconing_sources),
+            ("assets.critical_cidrs", &self.assets.critical_cidrs),
+            ("assets.high_cidrs", &self.assets.high_cidrs),
+        ] {
+            let nets: Vec<(&String, SubnetEntry)> = list
                 .iter()
-                .enumerate()
-                .map(|(i, e)| format!("  {}. {}", i + 1, e))
-                .collect::<Vec<_>>()
-                .join("\n");
-            anyhow::bail!(
-                "config.toml contine {} erori de configurare:\n{}",
-                errors.len(),
-                listing
-            );
+                .filter_map(|e| SubnetEntry::parse_host_or_cidr(e).map(|n| (e, n)))
+                .collect();
+            for (i, (a, na)) in nets.iter().enumerate() {
+                if let Some((b, _)) = nets[..i].iter().find(|(_, nb)| nb.same_network(na)) {
+                    warnings.push(format!(
+                        "{}: \"{}\" duplica \"{}\" — intrarea repetata nu are efect",
+                        key, a, b
+                    ));
+                }
+            }
+        }
+        for (key, ports) in [
+            ("detection.exceptions.ignore_lateral_ports", &exc.ignore_lateral_ports),
+            ("detection.exceptions.ignore_distributed_target_ports", &exc.ignore_distributed_target_ports),
+            ("detection.exceptions.ignore_beaconing_ports", &exc.ignore_beaconing_ports),
+        ] {
+            for (i, port) in ports.iter().enumerate() {
+                if ports[..i].contains(port) {
+                    warnings.push(format!("{}: portul {} apare de mai multe ori", key, port));
+                }
+            }
+        }
+
+        // 2. Acelasi subnet in ambele niveluri de criticitate: contradictie.
+        //    Subnete imbricate: rezolvat — "critical" are prioritate.
+        for critical in &self.assets.critical_cidrs {
+            let Some(nc) = SubnetEntry::parse(critical) else { continue };
+            for high in &self.assets.high_cidrs {
+                let Some(nh) = SubnetEntry::parse(high) else { continue };
+                if nc.same_network(&nh) {
+                    errors.push(format!(
+                        "assets: \"{}\" este si in critical_cidrs si in high_cidrs (\"{}\") — alegeti un singur nivel",
+                        critical, high
+                    ));
+                } else if nc.overlaps(&nh) {
+                    warnings.push(format!(
+                        "assets: critical_cidrs \"{}\" se suprapune cu high_cidrs \"{}\" — IP-urile comune sunt tratate ca critical",
+                        critical, high
+                    ));
+                }
+            }
+        }
+
+        // 3. Acelasi subnet cu doua etichete de locatie: eticheta afisata ar
+        //    depinde de ordinea (nedeterminista) a map-ului — contradictie.
+        let subnets: Vec<(&String, &String, SubnetEntry)> = self
+            .network
+            .subnets
+            .iter()
+            .filter_map(|(cidr, label)| SubnetEntry::parse(cidr).map(|n| (cidr, label, n)))
+            .collect();
+        for (i, (cidr_a, label_a, na)) in subnets.iter().enumerate() {
+            for (cidr_b, label_b, nb) in &subnets[i + 1..] {
+                if na.same_network(nb) && label_a != label_b {
+                    errors.push(format!(
+                        "network.subnets: \"{}\" (\"{}\") si \"{}\" (\"{}\") sunt acelasi subnet cu etichete diferite",
+                        cidr_a, label_a, cidr_b, label_b
+                    ));
+                }
+            }
+        }
+
+        // 4. Sursa in whitelist si in exceptii: whitelist-ul o exclude din ORICE
+        //    detectie, deci exceptia nu mai are efect.
+        let whitelist: Vec<(&String, SubnetEntry)> = det
+            .whitelist
+            .iter()
+            .filter_map(|e| SubnetEntry::parse_host_or_cidr(e).map(|n| (e, n)))
+            .collect();
+        for (key, list) in [
+            ("authorized_scanners", &exc.authorized_scanners),
+            ("authorized_beaconing_sources", &exc.authorized_beaconing_sources),
+        ] {
+            for entry in list {
+                let Some(ne) = SubnetEntry::parse_host_or_cidr(entry) else { continue };
+                if let Some((wl, _)) = whitelist.iter().find(|(_, nw)| nw.overlaps(&ne)) {
+                    warnings.push(format!(
+                        "detection.exceptions.{}: \"{}\" este acoperit de whitelist (\"{}\") — whitelist-ul are prioritate, exceptia nu are efect",
+                        key, entry, wl
+                    ));
+                }
+            }
         }
+
+        (errors, warnings)
     }
 }
 
@@ -1065,6 +4292,37 @@ impl SubnetEntry {
         }
     }
 
+    /// Ca `parse`, dar accepta si un IP simplu (tratat ca /32 sau /128) —
+    /// formatul listelor whitelist / exceptii.
+    fn parse_host_or_cidr(entry: &str) -> Option<Self> {
+        if entry.contains('/') {
+            return Self::parse(entry);
+        }
+        let ip: IpAddr = entry.parse().ok()?;
+        let prefix = if ip.is_ipv4() { 32 } else { 128 };
+        Self::parse(&format!("{}/{}", ip, prefix))
+    }
+
+    /// Acelasi subnet (aceeasi adresa de retea si acelasi prefix).
+    fn same_network(&self, other: &SubnetEntry) -> bool {
+        self.prefix_len == other.prefix_len && self.overlaps(other)
+    }
+
+    /// Cele doua subnete au cel putin un IP comun (unul il contine pe celalalt).
+    fn overlaps(&self, other: &SubnetEntry) -> bool {
+        match (&self.inner, &other.inner) {
+            (SubnetInner::V4 { network: a, mask: ma }, SubnetInner::V4 { network: b, mask: mb }) => {
+                let mask = ma & mb;
+                a & mask == b & mask
+            }
+            (SubnetInner::V6 { network: a, mask: ma }, SubnetInner::V6 { network: b, mask: mb }) => {
+                let mask = ma & mb;
+                a & mask == b & mask
+            }
+            _ => false,
+        }
+    }
+
     /// Parseaza mapping-urile din config.toml intr-o lista de SubnetEntry cu label.
     pub fn parse_subnets(raw: &HashMap<String, String>) -> Vec<SubnetEntry> {
         raw.iter()
@@ -1100,3 +4358,409 @@ impl SubnetEntry {
             .map(|entry| entry.label.clone())
     }
 }
+
+// =============================================================================
+// Teste unitare
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn net(entry: &str) -> SubnetEntry {
+        SubnetEntry::parse_host_or_cidr(entry).unwrap()
+    }
+
+    fn base_config() -> AppConfig {
+        let mut config = AppConfig::load("config.toml").unwrap();
+        config.detection.whitelist.clear();
+        config.detection.exceptions.authorized_scanners.clear();
+        config.detection.exceptions.authorized_beaconing_sources.clear();
+        config.assets.critical_cidrs.clear();
+        config.assets.high_cidrs.clear();
+        config.network.subnets.clear();
+        config
+    }
+
+    /// `config.toml` cu `extra` adaugat la sfarsit, incarcat printr-un fisier
+    /// temporar propriu testului (`tag`) — testele ruleaza in paralel.
+    fn load_with(tag: &str, extra: &str) -> Result<AppConfig> {
+        let path = std::env::temp_dir().join(format!("ids-rs-{}-{}.toml", tag, std::process::id()));
+        let base = std::fs::read_to_string("config.toml").unwrap();
+        std::fs::write(&path, format!("{}\n{}", base, extra)).unwrap();
+        let config = AppConfig::load(&path);
+        std::fs::remove_file(&path).ok();
+        config
+    }
+
+    fn profile(config: &AppConfig, sinks: &[&str]) -> ProfileConfig {
+        ProfileConfig {
+            sinks: sinks.iter().map(|s| s.to_string()).collect(),
+            detection: config.detection.clone(),
+        }
+    }
+
+    #[test]
+    fn test_parse_host_or_cidr() {
+        // IP simplu = /32 (IPv4) sau /128 (IPv6).
+        assert!(net("10.0.0.5").same_network(&net("10.0.0.5/32")));
+        assert!(net("2001:db8::1").same_network(&net("2001:db8::1/128")));
+        // Bitii de host din CIDR sunt mascati.
+        assert!(net("10.0.0.77/24").same_network(&net("10.0.0.0/24")));
+
+        assert!(SubnetEntry::parse_host_or_cidr("10.0.0.0/33").is_none());
+        assert!(SubnetEntry::parse_host_or_cidr("2001:db8::/129").is_none());
+        assert!(SubnetEntry::parse_host_or_cidr("nu-e-ip").is_none());
+    }
+
+    #[test]
+    fn test_same_network_and_overlaps_v4() {
+        let wide = net("10.0.0.0/16");
+        let narrow = net("10.0.5.0/24");
+        let other = net("10.1.0.0/24");
+
+        // Imbricate: se suprapun (in ambele sensuri), dar nu sunt acelasi subnet.
+        assert!(wide.overlaps(&narrow) && narrow.overlaps(&wide));
+        assert!(!wide.same_network(&narrow));
+        assert!(!wide.overlaps(&other));
+        // Un host din subnet se suprapune cu subnetul.
+        assert!(net("10.0.5.9").overlaps(&narrow));
+        // 0.0.0.0/0 acopera tot IPv4.
+        assert!(net("0.0.0.0/0").overlaps(&other));
+    }
+
+    #[test]
+    fn test_same_network_and_overlaps_v6() {
+        let wide = net("2001:db8::/32");
+        let narrow = net("2001:db8:1::/48");
+
+        assert!(wide.overlaps(&narrow) && narrow.overlaps(&wide));
+        assert!(!wide.same_network(&narrow));
+        assert!(narrow.same_network(&net("2001:db8:1::abcd/48")));
+        assert!(!narrow.overlaps(&net("2001:db8:2::/48")));
+        // IPv4 si IPv6 nu se suprapun niciodata, nici cu prefix 0.
+        assert!(!net("::/0").overlaps(&net("0.0.0.0/0")));
+    }
+
+    #[test]
+    fn test_overlap_critical_and_high_same_cidr_is_error() {
+        let mut config = base_config();
+        config.assets.critical_cidrs = vec!["10.0.5.0/24".into()];
+        config.assets.high_cidrs = vec!["10.0.5.0/24".into()];
+
+        let (errors, _) = config.list_overlaps();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("si in critical_cidrs si in high_cidrs"), "{}", errors[0]);
+        assert!(config.validation_errors().iter().any(|e| e == &errors[0]));
+    }
+
+    #[test]
+    fn test_overlap_critical_and_high_nested_is_warning() {
+        let mut config = base_config();
+        config.assets.critical_cidrs = vec!["10.0.5.0/24".into()];
+        config.assets.high_cidrs = vec!["10.0.0.0/16".into()];
+
+        let (errors, warnings) = config.list_overlaps();
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("tratate ca critical"), "{}", warnings[0]);
+    }
+
+    #[test]
+    fn test_overlap_subnet_label_conflict_is_error() {
+        let mut config = base_config();
+        // Acelasi subnet, scris diferit (bitii de host), cu etichete diferite.
+        config.network.subnets.insert("10.0.5.0/24".into(), "Etaj 1".into());
+        config.network.subnets.insert("10.0.5.1/24".into(), "Etaj 2".into());
+
+        let (errors, _) = config.list_overlaps();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("network.subnets:"), "{}", errors[0]);
+
+        // Aceeasi eticheta: nu e contradictie.
+        config.network.subnets.insert("10.0.5.1/24".into(), "Etaj 1".into());
+        assert!(config.list_overlaps().0.is_empty());
+    }
+
+    #[test]
+    fn test_overlap_duplicates_and_whitelisted_exceptions_are_warnings() {
+        let mut config = base_config();
+        config.detection.whitelist = vec!["10.0.0.0/16".into(), "10.0.0.0/16".into()];
+        config.detection.exceptions.authorized_scanners = vec!["10.0.9.9".into()];
+        config.detection.exceptions.ignore_lateral_ports = vec![445, 445];
+
+        let (errors, warnings) = config.list_overlaps();
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert!(warnings.iter().any(|w| w.starts_with("detection.whitelist:")));
+        assert!(warnings.iter().any(|w| w.contains("portul 445")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.starts_with("detection.exceptions.authorized_scanners:")));
+        assert_eq!(config.overlap_warnings(), warnings);
+    }
+
+    #[test]
+    fn test_startup_peek_before_full_load() {
+        let path = std::env::temp_dir().join(format!("ids-rs-startup-{}.toml", std::process::id()));
+
+        // secrets_file lipseste inca (asteptat): load() esueaza, peek() nu.
+        std::fs::write(
+            &path,
+            "secrets_file = \"/nu/exista/ids-rs.env\"\n\n[startup]\nwait_for = [\"file:/nu/exista/ids-rs.env\"]\ntimeout_secs = 5\n",
+        )
+        .unwrap();
+        let startup = StartupConfig::peek(&path).unwrap();
+        assert_eq!(startup.wait_for, vec!["file:/nu/exista/ids-rs.env"]);
+        assert_eq!(startup.timeout_secs, 5);
+        assert_eq!(startup.on_timeout, "fail");
+        assert!(AppConfig::load(&path).is_err());
+
+        // Fara [startup]: valorile implicite, pornire imediata.
+        std::fs::write(&path, "[network]\nlisten_port = 5555\n").unwrap();
+        assert!(StartupConfig::peek(&path).unwrap().wait_for.is_empty());
+
+        // Intrari invalide si referinte ${...} sunt respinse inainte de asteptare.
+        std::fs::write(&path, "[startup]\nwait_for = [\"tcp:siem.local\"]\n").unwrap();
+        assert!(StartupConfig::peek(&path).is_err());
+        std::fs::write(&path, "[startup]\nwait_for = [\"tcp:${SIEM_HOST}:514\"]\n").unwrap();
+        assert!(StartupConfig::peek(&path).is_err());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_source_port_exhaustion_bounded_by_max_hits() {
+        let mut config = base_config();
+        config.detection.source_port_exhaustion.enabled = true;
+        config.detection.source_port_exhaustion.min_source_ports = 100;
+        config.detection.max_hits_per_ip = 100;
+        assert!(!config.validation_errors().iter().any(|e| e.contains("min_source_ports")));
+
+        config.detection.max_hits_per_ip = 99;
+        assert!(config
+            .validation_errors()
+            .iter()
+            .any(|e| e.contains("min_source_ports = 100 depaseste detection.max_hits_per_ip = 99")));
+    }
+
+    #[test]
+    fn test_overlap_ignores_invalid_entries() {
+        let mut config = base_config();
+        config.assets.critical_cidrs = vec!["nu-e-cidr".into()];
+        config.assets.high_cidrs = vec!["nu-e-cidr".into()];
+        config.network.subnets.insert("999.0.0.0/8".into(), "A".into());
+
+        // Raportate de validate(), nu de list_overlaps().
+        let (errors, warnings) = config.list_overlaps();
+        assert!(errors.is_empty() && warnings.is_empty());
+    }
+
+    #[test]
+    fn test_profile_overlay_inherits_omitted_keys() {
+        let config = load_with(
+            "profile-overlay",
+            "[profiles.paging]\nsinks = [\"email\"]\n[profiles.paging.detection.fast_scan]\nport_threshold = 100\n\n[profiles.feed]\n",
+        )
+        .unwrap();
+
+        let paging = &config.profiles["paging"];
+        assert_eq!(paging.sinks, ["email"]);
+        assert_eq!(paging.detection.fast_scan.port_threshold, 100);
+        // Cheile omise din tabela imbricata vin din [detection.fast_scan] ...
+        assert_eq!(
+            paging.detection.fast_scan.time_window_secs,
+            config.detection.fast_scan.time_window_secs
+        );
+        // ... iar tabelele neatinse raman integral ale [detection].
+        assert_eq!(
+            paging.detection.slow_scan.port_threshold,
+            config.detection.slow_scan.port_threshold
+        );
+        assert_eq!(paging.detection.whitelist, config.detection.whitelist);
+
+        // Profil fara suprapunere si fara `sinks`: [detection] si toate sink-urile.
+        let feed = &config.profiles["feed"];
+        assert_eq!(feed.detection.policy_version(), config.detection.policy_version());
+        assert_eq!(feed.sinks, ALERT_SINKS);
+    }
+
+    #[test]
+    fn test_cleanup_every_n_packets_minimum() {
+        let mut config = base_config();
+        assert_eq!(config.detection.cleanup_every_n_packets, 0, "implicit: doar timer");
+        for ok in [0, 1000, 50_000] {
+            config.detection.cleanup_every_n_packets = ok;
+            assert!(config.validation_errors().is_empty(), "{}", ok);
+        }
+        config.detection.cleanup_every_n_packets = 999;
+        assert_eq!(
+            config.validation_errors(),
+            vec!["detection.cleanup_every_n_packets (999) prea mic: minim 1000 (0 = dezactivat)"]
+        );
+    }
+
+    #[test]
+    fn test_ingress_label_defaults_to_listener() {
+        let mut config = base_config();
+        config.network.ingress_name.clear();
+        assert_eq!(
+            config.network.ingress_label(),
+            format!("udp/{}:{}", config.network.listen_address, config.network.listen_port)
+        );
+
+        config.network.ingress_name = "tap-dmz".to_string();
+        assert_eq!(config.network.ingress_label(), "tap-dmz");
+        assert!(config.validation_errors().is_empty());
+
+        config.network.ingress_name = "tap\ndmz".to_string();
+        assert_eq!(
+            config.validation_errors(),
+            vec!["network.ingress_name = \"tap\\ndmz\" contine caractere de control"]
+        );
+    }
+
+    #[test]
+    fn test_profile_validation_errors() {
+        let mut config = base_config();
+        config.profiles.insert(DEFAULT_PROFILE.to_string(), profile(&config, &["siem"]));
+        assert!(config
+            .validation_errors()
+            .iter()
+            .any(|e| e == "profiles.default: numele este rezervat sectiunii [detection]"));
+
+        let mut config = base_config();
+        config.profiles.insert("paging".to_string(), profile(&config, &["pager"]));
+        let errors = config.validation_errors();
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(
+            errors[0].starts_with("profiles.paging.sinks contine destinatia necunoscuta 'pager'"),
+            "{}",
+            errors[0]
+        );
+
+        // Pragurile profilului trec prin aceleasi reguli ca [detection].
+        let mut config = base_config();
+        let mut paging = profile(&config, &["email"]);
+        paging.detection.fast_scan.port_threshold = 0;
+        config.profiles.insert("paging".to_string(), paging);
+        let errors = config.validation_errors();
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("profiles.paging: detection.fast_scan.port_threshold = 0")),
+            "{:?}",
+            errors
+        );
+        assert!(!errors.iter().any(|e| e.starts_with("detection.")), "{:?}", errors);
+    }
+
+    /// Tabela `[detection]` minima pentru `normalize_windows`.
+    fn detection_toml(text: &str) -> toml::Value {
+        toml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_normalize_windows_to_legacy_fields() {
+        let mut detection = detection_toml(
+            "[fast_scan]\nwindow = \"10s\"\n[slow_scan]\nwindow = \"5m\"\n[beaconing]\nwindow = \"1h\"\n",
+        );
+        let mut deprecations = Vec::new();
+        normalize_windows(&mut detection, "detection", &mut deprecations).unwrap();
+        assert!(deprecations.is_empty(), "{:?}", deprecations);
+
+        let field = |tier: &str, key: &str| detection[tier][key].as_integer();
+        assert_eq!(field("fast_scan", "time_window_secs"), Some(10));
+        assert_eq!(field("slow_scan", "time_window_mins"), Some(5));
+        assert_eq!(field("beaconing", "time_window_secs"), Some(3600));
+        assert!(detection["fast_scan"].get("window").is_none(), "`window` este consumat");
+    }
+
+    #[test]
+    fn test_normalize_windows_rejects_invalid_windows() {
+        let normalize = |text: &str| {
+            normalize_windows(&mut detection_toml(text), "detection", &mut Vec::new())
+                .map_err(|e| format!("{:#}", e))
+        };
+
+        let err = normalize("[slow_scan]\nwindow = \"90s\"\n").unwrap_err();
+        assert!(err.contains("minute intregi"), "{}", err);
+
+        let err = normalize("[fast_scan]\nwindow = \"10s\"\ntime_window_secs = 10\n").unwrap_err();
+        assert!(err.starts_with("detection.fast_scan: `window` si `time_window_secs` impreuna"), "{}", err);
+
+        let err = normalize("[flood]\nwindow = 10\n").unwrap_err();
+        assert!(err.starts_with("detection.flood.window trebuie sa fie text"), "{}", err);
+
+        assert!(normalize("[fast_scan]\nwindow = \"zece\"\n").is_err());
+    }
+
+    #[test]
+    fn test_legacy_window_fields_deprecated_with_suggestion() {
+        let mut detection = detection_toml(
+            "[slow_scan]\ntime_window_mins = 5\n[beaconing]\ntime_window_secs = 7200\n[flood]\ntime_window_secs = 90\n",
+        );
+        let mut deprecations = Vec::new();
+        normalize_windows(&mut detection, "detection", &mut deprecations).unwrap();
+        assert_eq!(deprecations.len(), 3);
+        // Ordinea din WINDOW_TIERS: slow_scan, flood, beaconing.
+        assert!(deprecations[0].starts_with("detection.slow_scan.time_window_mins este depreciat — foloseste window = \"5m\""));
+        assert!(deprecations[1].contains("window = \"90s\""), "{}", deprecations[1]);
+        assert!(deprecations[2].contains("window = \"2h\""), "{}", deprecations[2]);
+        // Valoarea veche ramane activa.
+        assert_eq!(detection["slow_scan"]["time_window_mins"].as_integer(), Some(5));
+
+        assert_eq!(human_duration(300), "5m");
+        assert_eq!(human_duration(3600), "1h");
+        assert_eq!(human_duration(90), "90s");
+        assert_eq!(human_duration(0), "0s");
+    }
+
+    #[test]
+    fn test_profile_windows_normalized() {
+        let config = load_with(
+            "profile-windows",
+            "[profiles.paging.detection.slow_scan]\nwindow = \"10m\"\n\n[profiles.legacy.detection.fast_scan]\ntime_window_secs = 30\n",
+        )
+        .unwrap();
+        assert_eq!(config.profiles["paging"].detection.slow_scan.time_window_mins, 10);
+        assert_eq!(config.detection.slow_scan.time_window_mins, 5);
+        assert_eq!(config.profiles["legacy"].detection.fast_scan.time_window_secs, 30);
+        assert!(config
+            .deprecations
+            .iter()
+            .any(|d| d.starts_with("profiles.legacy.detection.fast_scan.time_window_secs este depreciat")));
+
+        let err = load_with(
+            "profile-windows-bad",
+            "[profiles.paging.detection.slow_scan]\nwindow = \"90s\"\n",
+        )
+        .unwrap_err();
+        assert!(format!("{:#}", err).starts_with("profiles.paging.detection.slow_scan.window"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_window_tiers_cover_every_windowed_rule() {
+        // Orice regula noua cu `time_window_*` trebuie listata, altfel
+        // `window = "..."` ar fi ignorat tacut in tabela ei.
+        let detection = serde_json::to_value(&base_config().detection).unwrap();
+        let mut windowed: Vec<(String, String)> = Vec::new();
+        for (tier, table) in detection.as_object().unwrap() {
+            let Some(table) = table.as_object() else {
+                continue;
+            };
+            for key in table.keys().filter(|k| k.starts_with("time_window_")) {
+                windowed.push((tier.clone(), key.clone()));
+            }
+        }
+        assert!(!windowed.is_empty());
+        for (tier, key) in &windowed {
+            assert!(
+                WINDOW_TIERS.contains(&(tier.as_str(), key.as_str())),
+                "[detection.{}] are `{}` dar lipseste din WINDOW_TIERS",
+                tier,
+                key
+            );
+        }
+        assert_eq!(windowed.len(), WINDOW_TIERS.len(), "intrari WINDOW_TIERS fara regula");
+    }
+}
@@ -0,0 +1,331 @@
+// =============================================================================
+// control.rs - Socket de Control Local (control.socket_path)
+// =============================================================================
+//
+// In timpul unui incident, responderul vrea sa stranga pragurile IMEDIAT,
+// fara editarea config.toml si fara SIGHUP. Socket-ul de control este un
+// socket Unix local (permisiuni 600) cu un protocol text, o comanda per linie:
+//
+//   get-thresholds
+//     → {"policy_version":"a1b2c3d4","thresholds":{"fast_scan":{...},...}}
+//   set-threshold fast_scan port_threshold 8
+//     → OK fast_scan.port_threshold: 15 → 8 (politica a1b2c3d4 → 9f8e7d6c)
+//     → ERR <motiv>
+//
+// Exemplu:  echo "get-thresholds" | socat - UNIX-CONNECT:/run/ids-rs/control.sock
+//
+// `set-threshold` (opt-in, `control.allow_threshold_updates`) aplica modificarea
+// exact ca `PUT /config/detection` din dashboard: validata cu regulile din
+// config.toml (in contextul configuratiei active, dupa ultimul SIGHUP),
+// aplicata si textului alertelor, inlocuita atomic (ArcSwap — starea de tracking ramane intacta),
+// jurnalizata AUDIT. Modificarea este EFEMERA: un SIGHUP sau un restart revin
+// la config.toml — pentru persistare, valoarea trebuie scrisa si in fisier.
+//
+// Doar detectorul implicit (`[detection]`) este ajustat; profilurile
+// `[profiles.<nume>]` raman neschimbate.
+//
+// =============================================================================
+
+use crate::alerter::Alerter;
+use crate::config::{AppConfig, DetectionConfig};
+use crate::detector::Detector;
+use crate::display;
+use anyhow::Context;
+use arc_swap::ArcSwap;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+/// Pragurile ajustabile: sectiunea din `[detection]` si campurile ei numerice.
+const THRESHOLDS: [(&str, &[&str]); 9] = [
+    ("fast_scan", &["port_threshold", "time_window_secs"]),
+    ("slow_scan", &["port_threshold", "time_window_mins"]),
+    ("accept_scan", &["port_threshold", "time_window_secs"]),
+    ("lateral_movement", &["unique_dest_threshold", "time_window_secs"]),
+    ("distributed_scan", &["unique_sources_threshold", "time_window_secs"]),
+    ("coordinated_subnet", &["min_sources", "port_threshold", "time_window_secs"]),
+    ("flood", &["packets_per_sec_threshold", "time_window_secs"]),
+    ("slow_sweep", &["unique_dest_threshold", "time_window_secs"]),
+    ("malformed_input", &["threshold", "time_window_secs"]),
+];
+
+const USAGE: &str = "comenzi: get-thresholds | set-threshold <sectiune> <camp> <valoare>";
+
+/// Stare partajata intre conexiunile socket-ului.
+struct ControlState {
+    detector: Arc<Detector>,
+    /// Primeste pragurile noi odata cu detectorul (textul alertelor).
+    alerter: Arc<Alerter>,
+    /// Configuratia activa (inlocuita la fiecare SIGHUP) — context pentru
+    /// validare, ca la `PUT /config/detection` (verificarile incrucisate
+    /// detection ↔ cleanup).
+    config: Arc<ArcSwap<AppConfig>>,
+    allow_threshold_updates: bool,
+}
+
+/// Creeaza socket-ul si porneste task-ul care accepta conexiuni.
+///
+/// `live_config` este configuratia activa, inlocuita de bucla principala la
+/// fiecare SIGHUP; setarile socket-ului sunt citite o data, aici.
+pub async fn start_control_socket(
+    live_config: Arc<ArcSwap<AppConfig>>,
+    detector: Arc<Detector>,
+    alerter: Arc<Alerter>,
+) -> anyhow::Result<tokio::task::JoinHandle<()>> {
+    let app_config = live_config.load_full();
+    let path = &app_config.control.socket_path;
+
+    // Un socket ramas de la o rulare anterioara (oprire bruta) blocheaza
+    // bind-ul. Stergem doar socket-uri — niciodata un fisier obisnuit.
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if !meta.file_type().is_socket() {
+            anyhow::bail!("control.socket_path {:?} exista si nu este socket", path);
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("Nu pot sterge socket-ul vechi {:?}", path))?;
+    }
+    let listener = UnixListener::bind(path)
+        .with_context(|| format!("Nu pot crea socket-ul de control {:?}", path))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .with_context(|| format!("Nu pot seta permisiunile 600 pe {:?}", path))?;
+
+    display::log_info(&format!(
+        "Socket de control activ: {} (set-threshold {})",
+        path,
+        if app_config.control.allow_threshold_updates {
+            "permis"
+        } else {
+            "dezactivat"
+        }
+    ));
+
+    let state = Arc::new(ControlState {
+        detector,
+        alerter,
+        allow_threshold_updates: app_config.control.allow_threshold_updates,
+        config: live_config,
+    });
+    let handle = tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(handle_client(stream, Arc::clone(&state)));
+                }
+                Err(e) => {
+                    display::log_error(&format!("Socket de control: accept esuat: {}", e));
+                }
+            }
+        }
+    });
+    Ok(handle)
+}
+
+/// O conexiune: raspunde fiecarei linii pana la inchiderea clientului.
+async fn handle_client(stream: UnixStream, state: Arc<ControlState>) {
+    // UID-ul clientului (SO_PEERCRED) pentru jurnalul de audit.
+    let peer = stream
+        .peer_cred()
+        .map(|cred| format!("uid {}", cred.uid()))
+        .unwrap_or_else(|_| "uid ?".to_string());
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = execute(&line, &state, &peer);
+        if write.write_all(format!("{}\n", reply).as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Executa o comanda si intoarce raspunsul (o linie).
+fn execute(line: &str, state: &ControlState, peer: &str) -> String {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["get-thresholds"] => serde_json::json!({
+            "policy_version": state.detector.policy_version(),
+            "thresholds": thresholds_json(&state.detector.config()),
+        })
+        .to_string(),
+        ["set-threshold", tier, field, value] => {
+            match set_threshold(state, tier, field, value, peer) {
+                Ok(msg) => format!("OK {}", msg),
+                Err(e) => format!("ERR {}", e),
+            }
+        }
+        _ => format!("ERR comanda necunoscuta {:?} ({})", line.trim(), USAGE),
+    }
+}
+
+/// Valorile curente ale pragurilor ajustabile, grupate pe sectiuni.
+fn thresholds_json(cfg: &DetectionConfig) -> serde_json::Value {
+    let full = serde_json::to_value(cfg).unwrap_or_default();
+    let mut out = serde_json::Map::new();
+    for (tier, fields) in THRESHOLDS {
+        let mut section = serde_json::Map::new();
+        for field in fields {
+            if let Some(value) = full.get(tier).and_then(|t| t.get(field)) {
+                section.insert(field.to_string(), value.clone());
+            }
+        }
+        out.insert(tier.to_string(), section.into());
+    }
+    out.into()
+}
+
+/// Valideaza si aplica `set-threshold`; intoarce descrierea modificarii.
+fn set_threshold(
+    state: &ControlState,
+    tier: &str,
+    field: &str,
+    value: &str,
+    peer: &str,
+) -> Result<String, String> {
+    if !state.allow_threshold_updates {
+        return Err(
+            "ajustarea pragurilor este dezactivata (control.allow_threshold_updates = false)"
+                .to_string(),
+        );
+    }
+    let (patched, old) = patch_threshold(&state.detector.config(), tier, field, value)?;
+    // Aceleasi reguli ca la pornire; mesajul multi-linie devine o singura linie.
+    let validated = state
+        .config
+        .load()
+        .validate_detection(patched)
+        .map_err(|e| format!("{:#}", e).replace('\n', " "))?;
+
+    let old_policy = state.detector.policy_version();
+    state.detector.update_config(validated.clone());
+    state.alerter.update_detection(validated);
+    let new_policy = state.detector.policy_version();
+    let change = format!(
+        "{}.{}: {} → {} (politica {} → {})",
+        tier, field, old, value, old_policy, new_policy
+    );
+    display::log_reload(&format!(
+        "AUDIT: socket de control set-threshold de la {} — {} (doar in memorie)",
+        peer, change
+    ));
+    Ok(change)
+}
+
+/// Copia configurarii cu un singur prag schimbat. Intoarce si valoarea veche.
+///
+/// Sunt acceptate doar perechile din `THRESHOLDS`, cu valori intregi pozitive;
+/// verificarile incrucisate (ex: fereastra Slow Scan fata de cleanup) raman
+/// in `AppConfig::validate_detection`.
+fn patch_threshold(
+    cfg: &DetectionConfig,
+    tier: &str,
+    field: &str,
+    value: &str,
+) -> Result<(DetectionConfig, u64), String> {
+    let Some((_, fields)) = THRESHOLDS.iter().find(|(t, _)| *t == tier) else {
+        return Err(format!(
+            "sectiune necunoscuta {:?} (valide: {})",
+            tier,
+            THRESHOLDS.map(|(t, _)| t).join(", ")
+        ));
+    };
+    if !fields.contains(&field) {
+        return Err(format!(
+            "camp necunoscut {:?} pentru {} (valide: {})",
+            field,
+            tier,
+            fields.join(", ")
+        ));
+    }
+    let new_value: u64 = value
+        .parse()
+        .map_err(|_| format!("valoare invalida {:?}: numar intreg pozitiv", value))?;
+    if new_value == 0 {
+        return Err(format!("{}.{} = 0 face regula inutilizabila", tier, field));
+    }
+
+    let mut json = serde_json::to_value(cfg).map_err(|e| e.to_string())?;
+    let slot = json
+        .get_mut(tier)
+        .and_then(|t| t.get_mut(field))
+        .ok_or_else(|| format!("{}.{} lipseste din configurare", tier, field))?;
+    let old = slot.as_u64().unwrap_or(0);
+    *slot = new_value.into();
+    let patched = serde_json::from_value(json).map_err(|e| e.to_string())?;
+    Ok((patched, old))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_threshold_validates_input() {
+        let cfg: DetectionConfig = toml::from_str(
+            r#"
+            alert_cooldown_secs = 300
+            [fast_scan]
+            port_threshold = 15
+            time_window_secs = 10
+            [slow_scan]
+            port_threshold = 30
+            time_window_mins = 5
+            "#,
+        )
+        .unwrap();
+
+        let (patched, old) = patch_threshold(&cfg, "fast_scan", "port_threshold", "8").unwrap();
+        assert_eq!(old, 15);
+        assert_eq!(patched.fast_scan.port_threshold, 8);
+        // Restul configurarii este neatins.
+        assert_eq!(patched.fast_scan.time_window_secs, 10);
+        assert_eq!(patched.slow_scan.port_threshold, 30);
+        assert_eq!(patched.alert_cooldown_secs, 300);
+
+        let (patched, _) = patch_threshold(&cfg, "slow_scan", "time_window_mins", "2").unwrap();
+        assert_eq!(patched.slow_scan.time_window_mins, 2);
+
+        assert!(patch_threshold(&cfg, "turbo_scan", "port_threshold", "8").is_err());
+        assert!(patch_threshold(&cfg, "fast_scan", "alert_cooldown_secs", "8").is_err());
+        assert!(patch_threshold(&cfg, "fast_scan", "port_threshold", "0").is_err());
+        assert!(patch_threshold(&cfg, "fast_scan", "port_threshold", "-3").is_err());
+        assert!(patch_threshold(&cfg, "fast_scan", "port_threshold", "multe").is_err());
+
+        let json = thresholds_json(&cfg);
+        assert_eq!(json["fast_scan"]["port_threshold"], 15);
+        assert_eq!(json["slow_scan"]["time_window_mins"], 5);
+    }
+
+    #[tokio::test]
+    async fn test_set_threshold_validates_against_live_config() {
+        let mut config = AppConfig::load("config.toml").unwrap();
+        config.cleanup.max_entry_age_secs = 600;
+        config.detection.slow_scan.carry_over = 0.0;
+        let alerter = Alerter::new(
+            config.alerting.clone(),
+            config.detection.clone(),
+            Default::default(),
+            Vec::new(),
+            Arc::new(crate::metrics::LastErrors::new()),
+        )
+        .unwrap();
+        let state = ControlState {
+            detector: Arc::new(Detector::new(config.detection.clone())),
+            alerter: Arc::new(alerter),
+            config: Arc::new(ArcSwap::from_pointee(config.clone())),
+            allow_threshold_updates: true,
+        };
+
+        // Fereastra Slow Scan de 20 minute depaseste cleanup-ul de 600s.
+        assert!(set_threshold(&state, "slow_scan", "time_window_mins", "20", "uid 0").is_err());
+
+        // SIGHUP cu cleanup marit: validarea vede config-ul nou.
+        config.cleanup.max_entry_age_secs = 3600;
+        state.config.store(Arc::new(config));
+        set_threshold(&state, "slow_scan", "time_window_mins", "20", "uid 0").unwrap();
+        assert_eq!(state.detector.config().slow_scan.time_window_mins, 20);
+        assert_eq!(state.alerter.detection().slow_scan.time_window_mins, 20);
+    }
+}
@@ -0,0 +1,262 @@
+// =============================================================================
+// correlation.rs - Corelare Alerte (alerting.correlation_secs)
+// =============================================================================
+//
+// O scanare logica poate cobori momentan sub prag si reveni peste el, iar
+// un scanner in rafale poate produce doua alerte la cateva secunde distanta.
+// Stratul de corelare se afla INTRE detectie si etapele de afisare / buffer
+// web / trimitere (SIEM, email, GELF):
+//
+//   Detector → [FP feedback] → Correlator (hold N s) → consola, dashboard, sink-uri
+//
+// Prima alerta pentru o pereche (IP sursa, tip) este retinuta `correlation_secs`
+// (separat pentru fiecare profil de detectie).
+// Alertele cu aceeasi pereche sosite in acest interval sunt contopite in ea
+// (reuniunea porturilor); la expirare se emite O SINGURA alerta, cu
+// `merged_count` = numarul alertelor contopite.
+//
+// COST: latenta. Orice alerta corelata ajunge la operator / SIEM cu pana la
+// `correlation_secs` (+ ~1s granularitatea tick-ului) mai tarziu decat fara
+// corelare. Tipurile care nu au lista de porturi (Lateral Movement,
+// Distributed Scan, Beaconing, Saturation) trec imediat, necorelate.
+//
+// =============================================================================
+
+use crate::detector::{Alert, ScanType};
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+/// O alerta retinuta, in asteptarea altora cu aceeasi pereche (IP, tip).
+struct Held {
+    alert: Alert,
+    first_at: Instant,
+    merged: usize,
+}
+
+pub struct AlertCorrelator {
+    hold: Duration,
+    /// Vec (nu HashMap): alertele retinute simultan sunt putine, iar ordinea
+    /// de sosire se pastreaza la emitere.
+    held: Vec<Held>,
+    /// `alerting.summary_template`: rezumatul unei alerte contopite este
+    /// re-randat (numarul de porturi s-a schimbat). Gol = neatins.
+    summary_template: String,
+}
+
+/// Doar regulile pe porturi (Fast / Slow / Accept Scan) se coreleaza.
+fn correlatable(scan_type: &ScanType) -> bool {
+    matches!(scan_type, ScanType::Fast | ScanType::Slow | ScanType::AcceptScan)
+}
+
+/// Profilurile de detectie nu se amesteca: fiecare are sink-urile proprii.
+fn same_key(a: &Alert, b: &Alert) -> bool {
+    a.source_ip == b.source_ip
+        && std::mem::discriminant(&a.scan_type) == std::mem::discriminant(&b.scan_type)
+        && a.profile == b.profile
+}
+
+impl AlertCorrelator {
+    pub fn new(hold_secs: u64, summary_template: &str) -> Self {
+        Self {
+            hold: Duration::from_secs(hold_secs),
+            held: Vec::new(),
+            summary_template: summary_template.to_string(),
+        }
+    }
+
+    pub fn set_hold(&mut self, hold_secs: u64) {
+        self.hold = Duration::from_secs(hold_secs);
+    }
+
+    pub fn set_summary_template(&mut self, summary_template: &str) {
+        self.summary_template = summary_template.to_string();
+    }
+
+    /// Primeste o alerta proaspata. Returneaza alerta inapoi daca nu se
+    /// coreleaza (trebuie emisa imediat); altfel o retine sau o contopeste.
+    pub fn push(&mut self, alert: Alert, now: Instant) -> Option<Alert> {
+        if !correlatable(&alert.scan_type) {
+            return Some(alert);
+        }
+        match self
+            .held
+            .iter_mut()
+            .find(|h| same_key(&h.alert, &alert))
+        {
+            Some(held) => {
+                merge_into(&mut held.alert, alert);
+                held.merged += 1;
+            }
+            None => self.held.push(Held {
+                alert,
+                first_at: now,
+                merged: 1,
+            }),
+        }
+        None
+    }
+
+    /// Alertele a caror fereastra de corelare a expirat, gata de emis.
+    pub fn take_due(&mut self, now: Instant) -> Vec<Alert> {
+        let hold = self.hold;
+        let (due, keep): (Vec<Held>, Vec<Held>) = std::mem::take(&mut self.held)
+            .into_iter()
+            .partition(|h| now.saturating_duration_since(h.first_at) >= hold);
+        self.held = keep;
+        due.into_iter().map(|h| self.finish(h)).collect()
+    }
+
+    /// Toate alertele retinute (la oprire sau la dezactivare prin SIGHUP).
+    pub fn drain(&mut self) -> Vec<Alert> {
+        std::mem::take(&mut self.held)
+            .into_iter()
+            .map(|h| self.finish(h))
+            .collect()
+    }
+
+    fn finish(&self, held: Held) -> Alert {
+        let mut alert = held.alert;
+        if held.merged > 1 {
+            alert.merged_count = Some(held.merged);
+            if !self.summary_template.is_empty() {
+                alert.summary = alert.render_summary(&self.summary_template);
+            }
+        }
+        alert
+    }
+}
+
+/// Contopeste `new` in `base`: reuniunea porturilor si a protocoalelor,
+/// indicatorii cumulati.
+/// Identitatea (id, timestamp, time_to_threshold) ramane cea a primei alerte.
+fn merge_into(base: &mut Alert, new: Alert) {
+    let ports: BTreeSet<u16> = base
+        .unique_ports
+        .iter()
+        .chain(new.unique_ports.iter())
+        .copied()
+        .collect();
+    // Numar aproximativ (max_ports_per_ip): pastram cel mai mare total vazut.
+    base.approx_port_count = match (base.approx_port_count, new.approx_port_count) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0).max(b.unwrap_or(0)).max(ports.len())),
+    };
+    base.unique_ports = ports.into_iter().collect();
+    // Scorul ponderat al reuniunii nu se poate recalcula aici (ponderile si
+    // sondele goale nu sunt in alerta): pastram cel mai mare scor vazut — o
+    // limita inferioara, ca `approx_port_count`.
+    base.weighted_score = max_score(base.weighted_score, new.weighted_score);
+    // Contextul deciziei descrie alerta contopita: porturile reunite, nu doar
+    // ale primei detectii. Pragul, fereastra si timpul pana la prag raman.
+    let observed = base.port_count();
+    if let Some(ref mut ctx) = base.detection_context {
+        ctx.observed = observed;
+        ctx.weighted_count = max_score(
+            ctx.weighted_count,
+            new.detection_context.and_then(|c| c.weighted_count),
+        );
+    }
+    base.empty_probes |= new.empty_probes;
+    base.promoted_from_slow |= new.promoted_from_slow;
+    let protocols: BTreeSet<String> = base.protocols.drain(..).chain(new.protocols).collect();
+    base.protocols = protocols.into_iter().collect();
+    base.multi_protocol = base.protocols.len() > 1;
+    base.source_port_exhaustion = base.source_port_exhaustion.or(new.source_port_exhaustion);
+    base.asset_criticality = base.asset_criticality.max(new.asset_criticality);
+    base.false_positive &= new.false_positive;
+    if base.dest_ip.is_none() {
+        base.dest_ip = new.dest_ip;
+    }
+    if base.interface.is_empty() {
+        base.interface = new.interface;
+        base.vlan = new.vlan;
+    }
+}
+
+/// Cel mai mare dintre doua scoruri optionale.
+fn max_score(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fast(ip: &str, ports: &[u16]) -> Alert {
+        Alert {
+            unique_ports: ports.to_vec(),
+            ..Alert::new(ScanType::Fast, ip.parse().unwrap(), None)
+        }
+    }
+
+    #[test]
+    fn test_correlation_merges_ports_and_holds() {
+        let mut c = AlertCorrelator::new(5, "{type} de la {source_ip} — {detail}");
+        let t0 = Instant::now();
+
+        assert!(c.push(fast("10.0.0.1", &[22, 80]), t0).is_none());
+        assert!(c.push(fast("10.0.0.1", &[80, 443]), t0 + Duration::from_secs(2)).is_none());
+        assert!(c.push(fast("10.0.0.2", &[25]), t0 + Duration::from_secs(3)).is_none());
+        // Acelasi IP si tip, alt profil de detectie: retinut separat.
+        let paging = Alert {
+            profile: "paging".to_string(),
+            ..fast("10.0.0.1", &[3389])
+        };
+        assert!(c.push(paging, t0 + Duration::from_secs(3)).is_none());
+        // Alt tip (Lateral Movement) nu se coreleaza — trece imediat.
+        let lateral = Alert::new(ScanType::LateralMovement, "10.0.0.1".parse().unwrap(), None);
+        assert!(c.push(lateral, t0).is_some());
+
+        // Inainte de expirarea ferestrei nu se emite nimic.
+        assert!(c.take_due(t0 + Duration::from_secs(4)).is_empty());
+
+        let due = c.take_due(t0 + Duration::from_secs(5));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].profile, "");
+        assert_eq!(due[0].unique_ports, vec![22, 80, 443]);
+        assert_eq!(due[0].merged_count, Some(2));
+        // Rezumatul reflecta porturile contopite, nu doar prima alerta.
+        assert_eq!(due[0].summary, "Fast Scan de la 10.0.0.1 — 3 porturi");
+
+        // A doua sursa: alerta singulara, fara merged_count.
+        let rest = c.drain();
+        assert_eq!(rest.len(), 2);
+        assert_eq!(rest[0].merged_count, None);
+        assert_eq!(rest[1].profile, "paging");
+        assert_eq!(rest[1].unique_ports, vec![3389]);
+    }
+
+    #[test]
+    fn test_merge_updates_detection_context() {
+        use crate::detector::DetectionContext;
+
+        let with_context = |ports: &[u16], weighted: Option<f64>| Alert {
+            weighted_score: weighted,
+            detection_context: Some(DetectionContext {
+                rule: "fast_scan",
+                observed: ports.len(),
+                threshold: 2,
+                unit: "porturi",
+                window_secs: 10,
+                weighted_count: weighted,
+                time_to_threshold_ms: Some(1500),
+            }),
+            ..fast("10.0.0.1", ports)
+        };
+        let mut c = AlertCorrelator::new(5, "{type}");
+        let t0 = Instant::now();
+        assert!(c.push(with_context(&[22, 80], Some(3.0)), t0).is_none());
+        assert!(c.push(with_context(&[80, 443, 1521], Some(12.0)), t0).is_none());
+
+        let due = c.drain();
+        let ctx = due[0].detection_context.as_ref().unwrap();
+        // Porturile reunite (22, 80, 443, 1521), nu doar cele ale primei detectii.
+        assert_eq!(ctx.observed, 4);
+        assert_eq!(ctx.weighted_count, Some(12.0));
+        assert_eq!(due[0].weighted_score, Some(12.0));
+        assert_eq!(ctx.time_to_threshold_ms, Some(1500));
+    }
+}
@@ -0,0 +1,173 @@
+// =============================================================================
+// deadletter.rs - Fisier "Dead Letter" pentru Pachetele Respinse de Parser
+// =============================================================================
+//
+// Cand parser-ul respinge o linie, `parse_error_report` spune doar CATE au
+// fost si da un exemplu scurt. Pentru diagnosticarea unui format nou sau a
+// trafic-ului malformat avem nevoie de octetii exacti ai pachetului — fara
+// a porni un tcpdump. Cu `network.deadletter_file`, fiecare pachet UDP cu cel
+// putin o linie respinsa este adaugat in fisier ca o linie JSON:
+//
+//   {"timestamp":"...","peer":"10.0.0.1:514","len":312,
+//    "reason":"1/2 linii respinse de parser-ul 'Gaia-CEF'","hex":"3c31..."}
+//
+// Volumul este limitat agresiv (sub atac fisierul ar creste foarte repede):
+//   - pachetul este trunchiat la DEADLETTER_MAX_PACKET_BYTES (campul `truncated`)
+//   - cel mult DEADLETTER_MAX_PER_SEC inregistrari pe secunda; restul sunt
+//     doar numarate si raportate in urmatoarea inregistrare (`skipped`)
+//   - rotatie la `deadletter_max_bytes`: fisier → fisier.1 → ... →
+//     fisier.N (`deadletter_max_files`), cel mai vechi este sters
+//
+// =============================================================================
+
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Octetii maximi pastrati dintr-un pachet (hex = dublu in fisier).
+const DEADLETTER_MAX_PACKET_BYTES: usize = 2048;
+
+/// Inregistrari maxime pe secunda — restul sunt doar numarate.
+const DEADLETTER_MAX_PER_SEC: u32 = 50;
+
+pub struct DeadLetterWriter {
+    path: PathBuf,
+    file: File,
+    /// Dimensiunea curenta a fisierului activ (octeti).
+    size: u64,
+    max_bytes: u64,
+    max_files: u32,
+    window_start: Instant,
+    written_in_window: u32,
+    /// Pachete omise de limita pe secunda, raportate in urmatoarea inregistrare.
+    skipped: u64,
+}
+
+impl DeadLetterWriter {
+    /// Deschide (append) fisierul dead letter, creandu-l daca lipseste.
+    pub fn open(path: &str, max_bytes: u64, max_files: u32) -> Result<Self> {
+        let path = PathBuf::from(path);
+        let file = open_append(&path)?;
+        let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_bytes,
+            max_files,
+            window_start: Instant::now(),
+            written_in_window: 0,
+            skipped: 0,
+        })
+    }
+
+    /// Adauga un pachet respins. Erorile de I/O sunt returnate apelantului —
+    /// diagnosticul nu trebuie sa opreasca receptia.
+    pub fn record(&mut self, peer: SocketAddr, packet: &[u8], reason: &str) -> Result<()> {
+        let now = Instant::now();
+        if now.duration_since(self.window_start) >= Duration::from_secs(1) {
+            self.window_start = now;
+            self.written_in_window = 0;
+        }
+        if self.written_in_window >= DEADLETTER_MAX_PER_SEC {
+            self.skipped += 1;
+            return Ok(());
+        }
+        self.written_in_window += 1;
+
+        let shown = &packet[..packet.len().min(DEADLETTER_MAX_PACKET_BYTES)];
+        let hex: String = shown.iter().map(|b| format!("{:02x}", b)).collect();
+        let mut entry = serde_json::json!({
+            "timestamp": crate::display::format_rfc3339(&chrono::Local::now()),
+            "peer": peer.to_string(),
+            "len": packet.len(),
+            "reason": reason,
+            "hex": hex,
+        });
+        if packet.len() > DEADLETTER_MAX_PACKET_BYTES {
+            entry["truncated"] = serde_json::json!(true);
+        }
+        if self.skipped > 0 {
+            entry["skipped"] = serde_json::json!(std::mem::replace(&mut self.skipped, 0));
+        }
+        let line = format!("{}\n", entry);
+
+        if self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file
+            .write_all(line.as_bytes())
+            .with_context(|| format!("Nu pot scrie in dead letter: {:?}", self.path))?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Rotatie: fisier.(N-1) → fisier.N, ..., fisier → fisier.1, fisier nou gol.
+    /// Cu `max_files = 0` fisierul activ este doar trunchiat (fara istoric).
+    fn rotate(&mut self) -> Result<()> {
+        let rotated = |n: u32| PathBuf::from(format!("{}.{}", self.path.display(), n));
+        if self.max_files > 0 {
+            let _ = std::fs::remove_file(rotated(self.max_files));
+            for n in (1..self.max_files).rev() {
+                let _ = std::fs::rename(rotated(n), rotated(n + 1));
+            }
+            std::fs::rename(&self.path, rotated(1))
+                .with_context(|| format!("Nu pot roti dead letter: {:?}", self.path))?;
+        } else {
+            let _ = std::fs::remove_file(&self.path);
+        }
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &PathBuf) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Nu pot deschide fisierul dead letter: {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadletter_records_rotates_and_rate_limits() {
+        let dir = std::env::temp_dir().join(format!("ids-rs-deadletter-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("rejected.jsonl");
+        let peer: SocketAddr = "10.0.0.1:514".parse().unwrap();
+
+        let mut writer = DeadLetterWriter::open(path.to_str().unwrap(), 400, 2).unwrap();
+        writer.record(peer, b"\xef\xbb\xbfgarbage", "1/1 linii respinse").unwrap();
+        let first = std::fs::read_to_string(&path).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(first.trim()).unwrap();
+        assert_eq!(entry["hex"], "efbbbf67617262616765");
+        assert_eq!(entry["len"], 10);
+        assert_eq!(entry["peer"], "10.0.0.1:514");
+
+        // Peste max_bytes: rotatie, maxim 2 generatii pastrate.
+        for _ in 0..10 {
+            writer.record(peer, &[0x41; 100], "test").unwrap();
+        }
+        assert!(dir.join("rejected.jsonl.1").exists());
+        assert!(dir.join("rejected.jsonl.2").exists());
+        assert!(!dir.join("rejected.jsonl.3").exists());
+        assert!(std::fs::metadata(&path).unwrap().len() <= 400);
+
+        // Limita pe secunda: surplusul este numarat, nu scris.
+        let mut writer = DeadLetterWriter::open(path.to_str().unwrap(), u64::MAX, 0).unwrap();
+        for _ in 0..DEADLETTER_MAX_PER_SEC + 5 {
+            writer.record(peer, b"x", "test").unwrap();
+        }
+        assert_eq!(writer.skipped, 5);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -39,13 +39,18 @@
 //
 // =============================================================================
 
-use crate::config::{DetectionConfig, DetectionExceptions, DynamicThresholdConfig};
+use crate::assetmap::{AssetInfo, AssetMap};
+use crate::classify::AlertClass;
+use crate::config::{AssetsConfig, DetectionConfig, DetectionExceptions, DynamicThresholdConfig};
 use crate::parser::LogEvent;
+use crate::rules::RuleSet;
 use arc_swap::ArcSwap;
 use chrono::{DateTime, Local};
 use dashmap::DashMap;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
 use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
@@ -58,7 +63,7 @@ use std::time::{Duration, Instant};
 /// Parsata din string-urile din config.toml la constructia Detector-ului.
 /// Matching-ul CIDR se face prin bitmask: (ip & mask) == (network & mask).
 #[derive(Debug, Clone)]
-enum WhitelistEntry {
+pub(crate) enum WhitelistEntry {
     /// IP individual (ex: "10.0.1.10").
     Single(IpAddr),
     /// Subnet CIDR IPv4 (ex: "10.0.2.0/24"). Stocam adresa de retea si masca.
@@ -69,13 +74,16 @@ enum WhitelistEntry {
 
 impl WhitelistEntry {
     /// Parseaza un string din config.toml intr-o intrare whitelist.
-    /// Formatul validat deja in config.rs::validate().
-    fn parse(entry: &str) -> Option<Self> {
+    /// Formatul validat deja in config.rs::validate(); tintele din rules.d nu
+    /// trec prin validate, deci prefixele peste 32/128 intorc None (nu panica).
+    pub(crate) fn parse(entry: &str) -> Option<Self> {
         if entry.contains('/') {
             let parts: Vec<&str> = entry.splitn(2, '/').collect();
             let ip: IpAddr = parts[0].parse().ok()?;
             let prefix: u8 = parts[1].parse().ok()?;
             match ip {
+                IpAddr::V4(_) if prefix > 32 => None,
+                IpAddr::V6(_) if prefix > 128 => None,
                 IpAddr::V4(addr) => {
                     let mask = if prefix == 0 {
                         0u32
@@ -102,7 +110,7 @@ impl WhitelistEntry {
     }
 
     /// Verifica daca un IP se potriveste cu aceasta intrare.
-    fn matches(&self, ip: &IpAddr) -> bool {
+    pub(crate) fn matches(&self, ip: &IpAddr) -> bool {
         match self {
             WhitelistEntry::Single(wl_ip) => wl_ip == ip,
             WhitelistEntry::CidrV4(network, mask) => {
@@ -121,6 +129,17 @@ impl WhitelistEntry {
             }
         }
     }
+
+    /// Lungimea prefixului (32 / 128 pentru un IP individual) — intrarea cea
+    /// mai specifica castiga cand mai multe se potrivesc.
+    pub(crate) fn prefix_len(&self) -> u32 {
+        match self {
+            WhitelistEntry::Single(IpAddr::V4(_)) => 32,
+            WhitelistEntry::Single(IpAddr::V6(_)) => 128,
+            WhitelistEntry::CidrV4(_, mask) => mask.count_ones(),
+            WhitelistEntry::CidrV6(_, mask) => mask.count_ones(),
+        }
+    }
 }
 
 // =============================================================================
@@ -202,6 +221,99 @@ pub enum ScanType {
     ///
     /// SignatureID SIEM: 1006. Severitate: 9 (Critical).
     Beaconing,
+
+    /// Saturatia tabelei de tracking — evictii LRU sub presiune.
+    ///
+    /// Nu este o scanare in sine, ci un semnal operational cu implicatii de
+    /// securitate: `max_tracked_ips` a fost atins si IP-uri noi forteaza
+    /// evictia celor vechi intr-un ritm peste pragul configurat. Cauza tipica
+    /// este un flood cu IP-uri sursa spoofed, care "spala" din memorie
+    /// scanerii reali inainte ca acestia sa atinga pragul de detectie.
+    ///
+    /// `source_ip` = IP-ul nou care a declansat evictia; rata de evictie
+    /// (evictii/secunda) este in `eviction_rate`, numarul brut in `event_count`.
+    ///
+    /// SignatureID SIEM: 1007. Severitate: 8 (Critical) — detectia este degradata.
+    Saturation,
+
+    /// Scanare coordonata dintr-un bloc de retea (`coordinated_subnet`) —
+    /// mai multe surse din acelasi prefix (implicit /24), fiecare posibil sub
+    /// pragul Fast / Slow Scan, au vizat IMPREUNA multe porturi.
+    ///
+    /// Perspectiva sursei agregate:
+    ///   DistributedScan   → N surse oarecare × aceeasi tinta (cheie: dest_ip)
+    ///   CoordinatedSubnet → N surse din acelasi prefix × orice tinta (cheie: prefix)
+    ///
+    /// `source_ip` = sursa care a declansat alerta, `source_prefix` = blocul,
+    /// `unique_sources` = sursele din bloc, `unique_ports` = porturile agregate.
+    ///
+    /// SignatureID SIEM: 1008. Severitate: 7 (High) — la fel ca Distributed Scan.
+    CoordinatedSubnet,
+
+    /// Senzorul nu a primit niciun pachet `detection.no_traffic_alert_secs`.
+    ///
+    /// Semnal operational, ca Saturation: o liniste prelungita inseamna mai
+    /// des un mirror / tap / forwarding syslog cazut decat o retea linistita —
+    /// senzorul poate fi "orb". `source_ip` = adresa de ascultare a senzorului,
+    /// durata linistii este in `silent_secs`.
+    ///
+    /// SignatureID SIEM: 1009. Severitate: 6 (Medium) — detectia poate lipsi.
+    NoTraffic,
+
+    /// Regula definita intr-un fisier din `detection.rules_dir` (vezi
+    /// `rules.rs`): N porturi distincte catre tintele regulii intr-o fereastra.
+    /// Numele regulii este in `rule_name`.
+    ///
+    /// SignatureID SIEM: 1010. Severitate: 6 (Medium).
+    CustomRule,
+
+    /// Flood volumetric (`detection.flood`) — o sursa trimite multe pachete
+    /// pe secunda, indiferent cate porturi atinge.
+    ///
+    /// Complementar regulilor pe porturi:
+    ///   Fast/Slow/Accept → 1 sursa × N porturi DISTINCTE (recunoastere)
+    ///   Flood            → 1 sursa × M pachete, oricare porturi (DoS)
+    ///
+    /// `event_count` = pachetele din fereastra, `packet_rate` = rata medie.
+    ///
+    /// SignatureID SIEM: 1011. Severitate: 7 (High) — indicator DoS.
+    Flood,
+
+    /// Slow Sweep (`detection.slow_sweep`) — acelasi port destinatie sondat
+    /// pe multe hosturi de o singura sursa, oricat de lent.
+    ///
+    /// Perspectiva portului:
+    ///   Fast/Slow Scan  → 1 sursa × N porturi DISTINCTE
+    ///   Slow Sweep      → 1 sursa × 1 port × N hosturi, fereastra lunga
+    ///
+    /// `unique_ports` = portul sondat, `unique_dests` = hosturile atinse.
+    ///
+    /// SignatureID SIEM: 1012. Severitate: 6 (Medium) — recunoastere metodica.
+    SlowSweep,
+
+    /// Datagrame malformate (`detection.malformed_input`) — un expeditor UDP
+    /// trimite catre senzor multe datagrame fara nicio linie parsabila (goale,
+    /// binare, alt format).
+    ///
+    /// Tinta este senzorul insusi, nu reteaua monitorizata: fuzzing sau
+    /// sondarea portului de receptie, ori un forwarder configurat gresit.
+    /// `source_ip` = adresa expeditorului datagramelor (nu o sursa din log-uri),
+    /// `event_count` = datagramele malformate din fereastra.
+    ///
+    /// SignatureID SIEM: 1013. Severitate: 5 (Medium).
+    MalformedInput,
+
+    /// Scanare incheiata (`detection.scan_end_silence_secs`) — o sursa care a
+    /// depasit un prag de scanare a tacut intervalul configurat.
+    ///
+    /// Nu este o detectie noua, ci capatul episodului: totalurile lui
+    /// (`unique_ports` / `unique_dests` reunite peste alerte si evenimentele
+    /// ulterioare, `event_count`), durata si alertele in `scan_episode`,
+    /// linistea in `silent_secs`. Inchide incidentul campaniei
+    /// (`incidents.close_on_scan_end`).
+    ///
+    /// SignatureID SIEM: 1014. Severitate: 3 (Low) — informativ.
+    ScanEnded,
 }
 
 /// Implementarea trait-ului Display pentru ScanType.
@@ -221,10 +333,204 @@ impl std::fmt::Display for ScanType {
             ScanType::LateralMovement => write!(f, "Lateral Movement"),
             ScanType::DistributedScan => write!(f, "Distributed Scan"),
             ScanType::Beaconing => write!(f, "Beaconing C2"),
+            ScanType::Saturation => write!(f, "Tracking Saturation"),
+            ScanType::CoordinatedSubnet => write!(f, "Coordinated Subnet Scan"),
+            ScanType::NoTraffic => write!(f, "Sensor Silence"),
+            ScanType::CustomRule => write!(f, "Custom Rule"),
+            ScanType::Flood => write!(f, "Packet Flood"),
+            ScanType::SlowSweep => write!(f, "Slow Sweep"),
+            ScanType::MalformedInput => write!(f, "Malformed Input"),
+            ScanType::ScanEnded => write!(f, "Scan Ended"),
+        }
+    }
+}
+
+/// Criticitatea activului vizat de o alerta (`[assets]`).
+///
+/// Ordinea variantelor conteaza: `derive(PartialOrd, Ord)` compara dupa
+/// ordinea declararii, deci `High < Critical` si `max()` alege nivelul cel
+/// mai sever cand o alerta atinge mai multe active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetCriticality {
+    High,
+    Critical,
+}
+
+impl AssetCriticality {
+    /// Severitatea escaladata pornind de la severitatea tipului de alerta:
+    /// critical → 10 (maxim CEF), high → +1 (plafonat la 10).
+    pub fn escalate(self, base: u8) -> u8 {
+        match self {
+            AssetCriticality::Critical => 10,
+            AssetCriticality::High => base.saturating_add(1).min(10),
+        }
+    }
+}
+
+impl std::fmt::Display for AssetCriticality {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AssetCriticality::High => write!(f, "high"),
+            AssetCriticality::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+/// O alerta care ar fi fost emisa (sau aproape) dar a fost suprimata.
+///
+/// Produsa doar de `Detector::process_event_explained` (`--explain-suppressions`):
+/// in modul normal motivele nu se construiesc deloc.
+#[derive(Debug, Clone)]
+pub struct Suppression {
+    /// Regula afectata ("whitelist", "fast_scan", "lateral_movement" etc.).
+    pub rule: &'static str,
+    pub source_ip: IpAddr,
+    /// Motivul, lizibil (ex: "in cooldown inca 42s").
+    pub reason: String,
+}
+
+/// Avertisment provizoriu: o scanare Fast / Slow a atins `threshold_fraction`
+/// din prag (`[detection.early_warning]`). Nu este o alerta — nu trece prin
+/// sink-uri; bucla principala il afiseaza cu `display::log_early_warning`.
+#[derive(Debug, Clone)]
+pub struct EarlyWarning {
+    pub scan_type: ScanType,
+    pub source_ip: IpAddr,
+    pub dest_ip: Option<IpAddr>,
+    /// Scorul curent (porturi unice, eventual ponderate).
+    pub score: f64,
+    /// Pragul efectiv al regulii.
+    pub threshold: usize,
+}
+
+/// Colector optional de motive de suprimare.
+///
+/// NOTA RUST: motivul vine printr-o closure (`FnOnce() -> String`) — `format!`
+/// ruleaza doar cand colectorul exista, deci modul normal nu aloca nimic.
+struct Explain<'a>(Option<&'a mut Vec<Suppression>>);
+
+impl Explain<'_> {
+    fn enabled(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn note(&mut self, rule: &'static str, source_ip: IpAddr, reason: impl FnOnce() -> String) {
+        if let Some(out) = self.0.as_deref_mut() {
+            out.push(Suppression {
+                rule,
+                source_ip,
+                reason: reason(),
+            });
         }
     }
 }
 
+/// Intervalul de porturi sursa parcurs de o sursa in fereastra
+/// `detection.source_port_exhaustion` (vezi `SourcePortExhaustionConfig`).
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct SourcePortRun {
+    /// Porturi sursa distincte vazute in fereastra.
+    pub ports: usize,
+    pub low: u16,
+    pub high: u16,
+}
+
+impl SourcePortRun {
+    /// Fractiunea intervalului [low, high] acoperita de porturile vazute.
+    pub fn density(&self) -> f64 {
+        self.ports as f64 / (self.high as f64 - self.low as f64 + 1.0)
+    }
+}
+
+/// Numerele din spatele deciziei unei reguli pe prag (`alerting.detection_context`):
+/// valoarea observata fata de pragul efectiv, fereastra si, unde exista, scorul
+/// ponderat si timpul pana la prag. Alerta se explica singura, iar pragurile se
+/// ajusteaza pe date reale, nu pe intuitie.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DetectionContext {
+    /// Regula din config care a decis (ex: "fast_scan", "lateral_movement").
+    pub rule: &'static str,
+    /// Valoarea bruta numarata in fereastra.
+    pub observed: usize,
+    /// Pragul efectiv — dupa pragurile dinamice, `sweep.min_distinct_targets`
+    /// si `source_port_exhaustion`.
+    pub threshold: usize,
+    /// Unitatea lui `observed` si `threshold` (porturi, destinatii, surse, pachete).
+    pub unit: &'static str,
+    pub window_secs: u64,
+    /// Scorul comparat efectiv cu pragul (`port_weights`, `carry_over`).
+    /// Omis cand decizia s-a luat pe numarul brut.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weighted_count: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_to_threshold_ms: Option<u64>,
+}
+
+impl DetectionContext {
+    fn new(rule: &'static str, observed: usize, threshold: usize, unit: &'static str, window: Duration) -> Self {
+        Self {
+            rule,
+            observed,
+            threshold,
+            unit,
+            window_secs: window.as_secs(),
+            weighted_count: None,
+            time_to_threshold_ms: None,
+        }
+    }
+}
+
+/// Episodul de scanare incheiat, purtat de alerta Scan Ended
+/// (`detection.scan_end_silence_secs`).
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ScanEpisodeSummary {
+    /// Primul hit al episodului: alerta care l-a deschis minus timpul ei pana la prag.
+    pub started_at: DateTime<Local>,
+    /// De la inceput pana la ultimul eveniment al sursei (fara linistea finala).
+    pub duration_secs: u64,
+    /// Alertele de scanare ale sursei in episod.
+    pub alerts: usize,
+    pub scan_types: BTreeSet<String>,
+}
+
+/// Identitatea senzorului atasata alertelor (`[sensor]`).
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SensorIdentity {
+    pub hostname: String,
+    /// Etichete de implementare (ex: datacenter = "us-east"), ordonate dupa cheie.
+    #[serde(skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub tags: std::collections::BTreeMap<String, String>,
+}
+
+/// O sursa din topul `Detector::top_talkers` — hit-uri inca in fereastra.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TopTalker {
+    pub ip: IpAddr,
+    /// Hit-uri drop inca in memorie (alimenteaza Fast/Slow Scan).
+    pub dropped: usize,
+    /// Hit-uri accept inca in memorie (alimenteaza Accept Scan).
+    pub accepted: usize,
+}
+
+impl TopTalker {
+    fn new(ip: IpAddr) -> Self {
+        TopTalker {
+            ip,
+            dropped: 0,
+            accepted: 0,
+        }
+    }
+}
+
+/// Urmatorul identificator de alerta (`Alert::id`). Unic pe durata procesului —
+/// se reia de la 1 la restart, deci nu este o cheie persistenta.
+static NEXT_ALERT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Severitatea CEF a unei alerte marcate fals-pozitiv (`false_positives.action
+/// = "downgrade"`) — sub orice severitate de baza, vizibila dar fara escaladare.
+pub const FALSE_POSITIVE_SEVERITY: u8 = 3;
+
 /// Alerta generata cand se detecteaza o scanare.
 ///
 /// NOTA RUST: Aceasta structura este OWNED - cand este creata, toate
@@ -232,6 +538,13 @@ impl std::fmt::Display for ScanType {
 /// functii si thread-uri fara grija ca datele originale se schimba.
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct Alert {
+    /// Identificatorul alertei in procesul curent (`POST /alerts/{id}/false-positive`).
+    pub id: u64,
+    /// Cheia de idempotenta a alertei (`alerting.idempotency_key`), vezi
+    /// `Alert::delivery_key`. Identica la orice re-trimitere — colectorul
+    /// elimina dublurile. Gol cand optiunea este oprita.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub idempotency_key: String,
     pub scan_type: ScanType,
     /// IP-ul sursa al alertei.
     ///
@@ -257,6 +570,10 @@ pub struct Alert {
     pub unique_sources: Vec<IpAddr>,
     pub timestamp: DateTime<Local>,
 
+    /// Pentru Coordinated Subnet Scan: blocul sursa agregat (ex: "203.0.113.0/24").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_prefix: Option<String>,
+
     /// Pentru Beaconing C2: portul tinta al flow-ului. None pentru celelalte tipuri.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub beacon_port: Option<u16>,
@@ -267,8 +584,409 @@ pub struct Alert {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cv: Option<f64>,
     /// Pentru Beaconing C2: numarul de evenimente in fereastra. None pentru celelalte.
+    /// Pentru Saturation: numarul de evictii LRU in fereastra.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub event_count: Option<usize>,
+    /// Pentru Saturation: rata evictiilor LRU (evictii/secunda) in fereastra.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eviction_rate: Option<f64>,
+    /// Pentru Flood: rata medie de pachete (pachete/secunda) a sursei.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub packet_rate: Option<f64>,
+    /// Pentru NoTraffic: secundele scurse de la ultimul pachet primit.
+    /// Pentru ScanEnded: secundele de la ultimul eveniment al sursei.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub silent_secs: Option<u64>,
+    /// Pentru ScanEnded: inceputul, durata si alertele episodului.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan_episode: Option<ScanEpisodeSummary>,
+    /// Pentru CustomRule: numele regulii din `detection.rules_dir`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub rule_name: String,
+    /// Pentru Fast/Slow/Accept Scan: milisecunde de la primul hit din fereastra
+    /// regulii pana la hit-ul care a declansat alerta. Distinge o scanare
+    /// agresiva (100 porturi in 2s) de una lenta (100 porturi in 50s).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_to_threshold_ms: Option<u64>,
+    /// Pentru Fast/Slow Scan: cel putin un port din fereastra a fost vizat de o
+    /// datagrama UDP fara payload (`detection.empty_udp_probes`). Omis cand false.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub empty_probes: bool,
+    /// Pentru Fast Scan: sursa avea deja o alerta Slow Scan activa si a
+    /// accelerat (`slow_scan.promote_to_fast`). Omis cand false.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub promoted_from_slow: bool,
+    /// Pentru Fast/Slow/Accept Scan: protocoalele (tcp, udp, ...) porturilor
+    /// din fereastra. Cu `per_protocol_tracking = "separate"`, doar protocolul
+    /// numarat. Omis pentru celelalte tipuri.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub protocols: Vec<String>,
+    /// Aceeasi sursa a scanat mai multe protocoale — scanare mai determinata,
+    /// incredere ridicata (`detection.per_protocol_tracking`). Omis cand false.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub multi_protocol: bool,
+    /// Pentru Fast/Slow/Accept Scan cu `max_ports_per_ip` depasit: totalul
+    /// APROXIMATIV de porturi distincte (lista `unique_ports` e trunchiata la
+    /// plafon). Prezenta campului este si flag-ul "numar aproximativ".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approx_port_count: Option<usize>,
+    /// Pentru Fast/Slow/Accept Scan cu `detection.port_weights` configurat:
+    /// scorul ponderat comparat cu pragul (numarul brut ramane in
+    /// `unique_ports` / `approx_port_count`). Omis fara ponderi.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weighted_score: Option<f64>,
+    /// Pentru Slow Scan cu `slow_scan.carry_over`: porturile din fereastra
+    /// anterioara (absente din cea curenta) creditate partial la scor. Scorul
+    /// rezultat este in `weighted_score`. Omis cand nu a contribuit nimic.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub carried_ports: Vec<u16>,
+    /// Pentru Fast/Accept Scan: sursa parcurgea rapid un interval aproape
+    /// contiguu de porturi sursa (`detection.source_port_exhaustion`) —
+    /// connect scan agresiv. Omis cand tiparul nu a fost observat.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_port_exhaustion: Option<SourcePortRun>,
+    /// Valoarea observata, pragul efectiv si fereastra regulii care a decis
+    /// (`alerting.detection_context`). Omis cand optiunea este oprita sau
+    /// regula nu are un prag numeric (Beaconing, Saturation, reguli custom).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detection_context: Option<DetectionContext>,
+    /// Hash scurt al sectiunii `[detection]` active la momentul alertei
+    /// (vezi `DetectionConfig::policy_version`). Se schimba la reload.
+    pub policy_version: String,
+    /// Rezumat de o linie, randat din `alerting.summary_template` la
+    /// construirea alertei (ex: "Fast Scan de la 1.2.3.4 — 42 porturi in 8.0s").
+    /// Reutilizat de email, GELF si JSON — aceeasi formulare peste tot.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub summary: String,
+    /// Intrarea (interfata / port / tap) pe care a sosit pachetul care a
+    /// declansat alerta (`network.ingress_name`). Completat de listener-ul
+    /// care a primit pachetul, nu de detector.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ingress: String,
+    /// Interfata firewall-ului pe care a fost observat evenimentul care a
+    /// declansat alerta, din log (`network.interface_metadata`). Gol cand
+    /// optiunea este oprita sau log-ul nu contine interfata.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub interface: String,
+    /// VLAN-ul 802.1Q derivat din numele sub-interfetei (`eth1.100` → 100).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<u16>,
+    /// Senzorul care a emis alerta (`[sensor]`): hostname si etichete de
+    /// implementare. Completat la emitere de bucla principala.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sensor: Option<SensorIdentity>,
+    /// Profilul de detectie care a produs alerta: "default" (`[detection]`)
+    /// sau numele din `[profiles.<nume>]`. Completat de bucla principala.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub profile: String,
+    /// Criticitatea activului vizat (`[assets]`): tinta alertei sau, pentru
+    /// Lateral Movement, oricare destinatie. Escaladeaza severitatea in SIEM,
+    /// email si consola. Omis cand tinta nu este un activ clasificat.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_criticality: Option<AssetCriticality>,
+    /// Activul vizat, din harta CMDB (`enrichment.asset_map_path`): nume,
+    /// criticitate, proprietar. Omis cand tinta nu este in harta.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset: Option<AssetInfo>,
+    /// Sursa si tipul alertei au fost marcate fals-pozitiv de un operator
+    /// (`[false_positives]`, actiune "downgrade"). Omis cand false.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub false_positive: bool,
+    /// Sursa este un nod de iesire Tor (`enrichment.tor_exit_list_path`).
+    /// Omis cand false.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub tor_exit: bool,
+    /// Clasa alertei (`alerting.classification`): `noise` sau `targeted`.
+    /// Omisa cand clasificarea este dezactivata sau tipul nu se clasifica.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classification: Option<AlertClass>,
+    /// Factorii care au dus la clasa, in ordine (ultimul = scorul final).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub classification_reasons: Vec<String>,
+    /// Numarul de alerte contopite de stratul de corelare
+    /// (`alerting.correlation_secs`). Omis pentru o alerta singulara.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merged_count: Option<usize>,
+}
+
+impl Alert {
+    /// Construieste o alerta cu campurile comune completate si restul goale.
+    ///
+    /// Fiecare regula din `process_event` completeaza doar campurile relevante
+    /// prin struct update syntax:
+    ///   `Alert { unique_ports: ports, ..Alert::new(ScanType::Fast, ip, dst) }`
+    ///
+    /// NOTA RUST: `..expr` copiaza (muta) toate campurile NEspecificate explicit
+    /// din `expr`. Adaugarea unui camp nou in `Alert` nu mai cere modificarea
+    /// fiecarui literal — doar a acestui constructor.
+    pub(crate) fn new(scan_type: ScanType, source_ip: IpAddr, dest_ip: Option<IpAddr>) -> Self {
+        Self {
+            id: NEXT_ALERT_ID.fetch_add(1, Ordering::Relaxed),
+            idempotency_key: String::new(),
+            scan_type,
+            source_ip,
+            dest_ip,
+            unique_ports: Vec::new(),
+            unique_dests: Vec::new(),
+            unique_sources: Vec::new(),
+            timestamp: Local::now(),
+            source_prefix: None,
+            beacon_port: None,
+            mean_interval_secs: None,
+            cv: None,
+            event_count: None,
+            eviction_rate: None,
+            packet_rate: None,
+            silent_secs: None,
+            scan_episode: None,
+            rule_name: String::new(),
+            time_to_threshold_ms: None,
+            empty_probes: false,
+            promoted_from_slow: false,
+            protocols: Vec::new(),
+            multi_protocol: false,
+            approx_port_count: None,
+            weighted_score: None,
+            carried_ports: Vec::new(),
+            source_port_exhaustion: None,
+            detection_context: None,
+            policy_version: String::new(),
+            summary: String::new(),
+            ingress: String::new(),
+            interface: String::new(),
+            vlan: None,
+            sensor: None,
+            profile: String::new(),
+            asset_criticality: None,
+            asset: None,
+            false_positive: false,
+            tor_exit: false,
+            classification: None,
+            classification_reasons: Vec::new(),
+            merged_count: None,
+        }
+    }
+
+    /// Severitatea efectiva pornind de la severitatea CEF a tipului: escaladata
+    /// dupa activul vizat (`[assets]`), sau redusa la `FALSE_POSITIVE_SEVERITY`
+    /// cand alerta a fost marcata fals-pozitiv (marcajul operatorului castiga).
+    pub fn effective_severity(&self, base: u8) -> u8 {
+        if self.false_positive {
+            return base.min(FALSE_POSITIVE_SEVERITY);
+        }
+        self.asset_criticality.map_or(base, |c| c.escalate(base))
+    }
+
+    /// Cheia stabila a alertei: "<epoch ms al alertei>-<id>". `id` singur se
+    /// repeta dupa restart; momentul alertei il face unic per senzor. Folosita
+    /// si ca `id` CloudEvents.
+    pub fn delivery_key(&self) -> String {
+        format!("{}-{}", self.timestamp.timestamp_millis(), self.id)
+    }
+
+    /// Numarul de porturi raportat: totalul aproximativ cand lista a fost
+    /// plafonata (`max_ports_per_ip`), altfel lungimea `unique_ports`.
+    pub fn port_count(&self) -> usize {
+        self.approx_port_count.unwrap_or(self.unique_ports.len())
+    }
+
+    /// Ordoneaza numeric (si deduplica) `unique_ports`. Apelata la emitere:
+    /// consola, JSON, CEF, GELF si email vad aceeasi lista, stabila intre
+    /// rulari — indiferent de ordinea in care regula a colectat porturile.
+    pub fn sort_ports(&mut self) {
+        self.unique_ports.sort_unstable();
+        self.unique_ports.dedup();
+    }
+
+    /// `port_count` pentru afisare: prefix "~" cand valoarea este aproximativa.
+    pub fn port_count_label(&self) -> String {
+        match self.approx_port_count {
+            Some(n) => format!("~{}", n),
+            None => self.unique_ports.len().to_string(),
+        }
+    }
+
+    /// Porturile vizate comprimate in intervale contigue, sortate:
+    /// [22, 1, 2, 3, 80] → [(1, 3), (22, 22), (80, 80)].
+    pub fn port_ranges(&self) -> Vec<(u16, u16)> {
+        let mut ports = self.unique_ports.clone();
+        ports.sort_unstable();
+        ports.dedup();
+        let mut ranges: Vec<(u16, u16)> = Vec::new();
+        for port in ports {
+            match ranges.last_mut() {
+                Some((_, end)) if *end as u32 + 1 == port as u32 => *end = port,
+                _ => ranges.push((port, port)),
+            }
+        }
+        ranges
+    }
+
+    /// Lista porturilor ca text: intervale ("1-1024, 3389" cu `sep` = ", ")
+    /// sau porturile individuale, in ordinea din alerta.
+    pub fn ports_text(&self, sep: &str, ranges: bool) -> String {
+        if ranges {
+            self.port_ranges()
+                .iter()
+                .map(|&r| format_port_range(r))
+                .collect::<Vec<_>>()
+                .join(sep)
+        } else {
+            self.unique_ports
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(sep)
+        }
+    }
+
+    /// Interfata firewall-ului pentru afisare: "eth1.100 (VLAN 100)", "eth8",
+    /// sau gol cand alerta nu poarta interfata.
+    pub fn interface_label(&self) -> String {
+        match self.vlan {
+            Some(vlan) => format!("{} (VLAN {})", self.interface, vlan),
+            None => self.interface.clone(),
+        }
+    }
+
+    /// Randeaza `alerting.summary_template` pentru aceasta alerta.
+    ///
+    /// Campurile `{nume}` din `SUMMARY_FIELDS` sunt substituite; orice alt
+    /// text (inclusiv o acolada fara camp cunoscut) ramane neschimbat —
+    /// sablonul este oricum validat la incarcarea config-ului.
+    pub fn render_summary(&self, template: &str) -> String {
+        let mut out = String::with_capacity(template.len() + 32);
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let field = after
+                .find('}')
+                .and_then(|end| Some((end, self.summary_field(&after[..end])?)));
+            match field {
+                Some((end, value)) => {
+                    out.push_str(&value);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Valoarea unui camp de sablon, `None` pentru un nume necunoscut.
+    fn summary_field(&self, name: &str) -> Option<String> {
+        let dest = || self.dest_ip.map_or_else(|| "N/A".to_string(), |ip| ip.to_string());
+        let value = match name {
+            "type" => self.scan_type.to_string(),
+            "source_ip" => self.source_ip.to_string(),
+            "dest_ip" => dest(),
+            "count" => self.summary_count(),
+            "unit" => self.summary_unit().to_string(),
+            "duration" => self
+                .time_to_threshold_ms
+                .map(|ms| format!("{:.1}s", ms as f64 / 1000.0))
+                .unwrap_or_default(),
+            "ports" => {
+                // Primele 10 elemente: porturi sau intervale (`output.port_ranges`).
+                let items: Vec<String> = if crate::display::port_ranges_enabled() {
+                    self.port_ranges().into_iter().map(format_port_range).collect()
+                } else {
+                    self.unique_ports.iter().map(|p| p.to_string()).collect()
+                };
+                let mut ports: Vec<String> = items.iter().take(10).cloned().collect();
+                if items.len() > 10 {
+                    ports.push("...".to_string());
+                }
+                ports.join(",")
+            }
+            "detail" => {
+                let base = format!("{} {}", self.summary_count(), self.summary_unit());
+                match self.scan_type {
+                    ScanType::Fast | ScanType::Slow | ScanType::AcceptScan => {
+                        match self.time_to_threshold_ms {
+                            Some(ms) => format!("{} in {:.1}s", base, ms as f64 / 1000.0),
+                            None => base,
+                        }
+                    }
+                    ScanType::LateralMovement => base,
+                    ScanType::SlowSweep => format!("{} pe portul {}", base, self.ports_text(",", false)),
+                    ScanType::DistributedScan => format!("{} → {}", base, dest()),
+                    ScanType::CoordinatedSubnet => format!(
+                        "{} surse din {}, {} porturi",
+                        self.unique_sources.len(),
+                        self.source_prefix.as_deref().unwrap_or("?"),
+                        self.unique_ports.len()
+                    ),
+                    ScanType::Beaconing => format!(
+                        "{} → {}:{}, interval mediu {:.1}s",
+                        base,
+                        dest(),
+                        self.beacon_port.map_or_else(|| "?".to_string(), |p| p.to_string()),
+                        self.mean_interval_secs.unwrap_or(0.0)
+                    ),
+                    ScanType::Saturation => {
+                        format!("{} ({:.1}/s)", base, self.eviction_rate.unwrap_or(0.0))
+                    }
+                    ScanType::NoTraffic => base,
+                    ScanType::CustomRule => format!("{} — regula {}", base, self.rule_name),
+                    ScanType::Flood => {
+                        format!("{} ({:.0} pachete/s)", base, self.packet_rate.unwrap_or(0.0))
+                    }
+                    ScanType::MalformedInput => base,
+                    ScanType::ScanEnded => match &self.scan_episode {
+                        Some(ep) => format!(
+                            "{}, {} destinatii, {} alerte in {}s",
+                            base,
+                            self.unique_dests.len(),
+                            ep.alerts,
+                            ep.duration_secs
+                        ),
+                        None => base,
+                    },
+                }
+            }
+            "policy" => self.policy_version.clone(),
+            "id" => self.id.to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Numarul principal al alertei, pe tip: porturi, destinatii, surse, evenimente.
+    fn summary_count(&self) -> String {
+        match self.scan_type {
+            ScanType::Fast | ScanType::Slow | ScanType::AcceptScan => self.port_count_label(),
+            ScanType::CoordinatedSubnet | ScanType::CustomRule | ScanType::ScanEnded => {
+                self.unique_ports.len().to_string()
+            }
+            ScanType::LateralMovement | ScanType::SlowSweep => self.unique_dests.len().to_string(),
+            ScanType::DistributedScan => self.unique_sources.len().to_string(),
+            ScanType::Beaconing | ScanType::Saturation | ScanType::Flood | ScanType::MalformedInput => {
+                self.event_count.unwrap_or(0).to_string()
+            }
+            ScanType::NoTraffic => self.silent_secs.unwrap_or(0).to_string(),
+        }
+    }
+
+    /// Unitatea lui `summary_count`.
+    fn summary_unit(&self) -> &'static str {
+        match self.scan_type {
+            ScanType::Fast | ScanType::Slow | ScanType::AcceptScan => "porturi",
+            ScanType::CoordinatedSubnet | ScanType::CustomRule | ScanType::ScanEnded => "porturi",
+            ScanType::LateralMovement | ScanType::SlowSweep => "destinatii",
+            ScanType::DistributedScan => "surse",
+            ScanType::Beaconing => "calluri",
+            ScanType::Saturation => "evictii LRU",
+            ScanType::NoTraffic => "secunde fara trafic",
+            ScanType::Flood => "pachete",
+            ScanType::MalformedInput => "datagrame malformate",
+        }
+    }
 }
 
 /// Inregistrarea unei conexiuni catre o destinatie (Lateral Movement #22).
@@ -290,6 +1008,25 @@ struct DistributedHit {
     source_ip: IpAddr,
     port: u16,
     seen_at: Instant,
+    /// Tinta hit-ului — Coordinated Subnet numara tintele distincte
+    /// (`detection.sweep.min_distinct_targets`).
+    dest_ip: Option<IpAddr>,
+}
+
+/// Adresa de retea a prefixului sursei (Coordinated Subnet Scan):
+/// `203.0.113.77` cu /24 → `203.0.113.0`. Folosita drept cheie in `subnet_hits`
+/// si pentru `incidents.group_by = "subnet"`.
+pub fn source_prefix(ip: IpAddr, prefix_len_v4: u8, prefix_len_v6: u8) -> IpAddr {
+    match ip {
+        IpAddr::V4(v4) => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix_len_v4)).unwrap_or(0);
+            IpAddr::V4((u32::from(v4) & mask).into())
+        }
+        IpAddr::V6(v6) => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix_len_v6)).unwrap_or(0);
+            IpAddr::V6((u128::from(v6) & mask).into())
+        }
+    }
 }
 
 /// Inregistrarea unui port accesat de un IP la un moment dat.
@@ -302,8 +1039,223 @@ struct DistributedHit {
 struct PortHit {
     port: u16,
     seen_at: Instant,
+    /// Datagrama UDP fara payload (sonda goala) — ponderata la Fast/Slow Scan.
+    empty_probe: bool,
+    /// Protocolul normalizat (`tracked_protocol`) — numarare separata sau
+    /// combinata dupa `detection.per_protocol_tracking`.
+    protocol: &'static str,
+}
+
+/// Porturi noi vazute DUPA atingerea plafonului `max_ports_per_ip`.
+///
+/// Contoare saturate (nu set): porturile peste plafon nu mai sunt memorate,
+/// deci o repetare a aceluiasi port peste plafon este numarata din nou —
+/// de aici "aproximativ". Un scanner loveste de regula fiecare port o data,
+/// asa ca eroarea este mica exact in cazul pentru care exista plafonul.
+///
+/// Contoarele sunt pe galeti de o secunda: fereastra Fast Scan numara doar
+/// depasirile din fereastra ei, nu si pe cele acumulate pe fereastra Slow.
+/// Peste `OVERFLOW_BUCKETS` galeti, cele mai vechi doua se unesc sub momentul
+/// celei mai vechi — o fereastra poate doar subestima, niciodata supraestima.
+#[derive(Default)]
+struct PortOverflow {
+    buckets: VecDeque<(Instant, u32)>,
+}
+
+/// Galeti de depasire pastrate per IP (16 octeti fiecare).
+const OVERFLOW_BUCKETS: usize = 64;
+
+impl PortOverflow {
+    fn record(&mut self, now: Instant) {
+        match self.buckets.back_mut() {
+            Some((at, count)) if now.saturating_duration_since(*at) < Duration::from_secs(1) => {
+                *count = count.saturating_add(1);
+            }
+            _ => {
+                self.buckets.push_back((now, 1));
+                if self.buckets.len() > OVERFLOW_BUCKETS {
+                    if let Some((oldest_at, oldest)) = self.buckets.pop_front() {
+                        if let Some(front) = self.buckets.front_mut() {
+                            // Momentul celei mai vechi: subestimare, nu alerta falsa.
+                            *front = (oldest_at, front.1.saturating_add(oldest));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Depasirile din ultimele `window`.
+    fn in_window(&self, window: Duration, now: Instant) -> usize {
+        self.buckets
+            .iter()
+            .rev()
+            .take_while(|(at, _)| now.saturating_duration_since(*at) <= window)
+            .map(|(_, count)| *count as usize)
+            .sum()
+    }
+
+    /// Sterge galetile mai vechi de `max_age`; true daca nu a ramas nimic.
+    fn prune(&mut self, max_age: Duration, now: Instant) -> bool {
+        while self
+            .buckets
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) > max_age)
+        {
+            self.buckets.pop_front();
+        }
+        self.buckets.is_empty()
+    }
+}
+
+/// Porturile sursa recente ale unei surse (`detection.source_port_exhaustion`),
+/// mentinute incremental: tiparul se evalueaza la fiecare eveniment, deci nu
+/// sortam si deduplicam lista la fiecare pachet.
+///
+/// `latest` = portul → ultimul moment vazut, ordonat (numar, minim si maxim in
+/// O(log n)). `order` = sosirile in ordine cronologica, pentru expirare; un port
+/// revazut lasa in urma intrarea veche, ignorata cand iese din fereastra
+/// (momentul ei nu mai coincide cu cel din `latest`).
+#[derive(Default)]
+struct SourcePorts {
+    order: VecDeque<(Instant, u16)>,
+    latest: BTreeMap<u16, Instant>,
+}
+
+impl SourcePorts {
+    /// Inregistreaza `port`; pastreaza cel mult `max_hits` sosiri (cele mai noi).
+    fn record(&mut self, port: u16, now: Instant, max_hits: usize) {
+        self.order.push_back((now, port));
+        self.latest.insert(port, now);
+        while self.order.len() > max_hits {
+            self.pop_oldest();
+        }
+    }
+
+    /// Sterge sosirile mai vechi de `window`; true daca nu a ramas nimic.
+    fn expire(&mut self, window: Duration, now: Instant) -> bool {
+        while self
+            .order
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) > window)
+        {
+            self.pop_oldest();
+        }
+        self.order.is_empty()
+    }
+
+    fn pop_oldest(&mut self) {
+        if let Some((at, port)) = self.order.pop_front() {
+            // Portul dispare doar daca nu a fost revazut intre timp.
+            if self.latest.get(&port) == Some(&at) {
+                self.latest.remove(&port);
+            }
+        }
+    }
+
+    /// Porturile distincte si intervalul lor; `None` cand nu exista niciunul.
+    fn run(&self) -> Option<SourcePortRun> {
+        Some(SourcePortRun {
+            ports: self.latest.len(),
+            low: *self.latest.first_key_value()?.0,
+            high: *self.latest.last_key_value()?.0,
+        })
+    }
+}
+
+/// Contorul de pachete al unei surse pentru regula Flood (`detection.flood`).
+///
+/// Fereastra fixa, nu glisanta: un singur contor per IP (16 octeti), fara
+/// lista de momente — la mii de pachete/secunda, un `Vec<Instant>` per sursa
+/// ar costa exact memoria pe care flood-ul incearca sa o epuizeze.
+struct FloodCounter {
+    window_start: Instant,
+    packets: u64,
+}
+
+/// Episodul de scanare al unei surse (`detection.scan_end_silence_secs`):
+/// deschis de prima alerta de scanare, prelungit de fiecare eveniment
+/// ulterior al sursei, inchis de `Detector::check_scan_ends` dupa liniste.
+///
+/// Porturile si destinatiile sunt plafonate la `max_ports_per_ip` (sau
+/// `SCAN_EPISODE_MAX_ITEMS` fara plafon) — un episod lung nu creste nelimitat.
+struct ScanEpisode {
+    started_at: DateTime<Local>,
+    started: Instant,
+    last_seen: Instant,
+    alerts: usize,
+    events: usize,
+    scan_types: BTreeSet<String>,
+    ports: HashSet<u16>,
+    dests: HashSet<IpAddr>,
+}
+
+impl ScanEpisode {
+    /// Episod deschis de `alert`: inceputul este primul hit din fereastra
+    /// regulii (`time_to_threshold_ms`), nu momentul alertei.
+    fn open(alert: &Alert, now: Instant) -> Self {
+        let lead_ms = alert.time_to_threshold_ms.unwrap_or(0);
+        ScanEpisode {
+            started_at: alert.timestamp - chrono::Duration::milliseconds(lead_ms as i64),
+            started: now.checked_sub(Duration::from_millis(lead_ms)).unwrap_or(now),
+            last_seen: now,
+            alerts: 0,
+            events: 0,
+            scan_types: BTreeSet::new(),
+            ports: HashSet::new(),
+            dests: HashSet::new(),
+        }
+    }
+
+    fn add_port(&mut self, port: u16, cap: usize) {
+        if self.ports.len() < cap {
+            self.ports.insert(port);
+        }
+    }
+
+    fn add_dest(&mut self, dest: IpAddr, cap: usize) {
+        if self.dests.len() < cap {
+            self.dests.insert(dest);
+        }
+    }
+}
+
+/// Plafonul porturilor / destinatiilor unui episod cand `max_ports_per_ip = 0`:
+/// toate porturile incap, destinatiile raman marginite.
+const SCAN_EPISODE_MAX_ITEMS: usize = 65_536;
+
+/// Tipurile de alerta care deschid (si prelungesc) un episod de scanare:
+/// cele in care `source_ip` este chiar scanerul. Distributed / Coordinated
+/// Subnet (agregate), Beaconing si alertele operationale nu deschid episoade.
+fn opens_scan_episode(scan_type: &ScanType) -> bool {
+    matches!(
+        scan_type,
+        ScanType::Fast
+            | ScanType::Slow
+            | ScanType::AcceptScan
+            | ScanType::LateralMovement
+            | ScanType::Flood
+            | ScanType::SlowSweep
+            | ScanType::CustomRule
+    )
 }
 
+/// Stare de asteptare pentru confirmarea multi-fereastra (`confirm_windows`).
+///
+/// `first_at` limiteaza durata totala (`confirm_secs`); `last_at` garanteaza
+/// ca depasirile numarate provin din ferestre disjuncte — una noua conteaza
+/// doar dupa o fereastra completa, cand porturile vechi au iesit din calcul.
+struct PendingConfirm {
+    crossings: u32,
+    first_at: Instant,
+    last_at: Instant,
+}
+
+/// Regulile care trec prin `confirm_crossing` — a doua componenta a cheii
+/// `pending_confirms`. Evictia LRU sterge cele trei chei ale unui IP direct,
+/// fara sa parcurga tot map-ul.
+const CONFIRM_RULES: [&str; 3] = ["fast_scan", "slow_scan", "accept_scan"];
+
 /// Timestamp-ul unei conexiuni dintr-un flow Beaconing (#24).
 ///
 /// Cheia DashMap-ului `beacon_hits` este `(src_ip, dest_ip, dest_port)`,
@@ -427,29 +1379,244 @@ fn effective_threshold_value(
     (dynamic.ceil() as usize).clamp(floor.max(1), ceiling.max(1))
 }
 
-fn count_unique_ports_in_hits(hits: &[PortHit], window: Duration, now: Instant) -> usize {
-    let mut ports: Vec<u16> = hits
-        .iter()
-        .filter(|h| now.saturating_duration_since(h.seen_at) <= window)
-        .map(|h| h.port)
-        .collect();
-    ports.sort_unstable();
-    ports.dedup();
-    ports.len()
+/// Pragul redus cu `source_port_exhaustion.threshold_factor` (minim 1 port).
+fn scaled_threshold(threshold: usize, factor: f64) -> usize {
+    ((threshold as f64 * factor).ceil() as usize).max(1)
 }
 
-/// Exceptii de detectie parsate (IP-uri ca IpAddr, porturi ca HashSet pentru O(1) lookup).
-/// Parsate o singura data la constructie / hot reload, nu la fiecare eveniment.
-struct ParsedExceptions {
-    authorized_scanners: HashSet<IpAddr>,
-    ignore_lateral_ports: HashSet<u16>,
-    ignore_distributed_target_ports: HashSet<u16>,
-    ignore_beaconing_ports: HashSet<u16>,
-    authorized_beaconing_sources: HashSet<IpAddr>,
+// =============================================================================
+// Compactare memorie (synth-208)
+// =============================================================================
+//
+// `Vec::retain` si `DashMap::remove` elibereaza ELEMENTE, nu CAPACITATE:
+// un IP care a trimis 10.000 de hit-uri intr-un burst pastreaza buffer-ul
+// de 10.000 de sloturi chiar daca acum are 3 intrari. Compactarea periodica
+// micsoreaza doar structurile cu factor de incarcare sub 25% — sub acest prag
+// realocarea merita costul; peste el am plati realocari repetate la fiecare
+// ciclu pentru castiguri neglijabile.
+//
+// NOTA RUST: `std::mem::size_of::<T>()` da dimensiunea unui element la
+// compile-time. Memoria recuperata este o ESTIMARE (sloturi eliberate x
+// dimensiune slot) — alocatorul poate rotunji, iar DashMap are overhead
+// intern per shard pe care nu il vedem.
+
+/// Capacitatea minima sub care nu compactam (realocarea nu merita).
+const COMPACT_MIN_CAPACITY: usize = 16;
+
+/// Micsoreaza un Vec supra-alocat. Returneaza octetii eliberati (estimare).
+fn compact_vec<T>(v: &mut Vec<T>) -> usize {
+    let before = v.capacity();
+    if before <= COMPACT_MIN_CAPACITY || v.len() * 4 >= before {
+        return 0;
+    }
+    v.shrink_to_fit();
+    (before - v.capacity()) * std::mem::size_of::<T>()
 }
 
-impl ParsedExceptions {
-    fn from_config(exc: &DetectionExceptions) -> Self {
+/// Compacteaza valorile Vec ale unui map si apoi map-ul insusi.
+fn compact_vec_map<K, T>(map: &DashMap<K, Vec<T>>) -> usize
+where
+    K: Eq + std::hash::Hash + Clone,
+{
+    let mut reclaimed = 0;
+    for mut entry in map.iter_mut() {
+        reclaimed += compact_vec(entry.value_mut());
+    }
+    reclaimed + compact_map(map)
+}
+
+/// Elibereaza capacitatea unui DashMap cu factor de incarcare sub 25%.
+fn compact_map<K, V>(map: &DashMap<K, V>) -> usize
+where
+    K: Eq + std::hash::Hash + Clone,
+{
+    let before = map.capacity();
+    if before <= COMPACT_MIN_CAPACITY || map.len() * 4 >= before {
+        return 0;
+    }
+    map.shrink_to_fit();
+    before.saturating_sub(map.capacity()) * (std::mem::size_of::<K>() + std::mem::size_of::<V>())
+}
+
+/// Protocolul unui eveniment ca `&'static str`: `PortHit` ramane `Copy`-ieftin,
+/// fara un `String` alocat per hit. Protocoalele rare devin "other".
+fn tracked_protocol(protocol: &str) -> &'static str {
+    match protocol {
+        "tcp" => "tcp",
+        "udp" => "udp",
+        "icmp" => "icmp",
+        "sctp" => "sctp",
+        _ => "other",
+    }
+}
+
+/// Valorile posibile ale lui `tracked_protocol`.
+const TRACKED_PROTOCOLS: [&str; 5] = ["tcp", "udp", "icmp", "sctp", "other"];
+
+/// Scopul cooldown-urilor Fast / Slow / Accept Scan cu
+/// `per_protocol_tracking = "combined"`: toate protocoalele sursei.
+const ALL_PROTOCOLS: &str = "all";
+
+/// Cheie (IP sursa, scop) pentru starea Fast / Slow / Accept Scan care
+/// depinde de `per_protocol_tracking`: scopul este protocolul numarat
+/// ("separate") sau `ALL_PROTOCOLS` ("combined"). In modul "separate" o
+/// alerta TCP nu pune in cooldown scanarea UDP a aceleiasi surse.
+type ScopeKey = (IpAddr, &'static str);
+
+/// Sterge toate cheile (ip, scop) ale lui `ip` — cautari directe, fara
+/// parcurgerea map-ului.
+fn remove_scoped<V>(map: &DashMap<ScopeKey, V>, ip: IpAddr) {
+    map.remove(&(ip, ALL_PROTOCOLS));
+    for protocol in TRACKED_PROTOCOLS {
+        map.remove(&(ip, protocol));
+    }
+}
+
+/// Un interval de porturi ca text: "22" sau "1-1024".
+pub fn format_port_range((start, end): (u16, u16)) -> String {
+    if start == end {
+        start.to_string()
+    } else {
+        format!("{}-{}", start, end)
+    }
+}
+
+thread_local! {
+    /// Bitset-ul lui `distinct_ports` (65536 biti = 8 KB), unul per thread.
+    static PORT_BITSET: std::cell::RefCell<Box<[u64; 1024]>> =
+        std::cell::RefCell::new(Box::new([0u64; 1024]));
+}
+
+/// Numarul de porturi distincte dintr-un Vec de hit-uri, fara alocare:
+/// bitset de 65536 biti indexat direct dupa port. Bitset-ul este refolosit
+/// intre apeluri — dupa numarare se sterg doar bitii setati (O(n)), nu toti
+/// cei 8 KB la fiecare eveniment.
+fn distinct_ports(hits: &[PortHit]) -> usize {
+    PORT_BITSET.with(|seen| {
+        let mut seen = seen.borrow_mut();
+        let mut count = 0;
+        for h in hits {
+            let (word, bit) = (h.port as usize / 64, h.port % 64);
+            if seen[word] & (1 << bit) == 0 {
+                seen[word] |= 1 << bit;
+                count += 1;
+            }
+        }
+        for h in hits {
+            seen[h.port as usize / 64] = 0;
+        }
+        count
+    })
+}
+
+/// Porturile numarate peste plafon pentru `ip` in ultimele `window`: doar
+/// protocolul `proto_filter` ("separate") sau toate insumate ("combined").
+fn overflow_in_window(
+    map: &DashMap<ScopeKey, PortOverflow>,
+    ip: IpAddr,
+    window: Duration,
+    now: Instant,
+    proto_filter: Option<&'static str>,
+) -> usize {
+    let count = |protocol| map.get(&(ip, protocol)).map_or(0, |o| o.in_window(window, now));
+    match proto_filter {
+        Some(protocol) => count(protocol),
+        None => TRACKED_PROTOCOLS.iter().map(|p| count(p)).sum(),
+    }
+}
+
+/// Scorul comparat cu pragurile Fast/Slow Scan: suma ponderilor porturilor
+/// unice (`detection.port_weights`, implicit 1.0), plus porturile peste plafon
+/// (pondere 1.0 — nu le stim numarul), plus `weight - 1` pentru fiecare port
+/// vizat de o sonda UDP goala.
+///
+/// Exemplu: 6 porturi unice, 4 dintre ele sonde goale, weight = 2.0
+///          → 6 + 4 × 1.0 = 10.0
+/// Exemplu: porturile 22, 80, 1521 cu port_weights = { 1521 = 10.0 }
+///          → 1 + 1 + 10 = 12.0
+fn weighted_port_count(
+    ports: &[u16],
+    overflow: usize,
+    empty: usize,
+    cfg: &DetectionConfig,
+    weights: &HashMap<u16, f64>,
+) -> f64 {
+    let extra = if cfg.empty_udp_probes.enabled {
+        (cfg.empty_udp_probes.weight - 1.0) * empty as f64
+    } else {
+        0.0
+    };
+    port_weight_sum(ports, weights) + overflow as f64 + extra
+}
+
+/// Suma ponderilor porturilor; fara ponderi configurate = numarul de porturi.
+fn port_weight_sum(ports: &[u16], weights: &HashMap<u16, f64>) -> f64 {
+    if weights.is_empty() {
+        return ports.len() as f64;
+    }
+    ports
+        .iter()
+        .map(|p| weights.get(p).copied().unwrap_or(1.0))
+        .sum()
+}
+
+/// Parseaza `detection.port_weights` (chei string validate in config).
+fn parse_port_weights(cfg: &DetectionConfig) -> HashMap<u16, f64> {
+    cfg.port_weights
+        .iter()
+        .filter_map(|(port, weight)| Some((port.parse().ok()?, *weight)))
+        .collect()
+}
+
+fn count_unique_ports_in_hits(hits: &[PortHit], window: Duration, now: Instant) -> usize {
+    let mut ports: Vec<u16> = hits
+        .iter()
+        .filter(|h| now.saturating_duration_since(h.seen_at) <= window)
+        .map(|h| h.port)
+        .collect();
+    ports.sort_unstable();
+    ports.dedup();
+    ports.len()
+}
+
+/// Mediana intervalelor Beaconing si cate dintre ele sunt in toleranta de
+/// jitter (`|interval − mediana| <= mediana × jitter_percent / 100`).
+///
+/// Mediana (nu media) ca referinta: un beacon ratat produce un interval
+/// dublu care ar trage media in sus, dar nu muta mediana.
+fn jitter_consistency(intervals: &[f64], jitter_percent: f64) -> (f64, usize) {
+    if intervals.is_empty() {
+        return (0.0, 0);
+    }
+    let mut sorted = intervals.to_vec();
+    // NOTA RUST: f64 nu implementeaza Ord (NaN) — `total_cmp` da o ordine totala.
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    let median = if sorted.len() % 2 == 1 {
+        sorted[mid]
+    } else {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    };
+    let tolerance = median * jitter_percent / 100.0;
+    let consistent = intervals
+        .iter()
+        .filter(|iv| (*iv - median).abs() <= tolerance)
+        .count();
+    (median, consistent)
+}
+
+/// Exceptii de detectie parsate (IP-uri ca IpAddr, porturi ca HashSet pentru O(1) lookup).
+/// Parsate o singura data la constructie / hot reload, nu la fiecare eveniment.
+struct ParsedExceptions {
+    authorized_scanners: HashSet<IpAddr>,
+    ignore_lateral_ports: HashSet<u16>,
+    ignore_distributed_target_ports: HashSet<u16>,
+    ignore_beaconing_ports: HashSet<u16>,
+    authorized_beaconing_sources: HashSet<IpAddr>,
+}
+
+impl ParsedExceptions {
+    fn from_config(exc: &DetectionExceptions) -> Self {
         Self {
             authorized_scanners: exc
                 .authorized_scanners
@@ -472,7 +1639,51 @@ impl ParsedExceptions {
     }
 }
 
-pub struct Detector {
+/// Subnetele `[assets]` parsate (refolosim `WhitelistEntry` pentru IP/CIDR).
+#[derive(Default)]
+struct AssetTiers {
+    critical: Vec<WhitelistEntry>,
+    high: Vec<WhitelistEntry>,
+}
+
+impl AssetTiers {
+    fn from_config(cfg: &AssetsConfig) -> Self {
+        let parse = |list: &[String]| list.iter().filter_map(|e| WhitelistEntry::parse(e)).collect();
+        Self {
+            critical: parse(&cfg.critical_cidrs),
+            high: parse(&cfg.high_cidrs),
+        }
+    }
+
+    fn classify(&self, ip: &IpAddr) -> Option<AssetCriticality> {
+        if self.critical.iter().any(|e| e.matches(ip)) {
+            Some(AssetCriticality::Critical)
+        } else if self.high.iter().any(|e| e.matches(ip)) {
+            Some(AssetCriticality::High)
+        } else {
+            None
+        }
+    }
+
+    /// Nivelul maxim dintre tinta alertei si destinatiile Lateral Movement.
+    fn classify_alert(&self, alert: &Alert) -> Option<AssetCriticality> {
+        alert
+            .dest_ip
+            .iter()
+            .chain(alert.unique_dests.iter())
+            .filter_map(|ip| self.classify(ip))
+            .max()
+    }
+}
+
+/// Harta de urmarire a porturilor: hit-urile drop / accept per IP sursa.
+///
+/// Cea mai mare structura a detectorului (pana la `max_hits_per_ip` intrari
+/// per IP). Profilurile `[profiles.<nume>]` cu aceiasi parametri de urmarire
+/// (`tracking_key`) partajeaza o singura instanta: detectorul care a creat-o
+/// o alimenteaza (inregistrare, evictie, curatare), celelalte evalueaza doar
+/// propriile praguri si ferestre peste aceleasi hit-uri.
+struct PortTracker {
     /// Evidenta porturilor BLOCATE (drop) accesate per IP sursa.
     /// Alimenteaza detectia Fast Scan si Slow Scan.
     /// Key: IP-ul sursa | Value: lista de (port, timestamp)
@@ -486,15 +1697,93 @@ pub struct Detector {
     /// cu un Accept Scan de la acelasi IP, si vrem sa le detectam independent.
     accept_hits: DashMap<IpAddr, Vec<PortHit>>,
 
-    /// Cooldown alerte Fast Scan per IP.
+    /// Depasiri ale plafonului `max_ports_per_ip` in port_hits / accept_hits,
+    /// per (IP, protocolul portului) — "combined" le insumeaza, "separate"
+    /// citeste doar protocolul numarat (`overflow_in_window`).
+    port_overflow: DashMap<ScopeKey, PortOverflow>,
+    accept_overflow: DashMap<ScopeKey, PortOverflow>,
+
+    /// `tracking_key` al detectorului care alimenteaza harta.
+    key: AtomicU64,
+}
+
+impl PortTracker {
+    fn new(key: u64) -> Self {
+        Self {
+            port_hits: DashMap::new(),
+            accept_hits: DashMap::new(),
+            port_overflow: DashMap::new(),
+            accept_overflow: DashMap::new(),
+            key: AtomicU64::new(key),
+        }
+    }
+
+    /// Elimina toate hit-urile unui IP (reset la inactivitate, evictie LRU).
+    fn remove(&self, ip: IpAddr) {
+        self.port_hits.remove(&ip);
+        self.accept_hits.remove(&ip);
+        remove_scoped(&self.port_overflow, ip);
+        remove_scoped(&self.accept_overflow, ip);
+    }
+}
+
+/// Amprenta parametrilor care determina CONTINUTUL hartii de urmarire: ce
+/// evenimente ajung in ea (whitelist, semnaturi autorizate, inbound_only) si
+/// cum sunt retinute (plafoane, evictie LRU, reset la inactivitate).
+///
+/// Ferestrele si pragurile nu intra in amprenta: hit-urile sunt pastrate pana
+/// la `cleanup.max_entry_age_secs` (comun tuturor profilurilor), iar fiecare
+/// profil numara porturile din propria fereastra. Doua profiluri cu ferestre
+/// identice si praguri diferite au deci aceeasi amprenta.
+fn tracking_key(cfg: &DetectionConfig, inbound_only: bool) -> u64 {
+    use std::hash::Hasher;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    cfg.whitelist.hash(&mut hasher);
+    cfg.trusted_signatures.patterns.hash(&mut hasher);
+    cfg.trusted_signatures.suppress_secs.hash(&mut hasher);
+    cfg.inactivity_reset_secs.hash(&mut hasher);
+    cfg.max_tracked_ips.hash(&mut hasher);
+    cfg.max_ports_per_ip.hash(&mut hasher);
+    cfg.max_hits_per_ip.hash(&mut hasher);
+    cfg.empty_udp_probes.enabled.hash(&mut hasher);
+    inbound_only.hash(&mut hasher);
+    hasher.finish()
+}
+
+pub struct Detector {
+    /// Harta de urmarire a porturilor — proprie sau partajata cu un detector
+    /// anterior (`share_tracking`). Inlocuita doar la separare
+    /// (`detach_diverged_tracking`), de aceea in `ArcSwap`.
+    tracker: ArcSwap<PortTracker>,
+
+    /// True daca acest detector alimenteaza `tracker`. False = profil care
+    /// citeste harta altui detector, evaluat dupa acesta in bucla principala.
+    feeds_tracker: AtomicBool,
+
+    /// `tracking_key` al config-ului curent (recalculat la reload).
+    tracking_key: AtomicU64,
+
+    /// Cooldown alerte Fast Scan per (IP, scop) — vezi `ScopeKey`.
     /// Previne re-alertarea pentru acelasi IP inainte de expirarea cooldown-ului.
-    fast_cooldowns: DashMap<IpAddr, Instant>,
+    fast_cooldowns: DashMap<ScopeKey, Instant>,
+
+    /// Cooldown alerte Slow Scan per (IP, scop).
+    slow_cooldowns: DashMap<ScopeKey, Instant>,
+
+    /// Cooldown alerte Accept Scan per (IP, scop).
+    accept_cooldowns: DashMap<ScopeKey, Instant>,
 
-    /// Cooldown alerte Slow Scan per IP.
-    slow_cooldowns: DashMap<IpAddr, Instant>,
+    /// Ultimul avertisment provizoriu per IP sursa (`early_warning.cooldown_secs`).
+    early_cooldowns: DashMap<IpAddr, Instant>,
 
-    /// Cooldown alerte Accept Scan per IP.
-    accept_cooldowns: DashMap<IpAddr, Instant>,
+    /// Avertismentele provizorii produse de ultimele evenimente, preluate de
+    /// bucla principala prin `take_early_warnings`.
+    early_warnings: Mutex<Vec<EarlyWarning>>,
+
+    /// Depasiri de prag in asteptarea confirmarii (`confirm_windows > 1`).
+    /// Cheia este (IP sursa, regula) — Fast / Slow / Accept se confirma separat;
+    /// regulile posibile sunt `CONFIRM_RULES` (evictia sterge cheile direct).
+    pending_confirms: DashMap<(IpAddr, &'static str), PendingConfirm>,
 
     /// Ultimul moment cand fiecare IP a fost vazut (drop SAU accept).
     /// Folosit pentru LRU eviction: cand numarul de IP-uri urmarite ajunge
@@ -523,6 +1812,14 @@ pub struct Detector {
     /// Indexat dupa dest_ip — cooldown-ul este al tintei, nu al atacatorului.
     distributed_cooldowns: DashMap<IpAddr, Instant>,
 
+    /// Evidenta drop-urilor per bloc sursa (Coordinated Subnet Scan).
+    /// Acelasi index ca `distributed_hits`, dar cheia este prefixul sursei
+    /// (adresa de retea, ex: 203.0.113.0 pentru /24), nu tinta.
+    subnet_hits: DashMap<IpAddr, Vec<DistributedHit>>,
+
+    /// Cooldown alerte Coordinated Subnet Scan per prefix sursa.
+    subnet_cooldowns: DashMap<IpAddr, Instant>,
+
     /// Evidenta conexiunilor per flow Beaconing C2 (#24).
     /// Cheia este `(src_ip, dest_ip, dest_port)`; flow-urile diferite pe acelasi
     /// port sau intre aceleasi hosturi nu se amesteca. Valorile sunt timestamp-uri.
@@ -531,6 +1828,35 @@ pub struct Detector {
     /// Cooldown alerte Beaconing per flow (src, dst, dport).
     beacon_cooldowns: DashMap<(IpAddr, IpAddr, u16), Instant>,
 
+    /// Contor de pachete per IP sursa (Flood, fereastra fixa).
+    flood_counters: DashMap<IpAddr, FloodCounter>,
+
+    /// Cooldown alerte Flood per IP sursa.
+    flood_cooldowns: DashMap<IpAddr, Instant>,
+
+    /// Destinatiile distincte sondate per (IP sursa, port destinatie) — Slow
+    /// Sweep. O destinatie revizitata este mutata la coada (momentul nou).
+    sweep_hits: DashMap<(IpAddr, u16), Vec<DestHit>>,
+
+    /// Cooldown alerte Slow Sweep per (sursa, port).
+    sweep_cooldowns: DashMap<(IpAddr, u16), Instant>,
+
+    /// Datagrame malformate per expeditor UDP (Malformed Input, fereastra fixa).
+    /// Cheia este adresa care a trimis datagrama, nu o sursa din log-uri.
+    malformed_counters: DashMap<IpAddr, FloodCounter>,
+
+    /// Cooldown alerte Malformed Input per expeditor.
+    malformed_cooldowns: DashMap<IpAddr, Instant>,
+
+    /// Episoadele de scanare deschise per IP sursa (`scan_end_silence_secs`).
+    /// Nu sunt atinse de evictia LRU: scanerul evictat ramane in episod pana
+    /// la liniste. Plafonat la `max_tracked_ips` episoade.
+    scan_episodes: DashMap<IpAddr, ScanEpisode>,
+
+    /// Porturile sursa vazute per IP sursa (`detection.source_port_exhaustion`).
+    /// Alimentat doar cand detectia este activa si parserul raporteaza portul.
+    source_port_hits: DashMap<IpAddr, SourcePorts>,
+
     /// IP-uri si subretele excluse din detectie (parsate din config la constructie).
     /// Wrapat in ArcSwap pentru hot reload atomic la SIGHUP (#16).
     whitelist: ArcSwap<Vec<WhitelistEntry>>,
@@ -549,6 +1875,71 @@ pub struct Detector {
     /// Protejat de Mutex — accesat doar in cleanup (~60s) pentru write
     /// si in process_event pentru read (lock < 1μs, doar copie 3 floats).
     baseline: Mutex<BaselineState>,
+
+    /// Evictiile LRU recente (alerta de saturatie), numarate pe secunde:
+    /// (inceputul secundei, evictii). Coada ordonata cronologic — cele mai
+    /// vechi intrari sunt scoase din fata cand ies din fereastra
+    /// `saturation_alert.time_window_secs`, deci are cel mult o intrare per
+    /// secunda a ferestrei, oricat de multe evictii ar fi.
+    /// Mutex (nu DashMap): o singura coada globala, accesata doar la evictie.
+    evictions: Mutex<VecDeque<(Instant, u32)>>,
+
+    /// Momentul ultimei alerte de saturatie. Cooldown global (nu per IP):
+    /// saturatia este o stare a senzorului, nu a unui atacator anume.
+    saturation_cooldown: Mutex<Option<Instant>>,
+
+    /// Alerta "senzor fara trafic" (`no_traffic_alert_secs`): milisecundele
+    /// ultimului pachet primit, relative la `started`. Atomic (nu Mutex):
+    /// scris la fiecare datagrama, pe calea fierbinte a listener-ului.
+    started: Instant,
+    last_packet_ms: AtomicU64,
+    /// Linistea curenta a fost deja semnalata — o singura alerta per perioada
+    /// de liniste, rearmata de primul pachet care soseste.
+    silence_alerted: AtomicBool,
+
+    /// Surse care au prezentat o semnatura de scanner autorizat
+    /// (`trusted_signatures`). Value = momentul ultimului match; sursa este
+    /// suprimata pana la `suppress_secs` dupa acest moment.
+    trusted_sources: DashMap<IpAddr, Instant>,
+
+    /// Evenimente excluse inainte de reguli: whitelist, respectiv semnaturi de
+    /// scanner autorizat. Contabilitatea pachetelor (`--diagnostics-report`).
+    whitelisted_events: AtomicU64,
+    trusted_events: AtomicU64,
+
+    /// Hash-ul politicii de detectie active (`DetectionConfig::policy_version`).
+    /// Calculat o singura data la constructie si la fiecare reload — nu per alerta.
+    policy_version: ArcSwap<String>,
+
+    /// `network.traffic_direction = "inbound_only"`: senzorul vede doar un sens
+    /// al traficului. Regulile care depind de vizibilitate bidirectionala
+    /// (Accept Scan, Beaconing) sunt oprite explicit — altfel tacerea lor ar
+    /// parea o concluzie ("niciun port deschis"), nu o limita a senzorului.
+    inbound_only: AtomicBool,
+
+    /// Harta de active din CMDB (`enrichment.asset_map_path`). Partajata
+    /// intre detectoarele profilurilor — incarcata o singura data.
+    asset_map: ArcSwap<AssetMap>,
+    /// Subnetele cu active critice / importante (`[assets]`). Setate din
+    /// `main` la pornire si la SIGHUP — sectiune separata de `[detection]`.
+    assets: ArcSwap<AssetTiers>,
+
+    /// `detection.port_weights` parsat (port → pondere). Reconstruit la
+    /// constructie si la fiecare reload — nu per eveniment.
+    port_weights: ArcSwap<HashMap<u16, f64>>,
+
+    /// `alerting.summary_template` — setat din `main` la pornire si la SIGHUP.
+    /// Gol = alertele nu primesc `summary` (ex: detectorul din teste).
+    summary_template: ArcSwap<String>,
+
+    /// `alerting.detection_context` — setat din `main` la pornire si la SIGHUP.
+    /// Oprit: contextul calculat este eliminat inainte de emitere.
+    detection_context: AtomicBool,
+
+    /// Regulile din `detection.rules_dir`, inlocuite atomic la fiecare reload
+    /// al directorului. Setate doar pe detectorul implicit — profilurile nu
+    /// le evalueaza (alerta ar aparea o data per profil).
+    custom_rules: ArcSwap<RuleSet>,
 }
 
 impl Detector {
@@ -565,27 +1956,106 @@ impl Detector {
             .collect();
 
         let exceptions = ParsedExceptions::from_config(&config.exceptions);
+        let policy_version = config.policy_version();
+        let port_weights = parse_port_weights(&config);
+
+        let key = tracking_key(&config, false);
 
         Self {
-            port_hits: DashMap::new(),
-            accept_hits: DashMap::new(),
+            tracker: ArcSwap::from_pointee(PortTracker::new(key)),
+            feeds_tracker: AtomicBool::new(true),
+            tracking_key: AtomicU64::new(key),
             fast_cooldowns: DashMap::new(),
+            early_cooldowns: DashMap::new(),
+            early_warnings: Mutex::new(Vec::new()),
             slow_cooldowns: DashMap::new(),
             accept_cooldowns: DashMap::new(),
+            pending_confirms: DashMap::new(),
             lateral_hits: DashMap::new(),
             lateral_cooldowns: DashMap::new(),
             distributed_hits: DashMap::new(),
             distributed_cooldowns: DashMap::new(),
+            subnet_hits: DashMap::new(),
+            subnet_cooldowns: DashMap::new(),
             beacon_hits: DashMap::new(),
             beacon_cooldowns: DashMap::new(),
+            flood_counters: DashMap::new(),
+            flood_cooldowns: DashMap::new(),
+            sweep_hits: DashMap::new(),
+            sweep_cooldowns: DashMap::new(),
+            malformed_counters: DashMap::new(),
+            malformed_cooldowns: DashMap::new(),
+            scan_episodes: DashMap::new(),
+            source_port_hits: DashMap::new(),
             last_seen: DashMap::new(),
             whitelist: ArcSwap::from_pointee(whitelist),
             config: ArcSwap::from_pointee(config),
             exceptions: ArcSwap::from_pointee(exceptions),
             baseline: Mutex::new(BaselineState::new()),
+            evictions: Mutex::new(VecDeque::new()),
+            saturation_cooldown: Mutex::new(None),
+            started: Instant::now(),
+            last_packet_ms: AtomicU64::new(0),
+            silence_alerted: AtomicBool::new(false),
+            trusted_sources: DashMap::new(),
+            whitelisted_events: AtomicU64::new(0),
+            trusted_events: AtomicU64::new(0),
+            policy_version: ArcSwap::from_pointee(policy_version),
+            inbound_only: AtomicBool::new(false),
+            assets: ArcSwap::from_pointee(AssetTiers::default()),
+            asset_map: ArcSwap::from_pointee(AssetMap::default()),
+            port_weights: ArcSwap::from_pointee(port_weights),
+            summary_template: ArcSwap::from_pointee(String::new()),
+            detection_context: AtomicBool::new(false),
+            custom_rules: ArcSwap::from_pointee(RuleSet::default()),
         }
     }
 
+    /// Seteaza modul `inbound_only` (la pornire si la SIGHUP).
+    ///
+    /// Separat de `update_config` pentru ca optiunea apartine sectiunii
+    /// `[network]` (proprietate a tap-ului), nu politicii de detectie.
+    pub fn set_inbound_only(&self, inbound_only: bool) {
+        self.inbound_only.store(inbound_only, Ordering::Relaxed);
+        self.refresh_tracking_key(&self.config.load());
+    }
+
+    /// True daca regulile dependente de ambele sensuri sunt oprite.
+    pub fn inbound_only(&self) -> bool {
+        self.inbound_only.load(Ordering::Relaxed)
+    }
+
+    /// Seteaza subnetele `[assets]` folosite pentru `asset_criticality`
+    /// (la pornire si la SIGHUP).
+    pub fn set_assets(&self, assets: &AssetsConfig) {
+        self.assets.store(Arc::new(AssetTiers::from_config(assets)));
+    }
+
+    /// Seteaza harta de active (pornire si SIGHUP) folosita pentru `asset`.
+    pub fn set_asset_map(&self, asset_map: Arc<AssetMap>) {
+        self.asset_map.store(asset_map);
+    }
+
+    /// Inlocuieste setul de reguli din `detection.rules_dir`.
+    pub fn set_custom_rules(&self, rules: Arc<RuleSet>) {
+        self.custom_rules.store(rules);
+    }
+
+    /// Setul de reguli activ — baza pentru urmatorul reload al directorului.
+    pub fn custom_rules(&self) -> Arc<RuleSet> {
+        self.custom_rules.load_full()
+    }
+
+    /// Seteaza sablonul `summary` al alertelor (`alerting.summary_template`).
+    pub fn set_summary_template(&self, template: &str) {
+        self.summary_template.store(Arc::new(template.to_string()));
+    }
+
+    /// Ataseaza (sau nu) `detection_context` alertelor (`alerting.detection_context`).
+    pub fn set_detection_context(&self, enabled: bool) {
+        self.detection_context.store(enabled, Ordering::Relaxed);
+    }
+
     /// Actualizeaza configurarea detectorului la runtime (hot reload SIGHUP).
     ///
     /// NOTA RUST — SAFETY:
@@ -606,11 +2076,76 @@ impl Detector {
 
         // Re-parsam exceptiile din noua configurare.
         let new_exceptions = ParsedExceptions::from_config(&new_config.exceptions);
+        let new_policy_version = new_config.policy_version();
+        let new_port_weights = parse_port_weights(&new_config);
 
         // Swap atomic: noua configurare devine activa imediat.
         self.config.store(Arc::new(new_config));
+        self.policy_version.store(Arc::new(new_policy_version));
         self.whitelist.store(Arc::new(new_whitelist));
         self.exceptions.store(Arc::new(new_exceptions));
+        self.port_weights.store(Arc::new(new_port_weights));
+        self.refresh_tracking_key(&self.config.load());
+    }
+
+    /// Recalculeaza `tracking_key`; detectorul care alimenteaza harta ii
+    /// actualizeaza si amprenta (citita de profilurile care o partajeaza).
+    fn refresh_tracking_key(&self, cfg: &DetectionConfig) {
+        let key = tracking_key(cfg, self.inbound_only());
+        self.tracking_key.store(key, Ordering::Relaxed);
+        if self.feeds_tracker.load(Ordering::Relaxed) {
+            self.tracker.load().key.store(key, Ordering::Relaxed);
+        }
+    }
+
+    /// Citeste harta de urmarire a lui `source` in loc de una proprie, daca
+    /// parametrii de urmarire coincid (`tracking_key`). Intoarce true la succes.
+    ///
+    /// Apelat la pornire, inainte de primul eveniment. `source` trebuie sa
+    /// proceseze fiecare eveniment INAINTEA acestui detector — el inregistreaza
+    /// hit-ul pe care acesta doar il evalueaza (in `main`: detectorul implicit,
+    /// apoi profilurile in ordine).
+    pub fn share_tracking(&self, source: &Detector) -> bool {
+        if !source.feeds_tracker.load(Ordering::Relaxed)
+            || source.tracking_key.load(Ordering::Relaxed) != self.tracking_key.load(Ordering::Relaxed)
+        {
+            return false;
+        }
+        self.tracker.store(source.tracker.load_full());
+        self.feeds_tracker.store(false, Ordering::Relaxed);
+        true
+    }
+
+    /// Trece pe o harta proprie, goala, daca parametrii de urmarire ai acestui
+    /// detector nu mai coincid cu ai hartii partajate (reload cu alt whitelist
+    /// sau alte plafoane). Intoarce true daca s-a produs separarea.
+    ///
+    /// Verificat la fiecare eveniment (doua load-uri atomice) — o harta
+    /// alimentata dupa alte reguli ar da numaratori gresite.
+    pub fn detach_diverged_tracking(&self) -> bool {
+        if self.feeds_tracker.load(Ordering::Relaxed) {
+            return false;
+        }
+        let key = self.tracking_key.load(Ordering::Relaxed);
+        if self.tracker.load().key.load(Ordering::Relaxed) == key {
+            return false;
+        }
+        self.tracker.store(Arc::new(PortTracker::new(key)));
+        self.feeds_tracker.store(true, Ordering::Relaxed);
+        true
+    }
+
+    /// Snapshot al configurarii de detectie active (reflecta ultimul reload SIGHUP).
+    ///
+    /// `load_full()` returneaza un `Arc` independent — apelantul il poate tine
+    /// oricat (ex: serializare metrici) fara sa blocheze un swap ulterior.
+    pub fn config(&self) -> Arc<DetectionConfig> {
+        self.config.load_full()
+    }
+
+    /// Versiunea (hash scurt) a politicii de detectie active.
+    pub fn policy_version(&self) -> String {
+        self.policy_version.load().to_string()
     }
 
     /// Verifica daca un IP este in whitelist (exclus din detectie).
@@ -618,6 +2153,39 @@ impl Detector {
         self.whitelist.load().iter().any(|entry| entry.matches(ip))
     }
 
+    /// Evenimente excluse de la pornire: (whitelist, semnaturi autorizate).
+    pub fn filtered_events(&self) -> (u64, u64) {
+        (
+            self.whitelisted_events.load(Ordering::Relaxed),
+            self.trusted_events.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Verifica (si inregistreaza) o sursa cu semnatura de scanner autorizat.
+    ///
+    /// Un match in `raw_log` (re)porneste fereastra de suprimare pentru IP;
+    /// evenimentele fara marker sunt suprimate cat timp fereastra e activa.
+    fn is_trusted_source(&self, cfg: &DetectionConfig, event: &LogEvent, now: Instant) -> bool {
+        let ts = &cfg.trusted_signatures;
+        if ts.patterns.is_empty() {
+            return false;
+        }
+
+        if ts
+            .patterns
+            .iter()
+            .any(|pattern| event.raw_log.contains(pattern.as_str()))
+        {
+            self.trusted_sources.insert(event.source_ip, now);
+            return true;
+        }
+
+        let window = Duration::from_secs(ts.suppress_secs);
+        self.trusted_sources
+            .get(&event.source_ip)
+            .is_some_and(|since| now.saturating_duration_since(*since) < window)
+    }
+
     /// Proceseaza un eveniment de log si returneaza alertele detectate.
     ///
     /// NOTA RUST - BORROWING si LIFETIME-URI implicite:
@@ -636,6 +2204,21 @@ impl Detector {
     /// deoarece DashMap garanteaza consistenta prin sincronizare.
     ///
     pub fn process_event(&self, event: &LogEvent) -> Vec<Alert> {
+        self.process_event_inner(event, Explain(None))
+    }
+
+    /// Ca `process_event`, dar intoarce si motivele pentru care alertele
+    /// posibile au fost suprimate (whitelist, cooldown, sub prag etc.).
+    ///
+    /// "Sub prag" se raporteaza doar de la jumatatea pragului in sus —
+    /// altfel fiecare pachet izolat ar produce o linie.
+    pub fn process_event_explained(&self, event: &LogEvent) -> (Vec<Alert>, Vec<Suppression>) {
+        let mut suppressed = Vec::new();
+        let alerts = self.process_event_inner(event, Explain(Some(&mut suppressed)));
+        (alerts, suppressed)
+    }
+
+    fn process_event_inner(&self, event: &LogEvent, mut explain: Explain) -> Vec<Alert> {
         let now = Instant::now();
         let ip = event.source_ip;
 
@@ -645,10 +2228,41 @@ impl Detector {
         // eveniment continua cu config-ul vechi — urmatorul il va folosi pe cel nou.
         let cfg = self.config.load();
 
+        // Harta de urmarire a porturilor: proprie sau partajata cu un detector
+        // evaluat inainte (`share_tracking`) — atunci doar o citim.
+        self.detach_diverged_tracking();
+        let tracker = self.tracker.load_full();
+        let feeds_tracker = self.feeds_tracker.load(Ordering::Relaxed);
+
         // --- 0. Whitelist check ---
         // IP-urile din whitelist sunt excluse complet din detectie.
         // Nu consuma memorie in DashMap, nu genereaza alerte.
         if self.is_whitelisted(&ip) {
+            self.whitelisted_events.fetch_add(1, Ordering::Relaxed);
+            explain.note("whitelist", ip, || {
+                // Gasim intrarea textuala din config (doar in modul explain).
+                let entry = cfg
+                    .whitelist
+                    .iter()
+                    .find(|e| WhitelistEntry::parse(e).is_some_and(|w| w.matches(&ip)))
+                    .map(String::as_str)
+                    .unwrap_or("?");
+                format!("sursa potrivita de intrarea whitelist {}", entry)
+            });
+            return Vec::new();
+        }
+
+        // --- 0a. Semnaturi de scanere autorizate ---
+        // Sursa care s-a anuntat printr-un marker in payload este tratata ca
+        // whitelisted pe durata `suppress_secs` (IP-uri variabile, nu in whitelist).
+        if self.is_trusted_source(&cfg, event, now) {
+            self.trusted_events.fetch_add(1, Ordering::Relaxed);
+            explain.note("trusted_signatures", ip, || {
+                format!(
+                    "sursa anuntata ca scanner autorizat (suprimare {}s)",
+                    cfg.trusted_signatures.suppress_secs
+                )
+            });
             return Vec::new();
         }
 
@@ -664,6 +2278,27 @@ impl Detector {
             )
         };
 
+        // --- 0c. Reset dupa inactivitate (inactivity_reset_secs) ---
+        //
+        // O sursa tacuta mai mult de prag isi pierde hit-urile acumulate: o
+        // scanare reluata dupa pauza se numara de la zero, in loc sa completeze
+        // pragul cu porturi vazute inainte de pauza. Cooldown-urile raman —
+        // resetul priveste baza de numarare, nu deduplicarea alertelor.
+        if cfg.inactivity_reset_secs > 0 {
+            let reset_after = Duration::from_secs(cfg.inactivity_reset_secs);
+            // Copiem valoarea si eliberam Ref-ul inainte de remove (fara deadlock).
+            let last = self.last_seen.get(&ip).map(|e| *e.value());
+            if let Some(last) = last {
+                if now.saturating_duration_since(last) > reset_after {
+                    if feeds_tracker {
+                        tracker.remove(ip);
+                    }
+                    self.lateral_hits.remove(&ip);
+                    self.source_port_hits.remove(&ip);
+                }
+            }
+        }
+
         // --- 1. Limitare globala IP-uri (anti-IP-spoofing flood) ---
         //
         // NOTA #4 - LRU EVICTION:
@@ -699,6 +2334,7 @@ impl Detector {
         // Problema: un IP care trimite doar "accept"-uri (fara "drop") nu aparea in
         // port_hits → nu era considerat "urmarit" → evictia nu se activa corect.
         // Acum: `last_seen` urmareste orice IP, indiferent de actiune.
+        let mut saturation_alert: Option<Alert> = None;
         let is_new_ip = !self.last_seen.contains_key(&ip);
         if is_new_ip && self.last_seen.len() >= cfg.max_tracked_ips {
             // Gasim IP-ul cu cel mai vechi last_seen (Least Recently Used).
@@ -709,15 +2345,35 @@ impl Detector {
                 .map(|e| *e.key());
 
             if let Some(old_ip) = lru_ip {
-                // Eliminam IP-ul LRU din TOATE structurile.
-                self.port_hits.remove(&old_ip);
-                self.accept_hits.remove(&old_ip);
+                // Eliminam IP-ul LRU din TOATE structurile. Harta partajata
+                // este curatata de detectorul care o alimenteaza — acelasi
+                // IP, cu acelasi istoric `last_seen`.
+                if feeds_tracker {
+                    tracker.remove(old_ip);
+                }
                 self.lateral_hits.remove(&old_ip);
                 self.last_seen.remove(&old_ip);
-                self.fast_cooldowns.remove(&old_ip);
-                self.slow_cooldowns.remove(&old_ip);
-                self.accept_cooldowns.remove(&old_ip);
+                remove_scoped(&self.fast_cooldowns, old_ip);
+                remove_scoped(&self.slow_cooldowns, old_ip);
+                self.early_cooldowns.remove(&old_ip);
+                remove_scoped(&self.accept_cooldowns, old_ip);
+                for rule in CONFIRM_RULES {
+                    self.pending_confirms.remove(&(old_ip, rule));
+                }
                 self.lateral_cooldowns.remove(&old_ip);
+                self.flood_counters.remove(&old_ip);
+                self.flood_cooldowns.remove(&old_ip);
+                self.source_port_hits.remove(&old_ip);
+                // sweep_hits / sweep_cooldowns (cheie (IP, port)) NU sunt
+                // parcurse aici: un `retain` dupa IP ar fi O(n) la fiecare
+                // evictie, exact cand tabela e plina. Au propriul plafon LRU
+                // (`max_tracked_ips` chei) si expira in `cleanup()` dupa
+                // fereastra Slow Sweep / cooldown.
+            }
+
+            // --- 1b. Alerta de saturatie (evictii sub presiune) ---
+            if let Some(alert) = self.record_eviction(&cfg, ip, now) {
+                saturation_alert = Some(alert);
             }
         }
 
@@ -749,18 +2405,48 @@ impl Detector {
         // `.drain(..N)` sterge primele N elemente (cele mai vechi, oldest-first).
         // Aplica aceeasi limita (max_hits_per_ip) la ambele map-uri.
         //
-        let hits_map: &DashMap<IpAddr, Vec<PortHit>> = if event.action == "drop" {
-            &self.port_hits
+        let (hits_map, overflow_map) = if event.action == "drop" {
+            (&tracker.port_hits, &tracker.port_overflow)
         } else {
             // "accept" si orice alta actiune filtrata de parser → accept_hits.
-            &self.accept_hits
+            (&tracker.accept_hits, &tracker.accept_overflow)
         };
-        {
+        // inbound_only: accept_hits alimenteaza doar Accept Scan (oprit) —
+        // nu mai consumam memorie pe ele.
+        let inbound_only = self.inbound_only();
+        let record_hit = event.action == "drop" || !inbound_only;
+        // Sonda UDP goala: doar cand parserul a raportat octetii pachetului
+        // si acestia sunt exact antetele IP + UDP.
+        let empty_probe = cfg.empty_udp_probes.enabled && event.is_empty_udp_datagram();
+        // per_protocol_tracking = "separate": Fast/Slow/Accept Scan numara
+        // doar porturile cu protocolul evenimentului curent — (sursa, protocol)
+        // devine unitatea de numarare. "combined": toate protocoalele la un loc.
+        let protocol = tracked_protocol(&event.protocol);
+        let proto_filter = (cfg.per_protocol_tracking == "separate").then_some(protocol);
+        // Cooldown-urile (si precedenta) Fast / Slow / Accept au acelasi scop.
+        let scope: ScopeKey = (ip, proto_filter.unwrap_or(ALL_PROTOCOLS));
+        if record_hit && feeds_tracker {
             let mut hits = hits_map.entry(ip).or_default();
-            hits.push(PortHit {
-                port: event.dest_port,
-                seen_at: now,
-            });
+
+            // Plafon porturi distincte (max_ports_per_ip): un port NOU peste
+            // plafon este doar numarat. Porturile deja memorate se actualizeaza
+            // normal, deci ferestrele raman corecte pentru ele.
+            let cap = cfg.max_ports_per_ip;
+            // `hits.len() >= cap` intai: sub plafon nu numaram porturile deloc.
+            let over_cap = cap > 0
+                && hits.len() >= cap
+                && !hits.iter().any(|h| h.port == event.dest_port)
+                && distinct_ports(&hits) >= cap;
+            if over_cap {
+                overflow_map.entry((ip, protocol)).or_default().record(now);
+            } else {
+                hits.push(PortHit {
+                    port: event.dest_port,
+                    seen_at: now,
+                    empty_probe,
+                    protocol,
+                });
+            }
 
             // Cap la max_hits_per_ip: pastram doar cele mai recente intrari.
             let max_hits = cfg.max_hits_per_ip;
@@ -770,7 +2456,62 @@ impl Detector {
             }
         }
 
-        let mut alerts = Vec::new();
+        // Portul sursa (source_port_exhaustion): drop si accept, orice port
+        // destinatie — un connect scan consuma un port efemer per sonda.
+        if cfg.source_port_exhaustion.enabled {
+            if let Some(source_port) = event.source_port {
+                self.source_port_hits
+                    .entry(ip)
+                    .or_default()
+                    .record(source_port, now, cfg.max_hits_per_ip);
+            }
+        }
+
+        let mut alerts: Vec<Alert> = saturation_alert.into_iter().collect();
+
+        // --- 2b. Flood volumetric (detection.flood) ---
+        //
+        // Numara TOATE pachetele sursei (drop si accept, oricare port) intr-o
+        // fereastra fixa. Pragul este pe rata medie: `pps × fereastra` pachete.
+        // Un flood de 2× prag atinge limita la jumatatea ferestrei.
+        let flood_cfg = &cfg.flood;
+        if flood_cfg.enabled {
+            let window = Duration::from_secs(flood_cfg.time_window_secs);
+            // Scop explicit: RefMut-ul este eliberat inainte de cooldown.
+            let (packets, elapsed) = {
+                let mut counter = self.flood_counters.entry(ip).or_insert(FloodCounter {
+                    window_start: now,
+                    packets: 0,
+                });
+                if now.saturating_duration_since(counter.window_start) >= window {
+                    counter.window_start = now;
+                    counter.packets = 0;
+                }
+                counter.packets += 1;
+                (counter.packets, now.saturating_duration_since(counter.window_start))
+            };
+            let limit = flood_cfg
+                .packets_per_sec_threshold
+                .saturating_mul(flood_cfg.time_window_secs);
+            if packets >= limit && !self.in_cooldown(&self.flood_cooldowns, ip) {
+                self.flood_cooldowns.insert(ip, now);
+                // Rata de la inceputul ferestrei; minim 1s — o rafala de
+                // cateva milisecunde nu raporteaza milioane de pachete/s.
+                let rate = packets as f64 / elapsed.as_secs_f64().max(1.0);
+                alerts.push(Alert {
+                    event_count: Some(packets as usize),
+                    packet_rate: Some(rate),
+                    detection_context: Some(DetectionContext::new(
+                        "flood",
+                        packets as usize,
+                        limit as usize,
+                        "pachete",
+                        window,
+                    )),
+                    ..Alert::new(ScanType::Flood, ip, event.dest_ip)
+                });
+            }
+        }
 
         // --- 3. Verificam Fast Scan (pe port_hits — drop-uri) ---
         //
@@ -778,44 +2519,140 @@ impl Detector {
         // Aceasta este o REFACTORIZARE necesara: inainte functia accesa `self.port_hits`
         // direct (hardcodat). Acum poate lucra cu orice DashMap de tip corect,
         // ceea ce ne permite sa o refolosim pentru Accept Scan (pasul 5) cu `accept_hits`.
+        let weights = self.port_weights.load();
+        let weighted = !weights.is_empty();
+
+        // Epuizarea porturilor sursa coboara pragurile Fast / Accept Scan ale
+        // sursei cat timp tiparul este prezent (amplificator de incredere).
+        let exhaustion = self.source_port_run(&cfg, ip, now);
+        let (fast_threshold, accept_threshold) = match exhaustion {
+            Some(_) => {
+                let factor = cfg.source_port_exhaustion.threshold_factor;
+                (
+                    scaled_threshold(fast_threshold, factor),
+                    scaled_threshold(accept_threshold, factor),
+                )
+            }
+            None => (fast_threshold, accept_threshold),
+        };
         let fast_window = Duration::from_secs(cfg.fast_scan.time_window_secs);
-        if let Some(ports) = self.unique_ports_in_window(&self.port_hits, ip, fast_window, now) {
-            if ports.len() >= fast_threshold && !self.in_cooldown(&self.fast_cooldowns, ip) {
-                self.fast_cooldowns.insert(ip, now);
+        if let Some(ports) = self.unique_ports_in_window(&tracker.port_hits, ip, fast_window, now, proto_filter) {
+            let empty = self.empty_probe_ports(&cfg, &tracker.port_hits, ip, fast_window, now, proto_filter);
+            let overflow = overflow_in_window(&tracker.port_overflow, ip, fast_window, now, proto_filter);
+            let score = weighted_port_count(&ports, overflow, empty, &cfg, &weights);
+            if explain.enabled() {
+                self.explain_threshold(&mut explain, "fast_scan", self.cooldown_remaining(&self.fast_cooldowns, scope), ip, score, fast_threshold);
+            }
+            // Promovare slow → fast: scanarea deja alertata ca Slow a accelerat.
+            // Clasificarea mai urgenta castiga si peste rule_precedence = "slow".
+            let promoted = cfg.slow_scan.promote_to_fast && self.in_cooldown(&self.slow_cooldowns, scope);
+            if score >= fast_threshold as f64
+                && !self.in_cooldown(&self.fast_cooldowns, scope)
+                && (promoted || !self.outranked(&cfg, &mut explain, "fast_scan", scope))
+                && self.confirm_crossing(&cfg, &mut explain, "fast_scan", ip, fast_window, now)
+            {
+                self.fast_cooldowns.insert(scope, now);
+                if promoted {
+                    // Urmarirea slow este inlocuita: cooldown-ul Slow reinceput,
+                    // fara o noua alerta Slow Scan pentru aceeasi scanare.
+                    self.slow_cooldowns.insert(scope, now);
+                }
+                let protocols = self.protocols_in_window(&tracker.port_hits, ip, fast_window, now, proto_filter);
+                let time_to_threshold_ms = self.time_to_threshold_ms(&tracker.port_hits, ip, fast_window, now, proto_filter);
                 alerts.push(Alert {
+                    approx_port_count: (overflow > 0).then_some(ports.len() + overflow),
+                    weighted_score: weighted.then_some(score),
+                    detection_context: Some(DetectionContext {
+                        weighted_count: weighted.then_some(score),
+                        time_to_threshold_ms,
+                        ..DetectionContext::new("fast_scan", ports.len() + overflow, fast_threshold, "porturi", fast_window)
+                    }),
+                    unique_ports: ports,
+                    time_to_threshold_ms,
+                    empty_probes: empty > 0,
+                    promoted_from_slow: promoted,
+                    multi_protocol: protocols.len() > 1,
+                    protocols,
+                    source_port_exhaustion: exhaustion,
+                    ..Alert::new(ScanType::Fast, ip, event.dest_ip)
+                });
+            } else if !self.in_cooldown(&self.fast_cooldowns, scope) {
+                let warning = EarlyWarning {
                     scan_type: ScanType::Fast,
                     source_ip: ip,
                     dest_ip: event.dest_ip,
-                    unique_ports: ports,
-                    unique_dests: Vec::new(),
-                    unique_sources: Vec::new(),
-                    timestamp: Local::now(),
-                    beacon_port: None,
-                    mean_interval_secs: None,
-                    cv: None,
-                    event_count: None,
-                });
+                    score,
+                    threshold: fast_threshold,
+                };
+                self.early_warning(&cfg, warning, now);
             }
         }
 
         // --- 4. Verificam Slow Scan (pe port_hits — drop-uri) ---
         let slow_window = Duration::from_secs(cfg.slow_scan.time_window_mins * 60);
-        if let Some(ports) = self.unique_ports_in_window(&self.port_hits, ip, slow_window, now) {
-            if ports.len() >= slow_threshold && !self.in_cooldown(&self.slow_cooldowns, ip) {
-                self.slow_cooldowns.insert(ip, now);
+        if let Some(ports) = self.unique_ports_in_window(&tracker.port_hits, ip, slow_window, now, proto_filter) {
+            let empty = self.empty_probe_ports(&cfg, &tracker.port_hits, ip, slow_window, now, proto_filter);
+            let overflow = overflow_in_window(&tracker.port_overflow, ip, slow_window, now, proto_filter);
+            let mut score = weighted_port_count(&ports, overflow, empty, &cfg, &weights);
+            // Credit partial pentru porturile ferestrei anterioare (carry_over):
+            // o scanare mai lenta decat fereastra nu ramane sub prag la nesfarsit.
+            let carry_over = cfg.slow_scan.carry_over;
+            let carried = if carry_over > 0.0 {
+                self.previous_window_ports(&tracker.port_hits, ip, slow_window, now, proto_filter, &ports)
+            } else {
+                Vec::new()
+            };
+            score += carry_over * port_weight_sum(&carried, &weights);
+            if explain.enabled() {
+                self.explain_threshold(&mut explain, "slow_scan", self.cooldown_remaining(&self.slow_cooldowns, scope), ip, score, slow_threshold);
+            }
+            if score >= slow_threshold as f64
+                && !self.in_cooldown(&self.slow_cooldowns, scope)
+                && !self.outranked(&cfg, &mut explain, "slow_scan", scope)
+                && self.confirm_crossing(&cfg, &mut explain, "slow_scan", ip, slow_window, now)
+            {
+                self.slow_cooldowns.insert(scope, now);
+                // Precedenta "slow": ambele praguri atinse de acelasi eveniment —
+                // alerta Fast Scan de mai sus este retrasa (cooldown-ul ei ramane).
+                if cfg.rule_precedence == "slow" {
+                    alerts.retain(|a| {
+                        let fast = matches!(a.scan_type, ScanType::Fast);
+                        if fast {
+                            explain.note("fast_scan", ip, || {
+                                "suprimat de rule_precedence = \"slow\": Slow Scan emis simultan".to_string()
+                            });
+                        }
+                        !fast
+                    });
+                }
+                let protocols = self.protocols_in_window(&tracker.port_hits, ip, slow_window, now, proto_filter);
+                let time_to_threshold_ms = self.time_to_threshold_ms(&tracker.port_hits, ip, slow_window, now, proto_filter);
+                let weighted_score = (weighted || !carried.is_empty()).then_some(score);
                 alerts.push(Alert {
+                    approx_port_count: (overflow > 0).then_some(ports.len() + overflow),
+                    weighted_score,
+                    detection_context: Some(DetectionContext {
+                        weighted_count: weighted_score,
+                        time_to_threshold_ms,
+                        ..DetectionContext::new("slow_scan", ports.len() + overflow, slow_threshold, "porturi", slow_window)
+                    }),
+                    carried_ports: carried,
+                    unique_ports: ports,
+                    time_to_threshold_ms,
+                    empty_probes: empty > 0,
+                    multi_protocol: protocols.len() > 1,
+                    protocols,
+                    ..Alert::new(ScanType::Slow, ip, event.dest_ip)
+                });
+            } else if !self.in_cooldown(&self.slow_cooldowns, scope) {
+                let warning = EarlyWarning {
                     scan_type: ScanType::Slow,
                     source_ip: ip,
                     dest_ip: event.dest_ip,
-                    unique_ports: ports,
-                    unique_dests: Vec::new(),
-                    unique_sources: Vec::new(),
-                    timestamp: Local::now(),
-                    beacon_port: None,
-                    mean_interval_secs: None,
-                    cv: None,
-                    event_count: None,
-                });
+                    score,
+                    threshold: slow_threshold,
+                };
+                self.early_warning(&cfg, warning, now);
             }
         }
 
@@ -830,23 +2667,42 @@ impl Detector {
         // Separarea completa de Fast/Slow Scan inseamna ca un IP poate declansa
         // simultan o alerta Fast Scan (din drop-uri) SI o alerta Accept Scan (din
         // accept-uri) — si amandoua vor fi trimise la SIEM si email, independent.
+        //
+        // Oprit in modul inbound_only (vezi campul `inbound_only`).
         let accept_window = Duration::from_secs(cfg.accept_scan.time_window_secs);
-        if let Some(ports) = self.unique_ports_in_window(&self.accept_hits, ip, accept_window, now)
-        {
-            if ports.len() >= accept_threshold && !self.in_cooldown(&self.accept_cooldowns, ip) {
-                self.accept_cooldowns.insert(ip, now);
+        let accept_ports = if inbound_only {
+            None
+        } else {
+            self.unique_ports_in_window(&tracker.accept_hits, ip, accept_window, now, proto_filter)
+        };
+        if let Some(ports) = accept_ports {
+            let overflow = overflow_in_window(&tracker.accept_overflow, ip, accept_window, now, proto_filter);
+            let total = ports.len() + overflow;
+            let score = port_weight_sum(&ports, &weights) + overflow as f64;
+            if explain.enabled() {
+                self.explain_threshold(&mut explain, "accept_scan", self.cooldown_remaining(&self.accept_cooldowns, scope), ip, score, accept_threshold);
+            }
+            if score >= accept_threshold as f64
+                && !self.in_cooldown(&self.accept_cooldowns, scope)
+                && self.confirm_crossing(&cfg, &mut explain, "accept_scan", ip, accept_window, now)
+            {
+                self.accept_cooldowns.insert(scope, now);
+                let protocols = self.protocols_in_window(&tracker.accept_hits, ip, accept_window, now, proto_filter);
+                let time_to_threshold_ms = self.time_to_threshold_ms(&tracker.accept_hits, ip, accept_window, now, proto_filter);
                 alerts.push(Alert {
-                    scan_type: ScanType::AcceptScan,
-                    source_ip: ip,
-                    dest_ip: event.dest_ip,
+                    approx_port_count: (overflow > 0).then_some(total),
+                    weighted_score: weighted.then_some(score),
+                    detection_context: Some(DetectionContext {
+                        weighted_count: weighted.then_some(score),
+                        time_to_threshold_ms,
+                        ..DetectionContext::new("accept_scan", total, accept_threshold, "porturi", accept_window)
+                    }),
                     unique_ports: ports,
-                    unique_dests: Vec::new(),
-                    unique_sources: Vec::new(),
-                    timestamp: Local::now(),
-                    beacon_port: None,
-                    mean_interval_secs: None,
-                    cv: None,
-                    event_count: None,
+                    time_to_threshold_ms,
+                    multi_protocol: protocols.len() > 1,
+                    protocols,
+                    source_port_exhaustion: exhaustion,
+                    ..Alert::new(ScanType::AcceptScan, ip, event.dest_ip)
                 });
             }
         }
@@ -870,6 +2726,15 @@ impl Detector {
             if let Some(dest_ip) = event.dest_ip {
                 // Exceptii: authorized_scanners nu declanseaza Lateral Movement.
                 // Porturi din ignore_lateral_ports nu se contorizeaza.
+                if event.action == "accept" && exc.authorized_scanners.contains(&ip) {
+                    explain.note("lateral_movement", ip, || {
+                        "sursa este in exceptions.authorized_scanners".to_string()
+                    });
+                } else if event.action == "accept" && exc.ignore_lateral_ports.contains(&event.dest_port) {
+                    explain.note("lateral_movement", ip, || {
+                        format!("portul {} este in exceptions.ignore_lateral_ports", event.dest_port)
+                    });
+                }
                 if event.action == "accept"
                     && !exc.authorized_scanners.contains(&ip)
                     && !exc.ignore_lateral_ports.contains(&event.dest_port)
@@ -892,22 +2757,34 @@ impl Detector {
                     // Colectam destinatiile unice in fereastra de timp.
                     let lm_window = Duration::from_secs(lm_cfg.time_window_secs);
                     if let Some(unique_dests) = self.unique_dests_in_window(ip, lm_window, now) {
-                        if unique_dests.len() >= lm_cfg.unique_dest_threshold
+                        if explain.enabled() {
+                            self.explain_threshold(
+                                &mut explain,
+                                "lateral_movement",
+                                self.cooldown_remaining(&self.lateral_cooldowns, ip),
+                                ip,
+                                unique_dests.len() as f64,
+                                lm_cfg.unique_dest_threshold.max(cfg.sweep.min_distinct_targets),
+                            );
+                        }
+                        // detection.sweep.min_distinct_targets: prag minim de tinte.
+                        let lm_threshold = lm_cfg
+                            .unique_dest_threshold
+                            .max(cfg.sweep.min_distinct_targets);
+                        if unique_dests.len() >= lm_threshold
                             && !self.in_cooldown(&self.lateral_cooldowns, ip)
                         {
                             self.lateral_cooldowns.insert(ip, now);
                             alerts.push(Alert {
-                                scan_type: ScanType::LateralMovement,
-                                source_ip: ip,
-                                dest_ip: Some(dest_ip),
-                                unique_ports: Vec::new(),
+                                detection_context: Some(DetectionContext::new(
+                                    "lateral_movement",
+                                    unique_dests.len(),
+                                    lm_threshold,
+                                    "destinatii",
+                                    lm_window,
+                                )),
                                 unique_dests,
-                                unique_sources: Vec::new(),
-                                timestamp: Local::now(),
-                                beacon_port: None,
-                                mean_interval_secs: None,
-                                cv: None,
-                                event_count: None,
+                                ..Alert::new(ScanType::LateralMovement, ip, Some(dest_ip))
                             });
                         }
                     }
@@ -941,87 +2818,133 @@ impl Detector {
                 } else
                 // Inregistram hit-ul in distributed_hits pentru IP-ul destinatie.
                 {
-                    // --- Cap pe numarul de tinte urmarite ---
-                    //
-                    // `distributed_hits` este indexat dupa dest_ip, deci LRU-ul
-                    // global pe `last_seen` (source-keyed) nu afecteaza aceasta
-                    // tabela. Pentru a preveni cresterea nelimitata a numarului
-                    // de chei (#bug-8), aplicam o limita explicita = `max_tracked_ips`
-                    // si eviciem LRU-ul cand atingem capacitatea.
-                    //
-                    // LRU criteriu: ultima intrare (cea mai recenta) din vector-ul
-                    // de hits per dest. Vectorul fiind populat append-only,
-                    // `.last()` da `seen_at` cel mai recent O(1).
-                    let is_new_dest = !self.distributed_hits.contains_key(&dest_ip);
-                    if is_new_dest && self.distributed_hits.len() >= cfg.max_tracked_ips {
-                        let lru_dest: Option<IpAddr> = self
-                            .distributed_hits
-                            .iter()
-                            .min_by_key(|e| e.value().last().map(|h| h.seen_at).unwrap_or(now))
-                            .map(|e| *e.key());
-                        if let Some(old) = lru_dest {
-                            self.distributed_hits.remove(&old);
-                            self.distributed_cooldowns.remove(&old);
-                        }
-                    }
-
-                    let mut hits = self.distributed_hits.entry(dest_ip).or_default();
-                    hits.push(DistributedHit {
-                        source_ip: ip,
-                        port: event.dest_port,
-                        seen_at: now,
-                    });
-                    // Cap memorie: refolosim max_hits_per_ip ca limita.
-                    let max_hits = cfg.max_hits_per_ip;
-                    if hits.len() > max_hits {
-                        let overflow = hits.len() - max_hits;
-                        hits.drain(..overflow);
-                    }
+                    self.record_source_hit(
+                        &self.distributed_hits,
+                        &self.distributed_cooldowns,
+                        dest_ip,
+                        DistributedHit {
+                            source_ip: ip,
+                            port: event.dest_port,
+                            seen_at: now,
+                            dest_ip: Some(dest_ip),
+                        },
+                        &cfg,
+                        now,
+                    );
                 }
 
                 // Colectam sursele unice si porturile in fereastra de timp.
                 let ds_window = Duration::from_secs(ds_cfg.time_window_secs);
                 if let Some((unique_srcs, targeted_ports)) =
-                    self.unique_sources_in_window(dest_ip, ds_window, now)
+                    self.unique_sources_in_window(&self.distributed_hits, dest_ip, ds_window, now)
                 {
                     if unique_srcs.len() >= ds_cfg.unique_sources_threshold
                         && !self.in_cooldown(&self.distributed_cooldowns, dest_ip)
                     {
                         self.distributed_cooldowns.insert(dest_ip, now);
                         alerts.push(Alert {
-                            scan_type: ScanType::DistributedScan,
-                            source_ip: ip,
-                            dest_ip: Some(dest_ip),
+                            detection_context: Some(DetectionContext::new(
+                                "distributed_scan",
+                                unique_srcs.len(),
+                                ds_cfg.unique_sources_threshold,
+                                "surse",
+                                ds_window,
+                            )),
                             unique_ports: targeted_ports,
-                            unique_dests: Vec::new(),
                             unique_sources: unique_srcs,
-                            timestamp: Local::now(),
-                            beacon_port: None,
-                            mean_interval_secs: None,
-                            cv: None,
-                            event_count: None,
+                            ..Alert::new(ScanType::DistributedScan, ip, Some(dest_ip))
                         });
                     }
                 }
             }
         }
 
-        // --- 8. Verificam Beaconing C2 (#24) ---
+        // --- 7b. Verificam Coordinated Subnet Scan ---
         //
-        // Conditii:
-        //   a) Beaconing activat in config
-        //   b) dest_ip prezent
-        //   c) action == "accept" (callback C2 e o conexiune reusita; drop-urile
-        //      reflecta un firewall care taie traficul → nu mai e beacon viabil)
+        // Drop-urile se agrega dupa PREFIXUL sursei (implicit /24): surse
+        // diferite din acelasi bloc, fiecare sub prag, pot fi un singur actor.
+        // Reutilizam indexul Distributed Scan (`DistributedHit`, plafon LRU,
+        // `unique_sources_in_window`) cu alta cheie — prefixul, nu tinta.
+        let cs_cfg = &cfg.coordinated_subnet;
+        if cs_cfg.enabled && event.action == "drop" {
+            let prefix = source_prefix(ip, cs_cfg.prefix_len_v4, cs_cfg.prefix_len_v6);
+            self.record_source_hit(
+                &self.subnet_hits,
+                &self.subnet_cooldowns,
+                prefix,
+                DistributedHit {
+                    source_ip: ip,
+                    port: event.dest_port,
+                    seen_at: now,
+                    dest_ip: event.dest_ip,
+                },
+                &cfg,
+                now,
+            );
+            let cs_window = Duration::from_secs(cs_cfg.time_window_secs);
+            if let Some((sources, ports)) =
+                self.unique_sources_in_window(&self.subnet_hits, prefix, cs_window, now)
+            {
+                // Sweep: blocul trebuie sa fi atins destule tinte distincte —
+                // clientii unui NAT catre acelasi server nu sunt o scanare.
+                let targets = self.distinct_targets_in_window(&self.subnet_hits, prefix, cs_window, now);
+                let min_targets = cfg.sweep.min_distinct_targets;
+                let enough_targets = targets.len() >= min_targets;
+                if !enough_targets && sources.len() >= cs_cfg.min_sources && ports.len() >= cs_cfg.port_threshold {
+                    explain.note("coordinated_subnet", ip, || {
+                        format!(
+                            "{} tinte distincte < sweep.min_distinct_targets {}",
+                            targets.len(),
+                            min_targets
+                        )
+                    });
+                }
+                if sources.len() >= cs_cfg.min_sources
+                    && ports.len() >= cs_cfg.port_threshold
+                    && enough_targets
+                    && !self.in_cooldown(&self.subnet_cooldowns, prefix)
+                {
+                    self.subnet_cooldowns.insert(prefix, now);
+                    let prefix_len = match prefix {
+                        IpAddr::V4(_) => cs_cfg.prefix_len_v4,
+                        IpAddr::V6(_) => cs_cfg.prefix_len_v6,
+                    };
+                    alerts.push(Alert {
+                        source_prefix: Some(format!("{}/{}", prefix, prefix_len)),
+                        detection_context: Some(DetectionContext::new(
+                            "coordinated_subnet",
+                            ports.len(),
+                            cs_cfg.port_threshold,
+                            "porturi",
+                            cs_window,
+                        )),
+                        unique_ports: ports,
+                        unique_dests: targets,
+                        unique_sources: sources,
+                        ..Alert::new(ScanType::CoordinatedSubnet, ip, event.dest_ip)
+                    });
+                }
+            }
+        }
+
+        // --- 8. Verificam Beaconing C2 (#24) ---
+        //
+        // Conditii:
+        //   a) Beaconing activat in config
+        //   b) dest_ip prezent
+        //   c) action == "accept" (callback C2 e o conexiune reusita; drop-urile
+        //      reflecta un firewall care taie traficul → nu mai e beacon viabil)
         //   d) Portul destinatie nu e in ignore_beaconing_ports
         //   e) Sursa nu e in authorized_beaconing_sources
+        //   f) Senzorul vede ambele sensuri (callback-ul C2 este trafic
+        //      outbound — invizibil in modul inbound_only)
         //
         // Algoritm: pe cheia (src, dst, dport) acumulam timestamp-uri. Cand
         // avem >= min_events in fereastra, calculam CV pe intervalele intre
         // timestamp-uri consecutive. CV mic + mean in [min_interval, max_interval]
         // => beaconing.
         let bc_cfg = &cfg.beaconing;
-        if bc_cfg.enabled && event.action == "accept" {
+        if bc_cfg.enabled && event.action == "accept" && !inbound_only {
             if let Some(dest_ip) = event.dest_ip {
                 let dport = event.dest_port;
                 let flow_key = (ip, dest_ip, dport);
@@ -1055,30 +2978,37 @@ impl Detector {
                     }
 
                     // Calcul CV pe fereastra activa.
+                    //
+                    // Cu `jitter_percent > 0` decizia de periodicitate foloseste
+                    // intervalele consistente fata de mediana (robust la jitter si
+                    // la beacon-uri ratate); mediana inlocuieste si media la
+                    // verificarea [min_interval, max_interval].
                     let window = Duration::from_secs(bc_cfg.time_window_secs);
                     if let Some((mean_secs, cv, count)) =
                         self.beacon_stats(&flow_key, window, now)
                     {
-                        let mean_ok = mean_secs >= bc_cfg.min_interval_secs as f64
-                            && mean_secs <= bc_cfg.max_interval_secs as f64;
+                        let (interval_secs, periodic) = if bc_cfg.jitter_percent > 0.0 {
+                            let intervals = self.beacon_intervals(&flow_key, window, now);
+                            let (median, consistent) =
+                                jitter_consistency(&intervals, bc_cfg.jitter_percent);
+                            (median, consistent >= bc_cfg.min_consistent_intervals)
+                        } else {
+                            (mean_secs, cv <= bc_cfg.cv_threshold)
+                        };
+                        let mean_ok = interval_secs >= bc_cfg.min_interval_secs as f64
+                            && interval_secs <= bc_cfg.max_interval_secs as f64;
                         if count >= bc_cfg.min_events
                             && mean_ok
-                            && cv <= bc_cfg.cv_threshold
+                            && periodic
                             && !self.in_cooldown_tuple(&self.beacon_cooldowns, &flow_key)
                         {
                             self.beacon_cooldowns.insert(flow_key, now);
                             alerts.push(Alert {
-                                scan_type: ScanType::Beaconing,
-                                source_ip: ip,
-                                dest_ip: Some(dest_ip),
-                                unique_ports: Vec::new(),
-                                unique_dests: Vec::new(),
-                                unique_sources: Vec::new(),
-                                timestamp: Local::now(),
                                 beacon_port: Some(dport),
                                 mean_interval_secs: Some(mean_secs),
                                 cv: Some(cv),
                                 event_count: Some(count),
+                                ..Alert::new(ScanType::Beaconing, ip, Some(dest_ip))
                             });
                         }
                     }
@@ -1086,9 +3016,165 @@ impl Detector {
             }
         }
 
+        // --- 9. Verificam Slow Sweep (detection.slow_sweep) ---
+        //
+        // Cheia este (sursa, port destinatie); valoarea = destinatiile distincte
+        // din fereastra lunga. Drop si accept: sondarea conteaza, nu raspunsul.
+        let sw_cfg = &cfg.slow_sweep;
+        if sw_cfg.enabled {
+            if let Some(dest_ip) = event.dest_ip {
+                let key = (ip, event.dest_port);
+                if exc.authorized_scanners.contains(&ip) {
+                    explain.note("slow_sweep", ip, || {
+                        "sursa este in exceptions.authorized_scanners".to_string()
+                    });
+                } else {
+                    // --- Cap pe numarul de chei urmarite (LRU pe ultima destinatie) ---
+                    let is_new_key = !self.sweep_hits.contains_key(&key);
+                    if is_new_key && self.sweep_hits.len() >= cfg.max_tracked_ips {
+                        let lru_key: Option<(IpAddr, u16)> = self
+                            .sweep_hits
+                            .iter()
+                            .min_by_key(|e| e.value().last().map(|h| h.seen_at).unwrap_or(now))
+                            .map(|e| *e.key());
+                        if let Some(old) = lru_key {
+                            self.sweep_hits.remove(&old);
+                            self.sweep_cooldowns.remove(&old);
+                        }
+                    }
+
+                    let window = Duration::from_secs(sw_cfg.time_window_secs);
+                    // Scop explicit: RefMut-ul este eliberat inainte de cooldown.
+                    let mut dests: Vec<IpAddr> = {
+                        let mut hits = self.sweep_hits.entry(key).or_default();
+                        // Destinatie revizitata → mutata la coada: lista ramane
+                        // ordonata cronologic si contine doar destinatii distincte.
+                        hits.retain(|h| {
+                            h.dest_ip != dest_ip && now.saturating_duration_since(h.seen_at) <= window
+                        });
+                        hits.push(DestHit {
+                            dest_ip,
+                            seen_at: now,
+                        });
+                        let max_hits = cfg.max_hits_per_ip;
+                        if hits.len() > max_hits {
+                            let overflow = hits.len() - max_hits;
+                            hits.drain(..overflow);
+                        }
+                        hits.iter().map(|h| h.dest_ip).collect()
+                    };
+                    if dests.len() >= sw_cfg.unique_dest_threshold
+                        && !self.in_cooldown_tuple(&self.sweep_cooldowns, &key)
+                    {
+                        self.sweep_cooldowns.insert(key, now);
+                        dests.sort_unstable();
+                        alerts.push(Alert {
+                            detection_context: Some(DetectionContext::new(
+                                "slow_sweep",
+                                dests.len(),
+                                sw_cfg.unique_dest_threshold,
+                                "destinatii",
+                                window,
+                            )),
+                            unique_ports: vec![event.dest_port],
+                            unique_dests: dests,
+                            ..Alert::new(ScanType::SlowSweep, ip, Some(dest_ip))
+                        });
+                    }
+                }
+            }
+        }
+
+        // --- Reguli din fisiere (detection.rules_dir) ---
+        // Evaluate dupa whitelist / semnaturi autorizate, ca regulile incorporate.
+        let custom_rules = self.custom_rules.load();
+        if !custom_rules.is_empty() {
+            alerts.extend(custom_rules.evaluate(event, &cfg, now));
+        }
+
+        // --- Episoade de scanare (detection.scan_end_silence_secs) ---
+        if cfg.scan_end_silence_secs > 0 {
+            self.track_scan_episode(&cfg, event, &alerts, now);
+        }
+
+        // Fiecare alerta poarta versiunea politicii care a produs-o,
+        // criticitatea activului vizat (`[assets]` si harta CMDB — nivelul
+        // maxim), contextul activului si rezumatul de o linie.
+        if !alerts.is_empty() {
+            let policy_version = self.policy_version.load();
+            let assets = self.assets.load();
+            let asset_map = self.asset_map.load();
+            let summary_template = self.summary_template.load();
+            let with_context = self.detection_context.load(Ordering::Relaxed);
+            for alert in &mut alerts {
+                alert.policy_version = policy_version.to_string();
+                if !with_context {
+                    alert.detection_context = None;
+                }
+                alert.asset = asset_map.lookup_alert(alert).cloned();
+                alert.asset_criticality = assets
+                    .classify_alert(alert)
+                    .max(alert.asset.as_ref().and_then(|a| a.criticality));
+                if !summary_template.is_empty() {
+                    alert.summary = alert.render_summary(&summary_template);
+                }
+            }
+        }
+
         alerts
     }
 
+    /// Inregistreaza o evictie LRU si decide daca rata evictiilor justifica o alerta.
+    ///
+    /// Rata = evictii in fereastra / durata ferestrei (evictii/secunda).
+    /// Cu alerta dezactivata nu pastram istoric — zero memorie consumata.
+    /// Cooldown-ul reutilizeaza `alert_cooldown_secs`, ca la celelalte alerte.
+    fn record_eviction(&self, cfg: &DetectionConfig, ip: IpAddr, now: Instant) -> Option<Alert> {
+        let sat = &cfg.saturation_alert;
+        if !sat.enabled {
+            return None;
+        }
+        let window = Duration::from_secs(sat.time_window_secs);
+
+        let count = {
+            let mut ev = self.evictions.lock().unwrap_or_else(|e| e.into_inner());
+            match ev.back_mut() {
+                Some((at, n)) if now.saturating_duration_since(*at) < Duration::from_secs(1) => {
+                    *n = n.saturating_add(1);
+                }
+                _ => ev.push_back((now, 1)),
+            }
+            while ev
+                .front()
+                .is_some_and(|(t, _)| now.saturating_duration_since(*t) > window)
+            {
+                ev.pop_front();
+            }
+            // Toate evictiile din fereastra — rata raportata nu este plafonata la prag.
+            ev.iter().map(|(_, n)| *n as usize).sum::<usize>()
+        };
+
+        if count < sat.evictions_threshold {
+            return None;
+        }
+
+        let mut last = self
+            .saturation_cooldown
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let cooldown = Duration::from_secs(cfg.alert_cooldown_secs);
+        if last.is_some_and(|t| now.saturating_duration_since(t) < cooldown) {
+            return None;
+        }
+        *last = Some(now);
+
+        Some(Alert {
+            event_count: Some(count),
+            eviction_rate: Some(count as f64 / sat.time_window_secs as f64),
+            ..Alert::new(ScanType::Saturation, ip, None)
+        })
+    }
+
     /// Calculeaza statisticile (mean_interval_secs, CV, count) pentru un flow Beaconing.
     ///
     /// Returneaza None daca avem < 2 intrari in fereastra (nu putem calcula intervale)
@@ -1099,7 +3185,32 @@ impl Detector {
         window: Duration,
         now: Instant,
     ) -> Option<(f64, f64, usize)> {
-        let hits = self.beacon_hits.get(flow_key)?;
+        let intervals = self.beacon_intervals(flow_key, window, now);
+        if intervals.is_empty() {
+            return None;
+        }
+        let n = intervals.len() as f64;
+        let mean = intervals.iter().sum::<f64>() / n;
+        if mean <= 0.0 {
+            return None;
+        }
+        let variance = intervals.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        let cv = variance.sqrt() / mean;
+        // N intervale consecutive ⇔ N + 1 timestamp-uri in fereastra.
+        Some((mean, cv, intervals.len() + 1))
+    }
+
+    /// Intervalele (secunde) intre conexiunile consecutive ale unui flow
+    /// Beaconing, doar din fereastra de timp. Vec gol daca sunt < 2 intrari.
+    fn beacon_intervals(
+        &self,
+        flow_key: &(IpAddr, IpAddr, u16),
+        window: Duration,
+        now: Instant,
+    ) -> Vec<f64> {
+        let Some(hits) = self.beacon_hits.get(flow_key) else {
+            return Vec::new();
+        };
         // Selectam doar timestamp-urile din fereastra de timp, in ordine cronologica.
         let timestamps: Vec<Instant> = hits
             .iter()
@@ -1108,22 +3219,11 @@ impl Detector {
             .collect();
         drop(hits);
 
-        if timestamps.len() < 2 {
-            return None;
-        }
         // Intervalele consecutive (in secunde, f64 pentru precizie).
-        let intervals: Vec<f64> = timestamps
+        timestamps
             .windows(2)
             .map(|w| w[1].saturating_duration_since(w[0]).as_secs_f64())
-            .collect();
-        let n = intervals.len() as f64;
-        let mean = intervals.iter().sum::<f64>() / n;
-        if mean <= 0.0 {
-            return None;
-        }
-        let variance = intervals.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
-        let cv = variance.sqrt() / mean;
-        Some((mean, cv, timestamps.len()))
+            .collect()
     }
 
     /// Cooldown check pentru chei compozite (tuplu). Acelasi pattern ca `in_cooldown`,
@@ -1141,6 +3241,23 @@ impl Detector {
         }
     }
 
+    /// Tiparul de epuizare a porturilor sursa al lui `ip` in fereastra
+    /// `source_port_exhaustion`: suficiente porturi distincte, suficient de
+    /// dense in intervalul lor. `None` cand detectia este oprita.
+    fn source_port_run(&self, cfg: &DetectionConfig, ip: IpAddr, now: Instant) -> Option<SourcePortRun> {
+        let spe = &cfg.source_port_exhaustion;
+        if !spe.enabled {
+            return None;
+        }
+        let window = Duration::from_secs(spe.time_window_secs);
+        let run = {
+            let mut hits = self.source_port_hits.get_mut(&ip)?;
+            hits.expire(window, now);
+            hits.run()?
+        };
+        (run.ports >= spe.min_source_ports && run.density() >= spe.min_density).then_some(run)
+    }
+
     /// Returneaza lista porturilor unice accesate de un IP in fereastra de timp.
     ///
     /// NOTA RUST - REFACTORIZARE (#10): Aceasta functie primeste `hits_map` ca parametru.
@@ -1163,12 +3280,16 @@ impl Detector {
     /// NOTA RUST - `.get(&ip)` returneaza Option<Ref<K, V>>:
     /// `Ref` este un guard de citire al DashMap (similar cu RwLockReadGuard).
     /// Tine lock-ul de citire cat timp exista — dropat automat la finalul scope-ului (RAII).
+    ///
+    /// `protocol` = `Some(p)` numara doar hit-urile cu protocolul `p`
+    /// (`per_protocol_tracking = "separate"`); `None` = toate protocoalele.
     fn unique_ports_in_window(
         &self,
         hits_map: &DashMap<IpAddr, Vec<PortHit>>,
         ip: IpAddr,
         window: Duration,
         now: Instant,
+        protocol: Option<&str>,
     ) -> Option<Vec<u16>> {
         let entry = hits_map.get(&ip)?;
         let hits = entry.value();
@@ -1178,6 +3299,7 @@ impl Detector {
             // `now.duration_since(h.seen_at)` poate panica daca h.seen_at > now
             // (imposibil cu Instant monotonic, dar saturating_duration_since e mai safe).
             .filter(|h| now.saturating_duration_since(h.seen_at) <= window)
+            .filter(|h| protocol.is_none_or(|p| h.protocol == p))
             .map(|h| h.port)
             .collect();
 
@@ -1193,6 +3315,113 @@ impl Detector {
         }
     }
 
+    /// Porturile unice (drop) din fereastra ANTERIOARA — vazute intre `window`
+    /// si `2 × window` in urma — care lipsesc din fereastra curenta
+    /// (`current`, sortata). Baza creditului `slow_scan.carry_over`.
+    fn previous_window_ports(
+        &self,
+        hits_map: &DashMap<IpAddr, Vec<PortHit>>,
+        ip: IpAddr,
+        window: Duration,
+        now: Instant,
+        protocol: Option<&str>,
+        current: &[u16],
+    ) -> Vec<u16> {
+        let Some(entry) = hits_map.get(&ip) else {
+            return Vec::new();
+        };
+        let mut ports: Vec<u16> = entry
+            .value()
+            .iter()
+            .filter(|h| {
+                let age = now.saturating_duration_since(h.seen_at);
+                age > window && age <= window * 2
+            })
+            .filter(|h| protocol.is_none_or(|p| h.protocol == p))
+            .map(|h| h.port)
+            .filter(|p| current.binary_search(p).is_err())
+            .collect();
+        ports.sort_unstable();
+        ports.dedup();
+        ports
+    }
+
+    /// Porturile unice (drop) vizate de sonde UDP goale in fereastra.
+    /// Returneaza 0 fara iterare cand `empty_udp_probes` este dezactivat.
+    fn empty_probe_ports(
+        &self,
+        cfg: &DetectionConfig,
+        hits_map: &DashMap<IpAddr, Vec<PortHit>>,
+        ip: IpAddr,
+        window: Duration,
+        now: Instant,
+        protocol: Option<&str>,
+    ) -> usize {
+        if !cfg.empty_udp_probes.enabled {
+            return 0;
+        }
+        let Some(entry) = hits_map.get(&ip) else {
+            return 0;
+        };
+        let mut ports: Vec<u16> = entry
+            .value()
+            .iter()
+            .filter(|h| h.empty_probe && now.saturating_duration_since(h.seen_at) <= window)
+            .filter(|h| protocol.is_none_or(|p| h.protocol == p))
+            .map(|h| h.port)
+            .collect();
+        ports.sort_unstable();
+        ports.dedup();
+        ports.len()
+    }
+
+    /// Protocoalele distincte ale hit-urilor lui `ip` din fereastra, sortate.
+    /// Cu filtru (`per_protocol_tracking = "separate"`) — doar protocolul numarat.
+    fn protocols_in_window(
+        &self,
+        hits_map: &DashMap<IpAddr, Vec<PortHit>>,
+        ip: IpAddr,
+        window: Duration,
+        now: Instant,
+        protocol: Option<&'static str>,
+    ) -> Vec<String> {
+        if let Some(p) = protocol {
+            return vec![p.to_string()];
+        }
+        let Some(entry) = hits_map.get(&ip) else {
+            return Vec::new();
+        };
+        let mut protocols: Vec<&'static str> = entry
+            .value()
+            .iter()
+            .filter(|h| now.saturating_duration_since(h.seen_at) <= window)
+            .map(|h| h.protocol)
+            .collect();
+        protocols.sort_unstable();
+        protocols.dedup();
+        protocols.into_iter().map(String::from).collect()
+    }
+
+    /// Milisecunde de la primul hit al lui `ip` din fereastra pana la `now`.
+    ///
+    /// Vec-ul de hit-uri este append-only (ordonat cronologic), deci primul
+    /// element din fereastra este si cel mai vechi — `find` se opreste la el.
+    fn time_to_threshold_ms(
+        &self,
+        hits_map: &DashMap<IpAddr, Vec<PortHit>>,
+        ip: IpAddr,
+        window: Duration,
+        now: Instant,
+        protocol: Option<&str>,
+    ) -> Option<u64> {
+        let entry = hits_map.get(&ip)?;
+        let first = entry.value().iter().find(|h| {
+            now.saturating_duration_since(h.seen_at) <= window
+                && protocol.is_none_or(|p| h.protocol == p)
+        })?;
+        Some(now.saturating_duration_since(first.seen_at).as_millis() as u64)
+    }
+
     /// Returneaza destinatiile unice contactate de `ip` in fereastra `window`.
     ///
     /// Analog cu `unique_ports_in_window`, dar opereaza pe `lateral_hits`
@@ -1224,20 +3453,66 @@ impl Detector {
         }
     }
 
-    /// Returneaza sursele unice si porturile vizate pe o tinta in fereastra `window`.
+    /// Adauga un hit intr-un index de tip `distributed_hits` (cheie = tinta
+    /// sau prefix sursa), cu plafon pe numarul de chei si pe hit-uri per cheie.
+    ///
+    /// --- Cap pe numarul de chei urmarite ---
+    ///
+    /// Indexul nu este cheiat pe IP-ul sursa, deci LRU-ul global pe `last_seen`
+    /// nu il afecteaza. Pentru a preveni cresterea nelimitata a numarului de
+    /// chei (#bug-8), aplicam o limita explicita = `max_tracked_ips` si eviciem
+    /// LRU-ul cand atingem capacitatea.
+    ///
+    /// LRU criteriu: ultima intrare (cea mai recenta) din vector-ul de hits per
+    /// cheie. Vectorul fiind populat append-only, `.last()` da `seen_at` cel mai
+    /// recent O(1).
+    fn record_source_hit(
+        &self,
+        hits_map: &DashMap<IpAddr, Vec<DistributedHit>>,
+        cooldowns: &DashMap<IpAddr, Instant>,
+        key: IpAddr,
+        hit: DistributedHit,
+        cfg: &DetectionConfig,
+        now: Instant,
+    ) {
+        let is_new_key = !hits_map.contains_key(&key);
+        if is_new_key && hits_map.len() >= cfg.max_tracked_ips {
+            let lru_key: Option<IpAddr> = hits_map
+                .iter()
+                .min_by_key(|e| e.value().last().map(|h| h.seen_at).unwrap_or(now))
+                .map(|e| *e.key());
+            if let Some(old) = lru_key {
+                hits_map.remove(&old);
+                cooldowns.remove(&old);
+            }
+        }
+
+        let mut hits = hits_map.entry(key).or_default();
+        hits.push(hit);
+        // Cap memorie: refolosim max_hits_per_ip ca limita.
+        let max_hits = cfg.max_hits_per_ip;
+        if hits.len() > max_hits {
+            let overflow = hits.len() - max_hits;
+            hits.drain(..overflow);
+        }
+    }
+
+    /// Returneaza sursele unice si porturile vizate pe o cheie in fereastra `window`.
     ///
-    /// Analog cu `unique_dests_in_window`, dar opereaza pe `distributed_hits`
-    /// (indexat dupa dest_ip) si colecteaza IP-uri sursa unice + porturi vizate.
+    /// Analog cu `unique_dests_in_window`, dar opereaza pe un index de tip
+    /// `distributed_hits` si colecteaza IP-uri sursa unice + porturi vizate.
+    /// Cheia este tinta (`distributed_hits`) sau prefixul sursei (`subnet_hits`).
     ///
     /// Returneaza `None` daca nu exista date sau lista e goala.
     /// Returneaza `Some((Vec<IpAddr>, Vec<u16>))` cu sursele unice si porturile.
     fn unique_sources_in_window(
         &self,
-        dest_ip: IpAddr,
+        hits_map: &DashMap<IpAddr, Vec<DistributedHit>>,
+        key: IpAddr,
         window: Duration,
         now: Instant,
     ) -> Option<(Vec<IpAddr>, Vec<u16>)> {
-        let hits = self.distributed_hits.get(&dest_ip)?;
+        let hits = hits_map.get(&key)?;
         let mut sources: std::collections::HashSet<IpAddr> = std::collections::HashSet::new();
         let mut ports: std::collections::HashSet<u16> = std::collections::HashSet::new();
         for hit in hits.iter() {
@@ -1256,13 +3531,155 @@ impl Detector {
         }
     }
 
-    /// Verifica daca un IP este in perioada de cooldown pentru un tip de alerta.
+    /// Tintele distincte (dest_ip) ale hit-urilor cheii `key` din fereastra,
+    /// sortate. Hit-urile fara destinatie nu conteaza.
+    fn distinct_targets_in_window(
+        &self,
+        hits_map: &DashMap<IpAddr, Vec<DistributedHit>>,
+        key: IpAddr,
+        window: Duration,
+        now: Instant,
+    ) -> Vec<IpAddr> {
+        let Some(hits) = hits_map.get(&key) else {
+            return Vec::new();
+        };
+        let mut targets: Vec<IpAddr> = hits
+            .iter()
+            .filter(|h| now.saturating_duration_since(h.seen_at) <= window)
+            .filter_map(|h| h.dest_ip)
+            .collect();
+        targets.sort_unstable();
+        targets.dedup();
+        targets
+    }
+
+    /// Secunde ramase din cooldown-ul unei chei (None = nu este in cooldown).
+    fn cooldown_remaining<K: Eq + Hash>(&self, cooldowns: &DashMap<K, Instant>, key: K) -> Option<u64> {
+        let cooldown = Duration::from_secs(self.config.load().alert_cooldown_secs);
+        let elapsed = cooldowns.get(&key)?.elapsed();
+        (elapsed < cooldown).then(|| (cooldown - elapsed).as_secs().max(1))
+    }
+
+    /// `detection.rule_precedence`: true daca regula `rule` (Fast / Slow Scan)
+    /// este suprimata pentru `ip` de o alerta inca activa (in cooldown) a
+    /// regulii dominante. Verificat INAINTE de `confirm_crossing`, ca o
+    /// depasire suprimata sa nu armeze confirmarea.
+    fn outranked(
+        &self,
+        cfg: &DetectionConfig,
+        explain: &mut Explain,
+        rule: &'static str,
+        scope: ScopeKey,
+    ) -> bool {
+        let ip = scope.0;
+        let (dominant, cooldowns) = match (cfg.rule_precedence.as_str(), rule) {
+            ("fast", "slow_scan") => ("Fast Scan", &self.fast_cooldowns),
+            ("slow", "fast_scan") => ("Slow Scan", &self.slow_cooldowns),
+            _ => return false,
+        };
+        match self.cooldown_remaining(cooldowns, scope) {
+            Some(remaining) => {
+                explain.note(rule, ip, || {
+                    format!(
+                        "suprimat de rule_precedence: alerta {} activa inca {}s",
+                        dominant, remaining
+                    )
+                });
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Motivul pentru care o regula de tip prag NU a emis (modul explain):
+    /// prag atins dar in cooldown, sau sub prag (de la jumatatea pragului).
+    /// `cooldown` = secundele ramase din cooldown-ul regulii (`cooldown_remaining`).
+    fn explain_threshold(
+        &self,
+        explain: &mut Explain,
+        rule: &'static str,
+        cooldown: Option<u64>,
+        ip: IpAddr,
+        score: f64,
+        threshold: usize,
+    ) {
+        if score >= threshold as f64 {
+            if let Some(remaining) = cooldown {
+                explain.note(rule, ip, || {
+                    format!("prag atins ({:.0}/{}), dar in cooldown inca {}s", score, threshold, remaining)
+                });
+            }
+        } else if score * 2.0 >= threshold as f64 {
+            explain.note(rule, ip, || {
+                format!("sub prag cu {:.0} ({:.0}/{})", (threshold as f64 - score).ceil(), score, threshold)
+            });
+        }
+    }
+
+    /// Confirmare multi-fereastra (`confirm_windows`): true daca depasirea
+    /// curenta de prag trebuie sa emita alerta.
+    ///
+    /// Cu `confirm_windows = 1` raspunsul este mereu true (comportament clasic).
+    /// Altfel prima depasire armeaza o stare de asteptare; o depasire noua se
+    /// numara doar dupa o fereastra completa de la precedenta (ferestre
+    /// disjuncte — alta dovada, nu aceleasi porturi revazute). Daca a N-a
+    /// depasire nu apare in `confirm_secs` de la prima, starea se reia de la zero.
+    fn confirm_crossing(
+        &self,
+        cfg: &DetectionConfig,
+        explain: &mut Explain,
+        rule: &'static str,
+        ip: IpAddr,
+        window: Duration,
+        now: Instant,
+    ) -> bool {
+        debug_assert!(CONFIRM_RULES.contains(&rule), "regula lipsa din CONFIRM_RULES: {}", rule);
+        let required = cfg.confirm_windows;
+        if required <= 1 {
+            return true;
+        }
+        let confirm = Duration::from_secs(cfg.confirm_secs);
+
+        // Scop explicit: RefMut-ul trebuie eliberat inainte de remove (deadlock).
+        let crossings = {
+            let mut pending = self.pending_confirms.entry((ip, rule)).or_insert(PendingConfirm {
+                crossings: 0,
+                first_at: now,
+                last_at: now,
+            });
+            if pending.crossings > 0 && now.saturating_duration_since(pending.first_at) > confirm {
+                // Prima depasire a expirat fara confirmare — o luam de la capat.
+                pending.crossings = 0;
+            }
+            if pending.crossings == 0 {
+                *pending = PendingConfirm { crossings: 1, first_at: now, last_at: now };
+            } else if now.saturating_duration_since(pending.last_at) >= window {
+                pending.crossings += 1;
+                pending.last_at = now;
+            }
+            pending.crossings
+        };
+
+        if crossings >= required {
+            self.pending_confirms.remove(&(ip, rule));
+            return true;
+        }
+        explain.note(rule, ip, || {
+            format!(
+                "prag atins, asteapta confirmare ({}/{} ferestre in {}s)",
+                crossings, required, cfg.confirm_secs
+            )
+        });
+        false
+    }
+
+    /// Verifica daca o cheie (IP sau `ScopeKey`) este in perioada de cooldown pentru un tip de alerta.
     ///
     /// NOTA RUST - REFERINTE la DashMap:
     /// `cooldowns: &DashMap<...>` - imprumut imutabil al DashMap-ului.
     /// DashMap permite `.get()` prin &self (interior mutability cu read-lock).
-    fn in_cooldown(&self, cooldowns: &DashMap<IpAddr, Instant>, ip: IpAddr) -> bool {
-        if let Some(last_alert) = cooldowns.get(&ip) {
+    fn in_cooldown<K: Eq + Hash>(&self, cooldowns: &DashMap<K, Instant>, key: K) -> bool {
+        if let Some(last_alert) = cooldowns.get(&key) {
             // `elapsed()` = cat timp a trecut de la momentul stocat.
             last_alert.elapsed() < Duration::from_secs(self.config.load().alert_cooldown_secs)
         } else {
@@ -1270,6 +3687,230 @@ impl Detector {
         }
     }
 
+    /// Retine `warning` daca scorul este intre `threshold_fraction` din prag si
+    /// prag (exclusiv), limitat la un avertisment per IP la `cooldown_secs`.
+    fn early_warning(&self, cfg: &DetectionConfig, warning: EarlyWarning, now: Instant) {
+        let ew = &cfg.early_warning;
+        let threshold = warning.threshold as f64;
+        if !ew.enabled || warning.score >= threshold || warning.score < ew.threshold_fraction * threshold {
+            return;
+        }
+        // Doar tinte sensibile: clasificate in [assets] sau critical / high
+        // in harta CMDB (aceeasi clasificare ca `asset_criticality`).
+        if ew.assets_only {
+            let sensitive = warning.dest_ip.is_some_and(|dst| {
+                self.assets.load().classify(&dst).is_some()
+                    || self
+                        .asset_map
+                        .load()
+                        .lookup(&dst)
+                        .is_some_and(|a| a.criticality.is_some())
+            });
+            if !sensitive {
+                return;
+            }
+        }
+        let cooldown = Duration::from_secs(ew.cooldown_secs);
+        let recent = self
+            .early_cooldowns
+            .get(&warning.source_ip)
+            .is_some_and(|last| now.saturating_duration_since(*last) < cooldown);
+        if recent {
+            return;
+        }
+        self.early_cooldowns.insert(warning.source_ip, now);
+        self.early_warnings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(warning);
+    }
+
+    /// Preia avertismentele provizorii acumulate (apelat dupa fiecare eveniment).
+    pub fn take_early_warnings(&self) -> Vec<EarlyWarning> {
+        std::mem::take(&mut *self.early_warnings.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Un pachet a sosit (orice datagrama, parsabila sau nu): reporneste
+    /// cronometrul linistii. Intoarce durata linistii daca aceasta fusese
+    /// semnalata printr-o alerta (pentru mesajul "trafic reluat").
+    pub fn record_packet(&self, now: Instant) -> Option<Duration> {
+        let now_ms = now.saturating_duration_since(self.started).as_millis() as u64;
+        let previous_ms = self.last_packet_ms.swap(now_ms, Ordering::Relaxed);
+        if self.silence_alerted.swap(false, Ordering::Relaxed) {
+            Some(Duration::from_millis(now_ms.saturating_sub(previous_ms)))
+        } else {
+            None
+        }
+    }
+
+    /// O datagrama fara nicio linie parsabila a sosit de la `sender`
+    /// (`detection.malformed_input`). Numara in fereastra fixa a expeditorului
+    /// si intoarce alerta Malformed Input la atingerea pragului (cu cooldown).
+    pub fn record_malformed(&self, sender: IpAddr, now: Instant) -> Option<Alert> {
+        let cfg = self.config.load();
+        let mi = &cfg.malformed_input;
+        if !mi.enabled {
+            return None;
+        }
+        let window = Duration::from_secs(mi.time_window_secs);
+        // Scop explicit: RefMut-ul este eliberat inainte de cooldown.
+        let count = {
+            let mut counter = self.malformed_counters.entry(sender).or_insert(FloodCounter {
+                window_start: now,
+                packets: 0,
+            });
+            if now.saturating_duration_since(counter.window_start) >= window {
+                counter.window_start = now;
+                counter.packets = 0;
+            }
+            counter.packets += 1;
+            counter.packets as usize
+        };
+        if count < mi.threshold || self.in_cooldown(&self.malformed_cooldowns, sender) {
+            return None;
+        }
+        self.malformed_cooldowns.insert(sender, now);
+
+        let mut alert = Alert {
+            event_count: Some(count),
+            detection_context: self.detection_context.load(Ordering::Relaxed).then(|| {
+                DetectionContext::new("malformed_input", count, mi.threshold, "datagrame", window)
+            }),
+            ..Alert::new(ScanType::MalformedInput, sender, None)
+        };
+        alert.policy_version = self.policy_version.load().to_string();
+        let summary_template = self.summary_template.load();
+        if !summary_template.is_empty() {
+            alert.summary = alert.render_summary(&summary_template);
+        }
+        Some(alert)
+    }
+
+    /// Deschide episodul sursei la prima alerta de scanare si il prelungeste
+    /// cu fiecare eveniment ulterior (porturile, destinatiile si alertele lui).
+    fn track_scan_episode(&self, cfg: &DetectionConfig, event: &LogEvent, alerts: &[Alert], now: Instant) {
+        let ip = event.source_ip;
+        let cap = match cfg.max_ports_per_ip {
+            0 => SCAN_EPISODE_MAX_ITEMS,
+            n => n,
+        };
+        let mut opening = alerts
+            .iter()
+            .filter(|a| a.source_ip == ip && opens_scan_episode(&a.scan_type))
+            .peekable();
+
+        let mut episode = match opening.peek() {
+            Some(first) => {
+                if !self.scan_episodes.contains_key(&ip) && self.scan_episodes.len() >= cfg.max_tracked_ips {
+                    return;
+                }
+                self.scan_episodes.entry(ip).or_insert_with(|| ScanEpisode::open(first, now))
+            }
+            None => match self.scan_episodes.get_mut(&ip) {
+                Some(episode) => episode,
+                None => return,
+            },
+        };
+
+        episode.last_seen = now;
+        episode.events += 1;
+        episode.add_port(event.dest_port, cap);
+        if let Some(dest) = event.dest_ip {
+            episode.add_dest(dest, cap);
+        }
+        for alert in opening {
+            episode.alerts += 1;
+            episode.scan_types.insert(alert.scan_type.to_string());
+            for &port in &alert.unique_ports {
+                episode.add_port(port, cap);
+            }
+            for &dest in alert.unique_dests.iter().chain(alert.dest_ip.iter()) {
+                episode.add_dest(dest, cap);
+            }
+        }
+    }
+
+    /// Verificata periodic din bucla principala: inchide episoadele surselor
+    /// tacute de `scan_end_silence_secs` si intoarce cate o alerta Scan Ended
+    /// cu totalurile episodului. Dezactivata (si golita) cand optiunea este 0.
+    pub fn check_scan_ends(&self, now: Instant) -> Vec<Alert> {
+        let cfg = self.config.load();
+        if cfg.scan_end_silence_secs == 0 {
+            self.scan_episodes.clear();
+            return Vec::new();
+        }
+        let silence = Duration::from_secs(cfg.scan_end_silence_secs);
+        let is_silent = |episode: &ScanEpisode| now.saturating_duration_since(episode.last_seen) >= silence;
+        let ended: Vec<IpAddr> = self
+            .scan_episodes
+            .iter()
+            .filter(|e| is_silent(e.value()))
+            .map(|e| *e.key())
+            .collect();
+
+        let policy_version = self.policy_version.load();
+        let summary_template = self.summary_template.load();
+        let mut alerts = Vec::new();
+        for ip in ended {
+            // `remove_if`: un eveniment sosit intre timp tine episodul deschis.
+            let Some((_, episode)) = self.scan_episodes.remove_if(&ip, |_, e| is_silent(e)) else {
+                continue;
+            };
+            let mut ports: Vec<u16> = episode.ports.into_iter().collect();
+            ports.sort_unstable();
+            let mut dests: Vec<IpAddr> = episode.dests.into_iter().collect();
+            dests.sort_unstable();
+            let dest_ip = if dests.len() == 1 { Some(dests[0]) } else { None };
+            let mut alert = Alert {
+                unique_ports: ports,
+                unique_dests: dests,
+                event_count: Some(episode.events),
+                silent_secs: Some(now.saturating_duration_since(episode.last_seen).as_secs()),
+                scan_episode: Some(ScanEpisodeSummary {
+                    started_at: episode.started_at,
+                    duration_secs: episode.last_seen.saturating_duration_since(episode.started).as_secs(),
+                    alerts: episode.alerts,
+                    scan_types: episode.scan_types,
+                }),
+                ..Alert::new(ScanType::ScanEnded, ip, dest_ip)
+            };
+            alert.policy_version = policy_version.to_string();
+            if !summary_template.is_empty() {
+                alert.summary = alert.render_summary(&summary_template);
+            }
+            alerts.push(alert);
+        }
+        alerts
+    }
+
+    /// Verificata periodic din bucla principala: alerta NoTraffic cand niciun
+    /// pachet nu a sosit de `no_traffic_alert_secs` (de la pornire sau de la
+    /// ultimul pachet). `sensor` = adresa de ascultare, folosita ca `source_ip`.
+    pub fn check_no_traffic(&self, sensor: IpAddr, now: Instant) -> Option<Alert> {
+        let cfg = self.config.load();
+        if cfg.no_traffic_alert_secs == 0 || self.silence_alerted.load(Ordering::Relaxed) {
+            return None;
+        }
+        let now_ms = now.saturating_duration_since(self.started).as_millis() as u64;
+        let silent_secs = now_ms.saturating_sub(self.last_packet_ms.load(Ordering::Relaxed)) / 1000;
+        if silent_secs < cfg.no_traffic_alert_secs
+            || self.silence_alerted.swap(true, Ordering::Relaxed)
+        {
+            return None;
+        }
+
+        let mut alert = Alert {
+            silent_secs: Some(silent_secs),
+            ..Alert::new(ScanType::NoTraffic, sensor, None)
+        };
+        alert.policy_version = self.policy_version.load().to_string();
+        let summary_template = self.summary_template.load();
+        if !summary_template.is_empty() {
+            alert.summary = alert.render_summary(&summary_template);
+        }
+        Some(alert)
+    }
+
     /// Curata datele vechi din memorie - previne memory leaks.
     ///
     /// NOTA RUST - ITERATIE MUTABILA pe DashMap:
@@ -1283,6 +3924,10 @@ impl Detector {
     ///
     pub fn cleanup(&self, max_age: Duration) {
         let now = Instant::now();
+        // Harta partajata este curatata doar de detectorul care o alimenteaza;
+        // ceilalti o citesc (baseline, sincronizarea last_seen).
+        let tracker = self.tracker.load_full();
+        let feeds_tracker = self.feeds_tracker.load(Ordering::Relaxed);
 
         // --- Baseline sampling (#35) — INAINTE de curatare ---
         //
@@ -1322,14 +3967,14 @@ impl Detector {
                 let mut accept_max: usize = 0;
 
                 // Iteram port_hits: esantionam fast_max si slow_max.
-                for entry in self.port_hits.iter() {
+                for entry in tracker.port_hits.iter() {
                     let hits = entry.value();
                     fast_max = fast_max.max(count_unique_ports_in_hits(hits, fast_w, now));
                     slow_max = slow_max.max(count_unique_ports_in_hits(hits, slow_w, now));
                 }
 
                 // Iteram accept_hits: esantionam accept_max.
-                for entry in self.accept_hits.iter() {
+                for entry in tracker.accept_hits.iter() {
                     accept_max =
                         accept_max.max(count_unique_ports_in_hits(entry.value(), accept_w, now));
                 }
@@ -1341,38 +3986,44 @@ impl Detector {
             }
         }
 
-        // --- Curatam port_hits (drop-uri) ---
-        //
-        // NOTA RUST: Nu putem sterge din DashMap in timpul iteratiei (ar invalida
-        // iteratorul). De aceea colectam cheile goale si le stergem separat.
-        let mut drop_empty: Vec<IpAddr> = Vec::new();
-        for mut entry in self.port_hits.iter_mut() {
-            entry
-                .value_mut()
-                .retain(|hit| now.saturating_duration_since(hit.seen_at) <= max_age);
-            if entry.value().is_empty() {
-                drop_empty.push(*entry.key());
+        if feeds_tracker {
+            // --- Curatam port_hits (drop-uri) ---
+            //
+            // NOTA RUST: Nu putem sterge din DashMap in timpul iteratiei (ar invalida
+            // iteratorul). De aceea colectam cheile goale si le stergem separat.
+            let mut drop_empty: Vec<IpAddr> = Vec::new();
+            for mut entry in tracker.port_hits.iter_mut() {
+                entry
+                    .value_mut()
+                    .retain(|hit| now.saturating_duration_since(hit.seen_at) <= max_age);
+                if entry.value().is_empty() {
+                    drop_empty.push(*entry.key());
+                }
+            }
+            for ip in &drop_empty {
+                tracker.port_hits.remove(ip);
             }
-        }
-        for ip in &drop_empty {
-            self.port_hits.remove(ip);
-        }
 
-        // --- Curatam accept_hits (accept-uri) ---
-        //
-        // Aceeasi logica ca pentru port_hits, dar pe map-ul separat al Accept Scan.
-        // Datele vechi de accept sunt la fel de costisitoare in memorie ca cele de drop.
-        let mut accept_empty: Vec<IpAddr> = Vec::new();
-        for mut entry in self.accept_hits.iter_mut() {
-            entry
-                .value_mut()
-                .retain(|hit| now.saturating_duration_since(hit.seen_at) <= max_age);
-            if entry.value().is_empty() {
-                accept_empty.push(*entry.key());
+            // --- Curatam accept_hits (accept-uri) ---
+            //
+            // Aceeasi logica ca pentru port_hits, dar pe map-ul separat al Accept Scan.
+            // Datele vechi de accept sunt la fel de costisitoare in memorie ca cele de drop.
+            let mut accept_empty: Vec<IpAddr> = Vec::new();
+            for mut entry in tracker.accept_hits.iter_mut() {
+                entry
+                    .value_mut()
+                    .retain(|hit| now.saturating_duration_since(hit.seen_at) <= max_age);
+                if entry.value().is_empty() {
+                    accept_empty.push(*entry.key());
+                }
             }
-        }
-        for ip in &accept_empty {
-            self.accept_hits.remove(ip);
+            for ip in &accept_empty {
+                tracker.accept_hits.remove(ip);
+            }
+
+            // --- Curatam contoarele de depasire (max_ports_per_ip) ---
+            tracker.port_overflow.retain(|_, o| !o.prune(max_age, now));
+            tracker.accept_overflow.retain(|_, o| !o.prune(max_age, now));
         }
 
         // --- Curatam lateral_hits (Lateral Movement #22) ---
@@ -1397,11 +4048,25 @@ impl Detector {
                 .value_mut()
                 .retain(|hit| now.saturating_duration_since(hit.seen_at) <= max_age);
             if entry.value().is_empty() {
-                dist_empty.push(*entry.key());
+                dist_empty.push(*entry.key());
+            }
+        }
+        for ip in &dist_empty {
+            self.distributed_hits.remove(ip);
+        }
+
+        // --- Curatam subnet_hits (Coordinated Subnet Scan) --- cheie = prefix.
+        let mut subnet_empty: Vec<IpAddr> = Vec::new();
+        for mut entry in self.subnet_hits.iter_mut() {
+            entry
+                .value_mut()
+                .retain(|hit| now.saturating_duration_since(hit.seen_at) <= max_age);
+            if entry.value().is_empty() {
+                subnet_empty.push(*entry.key());
             }
         }
-        for ip in &dist_empty {
-            self.distributed_hits.remove(ip);
+        for prefix in &subnet_empty {
+            self.subnet_hits.remove(prefix);
         }
 
         // --- Curatam beacon_hits (Beaconing C2 #24) ---
@@ -1420,6 +4085,28 @@ impl Detector {
             self.beacon_hits.remove(key);
         }
 
+        // --- Curatam contoarele Flood cu fereastra incheiata ---
+        let flood_window = Duration::from_secs(self.config.load().flood.time_window_secs);
+        self.flood_counters
+            .retain(|_, c| now.saturating_duration_since(c.window_start) < flood_window);
+
+        // --- Curatam contoarele Malformed Input cu fereastra incheiata ---
+        let malformed_window = Duration::from_secs(self.config.load().malformed_input.time_window_secs);
+        self.malformed_counters
+            .retain(|_, c| now.saturating_duration_since(c.window_start) < malformed_window);
+
+        // --- Curatam sweep_hits (Slow Sweep) --- fereastra proprie, de regula
+        // mai lunga decat max_entry_age_secs.
+        let sweep_window = Duration::from_secs(self.config.load().slow_sweep.time_window_secs);
+        self.sweep_hits.retain(|_, hits| {
+            hits.retain(|h| now.saturating_duration_since(h.seen_at) <= sweep_window);
+            !hits.is_empty()
+        });
+
+        // --- Curatam porturile sursa iesite din fereastra source_port_exhaustion ---
+        let spe_window = Duration::from_secs(self.config.load().source_port_exhaustion.time_window_secs);
+        self.source_port_hits.retain(|_, hits| !hits.expire(spe_window, now));
+
         // --- Sincronizam last_seen ---
         //
         // Eliminam din last_seen IP-urile care nu mai au date in NICIUN map.
@@ -1433,8 +4120,8 @@ impl Detector {
         //   - Nu exista overlapping borrows sau deadlock potential
         //   - Garantat de Rust la compile-time prin tipurile Send + Sync ale DashMap
         self.last_seen.retain(|ip, _| {
-            self.port_hits.contains_key(ip)
-                || self.accept_hits.contains_key(ip)
+            tracker.port_hits.contains_key(ip)
+                || tracker.accept_hits.contains_key(ip)
                 || self.lateral_hits.contains_key(ip)
         });
 
@@ -1450,8 +4137,31 @@ impl Detector {
             .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
         self.distributed_cooldowns
             .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
+        self.subnet_cooldowns
+            .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
         self.beacon_cooldowns
             .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
+        self.flood_cooldowns
+            .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
+        self.sweep_cooldowns
+            .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
+        self.malformed_cooldowns
+            .retain(|_, instant| now.saturating_duration_since(*instant) <= cooldown_dur);
+        let early_dur = Duration::from_secs(self.config.load().early_warning.cooldown_secs);
+        self.early_cooldowns
+            .retain(|_, instant| now.saturating_duration_since(*instant) <= early_dur);
+        self.custom_rules.load().cleanup(cooldown_dur, now);
+
+        // --- Curatam confirmarile neconfirmate expirate (confirm_windows) ---
+        // O depasire izolata care nu s-a repetat in `confirm_secs` dispare tacut.
+        let confirm_dur = Duration::from_secs(self.config.load().confirm_secs);
+        self.pending_confirms
+            .retain(|_, p| now.saturating_duration_since(p.first_at) <= confirm_dur);
+
+        // --- Curatam sursele autorizate prin semnatura cu fereastra expirata ---
+        let trusted_dur = Duration::from_secs(self.config.load().trusted_signatures.suppress_secs);
+        self.trusted_sources
+            .retain(|_, since| now.saturating_duration_since(*since) < trusted_dur);
     }
 
     /// Returneaza numarul total de IP-uri urmarite in memorie (drop + accept).
@@ -1462,14 +4172,113 @@ impl Detector {
     pub fn tracked_ips(&self) -> usize {
         self.last_seen.len()
     }
+
+    /// Sursele cu cele mai multe hit-uri inca in memorie (drop + accept),
+    /// ordonate descrescator. Folosit de `/stats` si pagina web a metricilor.
+    ///
+    /// Cost O(n) peste IP-urile urmarite — apelat doar la cerere HTTP,
+    /// niciodata din calea de procesare a evenimentelor.
+    pub fn top_talkers(&self, limit: usize) -> Vec<TopTalker> {
+        let tracker = self.tracker.load();
+        let mut talkers: HashMap<IpAddr, TopTalker> = HashMap::new();
+        for entry in tracker.port_hits.iter() {
+            let ip = *entry.key();
+            talkers.entry(ip).or_insert_with(|| TopTalker::new(ip)).dropped = entry.value().len();
+        }
+        for entry in tracker.accept_hits.iter() {
+            let ip = *entry.key();
+            talkers.entry(ip).or_insert_with(|| TopTalker::new(ip)).accepted = entry.value().len();
+        }
+
+        let mut talkers: Vec<TopTalker> = talkers.into_values().collect();
+        talkers.sort_by(|a, b| {
+            (b.dropped + b.accepted)
+                .cmp(&(a.dropped + a.accepted))
+                .then(a.ip.cmp(&b.ip))
+        });
+        talkers.truncate(limit);
+        talkers
+    }
+
+    /// Sursele aflate acum peste pragul Fast sau Slow Scan (porturi drop
+    /// unice in fereastra regulii), cu porturile unice din fereastra Slow,
+    /// ordonate descrescator. Folosit de raportul SIGUSR2.
+    ///
+    /// Aproximare: pragurile statice din config, porturi neponderate — fara
+    /// baseline dinamic, ponderi sau plafonul `max_ports_per_ip`. Cost O(n)
+    /// peste IP-urile urmarite, doar la cerere.
+    pub fn scanners_over_threshold(&self, now: Instant) -> Vec<(IpAddr, usize)> {
+        let cfg = self.config.load();
+        let fast_w = Duration::from_secs(cfg.fast_scan.time_window_secs);
+        let slow_w = Duration::from_secs(cfg.slow_scan.time_window_mins * 60);
+        let mut scanners: Vec<(IpAddr, usize)> = self
+            .tracker
+            .load()
+            .port_hits
+            .iter()
+            .filter_map(|entry| {
+                let hits = entry.value();
+                let fast = count_unique_ports_in_hits(hits, fast_w, now);
+                let slow = count_unique_ports_in_hits(hits, slow_w, now);
+                (fast >= cfg.fast_scan.port_threshold || slow >= cfg.slow_scan.port_threshold)
+                    .then(|| (*entry.key(), slow.max(fast)))
+            })
+            .collect();
+        scanners.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        scanners
+    }
+
+    /// Compacteaza structurile de tracking supra-alocate (synth-208).
+    ///
+    /// Apelat periodic din task-ul de cleanup, DUPA `cleanup()` — atunci
+    /// map-urile si Vec-urile contin doar datele inca relevante.
+    /// Returneaza estimarea octetilor eliberati (pentru linia de statistici).
+    pub fn compact(&self) -> usize {
+        // Harta partajata este compactata o singura data, de cel care o alimenteaza.
+        let tracked = if self.feeds_tracker.load(Ordering::Relaxed) {
+            let tracker = self.tracker.load();
+            compact_vec_map(&tracker.port_hits)
+                + compact_vec_map(&tracker.accept_hits)
+                + compact_map(&tracker.port_overflow)
+                + compact_map(&tracker.accept_overflow)
+        } else {
+            0
+        };
+        tracked
+            + compact_vec_map(&self.lateral_hits)
+            + compact_vec_map(&self.distributed_hits)
+            + compact_vec_map(&self.subnet_hits)
+            + compact_vec_map(&self.beacon_hits)
+            + compact_map(&self.source_port_hits)
+            + compact_vec_map(&self.sweep_hits)
+            + compact_map(&self.last_seen)
+            + compact_map(&self.fast_cooldowns)
+            + compact_map(&self.slow_cooldowns)
+            + compact_map(&self.accept_cooldowns)
+            + compact_map(&self.lateral_cooldowns)
+            + compact_map(&self.distributed_cooldowns)
+            + compact_map(&self.subnet_cooldowns)
+            + compact_map(&self.beacon_cooldowns)
+            + compact_map(&self.flood_counters)
+            + compact_map(&self.flood_cooldowns)
+            + compact_map(&self.sweep_cooldowns)
+            + compact_map(&self.malformed_counters)
+            + compact_map(&self.malformed_cooldowns)
+            + compact_map(&self.scan_episodes)
+            + compact_map(&self.early_cooldowns)
+            + compact_map(&self.trusted_sources)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::{
-        AcceptScanConfig, BeaconingConfig, DetectionConfig, DistributedScanConfig,
-        DynamicThresholdConfig, FastScanConfig, LateralMovementConfig, SlowScanConfig,
+        AcceptScanConfig, BeaconingConfig, CoordinatedSubnetConfig, DetectionConfig,
+        DistributedScanConfig, DynamicThresholdConfig, EmptyUdpProbesConfig, FastScanConfig, FloodConfig, LateralMovementConfig,
+        EarlyWarningConfig, MalformedInputConfig, SaturationAlertConfig, SlowScanConfig, SlowSweepConfig,
+        SourcePortExhaustionConfig,
+        SweepConfig, TrustedSignaturesConfig,
     };
 
     /// Creeaza o configuratie de test cu praguri mici pentru teste rapide.
@@ -1477,6 +4286,15 @@ mod tests {
         DetectionConfig {
             alert_cooldown_secs: 5,
             max_hits_per_ip: 1_000,
+            compact_interval_secs: 0,
+            cleanup_every_n_packets: 0,
+            port_weights: Default::default(),
+            rule_precedence: "none".to_string(),
+            per_protocol_tracking: "combined".to_string(),
+            inactivity_reset_secs: 0,
+            confirm_windows: 1,
+            confirm_secs: 600,
+            max_ports_per_ip: 0,
             max_tracked_ips: 10_000,
             whitelist: Vec::new(),
             exceptions: Default::default(),
@@ -1487,6 +4305,8 @@ mod tests {
             slow_scan: SlowScanConfig {
                 port_threshold: 50,
                 time_window_mins: 1,
+                carry_over: 0.0,
+                promote_to_fast: false,
             },
             // Accept Scan cu acelasi prag ca Fast Scan pentru teste simetrice.
             accept_scan: AcceptScanConfig {
@@ -1507,6 +4327,32 @@ mod tests {
                 unique_sources_threshold: 3,
                 time_window_secs: 10,
             },
+            coordinated_subnet: CoordinatedSubnetConfig {
+                enabled: false,
+                prefix_len_v4: 24,
+                prefix_len_v6: 64,
+                min_sources: 3,
+                port_threshold: 20,
+                time_window_secs: 300,
+            },
+            sweep: SweepConfig {
+                min_distinct_targets: 0,
+            },
+            flood: FloodConfig {
+                enabled: false,
+                packets_per_sec_threshold: 1000,
+                time_window_secs: 10,
+            },
+            slow_sweep: SlowSweepConfig {
+                enabled: false,
+                unique_dest_threshold: 20,
+                time_window_secs: 3600,
+            },
+            malformed_input: MalformedInputConfig {
+                enabled: false,
+                threshold: 100,
+                time_window_secs: 60,
+            },
             dynamic_threshold: DynamicThresholdConfig {
                 enabled: false,
                 ewma_alpha: 0.1,
@@ -1522,6 +4368,37 @@ mod tests {
                 cv_threshold: 0.30,
                 min_interval_secs: 1,
                 max_interval_secs: 60,
+                jitter_percent: 0.0,
+                min_consistent_intervals: 6,
+            },
+            saturation_alert: SaturationAlertConfig {
+                enabled: false,
+                evictions_threshold: 3,
+                time_window_secs: 60,
+            },
+            early_warning: EarlyWarningConfig {
+                enabled: false,
+                threshold_fraction: 0.5,
+                cooldown_secs: 300,
+                assets_only: false,
+            },
+            no_traffic_alert_secs: 0,
+            scan_end_silence_secs: 0,
+            rules_dir: String::new(),
+            trusted_signatures: TrustedSignaturesConfig {
+                patterns: Vec::new(),
+                suppress_secs: 3600,
+            },
+            empty_udp_probes: EmptyUdpProbesConfig {
+                enabled: false,
+                weight: 2.0,
+            },
+            source_port_exhaustion: SourcePortExhaustionConfig {
+                enabled: false,
+                min_source_ports: 100,
+                min_density: 0.4,
+                time_window_secs: 10,
+                threshold_factor: 0.5,
             },
         }
     }
@@ -1531,6 +4408,15 @@ mod tests {
         DetectionConfig {
             alert_cooldown_secs: 5,
             max_hits_per_ip: 1_000,
+            compact_interval_secs: 0,
+            cleanup_every_n_packets: 0,
+            port_weights: Default::default(),
+            rule_precedence: "none".to_string(),
+            per_protocol_tracking: "combined".to_string(),
+            inactivity_reset_secs: 0,
+            confirm_windows: 1,
+            confirm_secs: 600,
+            max_ports_per_ip: 0,
             max_tracked_ips: 10_000,
             whitelist: Vec::new(),
             exceptions: Default::default(),
@@ -1541,6 +4427,8 @@ mod tests {
             slow_scan: SlowScanConfig {
                 port_threshold: 200,
                 time_window_mins: 1,
+                carry_over: 0.0,
+                promote_to_fast: false,
             },
             accept_scan: AcceptScanConfig {
                 port_threshold: 100,
@@ -1556,6 +4444,32 @@ mod tests {
                 unique_sources_threshold: 3,
                 time_window_secs: 10,
             },
+            coordinated_subnet: CoordinatedSubnetConfig {
+                enabled: false,
+                prefix_len_v4: 24,
+                prefix_len_v6: 64,
+                min_sources: 3,
+                port_threshold: 20,
+                time_window_secs: 300,
+            },
+            sweep: SweepConfig {
+                min_distinct_targets: 0,
+            },
+            flood: FloodConfig {
+                enabled: false,
+                packets_per_sec_threshold: 1000,
+                time_window_secs: 10,
+            },
+            slow_sweep: SlowSweepConfig {
+                enabled: false,
+                unique_dest_threshold: 20,
+                time_window_secs: 3600,
+            },
+            malformed_input: MalformedInputConfig {
+                enabled: false,
+                threshold: 100,
+                time_window_secs: 60,
+            },
             dynamic_threshold: DynamicThresholdConfig {
                 enabled: false,
                 ewma_alpha: 0.1,
@@ -1571,6 +4485,37 @@ mod tests {
                 cv_threshold: 0.30,
                 min_interval_secs: 1,
                 max_interval_secs: 60,
+                jitter_percent: 0.0,
+                min_consistent_intervals: 6,
+            },
+            saturation_alert: SaturationAlertConfig {
+                enabled: false,
+                evictions_threshold: 3,
+                time_window_secs: 60,
+            },
+            early_warning: EarlyWarningConfig {
+                enabled: false,
+                threshold_fraction: 0.5,
+                cooldown_secs: 300,
+                assets_only: false,
+            },
+            no_traffic_alert_secs: 0,
+            scan_end_silence_secs: 0,
+            rules_dir: String::new(),
+            trusted_signatures: TrustedSignaturesConfig {
+                patterns: Vec::new(),
+                suppress_secs: 3600,
+            },
+            empty_udp_probes: EmptyUdpProbesConfig {
+                enabled: false,
+                weight: 2.0,
+            },
+            source_port_exhaustion: SourcePortExhaustionConfig {
+                enabled: false,
+                min_source_ports: 100,
+                min_density: 0.4,
+                time_window_secs: 10,
+                threshold_factor: 0.5,
             },
         }
     }
@@ -1582,6 +4527,9 @@ mod tests {
             dest_port: port,
             protocol: "tcp".to_string(),
             action: "drop".to_string(),
+            packet_bytes: None,
+            interface: None,
+            source_port: None,
             raw_log: String::new(),
         }
     }
@@ -1594,6 +4542,9 @@ mod tests {
             dest_port: port,
             protocol: "tcp".to_string(),
             action: "accept".to_string(),
+            packet_bytes: None,
+            interface: None,
+            source_port: None,
             raw_log: String::new(),
         }
     }
@@ -1623,6 +4574,193 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_per_protocol_tracking_combined_vs_separate() {
+        let proto_event = |port: u16, protocol: &str| LogEvent {
+            protocol: protocol.to_string(),
+            ..make_event("10.0.0.9", port)
+        };
+
+        // "combined": TCP + UDP numarate impreuna, alerta marcata multi-protocol.
+        let detector = Detector::new(test_config());
+        detector.process_event(&proto_event(1, "tcp"));
+        detector.process_event(&proto_event(2, "udp"));
+        let alerts = detector.process_event(&proto_event(3, "tcp"));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].protocols, vec!["tcp", "udp"]);
+        assert!(alerts[0].multi_protocol);
+
+        // "separate": 2 porturi TCP + 1 UDP nu ating pragul de 3.
+        let detector = Detector::new(DetectionConfig {
+            per_protocol_tracking: "separate".to_string(),
+            ..test_config()
+        });
+        detector.process_event(&proto_event(1, "tcp"));
+        detector.process_event(&proto_event(2, "udp"));
+        assert!(detector.process_event(&proto_event(3, "tcp")).is_empty());
+        let alerts = detector.process_event(&proto_event(4, "tcp"));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].unique_ports, vec![1, 3, 4]);
+        assert_eq!(alerts[0].protocols, vec!["tcp"]);
+        assert!(!alerts[0].multi_protocol);
+    }
+
+    #[test]
+    fn test_separate_tracking_cooldown_per_protocol() {
+        let proto_event = |port: u16, protocol: &str| LogEvent {
+            protocol: protocol.to_string(),
+            ..make_event("10.0.0.9", port)
+        };
+        let detector = Detector::new(DetectionConfig {
+            per_protocol_tracking: "separate".to_string(),
+            ..test_config()
+        });
+        for port in 1..=2 {
+            detector.process_event(&proto_event(port, "tcp"));
+        }
+        let tcp = detector.process_event(&proto_event(3, "tcp"));
+        assert_eq!(tcp.len(), 1);
+        assert_eq!(tcp[0].protocols, vec!["tcp"]);
+
+        // Scanarea UDP a aceleiasi surse nu este in cooldown-ul alertei TCP.
+        for port in 1..=2 {
+            detector.process_event(&proto_event(port, "udp"));
+        }
+        let udp = detector.process_event(&proto_event(3, "udp"));
+        assert_eq!(udp.len(), 1);
+        assert_eq!(udp[0].protocols, vec!["udp"]);
+
+        // TCP ramane in cooldown.
+        assert!(detector.process_event(&proto_event(4, "tcp")).is_empty());
+    }
+
+    #[test]
+    fn test_time_to_threshold_from_first_hit_in_window() {
+        let detector = Detector::new(test_config());
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let now = Instant::now();
+
+        // Un hit vechi (in afara ferestrei Fast de 10s) si unul acum 2s.
+        detector.tracker.load().port_hits.insert(
+            ip,
+            vec![
+                PortHit {
+                    port: 1,
+                    seen_at: now - Duration::from_secs(30),
+                    empty_probe: false,
+                    protocol: "tcp",
+                },
+                PortHit {
+                    port: 2,
+                    seen_at: now - Duration::from_secs(2),
+                    empty_probe: false,
+                    protocol: "tcp",
+                },
+            ],
+        );
+        detector.process_event(&make_event("10.0.0.1", 3));
+        let alerts = detector.process_event(&make_event("10.0.0.1", 4));
+
+        assert_eq!(alerts.len(), 1);
+        let ms = alerts[0].time_to_threshold_ms.expect("Fast Scan are time_to_threshold_ms");
+        // Masurat de la hit-ul de acum 2s, nu de la cel de acum 30s (in afara ferestrei).
+        assert!((2_000..10_000).contains(&ms), "time_to_threshold_ms = {}", ms);
+    }
+
+    #[test]
+    fn test_policy_version_stamped_and_changes_on_reload() {
+        let detector = Detector::new(test_config());
+        let v1 = detector.policy_version();
+        assert_eq!(v1.len(), 8);
+        assert_eq!(v1, test_config().policy_version(), "Hash determinist");
+
+        let mut alerts = Vec::new();
+        for port in 1..=3 {
+            alerts = detector.process_event(&make_event("10.0.0.1", port));
+        }
+        assert_eq!(alerts[0].policy_version, v1);
+
+        let mut cfg = test_config();
+        cfg.fast_scan.port_threshold = 4;
+        detector.update_config(cfg);
+        assert_ne!(detector.policy_version(), v1);
+    }
+
+    #[test]
+    fn test_profile_shares_port_tracker_with_own_thresholds() {
+        let default = Detector::new(test_config());
+        let mut cfg = test_config();
+        cfg.fast_scan.port_threshold = 5;
+        let paging = Detector::new(cfg.clone());
+        assert!(paging.share_tracking(&default));
+        assert!(Arc::ptr_eq(&paging.tracker.load_full(), &default.tracker.load_full()));
+
+        // Ordinea din bucla principala: sursa hartii, apoi profilul.
+        let mut fired = (Vec::new(), Vec::new());
+        for port in 1..=5 {
+            let event = make_event("10.0.0.1", port);
+            fired.0.extend(default.process_event(&event));
+            fired.1.extend(paging.process_event(&event));
+        }
+        assert_eq!(fired.0.len(), 1, "profilul implicit alerteaza la 3 porturi");
+        assert_eq!(fired.1.len(), 1, "profilul propriu alerteaza la 5 porturi");
+        assert_eq!(fired.1[0].unique_ports.len(), 5);
+        // Un singur set de hit-uri — profilul nu le inregistreaza a doua oara.
+        assert_eq!(default.tracker.load().port_hits.get(&"10.0.0.1".parse().unwrap()).unwrap().len(), 5);
+
+        // Cleanup-ul profilului nu curata harta altui detector.
+        paging.cleanup(Duration::ZERO);
+        assert!(!default.tracker.load().port_hits.is_empty());
+    }
+
+    #[test]
+    fn test_profile_tracker_not_shared_or_detached_on_divergence() {
+        let default = Detector::new(test_config());
+        let mut cfg = test_config();
+        cfg.max_ports_per_ip = 10;
+        assert!(!Detector::new(cfg).share_tracking(&default), "plafoane diferite");
+
+        let mut cfg = test_config();
+        cfg.fast_scan.time_window_secs = 30;
+        let profile = Detector::new(cfg.clone());
+        assert!(profile.share_tracking(&default), "doar fereastra difera");
+        assert!(!profile.detach_diverged_tracking());
+
+        // Reload cu alt whitelist: profilul trece pe o harta proprie, goala.
+        default.process_event(&make_event("10.0.0.1", 1));
+        cfg.whitelist = vec!["10.0.0.0/8".to_string()];
+        profile.update_config(cfg);
+        assert!(profile.detach_diverged_tracking());
+        assert!(profile.tracker.load().port_hits.is_empty());
+        profile.process_event(&make_event("192.168.1.1", 1));
+        assert_eq!(profile.tracker.load().port_hits.len(), 1, "isi inregistreaza singur hit-urile");
+        assert_eq!(default.tracker.load().port_hits.len(), 1);
+    }
+
+    #[test]
+    fn test_inactivity_reset_clears_accumulated_ports() {
+        let mut cfg = test_config();
+        cfg.inactivity_reset_secs = 5;
+        let detector = Detector::new(cfg);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        // 2 porturi (sub pragul de 3), apoi sursa tace 8s (> reset de 5s).
+        for port in 1..=2 {
+            detector.process_event(&make_event("10.0.0.1", port));
+        }
+        let paused = Instant::now() - Duration::from_secs(8);
+        detector.last_seen.insert(ip, paused);
+        for hit in detector.tracker.load().port_hits.get_mut(&ip).unwrap().iter_mut() {
+            hit.seen_at = paused;
+        }
+
+        // Al 3-lea port ar completa pragul (toate in fereastra de 10s) —
+        // dar dupa reset se numara de la zero.
+        let alerts = detector.process_event(&make_event("10.0.0.1", 3));
+        assert!(alerts.is_empty(), "Hit-urile dinainte de pauza nu trebuie numarate");
+        assert_eq!(detector.tracker.load().port_hits.get(&ip).unwrap().len(), 1);
+    }
+
     #[test]
     fn test_cooldown_prevents_duplicate_alert() {
         let detector = Detector::new(test_config());
@@ -1640,6 +4778,206 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_confirm_windows_requires_second_crossing() {
+        let mut cfg = test_config();
+        cfg.confirm_windows = 2;
+        cfg.confirm_secs = 60;
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        // Imbatraneste starea unui detector (hit-uri + asteptare) cu `secs` secunde.
+        let age = |detector: &Detector, secs: u64| {
+            for hit in detector.tracker.load().port_hits.get_mut(&ip).unwrap().iter_mut() {
+                hit.seen_at -= Duration::from_secs(secs);
+            }
+            for mut p in detector.pending_confirms.iter_mut() {
+                p.first_at -= Duration::from_secs(secs);
+                p.last_at -= Duration::from_secs(secs);
+            }
+        };
+
+        // Prima depasire (3 porturi) doar armeaza; porturi noi in ACEEASI fereastra nu confirma.
+        let detector = Detector::new(cfg.clone());
+        for port in 1..=4 {
+            assert!(detector.process_event(&make_event("10.0.0.1", port)).is_empty());
+        }
+
+        // A doua depasire, intr-o fereastra separata (>10s) si in confirm_secs → alerta.
+        age(&detector, 11);
+        assert!(detector.process_event(&make_event("10.0.0.1", 10)).is_empty());
+        assert!(detector.process_event(&make_event("10.0.0.1", 11)).is_empty());
+        let alerts = detector.process_event(&make_event("10.0.0.1", 12));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Fast));
+
+        // O depasire izolata expira tacut: dupa confirm_secs se reia de la zero.
+        let detector = Detector::new(cfg);
+        for port in 1..=3 {
+            assert!(detector.process_event(&make_event("10.0.0.1", port)).is_empty());
+        }
+        age(&detector, 61);
+        for port in 20..=22 {
+            assert!(detector.process_event(&make_event("10.0.0.1", port)).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_confirm_windows_slow_scan() {
+        // Slow Scan se confirma pe fereastra LUI (1 minut), nu pe cea Fast.
+        let mut cfg = test_config();
+        cfg.confirm_windows = 2;
+        cfg.confirm_secs = 120;
+        cfg.fast_scan.port_threshold = 1_000;
+        cfg.slow_scan.port_threshold = 5;
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let detector = Detector::new(cfg);
+
+        // Prima depasire doar armeaza.
+        for port in 1..=6 {
+            assert!(detector.process_event(&make_event("10.0.0.1", port)).is_empty());
+        }
+
+        // Dupa o fereastra Fast (10s) nu se confirma — e aceeasi fereastra Slow.
+        for hit in detector.tracker.load().port_hits.get_mut(&ip).unwrap().iter_mut() {
+            hit.seen_at -= Duration::from_secs(11);
+        }
+        for mut p in detector.pending_confirms.iter_mut() {
+            p.first_at -= Duration::from_secs(11);
+            p.last_at -= Duration::from_secs(11);
+        }
+        assert!(detector.process_event(&make_event("10.0.0.1", 7)).is_empty());
+
+        // A doua depasire dupa o fereastra Slow completa, in confirm_secs → alerta.
+        for hit in detector.tracker.load().port_hits.get_mut(&ip).unwrap().iter_mut() {
+            hit.seen_at -= Duration::from_secs(61);
+        }
+        for mut p in detector.pending_confirms.iter_mut() {
+            p.first_at -= Duration::from_secs(61);
+            p.last_at -= Duration::from_secs(61);
+        }
+        let mut alerts = Vec::new();
+        for port in 20..=24 {
+            alerts.extend(detector.process_event(&make_event("10.0.0.1", port)));
+        }
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Slow));
+    }
+
+    #[test]
+    fn test_explain_suppressions_reasons() {
+        let mut config = test_config();
+        config.whitelist = vec!["192.168.0.0/16".to_string()];
+        let detector = Detector::new(config);
+
+        // Whitelist: motivul numeste intrarea din config.
+        let (alerts, why) = detector.process_event_explained(&make_event("192.168.1.7", 22));
+        assert!(alerts.is_empty());
+        assert_eq!(why.len(), 1);
+        assert_eq!(why[0].rule, "whitelist");
+        assert!(why[0].reason.contains("192.168.0.0/16"));
+
+        // Sub prag (2/3): raportat, pentru ca depaseste jumatatea pragului.
+        detector.process_event(&make_event("10.0.0.1", 1));
+        let (_, why) = detector.process_event_explained(&make_event("10.0.0.1", 2));
+        assert!(why.iter().any(|s| s.rule == "fast_scan" && s.reason.contains("sub prag cu 1")));
+
+        // Prag atins in cooldown.
+        detector.process_event(&make_event("10.0.0.1", 3));
+        let (alerts, why) = detector.process_event_explained(&make_event("10.0.0.1", 4));
+        assert!(alerts.is_empty());
+        assert!(why.iter().any(|s| s.rule == "fast_scan" && s.reason.contains("cooldown")));
+    }
+
+    #[test]
+    fn test_asset_criticality_attached_and_escalated() {
+        let detector = Detector::new(test_config());
+        detector.set_assets(&AssetsConfig {
+            critical_cidrs: vec!["10.0.0.1/32".to_string()],
+            high_cidrs: vec!["10.0.0.0/24".to_string()],
+        });
+
+        // Tinta 10.0.0.1 este in ambele liste → "critical" are prioritate.
+        let mut alerts = Vec::new();
+        for port in 1..=3 {
+            alerts.extend(detector.process_event(&make_event("192.168.1.1", port)));
+        }
+        let fast = alerts.iter().find(|a| matches!(a.scan_type, ScanType::Fast)).unwrap();
+        assert_eq!(fast.asset_criticality, Some(AssetCriticality::Critical));
+
+        // Fara active configurate: campul lipseste.
+        detector.set_assets(&AssetsConfig::default());
+        let mut alerts = Vec::new();
+        for port in 1..=3 {
+            alerts.extend(detector.process_event(&make_event("192.168.1.2", port)));
+        }
+        assert!(alerts.iter().all(|a| a.asset_criticality.is_none()));
+
+        assert_eq!(AssetCriticality::Critical.escalate(5), 10);
+        assert_eq!(AssetCriticality::High.escalate(7), 8);
+        assert_eq!(AssetCriticality::High.escalate(10), 10);
+    }
+
+    #[test]
+    fn test_early_warning_at_fraction_rate_limited_per_ip() {
+        let mut cfg = test_config();
+        cfg.fast_scan.port_threshold = 6;
+        cfg.early_warning.enabled = true;
+        let detector = Detector::new(cfg.clone());
+
+        let mut warnings = Vec::new();
+        let mut alerts = Vec::new();
+        for port in 1..=6 {
+            alerts.extend(detector.process_event(&make_event("192.168.1.1", port)));
+            warnings.extend(detector.take_early_warnings());
+            if port == 3 {
+                // 3/6 = 50%: avertisment provizoriu, inca nicio alerta.
+                assert_eq!(warnings.len(), 1);
+                assert!(alerts.is_empty());
+            }
+        }
+        // Porturile 4 si 5 nu repeta avertismentul (limita per IP); pragul
+        // produce alerta completa normal.
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(warnings[0].scan_type, ScanType::Fast));
+        assert_eq!(warnings[0].threshold, 6);
+        assert!(alerts.iter().any(|a| matches!(a.scan_type, ScanType::Fast)));
+
+        // assets_only: tinta neclasificata → niciun avertisment.
+        cfg.early_warning.assets_only = true;
+        let detector = Detector::new(cfg);
+        for port in 1..=4 {
+            detector.process_event(&make_event("192.168.1.2", port));
+        }
+        assert!(detector.take_early_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_asset_map_enriches_and_takes_max_criticality() {
+        let path = std::env::temp_dir().join(format!("ids-rs-assets-{}.csv", std::process::id()));
+        std::fs::write(&path, "ip,name,criticality,owner\n10.0.0.1,db-prod-01,critical,Echipa DBA\n")
+            .unwrap();
+        let map = AssetMap::load(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let detector = Detector::new(test_config());
+        // [assets] spune doar "high"; harta CMDB spune "critical" → castiga maximul.
+        detector.set_assets(&AssetsConfig {
+            critical_cidrs: vec![],
+            high_cidrs: vec!["10.0.0.0/24".to_string()],
+        });
+        detector.set_asset_map(Arc::new(map));
+
+        let mut alerts = Vec::new();
+        for port in 1..=3 {
+            alerts.extend(detector.process_event(&make_event("192.168.1.1", port)));
+        }
+        let fast = alerts.iter().find(|a| matches!(a.scan_type, ScanType::Fast)).unwrap();
+        assert_eq!(fast.asset_criticality, Some(AssetCriticality::Critical));
+        let asset = fast.asset.as_ref().unwrap();
+        assert_eq!(asset.name, "db-prod-01");
+        assert_eq!(asset.owner, "Echipa DBA");
+    }
+
     #[test]
     fn test_different_ips_tracked_separately() {
         let detector = Detector::new(test_config());
@@ -1686,7 +5024,8 @@ mod tests {
 
         // Vec-ul nu trebuie sa depaseasca limita de 5.
         let ip: std::net::IpAddr = "10.0.0.1".parse().unwrap();
-        let entry = detector.port_hits.get(&ip).unwrap();
+        let tracker = detector.tracker.load();
+        let entry = tracker.port_hits.get(&ip).unwrap();
         assert!(
             entry.len() <= 5,
             "Vec-ul a depasit max_hits_per_ip: are {} intrari",
@@ -1718,6 +5057,9 @@ mod tests {
             protocol: "tcp".to_string(),
             // Diferenta fata de make_event: actiunea este "accept" nu "drop".
             action: "accept".to_string(),
+            packet_bytes: None,
+            interface: None,
+            source_port: None,
             raw_log: String::new(),
         }
     }
@@ -1831,18 +5173,103 @@ mod tests {
         // IP-ul 10.0.0.1 a fost cel mai vechi → trebuie evictat.
         let ip1: std::net::IpAddr = "10.0.0.1".parse().unwrap();
         assert!(
-            !detector.port_hits.contains_key(&ip1),
+            !detector.tracker.load().port_hits.contains_key(&ip1),
             "IP-ul cel mai vechi (10.0.0.1) trebuia evictat"
         );
 
         // IP-ul 10.0.0.3 (cel mai recent) trebuie sa fie prezent.
         let ip3: std::net::IpAddr = "10.0.0.3".parse().unwrap();
         assert!(
-            detector.port_hits.contains_key(&ip3),
+            detector.tracker.load().port_hits.contains_key(&ip3),
             "IP-ul cel mai recent (10.0.0.3) trebuie sa fie prezent"
         );
     }
 
+    #[test]
+    fn test_saturation_alert_on_eviction_rate() {
+        // max_tracked_ips = 2, prag saturatie = 3 evictii / 60s.
+        // IP-urile 3, 4, 5 forteaza cate o evictie → a 3-a evictie declanseaza alerta.
+        let mut config = test_config();
+        config.max_tracked_ips = 2;
+        config.saturation_alert.enabled = true;
+        let detector = Detector::new(config);
+
+        let mut saturation = Vec::new();
+        for i in 1..=5 {
+            let alerts = detector.process_event(&make_event(&format!("10.0.0.{}", i), 80));
+            saturation.extend(
+                alerts
+                    .into_iter()
+                    .filter(|a| matches!(a.scan_type, ScanType::Saturation)),
+            );
+        }
+
+        assert_eq!(saturation.len(), 1, "Trebuia exact o alerta de saturatie");
+        let alert = &saturation[0];
+        assert_eq!(alert.event_count, Some(3));
+        assert_eq!(alert.eviction_rate, Some(3.0 / 60.0));
+        assert_eq!(alert.source_ip, "10.0.0.5".parse::<IpAddr>().unwrap());
+
+        // Cooldown global: evictiile ulterioare nu re-alerteaza imediat.
+        for i in 6..=7 {
+            let alerts = detector.process_event(&make_event(&format!("10.0.0.{}", i), 80));
+            assert!(
+                !alerts.iter().any(|a| matches!(a.scan_type, ScanType::Saturation)),
+                "Alerta de saturatie trebuie sa respecte cooldown-ul"
+            );
+        }
+
+        // Dupa cooldown, rata numara TOATE evictiile din fereastra (3..=8 → 6),
+        // nu doar pragul.
+        *detector.saturation_cooldown.lock().unwrap() = None;
+        let alerts = detector.process_event(&make_event("10.0.0.8", 80));
+        let alert = alerts
+            .iter()
+            .find(|a| matches!(a.scan_type, ScanType::Saturation))
+            .expect("alerta de saturatie dupa cooldown");
+        assert_eq!(alert.event_count, Some(6));
+        assert_eq!(alert.eviction_rate, Some(6.0 / 60.0));
+    }
+
+    #[test]
+    fn test_saturation_alert_disabled_no_alert() {
+        let mut config = test_config();
+        config.max_tracked_ips = 2;
+        let detector = Detector::new(config);
+
+        for i in 1..=10 {
+            let alerts = detector.process_event(&make_event(&format!("10.0.0.{}", i), 80));
+            assert!(
+                !alerts.iter().any(|a| matches!(a.scan_type, ScanType::Saturation)),
+                "Alerta de saturatie dezactivata nu trebuie sa se declanseze"
+            );
+        }
+    }
+
+    #[test]
+    fn test_trusted_signature_suppresses_source() {
+        let mut config = test_config();
+        config.trusted_signatures.patterns = vec!["X-Scanner: acme-pentest".to_string()];
+        let detector = Detector::new(config);
+
+        // Primul pachet poarta marker-ul; urmatoarele (fara marker) sunt suprimate.
+        let mut marked = make_event("10.0.0.50", 1);
+        marked.raw_log = "GET / HTTP/1.1 X-Scanner: acme-pentest".to_string();
+        assert!(detector.process_event(&marked).is_empty());
+        for port in 2..=10 {
+            assert!(
+                detector.process_event(&make_event("10.0.0.50", port)).is_empty(),
+                "Sursa cu semnatura autorizata nu trebuie sa genereze alerte"
+            );
+        }
+
+        // O alta sursa, fara marker, este detectata normal.
+        let alerts: Vec<Alert> = (1..=3)
+            .flat_map(|port| detector.process_event(&make_event("10.0.0.51", port)))
+            .collect();
+        assert!(alerts.iter().any(|a| matches!(a.scan_type, ScanType::Fast)));
+    }
+
     // =========================================================================
     // Teste pentru Slow Scan (#18)
     // =========================================================================
@@ -1861,6 +5288,15 @@ mod tests {
         DetectionConfig {
             alert_cooldown_secs: 5,
             max_hits_per_ip: 1_000,
+            compact_interval_secs: 0,
+            cleanup_every_n_packets: 0,
+            port_weights: Default::default(),
+            rule_precedence: "none".to_string(),
+            per_protocol_tracking: "combined".to_string(),
+            inactivity_reset_secs: 0,
+            confirm_windows: 1,
+            confirm_secs: 600,
+            max_ports_per_ip: 0,
             max_tracked_ips: 10_000,
             whitelist: Vec::new(),
             exceptions: Default::default(),
@@ -1871,6 +5307,8 @@ mod tests {
             slow_scan: SlowScanConfig {
                 port_threshold: 3, // prag mic pentru teste rapide
                 time_window_mins: 1,
+                carry_over: 0.0,
+                promote_to_fast: false,
             },
             accept_scan: AcceptScanConfig {
                 port_threshold: 1_000,
@@ -1886,6 +5324,32 @@ mod tests {
                 unique_sources_threshold: 3,
                 time_window_secs: 10,
             },
+            coordinated_subnet: CoordinatedSubnetConfig {
+                enabled: false,
+                prefix_len_v4: 24,
+                prefix_len_v6: 64,
+                min_sources: 3,
+                port_threshold: 20,
+                time_window_secs: 300,
+            },
+            sweep: SweepConfig {
+                min_distinct_targets: 0,
+            },
+            flood: FloodConfig {
+                enabled: false,
+                packets_per_sec_threshold: 1000,
+                time_window_secs: 10,
+            },
+            slow_sweep: SlowSweepConfig {
+                enabled: false,
+                unique_dest_threshold: 20,
+                time_window_secs: 3600,
+            },
+            malformed_input: MalformedInputConfig {
+                enabled: false,
+                threshold: 100,
+                time_window_secs: 60,
+            },
             dynamic_threshold: DynamicThresholdConfig {
                 enabled: false,
                 ewma_alpha: 0.1,
@@ -1901,8 +5365,371 @@ mod tests {
                 cv_threshold: 0.30,
                 min_interval_secs: 1,
                 max_interval_secs: 60,
+                jitter_percent: 0.0,
+                min_consistent_intervals: 6,
             },
+            saturation_alert: SaturationAlertConfig {
+                enabled: false,
+                evictions_threshold: 3,
+                time_window_secs: 60,
+            },
+            early_warning: EarlyWarningConfig {
+                enabled: false,
+                threshold_fraction: 0.5,
+                cooldown_secs: 300,
+                assets_only: false,
+            },
+            no_traffic_alert_secs: 0,
+            scan_end_silence_secs: 0,
+            rules_dir: String::new(),
+            trusted_signatures: TrustedSignaturesConfig {
+                patterns: Vec::new(),
+                suppress_secs: 3600,
+            },
+            empty_udp_probes: EmptyUdpProbesConfig {
+                enabled: false,
+                weight: 2.0,
+            },
+            source_port_exhaustion: SourcePortExhaustionConfig {
+                enabled: false,
+                min_source_ports: 100,
+                min_density: 0.4,
+                time_window_secs: 10,
+                threshold_factor: 0.5,
+            },
+        }
+    }
+
+    #[test]
+    fn test_compact_reclaims_overallocated_hits() {
+        let detector = Detector::new(test_config());
+        let ip: IpAddr = "192.168.1.100".parse().unwrap();
+
+        // Simulam resturile unui burst: capacitate mare, o singura intrare.
+        let mut hits = Vec::with_capacity(1_000);
+        hits.push(PortHit {
+            port: 22,
+            seen_at: Instant::now(),
+            empty_probe: false,
+            protocol: "tcp",
+        });
+        detector.tracker.load().port_hits.insert(ip, hits);
+
+        let reclaimed = detector.compact();
+        assert!(reclaimed >= 999 * std::mem::size_of::<PortHit>());
+        let tracker = detector.tracker.load();
+        let entry = tracker.port_hits.get(&ip).unwrap();
+        assert_eq!(entry.len(), 1, "Compactarea nu trebuie sa piarda date");
+        assert!(entry.capacity() < 1_000);
+        drop(entry);
+
+        // A doua trecere nu mai are nimic de eliberat.
+        assert_eq!(detector.compact(), 0);
+    }
+
+    #[test]
+    fn test_empty_udp_probes_weighted_toward_fast_scan() {
+        let mut cfg = test_config();
+        cfg.fast_scan.port_threshold = 4;
+        cfg.slow_scan.port_threshold = 100;
+        cfg.empty_udp_probes.enabled = true;
+
+        // 28 octeti = antetele IPv4 + UDP, fara payload.
+        let empty_udp = |port: u16| LogEvent {
+            protocol: "udp".to_string(),
+            packet_bytes: Some(28),
+            ..make_event("10.0.0.7", port)
+        };
+
+        // 2 porturi unice × pondere 2.0 = scor 4 → prag atins.
+        let detector = Detector::new(cfg.clone());
+        assert!(detector.process_event(&empty_udp(53)).is_empty());
+        let alerts = detector.process_event(&empty_udp(161));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Fast));
+        assert!(alerts[0].empty_probes);
+
+        // `bytes = 0` (octeti nenumarati) si datagramele cu payload nu sunt sonde goale.
+        let detector = Detector::new(cfg.clone());
+        for (port, bytes) in [(53, 0), (161, 29)] {
+            let event = LogEvent { packet_bytes: Some(bytes), ..empty_udp(port) };
+            assert!(detector.process_event(&event).is_empty());
+        }
+
+        // Aceleasi evenimente cu ponderarea dezactivata: doar 2 porturi, sub prag.
+        cfg.empty_udp_probes.enabled = false;
+        let detector = Detector::new(cfg);
+        detector.process_event(&empty_udp(53));
+        assert!(detector.process_event(&empty_udp(161)).is_empty());
+    }
+
+    #[test]
+    fn test_port_weights_count_toward_threshold() {
+        let mut cfg = test_config();
+        cfg.fast_scan.port_threshold = 10;
+        cfg.port_weights.insert("1521".to_string(), 8.0);
+
+        // 22 + 80 + 1521 = 1 + 1 + 8 = 10 → prag atins cu doar 3 porturi.
+        let detector = Detector::new(cfg);
+        assert!(detector.process_event(&make_event("10.0.0.8", 22)).is_empty());
+        assert!(detector.process_event(&make_event("10.0.0.8", 80)).is_empty());
+        let alerts = detector.process_event(&make_event("10.0.0.8", 1521));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Fast));
+        assert_eq!(alerts[0].unique_ports.len(), 3);
+        assert_eq!(alerts[0].weighted_score, Some(10.0));
+
+        // Fara ponderi: acelasi trafic ramane sub prag, fara scor ponderat.
+        let mut cfg = test_config();
+        cfg.fast_scan.port_threshold = 10;
+        let detector = Detector::new(cfg);
+        for port in [22, 80, 1521] {
+            assert!(detector.process_event(&make_event("10.0.0.8", port)).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_summary_rendered_from_template() {
+        let detector = Detector::new(test_config());
+        detector.set_summary_template("{type} de la {source_ip} — {detail} [{ports}] {unknown}");
+        for port in 1..=2 {
+            detector.process_event(&make_event("10.0.0.4", port));
+        }
+        let alerts = detector.process_event(&make_event("10.0.0.4", 3));
+        let fast = alerts
+            .iter()
+            .find(|a| matches!(a.scan_type, ScanType::Fast))
+            .expect("Fast Scan");
+        // `time_to_threshold_ms` variaza — comparam doar prefixul lui {detail}.
+        assert!(
+            fast.summary.starts_with("Fast Scan de la 10.0.0.4 — 3 porturi in "),
+            "{}",
+            fast.summary
+        );
+        // Campurile necunoscute raman literale (config-ul le respinge oricum).
+        assert!(fast.summary.ends_with(" [1,2,3] {unknown}"), "{}", fast.summary);
+
+        let beacon = Alert {
+            beacon_port: Some(443),
+            mean_interval_secs: Some(30.0),
+            event_count: Some(12),
+            ..Alert::new(
+                ScanType::Beaconing,
+                "10.0.0.4".parse().unwrap(),
+                Some("8.8.8.8".parse().unwrap()),
+            )
+        };
+        assert_eq!(
+            beacon.render_summary("{detail}"),
+            "12 calluri → 8.8.8.8:443, interval mediu 30.0s"
+        );
+    }
+
+    #[test]
+    fn test_port_ranges_collapse_contiguous_ports() {
+        let mut ports: Vec<u16> = (1..=1024).rev().collect();
+        ports.extend([8085, 3389, 8080, 8081, 8082, 8083, 8084, 8090, 3389, 65535]);
+        let alert = Alert {
+            unique_ports: ports,
+            ..Alert::new(ScanType::Fast, "10.0.0.4".parse().unwrap(), None)
+        };
+        assert_eq!(
+            alert.port_ranges(),
+            vec![(1, 1024), (3389, 3389), (8080, 8085), (8090, 8090), (65535, 65535)]
+        );
+        assert_eq!(alert.ports_text(", ", true), "1-1024, 3389, 8080-8085, 8090, 65535");
+
+        // Lista individuala ramane in ordinea din alerta.
+        let small = Alert {
+            unique_ports: vec![443, 22, 23],
+            ..Alert::new(ScanType::Fast, "10.0.0.4".parse().unwrap(), None)
+        };
+        assert_eq!(small.ports_text(",", false), "443,22,23");
+        assert_eq!(small.ports_text(",", true), "22-23,443");
+    }
+
+    #[test]
+    fn test_sort_ports_orders_and_dedups() {
+        // `emit_alert` (main.rs) ordoneaza orice alerta inainte de iesiri —
+        // inclusiv cele construite altfel (reguli externe, replay).
+        let mut alert = Alert {
+            unique_ports: vec![3389, 22, 443, 22],
+            ..Alert::new(ScanType::CustomRule, "10.0.0.5".parse().unwrap(), None)
+        };
+        alert.sort_ports();
+        assert_eq!(alert.unique_ports, vec![22, 443, 3389]);
+    }
+
+    #[test]
+    fn test_rule_precedence_avoids_double_alert() {
+        let mut cfg = test_config();
+        cfg.slow_scan.port_threshold = 4;
+
+        // "none": acelasi IP produce Fast (3 porturi) si apoi Slow (4 porturi).
+        let detector = Detector::new(cfg.clone());
+        let types: Vec<ScanType> = (1..=4)
+            .flat_map(|port| detector.process_event(&make_event("10.0.0.7", port)))
+            .map(|a| a.scan_type)
+            .collect();
+        assert_eq!(types.len(), 2);
+
+        // "fast": Fast Scan activ → Slow Scan suprimat, cu motiv in modul explain.
+        cfg.rule_precedence = "fast".to_string();
+        let detector = Detector::new(cfg.clone());
+        for port in 1..=3 {
+            detector.process_event(&make_event("10.0.0.7", port));
+        }
+        let (alerts, suppressed) = detector.process_event_explained(&make_event("10.0.0.7", 4));
+        assert!(alerts.is_empty());
+        assert!(suppressed
+            .iter()
+            .any(|s| s.rule == "slow_scan" && s.reason.contains("rule_precedence")));
+
+        // "slow": ambele praguri atinse de acelasi eveniment → doar Slow Scan.
+        cfg.rule_precedence = "slow".to_string();
+        cfg.slow_scan.port_threshold = 3;
+        let detector = Detector::new(cfg);
+        for port in 1..=2 {
+            detector.process_event(&make_event("10.0.0.7", port));
+        }
+        let alerts = detector.process_event(&make_event("10.0.0.7", 3));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Slow));
+    }
+
+    #[test]
+    fn test_slow_scan_promoted_to_fast() {
+        // Precedenta "slow": Slow Scan (2 porturi) activ, apoi scanarea
+        // accelereaza pana la pragul Fast (4 porturi in fereastra fast).
+        let mut cfg = test_config();
+        cfg.rule_precedence = "slow".to_string();
+        cfg.slow_scan.port_threshold = 2;
+        cfg.fast_scan.port_threshold = 4;
+        let scan = |detector: &Detector| -> Vec<Alert> {
+            (1..=4)
+                .flat_map(|port| detector.process_event(&make_event("10.0.0.8", port)))
+                .collect()
+        };
+
+        // Fara promovare: Fast Scan ramane suprimat de Slow Scan.
+        let alerts = scan(&Detector::new(cfg.clone()));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Slow));
+
+        // Cu promovare: Fast Scan emis si marcat, fara o a doua alerta Slow.
+        cfg.slow_scan.promote_to_fast = true;
+        let detector = Detector::new(cfg);
+        let alerts = scan(&detector);
+        assert_eq!(alerts.len(), 2);
+        assert!(matches!(alerts[0].scan_type, ScanType::Slow) && !alerts[0].promoted_from_slow);
+        assert!(matches!(alerts[1].scan_type, ScanType::Fast) && alerts[1].promoted_from_slow);
+        assert!(detector.process_event(&make_event("10.0.0.8", 5)).is_empty());
+    }
+
+    #[test]
+    fn test_inbound_only_disables_accept_scan() {
+        let detector = Detector::new(test_config());
+        detector.set_inbound_only(true);
+        for port in 1..=5 {
+            assert!(detector.process_event(&make_accept_event("10.0.0.3", port)).is_empty());
+        }
+        // Accept-urile nu mai sunt stocate; drop-urile raman detectate.
+        assert!(detector.tracker.load().accept_hits.is_empty());
+        for port in 1..=2 {
+            detector.process_event(&make_event("10.0.0.3", port));
+        }
+        let alerts = detector.process_event(&make_event("10.0.0.3", 3));
+        assert!(alerts.iter().any(|a| matches!(a.scan_type, ScanType::Fast)));
+    }
+
+    #[test]
+    fn test_max_ports_per_ip_caps_stored_ports() {
+        let mut cfg = test_config();
+        cfg.max_ports_per_ip = 5;
+        cfg.fast_scan.port_threshold = 5;
+        cfg.slow_scan.port_threshold = 8;
+        let detector = Detector::new(cfg);
+        let ip: IpAddr = "10.0.0.8".parse().unwrap();
+
+        let mut slow = None;
+        for port in 1..=8 {
+            for alert in detector.process_event(&make_event("10.0.0.8", port)) {
+                if matches!(alert.scan_type, ScanType::Slow) {
+                    slow = Some(alert);
+                }
+            }
+        }
+
+        // Doar 5 porturi memorate; celelalte 3 sunt numarate in contorul de depasire.
+        assert_eq!(detector.tracker.load().port_hits.get(&ip).unwrap().len(), 5);
+        let slow = slow.expect("pragul Slow (8) trebuie atins prin contorul aproximativ");
+        assert_eq!(slow.unique_ports.len(), 5);
+        assert_eq!(slow.approx_port_count, Some(8));
+        assert_eq!(slow.port_count_label(), "~8");
+    }
+
+    #[test]
+    fn test_overflow_counted_per_window() {
+        // Depasirile acumulate pe fereastra Slow nu intra in fereastra Fast.
+        let mut cfg = test_config();
+        cfg.max_ports_per_ip = 5;
+        cfg.fast_scan.port_threshold = 6;
+        let detector = Detector::new(cfg);
+        let ip: IpAddr = "10.0.0.8".parse().unwrap();
+
+        // 5 porturi memorate + 10 peste plafon, apoi 20s de liniste (alerta
+        // Fast de pe parcurs si cooldown-ul ei nu conteaza aici).
+        for port in 1..=5 {
+            detector.process_event(&make_event("10.0.0.8", port));
+        }
+        for port in 100..110 {
+            detector.process_event(&make_event("10.0.0.8", port));
+        }
+        for hit in detector.tracker.load().port_hits.get_mut(&ip).unwrap().iter_mut() {
+            hit.seen_at -= Duration::from_secs(20);
+        }
+        for (at, _) in detector.tracker.load().port_overflow.get_mut(&(ip, "tcp")).unwrap().buckets.iter_mut() {
+            *at -= Duration::from_secs(20);
+        }
+        detector.fast_cooldowns.clear();
+        let now = Instant::now();
+        assert_eq!(overflow_in_window(&detector.tracker.load().port_overflow, ip, Duration::from_secs(60), now, None), 10);
+        assert_eq!(overflow_in_window(&detector.tracker.load().port_overflow, ip, Duration::from_secs(10), now, None), 0);
+        // Depasirile sunt ale protocolului portului: "separate" pe UDP nu le vede.
+        assert_eq!(overflow_in_window(&detector.tracker.load().port_overflow, ip, Duration::from_secs(60), now, Some("tcp")), 10);
+        assert_eq!(overflow_in_window(&detector.tracker.load().port_overflow, ip, Duration::from_secs(60), now, Some("udp")), 0);
+
+        // Scanarea lenta continua: un port memorat si unul nou — 2 in fereastra Fast.
+        let mut alerts = detector.process_event(&make_event("10.0.0.8", 1));
+        alerts.extend(detector.process_event(&make_event("10.0.0.8", 200)));
+        assert!(alerts.iter().all(|a| !matches!(a.scan_type, ScanType::Fast)));
+    }
+
+    #[test]
+    fn test_top_talkers_merges_drop_and_accept() {
+        let detector = Detector::new(test_config());
+        detector.process_event(&make_event("10.0.0.1", 22));
+        detector.process_event(&make_event("10.0.0.2", 22));
+        detector.process_event(&make_accept_event("10.0.0.2", 80));
+
+        let top = detector.top_talkers(1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].ip, "10.0.0.2".parse::<IpAddr>().unwrap());
+        assert_eq!((top[0].dropped, top[0].accepted), (1, 1));
+        assert_eq!(detector.top_talkers(10).len(), 2);
+    }
+
+    #[test]
+    fn test_scanners_over_threshold() {
+        // Prag Fast = 3: sursa cu 3 porturi este peste prag, cea cu 1 nu.
+        let detector = Detector::new(test_config());
+        for port in 1..=4u16 {
+            detector.process_event(&make_event("10.0.0.1", port));
         }
+        detector.process_event(&make_event("10.0.0.2", 22));
+
+        let scanners = detector.scanners_over_threshold(Instant::now());
+        assert_eq!(scanners, vec![("10.0.0.1".parse::<IpAddr>().unwrap(), 4)]);
     }
 
     #[test]
@@ -1978,6 +5805,176 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_slow_sweep_same_port_across_hosts() {
+        let mut cfg = test_config();
+        // Fast Scan inalt: sweep-ul nu trebuie confundat cu o scanare de porturi.
+        cfg.fast_scan.port_threshold = 100;
+        cfg.slow_sweep.enabled = true;
+        cfg.slow_sweep.unique_dest_threshold = 3;
+        let probe = |src: &str, dst: &str, port: u16| LogEvent {
+            dest_ip: Some(dst.parse().unwrap()),
+            ..make_event(src, port)
+        };
+        let sweeps = |alerts: Vec<Alert>| -> Vec<Alert> {
+            alerts.into_iter().filter(|a| matches!(a.scan_type, ScanType::SlowSweep)).collect()
+        };
+
+        let detector = Detector::new(cfg.clone());
+        assert!(sweeps(detector.process_event(&probe("203.0.113.9", "10.0.0.1", 445))).is_empty());
+        // Aceeasi destinatie revizitata si alt port nu conteaza.
+        assert!(sweeps(detector.process_event(&probe("203.0.113.9", "10.0.0.1", 445))).is_empty());
+        assert!(sweeps(detector.process_event(&probe("203.0.113.9", "10.0.0.2", 139))).is_empty());
+        assert!(sweeps(detector.process_event(&probe("203.0.113.9", "10.0.0.2", 445))).is_empty());
+        let alerts = sweeps(detector.process_event(&probe("203.0.113.9", "10.0.0.3", 445)));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].unique_ports, vec![445]);
+        assert_eq!(alerts[0].unique_dests.len(), 3);
+        assert_eq!(alerts[0].dest_ip, Some("10.0.0.3".parse().unwrap()));
+
+        // Scanner autorizat: fara alerta.
+        cfg.exceptions.authorized_scanners = vec!["203.0.113.9".to_string()];
+        let detector = Detector::new(cfg);
+        for dst in ["10.0.0.1", "10.0.0.2", "10.0.0.3"] {
+            assert!(sweeps(detector.process_event(&probe("203.0.113.9", dst, 445))).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_detection_context_on_threshold_alerts() {
+        let fast = |detector: &Detector| -> Alert {
+            for port in [22, 80] {
+                assert!(detector.process_event(&make_event("192.0.2.50", port)).is_empty());
+            }
+            let mut alerts = detector.process_event(&make_event("192.0.2.50", 443));
+            assert_eq!(alerts.len(), 1);
+            alerts.remove(0)
+        };
+
+        // Implicit oprit: alertele raman ca inainte.
+        let off = Detector::new(test_config());
+        assert_eq!(fast(&off).detection_context, None);
+
+        let on = Detector::new(test_config());
+        on.set_detection_context(true);
+        let alert = fast(&on);
+        let ctx = alert.detection_context.expect("detection_context activat");
+        assert_eq!(ctx.rule, "fast_scan");
+        assert_eq!((ctx.observed, ctx.threshold, ctx.unit), (3, 3, "porturi"));
+        assert_eq!(ctx.window_secs, 10);
+        assert_eq!(ctx.weighted_count, None);
+        assert_eq!(ctx.time_to_threshold_ms, alert.time_to_threshold_ms);
+        let json = serde_json::to_value(&ctx).unwrap();
+        assert!(json.get("weighted_count").is_none());
+    }
+
+    #[test]
+    fn test_source_port_exhaustion_lowers_fast_threshold() {
+        let mut cfg = test_config();
+        cfg.source_port_exhaustion.enabled = true;
+        cfg.source_port_exhaustion.min_source_ports = 4;
+        cfg.source_port_exhaustion.min_density = 0.5;
+        let event = |ip: &str, source_port: u16, port: u16| LogEvent {
+            source_port: Some(source_port),
+            ..make_event(ip, port)
+        };
+
+        // Porturi sursa contigue: pragul 3 coboara la ceil(3 × 0.5) = 2.
+        let detector = Detector::new(cfg.clone());
+        for sport in 40000..40003 {
+            assert!(detector.process_event(&event("10.0.0.7", sport, 80)).is_empty());
+        }
+        let alerts = detector.process_event(&event("10.0.0.7", 40003, 443));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Fast));
+        assert_eq!(alerts[0].unique_ports, vec![80, 443]);
+        assert_eq!(
+            alerts[0].source_port_exhaustion,
+            Some(SourcePortRun { ports: 4, low: 40000, high: 40003 })
+        );
+
+        // Porturi sursa imprastiate (densitate mica): pragul ramane 3.
+        for sport in [1030, 20000, 45000] {
+            assert!(detector.process_event(&event("10.0.0.8", sport, 80)).is_empty());
+        }
+        assert!(detector.process_event(&event("10.0.0.8", 61000, 443)).is_empty());
+
+        // Dezactivat: acelasi tipar contiguu nu schimba nimic.
+        let detector = Detector::new(test_config());
+        for sport in 40000..40003 {
+            assert!(detector.process_event(&event("10.0.0.7", sport, 80)).is_empty());
+        }
+        assert!(detector.process_event(&event("10.0.0.7", 40003, 443)).is_empty());
+    }
+
+    #[test]
+    fn test_source_ports_incremental_window() {
+        let t0 = Instant::now();
+        let at = |secs| t0 + Duration::from_secs(secs);
+        let mut ports = SourcePorts::default();
+        ports.record(40000, at(0), 100);
+        ports.record(40005, at(1), 100);
+        ports.record(40002, at(2), 100);
+        // Port revazut: numarat o singura data, cu momentul nou.
+        ports.record(40000, at(3), 100);
+        assert_eq!(ports.run(), Some(SourcePortRun { ports: 3, low: 40000, high: 40005 }));
+
+        // Fereastra de 2s la t=4: 40005 (t=1) iese; 40000 ramane (revazut la t=3).
+        assert!(!ports.expire(Duration::from_secs(2), at(4)));
+        assert_eq!(ports.run(), Some(SourcePortRun { ports: 2, low: 40000, high: 40002 }));
+
+        // Plafonul max_hits pastreaza doar cele mai noi sosiri.
+        ports.record(50000, at(4), 2);
+        assert_eq!(ports.run(), Some(SourcePortRun { ports: 2, low: 40000, high: 50000 }));
+
+        assert!(ports.expire(Duration::from_secs(2), at(10)));
+        assert_eq!(ports.run(), None);
+    }
+
+    #[test]
+    fn test_slow_scan_carry_over_previous_window() {
+        // Sursa lenta: 2 porturi in fereastra anterioara (60-120s in urma),
+        // 2 in cea curenta — prag 3, niciuna nu il atinge singura.
+        let ip: IpAddr = "192.168.1.1".parse().unwrap();
+        let seed = |detector: &Detector| {
+            let now = Instant::now();
+            let hit = |port, secs| PortHit {
+                port,
+                seen_at: now - Duration::from_secs(secs),
+                empty_probe: false,
+                protocol: "tcp",
+            };
+            // Portul 2 apare in ambele ferestre: numarat doar in cea curenta.
+            detector
+                .tracker
+                .load()
+                .port_hits
+                .insert(ip, vec![hit(1, 100), hit(2, 90), hit(9, 200), hit(2, 20)]);
+        };
+
+        let plain = Detector::new(slow_test_config());
+        seed(&plain);
+        assert!(plain.process_event(&make_event("192.168.1.1", 3)).is_empty());
+
+        // carry_over = 0.5: 2 (curente) + 0.5 × 1 (portul 1) = 2.5 < 3.
+        let mut cfg = slow_test_config();
+        cfg.slow_scan.carry_over = 0.5;
+        let detector = Detector::new(cfg.clone());
+        seed(&detector);
+        assert!(detector.process_event(&make_event("192.168.1.1", 3)).is_empty());
+
+        // carry_over = 1.0: 2 + 1 = 3 → alerta, cu portul creditat si scorul.
+        cfg.slow_scan.carry_over = 1.0;
+        let detector = Detector::new(cfg);
+        seed(&detector);
+        let alerts = detector.process_event(&make_event("192.168.1.1", 3));
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::Slow));
+        assert_eq!(alerts[0].unique_ports, vec![2, 3]);
+        assert_eq!(alerts[0].carried_ports, vec![1], "portul 9 este peste 2× fereastra");
+        assert_eq!(alerts[0].weighted_score, Some(3.0));
+    }
+
     // =========================================================================
     // Teste Whitelist (#12)
     // =========================================================================
@@ -2183,6 +6180,15 @@ mod tests {
         DetectionConfig {
             alert_cooldown_secs: 5,
             max_hits_per_ip: 1_000,
+            compact_interval_secs: 0,
+            cleanup_every_n_packets: 0,
+            port_weights: Default::default(),
+            rule_precedence: "none".to_string(),
+            per_protocol_tracking: "combined".to_string(),
+            inactivity_reset_secs: 0,
+            confirm_windows: 1,
+            confirm_secs: 600,
+            max_ports_per_ip: 0,
             max_tracked_ips: 10_000,
             whitelist: Vec::new(),
             exceptions: Default::default(),
@@ -2193,6 +6199,8 @@ mod tests {
             slow_scan: SlowScanConfig {
                 port_threshold: 200,
                 time_window_mins: 1,
+                carry_over: 0.0,
+                promote_to_fast: false,
             },
             accept_scan: AcceptScanConfig {
                 port_threshold: 100,
@@ -2208,6 +6216,32 @@ mod tests {
                 unique_sources_threshold: 3,
                 time_window_secs: 10,
             },
+            coordinated_subnet: CoordinatedSubnetConfig {
+                enabled: false,
+                prefix_len_v4: 24,
+                prefix_len_v6: 64,
+                min_sources: 3,
+                port_threshold: 20,
+                time_window_secs: 300,
+            },
+            sweep: SweepConfig {
+                min_distinct_targets: 0,
+            },
+            flood: FloodConfig {
+                enabled: false,
+                packets_per_sec_threshold: 1000,
+                time_window_secs: 10,
+            },
+            slow_sweep: SlowSweepConfig {
+                enabled: false,
+                unique_dest_threshold: 20,
+                time_window_secs: 3600,
+            },
+            malformed_input: MalformedInputConfig {
+                enabled: false,
+                threshold: 100,
+                time_window_secs: 60,
+            },
             dynamic_threshold: DynamicThresholdConfig {
                 enabled: false,
                 ewma_alpha: 0.1,
@@ -2223,6 +6257,37 @@ mod tests {
                 cv_threshold: 0.30,
                 min_interval_secs: 1,
                 max_interval_secs: 60,
+                jitter_percent: 0.0,
+                min_consistent_intervals: 6,
+            },
+            saturation_alert: SaturationAlertConfig {
+                enabled: false,
+                evictions_threshold: 3,
+                time_window_secs: 60,
+            },
+            early_warning: EarlyWarningConfig {
+                enabled: false,
+                threshold_fraction: 0.5,
+                cooldown_secs: 300,
+                assets_only: false,
+            },
+            no_traffic_alert_secs: 0,
+            scan_end_silence_secs: 0,
+            rules_dir: String::new(),
+            trusted_signatures: TrustedSignaturesConfig {
+                patterns: Vec::new(),
+                suppress_secs: 3600,
+            },
+            empty_udp_probes: EmptyUdpProbesConfig {
+                enabled: false,
+                weight: 2.0,
+            },
+            source_port_exhaustion: SourcePortExhaustionConfig {
+                enabled: false,
+                min_source_ports: 100,
+                min_density: 0.4,
+                time_window_secs: 10,
+                threshold_factor: 0.5,
             },
         }
     }
@@ -2235,6 +6300,9 @@ mod tests {
             dest_port: port,
             protocol: "tcp".to_string(),
             action: "drop".to_string(),
+            packet_bytes: None,
+            interface: None,
+            source_port: None,
             raw_log: String::new(),
         }
     }
@@ -2361,6 +6429,9 @@ mod tests {
             dest_port: 80,
             protocol: "tcp".to_string(),
             action: "accept".to_string(),
+            packet_bytes: None,
+            interface: None,
+            source_port: None,
             raw_log: String::new(),
         });
         // Sursa 3: drop → ar trebui sa declanseze alerta
@@ -2398,6 +6469,172 @@ mod tests {
         assert!(dist[0].unique_ports.contains(&443));
     }
 
+    #[test]
+    fn test_coordinated_subnet_aggregates_sources_by_prefix() {
+        // Trei surse din 203.0.113.0/24, fiecare sub pragul Fast Scan (3 porturi
+        // fiecare, prag 10), depasesc impreuna 8 porturi unice.
+        let mut cfg = test_config();
+        cfg.fast_scan.port_threshold = 10;
+        cfg.coordinated_subnet.enabled = true;
+        cfg.coordinated_subnet.port_threshold = 8;
+        let detector = Detector::new(cfg);
+
+        let mut coordinated = Vec::new();
+        for (i, src) in ["203.0.113.5", "203.0.113.77", "203.0.113.200"].iter().enumerate() {
+            for p in 0..3u16 {
+                let alerts = detector.process_event(&make_event(src, 1000 + i as u16 * 10 + p));
+                assert!(!alerts.iter().any(|a| matches!(a.scan_type, ScanType::Fast)));
+                coordinated.extend(
+                    alerts
+                        .into_iter()
+                        .filter(|a| matches!(a.scan_type, ScanType::CoordinatedSubnet)),
+                );
+            }
+        }
+        assert_eq!(coordinated.len(), 1, "o singura alerta (cooldown pe bloc)");
+        assert_eq!(coordinated[0].source_prefix.as_deref(), Some("203.0.113.0/24"));
+        assert_eq!(coordinated[0].unique_sources.len(), 3);
+        assert!(coordinated[0].unique_ports.len() >= 8);
+
+        // Alt bloc /24: agregat independent, doua surse nu ajung la min_sources.
+        for src in ["198.51.100.1", "198.51.100.2"] {
+            for p in 0..5u16 {
+                let alerts = detector.process_event(&make_event(src, 2000 + p));
+                assert!(!alerts.iter().any(|a| matches!(a.scan_type, ScanType::CoordinatedSubnet)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sweep_min_distinct_targets_blocks_single_host_subnet_alert() {
+        // Trei clienti din acelasi /24 catre UN server: scanare verticala.
+        let mut cfg = test_config();
+        cfg.fast_scan.port_threshold = 10;
+        cfg.coordinated_subnet.enabled = true;
+        cfg.coordinated_subnet.port_threshold = 8;
+        cfg.sweep.min_distinct_targets = 2;
+        let detector = Detector::new(cfg);
+
+        let coordinated = |alerts: Vec<Alert>| {
+            alerts
+                .into_iter()
+                .filter(|a| matches!(a.scan_type, ScanType::CoordinatedSubnet))
+                .collect::<Vec<_>>()
+        };
+        for (i, src) in ["203.0.113.5", "203.0.113.77", "203.0.113.200"].iter().enumerate() {
+            for p in 0..3u16 {
+                let alerts = detector.process_event(&make_event(src, 1000 + i as u16 * 10 + p));
+                assert!(coordinated(alerts).is_empty(), "o singura tinta — fara alerta");
+            }
+        }
+
+        // O a doua tinta indeplineste cerinta: alerta, cu tintele in unique_dests.
+        let second = LogEvent {
+            dest_ip: Some("10.0.0.2".parse().unwrap()),
+            ..make_event("203.0.113.5", 1100)
+        };
+        let alerts = coordinated(detector.process_event(&second));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].unique_dests.len(), 2);
+    }
+
+    #[test]
+    fn test_flood_alerts_on_packet_rate_regardless_of_ports() {
+        // 1 pachet/s mediat pe 30s = 30 pachete; Fast Scan nu poate declansa
+        // (doua porturi, prag 10).
+        let mut cfg = test_config();
+        cfg.flood.enabled = true;
+        cfg.flood.packets_per_sec_threshold = 1;
+        cfg.flood.time_window_secs = 30;
+        let detector = Detector::new(cfg);
+
+        let floods = |alerts: Vec<Alert>| {
+            alerts
+                .into_iter()
+                .filter(|a| matches!(a.scan_type, ScanType::Flood))
+                .collect::<Vec<_>>()
+        };
+        for i in 0..29u16 {
+            let alerts = detector.process_event(&make_event("198.51.100.9", 80 + i % 2));
+            assert!(alerts.is_empty(), "sub prag: nicio alerta (pachetul {})", i + 1);
+        }
+        let alerts = floods(detector.process_event(&make_event("198.51.100.9", 80)));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].event_count, Some(30));
+        // Toate pachetele in mai putin de o secunda: rata raportata pe minim 1s.
+        assert_eq!(alerts[0].packet_rate, Some(30.0));
+
+        // Cooldown: pachetele urmatoare nu re-alerteaza; alta sursa are contor propriu.
+        assert!(floods(detector.process_event(&make_event("198.51.100.9", 80))).is_empty());
+        assert!(floods(detector.process_event(&make_event("198.51.100.10", 80))).is_empty());
+    }
+
+    #[test]
+    fn test_malformed_datagrams_never_panic_and_alert() {
+        use crate::parser::{create_parser, PARSER_NAMES};
+
+        // Datagrame ostile / defecte: goale, doar spatii, octeti aleatori
+        // (xorshift determinist), UTF-8 invalid, linii trunchiate.
+        let mut datagrams: Vec<Vec<u8>> = vec![
+            Vec::new(),
+            b" \r\n\t\n".to_vec(),
+            vec![0xff, 0xfe, 0x00, 0xc3],
+            b"CEF:0|".to_vec(),
+            b"action=drop src=".to_vec(),
+            b"CEF:0|Check Point|VPN-1|R81|x|Drop|5|src=999.1.1.1 dpt=99999".to_vec(),
+        ];
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        for len in [1usize, 7, 64, 512, 1500, 8192] {
+            let bytes = (0..len)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state as u8
+                })
+                .collect();
+            datagrams.push(bytes);
+        }
+
+        for name in PARSER_NAMES {
+            let parser = create_parser(name).unwrap();
+            let mut cfg = test_config();
+            cfg.malformed_input.enabled = true;
+            cfg.malformed_input.threshold = datagrams.len();
+            let detector = Detector::new(cfg);
+            let sender: IpAddr = "192.0.2.250".parse().unwrap();
+
+            // Aceeasi cale ca bucla principala: text lossy, linie cu linie.
+            let mut alerts = Vec::new();
+            for datagram in &datagrams {
+                let text = String::from_utf8_lossy(datagram);
+                let parsed = text
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .filter_map(|l| parser.parse(l))
+                    .count();
+                assert_eq!(parsed, 0, "parser {}: datagrama malformata acceptata", name);
+                alerts.extend(detector.record_malformed(sender, Instant::now()));
+            }
+            assert_eq!(alerts.len(), 1, "parser {}: o alerta la prag", name);
+            let alert = &alerts[0];
+            assert!(matches!(alert.scan_type, ScanType::MalformedInput));
+            assert_eq!(alert.source_ip, sender);
+            assert_eq!(alert.event_count, Some(datagrams.len()));
+
+            // Cooldown: volumul continuat nu re-alerteaza; detectorul ramane functional.
+            assert!(detector.record_malformed(sender, Instant::now()).is_none());
+            detector.process_event(&make_event("10.0.0.1", 22));
+        }
+
+        // Dezactivat (implicit): datagramele sunt doar numarate de bucla principala.
+        let detector = Detector::new(test_config());
+        for _ in 0..1000 {
+            assert!(detector.record_malformed("192.0.2.250".parse().unwrap(), Instant::now()).is_none());
+        }
+    }
+
     #[test]
     fn test_lateral_movement_drop_events_ignored() {
         // Evenimentele "drop" nu declanseaza Lateral Movement (doar "accept").
@@ -2411,6 +6648,9 @@ mod tests {
                 dest_port: 445,
                 protocol: "tcp".to_string(),
                 action: "drop".to_string(),
+                packet_bytes: None,
+                interface: None,
+                source_port: None,
                 raw_log: String::new(),
             });
             let lateral: Vec<_> = alerts
@@ -2444,6 +6684,8 @@ mod tests {
             cv_threshold: 0.30,
             min_interval_secs: 1,
             max_interval_secs: 60,
+            jitter_percent: 0.0,
+            min_consistent_intervals: 6,
         };
         cfg
     }
@@ -2455,6 +6697,9 @@ mod tests {
             dest_port: port,
             protocol: "tcp".to_string(),
             action: "accept".to_string(),
+            packet_bytes: None,
+            interface: None,
+            source_port: None,
             raw_log: String::new(),
         }
     }
@@ -2575,6 +6820,52 @@ mod tests {
         assert!(a.cv.unwrap() < 0.30, "CV ar trebui sub prag, primit {:?}", a.cv);
     }
 
+    #[test]
+    fn test_beaconing_jitter_tolerates_missed_beacon() {
+        // Beacon la ~10s cu jitter ±1s si un callback ratat (interval 30s):
+        // CV-ul depaseste 0.30, dar 8/9 intervale sunt in toleranta de 20%.
+        let intervals = [10u64, 11, 9, 30, 10, 9, 11, 10];
+        let inject = |detector: &Detector, key| {
+            let now = Instant::now();
+            // Ultimul hit injectat la now − 10s; evenimentul live vine la `now`.
+            let mut back = 10u64;
+            let mut hits = vec![BeaconHit { seen_at: now - Duration::from_secs(back) }];
+            for iv in intervals.iter().rev() {
+                back += iv;
+                hits.insert(0, BeaconHit { seen_at: now - Duration::from_secs(back) });
+            }
+            detector.beacon_hits.insert(key, hits);
+        };
+        let src: IpAddr = "10.0.1.5".parse().unwrap();
+        let dst: IpAddr = "10.0.1.50".parse().unwrap();
+        let key = (src, dst, 443);
+        let beacon_alerts = |detector: &Detector| {
+            detector
+                .process_event(&make_beacon_accept("10.0.1.5", "10.0.1.50", 443))
+                .into_iter()
+                .filter(|a| matches!(a.scan_type, ScanType::Beaconing))
+                .count()
+        };
+
+        let mut cfg = beaconing_config();
+        cfg.beaconing.time_window_secs = 300;
+        let detector = Detector::new(cfg.clone());
+        inject(&detector, key);
+        assert_eq!(beacon_alerts(&detector), 0, "doar CV: intervalul de 30s strica decizia");
+
+        cfg.beaconing.jitter_percent = 20.0;
+        cfg.beaconing.min_consistent_intervals = 8;
+        let detector = Detector::new(cfg.clone());
+        inject(&detector, key);
+        assert_eq!(beacon_alerts(&detector), 1);
+
+        // Toleranta prea stricta (5%): intervalele 9s/11s nu mai sunt consistente.
+        cfg.beaconing.jitter_percent = 5.0;
+        let detector = Detector::new(cfg);
+        inject(&detector, key);
+        assert_eq!(beacon_alerts(&detector), 0);
+    }
+
     #[test]
     fn test_beaconing_disabled_in_config() {
         let mut cfg = beaconing_config();
@@ -2713,6 +7004,9 @@ mod tests {
             dest_port: 443,
             protocol: "tcp".to_string(),
             action: "drop".to_string(),
+            packet_bytes: None,
+            interface: None,
+            source_port: None,
             raw_log: String::new(),
         };
         for _ in 0..10 {
@@ -2728,4 +7022,79 @@ mod tests {
             "Drop-urile nu trebuie sa populeze beacon_hits"
         );
     }
+
+    #[test]
+    fn test_no_traffic_alert_once_per_silence() {
+        let mut cfg = test_config();
+        cfg.no_traffic_alert_secs = 600;
+        let detector = Detector::new(cfg);
+        let sensor: IpAddr = "0.0.0.0".parse().unwrap();
+        let t0 = Instant::now();
+
+        detector.record_packet(t0);
+        assert!(detector.check_no_traffic(sensor, t0 + Duration::from_secs(599)).is_none());
+
+        let alert = detector
+            .check_no_traffic(sensor, t0 + Duration::from_secs(600))
+            .expect("linistea a atins pragul");
+        assert!(matches!(alert.scan_type, ScanType::NoTraffic));
+        assert_eq!(alert.silent_secs, Some(600));
+        assert_eq!(alert.source_ip, sensor);
+        // O singura alerta per perioada de liniste.
+        assert!(detector.check_no_traffic(sensor, t0 + Duration::from_secs(1200)).is_none());
+
+        // Primul pachet rearmeaza si raporteaza durata linistii.
+        let resumed = detector.record_packet(t0 + Duration::from_secs(1300)).unwrap();
+        assert_eq!(resumed.as_secs(), 1300);
+        assert!(detector.record_packet(t0 + Duration::from_secs(1301)).is_none());
+        assert!(detector.check_no_traffic(sensor, t0 + Duration::from_secs(1800)).is_none());
+        assert!(detector.check_no_traffic(sensor, t0 + Duration::from_secs(1901)).is_some());
+
+        // Dezactivat: niciodata.
+        let quiet = Detector::new(test_config());
+        assert!(quiet.check_no_traffic(sensor, t0 + Duration::from_secs(86400)).is_none());
+    }
+
+    #[test]
+    fn test_scan_end_after_silence_reports_episode_totals() {
+        let mut cfg = test_config();
+        cfg.scan_end_silence_secs = 30;
+        let detector = Detector::new(cfg);
+
+        let mut alerted = 0;
+        for port in 1..=5 {
+            alerted += detector.process_event(&make_event("192.168.1.100", port)).len();
+        }
+        assert!(alerted > 0, "Fast Scan deschide episodul");
+        // Evenimentele de dupa alerta prelungesc episodul si intra in totaluri.
+        detector.process_event(&make_event("192.168.1.100", 443));
+        // O sursa sub prag nu are episod.
+        detector.process_event(&make_event("192.168.1.200", 22));
+        let last = Instant::now();
+
+        assert!(detector.check_scan_ends(last + Duration::from_secs(29)).is_empty());
+        let ended = detector.check_scan_ends(last + Duration::from_secs(31));
+        assert_eq!(ended.len(), 1);
+        let alert = &ended[0];
+        assert!(matches!(alert.scan_type, ScanType::ScanEnded));
+        assert_eq!(alert.source_ip, "192.168.1.100".parse::<IpAddr>().unwrap());
+        assert_eq!(alert.unique_ports, vec![1, 2, 3, 4, 5, 443]);
+        assert_eq!(alert.dest_ip, Some("10.0.0.1".parse().unwrap()));
+        assert!(alert.silent_secs.unwrap() >= 30);
+        let episode = alert.scan_episode.as_ref().unwrap();
+        assert_eq!(episode.alerts, alerted);
+        assert!(episode.scan_types.contains("Fast Scan"));
+        assert!(!alert.policy_version.is_empty());
+
+        // Episodul inchis nu mai produce nimic; o scanare noua deschide altul.
+        assert!(detector.check_scan_ends(last + Duration::from_secs(120)).is_empty());
+
+        // Dezactivat: niciun episod urmarit.
+        let quiet = Detector::new(test_config());
+        for port in 1..=5 {
+            quiet.process_event(&make_event("192.168.1.100", port));
+        }
+        assert!(quiet.check_scan_ends(Instant::now() + Duration::from_secs(3600)).is_empty());
+    }
+
 }
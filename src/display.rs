@@ -30,17 +30,326 @@
 //
 // =============================================================================
 
-use crate::config::{AppConfig, SubnetEntry};
-use crate::detector::{Alert, ScanType};
+use crate::classify::AlertClass;
+use crate::config::{AppConfig, DisplayConfig, OutputConfig, SubnetEntry};
+use crate::detector::{Alert, AssetCriticality, EarlyWarning, ScanType, Suppression};
+use crate::metrics::{LatencyPercentiles, PacketReport};
 use crate::parser::LogEvent;
-use chrono::Local;
+use chrono::{DateTime, Local, SecondsFormat};
 use colored::*;
 use std::collections::HashMap;
 use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 /// Latimea separatorului orizontal (in caractere).
 const SEPARATOR_WIDTH: usize = 120;
 
+/// Simbolul care precede fiecare alerta in stream-ul de log.
+const ARROWS: &str = "▶▶▶";
+
+// ---------------------------------------------------------------------------
+// Tabela de stil (tema) — sursa UNICA pentru culori, simboluri si etichete
+//
+// `log_alert`, functiile `log_*` si `--legend` citesc toate din aceste tabele.
+// Schimbarea unei culori aici se reflecta automat si in legenda afisata
+// operatorilor — legenda nu poate ramane in urma fata de output-ul real.
+//
+// NOTA RUST: `colored::Color` este un enum simplu, deci poate aparea in
+// `const` si `static` (constructie la compile-time, zero cost la runtime).
+// ---------------------------------------------------------------------------
+
+/// Stilul vizual al unui tip de alerta.
+pub struct ScanStyle {
+    /// Culoarea separatoarelor, simbolului si valorilor evidentiate.
+    pub color: Color,
+    /// Culoarea textului din badge-ul " ALERT " (contrast pe fundal `color`).
+    pub badge_fg: Color,
+    /// Eticheta afisata in linia de alerta.
+    pub label: &'static str,
+    /// Severitatea CEF (paralel cu `Alerter::send_siem_alert`) si eticheta din email.
+    pub severity: (u8, &'static str),
+}
+
+/// Eticheta de severitate din email pentru o valoare CEF (0..=10).
+pub fn severity_label(severity: u8) -> &'static str {
+    match severity {
+        0..=4 => "SCAZUTA",
+        5 => "MEDIE-MICA",
+        6 => "MEDIE",
+        7 => "RIDICATA",
+        _ => "CRITICA",
+    }
+}
+
+/// Returneaza stilul pentru un tip de alerta.
+///
+/// NOTA RUST: match exhaustiv — o varianta noua de `ScanType` nu compileaza
+/// pana nu primeste un stil, deci legenda ramane completa.
+pub fn scan_style(scan_type: &ScanType) -> ScanStyle {
+    match scan_type {
+        ScanType::Fast => ScanStyle {
+            color: Color::Red,
+            badge_fg: Color::White,
+            label: "[FAST SCAN]",
+            severity: (7, "RIDICATA"),
+        },
+        ScanType::Slow => ScanStyle {
+            color: Color::Yellow,
+            badge_fg: Color::Black,
+            label: "[SLOW SCAN]",
+            severity: (6, "MEDIE"),
+        },
+        ScanType::AcceptScan => ScanStyle {
+            color: Color::Magenta,
+            badge_fg: Color::White,
+            label: "[ACCEPT SCAN]",
+            severity: (5, "MEDIE-MICA"),
+        },
+        ScanType::LateralMovement => ScanStyle {
+            color: Color::BrightRed,
+            badge_fg: Color::White,
+            label: "[LATERAL MOVEMENT]",
+            severity: (8, "CRITICA"),
+        },
+        ScanType::DistributedScan => ScanStyle {
+            color: Color::Cyan,
+            badge_fg: Color::Black,
+            label: "[DISTRIBUTED SCAN]",
+            severity: (7, "RIDICATA"),
+        },
+        ScanType::Beaconing => ScanStyle {
+            color: Color::Red,
+            badge_fg: Color::White,
+            label: "[BEACONING C2]",
+            severity: (9, "CRITICA"),
+        },
+        ScanType::Saturation => ScanStyle {
+            color: Color::BrightYellow,
+            badge_fg: Color::Black,
+            label: "[TRACKING SATURAT]",
+            severity: (8, "CRITICA"),
+        },
+        ScanType::CoordinatedSubnet => ScanStyle {
+            color: Color::BrightCyan,
+            badge_fg: Color::Black,
+            label: "[BLOC COORDONAT]",
+            severity: (7, "RIDICATA"),
+        },
+        ScanType::NoTraffic => ScanStyle {
+            color: Color::BrightBlack,
+            badge_fg: Color::White,
+            label: "[FARA TRAFIC]",
+            severity: (6, "MEDIE"),
+        },
+        ScanType::CustomRule => ScanStyle {
+            color: Color::Blue,
+            badge_fg: Color::White,
+            label: "[REGULA]",
+            severity: (6, "MEDIE"),
+        },
+        ScanType::Flood => ScanStyle {
+            color: Color::BrightMagenta,
+            badge_fg: Color::Black,
+            label: "[FLOOD]",
+            severity: (7, "RIDICATA"),
+        },
+        ScanType::SlowSweep => ScanStyle {
+            color: Color::BrightBlue,
+            badge_fg: Color::White,
+            label: "[SLOW SWEEP]",
+            severity: (6, "MEDIE"),
+        },
+        ScanType::MalformedInput => ScanStyle {
+            color: Color::BrightGreen,
+            badge_fg: Color::Black,
+            label: "[INTRARE MALFORMATA]",
+            severity: (5, "MEDIE-MICA"),
+        },
+        ScanType::ScanEnded => ScanStyle {
+            color: Color::Green,
+            badge_fg: Color::Black,
+            label: "[SCANARE INCHEIATA]",
+            severity: (3, "SCAZUTA"),
+        },
+    }
+}
+
+/// Toate tipurile de alerta, in ordinea afisarii in legenda.
+const ALL_SCAN_TYPES: [ScanType; 14] = [
+    ScanType::Fast,
+    ScanType::Slow,
+    ScanType::AcceptScan,
+    ScanType::LateralMovement,
+    ScanType::DistributedScan,
+    ScanType::Beaconing,
+    ScanType::Saturation,
+    ScanType::CoordinatedSubnet,
+    ScanType::NoTraffic,
+    ScanType::CustomRule,
+    ScanType::Flood,
+    ScanType::SlowSweep,
+    ScanType::MalformedInput,
+    ScanType::ScanEnded,
+];
+
+/// Stilul unui badge de nivel de log (" INFO ", " WARN " etc.).
+struct LevelStyle {
+    badge: &'static str,
+    bg: Color,
+    fg: Color,
+    meaning: &'static str,
+}
+
+impl LevelStyle {
+    fn render(&self) -> ColoredString {
+        self.badge.on_color(self.bg).color(self.fg).bold()
+    }
+}
+
+const LEVEL_INFO: LevelStyle = LevelStyle {
+    badge: " INFO ",
+    bg: Color::Green,
+    fg: Color::Black,
+    meaning: "Operatie normala",
+};
+const LEVEL_WARN: LevelStyle = LevelStyle {
+    badge: " WARN ",
+    bg: Color::Yellow,
+    fg: Color::Black,
+    meaning: "Situatie care merita atentie",
+};
+const LEVEL_ERR: LevelStyle = LevelStyle {
+    badge: " ERR  ",
+    bg: Color::Red,
+    fg: Color::White,
+    meaning: "Esec non-fatal (stderr)",
+};
+const LEVEL_SENT: LevelStyle = LevelStyle {
+    badge: " SENT ",
+    bg: Color::Green,
+    fg: Color::Black,
+    meaning: "Alerta transmisa catre SIEM/email",
+};
+const LEVEL_DROP: LevelStyle = LevelStyle {
+    badge: " DROP ",
+    bg: Color::Blue,
+    fg: Color::White,
+    meaning: "Conexiune blocata de firewall",
+};
+const LEVEL_ACCEPT: LevelStyle = LevelStyle {
+    badge: " ACCPT",
+    bg: Color::Green,
+    fg: Color::Black,
+    meaning: "Conexiune permisa de firewall",
+};
+const LEVEL_STAT: LevelStyle = LevelStyle {
+    badge: " STAT ",
+    bg: Color::Cyan,
+    fg: Color::Black,
+    meaning: "Statistici periodice",
+};
+const LEVEL_LOAD: LevelStyle = LevelStyle {
+    badge: " LOAD ",
+    bg: Color::Cyan,
+    fg: Color::Black,
+    meaning: "Configurare reincarcata (SIGHUP)",
+};
+const LEVEL_RATE: LevelStyle = LevelStyle {
+    badge: " RATE ",
+    bg: Color::Yellow,
+    fg: Color::Black,
+    meaning: "Pachete UDP dropate de rate limiter",
+};
+const LEVEL_RAW: LevelStyle = LevelStyle {
+    badge: " RAW  ",
+    bg: Color::Magenta,
+    fg: Color::White,
+    meaning: "Linie bruta primita (mod debug)",
+};
+const LEVEL_OK: LevelStyle = LevelStyle {
+    badge: "  OK  ",
+    bg: Color::Green,
+    fg: Color::Black,
+    meaning: "Parsare reusita (mod debug)",
+};
+const LEVEL_FAIL: LevelStyle = LevelStyle {
+    badge: " FAIL ",
+    bg: Color::Red,
+    fg: Color::White,
+    meaning: "Parsare esuata (mod debug)",
+};
+
+const LEVEL_EXPL: LevelStyle = LevelStyle {
+    badge: " EXPL ",
+    bg: Color::BrightBlack,
+    fg: Color::White,
+    meaning: "Alerta suprimata si motivul (--explain-suppressions)",
+};
+
+/// Toate nivelurile de log, in ordinea afisarii in legenda.
+const ALL_LEVELS: [&LevelStyle; 13] = [
+    &LEVEL_INFO,
+    &LEVEL_WARN,
+    &LEVEL_ERR,
+    &LEVEL_SENT,
+    &LEVEL_DROP,
+    &LEVEL_ACCEPT,
+    &LEVEL_STAT,
+    &LEVEL_LOAD,
+    &LEVEL_RATE,
+    &LEVEL_EXPL,
+    &LEVEL_RAW,
+    &LEVEL_OK,
+    &LEVEL_FAIL,
+];
+
+/// Afiseaza legenda culorilor si simbolurilor (`--legend`).
+///
+/// Citeste exclusiv din tabelele de stil de mai sus — afiseaza exact ce va
+/// vedea operatorul in stream-ul de log. Util si pentru a verifica rapid ca
+/// terminalul curent randeaza corect culorile ANSI.
+pub fn print_legend() {
+    println!();
+    println!("{}", "LEGENDA IDS-RS — tipuri de alerta".bold().cyan());
+    print_separator();
+    println!(
+        "{}",
+        format!(
+            "  {:<30} {:<22} {:<16} {}",
+            "Simbol / badge", "Eticheta", "Severitate CEF", "Tip alerta"
+        )
+        .dimmed()
+    );
+    for scan_type in &ALL_SCAN_TYPES {
+        let style = scan_style(scan_type);
+        // Padding calculat pe textul simplu — secventele ANSI nu ocupa coloane.
+        let sample = format!(
+            "{} {}",
+            ARROWS.color(style.color).bold(),
+            " ALERT ".on_color(style.color).color(style.badge_fg).bold()
+        );
+        let pad = 30usize.saturating_sub(ARROWS.chars().count() + 1 + " ALERT ".len());
+        println!(
+            "  {}{} {} {:<16} {}",
+            sample,
+            " ".repeat(pad),
+            format!("{:<22}", style.label).color(style.color).bold(),
+            format!("{} ({})", style.severity.0, style.severity.1),
+            scan_type
+        );
+    }
+
+    println!();
+    println!("{}", "LEGENDA IDS-RS — niveluri de log".bold().cyan());
+    print_separator();
+    for level in &ALL_LEVELS {
+        println!("  {}  {}", level.render(), level.meaning);
+    }
+    println!();
+}
+
 // ---------------------------------------------------------------------------
 // Banner-ul de pornire al aplicatiei
 //
@@ -57,7 +366,7 @@ pub fn print_banner(config: &AppConfig) {
     let inner_width = SEPARATOR_WIDTH - 2;
     let border = "═".repeat(inner_width);
 
-    println!();
+    blank_line();
     println!("{}", format!("╔{}╗", border).bold().cyan());
     println!(
         "{}",
@@ -82,9 +391,10 @@ pub fn print_banner(config: &AppConfig) {
 
     // Informatii de configurare - aliniate cu padding fix.
     let parser_line = format!(
-        "  Parser: {:<14} Listen: UDP/{}",
+        "  Parser: {:<14} Listen: UDP/{:<6} Politica: {}",
         config.network.parser.to_uppercase(),
-        config.network.listen_port
+        config.network.listen_port,
+        config.detection.policy_version()
     );
     println!(
         "{}",
@@ -121,6 +431,44 @@ pub fn print_banner(config: &AppConfig) {
         format!("║{:<width$}║", siem_line, width = inner_width).cyan()
     );
 
+    if config.alerting.gelf.enabled {
+        let gelf_line = format!(
+            "  GELF:   {}:{} (UDP, chunk {} B)",
+            config.alerting.gelf.host, config.alerting.gelf.port, config.alerting.gelf.chunk_size
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", gelf_line, width = inner_width).cyan()
+        );
+    }
+
+    if config.alerting.sentinel.enabled {
+        let sentinel_line = format!(
+            "  Sentinel: {} → {}_CL",
+            config.alerting.sentinel.workspace_id, config.alerting.sentinel.log_type
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", sentinel_line, width = inner_width).cyan()
+        );
+    }
+
+    // Instantele suplimentare din `alerting.sinks` (numele, in ordinea livrarii).
+    let extra_sinks: Vec<String> = config
+        .alerting
+        .sink_entries()
+        .into_iter()
+        .skip_while(|e| crate::config::ALERT_SINKS.contains(&e.name.as_str()))
+        .map(|e| format!("{} ({})", e.name, e.kind.type_name()))
+        .collect();
+    if !extra_sinks.is_empty() {
+        let sinks_line = format!("  Sinks:  {}", extra_sinks.join(", "));
+        println!(
+            "{}",
+            format!("║{:<width$}║", sinks_line, width = inner_width).cyan()
+        );
+    }
+
     let web_line = format!("  Web:    {}", web_label);
     println!(
         "{}",
@@ -145,18 +493,32 @@ pub fn print_banner(config: &AppConfig) {
     );
 
     // Praguri de detectie.
-    let thresh_line = format!(
-        "  Fast:  >={} ports/{}s       Slow: >={} ports/{}min",
+    // Ferestrele sunt afisate in aceeasi unitate (`display.window_unit`).
+    let fast_part = format!(
+        ">={} ports in {}",
         config.detection.fast_scan.port_threshold,
-        config.detection.fast_scan.time_window_secs,
+        format_window(Duration::from_secs(config.detection.fast_scan.time_window_secs))
+    );
+    let thresh_line = format!(
+        "  Fast:  {:<28} Slow: >={} ports in {}",
+        fast_part,
         config.detection.slow_scan.port_threshold,
-        config.detection.slow_scan.time_window_mins
+        format_window(Duration::from_secs(config.detection.slow_scan.time_window_mins * 60))
     );
     println!(
         "{}",
         format!("║{:<width$}║", thresh_line, width = inner_width).cyan()
     );
 
+    // Vizibilitate doar inbound — regulile dependente de ambele sensuri sunt oprite.
+    if config.network.inbound_only() {
+        let dir_line = "  Trafic: doar inbound — Accept Scan, Beaconing: N/A";
+        println!(
+            "{}",
+            format!("║{:<width$}║", dir_line, width = inner_width).yellow()
+        );
+    }
+
     // Whitelist — afisam numarul de intrari daca exista.
     let wl_count = config.detection.whitelist.len();
     if wl_count > 0 {
@@ -170,6 +532,20 @@ pub fn print_banner(config: &AppConfig) {
         );
     }
 
+    // Active clasificate ([assets]) — escaladeaza severitatea dupa tinta.
+    let critical_count = config.assets.critical_cidrs.len();
+    let high_count = config.assets.high_cidrs.len();
+    if critical_count + high_count > 0 {
+        let assets_line = format!(
+            "  Active: {} critice, {} importante (CIDR, escaladare severitate)",
+            critical_count, high_count
+        );
+        println!(
+            "{}",
+            format!("║{:<width$}║", assets_line, width = inner_width).cyan()
+        );
+    }
+
     // Hostnames — afisam numarul de mapping-uri IP→hostname daca exista.
     let hn_count = config.network.hostnames.len();
     if hn_count > 0 {
@@ -197,7 +573,25 @@ pub fn print_banner(config: &AppConfig) {
     }
 
     println!("{}", format!("╚{}╝", border).bold().cyan());
-    println!();
+    blank_line();
+}
+
+/// Formateaza o fereastra de detectie in unitatea configurata (`display.window_unit`).
+///
+/// Sursa unica pentru toate referintele catre ferestre vizibile operatorului
+/// (banner, SIEM, email) — Fast (secunde) si Slow (minute) apar in aceeasi unitate.
+/// Exemple: 300s → "300 secunde" sau "5 minute"; 10s in minute → "0.2 minute".
+pub fn format_window(window: Duration) -> String {
+    let secs = window.as_secs();
+    if WINDOW_IN_MINUTES.load(Ordering::Relaxed) {
+        if secs.is_multiple_of(60) {
+            format!("{} minute", secs / 60)
+        } else {
+            format!("{:.1} minute", secs as f64 / 60.0)
+        }
+    } else {
+        format!("{} secunde", secs)
+    }
 }
 
 /// Linie separatoare orizontala pentru lizibilitate vizuala.
@@ -217,35 +611,458 @@ pub fn print_separator() {
 //   - mai eficient (nu copiezi date) si mai flexibil (accepta &String, &str literal)
 // ---------------------------------------------------------------------------
 
+// ---------------------------------------------------------------------------
+// Coalescing mesaje repetate (`[display] repeat_window_secs`)
+//
+// Functiile log_* sunt libere (fara `&self`), apelate din orice task —
+// starea de coalescing este deci globala: setarile in atomice, contoarele
+// intr-un `Mutex<HashMap>` static. Cheia este textul exact al mesajului.
+//
+// Primul mesaj dintr-o fereastra este afisat imediat; repetarile identice
+// din aceeasi fereastra sunt doar numarate. Rezumatul "(repetat de N ori)"
+// apare fie la urmatoarea aparitie dupa expirarea ferestrei, fie la
+// `flush_repeated_logs()` (apelat periodic din main loop).
+//
+// NOTA RUST: `Mutex::new` este `const fn`, deci un `static Mutex<Option<..>>`
+// nu necesita lazy init — HashMap-ul este creat la primul mesaj.
+// ---------------------------------------------------------------------------
+
+/// Fereastra de coalescing in secunde. 0 = dezactivat.
+static REPEAT_WINDOW_SECS: AtomicU64 = AtomicU64::new(0);
+static COALESCE_INFO: AtomicBool = AtomicBool::new(false);
+static COALESCE_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// Limita de chei distincte urmarite — mesajele cu continut variabil
+/// (IP-uri, contoare) nu trebuie sa creasca map-ul nelimitat.
+const MAX_REPEAT_KEYS: usize = 1024;
+
+#[derive(Clone, Copy)]
+enum RepeatLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+struct RepeatState {
+    level: RepeatLevel,
+    window_start: Instant,
+    suppressed: u64,
+}
+
+static REPEATS: Mutex<Option<HashMap<String, RepeatState>>> = Mutex::new(None);
+
+/// Unitatea ferestrelor de detectie afisate: false = secunde, true = minute.
+static WINDOW_IN_MINUTES: AtomicBool = AtomicBool::new(false);
+
+/// Afiseaza `time_to_threshold_ms` in alertele de scanare porturi.
+static SHOW_TIME_TO_THRESHOLD: AtomicBool = AtomicBool::new(false);
+
+/// `display.format = "gelf"`: alertele sunt afisate ca linii JSON GELF 1.1.
+static GELF_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// `display.stats_sparkline`: histograma alertelor pe ore in linia STAT.
+static STATS_SPARKLINE: AtomicBool = AtomicBool::new(false);
+
+/// `display.compact_spacing`: fara liniile goale decorative din jurul
+/// banner-ului si al blocurilor de alerta.
+static COMPACT_SPACING: AtomicBool = AtomicBool::new(false);
+
+/// Linie goala decorativa — omisa cu `display.compact_spacing` (colectoarele
+/// stricte pe linii trateaza o linie goala ca inregistrare vida).
+fn blank_line() {
+    write_blank_line(&mut std::io::stdout().lock(), COMPACT_SPACING.load(Ordering::Relaxed));
+}
+
+fn write_blank_line(out: &mut impl std::io::Write, compact: bool) {
+    if !compact {
+        let _ = writeln!(out);
+    }
+}
+
+/// `output.colorize_ips`: culoare stabila per IP sursa (vezi `ip_color`).
+static COLORIZE_IPS: AtomicBool = AtomicBool::new(false);
+
+/// `output.port_ranges`: porturile vizate afisate ca intervale ("1-1024, 3389").
+static PORT_RANGES: AtomicBool = AtomicBool::new(false);
+
+/// `output.raw_ports`: campurile text SIEM (CEF, GELF) raman porturi individuale.
+static RAW_PORTS: AtomicBool = AtomicBool::new(false);
+
+/// Porturile pentru oameni (consola, email, rezumat) sunt intervale?
+pub fn port_ranges_enabled() -> bool {
+    PORT_RANGES.load(Ordering::Relaxed)
+}
+
+/// Porturile din campurile text SIEM (CEF `cs1`, GELF) sunt intervale?
+pub fn siem_port_ranges() -> bool {
+    PORT_RANGES.load(Ordering::Relaxed) && !RAW_PORTS.load(Ordering::Relaxed)
+}
+
+/// Precizia timestamp-urilor: 0 = secunde, 1 = milisecunde, 2 = microsecunde.
+/// Implicit milisecunde — ordinea alertelor din aceeasi secunda ramane clara.
+static TIMESTAMP_PRECISION: AtomicU8 = AtomicU8::new(1);
+
+/// Nivelul intern pentru `display.timestamp_precision` (vezi TIMESTAMP_PRECISION).
+fn precision_level(precision: &str) -> u8 {
+    match precision {
+        "seconds" => 0,
+        "micros" => 2,
+        _ => 1,
+    }
+}
+
+/// Aplica setarile de afisare din config (la pornire si la SIGHUP).
+pub fn configure(config: &DisplayConfig) {
+    TIMESTAMP_PRECISION.store(precision_level(&config.timestamp_precision), Ordering::Relaxed);
+    SHOW_TIME_TO_THRESHOLD.store(config.show_time_to_threshold, Ordering::Relaxed);
+    GELF_OUTPUT.store(config.format == "gelf", Ordering::Relaxed);
+    COMPACT_SPACING.store(config.compact_spacing, Ordering::Relaxed);
+    STATS_SPARKLINE.store(config.stats_sparkline, Ordering::Relaxed);
+    WINDOW_IN_MINUTES.store(config.window_unit == "minutes", Ordering::Relaxed);
+    REPEAT_WINDOW_SECS.store(config.repeat_window_secs, Ordering::Relaxed);
+    COALESCE_INFO.store(config.coalesce_info, Ordering::Relaxed);
+    COALESCE_ERRORS.store(config.coalesce_errors, Ordering::Relaxed);
+    if config.repeat_window_secs == 0 {
+        flush_all_repeated_logs();
+    }
+}
+
+/// Aplica setarile sectiunii `[output]` care privesc consola (pornire, SIGHUP).
+pub fn configure_output(config: &OutputConfig) {
+    COLORIZE_IPS.store(config.colorize_ips, Ordering::Relaxed);
+    PORT_RANGES.store(config.port_ranges, Ordering::Relaxed);
+    COLUMNAR.store(config.style == "columnar", Ordering::Relaxed);
+    RAW_PORTS.store(config.raw_ports, Ordering::Relaxed);
+    refresh_terminal_width();
+}
+
+/// Decide daca un mesaj trebuie afisat acum (true) sau doar numarat (false).
+fn should_print(level: RepeatLevel, message: &str) -> bool {
+    let window_secs = REPEAT_WINDOW_SECS.load(Ordering::Relaxed);
+    let enabled = window_secs > 0
+        && match level {
+            RepeatLevel::Info => COALESCE_INFO.load(Ordering::Relaxed),
+            RepeatLevel::Warn => true,
+            RepeatLevel::Error => COALESCE_ERRORS.load(Ordering::Relaxed),
+        };
+    if !enabled {
+        return true;
+    }
+
+    let window = Duration::from_secs(window_secs);
+    let now = Instant::now();
+    let mut guard = REPEATS.lock().unwrap_or_else(|e| e.into_inner());
+    let map = guard.get_or_insert_with(HashMap::new);
+
+    match map.get_mut(message) {
+        Some(state) if now.duration_since(state.window_start) < window => {
+            state.suppressed += 1;
+            false
+        }
+        Some(state) => {
+            // Fereastra expirata: rezumam repetarile si deschidem una noua.
+            if state.suppressed > 0 {
+                print_repeat_summary(level, message, state.suppressed, state.window_start);
+            }
+            state.window_start = now;
+            state.suppressed = 0;
+            true
+        }
+        None => {
+            if map.len() < MAX_REPEAT_KEYS {
+                map.insert(
+                    message.to_string(),
+                    RepeatState {
+                        level,
+                        window_start: now,
+                        suppressed: 0,
+                    },
+                );
+            }
+            true
+        }
+    }
+}
+
+/// Afiseaza rezumatele pentru ferestrele expirate si elibereaza cheile lor.
+///
+/// Apelat periodic din main loop — altfel ultima rafala dintr-o furtuna de
+/// erori nu ar fi raportata niciodata (nu mai urmeaza un mesaj identic).
+pub fn flush_repeated_logs() {
+    let window = Duration::from_secs(REPEAT_WINDOW_SECS.load(Ordering::Relaxed));
+    let mut guard = REPEATS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(map) = guard.as_mut() {
+        map.retain(|message, state| {
+            if state.window_start.elapsed() < window {
+                return true;
+            }
+            if state.suppressed > 0 {
+                print_repeat_summary(state.level, message, state.suppressed, state.window_start);
+            }
+            false
+        });
+    }
+}
+
+/// Goleste complet starea de coalescing (la dezactivare prin SIGHUP).
+fn flush_all_repeated_logs() {
+    let mut guard = REPEATS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(map) = guard.take() {
+        for (message, state) in map {
+            if state.suppressed > 0 {
+                print_repeat_summary(state.level, &message, state.suppressed, state.window_start);
+            }
+        }
+    }
+}
+
+/// Nota de rezumat atasata mesajului original.
+fn repeat_note(count: u64, elapsed_secs: u64) -> String {
+    format!("(repetat de {} ori in ultimele {}s)", count, elapsed_secs)
+}
+
+/// Linia de rezumat: mesajul original + "(repetat de N ori in ultimele Xs)".
+fn print_repeat_summary(level: RepeatLevel, message: &str, count: u64, since: Instant) {
+    let ts = timestamp();
+    if columnar() {
+        let row = Row {
+            count: format!("x{}", count + 1),
+            ..Row::message(format!("{} (repetat in ultimele {}s)", message, since.elapsed().as_secs()))
+        };
+        match level {
+            RepeatLevel::Info => println!("{}", format_row(&ts, LEVEL_INFO.render(), row, Color::White)),
+            RepeatLevel::Warn => println!("{}", format_row(&ts, LEVEL_WARN.render(), row, Color::Yellow)),
+            RepeatLevel::Error => eprintln!("{}", format_row(&ts, LEVEL_ERR.render(), row, Color::Red)),
+        }
+        return;
+    }
+    let note = repeat_note(count, since.elapsed().as_secs()).dimmed();
+    match level {
+        RepeatLevel::Info => println!(
+            "{} {} {} {}",
+            ts.bold().white(),
+            LEVEL_INFO.render(),
+            message.white(),
+            note
+        ),
+        RepeatLevel::Warn => println!(
+            "{} {} {} {}",
+            ts.bold().white(),
+            LEVEL_WARN.render(),
+            message.yellow(),
+            note
+        ),
+        RepeatLevel::Error => eprintln!(
+            "{} {} {} {}",
+            ts.bold().white(),
+            LEVEL_ERR.render(),
+            message.red(),
+            note
+        ),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Stil columnar (`output.style = "columnar"`)
+//
+// Aceleasi coloane pentru toate functiile log_*, o linie per mesaj:
+//
+//   [..14:30:12.345]  INFO  |                 |                    |         | Ascult pe UDP 0.0.0.0:5555
+//   [..14:30:14.101]  ALERT | 203.0.113.7     | FAST SCAN          |      15 | 1, 2, 3, 4, 5
+//
+// Latimi fixe: IP 15 (o adresa IPv4 — adresele mai lungi sunt scurtate la
+// mijloc cu "…"), tip 18, numar 7. Detaliile umplu restul liniei si sunt
+// trunchiate la latimea terminalului doar cand stdout este un terminal — in
+// fisiere, pipe-uri si journald linia ramane intreaga. Alinierea se calculeaza
+// pe textul simplu; culorile se aplica dupa padding.
+// ---------------------------------------------------------------------------
+
+/// `output.style = "columnar"`.
+static COLUMNAR: AtomicBool = AtomicBool::new(false);
+
+const COL_IP: usize = 15;
+const COL_TYPE: usize = 18;
+const COL_COUNT: usize = 7;
+/// Latimea minima a detaliilor, si pe un terminal foarte ingust.
+const COL_DETAIL_MIN: usize = 20;
+
+fn columnar() -> bool {
+    COLUMNAR.load(Ordering::Relaxed)
+}
+
+/// Coloanele unei linii; campurile lipsa raman goale.
+#[derive(Default)]
+struct Row {
+    ip: Option<IpAddr>,
+    kind: String,
+    count: String,
+    detail: String,
+}
+
+impl Row {
+    /// Linie fara IP / tip / numar — doar mesajul, in coloana de detalii.
+    fn message(detail: impl Into<String>) -> Self {
+        Row {
+            detail: detail.into(),
+            ..Row::default()
+        }
+    }
+}
+
+/// Formateaza o linie columnara. `badge` are 6 caractere (ca toate
+/// nivelurile); `color` = culoarea detaliilor.
+fn format_row(ts: &str, badge: ColoredString, row: Row, color: Color) -> String {
+    let ip_text = row
+        .ip
+        .map(|ip| elide_middle(&ip.to_string(), COL_IP))
+        .unwrap_or_default();
+    let ip_cell = format!("{:<w$}", ip_text, w = COL_IP);
+    let ip_cell = match row.ip {
+        Some(ip) => ip_cell.color(ip_color(&ip).unwrap_or(Color::BrightBlue)),
+        None => ip_cell.normal(),
+    };
+    let kind = format!("{:<w$}", elide_end(&row.kind, COL_TYPE), w = COL_TYPE);
+    let count = format!("{:>w$}", elide_end(&row.count, COL_COUNT), w = COL_COUNT);
+    // timestamp, spatiu, badge (6), patru separatoare " | " si coloanele fixe.
+    let fixed = ts.chars().count() + 1 + 6 + 4 * 3 + COL_IP + COL_TYPE + COL_COUNT;
+    let detail = match terminal_width() {
+        Some(width) => elide_end(&row.detail, width.saturating_sub(fixed).max(COL_DETAIL_MIN)),
+        None => row.detail,
+    };
+    let sep = " | ".dimmed();
+    format!(
+        "{} {}{}{}{}{}{}{}{}{}",
+        ts.dimmed(),
+        badge,
+        sep,
+        ip_cell,
+        sep,
+        kind.bold(),
+        sep,
+        count.white().bold(),
+        sep,
+        detail.color(color)
+    )
+}
+
+/// Scurteaza `text` la `width` caractere, cu "…" la final.
+fn elide_end(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut out: String = text.chars().take(width.saturating_sub(1)).collect();
+    out.push('…');
+    out
+}
+
+/// Scurteaza `text` la `width` caractere, cu "…" la mijloc — o adresa IPv6
+/// isi pastreaza prefixul si sufixul (partile care disting o sursa).
+fn elide_middle(text: &str, width: usize) -> String {
+    let len = text.chars().count();
+    if len <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let head = width.saturating_sub(1) / 2;
+    let tail = width.saturating_sub(1) - head;
+    let mut out: String = text.chars().take(head).collect();
+    out.push('…');
+    out.extend(text.chars().skip(len - tail));
+    out
+}
+
+/// Latimea terminalului, citita la `configure_output` si la SIGWINCH — nu la
+/// fiecare linie (isatty + ioctl + variabila de mediu pe calea fierbinte).
+/// 0 = stdout nu este un terminal.
+static TERMINAL_WIDTH: AtomicUsize = AtomicUsize::new(0);
+
+/// Reciteste latimea terminalului (pornire, SIGHUP, SIGWINCH).
+pub fn refresh_terminal_width() {
+    TERMINAL_WIDTH.store(query_terminal_width().unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Latimea terminalului memorata; `None` cand stdout nu este un terminal
+/// (fisier, pipe, journald) — acolo liniile columnare nu sunt trunchiate.
+fn terminal_width() -> Option<usize> {
+    match TERMINAL_WIDTH.load(Ordering::Relaxed) {
+        0 => None,
+        width => Some(width),
+    }
+}
+
+/// Latimea terminalului pe stdout; `None` cand stdout nu este un terminal.
+/// Fara raspuns de la TIOCGWINSZ: `COLUMNS`, apoi latimea separatorului.
+fn query_terminal_width() -> Option<usize> {
+    // SAFETY: isatty si ioctl(TIOCGWINSZ) doar citesc starea descriptorului 1;
+    // `winsize` este un struct C simplu, valid initializat cu zero.
+    unsafe {
+        if libc::isatty(libc::STDOUT_FILENO) == 0 {
+            return None;
+        }
+        let mut ws: libc::winsize = std::mem::zeroed();
+        if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) == 0 && ws.ws_col > 0 {
+            return Some(ws.ws_col as usize);
+        }
+    }
+    let columns = std::env::var("COLUMNS").ok().and_then(|c| c.parse().ok());
+    Some(columns.unwrap_or(SEPARATOR_WIDTH))
+}
+
 /// Mesaj informational - badge verde, pentru operatii normale.
 pub fn log_info(message: &str) {
+    if !should_print(RepeatLevel::Info, message) {
+        return;
+    }
     let ts = timestamp();
+    if columnar() {
+        println!("{}", format_row(&ts, LEVEL_INFO.render(), Row::message(message), Color::White));
+        return;
+    }
     println!(
         "{} {} {}",
         ts.bold().white(),
-        " INFO ".on_green().black().bold(),
-        message.white()
+        LEVEL_INFO.render(),
+        colorize_message_ips(message)
     );
 }
 
 /// Avertisment - badge galben, pentru situatii care merita atentie.
 pub fn log_warning(message: &str) {
+    if !should_print(RepeatLevel::Warn, message) {
+        return;
+    }
     let ts = timestamp();
+    if columnar() {
+        println!("{}", format_row(&ts, LEVEL_WARN.render(), Row::message(message), Color::Yellow));
+        return;
+    }
     println!(
         "{} {} {}",
         ts.bold().white(),
-        " WARN ".on_yellow().black().bold(),
+        LEVEL_WARN.render(),
         message.yellow()
     );
 }
 
 /// Eroare - badge rosu, pentru esecuri non-fatale.
 pub fn log_error(message: &str) {
+    if !should_print(RepeatLevel::Error, message) {
+        return;
+    }
     let ts = timestamp();
+    if columnar() {
+        eprintln!("{}", format_row(&ts, LEVEL_ERR.render(), Row::message(message), Color::Red));
+        return;
+    }
     eprintln!(
         "{} {} {}",
         ts.bold().white(),
-        " ERR  ".on_red().white().bold(),
+        LEVEL_ERR.render(),
         message.red()
     );
 }
@@ -269,77 +1086,313 @@ pub fn log_error(message: &str) {
 
 /// Afiseaza o alerta de securitate cu formatare vizual distincta.
 pub fn log_alert(alert: &Alert, hostnames: &HashMap<IpAddr, String>, subnets: &[SubnetEntry]) {
-    let ts = alert.timestamp.format("[%Y-%m-%d %H:%M:%S]").to_string();
+    // display.format = "gelf": o linie JSON per alerta, fara culori.
+    if GELF_OUTPUT.load(Ordering::Relaxed) {
+        println!("{}", crate::alerter::build_gelf_message(alert));
+        return;
+    }
+
+    let ts = format!("[{}]", format_timestamp(&alert.timestamp));
 
     // Formatam lista de porturi cu trunchiere.
     // `.take(25)` limiteaza la primele 25 porturi (iteratorul e lazy).
+    // Cu `output.port_ranges`, limita se aplica intervalelor, iar sufixul
+    // numara porturile din intervalele neafisate.
     let max_display = 25;
-    let port_list: String = alert
-        .unique_ports
-        .iter()
-        .take(max_display)
-        .map(|p| p.to_string())
-        .collect::<Vec<_>>()
-        .join(", ");
+    let (port_list, hidden) = if PORT_RANGES.load(Ordering::Relaxed) {
+        let ranges = alert.port_ranges();
+        let hidden: usize = ranges
+            .iter()
+            .skip(max_display)
+            .map(|&(start, end)| (end - start) as usize + 1)
+            .sum();
+        let list = ranges
+            .into_iter()
+            .take(max_display)
+            .map(crate::detector::format_port_range)
+            .collect::<Vec<_>>()
+            .join(", ");
+        (list, hidden)
+    } else {
+        let list = alert
+            .unique_ports
+            .iter()
+            .take(max_display)
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        (list, alert.unique_ports.len().saturating_sub(max_display))
+    };
 
-    let suffix = if alert.unique_ports.len() > max_display {
-        format!(" ... (+{} more)", alert.unique_ports.len() - max_display)
+    let suffix = if hidden > 0 {
+        format!(" ... (+{} more)", hidden)
     } else {
         String::new()
     };
 
-    let arrows = "▶▶▶";
+    // Culorile vin din tabela de stil (aceeasi folosita de `--legend`).
+    let style = scan_style(&alert.scan_type);
+    let separator = "─".repeat(SEPARATOR_WIDTH).color(style.color);
+    let arrows = ARROWS.color(style.color).bold();
+    let badge = " ALERT ".on_color(style.color).color(style.badge_fg).bold();
 
     let src_display = format_ip(&alert.source_ip, hostnames, subnets);
+    // Cu `output.colorize_ips`, sursa are culoarea ei; altfel culoarea tipului.
+    let src_color = ip_color(&alert.source_ip).unwrap_or(style.color);
+
+    // output.style = "columnar": o singura linie — porturile pentru regulile
+    // de porturi, altfel detaliul din rezumat (destinatii, surse, interval).
+    if columnar() {
+        let detail = if matches!(alert.scan_type, ScanType::Fast | ScanType::Slow | ScanType::AcceptScan) {
+            format!("{}{}", port_list, suffix)
+        } else {
+            alert.render_summary("{detail}")
+        };
+        let row = Row {
+            ip: Some(alert.source_ip),
+            kind: style.label.trim_matches(['[', ']']).to_string(),
+            count: alert.render_summary("{count}"),
+            detail,
+        };
+        let badge = " ALERT".on_color(style.color).color(style.badge_fg).bold();
+        println!("{}", format_row(&ts, badge, row, style.color));
+        return;
+    }
+
+    // Linie optionala (display.show_time_to_threshold) pentru Fast/Slow/Accept.
+    let time_to_threshold = match alert.time_to_threshold_ms {
+        Some(ms) if SHOW_TIME_TO_THRESHOLD.load(Ordering::Relaxed) => {
+            format!("\n  Timp pana la prag: {:.1}s", ms as f64 / 1000.0)
+        }
+        _ => String::new(),
+    };
+
+    // Linii suplimentare sub lista de porturi (Fast / Slow / Accept Scan),
+    // in ordinea de mai jos; fiecare apare doar cand campul alertei este setat.
+    let mut extra_lines: Vec<String> = Vec::new();
+
+    // Fast/Slow: marcaj sonde UDP goale (detection.empty_udp_probes).
+    if alert.empty_probes {
+        extra_lines.push("Sonde UDP goale (payload 0) — ponderate la prag".yellow().to_string());
+    }
+
+    // Ponderi per port (detection.port_weights): scorul alaturi de numarul brut.
+    if let Some(score) = alert.weighted_score {
+        extra_lines.push(format!("Scor ponderat: {:.1} (porturi: {})", score, alert.port_count_label()));
+    }
+
+    // Slow Scan cu slow_scan.carry_over: porturile creditate din fereastra anterioara.
+    if !alert.carried_ports.is_empty() {
+        extra_lines.push(format!(
+            "Fereastra anterioara: {} porturi creditate partial (carry_over)",
+            alert.carried_ports.len()
+        ));
+    }
+
+    // Fast Scan promovat din Slow Scan (slow_scan.promote_to_fast).
+    if alert.promoted_from_slow {
+        extra_lines.push("Promovat din Slow Scan — scanarea a accelerat".yellow().to_string());
+    }
+
+    // Fast/Accept Scan cu detection.source_port_exhaustion: connect scan agresiv.
+    if let Some(run) = alert.source_port_exhaustion {
+        extra_lines.push(
+            format!(
+                "Epuizare porturi sursa: {} porturi in {}-{} (densitate {:.2})",
+                run.ports,
+                run.low,
+                run.high,
+                run.density()
+            )
+            .yellow()
+            .to_string(),
+        );
+    }
+
+    // alerting.detection_context: numerele din spatele deciziei.
+    if let Some(ref ctx) = alert.detection_context {
+        let weighted = match ctx.weighted_count {
+            Some(score) => format!(" (scor ponderat {:.1})", score),
+            None => String::new(),
+        };
+        extra_lines.push(format!(
+            "Decizie {}: {} {}{}, prag {}, fereastra {}s",
+            ctx.rule, ctx.observed, ctx.unit, weighted, ctx.threshold, ctx.window_secs
+        ));
+    }
+
+    // Corelare (alerting.correlation_secs): alerta reuneste mai multe detectii.
+    if let Some(n) = alert.merged_count {
+        extra_lines.push(format!("Corelata: {} alerte contopite", n).cyan().to_string());
+    }
+
+    let extra: String = extra_lines.iter().map(|line| format!("\n  {}", line)).collect();
+
+    // Versiunea politicii de detectie (hash config) — discret, pentru corelare.
+    // Tinta clasificata in [assets]: linie evidentiata chiar inaintea ei.
+    // Intrarea senzorului (network.ingress_name), interfata firewall-ului
+    // (network.interface_metadata) si profilul de detectie ([profiles.<nume>]),
+    // pe aceeasi linie discreta.
+    let mut policy_text = format!("  Politica: {}", alert.policy_version);
+    if !alert.ingress.is_empty() {
+        policy_text.push_str(&format!("  Intrare: {}", alert.ingress));
+    }
+    if !alert.interface.is_empty() {
+        policy_text.push_str(&format!("  Interfata: {}", alert.interface_label()));
+    }
+    if !alert.profile.is_empty() && alert.profile != crate::config::DEFAULT_PROFILE {
+        policy_text.push_str(&format!("  Profil: {}", alert.profile));
+    }
+    let policy_line = policy_text.dimmed();
+    // Activul vizat din harta CMDB (enrichment.asset_map_path).
+    let policy_line = match &alert.asset {
+        Some(asset) => {
+            let mut asset_text = format!("  Activ: {}", asset.name.bold());
+            if !asset.owner.is_empty() {
+                asset_text.push_str(&format!(" — {}", asset.owner));
+            }
+            format!("{}\n{}", asset_text, policy_line)
+        }
+        None => policy_line.to_string(),
+    };
+    let policy_line = match alert.asset_criticality {
+        Some(criticality) => {
+            let base = style.severity.0;
+            let badge = format!(" ACTIV {} ", criticality.to_string().to_uppercase());
+            let badge = match criticality {
+                AssetCriticality::Critical => badge.on_red().white().bold(),
+                AssetCriticality::High => badge.on_yellow().black().bold(),
+            };
+            format!(
+                "  {} severitate escaladata {} → {}\n{}",
+                badge,
+                base,
+                criticality.escalate(base).to_string().bold(),
+                policy_line
+            )
+        }
+        None => policy_line.to_string(),
+    };
+    // Marcaj fals-pozitiv al unui operator: linie evidentiata, severitate redusa.
+    let policy_line = if alert.false_positive {
+        let base = style.severity.0;
+        format!(
+            "  {} marcat fals-pozitiv de operator, severitate {} → {}\n{}",
+            " FP ".on_white().black().bold(),
+            base,
+            alert.effective_severity(base),
+            policy_line
+        )
+    } else {
+        policy_line
+    };
+    // Sursa este nod de iesire Tor (enrichment.tor_exit_list_path).
+    let policy_line = if alert.tor_exit {
+        format!(
+            "  {} sursa este nod de iesire Tor — IP-ul nu apartine atacatorului\n{}",
+            " TOR ".on_magenta().white().bold(),
+            policy_line
+        )
+    } else {
+        policy_line
+    };
+    // Sursa a scanat mai multe protocoale (detection.per_protocol_tracking).
+    let policy_line = if alert.multi_protocol {
+        format!(
+            "  {} {} — incredere ridicata\n{}",
+            " MULTI-PROTOCOL ".on_magenta().white().bold(),
+            alert.protocols.join(", "),
+            policy_line
+        )
+    } else {
+        policy_line
+    };
+    // Clasa alertei (alerting.classification) si motivele ei.
+    let policy_line = match alert.classification {
+        Some(class) => {
+            let badge = match class {
+                AlertClass::Targeted => " TARGETED ".on_red().white().bold(),
+                AlertClass::Noise => " NOISE ".on_bright_black().white(),
+            };
+            format!(
+                "  {} {}\n{}",
+                badge,
+                alert.classification_reasons.join("; ").dimmed(),
+                policy_line
+            )
+        }
+        None => policy_line,
+    };
 
     match alert.scan_type {
         ScanType::Fast => {
-            println!();
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).red());
+            blank_line();
+            println!("{}", separator);
             println!(
                 "{} {} {} [FAST SCAN] {} | {} porturi unice detectate!",
                 ts.bold().white(),
-                arrows.red().bold(),
-                " ALERT ".on_red().white().bold(),
-                format!("[IP: {}]", src_display).red().bold(),
-                alert.unique_ports.len().to_string().red().bold()
+                arrows,
+                badge,
+                format!("[IP: {}]", src_display).color(src_color).bold(),
+                alert.port_count_label().color(style.color).bold()
             );
-            println!("  Porturi: {}{}", port_list, suffix);
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).red());
-            println!();
+            println!("  Porturi: {}{}{}{}", port_list, suffix, time_to_threshold, extra);
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
         }
         ScanType::Slow => {
-            println!();
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).yellow());
+            blank_line();
+            println!("{}", separator);
             println!(
                 "{} {} {} [SLOW SCAN] {} | {} porturi unice detectate!",
                 ts.bold().white(),
-                arrows.yellow().bold(),
-                " ALERT ".on_yellow().black().bold(),
-                format!("[IP: {}]", src_display).yellow().bold(),
-                alert.unique_ports.len().to_string().yellow().bold()
+                arrows,
+                badge,
+                format!("[IP: {}]", src_display).color(src_color).bold(),
+                alert.port_count_label().color(style.color).bold()
             );
-            println!("  Porturi: {}{}", port_list, suffix);
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).yellow());
-            println!();
+            println!("  Porturi: {}{}{}{}", port_list, suffix, time_to_threshold, extra);
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
+        }
+        // Regula din detection.rules_dir: albastru, cu numele regulii in badge.
+        ScanType::CustomRule => {
+            blank_line();
+            println!("{}", separator);
+            println!(
+                "{} {} {} [REGULA {}] {} | {} porturi unice detectate!",
+                ts.bold().white(),
+                arrows,
+                badge,
+                alert.rule_name.color(style.color).bold(),
+                format!("[IP: {}]", src_display).color(src_color).bold(),
+                alert.unique_ports.len().to_string().color(style.color).bold()
+            );
+            println!("  Porturi: {}{}{}", port_list, suffix, time_to_threshold);
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
         }
         // Accept Scan: magenta — distinct vizual fata de rosu (Fast) si galben (Slow).
         // Magenta semnalizeaza o amenintare de nivel mediu: traficul este "legitim"
         // din perspectiva firewall-ului, dar pattern-ul este suspect.
         ScanType::AcceptScan => {
-            println!();
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).magenta());
+            blank_line();
+            println!("{}", separator);
             println!(
                 "{} {} {} [ACCEPT SCAN] {} | {} porturi deschise accesate!",
                 ts.bold().white(),
-                arrows.magenta().bold(),
-                " ALERT ".on_magenta().white().bold(),
-                format!("[IP: {}]", src_display).magenta().bold(),
-                alert.unique_ports.len().to_string().magenta().bold()
+                arrows,
+                badge,
+                format!("[IP: {}]", src_display).color(src_color).bold(),
+                alert.port_count_label().color(style.color).bold()
             );
-            println!("  Porturi: {}{}", port_list, suffix);
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).magenta());
-            println!();
+            println!("  Porturi: {}{}{}{}", port_list, suffix, time_to_threshold, extra);
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
         }
         // Lateral Movement: portocaliu (bright_red aproximeaza orange in terminale ANSI).
         // Cel mai inalt nivel de urgenta vizuala — indica host compromis care se
@@ -357,19 +1410,51 @@ pub fn log_alert(alert: &Alert, hostnames: &HashMap<IpAddr, String>, subnets: &[
             } else {
                 String::new()
             };
-            println!();
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).bright_red());
+            blank_line();
+            println!("{}", separator);
             println!(
                 "{} {} {} [LATERAL MOVEMENT] {} | {} destinatii unice!",
                 ts.bold().white(),
-                arrows.bright_red().bold(),
-                " ALERT ".on_bright_red().white().bold(),
-                format!("[IP: {}]", src_display).bright_red().bold(),
-                alert.unique_dests.len().to_string().bright_red().bold()
+                arrows,
+                badge,
+                format!("[IP: {}]", src_display).color(src_color).bold(),
+                alert.unique_dests.len().to_string().color(style.color).bold()
+            );
+            println!("  Destinatii: {}{}", dest_list, dest_suffix);
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
+        }
+        // Slow Sweep: albastru intens — un singur serviciu cautat host cu host.
+        // Portul pe linia principala, hosturile atinse dedesubt.
+        ScanType::SlowSweep => {
+            let dest_list: String = alert
+                .unique_dests
+                .iter()
+                .take(25)
+                .map(|ip| ip.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let dest_suffix = if alert.unique_dests.len() > 25 {
+                format!(" ... (+{} more)", alert.unique_dests.len() - 25)
+            } else {
+                String::new()
+            };
+            blank_line();
+            println!("{}", separator);
+            println!(
+                "{} {} {} [SLOW SWEEP] {} | portul {} pe {} destinatii!",
+                ts.bold().white(),
+                arrows,
+                badge,
+                format!("[IP: {}]", src_display).color(src_color).bold(),
+                alert.ports_text(",", false).color(style.color).bold(),
+                alert.unique_dests.len().to_string().color(style.color).bold()
             );
             println!("  Destinatii: {}{}", dest_list, dest_suffix);
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).bright_red());
-            println!();
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
         }
         // Distributed Scan: cyan — vizual distinct, indica atac coordonat din surse multiple.
         // Afisam sursele unice si porturile vizate pe tinta.
@@ -390,21 +1475,55 @@ pub fn log_alert(alert: &Alert, hostnames: &HashMap<IpAddr, String>, subnets: &[
                 Some(ip) => format_ip(&ip, hostnames, subnets),
                 None => "N/A".to_string(),
             };
-            println!();
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).cyan());
+            blank_line();
+            println!("{}", separator);
             println!(
                 "{} {} {} [DISTRIBUTED SCAN] {} surse → {} | Porturi: {}",
                 ts.bold().white(),
-                arrows.cyan().bold(),
-                " ALERT ".on_cyan().black().bold(),
-                alert.unique_sources.len().to_string().cyan().bold(),
-                format!("[Target: {}]", target_display).cyan().bold(),
-                alert.unique_ports.len().to_string().cyan().bold()
+                arrows,
+                badge,
+                alert.unique_sources.len().to_string().color(style.color).bold(),
+                format!("[Target: {}]", target_display).color(style.color).bold(),
+                alert.unique_ports.len().to_string().color(style.color).bold()
+            );
+            println!("  Surse:   {}{}", src_list, src_suffix);
+            println!("  Porturi: {}{}", port_list, suffix);
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
+        }
+        // Coordinated Subnet Scan: cyan deschis — inrudit cu Distributed Scan,
+        // dar cheia este blocul sursa. Afisam prefixul, sursele si porturile.
+        ScanType::CoordinatedSubnet => {
+            let src_list: String = alert
+                .unique_sources
+                .iter()
+                .take(25)
+                .map(|ip| ip.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let src_suffix = if alert.unique_sources.len() > 25 {
+                format!(" ... (+{} more)", alert.unique_sources.len() - 25)
+            } else {
+                String::new()
+            };
+            let prefix = alert.source_prefix.as_deref().unwrap_or("?");
+            blank_line();
+            println!("{}", separator);
+            println!(
+                "{} {} {} [BLOC COORDONAT] {} | {} surse | Porturi: {}",
+                ts.bold().white(),
+                arrows,
+                badge,
+                format!("[Bloc: {}]", prefix).color(style.color).bold(),
+                alert.unique_sources.len().to_string().color(style.color).bold(),
+                alert.unique_ports.len().to_string().color(style.color).bold()
             );
             println!("  Surse:   {}{}", src_list, src_suffix);
             println!("  Porturi: {}{}", port_list, suffix);
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).cyan());
-            println!();
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
         }
         // Beaconing C2: rosu intens — cel mai sever (sev 9). Compromis confirmat.
         // Afisam flow-ul (src → dst:port), mean interval, CV si event count.
@@ -420,22 +1539,137 @@ pub fn log_alert(alert: &Alert, hostnames: &HashMap<IpAddr, String>, subnets: &[
             let mean = alert.mean_interval_secs.unwrap_or(0.0);
             let cv = alert.cv.unwrap_or(0.0);
             let count = alert.event_count.unwrap_or(0);
-            println!();
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).red());
+            blank_line();
+            println!("{}", separator);
             println!(
                 "{} {} {} [BEACONING C2] {} → {}:{} | {} calluri, mean {:.1}s, CV {:.3}",
                 ts.bold().white(),
-                arrows.red().bold(),
-                " ALERT ".on_red().white().bold(),
-                format!("[IP: {}]", src_display).red().bold(),
-                target_display.red().bold(),
-                port_str.red().bold(),
-                count.to_string().red().bold(),
+                arrows,
+                badge,
+                format!("[IP: {}]", src_display).color(src_color).bold(),
+                target_display.color(style.color).bold(),
+                port_str.color(style.color).bold(),
+                count.to_string().color(style.color).bold(),
                 mean,
                 cv
             );
-            println!("{}", "─".repeat(SEPARATOR_WIDTH).red());
-            println!();
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
+        }
+        // Saturatie tracking: galben intens — nu e un atacator anume, ci starea
+        // senzorului. Afisam rata evictiilor: detectia este degradata cat dureaza.
+        ScanType::Saturation => {
+            let rate = alert.eviction_rate.unwrap_or(0.0);
+            let count = alert.event_count.unwrap_or(0);
+            blank_line();
+            println!("{}", separator);
+            println!(
+                "{} {} {} [TRACKING SATURAT] {} evictii LRU | {} evictii/s | ultimul IP nou: {}",
+                ts.bold().white(),
+                arrows,
+                badge,
+                count.to_string().color(style.color).bold(),
+                format!("{:.1}", rate).color(style.color).bold(),
+                src_display.color(src_color)
+            );
+            println!("  Posibil flood cu IP-uri spoofed — scanerii reali pot fi evictati inainte de prag");
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
+        }
+        // Senzor fara trafic: starea senzorului, nu un atacator. `source_ip` este
+        // adresa de ascultare.
+        ScanType::NoTraffic => {
+            let window = format_window(std::time::Duration::from_secs(
+                alert.silent_secs.unwrap_or(0),
+            ));
+            blank_line();
+            println!("{}", separator);
+            println!(
+                "{} {} {} [FARA TRAFIC] niciun pachet in {} | senzor: {}",
+                ts.bold().white(),
+                arrows,
+                badge,
+                window.color(style.color).bold(),
+                src_display.color(src_color)
+            );
+            println!("  Posibil mirror/tap sau forwarding syslog cazut — senzorul poate fi orb");
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
+        }
+        // Flood volumetric: magenta intens. Nu lista de porturi, ci rata —
+        // indicator DoS, nu recunoastere.
+        ScanType::Flood => {
+            let rate = alert.packet_rate.unwrap_or(0.0);
+            let count = alert.event_count.unwrap_or(0);
+            let target_display = match alert.dest_ip {
+                Some(ip) => format_ip(&ip, hostnames, subnets),
+                None => "N/A".to_string(),
+            };
+            blank_line();
+            println!("{}", separator);
+            println!(
+                "{} {} {} [FLOOD] {} → {} | {} pachete | {} pachete/s",
+                ts.bold().white(),
+                arrows,
+                badge,
+                format!("[IP: {}]", src_display).color(src_color).bold(),
+                target_display.color(style.color).bold(),
+                count.to_string().color(style.color).bold(),
+                format!("{:.0}", rate).color(style.color).bold()
+            );
+            println!("  Volum mare catre putine porturi — posibil DoS, nu scanare");
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
+        }
+        // Intrare malformata: verde intens — tinta este senzorul, nu reteaua.
+        // `source_ip` este expeditorul datagramelor, nu o sursa din log-uri.
+        ScanType::MalformedInput => {
+            blank_line();
+            println!("{}", separator);
+            println!(
+                "{} {} {} [INTRARE MALFORMATA] {} | {} datagrame fara linie parsabila",
+                ts.bold().white(),
+                arrows,
+                badge,
+                format!("[Expeditor: {}]", src_display).color(src_color).bold(),
+                alert.event_count.unwrap_or(0).to_string().color(style.color).bold()
+            );
+            println!("  Posibil fuzzing / sondare a senzorului sau forwarder configurat gresit");
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
+        }
+        // Scanare incheiata: verde — capatul unui episod deja alertat, cu
+        // totalurile lui. Informativ, fara lista completa de porturi.
+        ScanType::ScanEnded => {
+            let (alerts, duration) = alert
+                .scan_episode
+                .as_ref()
+                .map_or((0, 0), |e| (e.alerts, e.duration_secs));
+            blank_line();
+            println!("{}", separator);
+            println!(
+                "{} {} {} [SCANARE INCHEIATA] {} | {} porturi, {} destinatii, {} alerte in {}",
+                ts.bold().white(),
+                arrows,
+                badge,
+                format!("[IP: {}]", src_display).color(src_color).bold(),
+                alert.unique_ports.len().to_string().color(style.color).bold(),
+                alert.unique_dests.len().to_string().color(style.color).bold(),
+                alerts,
+                format_window(std::time::Duration::from_secs(duration))
+            );
+            println!(
+                "  Fara activitate de {}",
+                format_window(std::time::Duration::from_secs(alert.silent_secs.unwrap_or(0)))
+            );
+            println!("{}", policy_line);
+            println!("{}", separator);
+            blank_line();
         }
     }
 }
@@ -443,10 +1677,18 @@ pub fn log_alert(alert: &Alert, hostnames: &HashMap<IpAddr, String>, subnets: &[
 /// Confirma ca o alerta a fost transmisa cu succes (verde subtil).
 pub fn log_alert_sent(destination: &str, alert_type: &str) {
     let ts = timestamp();
+    if columnar() {
+        let row = Row {
+            kind: alert_type.to_string(),
+            ..Row::message(format!("transmis -> {}", destination))
+        };
+        println!("{}", format_row(&ts, LEVEL_SENT.render(), row, Color::Green));
+        return;
+    }
     println!(
         "{} {} Alert '{}' transmis -> {}",
         ts.dimmed(),
-        " SENT ".on_green().black().bold(),
+        LEVEL_SENT.render(),
         alert_type.green(),
         destination.green().underline()
     );
@@ -474,15 +1716,30 @@ pub fn log_firewall_event(
     // NOTA RUST: `if-else` ca expresie — returneaza ColoredString din ambele ramuri.
     // Tipul este unificat la compile-time (ambele returneaza ColoredString).
     let badge = if action == "accept" {
-        " ACCPT".on_green().black().bold()
+        LEVEL_ACCEPT.render()
     } else {
-        " DROP ".on_blue().white().bold()
+        LEVEL_DROP.render()
     };
+    if columnar() {
+        let row = Row {
+            ip: Some(*ip),
+            kind: action.to_string(),
+            count: port.to_string(),
+            // IP-ul are deja coloana lui; in detalii raman doar hostname / subnet.
+            detail: format!(
+                "{}{}",
+                protocol,
+                format_ip(ip, hostnames, subnets).trim_start_matches(&ip.to_string())
+            ),
+        };
+        println!("{}", format_row(&ts, badge, row, Color::BrightBlue));
+        return;
+    }
     println!(
         "{} {} Src={} DstPort={} Proto={} Action={}",
         ts.dimmed(),
         badge,
-        format_ip(ip, hostnames, subnets).bright_blue(),
+        format_ip(ip, hostnames, subnets).color(ip_color(ip).unwrap_or(Color::BrightBlue)),
         format!("{}", port).bright_blue(),
         protocol.bright_blue(),
         action.bright_blue()
@@ -492,24 +1749,330 @@ pub fn log_firewall_event(
 /// Afiseaza statistici periodice (apelat din cleanup task).
 ///
 /// Format: [timestamp] [STAT] 42 IP-uri urmarite | Cleanup: 5 sterse
-pub fn log_stats(tracked_ips: usize, cleaned_ips: usize) {
+/// Cu compactare (detection.compact_interval_secs): ... | Compactare: 1.2 MB eliberati
+/// Cu display.stats_sparkline: ... | Alerte/ora: ▁▁▁▃█▂▁▁▁▁▁▁▁▁▁▁▁▁▁▁▁▁▁▁
+/// Cu metrics.latency_histogram: ... | Latenta p50/p95/p99: 100µs / 1ms / 2.5ms
+pub fn log_stats(
+    tracked_ips: usize,
+    cleaned_ips: usize,
+    reclaimed_bytes: usize,
+    alerts_by_hour: &[u64; 24],
+    latency: Option<LatencyPercentiles>,
+) {
+    let ts = timestamp();
+    let compaction = if reclaimed_bytes > 0 {
+        format!(" | Compactare: {} eliberati", format_bytes(reclaimed_bytes).white().bold())
+    } else {
+        String::new()
+    };
+    let sparkline = if STATS_SPARKLINE.load(Ordering::Relaxed) {
+        format!(" | Alerte/ora: {}", sparkline(alerts_by_hour).cyan())
+    } else {
+        String::new()
+    };
+    let latency = match latency {
+        Some(p) => format!(
+            " | Latenta p50/p95/p99: {} / {} / {}",
+            format_latency(p.p50_us),
+            format_latency(p.p95_us),
+            format_latency(p.p99_us).white().bold()
+        ),
+        None => String::new(),
+    };
+    if columnar() {
+        let row = Row {
+            kind: "IP-uri urmarite".to_string(),
+            count: tracked_ips.to_string(),
+            ..Row::message(format!("Cleanup: {} sterse{}{}{}", cleaned_ips, compaction, sparkline, latency))
+        };
+        println!("{}", format_row(&ts, LEVEL_STAT.render(), row, Color::White));
+        return;
+    }
+    println!(
+        "{} {} {} IP-uri urmarite | Cleanup: {} sterse{}{}{}",
+        ts.dimmed(),
+        LEVEL_STAT.render(),
+        tracked_ips.to_string().white().bold(),
+        cleaned_ips.to_string().white().bold(),
+        compaction,
+        sparkline,
+        latency
+    );
+}
+
+/// Heartbeat-ul perioadelor linistite (`output.suppress_empty_stats`).
+///
+/// Format: [timestamp] [STAT] Activ, linistit de 1h 0m | 3 IP-uri urmarite
+pub fn log_stats_quiet(quiet_secs: u64, tracked_ips: usize) {
+    let ts = timestamp();
+    if columnar() {
+        let row = Row {
+            kind: "IP-uri urmarite".to_string(),
+            count: tracked_ips.to_string(),
+            ..Row::message(format!("Activ, linistit de {}h {}m", quiet_secs / 3600, (quiet_secs % 3600) / 60))
+        };
+        println!("{}", format_row(&ts, LEVEL_STAT.render(), row, Color::White));
+        return;
+    }
+    println!(
+        "{} {} Activ, linistit de {}h {}m | {} IP-uri urmarite",
+        ts.dimmed(),
+        LEVEL_STAT.render(),
+        quiet_secs / 3600,
+        (quiet_secs % 3600) / 60,
+        tracked_ips.to_string().white().bold()
+    );
+}
+
+/// Limita unui bucket de latenta: "250µs", "2.5ms", "1s", ">1s".
+fn format_latency(us: u64) -> String {
+    match us {
+        u64::MAX => ">1s".to_string(),
+        0..=999 => format!("{}µs", us),
+        1_000..=999_999 => format!("{}ms", us as f64 / 1_000.0),
+        _ => format!("{}s", us as f64 / 1_000_000.0),
+    }
+}
+
+/// Afiseaza raportul agregat periodic (`output.rollup_sink = "log"`).
+///
+/// Format: [timestamp] [STAT] Rollup hourly: 17 alerte
+///           Perioada: ... / Alerte: ... / Top surse: ... (o linie fiecare)
+pub fn log_rollup(summary: &crate::rollup::RollupSummary) {
+    let ts = timestamp();
+    if columnar() {
+        let row = Row {
+            kind: format!("Rollup {}", summary.interval),
+            count: summary.total_alerts.to_string(),
+            ..Row::message("alerte")
+        };
+        println!("{}", format_row(&ts, LEVEL_STAT.render(), row, Color::White));
+        for line in summary.to_text().lines() {
+            println!("{}", format_row(&ts, LEVEL_STAT.render(), Row::message(line), Color::White));
+        }
+        return;
+    }
+    println!(
+        "{} {} Rollup {}: {} alerte",
+        ts.dimmed(),
+        LEVEL_STAT.render(),
+        summary.interval,
+        summary.total_alerts.to_string().white().bold()
+    );
+    for line in summary.to_text().lines() {
+        println!("  {}", line.dimmed());
+    }
+}
+
+/// Sparkline Unicode (▁..█) scalat la valoarea maxima. O valoare nenula are
+/// cel putin a doua treapta — se distinge de orele fara nicio alerta.
+fn sparkline(values: &[u64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| match v {
+            0 => BARS[0],
+            _ => BARS[((v * 7).div_ceil(max) as usize).clamp(1, 7)],
+        })
+        .collect()
+}
+
+/// Formateaza o dimensiune in octeti: "512 B", "12.3 KB", "1.5 MB".
+fn format_bytes(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let b = bytes as f64;
+    if b < KB {
+        format!("{} B", bytes)
+    } else if b < KB * KB {
+        format!("{:.1} KB", b / KB)
+    } else {
+        format!("{:.1} MB", b / (KB * KB))
+    }
+}
+
+/// Sumarul final afisat la oprirea gratioasa (Ctrl+C, SIGTERM, --max-runtime).
+///
+/// Format: [timestamp] [STAT] Sumar: rulat 30 minute | 1234 evenimente | 5 alerte | 42 IP-uri urmarite
+pub fn log_shutdown_summary(uptime: Duration, events: u64, alerts: u64, tracked_ips: usize) {
+    flush_all_repeated_logs();
     let ts = timestamp();
+    if columnar() {
+        let row = Row {
+            kind: "Sumar".to_string(),
+            count: alerts.to_string(),
+            ..Row::message(format!(
+                "alerte | rulat {} | {} evenimente | {} IP-uri urmarite",
+                format_window(Duration::from_secs(uptime.as_secs())),
+                events,
+                tracked_ips
+            ))
+        };
+        println!("{}", format_row(&ts, LEVEL_STAT.render(), row, Color::White));
+        return;
+    }
+    println!(
+        "{} {} Sumar: rulat {} | {} evenimente | {} alerte | {} IP-uri urmarite",
+        ts.dimmed(),
+        LEVEL_STAT.render(),
+        format_window(Duration::from_secs(uptime.as_secs())),
+        events.to_string().white().bold(),
+        alerts.to_string().white().bold(),
+        tracked_ips.to_string().white().bold()
+    );
+}
+
+/// Numarul maxim de surse listate in raportul SIGUSR2.
+const STATUS_REPORT_SCANNERS: usize = 10;
+
+/// Raportul de stare la SIGUSR2: o linie de sumar si sursele peste prag.
+///
+/// Format:
+///   [timestamp] [STAT] Stare: rulat 2h 5m | 42 IP-uri urmarite | 2 scanere active | 7 alerte in ultima ora
+///       10.0.0.9                  120 porturi
+///       ...
+pub fn log_status_report(
+    uptime: Duration,
+    tracked_ips: usize,
+    alerts_last_hour: u64,
+    scanners: &[(IpAddr, usize)],
+) {
+    flush_all_repeated_logs();
+    let ts = timestamp();
+    if columnar() {
+        let row = Row {
+            kind: "Stare".to_string(),
+            count: scanners.len().to_string(),
+            ..Row::message(format!(
+                "scanere active | rulat {} | {} IP-uri urmarite | {} alerte in ultima ora",
+                format_window(Duration::from_secs(uptime.as_secs())),
+                tracked_ips,
+                alerts_last_hour
+            ))
+        };
+        println!("{}", format_row(&ts, LEVEL_STAT.render(), row, Color::White));
+        for (ip, ports) in scanners.iter().take(STATUS_REPORT_SCANNERS) {
+            let row = Row {
+                ip: Some(*ip),
+                kind: "Scaner activ".to_string(),
+                count: ports.to_string(),
+                ..Row::message("porturi")
+            };
+            println!("{}", format_row(&ts, LEVEL_STAT.render(), row, Color::White));
+        }
+        if scanners.len() > STATUS_REPORT_SCANNERS {
+            let more = format!("... si inca {}", scanners.len() - STATUS_REPORT_SCANNERS);
+            println!("{}", format_row(&ts, LEVEL_STAT.render(), Row::message(more), Color::White));
+        }
+        return;
+    }
+    let active = if scanners.is_empty() {
+        "0".normal()
+    } else {
+        scanners.len().to_string().red().bold()
+    };
     println!(
-        "{} {} {} IP-uri urmarite | Cleanup: {} sterse",
+        "{} {} Stare: rulat {} | {} IP-uri urmarite | {} scanere active | {} alerte in ultima ora",
         ts.dimmed(),
-        " STAT ".on_cyan().black().bold(),
+        LEVEL_STAT.render(),
+        format_window(Duration::from_secs(uptime.as_secs())),
         tracked_ips.to_string().white().bold(),
-        cleaned_ips.to_string().white().bold()
+        active,
+        alerts_last_hour.to_string().white().bold()
+    );
+    for (ip, ports) in scanners.iter().take(STATUS_REPORT_SCANNERS) {
+        println!("    {:<39} {:>6} porturi", ip.to_string(), ports);
+    }
+    if scanners.len() > STATUS_REPORT_SCANNERS {
+        println!("    ... si inca {}", scanners.len() - STATUS_REPORT_SCANNERS);
+    }
+}
+
+/// Contabilitatea pachetelor (`--diagnostics-report`): fiecare etapa a
+/// pipeline-ului si ce s-a pierdut inainte de ea, o linie per categorie.
+///
+/// Format:
+///   [timestamp] [STAT] Contabilitate pachete:
+///       datagrame UDP primite      1200   (kernel, coada plina: 0)
+///         - rate limit                0
+///       linii log                  1250
+///         - erori parsare            12
+///       ...
+pub fn log_diagnostics_report(report: &PacketReport) {
+    flush_all_repeated_logs();
+    let ts = timestamp();
+    if columnar() {
+        let kernel = report
+            .kernel_dropped
+            .map_or_else(|| "n/a".to_string(), |n| n.to_string());
+        let rows = [
+            ("datagrame UDP primite", report.datagrams_received, format!(" (pierdute in kernel: {})", kernel)),
+            ("- rate limit", report.rate_limited, String::new()),
+            ("linii log", report.lines_received, String::new()),
+            ("- erori parsare", report.parse_errors, String::new()),
+            ("evenimente parsate", report.parsed, String::new()),
+            ("- duplicate (dedup)", report.duplicates, String::new()),
+            ("evenimente procesate", report.events_processed, String::new()),
+            ("- whitelist", report.whitelisted, String::new()),
+            ("- semnaturi autorizate", report.trusted_signature, String::new()),
+            ("analizate de reguli", report.analyzed, String::new()),
+            ("alerte generate", report.alerts_generated, String::new()),
+        ];
+        for (label, value, note) in rows {
+            let row = Row {
+                kind: "Pachete".to_string(),
+                count: value.to_string(),
+                ..Row::message(format!("{}{}", label, note))
+            };
+            println!("{}", format_row(&ts, LEVEL_STAT.render(), row, Color::White));
+        }
+        return;
+    }
+    println!("{} {} Contabilitate pachete:", ts.dimmed(), LEVEL_STAT.render());
+    let kernel = match report.kernel_dropped {
+        Some(n) => n.to_string(),
+        None => "n/a".to_string(),
+    };
+    let stage = |label: &str, value: u64, note: String| {
+        println!("    {:<28} {}{}", label, format!("{:>10}", value).white().bold(), note);
+    };
+    let drop = |label: &str, value: u64| {
+        let padded = format!("{:>10}", value);
+        let value = if value > 0 {
+            padded.yellow().bold()
+        } else {
+            padded.normal()
+        };
+        println!("      - {:<24} {}", label, value);
+    };
+    stage(
+        "datagrame UDP primite",
+        report.datagrams_received,
+        format!("   (pierdute in kernel, coada plina: {})", kernel),
     );
+    drop("rate limit", report.rate_limited);
+    stage("linii log", report.lines_received, String::new());
+    drop("erori parsare", report.parse_errors);
+    stage("evenimente parsate", report.parsed, String::new());
+    drop("duplicate (dedup)", report.duplicates);
+    stage("evenimente procesate", report.events_processed, String::new());
+    drop("whitelist", report.whitelisted);
+    drop("semnaturi autorizate", report.trusted_signature);
+    stage("analizate de reguli", report.analyzed, String::new());
+    stage("alerte generate", report.alerts_generated, String::new());
 }
 
 /// Confirma reincarcarea configuratiei la SIGHUP (badge cyan).
 pub fn log_reload(message: &str) {
     let ts = timestamp();
+    if columnar() {
+        println!("{}", format_row(&ts, LEVEL_LOAD.render(), Row::message(message), Color::Cyan));
+        return;
+    }
     println!(
         "{} {} {}",
         ts.bold().white(),
-        " LOAD ".on_cyan().black().bold(),
+        LEVEL_LOAD.render(),
         message.cyan()
     );
 }
@@ -520,14 +2083,71 @@ pub fn log_reload(message: &str) {
 /// Badge galben — vizibilitate medie, nu este o eroare ci protectie activa.
 pub fn log_rate_limited(dropped: u64) {
     let ts = timestamp();
+    if columnar() {
+        let row = Row {
+            kind: "Rate limit".to_string(),
+            count: dropped.to_string(),
+            ..Row::message("pachete UDP dropate")
+        };
+        println!("{}", format_row(&ts, LEVEL_RATE.render(), row, Color::Yellow));
+        return;
+    }
     println!(
         "{} {} {} pachete UDP dropate (rate limit)",
         ts.dimmed(),
-        " RATE ".on_yellow().black().bold(),
+        LEVEL_RATE.render(),
         dropped.to_string().white().bold()
     );
 }
 
+/// Afiseaza o alerta suprimata si motivul (`--explain-suppressions`).
+///
+/// Format: [timestamp] [ EXPL ] fast_scan Src=10.0.0.5: prag atins (15/15), dar in cooldown inca 42s
+pub fn log_suppression(suppression: &Suppression) {
+    let ts = timestamp();
+    if columnar() {
+        let row = Row {
+            ip: Some(suppression.source_ip),
+            kind: suppression.rule.to_string(),
+            ..Row::message(suppression.reason.clone())
+        };
+        println!("{}", format_row(&ts, LEVEL_EXPL.render(), row, Color::BrightBlack));
+        return;
+    }
+    println!(
+        "{} {} {} Src={}: {}",
+        ts.dimmed(),
+        LEVEL_EXPL.render(),
+        suppression.rule.white().bold(),
+        suppression.source_ip.to_string().bright_blue(),
+        suppression.reason.dimmed()
+    );
+}
+
+/// Avertisment provizoriu (`[detection.early_warning]`): scanare in curs, sub
+/// prag. Nivel WARN, etichetat explicit — nu este o alerta.
+pub fn log_early_warning(warning: &EarlyWarning, profile: &str) {
+    let target = warning
+        .dest_ip
+        .map(|ip| format!(" → {}", ip))
+        .unwrap_or_default();
+    let profile = if profile == crate::config::DEFAULT_PROFILE {
+        String::new()
+    } else {
+        format!(" [profil {}]", profile)
+    };
+    log_warning(&format!(
+        "PROVIZORIU {} in curs: {}{} — scor {:.0}/{} ({:.0}% din prag){}; alerta completa doar la depasirea pragului",
+        warning.scan_type,
+        warning.source_ip,
+        target,
+        warning.score,
+        warning.threshold,
+        warning.score * 100.0 / warning.threshold as f64,
+        profile
+    ));
+}
+
 // ---------------------------------------------------------------------------
 // Functii de debug/diagnostic - afiseaza detalii despre parsare
 // ---------------------------------------------------------------------------
@@ -535,10 +2155,14 @@ pub fn log_rate_limited(dropped: u64) {
 /// Afiseaza linia raw primita pe port (mod debug).
 pub fn log_debug_raw(line: &str) {
     let ts = timestamp();
+    if columnar() {
+        println!("{}", format_row(&ts, LEVEL_RAW.render(), Row::message(line), Color::BrightBlack));
+        return;
+    }
     println!(
         "{} {} {}",
         ts.bold().white(),
-        " RAW  ".on_magenta().white().bold(),
+        LEVEL_RAW.render(),
         line.dimmed()
     );
 }
@@ -546,10 +2170,20 @@ pub fn log_debug_raw(line: &str) {
 /// Afiseaza confirmarea parsarii reusite cu campurile extrase (mod debug).
 pub fn log_debug_parse_ok(event: &LogEvent) {
     let ts = timestamp();
+    if columnar() {
+        let row = Row {
+            ip: Some(event.source_ip),
+            kind: event.action.clone(),
+            count: event.dest_port.to_string(),
+            detail: event.protocol.to_string(),
+        };
+        println!("{}", format_row(&ts, LEVEL_OK.render(), row, Color::Green));
+        return;
+    }
     println!(
         "{} {}  src={} dpt={} proto={} action={}",
         ts.bold().white(),
-        "  OK  ".on_green().black().bold(),
+        LEVEL_OK.render(),
         event.source_ip.to_string().green(),
         event.dest_port.to_string().green(),
         event.protocol.green(),
@@ -560,10 +2194,18 @@ pub fn log_debug_parse_ok(event: &LogEvent) {
 /// Afiseaza detalii despre esecul parsarii (mod debug).
 pub fn log_debug_parse_fail(line: &str, parser_name: &str, expected: &str) {
     let ts = timestamp();
+    if columnar() {
+        let row = Row {
+            kind: parser_name.to_string(),
+            ..Row::message(format!("Parsare esuata: \"{}\" (asteptat: {})", line, expected))
+        };
+        println!("{}", format_row(&ts, LEVEL_FAIL.render(), row, Color::Red));
+        return;
+    }
     println!(
         "{} {} Parsare esuata! (parser: {})",
         ts.bold().white(),
-        " FAIL ".on_red().white().bold(),
+        LEVEL_FAIL.render(),
         parser_name.red().bold()
     );
     println!(
@@ -588,7 +2230,135 @@ pub fn log_debug_parse_fail(line: &str, parser_name: &str, expected: &str) {
 // `Local::now()` returneaza data/ora locala, `.format(...)` o formateaza
 // ---------------------------------------------------------------------------
 fn timestamp() -> String {
-    Local::now().format("[%Y-%m-%d %H:%M:%S]").to_string()
+    format!("[{}]", format_timestamp(&Local::now()))
+}
+
+/// Formateaza un moment ca "YYYY-MM-DD HH:MM:SS[.fff[fff]]", cu precizia
+/// din `display.timestamp_precision`. Folosit in consola si in email.
+///
+/// NOTA: `%.3f` / `%.6f` sunt specificatori chrono pentru fractiunea de
+/// secunda cu numar fix de cifre (punctul este inclus in output).
+pub fn format_timestamp(dt: &DateTime<Local>) -> String {
+    format_timestamp_at(dt, TIMESTAMP_PRECISION.load(Ordering::Relaxed))
+}
+
+fn format_timestamp_at(dt: &DateTime<Local>, precision: u8) -> String {
+    let fmt = match precision {
+        0 => "%Y-%m-%d %H:%M:%S",
+        2 => "%Y-%m-%d %H:%M:%S%.6f",
+        _ => "%Y-%m-%d %H:%M:%S%.3f",
+    };
+    dt.format(fmt).to_string()
+}
+
+/// Formateaza un moment RFC 3339 (JSON: SIEM ECS, API web) cu aceeasi
+/// precizie ca `format_timestamp`.
+pub fn format_rfc3339(dt: &DateTime<Local>) -> String {
+    format_rfc3339_at(dt, TIMESTAMP_PRECISION.load(Ordering::Relaxed))
+}
+
+fn format_rfc3339_at(dt: &DateTime<Local>, precision: u8) -> String {
+    let precision = match precision {
+        0 => SecondsFormat::Secs,
+        2 => SecondsFormat::Micros,
+        _ => SecondsFormat::Millis,
+    };
+    dt.to_rfc3339_opts(precision, false)
+}
+
+// ---------------------------------------------------------------------------
+// Culori stabile per IP (output.colorize_ips)
+// ---------------------------------------------------------------------------
+//
+// Intr-un log aglomerat, ochiul urmareste mai usor un actor daca adresa lui
+// are mereu aceeasi culoare. Culoarea se alege din hash-ul FNV-1a al
+// octetilor adresei — determinist intre rulari si intre senzori (spre
+// deosebire de `DefaultHasher`, care nu garanteaza stabilitatea).
+//
+// Paleta evita rosu si galben (rezervate erorilor, avertismentelor si
+// severitatilor mari) si negru (invizibil pe fundal inchis).
+// ---------------------------------------------------------------------------
+
+const IP_PALETTE: [Color; 9] = [
+    Color::Green,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::BrightGreen,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::BrightWhite,
+];
+
+/// Culoarea stabila a unui IP, sau `None` daca `output.colorize_ips` e oprit.
+fn ip_color(ip: &IpAddr) -> Option<Color> {
+    if !COLORIZE_IPS.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some(palette_color(ip))
+}
+
+/// Culoarea din paleta pentru un IP, independent de `output.colorize_ips`.
+fn palette_color(ip: &IpAddr) -> Color {
+    let hash = match ip {
+        IpAddr::V4(v4) => fnv1a(&v4.octets()),
+        IpAddr::V6(v6) => fnv1a(&v6.octets()),
+    };
+    IP_PALETTE[(hash % IP_PALETTE.len() as u64) as usize]
+}
+
+/// FNV-1a pe 64 de biti.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325u64, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Textul unui mesaj INFO (alb), cu fiecare IP colorat dupa `ip_color`.
+///
+/// Cuvintele sunt testate individual, fara punctuatia din jur: "10.0.0.5,",
+/// "(10.0.0.5)", "10.0.0.5:514" sunt recunoscute. Fiecare segment primeste
+/// propria culoare — secventele ANSI imbricate s-ar anula reciproc.
+fn colorize_message_ips(message: &str) -> String {
+    if !COLORIZE_IPS.load(Ordering::Relaxed) {
+        return message.white().to_string();
+    }
+    let mut out = String::new();
+    // Textul obisnuit se acumuleaza si se coloreaza o singura data (alb),
+    // nu cuvant cu cuvant — mai putine secvente ANSI pe linie.
+    let mut plain = String::new();
+    for (i, word) in message.split(' ').enumerate() {
+        if i > 0 {
+            plain.push(' ');
+        }
+        let core = word.trim_matches(|c| {
+            matches!(c, ',' | ';' | '(' | ')' | '[' | ']' | '\'' | '"')
+        });
+        // Sfarsit de propozitie ("... de la 10.0.0.5.") — punctul nu e colorat.
+        let trimmed = core.trim_end_matches(['.', ':']);
+        let found = core
+            .parse::<IpAddr>()
+            .or_else(|_| core.parse::<std::net::SocketAddr>().map(|sa| sa.ip()))
+            .map(|ip| (core, ip))
+            .or_else(|_| trimmed.parse::<IpAddr>().map(|ip| (trimmed, ip)));
+        match found.ok().and_then(|(text, ip)| Some((text, ip_color(&ip)?))) {
+            Some((text, color)) => {
+                let start = word.find(text).unwrap_or(0);
+                plain.push_str(&word[..start]);
+                if !plain.is_empty() {
+                    out.push_str(&std::mem::take(&mut plain).white().to_string());
+                }
+                out.push_str(&text.color(color).to_string());
+                plain.push_str(&word[start + text.len()..]);
+            }
+            None => plain.push_str(word),
+        }
+    }
+    if !plain.is_empty() {
+        out.push_str(&plain.white().to_string());
+    }
+    out
 }
 
 /// Formateaza un IP cu hostname si/sau locatie subnet.
@@ -603,3 +2373,163 @@ fn format_ip(ip: &IpAddr, hostnames: &HashMap<IpAddr, String>, subnets: &[Subnet
         .unwrap_or_default();
     format!("{}{}{}", ip, hostname, location)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Repetarile din fereastra sunt doar numarate; flush-ul dupa expirare
+    /// elibereaza cheia (rezumatul "repetat de N ori" este afisat atunci).
+    #[test]
+    fn test_repeated_logs_coalesce_and_flush() {
+        let message = format!("test coalescing {}", std::process::id());
+        REPEAT_WINDOW_SECS.store(60, Ordering::Relaxed);
+
+        // WARN este coalescat mereu cand fereastra este activa.
+        assert!(should_print(RepeatLevel::Warn, &message));
+        for _ in 0..3 {
+            assert!(!should_print(RepeatLevel::Warn, &message));
+        }
+
+        let suppressed = |message: &str| {
+            REPEATS.lock().unwrap().as_ref().unwrap().get(message).map(|s| s.suppressed)
+        };
+        assert_eq!(suppressed(&message), Some(3));
+
+        // Fereastra inca activa: flush-ul nu atinge cheia.
+        flush_repeated_logs();
+        assert_eq!(suppressed(&message), Some(3));
+
+        // Imbatranim fereastra peste limita de 60s.
+        if let Some(state) = REPEATS.lock().unwrap().as_mut().unwrap().get_mut(&message) {
+            state.window_start -= Duration::from_secs(120);
+        }
+        flush_repeated_logs();
+        assert_eq!(suppressed(&message), None);
+        REPEAT_WINDOW_SECS.store(0, Ordering::Relaxed);
+
+        assert_eq!(repeat_note(3, 120), "(repetat de 3 ori in ultimele 120s)");
+    }
+
+    #[test]
+    fn test_timestamp_precision_rendering() {
+        use chrono::TimeZone;
+        let dt = Local.with_ymd_and_hms(2026, 3, 4, 5, 6, 7).unwrap()
+            + chrono::Duration::microseconds(123_456);
+
+        let levels: Vec<u8> = ["seconds", "millis", "micros", "nanos"]
+            .iter()
+            .map(|p| precision_level(p))
+            .collect();
+        assert_eq!(levels, vec![0, 1, 2, 1], "valoare necunoscuta -> millis");
+
+        assert_eq!(format_timestamp_at(&dt, 0), "2026-03-04 05:06:07");
+        assert_eq!(format_timestamp_at(&dt, 1), "2026-03-04 05:06:07.123");
+        assert_eq!(format_timestamp_at(&dt, 2), "2026-03-04 05:06:07.123456");
+
+        // RFC 3339: aceeasi fractiune, urmata de offset-ul de fus orar.
+        let offset = dt.format("%:z").to_string();
+        assert_eq!(format_rfc3339_at(&dt, 0), format!("2026-03-04T05:06:07{}", offset));
+        assert_eq!(format_rfc3339_at(&dt, 1), format!("2026-03-04T05:06:07.123{}", offset));
+        assert_eq!(format_rfc3339_at(&dt, 2), format!("2026-03-04T05:06:07.123456{}", offset));
+    }
+
+    #[test]
+    fn test_compact_spacing_drops_blank_lines() {
+        let mut out = Vec::new();
+        write_blank_line(&mut out, false);
+        assert_eq!(out, b"\n");
+
+        let mut out = Vec::new();
+        write_blank_line(&mut out, true);
+        assert!(out.is_empty());
+
+        let config: DisplayConfig = toml::from_str("compact_spacing = true").unwrap();
+        assert!(config.compact_spacing);
+        let config: DisplayConfig = toml::from_str("").unwrap();
+        assert!(!config.compact_spacing, "implicit: spatiere aerisita");
+    }
+
+    #[test]
+    fn test_ip_colors_stable_and_spread() {
+        // Vectorii de referinta FNV-1a: culoarea nu depinde de rulare sau de senzor.
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+        let expected = IP_PALETTE[(fnv1a(&[10, 0, 0, 5]) % IP_PALETTE.len() as u64) as usize];
+        assert_eq!(palette_color(&ip("10.0.0.5")), expected);
+        assert_eq!(palette_color(&ip("2001:db8::1")), palette_color(&ip("2001:db8::1")));
+
+        // Adrese vecine se distribuie pe paleta; rosu/galben/negru nu apar niciodata.
+        let colors: Vec<Color> = (0..=255u8)
+            .map(|last| palette_color(&IpAddr::from([192, 168, 1, last])))
+            .collect();
+        let distinct = IP_PALETTE.iter().filter(|c| colors.contains(c)).count();
+        assert_eq!(distinct, IP_PALETTE.len());
+        assert!(!colors
+            .iter()
+            .any(|c| matches!(c, Color::Red | Color::BrightRed | Color::Yellow | Color::BrightYellow | Color::Black)));
+    }
+
+    #[test]
+    fn test_elide_edges_and_multibyte() {
+        assert_eq!(elide_end("abc", 0), "");
+        assert_eq!(elide_middle("abc", 0), "");
+        assert_eq!(elide_end("abc", 1), "…");
+        assert_eq!(elide_middle("abc", 1), "…");
+        // Textul care incape exact ramane neatins.
+        assert_eq!(elide_end("FAST SCAN", 9), "FAST SCAN");
+        assert_eq!(elide_middle("203.0.113.7", 11), "203.0.113.7");
+        assert_eq!(elide_end("FAST SCAN", 8), "FAST SC…");
+        // Latimea este in caractere, nu in octeti.
+        assert_eq!(elide_end("ăîșțâ", 3), "ăî…");
+        assert_eq!(elide_middle("ăîșțâ", 4), "ă…țâ");
+    }
+
+    #[test]
+    fn test_elide_middle_keeps_ipv6_head_and_tail() {
+        let ip = "2001:db8:85a3::8a2e:370:7334";
+        let short = elide_middle(ip, COL_IP);
+        assert_eq!(short.chars().count(), COL_IP);
+        assert_eq!(short, "2001:db…70:7334");
+    }
+
+    /// Textul fara secventele ANSI de culoare.
+    fn plain(text: &str) -> String {
+        let mut out = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_format_row_pads_columns() {
+        let row = Row {
+            ip: Some("2001:db8:85a3::8a2e:370:7334".parse().unwrap()),
+            kind: "FAST SCAN".to_string(),
+            count: "15".to_string(),
+            detail: "1, 2, 3".to_string(),
+        };
+        // Fara terminal (TERMINAL_WIDTH = 0): detaliile nu sunt trunchiate.
+        let line = plain(&format_row("[14:30:12.345]", LEVEL_DROP.render(), row, Color::Red));
+        let cells: Vec<&str> = line.split(" | ").collect();
+        assert_eq!(cells.len(), 5, "{}", line);
+        assert_eq!(cells[1], "2001:db…70:7334");
+        assert_eq!(cells[2], format!("{:<18}", "FAST SCAN"));
+        assert_eq!(cells[3], "     15");
+        assert_eq!(cells[4], "1, 2, 3");
+
+        let info = plain(&format_row("[14:30:12.345]", LEVEL_INFO.render(), Row::message("Ascult"), Color::White));
+        let cells: Vec<&str> = info.split(" | ").collect();
+        assert_eq!(cells[1].len(), COL_IP);
+        assert!(cells[1].trim().is_empty());
+        assert_eq!(cells[4], "Ascult");
+    }
+}
@@ -0,0 +1,217 @@
+// =============================================================================
+// feedback.rs - Feedback Fals-Pozitiv de la Operatori
+// =============================================================================
+//
+// In primele saptamani dupa instalare, un senzor nou produce zgomot: scanere
+// de inventar, monitorizare, backup. Operatorul marcheaza alerta din dashboard:
+//
+//   POST /alerts/{id}/false-positive
+//
+// si IDS-RS retine perechea (IP sursa, tip alerta). Alertele viitoare cu
+// aceeasi pereche, in `period_hours`, sunt:
+//   - "downgrade": emise cu severitate redusa si `false_positive: true`
+//   - "suppress":  neemise deloc (vizibile doar cu --explain-suppressions)
+//
+// Marcajele sunt persistate intr-un fisier JSON — supravietuiesc restartului.
+// Reglajul se face fara editarea config.toml; un marcaj expira singur.
+//
+// NOTA RUST: store-ul este partajat intre main loop (citire la fiecare alerta)
+// si serverul web (scriere la POST) prin `Arc<FalsePositiveStore>`. Lista e
+// protejata de un `Mutex` — marcajele sunt putine (zeci), iar verificarea
+// ruleaza doar pentru alertele emise, nu pentru fiecare eveniment.
+//
+// =============================================================================
+
+use crate::config::FalsePositivesConfig;
+use crate::detector::{Alert, Suppression};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Un marcaj fals-pozitiv: sursa + tipul alertei (pattern-ul) marcate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FalsePositive {
+    pub source_ip: IpAddr,
+    /// Tipul alertei marcate, ca in `ScanType::to_string()` (ex: "Fast Scan").
+    pub scan_type: String,
+    /// Alerta din care provine marcajul (id valabil doar in procesul de atunci).
+    pub alert_id: u64,
+    pub marked_at: DateTime<Local>,
+    /// Adresa clientului HTTP care a marcat alerta (audit).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marked_by: Option<String>,
+}
+
+/// Marcajele active, persistate in `false_positives.file`.
+pub struct FalsePositiveStore {
+    entries: Mutex<Vec<FalsePositive>>,
+    path: PathBuf,
+    period: chrono::Duration,
+    suppress: bool,
+}
+
+impl FalsePositiveStore {
+    /// Incarca marcajele din fisier (daca exista), fara cele expirate.
+    pub fn open(cfg: &FalsePositivesConfig) -> Result<Self> {
+        let path = PathBuf::from(&cfg.file);
+        let entries: Vec<FalsePositive> = if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Nu pot citi fisierul de feedback: {:?}", path))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("Fisier de feedback invalid: {:?}", path))?
+        } else {
+            Vec::new()
+        };
+
+        let store = Self {
+            entries: Mutex::new(entries),
+            path,
+            period: chrono::Duration::hours(cfg.period_hours as i64),
+            suppress: cfg.action == "suppress",
+        };
+        store.lock().retain(|fp| !store.expired(fp, Local::now()));
+        Ok(store)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<FalsePositive>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn expired(&self, fp: &FalsePositive, now: DateTime<Local>) -> bool {
+        now - fp.marked_at > self.period
+    }
+
+    /// Numarul de marcaje active.
+    pub fn len(&self) -> usize {
+        let now = Local::now();
+        self.lock().iter().filter(|fp| !self.expired(fp, now)).count()
+    }
+
+    /// Marcheaza sursa si tipul unei alerte ca fals-pozitiv si persista lista.
+    ///
+    /// Un marcaj existent pentru aceeasi pereche este reimprospatat (perioada
+    /// reincepe), nu duplicat.
+    pub fn mark(&self, alert: &Alert, marked_by: Option<String>) -> Result<FalsePositive> {
+        let now = Local::now();
+        let fp = FalsePositive {
+            source_ip: alert.source_ip,
+            scan_type: alert.scan_type.to_string(),
+            alert_id: alert.id,
+            marked_at: now,
+            marked_by,
+        };
+
+        let mut entries = self.lock();
+        // Eliminam marcajele expirate si pe cel vechi al aceleiasi perechi.
+        let same_pattern =
+            |e: &FalsePositive| e.source_ip == fp.source_ip && e.scan_type == fp.scan_type;
+        entries.retain(|e| !(self.expired(e, now) || same_pattern(e)));
+        entries.push(fp.clone());
+        persist(&self.path, &entries)?;
+        Ok(fp)
+    }
+
+    /// Aplica marcajele unei alerte proaspat emise.
+    ///
+    /// Returneaza `Some(Suppression)` daca alerta trebuie suprimata
+    /// (`action = "suppress"`); altfel `None`, iar in modul "downgrade" alerta
+    /// potrivita primeste `false_positive = true` (severitate redusa).
+    pub fn apply(&self, alert: &mut Alert) -> Option<Suppression> {
+        let now = Local::now();
+        let scan_type = alert.scan_type.to_string();
+        let marked_at = self
+            .lock()
+            .iter()
+            .find(|fp| {
+                fp.source_ip == alert.source_ip && fp.scan_type == scan_type && !self.expired(fp, now)
+            })
+            .map(|fp| fp.marked_at)?;
+
+        if self.suppress {
+            return Some(Suppression {
+                rule: "false_positive",
+                source_ip: alert.source_ip,
+                reason: format!(
+                    "{} marcat fals-pozitiv de operator la {}",
+                    scan_type,
+                    marked_at.format("%Y-%m-%d %H:%M")
+                ),
+            });
+        }
+        alert.false_positive = true;
+        None
+    }
+}
+
+/// Scrie lista atomic: fisier temporar + rename (un crash nu lasa JSON trunchiat).
+fn persist(path: &Path, entries: &[FalsePositive]) -> Result<()> {
+    let json = serde_json::to_string_pretty(entries)?;
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, json)
+        .with_context(|| format!("Nu pot scrie fisierul de feedback: {:?}", tmp))?;
+    std::fs::rename(&tmp, path)
+        .with_context(|| format!("Nu pot inlocui fisierul de feedback: {:?}", path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::ScanType;
+
+    fn store_config(action: &str, name: &str) -> FalsePositivesConfig {
+        let file = std::env::temp_dir().join(format!("ids-rs-fp-{}-{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&file);
+        FalsePositivesConfig {
+            enabled: true,
+            file: file.to_string_lossy().into_owned(),
+            period_hours: 1,
+            action: action.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_mark_downgrades_and_persists() {
+        let cfg = store_config("downgrade", "downgrade");
+        let ip: IpAddr = "10.0.0.5".parse().unwrap();
+        let store = FalsePositiveStore::open(&cfg).unwrap();
+        store.mark(&Alert::new(ScanType::Fast, ip, None), Some("127.0.0.1".into())).unwrap();
+        store.mark(&Alert::new(ScanType::Fast, ip, None), None).unwrap();
+        assert_eq!(store.len(), 1, "marcajul repetat nu se duplica");
+
+        // Aceeasi sursa + acelasi tip → retrogradata; alt tip → neatinsa.
+        let mut fast = Alert::new(ScanType::Fast, ip, None);
+        assert!(store.apply(&mut fast).is_none());
+        assert!(fast.false_positive);
+        assert_eq!(fast.effective_severity(7), crate::detector::FALSE_POSITIVE_SEVERITY);
+        let mut slow = Alert::new(ScanType::Slow, ip, None);
+        assert!(store.apply(&mut slow).is_none());
+        assert!(!slow.false_positive);
+
+        // Persistat: un store nou (restart) vede marcajul.
+        let reopened = FalsePositiveStore::open(&cfg).unwrap();
+        assert_eq!(reopened.len(), 1);
+        std::fs::remove_file(&cfg.file).unwrap();
+    }
+
+    #[test]
+    fn test_suppress_action_and_expiry() {
+        let cfg = store_config("suppress", "suppress");
+        let ip: IpAddr = "10.0.0.6".parse().unwrap();
+        let store = FalsePositiveStore::open(&cfg).unwrap();
+        store.mark(&Alert::new(ScanType::AcceptScan, ip, None), None).unwrap();
+
+        let mut alert = Alert::new(ScanType::AcceptScan, ip, None);
+        let suppression = store.apply(&mut alert).expect("alerta trebuie suprimata");
+        assert_eq!(suppression.rule, "false_positive");
+
+        // Marcaj expirat (mai vechi de period_hours) → alerta trece normal.
+        store.lock()[0].marked_at -= chrono::Duration::hours(2);
+        assert!(store.apply(&mut alert).is_none());
+        assert!(!alert.false_positive);
+        std::fs::remove_file(&cfg.file).unwrap();
+    }
+}
@@ -0,0 +1,246 @@
+// =============================================================================
+// fifo.rs - Alerte JSON intr-un Named Pipe (output.fifo_path)
+// =============================================================================
+//
+// Punct de integrare simplu pentru consumatori locali, fara server HTTP:
+//
+//   mkfifo /run/ids-rs/alerts.fifo
+//   cat /run/ids-rs/alerts.fifo | consumator      (sau orice cititor)
+//
+// Fiecare alerta este scrisa ca O LINIE JSON (acelasi document ca in
+// `GET /api/alerts`). Pipe-ul este creat de consumator (`mkfifo`), nu de
+// IDS-RS — proprietarul si permisiunile raman decizia lui.
+//
+// Detectorul NU se blocheaza niciodata din cauza pipe-ului:
+//   - fara cititor conectat: deschiderea esueaza (ENXIO) → alerta pierduta
+//   - pipe plin (cititor lent): scrierea ar bloca (EAGAIN) → alerta pierduta
+//   - cititorul s-a deconectat (EPIPE): pipe-ul se redeschide la alerta urmatoare
+// Alertele pierdute sunt numarate si raportate periodic in linia de statistici.
+//
+// NOTA RUST: `tokio::net::unix::pipe::OpenOptions` deschide FIFO-ul cu
+// O_NONBLOCK si verifica tipul fisierului (refuza un fisier obisnuit). Apoi
+// `into_nonblocking_fd` scoate descriptorul din reactor-ul tokio: un `File`
+// obisnuit cu O_NONBLOCK, la care `write` returneaza imediat `WouldBlock` in
+// loc sa astepte. `Sender::try_write` nu ar fi potrivit — raporteaza
+// `WouldBlock` pana cand reactor-ul proceseaza prima notificare de
+// disponibilitate, deci ar pierde prima alerta dupa fiecare conectare.
+//
+// =============================================================================
+
+use crate::detector::Alert;
+use std::fs::File;
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use tokio::net::unix::pipe;
+
+pub struct FifoSink {
+    path: PathBuf,
+    /// Capatul de scriere; `None` = niciun cititor conectat (se reincearca).
+    sender: Option<File>,
+    /// Restul unei linii scrise partial (linii > PIPE_BUF): trebuie terminata
+    /// inaintea urmatoarei alerte, altfel cititorul primeste JSON amestecat.
+    pending: Vec<u8>,
+    /// Alerte pierdute de la ultimul raport.
+    dropped: u64,
+    /// Avertismentul "fara cititor" a fost deja afisat (pana la reconectare).
+    warned: bool,
+}
+
+impl FifoSink {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: PathBuf::from(path),
+            sender: None,
+            pending: Vec::new(),
+            dropped: 0,
+            warned: false,
+        }
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    /// Scrie alerta in pipe sau o numara ca pierduta. Nu blocheaza niciodata.
+    pub fn send(&mut self, alert: &Alert) {
+        let Ok(mut line) = serde_json::to_vec(alert) else {
+            self.dropped += 1;
+            return;
+        };
+        line.push(b'\n');
+
+        if self.sender.is_none() && !self.connect() {
+            self.dropped += 1;
+            return;
+        }
+        // O linie ramasa partial are prioritate; daca nici ea nu incape,
+        // alerta noua este pierduta (pipe plin).
+        if !self.pending.is_empty() {
+            let pending = std::mem::take(&mut self.pending);
+            match self.write(&pending) {
+                Some(n) if n == pending.len() => {}
+                Some(n) => {
+                    self.pending = pending[n..].to_vec();
+                    self.dropped += 1;
+                    return;
+                }
+                None => {
+                    self.dropped += 1;
+                    return;
+                }
+            }
+        }
+        match self.write(&line) {
+            Some(n) if n < line.len() => self.pending = line[n..].to_vec(),
+            Some(_) => {}
+            None => self.dropped += 1,
+        }
+    }
+
+    /// Numarul de alerte pierdute de la ultimul apel (si il reseteaza).
+    pub fn take_dropped(&mut self) -> u64 {
+        std::mem::replace(&mut self.dropped, 0)
+    }
+
+    /// Deschide capatul de scriere. False daca nu exista cititor sau pipe.
+    fn connect(&mut self) -> bool {
+        let opened = pipe::OpenOptions::new()
+            .open_sender(&self.path)
+            .and_then(|sender| sender.into_nonblocking_fd());
+        match opened {
+            Ok(fd) => {
+                if self.warned {
+                    crate::display::log_info(&format!(
+                        "FIFO {}: cititor conectat",
+                        self.path.display()
+                    ));
+                }
+                self.sender = Some(File::from(fd));
+                self.warned = false;
+                true
+            }
+            Err(e) => {
+                if !self.warned {
+                    // ENXIO: FIFO deschis O_WRONLY|O_NONBLOCK fara cititor.
+                    let reason = if e.raw_os_error() == Some(libc::ENXIO) {
+                        "niciun cititor conectat".to_string()
+                    } else {
+                        e.to_string()
+                    };
+                    crate::display::log_warning(&format!(
+                        "FIFO {}: {} — alertele sunt pierdute pana la conectare",
+                        self.path.display(),
+                        reason
+                    ));
+                    self.warned = true;
+                }
+                false
+            }
+        }
+    }
+
+    /// Scriere non-blocanta: `Some(octeti scrisi)` sau `None` la pipe plin /
+    /// cititor deconectat (caz in care capatul de scriere este inchis).
+    fn write(&mut self, buf: &[u8]) -> Option<usize> {
+        let sender = self.sender.as_mut()?;
+        match sender.write(buf) {
+            Ok(n) => Some(n),
+            Err(e) if e.kind() == ErrorKind::WouldBlock => None,
+            Err(_) => {
+                // EPIPE: cititorul a inchis pipe-ul. Restul liniei nu mai are
+                // sens pentru un cititor nou.
+                self.sender = None;
+                self.pending.clear();
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::ScanType;
+
+    fn mkfifo(tag: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("ids-rs-fifo-{}-{}", tag, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let status = std::process::Command::new("mkfifo").arg(&path).status().unwrap();
+        assert!(status.success());
+        path
+    }
+
+    #[tokio::test]
+    async fn test_fifo_drops_without_reader_and_writes_lines() {
+        let path = mkfifo("lines");
+
+        let mut sink = FifoSink::new(path.to_str().unwrap());
+        let alert = Alert::new(ScanType::Fast, "10.0.0.9".parse().unwrap(), None);
+
+        // Fara cititor: alerta este numarata, apelul nu blocheaza.
+        sink.send(&alert);
+        assert_eq!(sink.take_dropped(), 1);
+
+        // Cu cititor: o linie JSON per alerta.
+        let receiver = pipe::OpenOptions::new().open_receiver(&path).unwrap();
+        sink.send(&alert);
+        assert_eq!(sink.take_dropped(), 0);
+        receiver.readable().await.unwrap();
+        let mut buf = vec![0u8; 4096];
+        let n = receiver.try_read(&mut buf).unwrap();
+        let line = std::str::from_utf8(&buf[..n]).unwrap();
+        assert!(line.ends_with('\n'));
+        let json: serde_json::Value = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(json["source_ip"], "10.0.0.9");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fifo_reconnects_after_reader_leaves() {
+        let path = mkfifo("reconnect");
+        let mut sink = FifoSink::new(path.to_str().unwrap());
+        let alert = Alert::new(ScanType::Slow, "10.0.0.7".parse().unwrap(), None);
+
+        // Fara cititor: fiecare alerta este pierduta, avertismentul o singura data.
+        sink.send(&alert);
+        sink.send(&alert);
+        assert_eq!(sink.take_dropped(), 2);
+        assert!(sink.warned);
+        assert!(sink.sender.is_none());
+
+        let receiver = pipe::OpenOptions::new().open_receiver(&path).unwrap();
+        sink.send(&alert);
+        assert_eq!(sink.take_dropped(), 0);
+        assert!(!sink.warned, "conectarea reseteaza avertismentul");
+        drop(receiver);
+
+        // Cititorul a plecat (EPIPE): alerta pierduta, capatul de scriere inchis.
+        sink.send(&alert);
+        assert_eq!(sink.take_dropped(), 1);
+        assert!(sink.sender.is_none());
+
+        // Un cititor nou primeste doar alertele de dupa conectare.
+        let receiver = pipe::OpenOptions::new().open_receiver(&path).unwrap();
+        sink.send(&alert);
+        assert_eq!(sink.take_dropped(), 0);
+        receiver.readable().await.unwrap();
+        let mut buf = vec![0u8; 4096];
+        let n = receiver.try_read(&mut buf).unwrap();
+        assert_eq!(std::str::from_utf8(&buf[..n]).unwrap().lines().count(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_fifo_rejects_regular_file() {
+        let path = std::env::temp_dir().join(format!("ids-rs-fifo-plain-{}", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+        let mut sink = FifoSink::new(path.to_str().unwrap());
+        sink.send(&Alert::new(ScanType::Fast, "10.0.0.9".parse().unwrap(), None));
+        assert_eq!(sink.take_dropped(), 1);
+        assert!(sink.sender.is_none());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "", "fisierul obisnuit nu este scris");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,267 @@
+// =============================================================================
+// incidents.rs - Gruparea Alertelor in Incidente ([incidents])
+// =============================================================================
+//
+// O campanie de scanare produce zeci de alerte: Fast Scan, apoi Accept Scan,
+// apoi Lateral Movement de pe aceeasi sursa. Analistul vrea sa le trieze
+// impreuna, nu alerta cu alerta. Un incident grupeaza toate alertele emise
+// pentru aceeasi cheie de campanie:
+//
+//   group_by = "source"  → IP-ul sursa (Coordinated Subnet: blocul agregat,
+//                          Distributed Scan: tinta comuna, "* → <tinta>")
+//   group_by = "subnet"  → blocul /24 (IPv4) sau /64 (IPv6) al sursei
+//
+// Incidentul retine momentul primei alerte, ultima activitate, severitatea
+// maxima (cea CEF, inclusiv escaladarea [assets]) si cele mai recente
+// `max_alerts_per_incident` alerte. Expus prin dashboard-ul web:
+//
+//   GET  /incidents             → incidentele deschise (cele mai recente primele)
+//   POST /incidents/{id}/close  → rezolvare; urmatoarea alerta a campaniei
+//                                 deschide un incident NOU
+//
+// Un incident fara alerte noi `idle_timeout_secs` este inchis automat. Cu
+// `close_on_scan_end`, alerta Scan Ended (`detection.scan_end_silence_secs`)
+// il inchide imediat — capatul episodului este si capatul incidentului.
+// Incidentele traiesc doar in memorie — un restart porneste de la zero.
+//
+// NOTA RUST: tracker-ul este partajat intre main loop (scriere la fiecare
+// alerta emisa) si serverul web (citire / inchidere) prin `Arc<IncidentTracker>`,
+// ca `FalsePositiveStore`. Lock-ul este tinut doar cat dureaza actualizarea.
+//
+// =============================================================================
+
+use crate::config::IncidentsConfig;
+use crate::detector::{self, Alert, ScanType};
+use crate::display;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+/// Prefixele pentru `group_by = "subnet"`.
+const SUBNET_PREFIX_V4: u8 = 24;
+const SUBNET_PREFIX_V6: u8 = 64;
+
+/// Un incident: alertele aceleiasi campanii.
+#[derive(Debug, Clone, Serialize)]
+pub struct Incident {
+    pub id: u64,
+    /// Cheia campaniei (ex: "203.0.113.7", "203.0.113.0/24", "* → 10.0.0.5").
+    pub campaign: String,
+    /// "open" sau "closed".
+    pub status: &'static str,
+    pub started_at: DateTime<Local>,
+    pub last_seen: DateTime<Local>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Local>>,
+    /// Severitatea maxima (CEF 0-10) a alertelor incidentului.
+    pub severity: u8,
+    /// Toate alertele grupate, inclusiv cele care nu mai sunt in `alerts`.
+    pub alert_count: usize,
+    pub scan_types: BTreeSet<String>,
+    /// Cele mai recente `max_alerts_per_incident` alerte, in ordinea emiterii.
+    pub alerts: Vec<Alert>,
+}
+
+/// Incidentele deschise, cautate dupa cheia campaniei.
+pub struct IncidentTracker {
+    state: Mutex<TrackerState>,
+    by_subnet: bool,
+    idle: chrono::Duration,
+    max_alerts: usize,
+    max_open: usize,
+    close_on_scan_end: bool,
+}
+
+struct TrackerState {
+    next_id: u64,
+    open: Vec<Incident>,
+}
+
+impl IncidentTracker {
+    pub fn new(cfg: &IncidentsConfig) -> Self {
+        Self {
+            state: Mutex::new(TrackerState {
+                next_id: 1,
+                open: Vec::new(),
+            }),
+            by_subnet: cfg.group_by == "subnet",
+            idle: chrono::Duration::seconds(cfg.idle_timeout_secs as i64),
+            max_alerts: cfg.max_alerts_per_incident,
+            max_open: cfg.max_open,
+            close_on_scan_end: cfg.close_on_scan_end,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TrackerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Cheia campaniei unei alerte (vezi antetul modulului).
+    fn campaign(&self, alert: &Alert) -> String {
+        if self.by_subnet {
+            let prefix = detector::source_prefix(alert.source_ip, SUBNET_PREFIX_V4, SUBNET_PREFIX_V6);
+            let len = if prefix.is_ipv4() { SUBNET_PREFIX_V4 } else { SUBNET_PREFIX_V6 };
+            return format!("{}/{}", prefix, len);
+        }
+        match (&alert.scan_type, &alert.source_prefix, alert.dest_ip) {
+            (ScanType::CoordinatedSubnet, Some(prefix), _) => prefix.clone(),
+            (ScanType::DistributedScan, _, Some(dest)) => format!("* → {}", dest),
+            _ => alert.source_ip.to_string(),
+        }
+    }
+
+    /// Adauga alerta la incidentul deschis al campaniei ei (sau deschide unul).
+    /// Scan Ended cu `close_on_scan_end` inchide incidentul si nu deschide altul.
+    pub fn record(&self, alert: &Alert) {
+        let severity = alert.effective_severity(display::scan_style(&alert.scan_type).severity.0);
+        let campaign = self.campaign(alert);
+        let closes = self.close_on_scan_end && matches!(alert.scan_type, ScanType::ScanEnded);
+        let mut state = self.lock();
+        self.expire(&mut state, alert.timestamp);
+
+        if let Some(pos) = state.open.iter().position(|i| i.campaign == campaign) {
+            if closes {
+                state.open.remove(pos);
+                return;
+            }
+            let incident = &mut state.open[pos];
+            incident.last_seen = alert.timestamp;
+            incident.severity = incident.severity.max(severity);
+            incident.alert_count += 1;
+            incident.scan_types.insert(alert.scan_type.to_string());
+            incident.alerts.push(alert.clone());
+            if incident.alerts.len() > self.max_alerts {
+                incident.alerts.remove(0);
+            }
+            return;
+        }
+        if closes {
+            return;
+        }
+
+        if state.open.len() >= self.max_open {
+            // Plafon: cedeaza locul incidentul cu cea mai veche activitate.
+            if let Some(oldest) = (0..state.open.len()).min_by_key(|&i| state.open[i].last_seen) {
+                state.open.swap_remove(oldest);
+            }
+        }
+        let id = state.next_id;
+        state.next_id += 1;
+        state.open.push(Incident {
+            id,
+            campaign,
+            status: "open",
+            started_at: alert.timestamp,
+            last_seen: alert.timestamp,
+            closed_at: None,
+            severity,
+            alert_count: 1,
+            scan_types: BTreeSet::from([alert.scan_type.to_string()]),
+            alerts: vec![alert.clone()],
+        });
+    }
+
+    /// Incidentele deschise la momentul `now`, cele mai recent active primele.
+    pub fn open_incidents(&self, now: DateTime<Local>) -> Vec<Incident> {
+        let mut state = self.lock();
+        self.expire(&mut state, now);
+        let mut open = state.open.clone();
+        open.sort_by_key(|i| std::cmp::Reverse(i.last_seen));
+        open
+    }
+
+    /// Inchide incidentul `id`; `None` daca nu este deschis.
+    pub fn close(&self, id: u64, now: DateTime<Local>) -> Option<Incident> {
+        let mut state = self.lock();
+        let pos = state.open.iter().position(|i| i.id == id)?;
+        let mut incident = state.open.remove(pos);
+        incident.status = "closed";
+        incident.closed_at = Some(now);
+        Some(incident)
+    }
+
+    /// Inchide tacut incidentele inactive de peste `idle_timeout_secs`.
+    fn expire(&self, state: &mut TrackerState, now: DateTime<Local>) {
+        state.open.retain(|i| now.signed_duration_since(i.last_seen) < self.idle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert_at(scan_type: ScanType, ip: &str, at: DateTime<Local>) -> Alert {
+        Alert {
+            timestamp: at,
+            ..Alert::new(scan_type, ip.parse().unwrap(), Some("10.0.0.5".parse().unwrap()))
+        }
+    }
+
+    #[test]
+    fn test_incidents_group_by_campaign_close_and_expire() {
+        let cfg = IncidentsConfig {
+            enabled: true,
+            max_alerts_per_incident: 2,
+            ..IncidentsConfig::default()
+        };
+        let tracker = IncidentTracker::new(&cfg);
+        let t0 = Local::now();
+        let at = |secs: i64| t0 + chrono::Duration::seconds(secs);
+
+        tracker.record(&alert_at(ScanType::Fast, "203.0.113.7", at(0)));
+        tracker.record(&alert_at(ScanType::AcceptScan, "203.0.113.7", at(10)));
+        tracker.record(&alert_at(ScanType::LateralMovement, "203.0.113.7", at(20)));
+        tracker.record(&alert_at(ScanType::Fast, "198.51.100.9", at(30)));
+        tracker.record(&alert_at(ScanType::DistributedScan, "198.51.100.9", at(40)));
+
+        let open = tracker.open_incidents(at(60));
+        assert_eq!(
+            open.iter().map(|i| i.campaign.as_str()).collect::<Vec<_>>(),
+            vec!["* → 10.0.0.5", "198.51.100.9", "203.0.113.7"]
+        );
+        let campaign = &open[2];
+        assert_eq!(campaign.alert_count, 3);
+        assert_eq!(campaign.alerts.len(), 2, "plafon max_alerts_per_incident");
+        assert_eq!(campaign.started_at, at(0));
+        assert_eq!(campaign.last_seen, at(20));
+        assert_eq!(campaign.scan_types.len(), 3);
+        let max_severity = [ScanType::Fast, ScanType::AcceptScan, ScanType::LateralMovement]
+            .iter()
+            .map(|t| display::scan_style(t).severity.0)
+            .max()
+            .unwrap();
+        assert_eq!(campaign.severity, max_severity);
+
+        // Inchidere: o alerta noua a campaniei deschide alt incident.
+        let closed = tracker.close(campaign.id, at(70)).unwrap();
+        assert_eq!(closed.status, "closed");
+        assert!(tracker.close(campaign.id, at(70)).is_none());
+        tracker.record(&alert_at(ScanType::Fast, "203.0.113.7", at(80)));
+        let reopened = tracker.open_incidents(at(80));
+        let new = reopened.iter().find(|i| i.campaign == "203.0.113.7").unwrap();
+        assert_ne!(new.id, closed.id);
+        assert_eq!(new.alert_count, 1);
+
+        // Scan Ended inchide incidentul campaniei, fara sa deschida altul.
+        tracker.record(&alert_at(ScanType::ScanEnded, "203.0.113.7", at(90)));
+        assert!(tracker.open_incidents(at(90)).iter().all(|i| i.campaign != "203.0.113.7"));
+        tracker.record(&alert_at(ScanType::ScanEnded, "203.0.113.7", at(95)));
+        assert!(tracker.open_incidents(at(95)).iter().all(|i| i.campaign != "203.0.113.7"));
+
+        // Inactivitate peste idle_timeout_secs → inchis automat.
+        assert!(tracker.open_incidents(at(80 + 3600)).is_empty());
+
+        // group_by = "subnet": sursele aceluiasi /24 intr-un singur incident.
+        let subnet = IncidentTracker::new(&IncidentsConfig {
+            group_by: "subnet".to_string(),
+            ..cfg
+        });
+        subnet.record(&alert_at(ScanType::Fast, "203.0.113.7", at(0)));
+        subnet.record(&alert_at(ScanType::Fast, "203.0.113.8", at(1)));
+        let open = subnet.open_incidents(at(2));
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].campaign, "203.0.113.0/24");
+        assert_eq!(open[0].alert_count, 2);
+    }
+}
@@ -49,24 +49,46 @@
 // =============================================================================
 
 mod alerter;
+mod alertfile;
+mod assetmap;
+mod classify;
+mod cli;
 mod config;
+mod control;
+mod correlation;
+mod deadletter;
 mod detector;
 mod display;
+mod feedback;
+mod fifo;
+mod incidents;
+mod metrics;
+mod nat;
+mod netns;
 mod parser;
+mod recorder;
+mod rollup;
+mod rules;
+mod secrets;
+mod sentinel;
+mod startup;
+mod torexit;
 mod web;
 
 use alerter::Alerter;
 use arc_swap::ArcSwap;
-use config::{AppConfig, SubnetEntry};
+use config::{AppConfig, StartupConfig, SubnetEntry};
 use detector::Detector;
+use metrics::Subsystem;
 use std::collections::{HashMap, VecDeque};
 use std::net::IpAddr;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::net::UdpSocket;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::Notify;
 
 // =============================================================================
 // TokenBucket — Rate Limiter pentru receptie UDP
@@ -151,6 +173,188 @@ impl TokenBucket {
     }
 }
 
+// =============================================================================
+// EventDeduplicator — eliminare copii identice (SPAN/mirror bidirectional)
+// =============================================================================
+//
+// Cand traficul ambelor directii ale unui link (sau doi forwarderi) ajunge la
+// aceeasi instanta, acelasi eveniment de firewall poate sosi de doua ori la
+// cateva milisecunde distanta — contoarele de porturi/destinatii se dubleaza.
+//
+// Log-urile de firewall nu au numar de secventa TCP, deci cheia de dedup este
+// hash-ul tuplului (src, dst, dport, proto, action). Doua evenimente cu aceeasi
+// cheie in `window` sunt considerate copii; a doua este eliminata INAINTE de
+// detectie. Pentru unique-port counting este inofensiv: o repetare legitima
+// in cateva ms nu adauga un port nou.
+//
+// NOTA RUST - DefaultHasher:
+// Hash-uim tuplul intr-un u64 in loc sa stocam cheia completa (String-uri
+// pentru proto/action) — HashMap<u64, Instant> = 16 bytes per intrare.
+// Coliziunile (probabilitate ~2^-64) ar elimina un eveniment legitim, acceptabil.
+//
+// =============================================================================
+
+struct EventDeduplicator {
+    window: Duration,
+    seen: HashMap<u64, Instant>,
+    last_prune: Instant,
+    duplicate_count: u64, // contor copii eliminate (pentru afisare periodica)
+}
+
+impl EventDeduplicator {
+    fn new(window_ms: u64) -> Self {
+        Self {
+            window: Duration::from_millis(window_ms),
+            seen: HashMap::new(),
+            last_prune: Instant::now(),
+            duplicate_count: 0,
+        }
+    }
+
+    /// Returneaza `true` daca evenimentul este o copie a unuia vazut in fereastra.
+    fn is_duplicate(&mut self, event: &parser::LogEvent) -> bool {
+        use std::hash::{Hash, Hasher};
+
+        let now = Instant::now();
+
+        // Prune periodic (o data per fereastra): map-ul contine doar cheile
+        // din ultima fereastra — cateva mii de intrari chiar sub flood.
+        if now.duration_since(self.last_prune) >= self.window {
+            let window = self.window;
+            self.seen.retain(|_, seen_at| now.duration_since(*seen_at) < window);
+            self.last_prune = now;
+        }
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (
+            event.source_ip,
+            event.dest_ip,
+            event.dest_port,
+            &event.protocol,
+            &event.action,
+        )
+            .hash(&mut hasher);
+        let key = hasher.finish();
+
+        // Momentul PRIMEI aparitii ramane cheia ferestrei: o copie nu o
+        // prelungeste — altfel un flux continuu de evenimente identice ar fi
+        // suprimat la nesfarsit.
+        match self.seen.entry(key) {
+            std::collections::hash_map::Entry::Occupied(mut first)
+                if now.duration_since(*first.get()) >= self.window =>
+            {
+                first.insert(now);
+                false
+            }
+            std::collections::hash_map::Entry::Occupied(_) => {
+                self.duplicate_count += 1;
+                true
+            }
+            std::collections::hash_map::Entry::Vacant(slot) => {
+                slot.insert(now);
+                false
+            }
+        }
+    }
+
+    /// Returneaza si reseteaza contorul de copii eliminate.
+    fn take_duplicate_count(&mut self) -> u64 {
+        std::mem::replace(&mut self.duplicate_count, 0)
+    }
+}
+
+// =============================================================================
+// PARSE ERROR SAMPLER - Contor + exemplu hex al liniilor neparsabile
+// =============================================================================
+//
+// Liniile care nu se potrivesc parser-ului activ sunt ignorate silentios
+// (doar in mod debug apar individual). Cu `network.parse_error_report`,
+// le numaram si pastram PRIMA linie din fereastra ca exemplu hex — o data
+// pe minut emitem un singur warning. Hex (nu text) pentru ca octetii
+// problematici sunt adesea invizibili: BOM, NUL, CR, encoding gresit.
+//
+// =============================================================================
+
+/// Numarul maxim de octeti pastrati in exemplul hex.
+const PARSE_ERROR_SAMPLE_BYTES: usize = 32;
+
+struct ParseErrorSampler {
+    count: u64,
+    sample: Option<String>,
+    window_start: Instant,
+}
+
+impl ParseErrorSampler {
+    fn new() -> Self {
+        Self {
+            count: 0,
+            sample: None,
+            window_start: Instant::now(),
+        }
+    }
+
+    /// Inregistreaza o linie neparsabila (doar prima din fereastra e esantionata).
+    fn record(&mut self, line: &str) {
+        self.count += 1;
+        if self.sample.is_none() {
+            let bytes = line.as_bytes();
+            let shown = &bytes[..bytes.len().min(PARSE_ERROR_SAMPLE_BYTES)];
+            let mut hex = shown
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if bytes.len() > PARSE_ERROR_SAMPLE_BYTES {
+                hex.push_str(&format!(" ... ({} octeti)", bytes.len()));
+            }
+            self.sample = Some(hex);
+        }
+    }
+
+    /// Dupa cel putin un minut: returneaza (erori, secunde, exemplu) si reseteaza.
+    /// `None` daca fereastra nu a expirat sau nu au fost erori.
+    fn take_report(&mut self) -> Option<(u64, u64, String)> {
+        let elapsed = self.window_start.elapsed();
+        if elapsed < Duration::from_secs(60) {
+            return None;
+        }
+        self.window_start = Instant::now();
+        let count = std::mem::replace(&mut self.count, 0);
+        let sample = self.sample.take()?;
+        Some((count, elapsed.as_secs(), sample))
+    }
+}
+
+// =============================================================================
+// CleanupCounter — cleanup declansat de volum (detection.cleanup_every_n_packets)
+// =============================================================================
+
+/// Numara pachetele procesate de la ultima trecere de cleanup declansata de volum.
+struct CleanupCounter {
+    packets: u64,
+}
+
+impl CleanupCounter {
+    fn new() -> Self {
+        Self { packets: 0 }
+    }
+
+    /// Numara un pachet; `true` la fiecare al `every_n`-lea (0 = dezactivat).
+    /// Pragul este citit la fiecare pachet — o valoare noua dupa SIGHUP se
+    /// aplica imediat, fara a pierde pachetele deja numarate.
+    fn tick(&mut self, every_n: u64) -> bool {
+        if every_n == 0 {
+            return false;
+        }
+        self.packets += 1;
+        if self.packets >= every_n {
+            self.packets = 0;
+            return true;
+        }
+        false
+    }
+}
+
 /// Punctul de intrare al aplicatiei.
 ///
 /// NOTA RUST: `-> anyhow::Result<()>`
@@ -194,15 +398,35 @@ async fn main() -> anyhow::Result<()> {
     // 2. INCARCARE CONFIGURARE
     // =========================================================================
     //
-    // NOTA RUST: `std::env::args()` returneaza un iterator peste argumentele
-    // liniei de comanda. `.nth(1)` returneaza al doilea argument (index 0 = exe).
-    // `.unwrap_or_else` ofera o valoare default daca nu exista argument.
+    // Argumentele liniei de comanda sunt parsate in cli.rs. Primul argument
+    // pozitional este calea configurarii (implicit "config.toml").
+    // Flag-urile informative (--help, --legend) ies INAINTE de incarcarea
+    // configurarii — functioneaza si fara un config.toml valid.
     //
-    let config_path = std::env::args()
-        .nth(1)
-        .unwrap_or_else(|| "config.toml".to_string());
+    let cli = cli::CliArgs::from_env()?;
+    if cli.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    if cli.legend {
+        display::print_legend();
+        return Ok(());
+    }
+    let config_path = cli.config_path;
+    let explain_suppressions = cli.explain_suppressions;
+    let diagnostics_report = cli.diagnostics_report;
+
+    // Dependinte asteptate inainte de initializare (startup.wait_for): colectorul
+    // SIEM, fisierele montate de alt container (enrichment, secrets_file,
+    // replay_dir, rules_dir). Asteptarea ruleaza INAINTE de `AppConfig::load`,
+    // care cere deja ca aceste cai sa existe — doar `[startup]` este citit aici.
+    // Dashboard-ul si metricile pornesc abia dupa — readiness-ul lor inseamna
+    // "detectie activa".
+    startup::wait_for_dependencies(&StartupConfig::peek(&config_path)?).await?;
 
     let mut config = AppConfig::load(&config_path)?;
+    display::configure(&config.display);
+    display::configure_output(&config.output);
 
     // =========================================================================
     // 3. BANNER DE START
@@ -213,6 +437,12 @@ async fn main() -> anyhow::Result<()> {
     if debug_mode {
         display::log_warning("Mod DEBUG activ - toate pachetele vor fi afisate");
     }
+    if let Some(ref secrets_file) = config.secrets_file {
+        display::log_info(&format!("Secrete interpolate din {}", secrets_file));
+    }
+    for warning in config.deprecations.iter().chain(&config.overlap_warnings()) {
+        display::log_warning(warning);
+    }
 
     // =========================================================================
     // 4. INITIALIZARE COMPONENTE
@@ -265,13 +495,90 @@ async fn main() -> anyhow::Result<()> {
         &config.network.subnets,
     )));
 
+    // Harta de active din CMDB ([enrichment]): o eroare la pornire este fatala.
+    let asset_map = Arc::new(assetmap::AssetMap::load(&config.enrichment.asset_map_path)?);
+    if !asset_map.is_empty() {
+        display::log_info(&format!(
+            "Harta de active: {} intrari din {}",
+            asset_map.len(),
+            config.enrichment.asset_map_path
+        ));
+    }
+
+    // Noduri de iesire Tor ([enrichment]): fisier ilizibil la pornire = fatal,
+    // intrari invalide = WARN. Verificat la 30s, reincarcat la schimbare.
+    let (tor_list, tor_warning) = torexit::TorExitList::load(&config.enrichment.tor_exit_list_path)?;
+    if let Some(w) = tor_warning {
+        display::log_warning(&w);
+    }
+    if !config.enrichment.tor_exit_list_path.is_empty() && tor_list.is_empty() {
+        display::log_warning(&format!(
+            "Lista Tor {} nu contine niciun IP — nicio alerta nu va fi marcata tor_exit",
+            config.enrichment.tor_exit_list_path
+        ));
+    } else if !config.enrichment.tor_exit_list_path.is_empty() {
+        display::log_info(&format!(
+            "Noduri de iesire Tor: {} IP-uri din {}",
+            tor_list.len(),
+            config.enrichment.tor_exit_list_path
+        ));
+    }
+    let tor_exits = ArcSwap::from_pointee(tor_list);
+    let mut tor_watcher = (!config.enrichment.tor_exit_list_path.is_empty())
+        .then(|| torexit::TorExitWatcher::new(&config.enrichment.tor_exit_list_path));
+
     let detector = Arc::new(Detector::new(config.detection.clone()));
+    detector.set_inbound_only(config.network.inbound_only());
+    detector.set_assets(&config.assets);
+    detector.set_asset_map(Arc::clone(&asset_map));
+    detector.set_summary_template(&config.alerting.summary_template);
+    detector.set_detection_context(config.alerting.detection_context);
+    if config.network.inbound_only() {
+        display::log_warning(
+            "Trafic doar inbound: Accept Scan si Beaconing dezactivate (necesita ambele sensuri)",
+        );
+    }
+    // Profiluri de detectie suplimentare ([profiles.<nume>]): cate un Detector
+    // cu praguri, ferestre si cooldown-uri proprii, alimentat cu acelasi flux
+    // de evenimente. Harta de urmarire a porturilor este partajata cu primul
+    // detector anterior (implicit sau profil) cu aceiasi parametri de urmarire
+    // — evenimentele sunt evaluate in aceeasi ordine, deci sursa hartii
+    // inregistreaza hit-ul inaintea profilului care doar il citeste.
+    let mut profile_detectors: Vec<(String, Arc<Detector>)> = Vec::new();
+    for (name, profile) in &config.profiles {
+        let d = Detector::new(profile.detection.clone());
+        d.set_inbound_only(config.network.inbound_only());
+        d.set_assets(&config.assets);
+        d.set_asset_map(Arc::clone(&asset_map));
+        d.set_summary_template(&config.alerting.summary_template);
+        d.set_detection_context(config.alerting.detection_context);
+        let shared_with = std::iter::once((config::DEFAULT_PROFILE, &detector))
+            .chain(profile_detectors.iter().map(|(n, d)| (n.as_str(), d)))
+            .find(|(_, source)| d.share_tracking(source))
+            .map(|(source, _)| format!(", harta de urmarire partajata cu '{}'", source))
+            .unwrap_or_default();
+        display::log_info(&format!(
+            "Profil de detectie '{}': sink-uri [{}], politica {}{}",
+            name,
+            profile.sinks.join(", "),
+            d.policy_version(),
+            shared_with
+        ));
+        profile_detectors.push((name.clone(), Arc::new(d)));
+    }
+
+    // Contoarele de rulare sunt create INTOTDEAUNA, inaintea Alerter-ului:
+    // acesta raporteaza erorile sink-urilor in `counters.last_errors`.
+    let counters = Arc::new(metrics::RuntimeCounters::new());
+    counters.latency.set_enabled(config.metrics.latency_histogram);
     let alerter = Arc::new(Alerter::new(
         config.alerting.clone(),
         config.detection.clone(),
         parse_hostnames(&config),
         SubnetEntry::parse_subnets(&config.network.subnets),
+        Arc::clone(&counters.last_errors),
     )?);
+    alerter.set_profiles(&config.profiles);
 
     let (alert_tx, mut alert_rx) = mpsc::channel(1024);
     let alert_worker_alerter = Arc::clone(&alerter);
@@ -298,9 +605,58 @@ async fn main() -> anyhow::Result<()> {
     //
     let alert_buffer: web::AlertBuffer = Arc::new(Mutex::new(VecDeque::new()));
 
+    // Marcaje fals-pozitiv ([false_positives]): incarcate la pornire din fisier,
+    // scrise de dashboard (POST), aplicate alertelor in main loop. Un fisier
+    // corupt opreste pornirea — altfel marcajele operatorilor s-ar pierde tacut.
+    let false_positives: Option<Arc<feedback::FalsePositiveStore>> =
+        if config.false_positives.enabled {
+            let store = feedback::FalsePositiveStore::open(&config.false_positives)?;
+            display::log_info(&format!(
+                "Feedback fals-pozitiv activ: {} marcaje incarcate din {} (actiune: {}, {}h)",
+                store.len(),
+                config.false_positives.file,
+                config.false_positives.action,
+                config.false_positives.period_hours
+            ));
+            Some(Arc::new(store))
+        } else {
+            None
+        };
+
+    // Incidente ([incidents]): alertele emise grupate pe campanie, expuse prin
+    // dashboard (GET /incidents). Doar in memorie; citit la pornire.
+    let incidents: Option<Arc<incidents::IncidentTracker>> = if config.incidents.enabled {
+        display::log_info(&format!(
+            "Grupare in incidente activa: dupa {} (inchidere automata dupa {}s de inactivitate)",
+            config.incidents.group_by, config.incidents.idle_timeout_secs
+        ));
+        if !config.web_dashboard.enabled {
+            display::log_warning(
+                "incidents.enabled fara web_dashboard.enabled: GET /incidents nu este disponibil",
+            );
+        }
+        Some(Arc::new(incidents::IncidentTracker::new(&config.incidents)))
+    } else {
+        None
+    };
+
+    // Configuratia activa pentru dashboard si socket-ul de control: inlocuita
+    // la fiecare SIGHUP reusit, ca validarea unui prag nou sa vada config-ul
+    // curent, nu pe cel de la pornire.
+    let live_config = Arc::new(ArcSwap::from_pointee(config.clone()));
+
     if config.web_dashboard.enabled {
         let web_alerts = Arc::clone(&alert_buffer);
-        match web::start_web_server(&config.web_dashboard, web_alerts).await {
+        match web::start_web_server(
+            Arc::clone(&live_config),
+            web_alerts,
+            Arc::clone(&detector),
+            Arc::clone(&alerter),
+            false_positives.clone(),
+            incidents.clone(),
+        )
+        .await
+        {
             Ok(_handle) => {}
             Err(e) => {
                 display::log_warning(&format!("Web dashboard nu a pornit: {:#}", e));
@@ -308,6 +664,47 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    // Socket de control local ([control]): get-thresholds / set-threshold.
+    // Un esec la creare nu opreste senzorul — detectia nu depinde de el.
+    if !config.control.socket_path.is_empty() {
+        let started = control::start_control_socket(
+            Arc::clone(&live_config),
+            Arc::clone(&detector),
+            Arc::clone(&alerter),
+        )
+        .await;
+        if let Err(e) = started {
+            display::log_warning(&format!("Socket de control nu a pornit: {:#}", e));
+        }
+    }
+
+    // =========================================================================
+    // 4c. METRICI PROMETHEUS
+    // =========================================================================
+    //
+    // Server HTTP separat (port dedicat) pentru scrape Prometheus. Primeste
+    // Arc<Detector> — citeste config-ul activ la fiecare scrape, deci pragurile
+    // exportate reflecta automat reload-urile SIGHUP.
+    //
+    // Contoarele de rulare (create mai sus, inaintea Alerter-ului) alimenteaza
+    // si sumarul de la oprire, nu doar `/stats`.
+
+    if config.metrics.enabled {
+        match metrics::start_metrics_server(
+            &config.metrics,
+            Arc::clone(&detector),
+            Arc::clone(&alert_buffer),
+            Arc::clone(&counters),
+        )
+        .await
+        {
+            Ok(_handle) => {}
+            Err(e) => {
+                display::log_warning(&format!("Server metrici nu a pornit: {:#}", e));
+            }
+        }
+    }
+
     // =========================================================================
     // 5. TASK CLEANUP PERIODIC (Background Async Task)
     // =========================================================================
@@ -327,25 +724,102 @@ async fn main() -> anyhow::Result<()> {
     // `detector` original ramane valid (Arc separat) pentru main loop.
     //
     let cleanup_detector = Arc::clone(&detector);
+    let cleanup_profiles: Vec<Arc<Detector>> =
+        profile_detectors.iter().map(|(_, d)| Arc::clone(d)).collect();
+    let cleanup_counters = Arc::clone(&counters);
     let cleanup_interval = config.cleanup.interval_secs;
     let max_age = config.cleanup.max_entry_age_secs;
 
+    // Declansator de cleanup dupa volum (`detection.cleanup_every_n_packets`).
+    //
+    // NOTA RUST - tokio::sync::Notify: main loop-ul apeleaza `notify_one()`
+    // dupa N pachete; task-ul de cleanup asteapta `notified()` in paralel cu
+    // timer-ul. Notify pastreaza cel mult UN permis — notificarile repetate
+    // cat timp cleanup-ul ruleaza nu se aduna intr-o coada.
+    let mut quiet_stats = metrics::QuietStats::new(&config.output);
+    let cleanup_trigger = Arc::new(Notify::new());
+    let cleanup_notified = Arc::clone(&cleanup_trigger);
+
     tokio::spawn(async move {
         // NOTA RUST: `tokio::time::interval()` face primul tick IMEDIAT la creare,
         // ceea ce ar rula un cleanup inutil la startup (cand memoria e goala).
         // Folosim `sleep` intr-un loop simplu: asteapta intai, curata dupa.
         // Pattern: sleep-first loop garanteaza ca primul cleanup are loc abia
         // dupa `cleanup_interval` secunde de la pornire.
+        let mut last_compact = Instant::now();
         loop {
-            tokio::time::sleep(Duration::from_secs(cleanup_interval)).await;
+            // Primul declansator castiga: timer-ul sau N pachete procesate.
+            // Trecerea declansata de volum doar elibereaza memoria — fara
+            // compactare si fara linia STAT (ar inunda consola sub burst).
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(cleanup_interval)) => {}
+                _ = cleanup_notified.notified() => {
+                    cleanup_detector.cleanup(Duration::from_secs(max_age));
+                    for d in &cleanup_profiles {
+                        d.cleanup(Duration::from_secs(max_age));
+                    }
+                    continue;
+                }
+            }
 
             let tracked_before = cleanup_detector.tracked_ips();
             cleanup_detector.cleanup(Duration::from_secs(max_age));
             let tracked_after = cleanup_detector.tracked_ips();
+            for d in &cleanup_profiles {
+                d.cleanup(Duration::from_secs(max_age));
+            }
+
+            // Compactare periodica (detection.compact_interval_secs > 0).
+            // Intervalul e citit din config-ul detectorului la fiecare ciclu,
+            // deci o modificare prin SIGHUP se aplica fara restart.
+            let compact_secs = cleanup_detector.config().compact_interval_secs;
+            let reclaimed = if compact_secs > 0
+                && last_compact.elapsed() >= Duration::from_secs(compact_secs)
+            {
+                last_compact = Instant::now();
+                cleanup_detector.compact()
+                    + cleanup_profiles.iter().map(|d| d.compact()).sum::<usize>()
+            } else {
+                0
+            };
+
+            // Fereastra percentilelor de latenta = un ciclu de cleanup.
+            let latency = if cleanup_counters.latency.is_enabled() {
+                cleanup_counters.latency.roll()
+            } else {
+                None
+            };
 
             let cleaned = tracked_before.saturating_sub(tracked_after);
-            if tracked_after > 0 || cleaned > 0 {
-                display::log_stats(tracked_after, cleaned);
+            // Contabilitatea pachetelor INAINTEA filtrului de retea linistita:
+            // un ciclu fara evenimente este exact cel in care operatorul cauta
+            // unde s-au pierdut pachetele.
+            if diagnostics_report {
+                display::log_diagnostics_report(
+                    &cleanup_counters.packets.report(&cleanup_counters, &cleanup_detector),
+                );
+            }
+            // Retea linistita (output.suppress_empty_stats): heartbeat rar in loc de STAT.
+            match quiet_stats.check(
+                cleanup_counters.events_processed.load(Ordering::Relaxed),
+                cleanup_counters.alerts_generated.load(Ordering::Relaxed),
+                Instant::now(),
+            ) {
+                metrics::StatsReport::Full => {}
+                metrics::StatsReport::Heartbeat { quiet_secs } => {
+                    display::log_stats_quiet(quiet_secs, tracked_after);
+                    continue;
+                }
+                metrics::StatsReport::Skip => continue,
+            }
+            if tracked_after > 0 || cleaned > 0 || reclaimed > 0 || latency.is_some() {
+                display::log_stats(
+                    tracked_after,
+                    cleaned,
+                    reclaimed,
+                    &cleanup_counters.alerts_by_hour(),
+                    latency,
+                );
             }
         }
     });
@@ -368,8 +842,39 @@ async fn main() -> anyhow::Result<()> {
         "{}:{}",
         config.network.listen_address, config.network.listen_port
     );
-    let socket = UdpSocket::bind(&bind_addr).await?;
+    // network.netns: socket-ul este creat in namespace-ul tenant-ului, apoi
+    // firul revine in namespace-ul de lansare (vezi netns.rs).
+    let socket = if config.network.netns.is_empty() {
+        UdpSocket::bind(&bind_addr).await?
+    } else {
+        let std_socket = netns::run_in(&config.network.netns, || {
+            let socket = std::net::UdpSocket::bind(&bind_addr)?;
+            socket.set_nonblocking(true)?;
+            Ok(socket)
+        })?;
+        display::log_info(&format!("Namespace de retea: {}", config.network.netns));
+        UdpSocket::from_std(std_socket)?
+    };
+    // Drop-urile kernel ale socket-ului (coada plina) intra in contabilitatea pachetelor.
+    counters.packets.set_socket(&socket);
     display::log_info(&format!("Ascult pe UDP {}", bind_addr));
+    // Eticheta `ingress` a alertelor: segmentul de retea de pe care a sosit feed-ul.
+    let ingress = config.network.ingress_label();
+    // Interfata / VLAN din log-ul firewall-ului (network.interface_metadata).
+    let interface_metadata = config.network.interface_metadata;
+    // Clientul real din spatele NAT-ului (network.nat_source) — citit la pornire.
+    let nat_resolver = nat::NatResolver::new(&config.network.nat_source);
+    if nat_resolver.is_some() {
+        display::log_info(&format!(
+            "Sursa NAT: campul \"{}\" inlocuieste IP-ul sursa ({})",
+            config.network.nat_source.field,
+            if config.network.nat_source.nat_devices.is_empty() {
+                "orice sursa".to_string()
+            } else {
+                format!("dispozitive: {}", config.network.nat_source.nat_devices.join(", "))
+            }
+        ));
+    }
     display::log_info("Astept log-uri de la firewall... (Ctrl+C pentru oprire)");
     display::print_separator();
 
@@ -399,6 +904,271 @@ async fn main() -> anyhow::Result<()> {
         None
     };
 
+    // Deduplicare evenimente (optional, `network.dedup_window_ms > 0`).
+    let mut deduplicator: Option<EventDeduplicator> = if config.network.dedup_window_ms > 0 {
+        display::log_info(&format!(
+            "Deduplicare evenimente activa: fereastra {} ms",
+            config.network.dedup_window_ms
+        ));
+        Some(EventDeduplicator::new(config.network.dedup_window_ms))
+    } else {
+        None
+    };
+
+    // Raport erori de parsare (optional, `network.parse_error_report`).
+    let mut parse_errors: Option<ParseErrorSampler> = if config.network.parse_error_report {
+        Some(ParseErrorSampler::new())
+    } else {
+        None
+    };
+
+    // Fisier dead letter (optional, `network.deadletter_file`): pachetele cu
+    // linii respinse de parser, in hex. Un fisier care nu se poate deschide
+    // nu opreste senzorul — diagnosticul este optional.
+    let open_deadletter = |network: &config::NetworkConfig| {
+        let path = network.deadletter_file.as_deref()?;
+        match deadletter::DeadLetterWriter::open(
+            path,
+            network.deadletter_max_bytes,
+            network.deadletter_max_files,
+        ) {
+            Ok(writer) => {
+                display::log_info(&format!(
+                    "Dead letter activ: pachetele respinse de parser → {} (rotatie la {} octeti)",
+                    path, network.deadletter_max_bytes
+                ));
+                Some(writer)
+            }
+            Err(e) => {
+                display::log_warning(&format!("Dead letter dezactivat: {:#}", e));
+                None
+            }
+        }
+    };
+    let mut deadletter = open_deadletter(&config.network);
+
+    // FIFO local (optional, `output.fifo_path`): o linie JSON per alerta.
+    // Mutex doar pentru mutabilitate in closure-ul de emitere — un singur
+    // producator (main loop), lock necontestat.
+    let open_fifo = |output: &config::OutputConfig| {
+        let path = output.fifo_path.as_deref()?;
+        display::log_info(&format!("Alerte JSON in FIFO: {} (non-blocant)", path));
+        Some(fifo::FifoSink::new(path))
+    };
+    let fifo_sink = Mutex::new(open_fifo(&config.output));
+
+    // Fisier de arhiva (optional, `output.file_path`): text, JSON sau ambele
+    // per alerta. Ca dead letter-ul, un fisier care nu se deschide nu opreste
+    // senzorul.
+    let open_alert_file = |output: &config::OutputConfig| {
+        let path = output.file_path.as_deref()?;
+        match alertfile::AlertFile::open(path, &output.file_format) {
+            Ok(file) => {
+                display::log_info(&format!(
+                    "Alerte in fisier: {} (format \"{}\")",
+                    path, output.file_format
+                ));
+                Some(file)
+            }
+            Err(e) => {
+                display::log_warning(&format!("Fisier de alerte dezactivat: {:#}", e));
+                None
+            }
+        }
+    };
+    let alert_file = Mutex::new(open_alert_file(&config.output));
+
+    // Inregistrare replay (optional, `output.replay_dir`): ultimele linii ale
+    // fiecarei surse, scrise intr-un fisier la alerta. Mutex din acelasi
+    // motiv ca FIFO-ul — inregistrare in main loop, scriere la emitere.
+    // Parserul din antet este cel activ (schimbarea lui cere restart).
+    let active_parser = config.network.parser.clone();
+    let open_recorder = |config: &AppConfig| {
+        let dir = config.output.replay_dir.as_deref()?;
+        display::log_info(&format!(
+            "Replay la alerta: {} (ultimele {} linii per sursa, maxim {} fisiere)",
+            dir, config.output.replay_max_lines, config.output.replay_max_files
+        ));
+        Some(recorder::ReplayRecorder::new(
+            dir,
+            config.output.replay_max_lines,
+            config.output.replay_max_files,
+            &active_parser,
+        ))
+    };
+    let replay_recorder = Mutex::new(open_recorder(&config));
+
+    // Raport agregat periodic (optional, `output.rollup_interval`): alertele
+    // emise sunt numarate pana la granita orei / zilei. Mutex din acelasi
+    // motiv ca FIFO-ul — mutabilitate in closure-ul de emitere.
+    let open_rollup = |output: &config::OutputConfig| {
+        let interval = rollup::RollupInterval::parse(&output.rollup_interval)?;
+        display::log_info(&format!(
+            "Rollup {} activ (destinatie: {})",
+            interval.as_str(),
+            output.rollup_sink
+        ));
+        Some(rollup::Rollup::new(interval, chrono::Local::now()))
+    };
+    let rollup_state = Mutex::new(open_rollup(&config.output));
+
+    // Clasificare zgomot / atac tintit (optional, `alerting.classification`).
+    // ArcSwap: re-construit la SIGHUP, citit la fiecare alerta emisa.
+    let classifier = ArcSwap::from_pointee(classify::Classifier::new(
+        &config.alerting.classification,
+    ));
+    if config.alerting.classification.enabled {
+        display::log_info("Clasificare alerte activa: noise / targeted (alerting.classification)");
+    }
+
+    // Cheia de idempotenta (alerting.idempotency_key), setata la emitere —
+    // dupa corelare, deci alerta contopita pastreaza cheia primei detectii.
+    let idempotency_key = AtomicBool::new(config.alerting.idempotency_key);
+
+    // Identitatea senzorului ([sensor]) atasata fiecarei alerte; re-citita la SIGHUP.
+    let sensor = ArcSwap::from_pointee(alerter::sensor_identity(&config.sensor));
+
+    // Emiterea unei alerte: consola, buffer-ul web dashboard (#25) si coada
+    // catre sink-uri (SIEM/email/GELF). Apelata direct sau, cu corelare
+    // activa, dupa expirarea ferestrei `alerting.correlation_secs`.
+    // Clasificarea ruleaza aici — dupa corelare, pe reuniunea porturilor.
+    // Porturile sunt ordonate numeric: toate iesirile vad aceeasi lista.
+    let emit_alert = |mut alert: detector::Alert, max_alerts: usize| {
+        alert.sort_ports();
+        if idempotency_key.load(Ordering::Relaxed) {
+            alert.idempotency_key = alert.delivery_key();
+        }
+        alert.sensor = sensor.load().as_ref().clone();
+        alert.tor_exit = tor_exits.load().contains(&alert.source_ip);
+        classifier.load().classify(&mut alert);
+
+        // Afisam alerta in terminal (colorat, cu hostname-uri).
+        display::log_alert(&alert, &hostnames.load(), &subnets.load());
+
+        // Lock tinut doar cateva microsecunde — eliberat inainte de .await.
+        {
+            let mut buf = alert_buffer
+                .lock()
+                .unwrap_or_else(|e: std::sync::PoisonError<_>| e.into_inner());
+            buf.push_back(alert.clone());
+            while buf.len() > max_alerts {
+                buf.pop_front();
+            }
+        }
+
+        if let Some(ref tracker) = incidents {
+            tracker.record(&alert);
+        }
+        if let Some(ref mut sink) = *fifo_sink.lock().unwrap_or_else(|e| e.into_inner()) {
+            sink.send(&alert);
+        }
+        if let Some(ref mut file) = *alert_file.lock().unwrap_or_else(|e| e.into_inner()) {
+            if let Err(e) = file.write(&alert) {
+                display::log_warning(&format!("{:#}", e));
+            }
+        }
+        if let Some(ref mut r) = *rollup_state.lock().unwrap_or_else(|e| e.into_inner()) {
+            r.record(&alert);
+        }
+        if let Some(ref mut rec) = *replay_recorder.lock().unwrap_or_else(|e| e.into_inner()) {
+            match rec.dump(&alert) {
+                Ok(Some(path)) => {
+                    display::log_info(&format!("Replay salvat: {}", path.display()));
+                }
+                Ok(None) => {}
+                Err(e) => display::log_error(&format!("Replay: {:#}", e)),
+            }
+        }
+
+        // Coada bounded decupleaza I/O-ul lent SIEM/SMTP de receptia UDP.
+        match alert_tx.try_send(alert) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                dropped_alerts.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(TrySendError::Closed(_)) => {
+                display::log_warning("Worker-ul de alerte este oprit");
+            }
+        }
+    };
+
+    // Corelare alerte (optional, `alerting.correlation_secs > 0`): alertele
+    // aceluiasi IP sunt retinute si contopite — cost: latenta de emitere.
+    let log_correlation = |secs: u64| {
+        display::log_info(&format!(
+            "Corelare alerte activa: fereastra {}s (alertele Fast/Slow/Accept Scan intarzie pana la {}s)",
+            secs, secs
+        ));
+    };
+    let mut correlator: Option<correlation::AlertCorrelator> =
+        if config.alerting.correlation_secs > 0 {
+            log_correlation(config.alerting.correlation_secs);
+            Some(correlation::AlertCorrelator::new(
+                config.alerting.correlation_secs,
+                &config.alerting.summary_template,
+            ))
+        } else {
+            None
+        };
+    let mut correlation_tick = tokio::time::interval(Duration::from_secs(1));
+    correlation_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    // Rollup: esantionarea IP-urilor urmarite (varful perioadei) si verificarea
+    // granitei, la fiecare 5 secunde.
+    let mut rollup_tick = tokio::time::interval(Duration::from_secs(5));
+    rollup_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    // Senzor fara trafic (detection.no_traffic_alert_secs): verificarea linistii
+    // la fiecare 5 secunde. `source_ip` al alertei = adresa de ascultare.
+    let mut silence_tick = tokio::time::interval(Duration::from_secs(5));
+    silence_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let sensor_ip: IpAddr = config
+        .network
+        .listen_address
+        .parse()
+        .unwrap_or(IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED));
+    if config.detection.no_traffic_alert_secs > 0 {
+        display::log_info(&format!(
+            "Alerta senzor fara trafic activa: dupa {} fara niciun pachet",
+            display::format_window(Duration::from_secs(config.detection.no_traffic_alert_secs))
+        ));
+    }
+    if config.detection.scan_end_silence_secs > 0 {
+        display::log_info(&format!(
+            "Eveniment scanare incheiata activ: dupa {} de liniste a unei surse alertate",
+            display::format_window(Duration::from_secs(config.detection.scan_end_silence_secs))
+        ));
+    }
+
+    // Reguli din fisiere (detection.rules_dir): doar pe detectorul implicit.
+    // Setul activ este baza reload-ului — starea regulilor neschimbate si
+    // versiunea anterioara a fisierelor invalide sunt preluate din el.
+    let reload_rules = |dir: &str| -> Result<(usize, Vec<String>), String> {
+        let (set, warnings) =
+            rules::RuleSet::load_dir(std::path::Path::new(dir), &detector.custom_rules())?;
+        for w in &warnings {
+            display::log_warning(&format!("Regula ignorata: {}", w));
+        }
+        let count = set.len();
+        detector.set_custom_rules(Arc::new(set));
+        Ok((count, warnings))
+    };
+    let mut rules_watcher = None;
+    if !config.detection.rules_dir.is_empty() {
+        let (count, _) = reload_rules(&config.detection.rules_dir).map_err(anyhow::Error::msg)?;
+        display::log_info(&format!(
+            "Reguli din fisiere: {} active din {} (verificare la 5s) [{}]",
+            count,
+            config.detection.rules_dir,
+            detector.custom_rules().names().join(", ")
+        ));
+        rules_watcher = Some(rules::RulesWatcher::new(&config.detection.rules_dir));
+    }
+    let mut rules_tick = tokio::time::interval(Duration::from_secs(5));
+    rules_tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    let mut cleanup_counter = CleanupCounter::new();
+
     // Interval pentru afisarea periodica a statisticilor de rate limiting.
     // Folosim `tokio::time::interval` cu 30 secunde — suficient de rar
     // pentru a nu polua output-ul, dar suficient de des pentru vizibilitate.
@@ -438,6 +1208,16 @@ async fn main() -> anyhow::Result<()> {
 
     let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
 
+    // SIGUSR2 — raport de stare pe stdout (`kill -USR2 <pid>`), fara oprirea
+    // detectiei si fara API-ul HTTP: cron-uri si scripturi de monitorizare.
+    let mut sigusr2 =
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::user_defined2())?;
+
+    // SIGWINCH — terminalul a fost redimensionat: latimea folosita de
+    // `output.style = "columnar"` este recitita (nu la fiecare linie).
+    let mut sigwinch =
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::window_change())?;
+
     // =========================================================================
     // 9. MAIN LOOP - Receptie si Procesare Log-uri
     // =========================================================================
@@ -452,6 +1232,37 @@ async fn main() -> anyhow::Result<()> {
     //
     let mut buf = [0u8; 65535];
 
+    // --max-runtime: termen limita dupa care iesim din loop pe aceeasi cale
+    // ca la Ctrl+C/SIGTERM. `None` = ruleaza pana la semnal.
+    //
+    // NOTA RUST: `sleep_until` e creat O SINGURA DATA in afara loop-ului si
+    // fixat cu `tokio::pin!` — select! il poate astepta prin `&mut` la fiecare
+    // iteratie fara a reporni timer-ul (un `sleep(d)` creat in select! ar
+    // reincepe numaratoarea la fiecare pachet primit). Fara flag, branch-ul
+    // e dezactivat prin precondition (`if ...`), iar timer-ul primeste un
+    // termen de ~30 ani (`checked_add` evita panic-ul la overflow de Instant).
+    let now = tokio::time::Instant::now();
+    let far_future = now + Duration::from_secs(86400 * 365 * 30);
+    let max_runtime_deadline = tokio::time::sleep_until(
+        cli.max_runtime
+            .and_then(|d| now.checked_add(d))
+            .unwrap_or(far_future)
+            .min(far_future),
+    );
+    tokio::pin!(max_runtime_deadline);
+    if let Some(max_runtime) = cli.max_runtime {
+        display::log_info(&format!(
+            "Durata maxima de rulare: {} (--max-runtime)",
+            display::format_window(max_runtime)
+        ));
+    }
+    if explain_suppressions {
+        display::log_info("Mod audit activ: alertele suprimate sunt logate cu motivul (--explain-suppressions)");
+    }
+    if diagnostics_report {
+        display::log_info("Contabilitatea pachetelor este afisata la fiecare ciclu de statistici si la oprire (--diagnostics-report)");
+    }
+
     loop {
         // NOTA RUST - tokio::select!:
         //
@@ -492,11 +1303,45 @@ async fn main() -> anyhow::Result<()> {
                 break;
             }
 
+            // Branch: --max-runtime expirat — aceeasi oprire gratioasa.
+            _ = &mut max_runtime_deadline, if cli.max_runtime.is_some() => {
+                display::log_info("Durata maxima de rulare atinsa (--max-runtime) — oprire gratiosa...");
+                break;
+            }
+
+            _ = sigwinch.recv() => display::refresh_terminal_width(),
+
+            // Branch: Raport de stare la SIGUSR2 — starea ramane neatinsa.
+            // Profilurile primesc acelasi flux: IP-urile urmarite vin din
+            // detectorul implicit, scanerele din toate (maximul per sursa).
+            _ = sigusr2.recv() => {
+                let now = Instant::now();
+                let mut scanners: HashMap<IpAddr, usize> = HashMap::new();
+                for d in std::iter::once(&detector).chain(profile_detectors.iter().map(|(_, d)| d)) {
+                    for (ip, ports) in d.scanners_over_threshold(now) {
+                        let entry = scanners.entry(ip).or_insert(0);
+                        *entry = (*entry).max(ports);
+                    }
+                }
+                let mut scanners: Vec<(IpAddr, usize)> = scanners.into_iter().collect();
+                scanners.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+                let now_minute = chrono::Utc::now().timestamp().max(0) as u64 / 60;
+                display::log_status_report(
+                    counters.started_at.elapsed(),
+                    detector.tracked_ips(),
+                    counters.alerts_last_hour(now_minute),
+                    &scanners,
+                );
+            }
+
             // Branch: Hot reload config la SIGHUP (#16).
             _ = sighup.recv() => {
                 display::log_reload("SIGHUP primit — reincarc config.toml...");
                 match AppConfig::load(&config_path) {
                     Ok(new_config) => {
+                        for warning in new_config.deprecations.iter().chain(&new_config.overlap_warnings()) {
+                            display::log_warning(&format!("SIGHUP: {}", warning));
+                        }
                         // Verificam campurile care NU pot fi reincarcate (necesita restart).
                         if new_config.network.listen_port != config.network.listen_port
                             || new_config.network.listen_address != config.network.listen_address
@@ -505,6 +1350,11 @@ async fn main() -> anyhow::Result<()> {
                                 "SIGHUP: listen_address/listen_port modificate — necesita restart, ignorat"
                             );
                         }
+                        if new_config.network.netns != config.network.netns {
+                            display::log_warning(
+                                "SIGHUP: network.netns modificat — necesita restart, ignorat"
+                            );
+                        }
                         if new_config.network.parser != config.network.parser {
                             display::log_warning(
                                 "SIGHUP: parser modificat — necesita restart, ignorat"
@@ -513,9 +1363,60 @@ async fn main() -> anyhow::Result<()> {
 
                         // Aplicam noile valori la componentele reincarcabile.
                         debug_mode = new_config.network.debug;
+                        display::configure(&new_config.display);
+                        display::configure_output(&new_config.output);
 
                         // Detector: praguri, cooldown, whitelist.
+                        let old_policy = detector.policy_version();
                         detector.update_config(new_config.detection.clone());
+                        let new_policy = detector.policy_version();
+                        if new_policy != old_policy {
+                            display::log_reload(&format!(
+                                "Politica de detectie: {} → {}",
+                                old_policy, new_policy
+                            ));
+                        }
+
+                        if new_config.network.inbound_only() != detector.inbound_only() {
+                            detector.set_inbound_only(new_config.network.inbound_only());
+                            display::log_reload(&format!(
+                                "network.traffic_direction = {}",
+                                new_config.network.traffic_direction
+                            ));
+                        }
+
+                        detector.set_assets(&new_config.assets);
+                        detector.set_summary_template(&new_config.alerting.summary_template);
+                        detector.set_detection_context(new_config.alerting.detection_context);
+
+                        // Profiluri: pragurile se reincarca; adaugarea sau
+                        // eliminarea unui profil necesita restart.
+                        if new_config.profiles.keys().ne(config.profiles.keys()) {
+                            display::log_warning(
+                                "SIGHUP: lista [profiles] modificata — necesita restart, ignorat"
+                            );
+                        } else {
+                            alerter.set_profiles(&new_config.profiles);
+                        }
+                        for (name, d) in &profile_detectors {
+                            if let Some(profile) = new_config.profiles.get(name) {
+                                d.update_config(profile.detection.clone());
+                                d.set_inbound_only(new_config.network.inbound_only());
+                                d.set_assets(&new_config.assets);
+                                d.set_summary_template(&new_config.alerting.summary_template);
+                                d.set_detection_context(new_config.alerting.detection_context);
+                            }
+                        }
+                        // Un profil care partaja harta de urmarire si are acum
+                        // alti parametri de urmarire trece pe o harta proprie.
+                        for (name, d) in &profile_detectors {
+                            if d.detach_diverged_tracking() {
+                                display::log_warning(&format!(
+                                    "SIGHUP: profilul '{}' nu mai partajeaza harta de urmarire (whitelist / plafoane diferite) — porturile se numara de la zero",
+                                    name
+                                ));
+                            }
+                        }
 
                         // Alerter: SIEM, email, hostnames, subnets.
                         let new_hostnames = parse_hostnames(&new_config);
@@ -527,6 +1428,12 @@ async fn main() -> anyhow::Result<()> {
                             new_subnets.clone(),
                         );
 
+                        classifier.store(Arc::new(classify::Classifier::new(
+                            &new_config.alerting.classification,
+                        )));
+                        idempotency_key.store(new_config.alerting.idempotency_key, Ordering::Relaxed);
+                        sensor.store(Arc::new(alerter::sensor_identity(&new_config.sensor)));
+
                         // Hostnames si subnets partajate (folosite in main loop pentru display).
                         hostnames.store(Arc::new(new_hostnames));
                         subnets.store(Arc::new(new_subnets));
@@ -546,6 +1453,28 @@ async fn main() -> anyhow::Result<()> {
                             );
                         }
 
+                        // Metrici: enabled/bind/port necesita restart.
+                        // export_ruleset, web_ui si alerts_by_hour sunt fixate la pornirea serverului.
+                        if new_config.metrics.enabled != config.metrics.enabled
+                            || new_config.metrics.bind != config.metrics.bind
+                            || new_config.metrics.port != config.metrics.port
+                            || new_config.metrics.export_ruleset != config.metrics.export_ruleset
+                            || new_config.metrics.web_ui != config.metrics.web_ui
+                            || new_config.metrics.alerts_by_hour != config.metrics.alerts_by_hour
+                        {
+                            display::log_warning(
+                                "SIGHUP: sectiunea [metrics] modificata — necesita restart, ignorat"
+                            );
+                        }
+                        // Histograma de latenta este doar un flag in bucla principala.
+                        if new_config.metrics.latency_histogram != config.metrics.latency_histogram {
+                            counters.latency.set_enabled(new_config.metrics.latency_histogram);
+                            display::log_reload(&format!(
+                                "Histograma latenta detectie: {}",
+                                if new_config.metrics.latency_histogram { "activata" } else { "dezactivata" }
+                            ));
+                        }
+
                         // Rate limiter: recream daca s-a schimbat.
                         if new_config.network.udp_rate_limit != config.network.udp_rate_limit
                             || new_config.network.udp_burst_size != config.network.udp_burst_size
@@ -566,12 +1495,190 @@ async fn main() -> anyhow::Result<()> {
                             };
                         }
 
+                        // Deduplicator: recream daca fereastra s-a schimbat.
+                        if new_config.network.dedup_window_ms != config.network.dedup_window_ms {
+                            deduplicator = if new_config.network.dedup_window_ms > 0 {
+                                display::log_reload(&format!(
+                                    "Deduplicare evenimente: fereastra {} ms",
+                                    new_config.network.dedup_window_ms
+                                ));
+                                Some(EventDeduplicator::new(new_config.network.dedup_window_ms))
+                            } else {
+                                display::log_reload("Deduplicare evenimente dezactivata");
+                                None
+                            };
+                        }
+
+                        if new_config.network.parse_error_report != config.network.parse_error_report {
+                            parse_errors = if new_config.network.parse_error_report {
+                                Some(ParseErrorSampler::new())
+                            } else {
+                                None
+                            };
+                        }
+
+                        if new_config.alerting.correlation_secs != config.alerting.correlation_secs {
+                            let secs = new_config.alerting.correlation_secs;
+                            match (correlator.as_mut(), secs) {
+                                (Some(c), 0) => {
+                                    // Dezactivare: alertele retinute se emit imediat.
+                                    for alert in c.drain() {
+                                        emit_alert(alert, new_config.web_dashboard.max_alerts);
+                                    }
+                                    correlator = None;
+                                    display::log_reload("Corelare alerte dezactivata");
+                                }
+                                (Some(c), _) => {
+                                    c.set_hold(secs);
+                                    display::log_reload(&format!(
+                                        "Corelare alerte: fereastra {}s", secs
+                                    ));
+                                }
+                                (None, _) => {
+                                    log_correlation(secs);
+                                    correlator = Some(correlation::AlertCorrelator::new(
+                                        secs,
+                                        &new_config.alerting.summary_template,
+                                    ));
+                                }
+                            }
+                        }
+                        if let Some(ref mut c) = correlator {
+                            c.set_summary_template(&new_config.alerting.summary_template);
+                        }
+
+                        if new_config.network.deadletter_file != config.network.deadletter_file
+                            || new_config.network.deadletter_max_bytes
+                                != config.network.deadletter_max_bytes
+                            || new_config.network.deadletter_max_files
+                                != config.network.deadletter_max_files
+                        {
+                            deadletter = open_deadletter(&new_config.network);
+                        }
+
+                        if new_config.output.replay_dir != config.output.replay_dir
+                            || new_config.output.replay_max_lines != config.output.replay_max_lines
+                            || new_config.output.replay_max_files != config.output.replay_max_files
+                        {
+                            let mut rec = replay_recorder.lock().unwrap_or_else(|e| e.into_inner());
+                            *rec = open_recorder(&new_config);
+                            if rec.is_none() {
+                                display::log_reload("Replay la alerta dezactivat");
+                            }
+                        }
+
+                        if new_config.output.fifo_path != config.output.fifo_path {
+                            let mut sink = fifo_sink.lock().unwrap_or_else(|e| e.into_inner());
+                            *sink = open_fifo(&new_config.output);
+                            if sink.is_none() {
+                                display::log_reload("FIFO alerte dezactivat");
+                            }
+                        }
+
+                        // Redeschis la fiecare SIGHUP, si cu aceeasi cale: dupa
+                        // logrotate, scrierea continua in fisierul nou.
+                        {
+                            let mut file = alert_file.lock().unwrap_or_else(|e| e.into_inner());
+                            let was_open = file.is_some();
+                            *file = open_alert_file(&new_config.output);
+                            if was_open && file.is_none() {
+                                display::log_reload("Fisier de alerte dezactivat");
+                            }
+                        }
+
+                        if new_config.output.rollup_interval != config.output.rollup_interval {
+                            let mut r = rollup_state.lock().unwrap_or_else(|e| e.into_inner());
+                            *r = open_rollup(&new_config.output);
+                            if r.is_none() {
+                                display::log_reload("Rollup dezactivat");
+                            }
+                        }
+
+                        // Harta de active: recitita mereu (exportul CMDB se poate
+                        // schimba fara modificarea caii); la eroare pastram harta veche.
+                        let asset_map_error =
+                            match assetmap::AssetMap::load(&new_config.enrichment.asset_map_path) {
+                                Ok(map) => {
+                                    if !map.is_empty() {
+                                        display::log_reload(&format!(
+                                            "Harta de active: {} intrari",
+                                            map.len()
+                                        ));
+                                    }
+                                    let map = Arc::new(map);
+                                    detector.set_asset_map(Arc::clone(&map));
+                                    for (_, d) in &profile_detectors {
+                                        d.set_asset_map(Arc::clone(&map));
+                                    }
+                                    None
+                                }
+                                Err(e) => {
+                                    display::log_error(&format!(
+                                        "SIGHUP: harta de active neincarcata, pastrez harta veche: {:#}",
+                                        e
+                                    ));
+                                    Some(format!("{:#}", e))
+                                }
+                            };
+
+                        // Lista Tor: recitita la SIGHUP (si la schimbarea caii);
+                        // la eroare pastram lista veche.
+                        let tor_path = &new_config.enrichment.tor_exit_list_path;
+                        match torexit::TorExitList::load(tor_path) {
+                            Ok((list, warning)) => {
+                                if let Some(w) = warning {
+                                    display::log_warning(&w);
+                                }
+                                if !tor_path.is_empty() {
+                                    display::log_reload(&format!(
+                                        "Noduri de iesire Tor: {} IP-uri",
+                                        list.len()
+                                    ));
+                                }
+                                tor_exits.store(Arc::new(list));
+                                tor_watcher = (!tor_path.is_empty())
+                                    .then(|| torexit::TorExitWatcher::new(tor_path));
+                            }
+                            Err(e) => display::log_error(&format!(
+                                "SIGHUP: lista Tor neincarcata, pastrez lista veche: {:#}",
+                                e
+                            )),
+                        }
+
+                        // Director de reguli schimbat: setul este reincarcat din
+                        // noua locatie (regulile vechi dispar).
+                        if new_config.detection.rules_dir != config.detection.rules_dir {
+                            rules_watcher = None;
+                            detector.set_custom_rules(Arc::new(rules::RuleSet::default()));
+                            let dir = &new_config.detection.rules_dir;
+                            if dir.is_empty() {
+                                display::log_reload("Reguli din fisiere dezactivate");
+                            } else {
+                                match reload_rules(dir) {
+                                    Ok((count, _)) => {
+                                        display::log_reload(&format!(
+                                            "Reguli din fisiere: {} active din {}",
+                                            count, dir
+                                        ));
+                                        rules_watcher = Some(rules::RulesWatcher::new(dir));
+                                    }
+                                    Err(e) => display::log_error(&format!("SIGHUP: {}", e)),
+                                }
+                            }
+                        }
+
                         // Salvam config-ul nou pentru comparatii viitoare la urmatorul SIGHUP.
                         config = new_config;
+                        live_config.store(Arc::new(config.clone()));
 
+                        match asset_map_error {
+                            Some(e) => counters.last_errors.record(Subsystem::Config, e),
+                            None => counters.last_errors.clear(Subsystem::Config),
+                        }
                         display::log_reload("Config reincarcat cu succes");
                     }
                     Err(e) => {
+                        counters.last_errors.record(Subsystem::Config, format!("{:#}", e));
                         display::log_warning(&format!(
                             "SIGHUP: reload esuat, pastrez config-ul vechi: {:#}", e
                         ));
@@ -579,14 +1686,158 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
 
+            // Branch: Emiterea alertelor corelate cu fereastra expirata (1s).
+            _ = correlation_tick.tick(), if correlator.is_some() => {
+                if let Some(ref mut c) = correlator {
+                    for alert in c.take_due(Instant::now()) {
+                        emit_alert(alert, config.web_dashboard.max_alerts);
+                    }
+                }
+            }
+
+            // Branch: Rollup — varf IP-uri urmarite si emiterea la granita.
+            _ = rollup_tick.tick() => {
+                let due = match *rollup_state.lock().unwrap_or_else(|e| e.into_inner()) {
+                    Some(ref mut r) => {
+                        r.observe_tracked_ips(detector.tracked_ips());
+                        r.take_due(chrono::Local::now())
+                    }
+                    None => None,
+                };
+                if let Some(summary) = due {
+                    match config.output.rollup_sink.as_str() {
+                        "email" | "siem" => {
+                            let sink = config.output.rollup_sink.clone();
+                            let rollup_alerter = Arc::clone(&alerter);
+                            tokio::spawn(async move {
+                                rollup_alerter.send_rollup(&summary, &sink).await;
+                            });
+                        }
+                        _ => display::log_rollup(&summary),
+                    }
+                }
+            }
+
+            // Branch: Alerta operationala "senzor fara trafic". Nu trece prin
+            // corelare — nu are porturi de contopit.
+            _ = silence_tick.tick() => {
+                if let Some(mut alert) = detector.check_no_traffic(sensor_ip, Instant::now()) {
+                    alert.ingress.clone_from(&ingress);
+                    counters.record_alerts(std::slice::from_ref(&alert));
+                    emit_alert(alert, config.web_dashboard.max_alerts);
+                }
+
+                // Scanari incheiate (detection.scan_end_silence_secs): fiecare
+                // profil isi inchide propriile episoade.
+                let now = Instant::now();
+                let mut ended: Vec<detector::Alert> = detector.check_scan_ends(now);
+                for alert in &mut ended {
+                    alert.profile = config::DEFAULT_PROFILE.to_string();
+                }
+                for (name, d) in &profile_detectors {
+                    for mut alert in d.check_scan_ends(now) {
+                        alert.profile.clone_from(name);
+                        ended.push(alert);
+                    }
+                }
+                if let Some(ref store) = false_positives {
+                    ended.retain_mut(|alert| store.apply(alert).is_none());
+                }
+                counters.record_alerts(&ended);
+                for mut alert in ended {
+                    alert.ingress.clone_from(&ingress);
+                    emit_alert(alert, config.web_dashboard.max_alerts);
+                }
+            }
+
+            // Branch: Reguli din fisiere — reload la orice fisier adaugat,
+            // modificat sau sters in detection.rules_dir.
+            _ = rules_tick.tick(), if rules_watcher.is_some() => {
+                if let Some(ref mut watcher) = rules_watcher {
+                    if watcher.changed() {
+                        match reload_rules(&config.detection.rules_dir) {
+                            Ok((count, warnings)) => {
+                                display::log_reload(&format!(
+                                    "Reguli din fisiere reincarcate: {} active ({})",
+                                    count,
+                                    watcher.dir().display()
+                                ));
+                                if warnings.is_empty() {
+                                    counters.last_errors.clear(Subsystem::Config);
+                                } else {
+                                    counters.last_errors.record(Subsystem::Config, warnings.join("; "));
+                                }
+                            }
+                            Err(e) => {
+                                display::log_error(&format!("Reguli din fisiere: {}", e));
+                                counters.last_errors.record(Subsystem::Config, e);
+                            }
+                        }
+                    }
+                }
+            }
+
             // Branch: Afisare periodica statistici rate limiting (la fiecare 30s).
             _ = rate_limit_tick.tick() => {
+                // Rezumatele mesajelor repetate (coalescing) cu fereastra expirata.
+                display::flush_repeated_logs();
+                // Lista Tor actualizata de job-ul extern: reincarcata la schimbare.
+                if let Some(ref mut watcher) = tor_watcher {
+                    if watcher.changed() {
+                        let path = &config.enrichment.tor_exit_list_path;
+                        match torexit::TorExitList::load(path) {
+                            Ok((list, warning)) => {
+                                if let Some(w) = warning {
+                                    display::log_warning(&w);
+                                }
+                                display::log_reload(&format!(
+                                    "Noduri de iesire Tor reincarcate: {} IP-uri ({})",
+                                    list.len(),
+                                    path
+                                ));
+                                tor_exits.store(Arc::new(list));
+                            }
+                            Err(e) => display::log_error(&format!(
+                                "Lista Tor neincarcata, pastrez lista veche: {:#}",
+                                e
+                            )),
+                        }
+                    }
+                }
+                // Inelele replay ale surselor tacute — aceeasi varsta ca detectorul.
+                if let Some(ref mut rec) = *replay_recorder.lock().unwrap_or_else(|e| e.into_inner()) {
+                    rec.cleanup(
+                        Duration::from_secs(config.cleanup.max_entry_age_secs),
+                        Instant::now(),
+                    );
+                }
+                if let Some((count, secs, sample)) =
+                    parse_errors.as_mut().and_then(|p| p.take_report())
+                {
+                    display::log_warning(&format!(
+                        "{} linii neparsabile in ultimele {} secunde (parser '{}' — verificati \
+                         setarea network.parser). Exemplu hex: {}",
+                        count,
+                        secs,
+                        parser.name(),
+                        sample
+                    ));
+                }
                 if let Some(ref mut limiter) = rate_limiter {
                     let dropped = limiter.take_dropped_count();
                     if dropped > 0 {
                         display::log_rate_limited(dropped);
                     }
                 }
+                if let Some(ref mut dedup) = deduplicator {
+                    let duplicates = dedup.take_duplicate_count();
+                    if duplicates > 0 {
+                        display::log_info(&format!(
+                            "Deduplicare: {} evenimente duplicate eliminate",
+                            duplicates
+                        ));
+                    }
+                }
                 let dropped_alerts = dropped_alerts_stats.swap(0, Ordering::Relaxed);
                 if dropped_alerts > 0 {
                     display::log_warning(&format!(
@@ -594,20 +1845,48 @@ async fn main() -> anyhow::Result<()> {
                         dropped_alerts
                     ));
                 }
+                if let Some(ref mut sink) = *fifo_sink.lock().unwrap_or_else(|e| e.into_inner()) {
+                    let dropped = sink.take_dropped();
+                    if dropped > 0 {
+                        display::log_warning(&format!(
+                            "FIFO {}: {} alerte pierdute (fara cititor sau pipe plin)",
+                            sink.path().display(),
+                            dropped
+                        ));
+                    }
+                }
             }
 
             // Branch: Pachet UDP primit.
             result = socket.recv_from(&mut buf) => {
                 match result {
-                    Ok((len, _addr)) => {
+                    Ok((len, addr)) => {
+                        counters.last_errors.clear(Subsystem::Socket);
+                        counters.packets.datagrams.fetch_add(1, Ordering::Relaxed);
+                        // Momentul receptiei: cronometrul linistii si latenta pachetului.
+                        let received_at = Instant::now();
+                        // Orice datagrama reporneste cronometrul linistii — si cele
+                        // dropate de rate limiter sau neparsabile: senzorul nu e orb.
+                        if let Some(silent) = detector.record_packet(received_at) {
+                            display::log_info(&format!(
+                                "Trafic reluat dupa {} fara pachete",
+                                display::format_window(silent)
+                            ));
+                        }
                         // Rate limiting: verificam daca avem token disponibil.
                         // Daca bucket-ul e gol, dropam pachetul silentios.
                         if let Some(ref mut limiter) = rate_limiter {
                             if !limiter.try_consume() {
+                                counters.packets.rate_limited.fetch_add(1, Ordering::Relaxed);
                                 continue;
                             }
                         }
 
+                        // Cleanup dupa volum: la fiecare N pachete procesate.
+                        if cleanup_counter.tick(detector.config().cleanup_every_n_packets) {
+                            cleanup_trigger.notify_one();
+                        }
+
                         // NOTA RUST - String::from_utf8_lossy:
                         //
                         // Converteste bytes in text UTF-8.
@@ -629,6 +1908,9 @@ async fn main() -> anyhow::Result<()> {
                         // `.lines()` returneaza un iterator care produce &str
                         // pentru fiecare linie, ignorand delimitatorii (\n, \r\n).
                         //
+                        // Contoare per pachet pentru dead letter (linii respinse / total).
+                        let mut rejected_lines = 0usize;
+                        let mut total_lines = 0usize;
                         for line in data.lines() {
                             // `.trim()` returneaza un &str fara spatii la inceput/sfarsit.
                             // Nu aloca memorie noua - returneaza un sub-slice.
@@ -636,6 +1918,8 @@ async fn main() -> anyhow::Result<()> {
                             if line.is_empty() {
                                 continue;
                             }
+                            total_lines += 1;
+                            counters.packets.lines.fetch_add(1, Ordering::Relaxed);
 
                             // Debug: afiseaza linia raw primita.
                             if debug_mode {
@@ -643,12 +1927,38 @@ async fn main() -> anyhow::Result<()> {
                             }
 
                             // Parsam linia cu parser-ul activ (dynamic dispatch).
-                            if let Some(event) = parser.parse(line) {
+                            if let Some(mut event) = parser.parse(line) {
                                 // Debug: afiseaza campurile extrase.
                                 if debug_mode {
                                     display::log_debug_parse_ok(&event);
                                 }
 
+                                // Sursa reala din spatele NAT-ului, inainte de
+                                // deduplicare si detectie.
+                                if let Some(ref resolver) = nat_resolver {
+                                    if let Some(nat_device) = resolver.resolve(&mut event) {
+                                        tracing::debug!(
+                                            nat = %nat_device,
+                                            client = %event.source_ip,
+                                            "Sursa NAT inlocuita"
+                                        );
+                                    }
+                                }
+
+                                // Copie mirror a unui eveniment deja procesat — ignorata.
+                                if let Some(ref mut dedup) = deduplicator {
+                                    if dedup.is_duplicate(&event) {
+                                        counters.packets.duplicates.fetch_add(1, Ordering::Relaxed);
+                                        continue;
+                                    }
+                                }
+
+                                if let Some(ref mut rec) =
+                                    *replay_recorder.lock().unwrap_or_else(|e| e.into_inner())
+                                {
+                                    rec.record(event.source_ip, &event.raw_log, received_at);
+                                }
+
                                 // Afisam evenimentul in terminal cu badge dinamic
                                 // (albastru = drop, verde = accept).
                                 display::log_firewall_event(
@@ -664,49 +1974,144 @@ async fn main() -> anyhow::Result<()> {
                                 tracing::debug!(raw = %event.raw_log, "Log original");
 
                                 // Procesam evenimentul in detector.
-                                let alerts = detector.process_event(&event);
-
-                                // Procesam alertele generate (daca exista).
-                                for alert in alerts {
-                                    // Afisam alerta in terminal (colorat, cu hostname-uri).
-                                    display::log_alert(&alert, &hostnames.load(), &subnets.load());
-
-                                    // Adaugam alerta in buffer-ul web dashboard (#25).
-                                    // Lock tinut doar cateva microsecunde — eliberat inainte de .await.
-                                    {
-                                        let mut buf = alert_buffer.lock()
-                                            .unwrap_or_else(|e: std::sync::PoisonError<_>| e.into_inner());
-                                        buf.push_back(alert.clone());
-                                        let max = config.web_dashboard.max_alerts;
-                                        while buf.len() > max {
-                                            buf.pop_front();
+                                counters.events_processed.fetch_add(1, Ordering::Relaxed);
+                                // Profilul implicit si profilurile [profiles.<nume>]
+                                // vad acelasi eveniment; fiecare alerta poarta profilul.
+                                let evaluate = |d: &Detector, profile: &str| {
+                                    let mut alerts = if explain_suppressions {
+                                        let (alerts, suppressed) = d.process_event_explained(&event);
+                                        for suppression in &suppressed {
+                                            display::log_suppression(suppression);
                                         }
+                                        alerts
+                                    } else {
+                                        d.process_event(&event)
+                                    };
+                                    // Avertismente provizorii ([detection.early_warning]):
+                                    // doar in consola, nu trec prin sink-uri.
+                                    for warning in d.take_early_warnings() {
+                                        display::log_early_warning(&warning, profile);
+                                    }
+                                    for alert in &mut alerts {
+                                        alert.profile = profile.to_string();
                                     }
+                                    alerts
+                                };
+                                let mut alerts = evaluate(&detector, config::DEFAULT_PROFILE);
+                                for (name, d) in &profile_detectors {
+                                    alerts.extend(evaluate(d, name));
+                                }
 
-                                    // Coada bounded decupleaza I/O-ul lent SIEM/SMTP de receptia UDP.
-                                    match alert_tx.try_send(alert) {
-                                        Ok(()) => {}
-                                        Err(TrySendError::Full(_)) => {
-                                            dropped_alerts.fetch_add(1, Ordering::Relaxed);
+                                // Marcaje fals-pozitiv ale operatorilor: retrogradare sau suprimare.
+                                if let Some(ref store) = false_positives {
+                                    alerts.retain_mut(|alert| match store.apply(alert) {
+                                        Some(suppression) => {
+                                            if explain_suppressions {
+                                                display::log_suppression(&suppression);
+                                            }
+                                            false
                                         }
-                                        Err(TrySendError::Closed(_)) => {
-                                            display::log_warning("Worker-ul de alerte este oprit");
+                                        None => true,
+                                    });
+                                }
+                                counters.record_alerts(&alerts);
+
+                                // Procesam alertele generate (daca exista): direct sau
+                                // prin stratul de corelare (retinute, emise la tick).
+                                for mut alert in alerts {
+                                    alert.ingress.clone_from(&ingress);
+                                    if interface_metadata {
+                                        if let Some(ref interface) = event.interface {
+                                            alert.interface.clone_from(interface);
+                                            alert.vlan = crate::parser::vlan_from_interface(interface);
                                         }
                                     }
+                                    let alert = match correlator {
+                                        Some(ref mut c) => c.push(alert, Instant::now()),
+                                        None => Some(alert),
+                                    };
+                                    if let Some(alert) = alert {
+                                        emit_alert(alert, config.web_dashboard.max_alerts);
+                                    }
+                                }
+                            } else {
+                                rejected_lines += 1;
+                                counters.packets.parse_errors.fetch_add(1, Ordering::Relaxed);
+                                if let Some(ref mut sampler) = parse_errors {
+                                    sampler.record(line);
                                 }
-                            } else if debug_mode {
-                                // Debug: afiseaza detalii despre esecul parsarii.
-                                display::log_debug_parse_fail(
-                                    line,
-                                    parser.name(),
-                                    parser.expected_format(),
+                                if debug_mode {
+                                    // Debug: afiseaza detalii despre esecul parsarii.
+                                    display::log_debug_parse_fail(
+                                        line,
+                                        parser.name(),
+                                        parser.expected_format(),
+                                    );
+                                }
+                            }
+                        }
+
+                        // Datagrama goala (0 octeti sau doar spatii / newline-uri):
+                        // nicio linie de parsat. Numarata ca o linie respinsa, deci
+                        // ca eroare de parsare — altfel ar trece neobservata.
+                        let empty = total_lines == 0;
+                        if empty {
+                            total_lines = 1;
+                            rejected_lines = 1;
+                            counters.packets.lines.fetch_add(1, Ordering::Relaxed);
+                            counters.packets.parse_errors.fetch_add(1, Ordering::Relaxed);
+                            if let Some(ref mut sampler) = parse_errors {
+                                sampler.record("");
+                            }
+                            if debug_mode {
+                                display::log_debug_parse_fail("", parser.name(), parser.expected_format());
+                            }
+                        }
+
+                        // Nicio linie parsabila: datagrama malformata. Volumul per
+                        // expeditor poate declansa Malformed Input (fuzzing / sondare
+                        // a senzorului). Nu trece prin corelare — nu are porturi.
+                        if rejected_lines == total_lines {
+                            if let Some(mut alert) = detector.record_malformed(addr.ip(), received_at) {
+                                alert.ingress.clone_from(&ingress);
+                                counters.record_alerts(std::slice::from_ref(&alert));
+                                emit_alert(alert, config.web_dashboard.max_alerts);
+                            }
+                        }
+
+                        // Decizia de detectie pentru toate liniile pachetului e luata.
+                        counters.latency.record(received_at.elapsed());
+
+                        // Dead letter: pachetul INTREG (octetii bruti, nu textul
+                        // lossy), cu motivul respingerii.
+                        if rejected_lines > 0 {
+                            if let Some(ref mut writer) = deadletter {
+                                let utf8 = if empty {
+                                    "datagrama goala; "
+                                } else if std::str::from_utf8(&buf[..len]).is_err() {
+                                    "UTF-8 invalid; "
+                                } else {
+                                    ""
+                                };
+                                let reason = format!(
+                                    "{}{}/{} linii respinse de parser-ul '{}'",
+                                    utf8,
+                                    rejected_lines,
+                                    total_lines,
+                                    parser.name()
                                 );
+                                let result = writer.record(addr, &buf[..len], &reason);
+                                counters.last_errors.observe(Subsystem::DeadLetter, &result);
+                                if let Err(e) = result {
+                                    display::log_warning(&format!("{:#}", e));
+                                }
                             }
                         }
                     }
                     Err(e) => {
                         // Erorile de receptie UDP sunt de obicei tranzitorii.
                         // Le logam ca warning si continuam - nu oprim procesul.
+                        counters.last_errors.record(Subsystem::Socket, e.to_string());
                         display::log_warning(&format!("Eroare receptie UDP: {}", e));
                     }
                 }
@@ -714,6 +2119,12 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    // Alertele inca retinute de corelare nu se pierd la oprire.
+    if let Some(ref mut c) = correlator {
+        for alert in c.drain() {
+            emit_alert(alert, config.web_dashboard.max_alerts);
+        }
+    }
     drop(alert_tx);
     match tokio::time::timeout(Duration::from_secs(10), alert_worker).await {
         Ok(Ok(())) => {}
@@ -725,5 +2136,100 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
+    display::log_shutdown_summary(
+        counters.started_at.elapsed(),
+        counters.events_processed.load(Ordering::Relaxed),
+        counters.alerts_generated.load(Ordering::Relaxed),
+        detector.tracked_ips(),
+    );
+    if diagnostics_report {
+        display::log_diagnostics_report(&counters.packets.report(&counters, &detector));
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_event(port: u16) -> parser::LogEvent {
+        parser::LogEvent {
+            source_ip: "192.168.1.10".parse().unwrap(),
+            dest_ip: Some("10.0.0.1".parse().unwrap()),
+            dest_port: port,
+            protocol: "tcp".to_string(),
+            action: "drop".to_string(),
+            packet_bytes: None,
+            interface: None,
+            source_port: None,
+            raw_log: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_dedup_window_starts_at_first_seen() {
+        let mut dedup = EventDeduplicator::new(100);
+        let age = |dedup: &mut EventDeduplicator, ms: u64| {
+            for seen_at in dedup.seen.values_mut() {
+                *seen_at -= Duration::from_millis(ms);
+            }
+        };
+
+        assert!(!dedup.is_duplicate(&make_event(22)));
+        assert!(dedup.is_duplicate(&make_event(22)));
+        assert!(!dedup.is_duplicate(&make_event(23)), "alt port = alt eveniment");
+
+        // O copie la 60ms nu prelungeste fereastra...
+        age(&mut dedup, 60);
+        assert!(dedup.is_duplicate(&make_event(22)));
+        // ...deci la 120ms de la prima aparitie evenimentul trece din nou.
+        age(&mut dedup, 60);
+        assert!(!dedup.is_duplicate(&make_event(22)));
+        assert!(dedup.is_duplicate(&make_event(22)), "noua aparitie deschide o fereastra noua");
+        assert_eq!(dedup.take_duplicate_count(), 3);
+    }
+
+    #[test]
+    fn test_cleanup_counter_fires_every_n_packets() {
+        let mut counter = CleanupCounter::new();
+        assert!(!(0..10).any(|_| counter.tick(0)), "0 = dezactivat");
+        assert_eq!(counter.packets, 0);
+
+        let fired: Vec<usize> = (1..=7000).filter(|_| counter.tick(2000)).collect();
+        assert_eq!(fired, vec![2000, 4000, 6000]);
+
+        // Prag redus la SIGHUP sub numarul deja acumulat (1000): declansare imediata.
+        assert!(counter.tick(1000));
+        assert_eq!(counter.packets, 0);
+    }
+
+    #[test]
+    fn test_parse_error_sampler_one_sample_per_window() {
+        let mut sampler = ParseErrorSampler::new();
+        sampler.record("ab");
+        sampler.record("linie ignorata");
+        sampler.record("alta linie ignorata");
+
+        // Fereastra de un minut inca deschisa: niciun raport.
+        assert!(sampler.take_report().is_none());
+
+        sampler.window_start -= Duration::from_secs(61);
+        let (count, secs, sample) = sampler.take_report().unwrap();
+        assert_eq!(count, 3, "toate erorile sunt numarate");
+        assert!(secs >= 61);
+        assert_eq!(sample, "61 62", "doar prima linie este esantionata");
+
+        // Fereastra noua: fara erori nu exista raport, chiar dupa expirare.
+        sampler.window_start -= Duration::from_secs(61);
+        assert!(sampler.take_report().is_none());
+
+        // Exemplul este limitat la PARSE_ERROR_SAMPLE_BYTES octeti.
+        sampler.record(&"x".repeat(PARSE_ERROR_SAMPLE_BYTES + 8));
+        sampler.window_start -= Duration::from_secs(61);
+        let (count, _, sample) = sampler.take_report().unwrap();
+        assert_eq!(count, 1);
+        assert!(sample.ends_with(&format!(" ... ({} octeti)", PARSE_ERROR_SAMPLE_BYTES + 8)));
+        assert_eq!(sample.matches("78").count(), PARSE_ERROR_SAMPLE_BYTES);
+    }
+}
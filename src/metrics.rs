@@ -0,0 +1,1381 @@
+// =============================================================================
+// metrics.rs - Endpoint Metrici Prometheus
+// =============================================================================
+//
+// Server HTTP minimal (axum) care expune metrici in formatul text Prometheus:
+//   GET /metrics → text/plain; version=0.0.4
+//   GET /stats   → snapshot JSON: contoare, top talkers, alerte recente,
+//                  ultima eroare per subsistem, contabilitatea pachetelor
+//   GET /        → pagina HTML minimala peste /stats (optional, `metrics.web_ui`)
+//
+// Doua categorii de metrici:
+//   1. Operationale  — starea curenta a senzorului (IP-uri urmarite etc.)
+//   2. Ruleset       — pragurile de detectie si regulile active (optional,
+//                      `metrics.export_ruleset`). Permit detectia config drift:
+//                      Prometheus poate alerta cand pragurile unui senzor
+//                      difera de baseline-ul flotei.
+//
+// Config-ul este citit din Detector la FIECARE scrape (snapshot ArcSwap),
+// deci metricile reflecta automat un reload SIGHUP — fara restart.
+//
+// Pagina `web_ui` este gandita pentru instalari mici fara Grafana: HTML + JS
+// inline (include_str!), fara resurse externe, reimprospatare la 5s.
+//
+// NOTA: Formatul text Prometheus este trivial (HELP/TYPE + `nume{labels} valoare`),
+// asa ca il generam manual cu `format!` — fara dependenta prometheus/opentelemetry.
+//
+// =============================================================================
+
+use crate::config::{DetectionConfig, MetricsConfig};
+use crate::detector::{Alert, Detector};
+use crate::display;
+use crate::web::AlertBuffer;
+use axum::{extract::State, response::Html, routing::get, Json, Router};
+use chrono::Timelike;
+use std::fmt::Write;
+use chrono::{DateTime, Local};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Numarul de surse din `top_talkers` in `/stats`.
+const STATS_TOP_TALKERS: usize = 10;
+
+/// Numarul de alerte recente din `/stats` (cele mai noi primele).
+const STATS_RECENT_ALERTS: usize = 20;
+
+/// Contoare de rulare partajate intre bucla principala (producator) si
+/// serverul de metrici (cititor). Folosite si pentru sumarul de la oprire.
+///
+/// NOTA RUST: `AtomicU64` cu `Ordering::Relaxed` — contoarele sunt
+/// independente, nu sincronizeaza alte date, deci nu avem nevoie de
+/// garantii de ordonare mai stricte.
+pub struct RuntimeCounters {
+    pub started_at: Instant,
+    pub events_processed: AtomicU64,
+    pub alerts_generated: AtomicU64,
+    /// Histograma alertelor pe ora din zi (ora locala a alertei, 0-23).
+    /// Actualizata la fiecare alerta — 24 de atomice, cost neglijabil.
+    alerts_by_hour: [AtomicU64; 24],
+    /// Alertele pe minut din ultima ora (raportul SIGUSR2): inel de 60 de
+    /// bucket-uri, fiecare cu minutul Unix caruia ii apartine contorul.
+    alerts_by_minute: [AtomicU64; 60],
+    alerts_minute_stamp: [AtomicU64; 60],
+    /// Ultima eroare per subsistem (socket, sink-uri, config). Partajat si
+    /// cu Alerter-ul, care raporteaza erorile sink-urilor.
+    pub last_errors: Arc<LastErrors>,
+    /// Latenta per pachet receptie → decizie (`metrics.latency_histogram`).
+    pub latency: LatencyHistogram,
+    /// Unde s-au dus pachetele: primite, parsate, dropate pe motive.
+    pub packets: PacketAccounting,
+}
+
+impl RuntimeCounters {
+    pub fn new() -> Self {
+        RuntimeCounters {
+            started_at: Instant::now(),
+            events_processed: AtomicU64::new(0),
+            alerts_generated: AtomicU64::new(0),
+            alerts_by_hour: std::array::from_fn(|_| AtomicU64::new(0)),
+            alerts_by_minute: std::array::from_fn(|_| AtomicU64::new(0)),
+            alerts_minute_stamp: std::array::from_fn(|_| AtomicU64::new(0)),
+            last_errors: Arc::new(LastErrors::new()),
+            latency: LatencyHistogram::new(),
+            packets: PacketAccounting::new(),
+        }
+    }
+
+    /// Numara alertele generate: totalul si bucket-ul orei fiecarei alerte.
+    pub fn record_alerts(&self, alerts: &[Alert]) {
+        self.alerts_generated
+            .fetch_add(alerts.len() as u64, Ordering::Relaxed);
+        for alert in alerts {
+            self.alerts_by_hour[alert.timestamp.hour() as usize].fetch_add(1, Ordering::Relaxed);
+            self.record_alert_minute(alert.timestamp.timestamp().max(0) as u64 / 60);
+        }
+    }
+
+    /// Numara o alerta in bucket-ul minutului Unix `minute`. Bucket-ul ramas
+    /// de acum o ora (acelasi index) este rescris inainte de numarare.
+    ///
+    /// NOTA: stamp + contor nu sunt actualizate atomic impreuna — alertele
+    /// sunt numarate din bucla principala, deci cursa nu apare in practica;
+    /// in cel mai rau caz o alerta este pierduta din raport, nu din total.
+    fn record_alert_minute(&self, minute: u64) {
+        let idx = (minute % 60) as usize;
+        if self.alerts_minute_stamp[idx].swap(minute, Ordering::Relaxed) != minute {
+            self.alerts_by_minute[idx].store(0, Ordering::Relaxed);
+        }
+        self.alerts_by_minute[idx].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Alertele din ultimele 60 de minute (inclusiv minutul curent) la
+    /// minutul Unix `now_minute`. Rezolutie de un minut.
+    pub fn alerts_last_hour(&self, now_minute: u64) -> u64 {
+        (0..60)
+            .filter(|&i| {
+                let stamp = self.alerts_minute_stamp[i].load(Ordering::Relaxed);
+                stamp <= now_minute && now_minute - stamp < 60
+            })
+            .map(|i| self.alerts_by_minute[i].load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Snapshot al histogramei pe ore (index = ora din zi).
+    pub fn alerts_by_hour(&self) -> [u64; 24] {
+        std::array::from_fn(|h| self.alerts_by_hour[h].load(Ordering::Relaxed))
+    }
+}
+
+impl Default for RuntimeCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Statistici pe o retea linistita (output.suppress_empty_stats)
+// -----------------------------------------------------------------------------
+//
+// Pe o retea fara trafic, linia STAT repeta "N IP-uri urmarite" la fiecare
+// ciclu de cleanup, la nesfarsit. Cu optiunea activata, un ciclu in care au
+// sosit cel mult `quiet_max_events` evenimente si nicio alerta nu mai afiseaza
+// linia STAT; la fiecare `quiet_heartbeat_secs` de liniste apare o singura
+// linie "activ, linistit" — semnalul de viata ramane.
+
+/// Ce afiseaza ciclul curent de statistici.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsReport {
+    /// Activitate (sau suprimarea dezactivata): linia STAT normala.
+    Full,
+    /// Liniste de `quiet_secs` secunde: heartbeat-ul periodic.
+    Heartbeat { quiet_secs: u64 },
+    /// Liniste, heartbeat-ul nu este inca datorat: nimic.
+    Skip,
+}
+
+/// Starea suprimarii intre cicluri (detinuta de task-ul de cleanup).
+pub struct QuietStats {
+    enabled: bool,
+    max_events: u64,
+    heartbeat: std::time::Duration,
+    last_events: u64,
+    last_alerts: u64,
+    /// Inceputul perioadei linistite curente (`None` = ultimul ciclu a fost activ).
+    quiet_since: Option<Instant>,
+    last_heartbeat: Option<Instant>,
+}
+
+impl QuietStats {
+    pub fn new(cfg: &crate::config::OutputConfig) -> Self {
+        Self {
+            enabled: cfg.suppress_empty_stats,
+            max_events: cfg.quiet_max_events,
+            heartbeat: std::time::Duration::from_secs(cfg.quiet_heartbeat_secs),
+            last_events: 0,
+            last_alerts: 0,
+            quiet_since: None,
+            last_heartbeat: None,
+        }
+    }
+
+    /// Decide ciclul curent din totalurile cumulative ale contoarelor.
+    pub fn check(&mut self, events_total: u64, alerts_total: u64, now: Instant) -> StatsReport {
+        let events = events_total.saturating_sub(self.last_events);
+        let alerts = alerts_total.saturating_sub(self.last_alerts);
+        self.last_events = events_total;
+        self.last_alerts = alerts_total;
+        if !self.enabled {
+            return StatsReport::Full;
+        }
+        if events > self.max_events || alerts > 0 {
+            self.quiet_since = None;
+            self.last_heartbeat = None;
+            return StatsReport::Full;
+        }
+        let since = *self.quiet_since.get_or_insert(now);
+        let reference = self.last_heartbeat.unwrap_or(since);
+        if now.saturating_duration_since(reference) >= self.heartbeat {
+            self.last_heartbeat = Some(now);
+            return StatsReport::Heartbeat {
+                quiet_secs: now.saturating_duration_since(since).as_secs(),
+            };
+        }
+        StatsReport::Skip
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Ultima eroare per subsistem
+// -----------------------------------------------------------------------------
+//
+// Monitorizarea trebuie sa poata alerta pe "sink-ul SIEM esueaza de 5 minute"
+// fara sa parseze log-urile. Fiecare subsistem are un slot fix: o eroare il
+// completeaza (pastrand momentul PRIMEI erori din seria curenta), urmatorul
+// succes il goleste. Destinatiile de alertare au cate un slot per INSTANTA
+// (`AlertSink::name` — "siem" pentru sectiunea legacy, `name` pentru o
+// intrare din `alerting.sinks`): doua SIEM-uri nu isi sterg erorile una alteia.
+// Expus in `/stats` (`last_errors`) si Prometheus (`ids_subsystem_error`,
+// `ids_subsystem_error_seconds`).
+
+/// Subsistemele fixe urmarite — cate un slot fiecare.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    /// Receptia UDP (`recv_from`).
+    Socket,
+    /// Reincarcarea config.toml (SIGHUP).
+    Config,
+    /// Scrierea fisierului `network.deadletter_file`.
+    DeadLetter,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 3] = [Subsystem::Socket, Subsystem::Config, Subsystem::DeadLetter];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Subsystem::Socket => "socket",
+            Subsystem::Config => "config",
+            Subsystem::DeadLetter => "deadletter",
+        }
+    }
+}
+
+/// Eroarea curenta a unui subsistem.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LastError {
+    pub message: String,
+    /// Prima eroare din seria curenta (fara succes intre ele).
+    pub since: DateTime<Local>,
+    pub last_at: DateTime<Local>,
+    /// Erori consecutive de la `since`.
+    pub consecutive: u64,
+}
+
+/// Sloturile de eroare, partajate intre bucla principala, worker-ul de
+/// alerte si serverul de metrici.
+///
+/// NOTA RUST: `clear` este apelat la FIECARE succes (ex: fiecare pachet UDP).
+/// `AtomicBool` per slot il face un simplu load cand slotul e deja gol —
+/// mutex-ul este luat doar cand exista o eroare de sters.
+pub struct LastErrors {
+    active: [AtomicBool; Subsystem::ALL.len()],
+    slots: Mutex<[Option<LastError>; Subsystem::ALL.len()]>,
+    /// Sloturile destinatiilor de alertare, in ordinea livrarii (`set_sinks`).
+    sinks: Mutex<Vec<(String, Option<LastError>)>>,
+}
+
+/// Completeaza un slot; `since` ramane cel al primei erori din serie.
+fn fill_slot(slot: &mut Option<LastError>, message: String) {
+    let now = Local::now();
+    let (since, consecutive) = match slot {
+        Some(prev) => (prev.since, prev.consecutive + 1),
+        None => (now, 1),
+    };
+    *slot = Some(LastError {
+        message,
+        since,
+        last_at: now,
+        consecutive,
+    });
+}
+
+impl LastErrors {
+    pub fn new() -> Self {
+        Self {
+            active: std::array::from_fn(|_| AtomicBool::new(false)),
+            slots: Mutex::new(Default::default()),
+            sinks: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, [Option<LastError>; Subsystem::ALL.len()]> {
+        self.slots.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Inregistreaza o eroare; `since` ramane cel al primei erori din serie.
+    pub fn record(&self, subsystem: Subsystem, message: impl Into<String>) {
+        fill_slot(&mut self.lock()[subsystem as usize], message.into());
+        self.active[subsystem as usize].store(true, Ordering::Relaxed);
+    }
+
+    /// Succes: goleste slotul subsistemului.
+    pub fn clear(&self, subsystem: Subsystem) {
+        if self.active[subsystem as usize].swap(false, Ordering::Relaxed) {
+            self.lock()[subsystem as usize] = None;
+        }
+    }
+
+    /// `Ok` goleste slotul, `Err` il completeaza (mesajul cu tot lantul anyhow).
+    pub fn observe<T>(&self, subsystem: Subsystem, result: &anyhow::Result<T>) {
+        match result {
+            Ok(_) => self.clear(subsystem),
+            Err(e) => self.record(subsystem, format!("{:#}", e)),
+        }
+    }
+
+    /// Stabileste instantele destinatiilor (pornire si SIGHUP). O instanta
+    /// pastrata isi pastreaza eroarea; una disparuta isi pierde slotul.
+    pub fn set_sinks<'a>(&self, names: impl IntoIterator<Item = &'a str>) {
+        let mut sinks = self.sinks.lock().unwrap_or_else(|e| e.into_inner());
+        let mut old = std::mem::take(&mut *sinks);
+        for name in names {
+            if sinks.iter().any(|(n, _)| n == name) {
+                continue;
+            }
+            let error = old
+                .iter_mut()
+                .find(|(n, _)| n == name)
+                .and_then(|(_, e)| e.take());
+            sinks.push((name.to_string(), error));
+        }
+    }
+
+    /// Rezultatul unei livrari catre instanta `name`: `Ok` goleste slotul ei,
+    /// `Err` il completeaza. O instanta neinregistrata (ex: dupa un SIGHUP care
+    /// a scos-o) este ignorata.
+    pub fn observe_sink<T>(&self, name: &str, result: &anyhow::Result<T>) {
+        let mut sinks = self.sinks.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, slot)) = sinks.iter_mut().find(|(n, _)| n == name) {
+            match result {
+                Ok(_) => *slot = None,
+                Err(e) => fill_slot(slot, format!("{:#}", e)),
+            }
+        }
+    }
+
+    /// Copie a tuturor sloturilor: subsistemele fixe, in ordinea
+    /// `Subsystem::ALL`, apoi instantele destinatiilor.
+    pub fn snapshot(&self) -> Vec<(String, Option<LastError>)> {
+        let mut out: Vec<(String, Option<LastError>)> = {
+            let slots = self.lock();
+            Subsystem::ALL
+                .iter()
+                .map(|&sub| (sub.as_str().to_string(), slots[sub as usize].clone()))
+                .collect()
+        };
+        out.extend(self.sinks.lock().unwrap_or_else(|e| e.into_inner()).iter().cloned());
+        out
+    }
+}
+
+impl Default for LastErrors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Latenta pipeline-ului de detectie
+// -----------------------------------------------------------------------------
+//
+// Timpul de la receptia unui pachet UDP pana la decizia de detectie pentru
+// toate liniile lui (parsare, afisare, detector, profiluri). Un p99 in crestere
+// arata ca senzorul devine gatuire INAINTE sa inceapa sa piarda pachete.
+//
+// Histograma are bucket-uri fixe (scara ~logaritmica, 10µs .. 1s): `record`
+// inseamna doua `fetch_add` pe atomice, fara lock si fara alocare. Dezactivata,
+// costul este un singur load atomic. Doua seturi de contoare:
+//   - cumulativ   → histograma Prometheus (`_bucket`, `_sum`, `_count`);
+//   - fereastra   → percentilele rulante p50/p95/p99, resetate la fiecare
+//                   ciclu de statistici (`cleanup.interval_secs`).
+// Percentila este limita superioara a bucket-ului in care cade — precizia
+// este cea a bucket-urilor, suficienta pentru a vedea o tendinta.
+
+/// Limitele superioare ale bucket-urilor, in microsecunde. Ultimul bucket
+/// (peste 1s) este `+Inf`.
+const LATENCY_BOUNDS_US: [u64; 16] = [
+    10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000,
+    500_000, 1_000_000,
+];
+const LATENCY_BUCKETS: usize = LATENCY_BOUNDS_US.len() + 1;
+
+/// Percentilele unei ferestre, in microsecunde (`u64::MAX` = peste 1s).
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct LatencyPercentiles {
+    pub p50_us: u64,
+    pub p95_us: u64,
+    pub p99_us: u64,
+    /// Pachete masurate in fereastra.
+    pub packets: u64,
+}
+
+pub struct LatencyHistogram {
+    enabled: AtomicBool,
+    total: [AtomicU64; LATENCY_BUCKETS],
+    window: [AtomicU64; LATENCY_BUCKETS],
+    sum_us: AtomicU64,
+    /// Percentilele ultimei ferestre incheiate — citite de `/metrics` si `/stats`.
+    last: Mutex<Option<LatencyPercentiles>>,
+}
+
+impl LatencyHistogram {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            total: std::array::from_fn(|_| AtomicU64::new(0)),
+            window: std::array::from_fn(|_| AtomicU64::new(0)),
+            sum_us: AtomicU64::new(0),
+            last: Mutex::new(None),
+        }
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Inregistreaza latenta unui pachet. No-op cand histograma e dezactivata.
+    pub fn record(&self, elapsed: std::time::Duration) {
+        if !self.is_enabled() {
+            return;
+        }
+        let us = elapsed.as_micros().min(u64::MAX as u128) as u64;
+        let idx = LATENCY_BOUNDS_US.partition_point(|&bound| bound < us);
+        self.total[idx].fetch_add(1, Ordering::Relaxed);
+        self.window[idx].fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(us, Ordering::Relaxed);
+    }
+
+    /// Incheie fereastra curenta: calculeaza percentilele si reseteaza
+    /// contoarele ferestrei. `None` daca niciun pachet nu a fost masurat.
+    pub fn roll(&self) -> Option<LatencyPercentiles> {
+        let counts: [u64; LATENCY_BUCKETS] =
+            std::array::from_fn(|i| self.window[i].swap(0, Ordering::Relaxed));
+        let percentiles = window_percentiles(&counts);
+        *self.last.lock().unwrap_or_else(|e| e.into_inner()) = percentiles;
+        percentiles
+    }
+
+    /// Percentilele ultimei ferestre incheiate.
+    pub fn last(&self) -> Option<LatencyPercentiles> {
+        *self.last.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// p50/p95/p99 din contoarele unei ferestre (limita superioara a bucket-ului).
+fn window_percentiles(counts: &[u64; LATENCY_BUCKETS]) -> Option<LatencyPercentiles> {
+    let packets: u64 = counts.iter().sum();
+    if packets == 0 {
+        return None;
+    }
+    let quantile = |q: f64| {
+        let rank = ((q * packets as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, count) in counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return LATENCY_BOUNDS_US.get(i).copied().unwrap_or(u64::MAX);
+            }
+        }
+        u64::MAX
+    };
+    Some(LatencyPercentiles {
+        p50_us: quantile(0.50),
+        p95_us: quantile(0.95),
+        p99_us: quantile(0.99),
+        packets,
+    })
+}
+
+/// Microsecunde → secunde pentru Prometheus (`+Inf` peste ultimul bucket).
+fn latency_seconds(us: u64) -> String {
+    if us == u64::MAX {
+        "+Inf".to_string()
+    } else {
+        (us as f64 / 1_000_000.0).to_string()
+    }
+}
+
+/// Histograma Prometheus cumulativa + gauge-urile percentilelor rulante.
+fn render_latency(out: &mut String, latency: &LatencyHistogram) {
+    let name = "ids_detection_latency_seconds";
+    let _ = writeln!(out, "# HELP {} Latenta per pachet: receptie UDP pana la decizia de detectie", name);
+    let _ = writeln!(out, "# TYPE {} histogram", name);
+    let mut cumulative = 0;
+    for (i, bucket) in latency.total.iter().enumerate() {
+        cumulative += bucket.load(Ordering::Relaxed);
+        let le = LATENCY_BOUNDS_US.get(i).copied().unwrap_or(u64::MAX);
+        let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, latency_seconds(le), cumulative);
+    }
+    let sum = latency.sum_us.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+    let _ = writeln!(out, "{}_sum {}", name, sum);
+    let _ = writeln!(out, "{}_count {}", name, cumulative);
+
+    // Fara fereastra incheiata inca (sau fara trafic) seriile lipsesc —
+    // un 0 ar arata ca o latenta excelenta.
+    if let Some(p) = latency.last() {
+        let name = "ids_detection_latency_rolling_seconds";
+        let _ = writeln!(out, "# HELP {} Percentila latentei in ultima fereastra de statistici", name);
+        let _ = writeln!(out, "# TYPE {} gauge", name);
+        for (q, us) in [("0.5", p.p50_us), ("0.95", p.p95_us), ("0.99", p.p99_us)] {
+            let _ = writeln!(out, "{}{{quantile=\"{}\"}} {}", name, q, latency_seconds(us));
+        }
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Contabilitatea pachetelor ("unde s-au dus pachetele mele?")
+// -----------------------------------------------------------------------------
+//
+// Un senzor care "rateaza" scanari pierde de obicei evenimentele INAINTE de
+// reguli: coada socket-ului plina, rate limiter, linii neparsabile, whitelist.
+// Fiecare etapa a pipeline-ului are un contor; `report()` le aduna intr-un
+// snapshot afisat de `--diagnostics-report` si expus in `/stats`
+// (`packet_accounting`) si Prometheus (`ids_packets_dropped_total{reason}`).
+//
+//   datagrame primite ── rate_limit ──→ linii ── parse_error ──→ parsate
+//   parsate ── duplicate ──→ procesate ── whitelist / trusted_signature ──→ analizate
+//
+// Drop-urile kernel (coada de receptie plina) nu ajung niciodata la aplicatie:
+// sunt citite din coloana `drops` a `/proc/net/udp{,6}`, dupa inode-ul
+// socket-ului. Pe alte sisteme decat Linux raman necunoscute (`null`).
+//
+// Categorii fara contor, pentru ca etapa nu exista in pipeline:
+//   - sampled-out: nu exista esantionare; singura reducere de volum este
+//     `network.udp_rate_limit` (contorul `rate_limit`).
+//   - ignored-port: nu exista filtru global de porturi; listele
+//     `detection.exceptions.ignore_*_ports` exclud porturi doar din regula
+//     lor, dupa etapa `analizate`.
+//   - out-of-range: un port sau IP invalid face linia neparsabila — numarata
+//     la `parse_error`.
+
+/// Contoarele etapelor, incrementate de bucla principala.
+pub struct PacketAccounting {
+    pub datagrams: AtomicU64,
+    /// Datagrame aruncate de `network.udp_rate_limit`.
+    pub rate_limited: AtomicU64,
+    /// Linii nevide din datagramele acceptate de rate limiter.
+    pub lines: AtomicU64,
+    pub parse_errors: AtomicU64,
+    /// Evenimente eliminate de `network.dedup_window_ms`.
+    pub duplicates: AtomicU64,
+    /// Inode-ul socket-ului UDP (0 = necunoscut) pentru drop-urile kernel.
+    socket_inode: AtomicU64,
+}
+
+/// Snapshot-ul contabilitatii; `parsed` si `analyzed` sunt derivate.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PacketReport {
+    /// Drop-uri kernel (coada socket-ului plina); `None` = indisponibil.
+    pub kernel_dropped: Option<u64>,
+    pub datagrams_received: u64,
+    pub rate_limited: u64,
+    pub lines_received: u64,
+    pub parse_errors: u64,
+    pub parsed: u64,
+    pub duplicates: u64,
+    pub events_processed: u64,
+    pub whitelisted: u64,
+    pub trusted_signature: u64,
+    /// Evenimente care au ajuns la regulile de detectie.
+    pub analyzed: u64,
+    pub alerts_generated: u64,
+}
+
+impl PacketAccounting {
+    pub fn new() -> Self {
+        Self {
+            datagrams: AtomicU64::new(0),
+            rate_limited: AtomicU64::new(0),
+            lines: AtomicU64::new(0),
+            parse_errors: AtomicU64::new(0),
+            duplicates: AtomicU64::new(0),
+            socket_inode: AtomicU64::new(0),
+        }
+    }
+
+    /// Retine socket-ul de receptie pentru citirea drop-urilor kernel.
+    #[cfg(unix)]
+    pub fn set_socket(&self, socket: &impl std::os::unix::io::AsRawFd) {
+        use std::os::unix::fs::MetadataExt;
+        let fd = socket.as_raw_fd();
+        if let Ok(meta) = std::fs::metadata(format!("/proc/self/fd/{}", fd)) {
+            self.socket_inode.store(meta.ino(), Ordering::Relaxed);
+        }
+    }
+
+    /// Drop-urile kernel ale socket-ului, din `/proc/net/udp` si `/proc/net/udp6`.
+    fn kernel_dropped(&self) -> Option<u64> {
+        let inode = self.socket_inode.load(Ordering::Relaxed);
+        if inode == 0 {
+            return None;
+        }
+        ["/proc/net/udp", "/proc/net/udp6"]
+            .iter()
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .find_map(|table| proc_udp_drops(&table, inode))
+    }
+
+    /// Consolideaza contoarele etapelor cu cele ale detectorului.
+    pub fn report(&self, counters: &RuntimeCounters, detector: &Detector) -> PacketReport {
+        let load = |c: &AtomicU64| c.load(Ordering::Relaxed);
+        let (whitelisted, trusted_signature) = detector.filtered_events();
+        let lines_received = load(&self.lines);
+        let parse_errors = load(&self.parse_errors);
+        let events_processed = load(&counters.events_processed);
+        PacketReport {
+            kernel_dropped: self.kernel_dropped(),
+            datagrams_received: load(&self.datagrams),
+            rate_limited: load(&self.rate_limited),
+            lines_received,
+            parse_errors,
+            parsed: lines_received.saturating_sub(parse_errors),
+            duplicates: load(&self.duplicates),
+            events_processed,
+            whitelisted,
+            trusted_signature,
+            analyzed: events_processed.saturating_sub(whitelisted + trusted_signature),
+            alerts_generated: load(&counters.alerts_generated),
+        }
+    }
+}
+
+impl Default for PacketAccounting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Coloana `drops` (ultima) a liniei cu inode-ul dat dintr-un tabel
+/// `/proc/net/udp`. Coloanele: sl local rem st tx:rx tr:tm retr uid timeout
+/// inode ref pointer drops.
+fn proc_udp_drops(table: &str, inode: u64) -> Option<u64> {
+    table.lines().skip(1).find_map(|line| {
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.get(9)?.parse::<u64>().ok()? != inode {
+            return None;
+        }
+        cols.last()?.parse().ok()
+    })
+}
+
+/// `ids_packets_*`: etapele pipeline-ului si drop-urile pe motive.
+fn render_packet_accounting(out: &mut String, report: &PacketReport) {
+    write_counter(
+        out,
+        "ids_udp_datagrams_received_total",
+        "Datagrame UDP primite de la pornire",
+        report.datagrams_received,
+    );
+    write_counter(
+        out,
+        "ids_log_lines_received_total",
+        "Linii de log primite (dupa rate limiter)",
+        report.lines_received,
+    );
+    let name = "ids_packets_dropped_total";
+    let _ = writeln!(out, "# HELP {} Pachete / evenimente pierdute inainte de reguli, pe motive", name);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    // Seria kernel_overflow lipseste cand nu poate fi citita — un 0 ar minti.
+    if let Some(dropped) = report.kernel_dropped {
+        let _ = writeln!(out, "{}{{reason=\"kernel_overflow\"}} {}", name, dropped);
+    }
+    for (reason, count) in [
+        ("rate_limit", report.rate_limited),
+        ("parse_error", report.parse_errors),
+        ("duplicate", report.duplicates),
+        ("whitelist", report.whitelisted),
+        ("trusted_signature", report.trusted_signature),
+    ] {
+        let _ = writeln!(out, "{}{{reason=\"{}\"}} {}", name, reason, count);
+    }
+}
+
+/// Stare partajata injectata in handler-ele serverului de metrici.
+#[derive(Clone)]
+struct MetricsState {
+    detector: Arc<Detector>,
+    alerts: AlertBuffer,
+    counters: Arc<RuntimeCounters>,
+    export_ruleset: bool,
+    alerts_by_hour: bool,
+}
+
+/// Porneste serverul de metrici pe un task tokio separat.
+pub async fn start_metrics_server(
+    config: &MetricsConfig,
+    detector: Arc<Detector>,
+    alerts: AlertBuffer,
+    counters: Arc<RuntimeCounters>,
+) -> anyhow::Result<tokio::task::JoinHandle<()>> {
+    let state = MetricsState {
+        detector,
+        alerts,
+        counters,
+        export_ruleset: config.export_ruleset,
+        alerts_by_hour: config.alerts_by_hour,
+    };
+
+    let mut app = Router::new()
+        .route("/metrics", get(get_metrics))
+        .route("/stats", get(get_stats));
+    if config.web_ui {
+        app = app.route("/", get(get_web_ui));
+    }
+    let app = app.with_state(state);
+
+    let bind_addr = format!("{}:{}", config.bind, config.port);
+    let listener = tokio::net::TcpListener::bind(&bind_addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("Nu pot face bind pe {}: {}", bind_addr, e))?;
+
+    display::log_info(&format!("Metrici Prometheus: http://{}/metrics", bind_addr));
+    if config.web_ui {
+        display::log_info(&format!("Pagina stare senzor: http://{}/", bind_addr));
+    }
+
+    let handle = tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            display::log_error(&format!("Metrics server error: {:#}", e));
+        }
+    });
+
+    Ok(handle)
+}
+
+/// GET /metrics — metrici in format text Prometheus (exposition format 0.0.4).
+async fn get_metrics(
+    State(state): State<MetricsState>,
+) -> ([(axum::http::header::HeaderName, &'static str); 1], String) {
+    let mut out = String::with_capacity(4096);
+
+    write_metric(
+        &mut out,
+        "ids_tracked_ips",
+        "IP-uri sursa urmarite in memorie",
+        "",
+        state.detector.tracked_ips() as f64,
+    );
+    write_counter(
+        &mut out,
+        "ids_events_processed_total",
+        "Evenimente procesate de detector de la pornire",
+        state.counters.events_processed.load(Ordering::Relaxed),
+    );
+    write_counter(
+        &mut out,
+        "ids_alerts_generated_total",
+        "Alerte generate de la pornire",
+        state.counters.alerts_generated.load(Ordering::Relaxed),
+    );
+    render_packet_accounting(
+        &mut out,
+        &state.counters.packets.report(&state.counters, &state.detector),
+    );
+
+    render_last_errors(&mut out, &state.counters.last_errors);
+
+    if state.counters.latency.is_enabled() {
+        render_latency(&mut out, &state.counters.latency);
+    }
+
+    if state.alerts_by_hour {
+        render_alerts_by_hour(&mut out, &state.counters.alerts_by_hour());
+    }
+
+    if state.export_ruleset {
+        render_ruleset(&mut out, &state.detector.config(), state.detector.inbound_only());
+    }
+
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        out,
+    )
+}
+
+/// GET /stats — snapshot JSON al starii senzorului (sursa paginii `web_ui`).
+async fn get_stats(State(state): State<MetricsState>) -> Json<serde_json::Value> {
+    // Copiem doar ultimele N alerte sub lock; serializarea se face fara lock.
+    let recent: Vec<Alert> = {
+        let buf = state.alerts.lock().unwrap_or_else(|e| e.into_inner());
+        buf.iter().rev().take(STATS_RECENT_ALERTS).cloned().collect()
+    };
+
+    let mut stats = build_stats(&state.detector, &state.counters, &recent);
+    if state.alerts_by_hour {
+        stats["alerts_by_hour"] = serde_json::json!(state.counters.alerts_by_hour());
+    }
+    if state.counters.latency.is_enabled() {
+        stats["detection_latency"] = serde_json::json!(state.counters.latency.last());
+    }
+    Json(stats)
+}
+
+/// Construieste documentul `/stats`. Separat de handler pentru testare.
+fn build_stats(
+    detector: &Detector,
+    counters: &RuntimeCounters,
+    recent_alerts: &[Alert],
+) -> serde_json::Value {
+    serde_json::json!({
+        "uptime_secs": counters.started_at.elapsed().as_secs(),
+        "events_processed": counters.events_processed.load(Ordering::Relaxed),
+        "alerts_generated": counters.alerts_generated.load(Ordering::Relaxed),
+        "tracked_ips": detector.tracked_ips(),
+        "policy_version": detector.policy_version(),
+        "traffic_direction": if detector.inbound_only() { "inbound_only" } else { "both" },
+        "top_talkers": detector.top_talkers(STATS_TOP_TALKERS),
+        "recent_alerts": recent_alerts,
+        "last_errors": last_errors_json(&counters.last_errors),
+        "packet_accounting": counters.packets.report(counters, detector),
+    })
+}
+
+/// `last_errors` din `/stats`: subsistem / instanta de destinatie → eroarea
+/// curenta sau `null`. Toate sloturile sunt prezente, ca un consumator sa nu
+/// confunde "fara eroare" cu "camp lipsa".
+fn last_errors_json(errors: &LastErrors) -> serde_json::Value {
+    let map: serde_json::Map<String, serde_json::Value> = errors
+        .snapshot()
+        .into_iter()
+        .map(|(name, err)| (name, serde_json::json!(err)))
+        .collect();
+    serde_json::Value::Object(map)
+}
+
+/// Starea de eroare per subsistem: `ids_subsystem_error` (0/1) si durata
+/// seriei curente de erori — baza pentru reguli de tip "SIEM esueaza de 5m".
+fn render_last_errors(out: &mut String, errors: &LastErrors) {
+    let snapshot = errors.snapshot();
+    let now = Local::now();
+    let name = "ids_subsystem_error";
+    let _ = writeln!(out, "# HELP {} Subsistemul are o eroare nerezolvata (1) sau nu (0)", name);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (sub, err) in &snapshot {
+        let _ = writeln!(out, "{}{{subsystem=\"{}\"}} {}", name, sub, u8::from(err.is_some()));
+    }
+    let name = "ids_subsystem_error_seconds";
+    let _ = writeln!(out, "# HELP {} Secunde de la prima eroare din seria curenta (0 = fara eroare)", name);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (sub, err) in &snapshot {
+        let secs = err
+            .as_ref()
+            .map_or(0, |e| (now - e.since).num_seconds().max(0));
+        let _ = writeln!(out, "{}{{subsystem=\"{}\"}} {}", name, sub, secs);
+    }
+}
+
+/// GET / — pagina HTML minimala (doar cu `metrics.web_ui = true`).
+async fn get_web_ui() -> Html<&'static str> {
+    Html(WEB_UI_HTML)
+}
+
+const WEB_UI_HTML: &str = include_str!("../static/metrics_ui.html");
+
+/// Scrie o metrica counter (monotona) cu HELP/TYPE.
+fn write_counter(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+/// Scrie o metrica gauge cu HELP/TYPE. `labels` = "" sau `{k="v",...}`.
+fn write_metric(out: &mut String, name: &str, help: &str, labels: &str, value: f64) {
+    // `write!` pe String nu poate esua — ignoram Result-ul.
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    let _ = writeln!(out, "{}{} {}", name, labels, value);
+}
+
+/// Histograma alertelor pe ora din zi: o familie counter, un label `hour`
+/// per bucket (0-23), toate cele 24 de serii prezente (si cele cu 0) —
+/// altfel `rate()` in Prometheus ar vedea serii care apar si dispar.
+fn render_alerts_by_hour(out: &mut String, buckets: &[u64; 24]) {
+    let name = "ids_alerts_by_hour";
+    let _ = writeln!(out, "# HELP {} Alerte generate de la pornire, pe ora locala din zi", name);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    for (hour, count) in buckets.iter().enumerate() {
+        let _ = writeln!(out, "{}{{hour=\"{}\"}} {}", name, hour, count);
+    }
+}
+
+/// Genereaza metricile de ruleset: reguli active + pragurile fiecarei reguli.
+///
+/// `ids_rule_enabled{rule="..."}` este o singura familie cu un label per regula
+/// (HELP/TYPE o singura data) — conventia Prometheus pentru serii inrudite.
+/// Fast, Slow si Accept Scan nu au flag `enabled` — sunt active, cu exceptia
+/// Accept Scan si Beaconing in modul `network.traffic_direction = "inbound_only"`.
+fn render_ruleset(out: &mut String, cfg: &DetectionConfig, inbound_only: bool) {
+    let rules: [(&str, bool); 14] = [
+        ("fast", true),
+        ("slow", true),
+        ("accept", !inbound_only),
+        ("lateral_movement", cfg.lateral_movement.enabled),
+        ("distributed", cfg.distributed_scan.enabled),
+        ("coordinated_subnet", cfg.coordinated_subnet.enabled),
+        ("beaconing", cfg.beaconing.enabled && !inbound_only),
+        ("dynamic_threshold", cfg.dynamic_threshold.enabled),
+        ("saturation", cfg.saturation_alert.enabled),
+        ("no_traffic", cfg.no_traffic_alert_secs > 0),
+        ("flood", cfg.flood.enabled),
+        ("slow_sweep", cfg.slow_sweep.enabled),
+        ("malformed_input", cfg.malformed_input.enabled),
+        ("scan_end", cfg.scan_end_silence_secs > 0),
+    ];
+
+    let _ = writeln!(
+        out,
+        "# HELP ids_rule_enabled Regula de detectie activa (1) sau inactiva (0)"
+    );
+    let _ = writeln!(out, "# TYPE ids_rule_enabled gauge");
+    for (rule, enabled) in rules {
+        let _ = writeln!(
+            out,
+            "ids_rule_enabled{{rule=\"{}\"}} {}",
+            rule, enabled as u8
+        );
+    }
+
+    let thresholds: [(&str, &str, f64); 26] = [
+        (
+            "ids_alert_cooldown_seconds",
+            "Cooldown intre alerte repetate",
+            cfg.alert_cooldown_secs as f64,
+        ),
+        (
+            "ids_max_tracked_ips",
+            "Limita IP-uri urmarite (LRU)",
+            cfg.max_tracked_ips as f64,
+        ),
+        (
+            "ids_max_hits_per_ip",
+            "Limita port-hit-uri per IP",
+            cfg.max_hits_per_ip as f64,
+        ),
+        (
+            "ids_whitelist_entries",
+            "Intrari in whitelist",
+            cfg.whitelist.len() as f64,
+        ),
+        (
+            "ids_fast_scan_threshold",
+            "Prag porturi unice Fast Scan",
+            cfg.fast_scan.port_threshold as f64,
+        ),
+        (
+            "ids_fast_scan_window_seconds",
+            "Fereastra Fast Scan",
+            cfg.fast_scan.time_window_secs as f64,
+        ),
+        (
+            "ids_slow_scan_threshold",
+            "Prag porturi unice Slow Scan",
+            cfg.slow_scan.port_threshold as f64,
+        ),
+        (
+            "ids_slow_scan_window_seconds",
+            "Fereastra Slow Scan",
+            (cfg.slow_scan.time_window_mins * 60) as f64,
+        ),
+        (
+            "ids_accept_scan_threshold",
+            "Prag porturi acceptate Accept Scan",
+            cfg.accept_scan.port_threshold as f64,
+        ),
+        (
+            "ids_accept_scan_window_seconds",
+            "Fereastra Accept Scan",
+            cfg.accept_scan.time_window_secs as f64,
+        ),
+        (
+            "ids_lateral_movement_threshold",
+            "Prag destinatii unice Lateral Movement",
+            cfg.lateral_movement.unique_dest_threshold as f64,
+        ),
+        (
+            "ids_lateral_movement_window_seconds",
+            "Fereastra Lateral Movement",
+            cfg.lateral_movement.time_window_secs as f64,
+        ),
+        (
+            "ids_distributed_scan_threshold",
+            "Prag surse unice Distributed Scan",
+            cfg.distributed_scan.unique_sources_threshold as f64,
+        ),
+        (
+            "ids_distributed_scan_window_seconds",
+            "Fereastra Distributed Scan",
+            cfg.distributed_scan.time_window_secs as f64,
+        ),
+        (
+            "ids_coordinated_subnet_port_threshold",
+            "Prag porturi unice per bloc sursa Coordinated Subnet Scan",
+            cfg.coordinated_subnet.port_threshold as f64,
+        ),
+        (
+            "ids_coordinated_subnet_min_sources",
+            "Surse minime per bloc Coordinated Subnet Scan",
+            cfg.coordinated_subnet.min_sources as f64,
+        ),
+        (
+            "ids_flood_packets_per_sec_threshold",
+            "Prag rata pachete per sursa Flood",
+            cfg.flood.packets_per_sec_threshold as f64,
+        ),
+        (
+            "ids_slow_sweep_threshold",
+            "Prag destinatii per port Slow Sweep",
+            cfg.slow_sweep.unique_dest_threshold as f64,
+        ),
+        (
+            "ids_slow_sweep_window_seconds",
+            "Fereastra Slow Sweep",
+            cfg.slow_sweep.time_window_secs as f64,
+        ),
+        (
+            "ids_malformed_input_threshold",
+            "Prag datagrame malformate per expeditor",
+            cfg.malformed_input.threshold as f64,
+        ),
+        (
+            "ids_malformed_input_window_seconds",
+            "Fereastra Malformed Input",
+            cfg.malformed_input.time_window_secs as f64,
+        ),
+        (
+            "ids_beaconing_min_events",
+            "Conexiuni minime Beaconing",
+            cfg.beaconing.min_events as f64,
+        ),
+        (
+            "ids_beaconing_cv_threshold",
+            "Prag CV Beaconing",
+            cfg.beaconing.cv_threshold,
+        ),
+        (
+            "ids_beaconing_window_seconds",
+            "Fereastra Beaconing",
+            cfg.beaconing.time_window_secs as f64,
+        ),
+        (
+            "ids_saturation_evictions_threshold",
+            "Prag evictii alerta saturatie",
+            cfg.saturation_alert.evictions_threshold as f64,
+        ),
+        (
+            "ids_saturation_window_seconds",
+            "Fereastra alerta saturatie",
+            cfg.saturation_alert.time_window_secs as f64,
+        ),
+    ];
+
+    for (name, help, value) in thresholds {
+        write_metric(out, name, help, "", value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alerts_last_hour_window() {
+        let counters = RuntimeCounters::new();
+        let t0: u64 = 29_000_000; // un minut Unix oarecare
+        counters.record_alert_minute(t0);
+        counters.record_alert_minute(t0);
+        counters.record_alert_minute(t0 + 30);
+        assert_eq!(counters.alerts_last_hour(t0 + 30), 3);
+        // Minutul t0 iese din fereastra dupa 60 de minute.
+        assert_eq!(counters.alerts_last_hour(t0 + 60), 1);
+        // Acelasi bucket, o ora mai tarziu: contorul vechi este rescris.
+        counters.record_alert_minute(t0 + 60);
+        assert_eq!(counters.alerts_last_hour(t0 + 60), 2);
+        assert_eq!(counters.alerts_last_hour(t0 + 200), 0);
+    }
+
+    #[test]
+    fn test_quiet_stats_suppresses_and_heartbeats() {
+        use std::time::Duration;
+        let cfg: crate::config::OutputConfig = toml::from_str(
+            r#"
+            suppress_empty_stats = true
+            quiet_max_events = 5
+            quiet_heartbeat_secs = 600
+            "#,
+        )
+        .unwrap();
+        let mut quiet = QuietStats::new(&cfg);
+        let t0 = Instant::now();
+        let at = |secs| t0 + Duration::from_secs(secs);
+
+        assert_eq!(quiet.check(100, 0, at(0)), StatsReport::Full);
+        // Sub prag (3 evenimente, nicio alerta): linistit, heartbeat nedatorat.
+        assert_eq!(quiet.check(103, 0, at(60)), StatsReport::Skip);
+        assert_eq!(quiet.check(103, 0, at(600)), StatsReport::Skip);
+        assert_eq!(
+            quiet.check(103, 0, at(660)),
+            StatsReport::Heartbeat { quiet_secs: 600 }
+        );
+        assert_eq!(quiet.check(103, 0, at(720)), StatsReport::Skip);
+        assert_eq!(
+            quiet.check(103, 0, at(1260)),
+            StatsReport::Heartbeat { quiet_secs: 1200 }
+        );
+        // O singura alerta intrerupe linistea.
+        assert_eq!(quiet.check(104, 1, at(1320)), StatsReport::Full);
+        assert_eq!(quiet.check(104, 1, at(1380)), StatsReport::Skip);
+
+        // Dezactivat: fiecare ciclu are linia STAT.
+        let mut off = QuietStats::new(&crate::config::OutputConfig::default());
+        assert_eq!(off.check(0, 0, at(0)), StatsReport::Full);
+        assert_eq!(off.check(0, 0, at(60)), StatsReport::Full);
+    }
+
+    #[test]
+    fn test_render_ruleset_reflects_config() {
+        // Sectiunile optionale lipsesc → valorile implicite serde (reguli dezactivate).
+        let cfg: DetectionConfig = toml::from_str(
+            r#"
+            alert_cooldown_secs = 300
+            [fast_scan]
+            port_threshold = 42
+            time_window_secs = 10
+            [slow_scan]
+            port_threshold = 30
+            time_window_mins = 5
+            [lateral_movement]
+            enabled = true
+            "#,
+        )
+        .unwrap();
+
+        let mut out = String::new();
+        render_ruleset(&mut out, &cfg, false);
+
+        assert!(out.contains("ids_fast_scan_threshold 42\n"));
+        assert!(out.contains("ids_rule_enabled{rule=\"slow\"} 1\n"));
+        assert!(out.contains("ids_rule_enabled{rule=\"lateral_movement\"} 1\n"));
+        assert!(out.contains("ids_rule_enabled{rule=\"beaconing\"} 0\n"));
+        // O singura declaratie TYPE per familie de metrici.
+        assert_eq!(out.matches("# TYPE ids_rule_enabled gauge").count(), 1);
+
+        // inbound_only: regulile dependente de ambele sensuri apar inactive.
+        let mut out = String::new();
+        render_ruleset(&mut out, &cfg, true);
+        assert!(out.contains("ids_rule_enabled{rule=\"accept\"} 0\n"));
+    }
+
+    #[test]
+    fn test_build_stats_snapshot() {
+        let cfg: DetectionConfig = toml::from_str(
+            r#"
+            alert_cooldown_secs = 300
+            [fast_scan]
+            port_threshold = 100
+            time_window_secs = 10
+            [slow_scan]
+            port_threshold = 100
+            time_window_mins = 5
+            "#,
+        )
+        .unwrap();
+        let detector = Detector::new(cfg);
+        let counters = RuntimeCounters::new();
+        counters.events_processed.store(7, Ordering::Relaxed);
+
+        let attacker: std::net::IpAddr = "10.0.0.9".parse().unwrap();
+        for port in 1..=3 {
+            detector.process_event(&crate::parser::LogEvent {
+                source_ip: attacker,
+                dest_ip: Some("10.0.0.1".parse().unwrap()),
+                dest_port: port,
+                protocol: "tcp".to_string(),
+                action: "drop".to_string(),
+                packet_bytes: None,
+                interface: None,
+                source_port: None,
+                raw_log: String::new(),
+            });
+        }
+
+        let stats = build_stats(&detector, &counters, &[]);
+        assert_eq!(stats["events_processed"], 7);
+        assert_eq!(stats["tracked_ips"], 1);
+        assert_eq!(stats["top_talkers"][0]["ip"], "10.0.0.9");
+        assert_eq!(stats["top_talkers"][0]["dropped"], 3);
+        assert_eq!(stats["recent_alerts"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_packet_accounting_report() {
+        let cfg: DetectionConfig = toml::from_str(
+            r#"
+            alert_cooldown_secs = 300
+            whitelist = ["10.0.0.250"]
+            [fast_scan]
+            port_threshold = 100
+            time_window_secs = 10
+            [slow_scan]
+            port_threshold = 100
+            time_window_mins = 5
+            "#,
+        )
+        .unwrap();
+        let detector = Detector::new(cfg);
+        let counters = RuntimeCounters::new();
+        let packets = &counters.packets;
+        packets.datagrams.store(10, Ordering::Relaxed);
+        packets.rate_limited.store(2, Ordering::Relaxed);
+        packets.lines.store(9, Ordering::Relaxed);
+        packets.parse_errors.store(3, Ordering::Relaxed);
+        packets.duplicates.store(1, Ordering::Relaxed);
+        for (ip, port) in [("10.0.0.250", 22), ("10.0.0.9", 22), ("10.0.0.9", 23)] {
+            counters.events_processed.fetch_add(1, Ordering::Relaxed);
+            detector.process_event(&crate::parser::LogEvent {
+                source_ip: ip.parse().unwrap(),
+                dest_ip: Some("10.0.0.1".parse().unwrap()),
+                dest_port: port,
+                protocol: "tcp".to_string(),
+                action: "drop".to_string(),
+                packet_bytes: None,
+                interface: None,
+                source_port: None,
+                raw_log: String::new(),
+            });
+        }
+
+        let report = packets.report(&counters, &detector);
+        assert_eq!(report.kernel_dropped, None, "fara socket: necunoscut");
+        assert_eq!(report.parsed, 6);
+        assert_eq!(report.events_processed, 3);
+        assert_eq!(report.whitelisted, 1);
+        assert_eq!(report.analyzed, 2);
+
+        let mut out = String::new();
+        render_packet_accounting(&mut out, &report);
+        assert!(out.contains("ids_udp_datagrams_received_total 10\n"));
+        assert!(out.contains("ids_packets_dropped_total{reason=\"rate_limit\"} 2\n"));
+        assert!(out.contains("ids_packets_dropped_total{reason=\"whitelist\"} 1\n"));
+        assert!(!out.contains("kernel_overflow"));
+        let stats = build_stats(&detector, &counters, &[]);
+        assert_eq!(stats["packet_accounting"]["parse_errors"], 3);
+
+        // Coloana `drops` a socket-ului, gasit dupa inode.
+        let table = "\
+   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  123: 00000000:1392 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 41234 2 0000000000000000 0
+  456: 00000000:0202 00000000:0000 07 00000000:00034000 00:00000000 00000000     0        0 98765 2 0000000000000000 1742
+";
+        assert_eq!(proc_udp_drops(table, 98765), Some(1742));
+        assert_eq!(proc_udp_drops(table, 41234), Some(0));
+        assert_eq!(proc_udp_drops(table, 1), None);
+    }
+
+    #[test]
+    fn test_last_errors_record_and_clear() {
+        let errors = LastErrors::new();
+        errors.record(Subsystem::Socket, "connection refused");
+        let first = errors.snapshot()[Subsystem::Socket as usize].1.clone().unwrap();
+        errors.observe::<()>(Subsystem::Socket, &Err(anyhow::anyhow!("timeout")));
+
+        // A doua eroare: mesaj nou, dar `since` ramane cel al primei erori.
+        let json = last_errors_json(&errors);
+        assert_eq!(json["socket"]["message"], "timeout");
+        assert_eq!(json["socket"]["consecutive"], 2);
+        assert_eq!(json["socket"]["since"], serde_json::json!(first.since));
+        assert!(json["config"].is_null(), "toate subsistemele sunt prezente");
+
+        let mut out = String::new();
+        render_last_errors(&mut out, &errors);
+        assert!(out.contains("ids_subsystem_error{subsystem=\"socket\"} 1\n"));
+        assert!(out.contains("ids_subsystem_error{subsystem=\"deadletter\"} 0\n"));
+
+        // Un succes goleste slotul.
+        errors.observe(Subsystem::Socket, &Ok(()));
+        assert!(last_errors_json(&errors)["socket"].is_null());
+    }
+
+    #[test]
+    fn test_last_errors_per_sink_instance() {
+        let errors = LastErrors::new();
+        errors.set_sinks(["siem", "siem_backup"]);
+
+        // Doua instante de acelasi tip: succesul uneia nu sterge eroarea celeilalte.
+        errors.observe_sink::<()>("siem_backup", &Err(anyhow::anyhow!("connection refused")));
+        errors.observe_sink::<()>("siem_backup", &Err(anyhow::anyhow!("connection refused")));
+        errors.observe_sink("siem", &Ok(()));
+        let json = last_errors_json(&errors);
+        assert!(json["siem"].is_null());
+        assert_eq!(json["siem_backup"]["consecutive"], 2);
+
+        let mut out = String::new();
+        render_last_errors(&mut out, &errors);
+        assert!(out.contains("ids_subsystem_error{subsystem=\"siem\"} 0\n"));
+        assert!(out.contains("ids_subsystem_error{subsystem=\"siem_backup\"} 1\n"));
+
+        // SIGHUP: instanta pastrata isi pastreaza eroarea, cea scoasa dispare.
+        errors.set_sinks(["siem_backup", "gelf"]);
+        let json = last_errors_json(&errors);
+        assert_eq!(json["siem_backup"]["consecutive"], 2);
+        assert!(json["gelf"].is_null());
+        assert!(json.get("siem").is_none());
+    }
+
+    #[test]
+    fn test_alerts_by_hour_histogram() {
+        use chrono::TimeZone;
+        let counters = RuntimeCounters::new();
+        let at = |hour: u32| Alert {
+            timestamp: chrono::Local.with_ymd_and_hms(2026, 3, 2, hour, 15, 0).unwrap(),
+            ..Alert::new(crate::detector::ScanType::Fast, "10.0.0.9".parse().unwrap(), None)
+        };
+        counters.record_alerts(&[at(3), at(3), at(22)]);
+
+        let buckets = counters.alerts_by_hour();
+        assert_eq!(buckets[3], 2);
+        assert_eq!(buckets[22], 1);
+        assert_eq!(counters.alerts_generated.load(Ordering::Relaxed), 3);
+
+        let mut out = String::new();
+        render_alerts_by_hour(&mut out, &buckets);
+        assert!(out.contains("ids_alerts_by_hour{hour=\"3\"} 2\n"));
+        assert!(out.contains("ids_alerts_by_hour{hour=\"0\"} 0\n"));
+        assert_eq!(out.lines().filter(|l| l.starts_with("ids_alerts_by_hour{")).count(), 24);
+    }
+
+    #[test]
+    fn test_latency_histogram_percentiles() {
+        use std::time::Duration;
+        let latency = LatencyHistogram::new();
+        // Dezactivata: nimic inregistrat.
+        latency.record(Duration::from_micros(40));
+        assert_eq!(latency.roll(), None);
+
+        latency.set_enabled(true);
+        for _ in 0..90 {
+            latency.record(Duration::from_micros(40)); // bucket 50µs
+        }
+        for _ in 0..9 {
+            latency.record(Duration::from_micros(1_800)); // bucket 2.5ms
+        }
+        latency.record(Duration::from_secs(3)); // peste 1s → +Inf
+
+        let p = latency.roll().unwrap();
+        assert_eq!((p.p50_us, p.p95_us, p.p99_us, p.packets), (50, 2_500, 2_500, 100));
+        // Fereastra a fost resetata: fara pachete noi, nicio percentila.
+        assert_eq!(latency.roll(), None);
+        latency.record(Duration::from_secs(3));
+        assert_eq!(latency.roll().unwrap().p99_us, u64::MAX);
+
+        let mut out = String::new();
+        render_latency(&mut out, &latency);
+        assert!(out.contains("ids_detection_latency_seconds_bucket{le=\"0.00005\"} 90\n"));
+        assert!(out.contains("ids_detection_latency_seconds_bucket{le=\"+Inf\"} 101\n"));
+        assert!(out.contains("ids_detection_latency_seconds_count 101\n"));
+        assert!(out.contains("ids_detection_latency_rolling_seconds{quantile=\"0.99\"} +Inf\n"));
+    }
+}
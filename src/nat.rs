@@ -0,0 +1,146 @@
+// =============================================================================
+// nat.rs - Sursa Reala din Spatele unui NAT (network.nat_source)
+// =============================================================================
+//
+// Intr-o retea in care tot traficul extern trece printr-un NAT / proxy, log-ul
+// firewall-ului intern vede o singura sursa: IP-ul NAT-ului. Fiecare client
+// extern se aduna la acelasi contor, iar IP-ul NAT depaseste permanent
+// pragurile — alerte inutile si atacatori reali neidentificabili.
+//
+// Unele exportoare includ clientul original intr-un camp separat. Rezolvarea
+// citeste acel camp din linia bruta si inlocuieste `source_ip` INAINTE de
+// deduplicare si detectie:
+//
+//   CEF:   ... src=10.0.0.254 dst=10.0.5.10 dpt=22 cs6=203.0.113.7
+//   Gaia:  ... src: 10.0.0.254; dst: 10.0.5.10; original_src: 203.0.113.7;
+//   LEA:   ... src:"10.0.0.254" original_src:"203.0.113.7"
+//
+// Cautarea este generica (nu depinde de parser): `<camp>=`, `<camp>:` sau
+// `<camp>: `, valoarea optional intre ghilimele, terminata de spatiu, `;`,
+// `|` sau ghilimele. O valoare care nu este IP lasa evenimentul neschimbat.
+//
+// =============================================================================
+
+use crate::config::NatSourceConfig;
+use crate::detector::WhitelistEntry;
+use crate::parser::LogEvent;
+use std::net::IpAddr;
+
+pub struct NatResolver {
+    field: String,
+    /// Dispozitivele NAT (`nat_devices`); gol = orice sursa.
+    devices: Vec<WhitelistEntry>,
+}
+
+impl NatResolver {
+    /// `None` cand optiunea este dezactivata (`field` gol). Intrarile invalide
+    /// au fost respinse deja de `AppConfig::validate()`.
+    pub fn new(cfg: &NatSourceConfig) -> Option<Self> {
+        if cfg.field.is_empty() {
+            return None;
+        }
+        Some(Self {
+            field: cfg.field.clone(),
+            devices: cfg
+                .nat_devices
+                .iter()
+                .filter_map(|s| WhitelistEntry::parse(s))
+                .collect(),
+        })
+    }
+
+    /// Inlocuieste sursa evenimentului cu clientul real, daca linia il contine.
+    /// Intoarce IP-ul dispozitivului NAT inlocuit.
+    pub fn resolve(&self, event: &mut LogEvent) -> Option<IpAddr> {
+        if !self.devices.is_empty() && !self.devices.iter().any(|d| d.matches(&event.source_ip)) {
+            return None;
+        }
+        let original: IpAddr = field_value(&event.raw_log, &self.field)?.parse().ok()?;
+        if original == event.source_ip {
+            return None;
+        }
+        let nat_device = event.source_ip;
+        event.source_ip = original;
+        Some(nat_device)
+    }
+}
+
+/// Valoarea campului `field` din linia bruta (CEF `k=v`, Gaia `k: v;`, LEA `k:"v"`).
+///
+/// Campul trebuie sa inceapa un cuvant (inceput de linie, spatiu, `;`, `|`):
+/// `src` nu se potriveste in `nat_src=`.
+fn field_value<'a>(line: &'a str, field: &str) -> Option<&'a str> {
+    let mut search = 0;
+    while let Some(pos) = line[search..].find(field) {
+        let start = search + pos;
+        let end = start + field.len();
+        search = end;
+        let boundary = line[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|c| c == ' ' || c == ';' || c == '|');
+        if !boundary {
+            continue;
+        }
+        let rest = &line[end..];
+        let Some(rest) = rest.strip_prefix('=').or_else(|| rest.strip_prefix(':')) else {
+            continue;
+        };
+        let rest = rest.trim_start_matches(' ').trim_start_matches('"');
+        let len = rest
+            .find([' ', ';', '|', '"'])
+            .unwrap_or(rest.len());
+        if len > 0 {
+            return Some(&rest[..len]);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(raw: &str, src: &str) -> LogEvent {
+        LogEvent {
+            source_ip: src.parse().unwrap(),
+            dest_ip: None,
+            dest_port: 22,
+            protocol: "tcp".to_string(),
+            action: "drop".to_string(),
+            packet_bytes: None,
+            interface: None,
+            source_port: None,
+            raw_log: raw.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_nat_resolver_rewrites_source_from_field() {
+        assert_eq!(field_value("src=1.1.1.1 cs6=203.0.113.7 act=drop", "cs6"), Some("203.0.113.7"));
+        assert_eq!(field_value("src: 10.0.0.1; original_src: 203.0.113.7;", "original_src"), Some("203.0.113.7"));
+        assert_eq!(field_value(r#"src:"10.0.0.1" original_src:"203.0.113.7""#, "original_src"), Some("203.0.113.7"));
+        // Granita de cuvant: `src` nu se potriveste in `original_src`.
+        assert_eq!(field_value("original_src: 203.0.113.7;", "src"), None);
+
+        let cfg = NatSourceConfig {
+            field: "cs6".to_string(),
+            nat_devices: vec!["10.0.0.254".to_string()],
+        };
+        let resolver = NatResolver::new(&cfg).unwrap();
+        let mut ev = event("CEF:0|X|Y|1|100|Drop|5|src=10.0.0.254 dpt=22 cs6=203.0.113.7", "10.0.0.254");
+        assert_eq!(resolver.resolve(&mut ev), Some("10.0.0.254".parse().unwrap()));
+        assert_eq!(ev.source_ip, "203.0.113.7".parse::<IpAddr>().unwrap());
+
+        // Sursa nu este un dispozitiv NAT: campul este ignorat.
+        let mut ev = event("src=198.51.100.1 cs6=203.0.113.7", "198.51.100.1");
+        assert_eq!(resolver.resolve(&mut ev), None);
+        assert_eq!(ev.source_ip, "198.51.100.1".parse::<IpAddr>().unwrap());
+
+        // Valoare care nu este IP: eveniment neschimbat.
+        let mut ev = event("src=10.0.0.254 cs6=necunoscut", "10.0.0.254");
+        assert_eq!(resolver.resolve(&mut ev), None);
+
+        assert!(NatResolver::new(&NatSourceConfig::default()).is_none());
+    }
+}
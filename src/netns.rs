@@ -0,0 +1,108 @@
+// =============================================================================
+// netns.rs - Socket-ul de Receptie in alt Namespace de Retea (network.netns)
+// =============================================================================
+//
+// Pe un host de containere multi-tenant, traficul fiecarui tenant traieste in
+// namespace-ul de retea propriu. IDS-RS ruleaza in namespace-ul host-ului (sau
+// al unui container de monitorizare), dar trebuie sa primeasca log-urile in
+// namespace-ul tenant-ului:
+//
+//   netns = "tenant-a"                 → /run/netns/tenant-a (`ip netns add`)
+//   netns = "/proc/4242/ns/net"        → namespace-ul procesului 4242
+//
+// Doar socket-ul de receptie este creat in namespace-ul tinta: firul curent
+// intra cu setns(2), face bind, apoi revine in namespace-ul initial. Socket-ul
+// ramane legat de namespace-ul in care a fost creat, indiferent de firul care
+// il citeste. Restul conexiunilor (SIEM, email, dashboard, metrici) raman in
+// namespace-ul de lansare.
+//
+// NOTA RUST: `setns` nu are un wrapper in std — apelul trece prin `libc`, intr-un
+// bloc `unsafe`. Singura conditie este un descriptor valid, garantat de `File`
+// pe durata apelului.
+//
+// =============================================================================
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+/// Directorul namespace-urilor cu nume (conventia `ip netns`).
+const NETNS_RUN_DIR: &str = "/run/netns";
+
+/// Calea fisierului namespace-ului: un nume → `/run/netns/<nume>`, o cale
+/// (contine `/`) → folosita ca atare.
+pub fn namespace_path(netns: &str) -> PathBuf {
+    if netns.contains('/') {
+        PathBuf::from(netns)
+    } else {
+        PathBuf::from(NETNS_RUN_DIR).join(netns)
+    }
+}
+
+/// Ruleaza `f` (bind-ul socket-ului) in namespace-ul `netns`, apoi revine in
+/// namespace-ul initial al firului.
+#[cfg(target_os = "linux")]
+pub fn run_in<T>(netns: &str, f: impl FnOnce() -> std::io::Result<T>) -> Result<T> {
+    let path = namespace_path(netns);
+    let target = std::fs::File::open(&path).with_context(|| {
+        format!("network.netns: namespace-ul {:?} nu exista ({})", netns, path.display())
+    })?;
+    let original = std::fs::File::open("/proc/thread-self/ns/net")
+        .context("network.netns: nu pot deschide namespace-ul curent (/proc/thread-self/ns/net)")?;
+
+    enter(&target).map_err(|e| match e.raw_os_error() {
+        Some(libc::EPERM) => anyhow::anyhow!(
+            "network.netns: intrarea in {:?} refuzata — procesul nu are CAP_SYS_ADMIN",
+            netns
+        ),
+        Some(libc::EINVAL) => anyhow::anyhow!(
+            "network.netns: {} nu este un namespace de retea",
+            path.display()
+        ),
+        _ => anyhow::anyhow!("network.netns: setns({}) a esuat: {}", path.display(), e),
+    })?;
+    let result = f();
+    // Revenirea nu poate fi sarita: firul ar ramane in namespace-ul tenant-ului.
+    enter(&original).context("network.netns: revenirea in namespace-ul initial a esuat")?;
+    result.with_context(|| format!("Bind in namespace-ul {:?} esuat", netns))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn run_in<T>(_netns: &str, _f: impl FnOnce() -> std::io::Result<T>) -> Result<T> {
+    anyhow::bail!("network.netns este suportat doar pe Linux")
+}
+
+#[cfg(target_os = "linux")]
+fn enter(ns: &std::fs::File) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+    // SAFETY: descriptorul este valid cat traieste `ns`; CLONE_NEWNET respinge
+    // orice alt tip de namespace (EINVAL).
+    let rc = unsafe { libc::setns(ns.as_raw_fd(), libc::CLONE_NEWNET) };
+    if rc == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespace_path_and_missing_namespace() {
+        assert_eq!(namespace_path("tenant-a"), PathBuf::from("/run/netns/tenant-a"));
+        assert_eq!(namespace_path("/proc/4242/ns/net"), PathBuf::from("/proc/4242/ns/net"));
+
+        #[cfg(target_os = "linux")]
+        {
+            let err = run_in("ids-rs-nu-exista", || Ok(())).unwrap_err();
+            assert!(format!("{:#}", err).contains("nu exista"));
+
+            // Namespace-ul propriu: bind reusit si revenire (EPERM fara capabilitate).
+            match run_in("/proc/self/ns/net", || std::net::UdpSocket::bind("127.0.0.1:0")) {
+                Ok(socket) => assert!(socket.local_addr().is_ok()),
+                Err(e) => assert!(format!("{:#}", e).contains("CAP_SYS_ADMIN")),
+            }
+        }
+    }
+}
@@ -88,6 +88,9 @@ impl LogParser for CefParser {
         let mut dest_port: Option<u16> = None;
         let mut protocol = String::from("tcp");
         let mut action = String::new();
+        let mut packet_bytes: Option<u32> = None;
+        let mut interface: Option<String> = None;
+        let mut source_port: Option<u16> = None;
 
         // Parcurgem perechile cheie=valoare din extensii.
         //
@@ -105,8 +108,16 @@ impl LogParser for CefParser {
                     "src" => source_ip = kv[1].parse().ok(),
                     "dst" => dest_ip = kv[1].parse().ok(),
                     "dpt" => dest_port = kv[1].parse().ok(),
+                    "spt" => source_port = kv[1].parse().ok(),
                     "proto" => protocol = kv[1].to_lowercase(),
                     "act" => action = kv[1].to_lowercase(),
+                    // `in` = bytesIn (cheie standard CEF) — octetii primiti,
+                    // cu antete (nu lungimea payload-ului).
+                    "in" => packet_bytes = kv[1].parse().ok(),
+                    // Interfata pe care firewall-ul a primit pachetul.
+                    "deviceInboundInterface" if !kv[1].is_empty() => {
+                        interface = Some(kv[1].to_string())
+                    }
                     // Ignoram cheile necunoscute (extensibil pe viitor).
                     _ => {}
                 }
@@ -131,6 +142,9 @@ impl LogParser for CefParser {
             dest_port,
             protocol,
             action,
+            packet_bytes,
+            interface,
+            source_port,
             raw_log: line.to_string(),
         })
     }
@@ -158,6 +172,23 @@ mod tests {
         assert_eq!(event.dest_port, 443);
         assert_eq!(event.protocol, "tcp");
         assert_eq!(event.action, "drop");
+        assert_eq!(event.source_port, None);
+
+        let log = "CEF:0|CheckPoint|VPN-1|R81|100|Drop|5|src=192.168.11.7 spt=49152 dst=10.0.0.1 dpt=443 proto=TCP act=drop";
+        assert_eq!(parser.parse(log).unwrap().source_port, Some(49152));
+    }
+
+    #[test]
+    fn test_parse_cef_packet_bytes() {
+        let parser = CefParser::new();
+        let log = "CEF:0|CheckPoint|VPN-1|R81|100|Drop|5|src=192.168.11.7 dst=10.0.0.1 dpt=161 proto=UDP act=drop in=28";
+        let event = parser.parse(log).unwrap();
+        assert_eq!(event.packet_bytes, Some(28));
+        assert!(event.is_empty_udp_datagram(), "28 octeti = doar antetele IPv4 + UDP");
+
+        // Fara `in=` numarul de octeti este necunoscut, nu zero.
+        let log = "CEF:0|CheckPoint|VPN-1|R81|100|Drop|5|src=192.168.11.7 dst=10.0.0.1 dpt=161 proto=UDP act=drop";
+        assert_eq!(parser.parse(log).unwrap().packet_bytes, None);
     }
 
     #[test]
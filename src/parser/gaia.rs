@@ -19,6 +19,7 @@
 //   - IP sursa: din "src: <IP>" (cel care scaneaza)
 //   - Port destinatie: din "service: <port>" (portul scanat)
 //   - Protocol: din "proto: <proto>"
+//   - Interfata: din "<origin> >eth8" (`>` = intrare, `<` = iesire)
 //
 // CONCEPTE RUST EXPLICATE:
 //
@@ -162,6 +163,24 @@ impl LogParser for GaiaParser {
         let service_str = Self::extract_field(extensions, "service")?;
         let dest_port: u16 = service_str.parse().ok()?;
 
+        // Optional: "bytes: <N>" — octetii raportati de firewall, cu antete.
+        let packet_bytes: Option<u32> =
+            Self::extract_field(extensions, "bytes").and_then(|s| s.parse().ok());
+
+        // Optional: "s_port: <N>" — portul sursa (efemer) al conexiunii.
+        let source_port: Option<u16> =
+            Self::extract_field(extensions, "s_port").and_then(|s| s.parse().ok());
+
+        // Interfata firewall-ului: token-ul ">eth8" / "<eth8" de dupa IP-ul
+        // de origine (header-ul clasic fw log), altfel campul "ifname: <nume>".
+        let interface = extensions
+            .split_whitespace()
+            .take(2)
+            .find_map(|token| token.strip_prefix('>').or_else(|| token.strip_prefix('<')))
+            .or_else(|| Self::extract_field(extensions, "ifname"))
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+
         // Construim LogEvent-ul. `line.to_string()` creaza un String owned
         // din &str (copiaza datele pe heap). Necesar deoarece LogEvent
         // trebuie sa fie independent de buffer-ul original.
@@ -171,6 +190,9 @@ impl LogParser for GaiaParser {
             dest_port,
             protocol,
             action,
+            packet_bytes,
+            interface,
+            source_port,
             raw_log: line.to_string(),
         })
     }
@@ -209,6 +231,26 @@ mod tests {
         assert_eq!(event.dest_port, 80);
         assert_eq!(event.protocol, "tcp");
         assert_eq!(event.action, "drop");
+        assert_eq!(event.interface.as_deref(), Some("eth8"));
+        assert_eq!(event.source_port, Some(2854));
+    }
+
+    #[test]
+    fn test_interface_and_vlan_subinterface() {
+        let parser = GaiaParser::new().unwrap();
+        let log = "Sep 3 15:12:20 192.168.99.1 Checkpoint: 3Sep2007 15:12:08 drop \
+            192.168.11.7 <bond0.120 rule: 113; src: 192.168.11.34; dst: 10.0.0.1; \
+            proto: tcp; service: 22; s_port: 2854;";
+        let event = parser.parse(log).unwrap();
+        assert_eq!(event.interface.as_deref(), Some("bond0.120"));
+        assert_eq!(crate::parser::vlan_from_interface("bond0.120"), Some(120));
+        assert_eq!(crate::parser::vlan_from_interface("eth8"), None);
+        assert_eq!(crate::parser::vlan_from_interface("eth1.4095"), None);
+
+        // Fara token de interfata: None.
+        let log = "Sep 3 15:12:20 192.168.99.1 Checkpoint: 3Sep2007 15:12:08 drop \
+            src: 192.168.11.34; dst: 10.0.0.1; proto: tcp; service: 22;";
+        assert_eq!(parser.parse(log).unwrap().interface, None);
     }
 
     #[test]
@@ -233,12 +233,28 @@ impl GaiaCefParser {
             .map(Self::map_protocol)
             .unwrap_or_else(|| "tcp".to_string());
 
+        // Octetii raportati de firewall (optional), cu antete — pentru empty_udp_probes.
+        let packet_bytes: Option<u32> =
+            Self::extract_lea_field(blob, "bytes").and_then(|s| s.parse().ok());
+
+        // Portul sursa (optional): "s_port" in exporturile LEA.
+        let source_port: Option<u16> =
+            Self::extract_lea_field(blob, "s_port").and_then(|s| s.parse().ok());
+
+        // Interfata firewall-ului (optional): "ifname" in exporturile LEA.
+        let interface = Self::extract_lea_field(blob, "ifname")
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+
         Some(LogEvent {
             source_ip,
             dest_ip,
             dest_port,
             protocol,
             action,
+            packet_bytes,
+            interface,
+            source_port,
             raw_log: raw_log.to_string(),
         })
     }
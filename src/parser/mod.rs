@@ -77,10 +77,47 @@ pub struct LogEvent {
     /// Actiunea firewall-ului (drop, reject, accept, etc.).
     pub action: String,
 
+    /// Octetii raportati de firewall (Checkpoint `bytes`, CEF `in`): totalul
+    /// pachetului / conexiunii INCLUSIV antetele IP si UDP/TCP — nu lungimea
+    /// payload-ului, pe care niciun format suportat nu o transporta.
+    /// `None` cand log-ul nu contine informatia (majoritatea log-urilor de
+    /// drop). Vezi `is_empty_udp_datagram` pentru detectia `empty_udp_probes`.
+    pub packet_bytes: Option<u32>,
+
+    /// Interfata firewall-ului pe care a fost observat pachetul, cand log-ul
+    /// o contine (Gaia `>eth8`, LEA `ifname`, CEF `deviceInboundInterface`).
+    /// `None` = log fara informatie de interfata.
+    pub interface: Option<String>,
+
+    /// Portul sursa al conexiunii, cand log-ul il contine (Gaia / LEA
+    /// `s_port`, CEF `spt`). Folosit de detectia `source_port_exhaustion`.
+    pub source_port: Option<u16>,
+
     /// Log-ul original brut - pastrat pentru audit/debugging.
     pub raw_log: String,
 }
 
+/// Antetele unei datagrame UDP fara payload: IPv4 (20) + UDP (8).
+const EMPTY_UDP_BYTES_V4: u32 = 28;
+/// Antetele unei datagrame UDP fara payload: IPv6 (40) + UDP (8).
+const EMPTY_UDP_BYTES_V6: u32 = 48;
+
+impl LogEvent {
+    /// Datagrama UDP fara payload: `packet_bytes` este exact dimensiunea
+    /// antetelor (28 octeti pe IPv4, 48 pe IPv6, fara optiuni / extension
+    /// headers). `bytes = 0` inseamna ca firewall-ul nu a numarat octetii,
+    /// nu o sonda goala; o conexiune cu mai multe datagrame goale are un
+    /// total mai mare si nu este recunoscuta — estimare conservatoare.
+    pub fn is_empty_udp_datagram(&self) -> bool {
+        let headers = if self.source_ip.is_ipv4() {
+            EMPTY_UDP_BYTES_V4
+        } else {
+            EMPTY_UDP_BYTES_V6
+        };
+        self.protocol == "udp" && self.packet_bytes == Some(headers)
+    }
+}
+
 /// Trait-ul central de parsing - contractul pe care orice parser trebuie
 /// sa il respecte.
 ///
@@ -106,6 +143,26 @@ pub trait LogParser: Send + Sync {
     fn expected_format(&self) -> &str;
 }
 
+/// VLAN-ul unei sub-interfete cu tag 802.1Q in conventia Linux / Gaia
+/// (`eth1.100`, `bond0.20` → 100, 20). `None` pentru interfete fara tag
+/// sau cu un numar in afara intervalului valid 1-4094.
+pub fn vlan_from_interface(interface: &str) -> Option<u16> {
+    let (_, tag) = interface.rsplit_once('.')?;
+    tag.parse().ok().filter(|vlan| (1..=4094).contains(vlan))
+}
+
+/// Numele acceptate in `network.parser`, in forma canonica.
+pub const PARSER_NAMES: [&str; 3] = ["gaia", "cef", "gaia_cef"];
+
+/// Forma canonica a unui nume de parser: fara spatii la capete, litere mici.
+///
+/// NOTA RUST: `to_ascii_lowercase` (nu `to_lowercase`) — conversia nu depinde
+/// de locale sau de regulile Unicode; numele valide sunt oricum ASCII, iar
+/// un nume non-ASCII ramane neschimbat si este respins ca necunoscut.
+pub fn canonical_name(parser_type: &str) -> String {
+    parser_type.trim().to_ascii_lowercase()
+}
+
 /// Factory function - creeaza parser-ul potrivit pe baza configurarii.
 ///
 /// NOTA RUST: Returneaza `Result<Box<dyn LogParser>>`:
@@ -117,16 +174,45 @@ pub trait LogParser: Send + Sync {
 /// Aceasta functie exemplifica POLIMORFISMUL in Rust:
 /// La compile-time nu stim ce tip concret vom returna (GaiaParser sau
 /// CefParser). Box<dyn LogParser> rezolva metoda corecta la runtime.
+///
+/// Numele este comparat in forma canonica (`canonical_name`): "GAIA",
+/// " gaia " si "Gaia" selecteaza acelasi parser.
 pub fn create_parser(parser_type: &str) -> anyhow::Result<Box<dyn LogParser>> {
     // `match` este EXHAUSTIV in Rust - compilatorul verifica ca toate
     // cazurile posibile sunt acoperite. Wildcard `_` prinde tot restul.
-    match parser_type {
+    match canonical_name(parser_type).as_str() {
         "gaia" => Ok(Box::new(gaia::GaiaParser::new()?)),
         "cef" => Ok(Box::new(cef::CefParser::new())),
         "gaia_cef" => Ok(Box::new(gaia_cef::GaiaCefParser::new())),
         _ => anyhow::bail!(
-            "Parser necunoscut: '{}'. Optiuni valide: gaia, cef, gaia_cef",
-            parser_type
+            "Parser necunoscut: '{}'. Optiuni valide: {}",
+            parser_type,
+            PARSER_NAMES.join(", ")
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_parser_canonical_names() {
+        for name in ["gaia", "GAIA", " gaia ", "Gaia"] {
+            let parser = create_parser(name).unwrap();
+            assert_eq!(parser.name(), "Checkpoint Gaia (Raw)", "{:?}", name);
+        }
+        assert_eq!(create_parser("CEF").unwrap().name(), "CEF (ArcSight)");
+        assert_eq!(create_parser("Gaia_CEF\n").unwrap().name(), "Checkpoint Gaia LEA (ArcSight)");
+    }
+
+    #[test]
+    fn test_create_parser_unknown_lists_names() {
+        let err = create_parser("syslog").err().expect("parser necunoscut").to_string();
+        assert!(err.contains("'syslog'"), "{}", err);
+        assert!(err.contains(&PARSER_NAMES.join(", ")), "{}", err);
+        // Forma canonica nu accepta nume partiale sau separatori diferiti.
+        assert!(create_parser("gaia-cef").is_err());
+        assert!(create_parser("").is_err());
+    }
+}
@@ -0,0 +1,192 @@
+// =============================================================================
+// recorder.rs - Inregistrare Replay la Alerta (output.replay_dir)
+// =============================================================================
+//
+// Cand o scanare reala declanseaza o alerta interesanta, vrem secventa EXACTA
+// de log-uri care a produs-o — pentru un test de regresie sau pentru a
+// reproduce o intrebare de reglaj ("ar fi alertat si cu pragul 20?").
+//
+// Recorder-ul pastreaza in memorie ultimele `replay_max_lines` linii brute
+// ale fiecarui IP sursa (inel per IP). La emiterea unei alerte, secventa sursei
+// este scrisa intr-un fisier, in ordinea sosirii:
+//
+//   <replay_dir>/20261015-143012_fast-scan_192.168.11.34_7.log
+//
+//   # ids-rs replay: Fast Scan de la 192.168.11.34 (alerta #7)
+//   # parser: gaia | politica: e3db7c65 | 15 linii | 2026-10-15T14:30:12+03:00
+//   Sep  3 15:10:54 fw01 ... (liniile originale, neschimbate)
+//
+// Fisierul este direct reluabil cu harness-ul de test (liniile `#` sunt
+// ignorate de tester.py):
+//
+//   python tester/tester.py replay <fisier> --replay-speed 1
+//
+// Volum limitat: cel mult REPLAY_MAX_SOURCES IP-uri urmarite simultan (IP-urile
+// noi peste plafon nu sunt inregistrate) si `replay_max_files` fisiere scrise
+// per proces — o furtuna de alerte nu umple discul. Inelele surselor tacute
+// sunt eliberate la cleanup (`cleanup.max_entry_age_secs`).
+//
+// =============================================================================
+
+use crate::detector::Alert;
+use anyhow::{Context, Result};
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// IP-uri sursa urmarite simultan (memorie: surse × `replay_max_lines` linii).
+const REPLAY_MAX_SOURCES: usize = 1024;
+
+/// Ultimele linii ale unei surse.
+struct SourceLines {
+    lines: VecDeque<String>,
+    last_seen: Instant,
+}
+
+pub struct ReplayRecorder {
+    dir: PathBuf,
+    max_lines: usize,
+    max_files: usize,
+    /// Numele parserului activ — scris in antet, replay-ul cere acelasi format.
+    parser: String,
+    sources: HashMap<IpAddr, SourceLines>,
+    written: usize,
+    /// Avertismentul "plafon de fisiere atins" a fost deja afisat.
+    warned_full: bool,
+}
+
+impl ReplayRecorder {
+    pub fn new(dir: &str, max_lines: usize, max_files: usize, parser: &str) -> Self {
+        Self {
+            dir: PathBuf::from(dir),
+            max_lines,
+            max_files,
+            parser: parser.to_string(),
+            sources: HashMap::new(),
+            written: 0,
+            warned_full: false,
+        }
+    }
+
+    /// Adauga linia bruta a unui eveniment procesat de detector.
+    pub fn record(&mut self, source_ip: IpAddr, raw_log: &str, now: Instant) {
+        if !self.sources.contains_key(&source_ip) && self.sources.len() >= REPLAY_MAX_SOURCES {
+            return;
+        }
+        let source = self.sources.entry(source_ip).or_insert_with(|| SourceLines {
+            lines: VecDeque::new(),
+            last_seen: now,
+        });
+        if source.lines.len() >= self.max_lines {
+            source.lines.pop_front();
+        }
+        source.lines.push_back(raw_log.to_string());
+        source.last_seen = now;
+    }
+
+    /// Elibereaza inelele surselor fara trafic de cel putin `max_age`.
+    pub fn cleanup(&mut self, max_age: Duration, now: Instant) {
+        self.sources
+            .retain(|_, s| now.saturating_duration_since(s.last_seen) < max_age);
+    }
+
+    /// Scrie secventa sursei alertei intr-un fisier nou. `Ok(None)` cand sursa
+    /// nu are linii inregistrate (ex: Saturation) sau plafonul de fisiere a
+    /// fost atins.
+    pub fn dump(&mut self, alert: &Alert) -> Result<Option<PathBuf>> {
+        let Some(source) = self.sources.get(&alert.source_ip) else {
+            return Ok(None);
+        };
+        if self.written >= self.max_files {
+            if !self.warned_full {
+                self.warned_full = true;
+                crate::display::log_warning(&format!(
+                    "Replay: plafonul de {} fisiere atins — alertele urmatoare nu mai sunt inregistrate (output.replay_max_files)",
+                    self.max_files
+                ));
+            }
+            return Ok(None);
+        }
+
+        let path = self.dir.join(file_name(alert));
+        let mut content = format!(
+            "# ids-rs replay: {} de la {} (alerta #{})\n# parser: {} | politica: {} | {} linii | {}\n",
+            alert.scan_type,
+            alert.source_ip,
+            alert.id,
+            self.parser,
+            alert.policy_version,
+            source.lines.len(),
+            crate::display::format_rfc3339(&alert.timestamp)
+        );
+        for line in &source.lines {
+            content.push_str(line);
+            content.push('\n');
+        }
+        // `create_new`: doua alerte in aceeasi secunda nu se suprascriu.
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .with_context(|| format!("Nu pot crea fisierul replay {:?}", path))?;
+        file.write_all(content.as_bytes())
+            .with_context(|| format!("Nu pot scrie fisierul replay {:?}", path))?;
+        self.written += 1;
+        Ok(Some(path))
+    }
+}
+
+/// `<YYYYmmdd-HHMMSS>_<tip>_<ip>_<id alerta>.log` — tipul in kebab-case, `:` din
+/// IPv6 inlocuit (nume portabile). Id-ul alertei evita coliziunile.
+fn file_name(alert: &Alert) -> String {
+    let scan_type = alert.scan_type.to_string().to_ascii_lowercase().replace(' ', "-");
+    let ip = alert.source_ip.to_string().replace(':', "_");
+    format!(
+        "{}_{}_{}_{}.log",
+        alert.timestamp.format("%Y%m%d-%H%M%S"),
+        scan_type,
+        ip,
+        alert.id
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::ScanType;
+
+    #[test]
+    fn test_replay_recorder_keeps_last_lines_and_dumps_source() {
+        let dir = std::env::temp_dir().join(format!("ids-rs-replay-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut rec = ReplayRecorder::new(dir.to_str().unwrap(), 3, 1, "gaia");
+        let now = Instant::now();
+        let scanner: IpAddr = "10.0.0.5".parse().unwrap();
+        for port in 1..=5 {
+            rec.record(scanner, &format!("linie port {}", port), now);
+        }
+        rec.record("10.0.0.6".parse().unwrap(), "alta sursa", now);
+
+        let alert = Alert::new(ScanType::Fast, scanner, None);
+        let path = rec.dump(&alert).unwrap().expect("sursa are linii");
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().filter(|l| !l.starts_with('#')).collect();
+        // Doar ultimele 3 linii ale sursei alertei, in ordinea sosirii.
+        assert_eq!(lines, vec!["linie port 3", "linie port 4", "linie port 5"]);
+        assert!(content.starts_with("# ids-rs replay: Fast Scan de la 10.0.0.5"));
+        assert!(path.file_name().unwrap().to_str().unwrap().contains("_fast-scan_10.0.0.5_"));
+
+        // Plafonul de fisiere (1) este atins; sursele fara linii sunt ignorate.
+        assert!(rec.dump(&alert).unwrap().is_none());
+        let unknown = Alert::new(ScanType::Fast, "10.9.9.9".parse().unwrap(), None);
+        assert!(rec.dump(&unknown).unwrap().is_none());
+
+        // Cleanup: sursele tacute sunt eliberate.
+        rec.cleanup(Duration::from_secs(60), now + Duration::from_secs(61));
+        assert!(rec.sources.is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
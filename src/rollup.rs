@@ -0,0 +1,288 @@
+// =============================================================================
+// rollup.rs - Raport Agregat Periodic (output.rollup_interval)
+// =============================================================================
+//
+// Managementul nu citeste alerte individuale — vrea un rezumat pe ora sau pe
+// zi. Rollup-ul acumuleaza alertele EMISE (dupa corelare si feedback) intre
+// doua granite de calendar (ora fixa / miezul noptii, ora locala) si produce
+// un document structurat:
+//
+//   {"interval":"hourly","period_start":"...","period_end":"...",
+//    "total_alerts":17,"alerts_by_type":{"Fast Scan":12,"Slow Scan":5},
+//    "top_sources":[{"ip":"10.0.0.9","alerts":9},...],
+//    "top_ports":[{"port":22,"alerts":14},...],"peak_tracked_ips":412}
+//
+// Documentul este trimis prin `output.rollup_sink` (consola, email sau SIEM)
+// — independent de alertele individuale. La fiecare granita contoarele se
+// reseteaza; o perioada fara alerte produce totusi un raport (cu zero), ca
+// absenta lui sa insemne "senzor oprit", nu "liniste".
+//
+// =============================================================================
+
+use crate::detector::Alert;
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone, Timelike};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+
+/// Numarul de intrari din `top_sources` / `top_ports`.
+const ROLLUP_TOP_N: usize = 10;
+
+/// Perioada unui rollup, din `output.rollup_interval`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollupInterval {
+    Hourly,
+    Daily,
+}
+
+impl RollupInterval {
+    /// `None` pentru "" (dezactivat) sau o valoare necunoscuta (respinsa de validare).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "hourly" => Some(RollupInterval::Hourly),
+            "daily" => Some(RollupInterval::Daily),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RollupInterval::Hourly => "hourly",
+            RollupInterval::Daily => "daily",
+        }
+    }
+
+    /// Urmatoarea granita de calendar STRICT dupa `now`.
+    ///
+    /// NOTA RUST: `Local.from_local_datetime(..).earliest()` — la schimbarea orei
+    /// (DST) miezul noptii poate fi ambiguu sau inexistent; `earliest` alege
+    /// prima varianta valida, iar fallback-ul de +1h/+24h acopera ora sarita.
+    fn next_boundary(self, now: DateTime<Local>) -> DateTime<Local> {
+        let naive = match self {
+            RollupInterval::Hourly => now
+                .date_naive()
+                .and_time(NaiveTime::from_hms_opt(now.hour(), 0, 0).unwrap_or_default())
+                + Duration::hours(1),
+            RollupInterval::Daily => {
+                now.date_naive().and_time(NaiveTime::MIN) + Duration::days(1)
+            }
+        };
+        Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .unwrap_or_else(|| match self {
+                RollupInterval::Hourly => now + Duration::hours(1),
+                RollupInterval::Daily => now + Duration::days(1),
+            })
+    }
+}
+
+/// O intrare din `top_sources`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceCount {
+    pub ip: IpAddr,
+    pub alerts: u64,
+}
+
+/// O intrare din `top_ports`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PortCount {
+    pub port: u16,
+    pub alerts: u64,
+}
+
+/// Documentul emis la fiecare granita.
+#[derive(Debug, Clone, Serialize)]
+pub struct RollupSummary {
+    pub interval: &'static str,
+    pub period_start: DateTime<Local>,
+    pub period_end: DateTime<Local>,
+    pub total_alerts: u64,
+    /// Tip alerta (`ScanType::to_string()`) → numar. BTreeMap: ordine stabila.
+    pub alerts_by_type: BTreeMap<String, u64>,
+    pub top_sources: Vec<SourceCount>,
+    /// Porturile vizate: fiecare alerta numara o data fiecare port din `unique_ports`.
+    pub top_ports: Vec<PortCount>,
+    /// Varful de IP-uri sursa urmarite simultan de detector in perioada.
+    pub peak_tracked_ips: usize,
+}
+
+/// Acumulatorul perioadei curente.
+pub struct Rollup {
+    interval: RollupInterval,
+    period_start: DateTime<Local>,
+    next_boundary: DateTime<Local>,
+    total: u64,
+    by_type: BTreeMap<String, u64>,
+    sources: HashMap<IpAddr, u64>,
+    ports: HashMap<u16, u64>,
+    peak_tracked_ips: usize,
+}
+
+impl Rollup {
+    pub fn new(interval: RollupInterval, now: DateTime<Local>) -> Self {
+        Self {
+            interval,
+            period_start: now,
+            next_boundary: interval.next_boundary(now),
+            total: 0,
+            by_type: BTreeMap::new(),
+            sources: HashMap::new(),
+            ports: HashMap::new(),
+            peak_tracked_ips: 0,
+        }
+    }
+
+    /// Numara o alerta emisa.
+    pub fn record(&mut self, alert: &Alert) {
+        self.total += 1;
+        *self.by_type.entry(alert.scan_type.to_string()).or_insert(0) += 1;
+        *self.sources.entry(alert.source_ip).or_insert(0) += 1;
+        for &port in &alert.unique_ports {
+            *self.ports.entry(port).or_insert(0) += 1;
+        }
+    }
+
+    /// Esantion al numarului de IP-uri urmarite (pastram maximul perioadei).
+    pub fn observe_tracked_ips(&mut self, tracked: usize) {
+        self.peak_tracked_ips = self.peak_tracked_ips.max(tracked);
+    }
+
+    /// Daca granita a fost depasita: rezumatul perioadei incheiate, iar
+    /// contoarele se reseteaza pentru perioada urmatoare.
+    pub fn take_due(&mut self, now: DateTime<Local>) -> Option<RollupSummary> {
+        if now < self.next_boundary {
+            return None;
+        }
+        let period_end = self.next_boundary;
+        let summary = RollupSummary {
+            interval: self.interval.as_str(),
+            period_start: self.period_start,
+            period_end,
+            total_alerts: self.total,
+            alerts_by_type: std::mem::take(&mut self.by_type),
+            top_sources: top_n(std::mem::take(&mut self.sources))
+                .into_iter()
+                .map(|(ip, alerts)| SourceCount { ip, alerts })
+                .collect(),
+            top_ports: top_n(std::mem::take(&mut self.ports))
+                .into_iter()
+                .map(|(port, alerts)| PortCount { port, alerts })
+                .collect(),
+            peak_tracked_ips: self.peak_tracked_ips,
+        };
+        self.total = 0;
+        self.peak_tracked_ips = 0;
+        self.period_start = period_end;
+        // Dupa o pauza lunga (suspendare) sarim direct la granita de dupa `now`.
+        self.next_boundary = self.interval.next_boundary(now);
+        Some(summary)
+    }
+}
+
+/// Primele `ROLLUP_TOP_N` intrari dupa numar (descrescator), apoi dupa cheie —
+/// ordine determinista la egalitate.
+fn top_n<K: Ord + Copy>(counts: HashMap<K, u64>) -> Vec<(K, u64)> {
+    let mut entries: Vec<(K, u64)> = counts.into_iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    entries.truncate(ROLLUP_TOP_N);
+    entries
+}
+
+impl RollupSummary {
+    /// Text simplu (consola / corp email): o linie per sectiune.
+    pub fn to_text(&self) -> String {
+        let by_type = if self.alerts_by_type.is_empty() {
+            "-".to_string()
+        } else {
+            self.alerts_by_type
+                .iter()
+                .map(|(t, n)| format!("{}: {}", t, n))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let sources = join_or_dash(self.top_sources.iter().map(|s| format!("{} ({})", s.ip, s.alerts)));
+        let ports = join_or_dash(self.top_ports.iter().map(|p| format!("{} ({})", p.port, p.alerts)));
+        format!(
+            "Perioada: {} → {} ({})\n\
+             Alerte: {} — {}\n\
+             Top surse: {}\n\
+             Top porturi: {}\n\
+             Varf IP-uri urmarite: {}",
+            self.period_start.format("%Y-%m-%d %H:%M"),
+            self.period_end.format("%Y-%m-%d %H:%M"),
+            self.interval,
+            self.total_alerts,
+            by_type,
+            sources,
+            ports,
+            self.peak_tracked_ips
+        )
+    }
+}
+
+fn join_or_dash(items: impl Iterator<Item = String>) -> String {
+    let joined: Vec<String> = items.collect();
+    if joined.is_empty() {
+        "-".to_string()
+    } else {
+        joined.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector::ScanType;
+
+    fn at(h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, 10, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_rollup_aggregates_and_resets_at_boundary() {
+        let mut rollup = Rollup::new(RollupInterval::Hourly, at(10, 17));
+        let src: IpAddr = "10.0.0.9".parse().unwrap();
+        for _ in 0..3 {
+            rollup.record(&Alert {
+                unique_ports: vec![22, 80],
+                ..Alert::new(ScanType::Fast, src, None)
+            });
+        }
+        rollup.record(&Alert {
+            unique_ports: vec![22],
+            ..Alert::new(ScanType::Slow, "10.0.0.7".parse().unwrap(), None)
+        });
+        rollup.observe_tracked_ips(40);
+        rollup.observe_tracked_ips(12);
+
+        // Inainte de ora fixa nu se emite nimic.
+        assert!(rollup.take_due(at(10, 59)).is_none());
+
+        let summary = rollup.take_due(at(11, 0)).expect("granita orei 11:00");
+        assert_eq!(summary.period_start, at(10, 17));
+        assert_eq!(summary.period_end, at(11, 0));
+        assert_eq!(summary.total_alerts, 4);
+        assert_eq!(summary.alerts_by_type["Fast Scan"], 3);
+        assert_eq!(summary.top_sources[0].ip, src);
+        assert_eq!(summary.top_ports[0].port, 22);
+        assert_eq!(summary.top_ports[0].alerts, 4);
+        assert_eq!(summary.peak_tracked_ips, 40);
+
+        // Contoare resetate; perioada urmatoare incepe la granita.
+        let next = rollup.take_due(at(12, 0)).unwrap();
+        assert_eq!(next.period_start, at(11, 0));
+        assert_eq!(next.total_alerts, 0);
+        assert!(next.top_sources.is_empty());
+        assert_eq!(next.peak_tracked_ips, 0);
+    }
+
+    #[test]
+    fn test_daily_boundary_is_local_midnight() {
+        let rollup = Rollup::new(RollupInterval::Daily, at(23, 59));
+        assert_eq!(
+            rollup.next_boundary,
+            Local.with_ymd_and_hms(2026, 3, 11, 0, 0, 0).unwrap()
+        );
+    }
+}
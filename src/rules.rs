@@ -0,0 +1,553 @@
+// =============================================================================
+// rules.rs - Reguli de Detectie din Fisiere (detection.rules_dir)
+// =============================================================================
+//
+// Regulile incorporate (Fast / Slow / Accept Scan etc.) se configureaza in
+// config.toml. Pentru management GitOps, regulile suplimentare stau in fisiere
+// versionate, cate o regula per fisier, intr-un director urmarit:
+//
+//   # rules.d/ssh-dmz.toml
+//   name = "ssh-dmz"                  # implicit: numele fisierului
+//   description = "Scanare DMZ SSH"
+//   port_threshold = 5                # porturi distincte ...
+//   time_window_secs = 60             # ... in aceasta fereastra
+//   targets = ["10.0.5.0/24"]         # tinte (dst) vizate; gol = orice tinta
+//   action = "drop"                   # "drop" (implicit), "accept" sau "any"
+//
+// O sursa care atinge `port_threshold` porturi distincte catre tintele regulii
+// in `time_window_secs` produce o alerta "Custom Rule" cu numele regulii.
+//
+// RELOAD: directorul este verificat periodic (lista de fisiere, mtime si
+// dimensiune). La orice fisier adaugat / modificat / sters, setul COMPLET este
+// reincarcat si validat inainte de aplicare:
+//   - un fisier invalid este ignorat cu un avertisment, iar versiunea lui
+//     anterioara (daca exista) ramane activa;
+//   - o regula neschimbata isi pastreaza starea (hit-uri, cooldown) — doar
+//     regulile noi sau modificate pornesc de la zero.
+//
+// NOTA RUST: nu exista crate de filesystem watcher (inotify) in build-ul
+// offline — verificarea prin polling costa un `read_dir` + `metadata` per
+// fisier la cateva secunde, neglijabil pentru zeci de reguli.
+//
+// =============================================================================
+
+use crate::config::DetectionConfig;
+use crate::detector::{Alert, ScanType, WhitelistEntry};
+use crate::parser::LogEvent;
+use dashmap::DashMap;
+use serde::Deserialize;
+use std::collections::{BTreeSet, VecDeque};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Actiunile firewall acceptate de campul `action`.
+pub const RULE_ACTIONS: [&str; 3] = ["drop", "accept", "any"];
+
+/// Definitia unei reguli, exact ca in fisier.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleDef {
+    /// Numele regulii (apare in alerta). Gol = numele fisierului fara extensie.
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub port_threshold: usize,
+    pub time_window_secs: u64,
+    /// IP-uri / CIDR-uri destinatie. Gol = orice tinta.
+    #[serde(default)]
+    pub targets: Vec<String>,
+    #[serde(default = "default_rule_action")]
+    pub action: String,
+}
+
+fn default_rule_action() -> String {
+    "drop".to_string()
+}
+
+/// Starea unei reguli: hit-urile (moment, port) per IP sursa si cooldown-ul.
+/// Partajata prin `Arc` — o regula neschimbata la reload isi pastreaza starea.
+#[derive(Default)]
+struct RuleState {
+    hits: DashMap<IpAddr, VecDeque<(Instant, u16)>>,
+    cooldowns: DashMap<IpAddr, Instant>,
+}
+
+/// O regula validata, gata de evaluare.
+pub struct CustomRule {
+    pub def: RuleDef,
+    /// Fisierul sursa — pastrarea versiunii anterioare la un fisier invalid.
+    pub file: PathBuf,
+    targets: Vec<WhitelistEntry>,
+    state: Arc<RuleState>,
+}
+
+impl CustomRule {
+    fn matches_event(&self, event: &LogEvent) -> bool {
+        let action_ok = match self.def.action.as_str() {
+            "any" => true,
+            action => event.action == action,
+        };
+        action_ok
+            && (self.targets.is_empty()
+                || event
+                    .dest_ip
+                    .is_some_and(|dst| self.targets.iter().any(|t| t.matches(&dst))))
+    }
+}
+
+/// Setul de reguli activ. Gol cand `rules_dir` nu este setat.
+#[derive(Default)]
+pub struct RuleSet {
+    rules: Vec<CustomRule>,
+}
+
+impl RuleSet {
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.rules.iter().map(|r| r.def.name.as_str()).collect()
+    }
+
+    /// Incarca toate fisierele `.toml` din `dir`. Intoarce setul si
+    /// avertismentele (fisiere ignorate). `previous` = setul activ: starea
+    /// regulilor neschimbate si versiunea anterioara a fisierelor invalide
+    /// sunt preluate din el.
+    pub fn load_dir(dir: &Path, previous: &RuleSet) -> Result<(RuleSet, Vec<String>), String> {
+        let mut files = list_rule_files(dir)?;
+        files.sort();
+
+        let mut rules: Vec<CustomRule> = Vec::new();
+        let mut warnings = Vec::new();
+        for path in files {
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if matches!(ext, "yaml" | "yml") {
+                warnings.push(format!(
+                    "{}: YAML nu este suportat (doar .toml), fisier ignorat",
+                    path.display()
+                ));
+                continue;
+            }
+            let compiled = std::fs::read_to_string(&path)
+                .map_err(|e| format!("nu pot citi fisierul: {}", e))
+                .and_then(|content| compile(&path, &content, previous))
+                .and_then(|rule| {
+                    if rules.iter().any(|r| r.def.name == rule.def.name) {
+                        Err(format!("numele '{}' este deja folosit", rule.def.name))
+                    } else {
+                        Ok(rule)
+                    }
+                });
+            match compiled {
+                Ok(rule) => rules.push(rule),
+                Err(e) => {
+                    // Versiunea anterioara a fisierului ramane activa.
+                    let kept = previous
+                        .rules
+                        .iter()
+                        .find(|r| r.file == path && !rules.iter().any(|n| n.def.name == r.def.name));
+                    let note = match kept {
+                        Some(old) => {
+                            rules.push(CustomRule {
+                                def: old.def.clone(),
+                                file: old.file.clone(),
+                                targets: old.targets.clone(),
+                                state: Arc::clone(&old.state),
+                            });
+                            " (ramane activa versiunea anterioara)"
+                        }
+                        None => "",
+                    };
+                    warnings.push(format!("{}: {}{}", path.display(), e, note));
+                }
+            }
+        }
+        Ok((RuleSet { rules }, warnings))
+    }
+
+    /// Evalueaza evenimentul contra fiecarei reguli. Cooldown-ul si plafoanele
+    /// de memorie (`alert_cooldown_secs`, `max_tracked_ips`, `max_hits_per_ip`)
+    /// sunt cele din `[detection]`.
+    pub fn evaluate(&self, event: &LogEvent, cfg: &DetectionConfig, now: Instant) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        let ip = event.source_ip;
+        for rule in &self.rules {
+            if !rule.matches_event(event) {
+                continue;
+            }
+            let state = &rule.state;
+            if !state.hits.contains_key(&ip) && state.hits.len() >= cfg.max_tracked_ips {
+                continue;
+            }
+            let window = Duration::from_secs(rule.def.time_window_secs);
+            let (ports, first) = {
+                let mut hits = state.hits.entry(ip).or_default();
+                while hits
+                    .front()
+                    .is_some_and(|(t, _)| now.saturating_duration_since(*t) > window)
+                {
+                    hits.pop_front();
+                }
+                if hits.len() >= cfg.max_hits_per_ip {
+                    hits.pop_front();
+                }
+                hits.push_back((now, event.dest_port));
+                let ports: BTreeSet<u16> = hits.iter().map(|(_, p)| *p).collect();
+                (ports, hits.front().map_or(now, |(t, _)| *t))
+            };
+            if ports.len() < rule.def.port_threshold {
+                continue;
+            }
+            let cooldown = Duration::from_secs(cfg.alert_cooldown_secs);
+            if state
+                .cooldowns
+                .get(&ip)
+                .is_some_and(|t| now.saturating_duration_since(*t) < cooldown)
+            {
+                continue;
+            }
+            state.cooldowns.insert(ip, now);
+            alerts.push(Alert {
+                unique_ports: ports.into_iter().collect(),
+                rule_name: rule.def.name.clone(),
+                time_to_threshold_ms: Some(now.saturating_duration_since(first).as_millis() as u64),
+                ..Alert::new(ScanType::CustomRule, ip, event.dest_ip)
+            });
+        }
+        alerts
+    }
+
+    /// Elimina IP-urile fara hit-uri in fereastra regulii si cooldown-urile expirate.
+    pub fn cleanup(&self, cooldown: Duration, now: Instant) {
+        for rule in &self.rules {
+            let window = Duration::from_secs(rule.def.time_window_secs);
+            rule.state.hits.retain(|_, hits| {
+                hits.back()
+                    .is_some_and(|(t, _)| now.saturating_duration_since(*t) <= window)
+            });
+            rule.state
+                .cooldowns
+                .retain(|_, t| now.saturating_duration_since(*t) < cooldown);
+        }
+    }
+}
+
+/// Parseaza si valideaza un fisier de regula.
+fn compile(path: &Path, content: &str, previous: &RuleSet) -> Result<CustomRule, String> {
+    // Mesajul toml poate avea mai multe linii — avertismentul ramane pe una.
+    let mut def: RuleDef =
+        toml::from_str(content).map_err(|e| e.message().trim().replace('\n', " "))?;
+    if def.name.is_empty() {
+        def.name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+    }
+    if def.name.is_empty() || def.name.chars().any(char::is_control) {
+        return Err(format!("nume de regula invalid: {:?}", def.name));
+    }
+    if def.port_threshold == 0 {
+        return Err("port_threshold = 0: orice pachet ar declansa alerta".to_string());
+    }
+    if def.time_window_secs == 0 {
+        return Err("time_window_secs = 0: fereastra zero face detectia imposibila".to_string());
+    }
+    if !RULE_ACTIONS.contains(&def.action.as_str()) {
+        return Err(format!(
+            "action = {:?} necunoscuta (valide: {})",
+            def.action,
+            RULE_ACTIONS.join(", ")
+        ));
+    }
+    let targets = def
+        .targets
+        .iter()
+        .map(|t| WhitelistEntry::parse(t).ok_or_else(|| format!("tinta invalida: {:?}", t)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Regula neschimbata (aceeasi definitie): starea continua.
+    let state = previous
+        .rules
+        .iter()
+        .find(|r| r.def == def)
+        .map(|r| Arc::clone(&r.state))
+        .unwrap_or_default();
+    Ok(CustomRule {
+        def,
+        file: path.to_path_buf(),
+        targets,
+        state,
+    })
+}
+
+/// Fisierele candidate din director (`.toml`, `.yaml`, `.yml`), fara subdirectoare.
+fn list_rule_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("nu pot citi directorul de reguli {}: {}", dir.display(), e))?;
+    Ok(entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| matches!(e, "toml" | "yaml" | "yml"))
+        })
+        .collect())
+}
+
+/// Amprenta directorului: (fisier, mtime, dimensiune) pentru fiecare regula.
+type DirSnapshot = Vec<(PathBuf, Option<SystemTime>, u64)>;
+
+fn snapshot(dir: &Path) -> DirSnapshot {
+    let mut files: DirSnapshot = list_rule_files(dir)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|path| {
+            let meta = std::fs::metadata(&path).ok();
+            let modified = meta.as_ref().and_then(|m| m.modified().ok());
+            let len = meta.map_or(0, |m| m.len());
+            (path, modified, len)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Detecteaza schimbarile din `rules_dir` prin comparatia amprentelor.
+pub struct RulesWatcher {
+    dir: PathBuf,
+    last: DirSnapshot,
+}
+
+impl RulesWatcher {
+    /// Amprenta initiala = starea de la incarcare (fara reload imediat).
+    pub fn new(dir: &str) -> Self {
+        let dir = PathBuf::from(dir);
+        let last = snapshot(&dir);
+        Self { dir, last }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// True daca un fisier a fost adaugat, modificat sau sters de la ultimul apel.
+    pub fn changed(&mut self) -> bool {
+        let current = snapshot(&self.dir);
+        if current == self.last {
+            return false;
+        }
+        self.last = current;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(src: &str, dst: &str, port: u16) -> LogEvent {
+        LogEvent {
+            source_ip: src.parse().unwrap(),
+            dest_ip: Some(dst.parse().unwrap()),
+            dest_port: port,
+            protocol: "tcp".to_string(),
+            action: "drop".to_string(),
+            packet_bytes: None,
+            interface: None,
+            source_port: None,
+            raw_log: String::new(),
+        }
+    }
+
+    /// Director de reguli temporar, propriu fiecarui test, sters la final.
+    struct RulesDir(PathBuf);
+
+    impl RulesDir {
+        fn new(tag: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("ids-rs-rules-{}-{}", tag, std::process::id()));
+            std::fs::remove_dir_all(&dir).ok();
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn write(&self, file: &str, content: &str) {
+            std::fs::write(self.0.join(file), content).unwrap();
+        }
+
+        fn load(&self, previous: &RuleSet) -> (RuleSet, Vec<String>) {
+            RuleSet::load_dir(&self.0, previous).unwrap()
+        }
+    }
+
+    impl Drop for RulesDir {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    fn cfg() -> DetectionConfig {
+        toml::from_str(
+            r#"
+            alert_cooldown_secs = 300
+            [fast_scan]
+            port_threshold = 42
+            time_window_secs = 10
+            [slow_scan]
+            port_threshold = 30
+            time_window_mins = 5
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn compile_err(content: &str) -> String {
+        match compile(Path::new("rules.d/regula.toml"), content, &RuleSet::default()) {
+            Ok(_) => panic!("regula acceptata: {}", content),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let rule = compile(
+            Path::new("rules.d/ssh-dmz.toml"),
+            "port_threshold = 3\ntime_window_secs = 60\n",
+            &RuleSet::default(),
+        )
+        .unwrap();
+        assert_eq!(rule.def.name, "ssh-dmz", "implicit: numele fisierului");
+        assert_eq!(rule.def.action, "drop");
+
+        assert!(compile_err("port_threshold = \"multe\"\ntime_window_secs = 60\n").contains("invalid type"));
+        assert!(compile_err("port_threshold = 3\ntime_window_secs = 60\nprag = 1\n").contains("unknown field"));
+        assert!(compile_err("port_threshold = 0\ntime_window_secs = 60\n").starts_with("port_threshold = 0"));
+        assert!(compile_err("port_threshold = 3\ntime_window_secs = 0\n").starts_with("time_window_secs = 0"));
+        assert!(compile_err("port_threshold = 3\ntime_window_secs = 60\naction = \"reject\"\n")
+            .starts_with("action = \"reject\" necunoscuta"));
+        assert!(compile_err("port_threshold = 3\ntime_window_secs = 60\ntargets = [\"10.0.5.0/33\"]\n")
+            .starts_with("tinta invalida"));
+        assert!(compile_err("name = \"a\\nb\"\nport_threshold = 3\ntime_window_secs = 60\n")
+            .starts_with("nume de regula invalid"));
+    }
+
+    #[test]
+    fn test_load_dir_skips_yaml_and_duplicate_names() {
+        let dir = RulesDir::new("skip");
+        dir.write("a.toml", "name = \"ssh\"\nport_threshold = 3\ntime_window_secs = 60\n");
+        dir.write("b.toml", "name = \"ssh\"\nport_threshold = 5\ntime_window_secs = 60\n");
+        dir.write("x.yaml", "port_threshold: 3\n");
+        dir.write("notes.txt", "nu este regula\n");
+
+        let (set, warnings) = dir.load(&RuleSet::default());
+        assert_eq!(set.names(), vec!["ssh"]);
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert!(warnings.iter().any(|w| w.contains("numele 'ssh' este deja folosit")));
+        assert!(warnings.iter().any(|w| w.contains("YAML nu este suportat")));
+    }
+
+    #[test]
+    fn test_invalid_file_keeps_previous_version() {
+        let dir = RulesDir::new("fallback");
+        dir.write("ssh.toml", "port_threshold = 2\ntime_window_secs = 60\n");
+        let (set, _) = dir.load(&RuleSet::default());
+        let now = Instant::now();
+        assert!(set.evaluate(&event("10.0.0.9", "10.0.5.10", 22), &cfg(), now).is_empty());
+
+        dir.write("ssh.toml", "port_threshold = 0\ntime_window_secs = 60\n");
+        let (set, warnings) = dir.load(&set);
+        assert_eq!(set.names(), vec!["ssh"]);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].ends_with("(ramane activa versiunea anterioara)"), "{}", warnings[0]);
+        // Versiunea pastrata isi continua starea: al doilea port declanseaza.
+        assert_eq!(set.evaluate(&event("10.0.0.9", "10.0.5.10", 23), &cfg(), now).len(), 1);
+
+        // Un fisier nou invalid nu are versiune anterioara.
+        dir.write("new.toml", "port_threshold = 0\ntime_window_secs = 60\n");
+        let (set, warnings) = dir.load(&set);
+        assert_eq!(set.names(), vec!["ssh"]);
+        assert!(warnings.iter().any(|w| w.contains("new.toml") && !w.contains("versiunea anterioara")));
+    }
+
+    #[test]
+    fn test_targets_cidr_and_action_filter() {
+        let dir = RulesDir::new("targets");
+        dir.write(
+            "dmz.toml",
+            "port_threshold = 2\ntime_window_secs = 60\ntargets = [\"10.0.5.0/24\", \"2001:db8::/32\"]\n",
+        );
+        let (set, _) = dir.load(&RuleSet::default());
+        let now = Instant::now();
+
+        // Tinta in afara CIDR-urilor: ignorata, nu numara portul.
+        assert!(set.evaluate(&event("10.0.0.9", "10.0.9.1", 1), &cfg(), now).is_empty());
+        assert!(set.evaluate(&event("10.0.0.9", "10.0.5.10", 22), &cfg(), now).is_empty());
+        // Accept-ul nu trece de `action = "drop"` (implicit).
+        let mut accept = event("10.0.0.9", "10.0.5.11", 80);
+        accept.action = "accept".to_string();
+        assert!(set.evaluate(&accept, &cfg(), now).is_empty());
+
+        let alerts = set.evaluate(&event("10.0.0.9", "10.0.5.12", 23), &cfg(), now);
+        assert_eq!(alerts.len(), 1);
+        assert!(matches!(alerts[0].scan_type, ScanType::CustomRule));
+        assert_eq!(alerts[0].rule_name, "dmz");
+        assert_eq!(alerts[0].unique_ports, vec![22, 23]);
+
+        // IPv6 in lista de tinte.
+        assert!(set.evaluate(&event("2001:db8:ff::1", "2001:db8::5", 22), &cfg(), now).is_empty());
+        assert_eq!(set.evaluate(&event("2001:db8:ff::1", "2001:db8::6", 23), &cfg(), now).len(), 1);
+    }
+
+    #[test]
+    fn test_reload_carries_state_of_unchanged_rules() {
+        let dir = RulesDir::new("state");
+        dir.write("kept.toml", "port_threshold = 3\ntime_window_secs = 60\n");
+        dir.write("edited.toml", "port_threshold = 3\ntime_window_secs = 60\n");
+        let (set, _) = dir.load(&RuleSet::default());
+        let now = Instant::now();
+        for port in [22, 23] {
+            assert!(set.evaluate(&event("10.0.0.9", "10.0.5.10", port), &cfg(), now).is_empty());
+        }
+
+        // `edited` este modificata (alta fereastra) — porneste de la zero.
+        dir.write("edited.toml", "port_threshold = 3\ntime_window_secs = 120\n");
+        let (set, warnings) = dir.load(&set);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        let alerts = set.evaluate(&event("10.0.0.9", "10.0.5.10", 24), &cfg(), now);
+        let names: Vec<&str> = alerts.iter().map(|a| a.rule_name.as_str()).collect();
+        assert_eq!(names, vec!["kept"]);
+        assert_eq!(alerts[0].unique_ports, vec![22, 23, 24]);
+    }
+
+    #[test]
+    fn test_watcher_detects_added_changed_removed_files() {
+        let dir = RulesDir::new("watcher");
+        dir.write("ssh.toml", "port_threshold = 3\ntime_window_secs = 60\n");
+        let mut watcher = RulesWatcher::new(dir.0.to_str().unwrap());
+        assert!(!watcher.changed(), "amprenta initiala = starea de la incarcare");
+
+        // Dimensiune diferita: detectata si cand mtime ramane in aceeasi secunda.
+        dir.write("ssh.toml", "port_threshold = 30\ntime_window_secs = 60\n");
+        assert!(watcher.changed());
+        assert!(!watcher.changed(), "schimbarea este raportata o singura data");
+
+        dir.write("rdp.toml", "port_threshold = 3\ntime_window_secs = 60\n");
+        assert!(watcher.changed());
+        // Fisierele ignorate (alte extensii) nu declanseaza reload.
+        dir.write("README", "note\n");
+        assert!(!watcher.changed());
+
+        std::fs::remove_file(dir.0.join("rdp.toml")).unwrap();
+        assert!(watcher.changed());
+    }
+}
@@ -0,0 +1,237 @@
+// =============================================================================
+// secrets.rs - Fisier de Secrete si Interpolare `${NUME}` in Configurare
+// =============================================================================
+//
+// Parolele SMTP si cheile API nu trebuie sa stea in config.toml (care ajunge
+// in version control, backup-uri, tichete de suport). Ele se muta intr-un
+// fisier separat, referit din config:
+//
+//   config.toml:     secrets_file = "secrets.env"
+//                    [alerting.email]
+//                    password = "${SMTP_PASSWORD}"
+//
+//   secrets.env:     # comentariu
+//                    SMTP_PASSWORD=parola-reala
+//
+// Reguli:
+//   - Fisierul de secrete NU poate fi accesibil altor utilizatori
+//     (permisiuni "other" = 0, ex: chmod 600) — altfel pornirea esueaza.
+//   - O referinta `${NUME}` fara secret corespondent opreste pornirea.
+//   - Interpolarea se aplica doar valorilor string din TOML, nu cheilor.
+//
+// NOTA RUST: interpolarea lucreaza pe `toml::Value` (arborele generic),
+// INAINTE de deserializarea in `AppConfig`. Astfel orice camp string din
+// orice sectiune suporta `${...}` fara cod dedicat per camp.
+//
+// =============================================================================
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Citeste fisierul de secrete: linii `NUME=valoare`, `#` comentarii.
+///
+/// Valoarea este tot ce urmeaza dupa primul `=`, fara spatiile de la capete;
+/// ghilimelele duble sau simple care o incadreaza complet sunt eliminate.
+pub fn load_secrets(path: &Path) -> Result<HashMap<String, String>> {
+    check_permissions(path)?;
+
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Nu pot citi fisierul de secrete: {:?}", path))?;
+    parse_secrets(&content).with_context(|| format!("Fisier de secrete invalid: {:?}", path))
+}
+
+/// Parseaza continutul unui fisier de secrete (separat pentru teste).
+fn parse_secrets(content: &str) -> Result<HashMap<String, String>> {
+    let mut secrets = HashMap::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else {
+            bail!("linia {}: lipseste '=' (format: NUME=valoare)", i + 1);
+        };
+        let name = name.trim();
+        if !is_valid_name(name) {
+            bail!(
+                "linia {}: nume invalid {:?} (doar litere, cifre si '_')",
+                i + 1,
+                name
+            );
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+            .unwrap_or(value);
+        secrets.insert(name.to_string(), value.to_string());
+    }
+    Ok(secrets)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Refuza un fisier de secrete accesibil altor utilizatori (bitii "other").
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)
+        .with_context(|| format!("Nu pot citi fisierul de secrete: {:?}", path))?
+        .permissions()
+        .mode();
+    if mode & 0o007 != 0 {
+        bail!(
+            "Fisierul de secrete {:?} este accesibil tuturor utilizatorilor (mod {:o}). \
+             Restrictionati-l: chmod 600 {:?}",
+            path,
+            mode & 0o777,
+            path
+        );
+    }
+    Ok(())
+}
+
+/// Pe platforme non-Unix permisiunile nu au bitii rwx — verificarea lipseste.
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Inlocuieste `${NUME}` in toate valorile string din arborele TOML.
+///
+/// Colecteaza TOATE referintele lipsa si le raporteaza impreuna, ca
+/// `AppConfig::validate()`.
+pub fn interpolate(value: &mut toml::Value, secrets: &HashMap<String, String>) -> Result<()> {
+    let mut missing = Vec::new();
+    interpolate_value(value, secrets, &mut missing);
+    if !missing.is_empty() {
+        missing.sort();
+        missing.dedup();
+        bail!(
+            "Secrete referite in configurare dar absente din secrets_file: {}",
+            missing.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Parcurgere recursiva: doar `String` se modifica, tabelele/array-urile se
+/// parcurg, restul tipurilor (numere, bool, date) raman neatinse.
+fn interpolate_value(
+    value: &mut toml::Value,
+    secrets: &HashMap<String, String>,
+    missing: &mut Vec<String>,
+) {
+    match value {
+        toml::Value::String(s) if s.contains("${") => {
+            *s = interpolate_str(s, secrets, missing);
+        }
+        toml::Value::Array(items) => {
+            for item in items {
+                interpolate_value(item, secrets, missing);
+            }
+        }
+        toml::Value::Table(table) => {
+            for (_, item) in table.iter_mut() {
+                interpolate_value(item, secrets, missing);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Inlocuieste referintele dintr-un singur string. Un `${` fara `}` de
+/// inchidere sau cu nume invalid este lasat neschimbat (text literal).
+fn interpolate_str(
+    input: &str,
+    secrets: &HashMap<String, String>,
+    missing: &mut Vec<String>,
+) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find('}') {
+            Some(end) if is_valid_name(&after[..end]) => {
+                let name = &after[..end];
+                match secrets.get(name) {
+                    Some(secret) => out.push_str(secret),
+                    None => missing.push(name.to_string()),
+                }
+                rest = &after[end + 1..];
+            }
+            _ => {
+                out.push_str("${");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// True daca arborele TOML contine cel putin o referinta `${NUME}`.
+pub fn has_references(value: &toml::Value) -> bool {
+    let mut missing = Vec::new();
+    let mut probe = value.clone();
+    interpolate_value(&mut probe, &HashMap::new(), &mut missing);
+    !missing.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_secrets_format() {
+        let secrets = parse_secrets(
+            "# comentariu\n\nSMTP_PASSWORD = s3cr=et\nAPI_KEY=\"cu spatii \"\nQ='x'\n",
+        )
+        .unwrap();
+        assert_eq!(secrets["SMTP_PASSWORD"], "s3cr=et");
+        assert_eq!(secrets["API_KEY"], "cu spatii ");
+        assert_eq!(secrets["Q"], "x");
+        assert!(parse_secrets("fara_egal").is_err());
+        assert!(parse_secrets("NUME-CU-LINIE=x").is_err());
+    }
+
+    #[test]
+    fn test_interpolate_nested_and_missing() {
+        let secrets: HashMap<String, String> =
+            [("PASS".to_string(), "p@ss".to_string())].into_iter().collect();
+        let mut value: toml::Value = toml::from_str(
+            "port = 25\n[email]\npassword = \"${PASS}\"\nto = [\"a-${PASS}\", \"${nu inchis\"]\n",
+        )
+        .unwrap();
+        interpolate(&mut value, &secrets).unwrap();
+        assert_eq!(value["email"]["password"].as_str(), Some("p@ss"));
+        assert_eq!(value["email"]["to"][0].as_str(), Some("a-p@ss"));
+        assert_eq!(value["email"]["to"][1].as_str(), Some("${nu inchis"));
+        assert_eq!(value["port"].as_integer(), Some(25));
+
+        let mut value: toml::Value = toml::from_str("a = \"${X}\"\nb = \"${Y}\"").unwrap();
+        assert!(has_references(&value));
+        let err = interpolate(&mut value, &secrets).unwrap_err().to_string();
+        assert!(err.contains("X, Y"), "{}", err);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_world_readable_secrets_rejected() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("ids-rs-secrets-{}.env", std::process::id()));
+        std::fs::write(&path, "PASS=x\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(load_secrets(&path).is_err());
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        assert_eq!(load_secrets(&path).unwrap()["PASS"], "x");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
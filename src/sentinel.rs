@@ -0,0 +1,240 @@
+// =============================================================================
+// sentinel.rs - Destinatie Microsoft Sentinel (Log Analytics Data Collector API)
+// =============================================================================
+//
+// SOC-urile pe Azure ingereaza prin HTTP Data Collector API: fiecare alerta
+// este un POST HTTPS cu un array JSON catre
+//
+//   https://<workspace_id>.ods.opinsights.azure.com/api/logs?api-version=2016-04-01
+//
+// si ajunge in tabela custom `<log_type>_CL`. Autentificarea este o semnatura
+// HMAC-SHA256 cu cheia workspace-ului peste "cererea canonica":
+//
+//   POST\n<content-length>\napplication/json\nx-ms-date:<data RFC 1123>\n/api/logs
+//
+//   Authorization: SharedKey <workspace_id>:<base64(HMAC)>
+//
+// Azure respinge cererile cu `x-ms-date` decalat cu mai mult de ~15 minute —
+// ceasul senzorului trebuie sincronizat (NTP).
+//
+// Cu `alerting.idempotency_key`, cererea poarta antetul `Idempotency-Key`
+// (cheia alertei). O cerere scrisa complet, dar fara raspuns (timeout,
+// conexiune cazuta), are un rezultat NECUNOSCUT — poate a fost ingerata.
+// `post_logs` semnaleaza acest caz, iar re-trimiterea (`max_retries`) marcheaza
+// documentul `possible_duplicate`.
+//
+// NOTA RUST: clientul HTTP este minimal (HTTP/1.1, `Connection: close`, o
+// cerere per conexiune) peste `tokio-native-tls` — aceeasi stiva TLS pe care
+// lettre o foloseste deja pentru SMTP, fara un client HTTP complet ca dependenta.
+//
+// =============================================================================
+
+use crate::config::SentinelConfig;
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::sign::Signer;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Versiunea API-ului Data Collector.
+const API_VERSION: &str = "2016-04-01";
+
+/// Timeout pentru conectare + cerere + raspuns.
+const SENTINEL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Raspunsul este citit doar pentru linia de status si un mesaj de eroare scurt.
+const MAX_RESPONSE_BYTES: u64 = 16 * 1024;
+
+/// Decodeaza cheia workspace-ului (base64). Folosit si la validarea config-ului.
+pub fn decode_shared_key(key: &str) -> std::result::Result<Vec<u8>, String> {
+    if key.is_empty() {
+        return Err("nu poate fi goala cand Sentinel este activat".to_string());
+    }
+    match BASE64.decode(key) {
+        Ok(bytes) if !bytes.is_empty() => Ok(bytes),
+        _ => Err("nu este o cheie base64 valida (cheia primara/secundara a workspace-ului)".to_string()),
+    }
+}
+
+/// Data in formatul cerut de `x-ms-date` (RFC 1123, mereu GMT).
+fn rfc1123_date(now: DateTime<Utc>) -> String {
+    now.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Antetul `Authorization` pentru un POST de `content_length` octeti.
+fn authorization(workspace_id: &str, key: &[u8], date: &str, content_length: usize) -> Result<String> {
+    let canonical = format!(
+        "POST\n{}\napplication/json\nx-ms-date:{}\n/api/logs",
+        content_length, date
+    );
+    let pkey = PKey::hmac(key).context("Cheie HMAC invalida")?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &pkey).context("HMAC-SHA256 indisponibil")?;
+    signer.update(canonical.as_bytes())?;
+    let signature = signer.sign_to_vec()?;
+    Ok(format!("SharedKey {}:{}", workspace_id, BASE64.encode(signature)))
+}
+
+/// Codul de status din prima linie a raspunsului (`HTTP/1.1 200 OK`).
+fn parse_status(response: &[u8]) -> Result<u16> {
+    let line = response.split(|&b| b == b'\n').next().unwrap_or_default();
+    let line = String::from_utf8_lossy(line);
+    line.split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .with_context(|| format!("Raspuns HTTP invalid: {:?}", line.trim_end()))
+}
+
+/// Trimite `body` (array JSON) catre tabela `<log_type>_CL`.
+///
+/// `time_field` = campul din document folosit ca `TimeGenerated` (timestamp-ul
+/// alertei, nu momentul ingestiei). `idempotency_key` devine antetul
+/// `Idempotency-Key`. La eroare, `uncertain` = true daca cererea a fost scrisa
+/// complet fara un raspuns citit (livrare posibila).
+pub async fn post_logs(
+    cfg: &SentinelConfig,
+    body: &[u8],
+    time_field: &str,
+    idempotency_key: Option<&str>,
+    uncertain: &mut bool,
+) -> Result<()> {
+    *uncertain = false;
+    tokio::time::timeout(
+        SENTINEL_TIMEOUT,
+        post_logs_inner(cfg, body, time_field, idempotency_key, uncertain),
+    )
+    .await
+    .context("Timeout Sentinel (30s)")?
+}
+
+/// Linia de cerere si antetele POST-ului (fara corp), semnate pentru `date`.
+fn request_head(
+    cfg: &SentinelConfig,
+    date: &str,
+    content_length: usize,
+    time_field: &str,
+    idempotency_key: Option<&str>,
+) -> Result<String> {
+    let key = decode_shared_key(&cfg.shared_key).map_err(anyhow::Error::msg)?;
+    let auth = authorization(&cfg.workspace_id, &key, date, content_length)?;
+    let host = format!("{}.{}", cfg.workspace_id, cfg.endpoint_domain);
+    let key_header = idempotency_key
+        .map(|key| format!("Idempotency-Key: {}\r\n", key))
+        .unwrap_or_default();
+    Ok(format!(
+        "POST /api/logs?api-version={API_VERSION} HTTP/1.1\r\n\
+         Host: {host}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {content_length}\r\n\
+         Log-Type: {log_type}\r\n\
+         x-ms-date: {date}\r\n\
+         time-generated-field: {time_field}\r\n\
+         {key_header}\
+         Authorization: {auth}\r\n\
+         Connection: close\r\n\r\n",
+        log_type = cfg.log_type,
+    ))
+}
+
+async fn post_logs_inner(
+    cfg: &SentinelConfig,
+    body: &[u8],
+    time_field: &str,
+    idempotency_key: Option<&str>,
+    uncertain: &mut bool,
+) -> Result<()> {
+    let host = format!("{}.{}", cfg.workspace_id, cfg.endpoint_domain);
+    let head = request_head(cfg, &rfc1123_date(Utc::now()), body.len(), time_field, idempotency_key)?;
+
+    let tcp = TcpStream::connect((host.as_str(), 443))
+        .await
+        .with_context(|| format!("Nu ma pot conecta la {}:443", host))?;
+    let connector = tokio_native_tls::TlsConnector::from(
+        native_tls::TlsConnector::new().context("Nu pot initializa TLS")?,
+    );
+    let mut stream = connector
+        .connect(&host, tcp)
+        .await
+        .with_context(|| format!("Handshake TLS esuat cu {}", host))?;
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.flush().await?;
+    // De aici rezultatul este necunoscut pana la citirea statusului.
+    *uncertain = true;
+
+    let mut response = Vec::new();
+    (&mut stream)
+        .take(MAX_RESPONSE_BYTES)
+        .read_to_end(&mut response)
+        .await
+        .context("Eroare la citirea raspunsului Sentinel")?;
+
+    let status = parse_status(&response)?;
+    *uncertain = false;
+    if !(200..300).contains(&status) {
+        // Corpul raspunsului contine motivul (ex: "InvalidAuthorization").
+        let text = String::from_utf8_lossy(&response);
+        let detail = text.split("\r\n\r\n").nth(1).unwrap_or("").trim();
+        bail!(
+            "Sentinel a respins cererea: HTTP {} {}",
+            status,
+            detail.chars().take(200).collect::<String>()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_sentinel_signature_matches_reference() {
+        let key = decode_shared_key("aWRzLXJzLXRlc3Qta2V5LTAxMjM0NTY3ODlhYmNkZWY=").unwrap();
+        let date = rfc1123_date(Utc.with_ymd_and_hms(2026, 3, 10, 9, 5, 0).unwrap());
+        assert_eq!(date, "Tue, 10 Mar 2026 09:05:00 GMT");
+
+        // Valoare de referinta calculata independent (Python hmac + hashlib).
+        let auth = authorization("ws-id", &key, &date, 123).unwrap();
+        assert_eq!(auth, "SharedKey ws-id:7N+RU1VxfJt7pZKc4+y+OqFs0t/crAV4FN1nyAp0+X8=");
+
+        assert!(decode_shared_key("").is_err());
+        assert!(decode_shared_key("nu-e-base64!").is_err());
+        assert_eq!(parse_status(b"HTTP/1.1 403 Forbidden\r\n\r\n").unwrap(), 403);
+        assert!(parse_status(b"garbage").is_err());
+    }
+
+    #[test]
+    fn test_request_head_idempotency_key() {
+        let cfg: SentinelConfig = toml::from_str(
+            r#"
+            enabled = true
+            workspace_id = "ws-id"
+            shared_key = "aWRzLXJzLXRlc3Qta2V5LTAxMjM0NTY3ODlhYmNkZWY="
+            "#,
+        )
+        .unwrap();
+        let date = "Tue, 10 Mar 2026 09:05:00 GMT";
+
+        let head = request_head(&cfg, date, 123, "timestamp", Some("1773133500000-7")).unwrap();
+        assert!(head.starts_with("POST /api/logs?api-version=2016-04-01 HTTP/1.1\r\n"));
+        assert!(head.contains("Host: ws-id.ods.opinsights.azure.com\r\n"));
+        assert!(head.contains("Content-Length: 123\r\n"));
+        assert!(head.contains("Log-Type: IdsRsAlert\r\n"));
+        assert!(head.contains("time-generated-field: timestamp\r\n"));
+        assert!(head.contains("Idempotency-Key: 1773133500000-7\r\n"));
+        // Aceeasi semnatura ca valoarea de referinta de mai sus.
+        assert!(head.contains("Authorization: SharedKey ws-id:7N+RU1VxfJt7pZKc4+y+OqFs0t/crAV4FN1nyAp0+X8=\r\n"));
+        assert!(head.ends_with("Connection: close\r\n\r\n"));
+
+        // Fara cheie: antetul lipseste cu totul.
+        let head = request_head(&cfg, date, 123, "timestamp", None).unwrap();
+        assert!(!head.contains("Idempotency-Key"));
+    }
+}
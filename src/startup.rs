@@ -0,0 +1,209 @@
+// =============================================================================
+// startup.rs - Asteptarea Dependintelor la Pornire (startup.wait_for)
+// =============================================================================
+//
+// Intr-o implementare orchestrata (docker compose, Kubernetes), IDS-RS poate
+// porni inaintea colectorului SIEM sau inaintea fisierului de enrichment
+// montat de un init-container. Fara asteptare, primele alerte esueaza la
+// livrare, iar harta CMDB lipsa opreste pornirea.
+//
+// `startup.wait_for` listeaza dependintele verificate INAINTE de incarcarea
+// configurarii (`StartupConfig::peek` citeste doar `[startup]` — secrets_file,
+// replay_dir si rules_dir trebuie sa existe la `AppConfig::load`) si deci
+// inaintea componentelor (harta de active, sink-uri, dashboard, metrici, UDP):
+//
+//   tcp:siem.local:514          → conexiune TCP reusita
+//   file:/etc/ids-rs/assets.csv → fisierul (sau directorul) exista
+//
+// Fiecare dependinta este reincercata la `poll_interval_secs` pana devine
+// disponibila sau pana la `timeout_secs`. La expirare, `on_timeout` decide:
+// "fail" opreste procesul (orchestratorul il reporneste), "continue" porneste
+// detectia cu un WARN. Serverele web / metrici pornesc abia dupa asteptare,
+// deci o sonda de readiness pe ele devine semnificativa.
+//
+// NOTA: un colector SIEM UDP (syslog 514/udp) nu poate fi verificat — UDP nu
+// are handshake. Pentru el se foloseste un port TCP al aceluiasi colector.
+//
+// =============================================================================
+
+use crate::config::StartupConfig;
+use crate::display;
+use std::time::{Duration, Instant};
+
+/// Intervalul minim intre doua mesaje de progres "astept inca ...".
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// O dependinta din `startup.wait_for`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dependency {
+    /// `tcp:<host>:<port>` — adresa pentru `TcpStream::connect` ("host:port").
+    Tcp(String),
+    /// `file:<cale>` — fisier sau director care trebuie sa existe.
+    File(String),
+}
+
+impl Dependency {
+    /// Parseaza o intrare `tcp:<host>:<port>` sau `file:<cale>`.
+    pub fn parse(entry: &str) -> Result<Self, String> {
+        if let Some(addr) = entry.strip_prefix("tcp:") {
+            let (host, port) = addr
+                .rsplit_once(':')
+                .ok_or_else(|| format!("{:?}: lipseste portul (tcp:<host>:<port>)", entry))?;
+            if host.is_empty() {
+                return Err(format!("{:?}: host gol", entry));
+            }
+            match port.parse::<u16>() {
+                Ok(p) if p != 0 => Ok(Dependency::Tcp(addr.to_string())),
+                _ => Err(format!("{:?}: port invalid {:?}", entry, port)),
+            }
+        } else if let Some(path) = entry.strip_prefix("file:") {
+            if path.is_empty() {
+                return Err(format!("{:?}: cale goala", entry));
+            }
+            Ok(Dependency::File(path.to_string()))
+        } else {
+            Err(format!(
+                "{:?}: prefix necunoscut (acceptate: \"tcp:<host>:<port>\", \"file:<cale>\")",
+                entry
+            ))
+        }
+    }
+
+    /// O singura verificare; conexiunea TCP are cel mult `connect_timeout`.
+    async fn is_ready(&self, connect_timeout: Duration) -> bool {
+        match self {
+            Dependency::Tcp(addr) => matches!(
+                tokio::time::timeout(connect_timeout, tokio::net::TcpStream::connect(addr)).await,
+                Ok(Ok(_))
+            ),
+            Dependency::File(path) => tokio::fs::metadata(path).await.is_ok(),
+        }
+    }
+}
+
+impl std::fmt::Display for Dependency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dependency::Tcp(addr) => write!(f, "tcp:{}", addr),
+            Dependency::File(path) => write!(f, "file:{}", path),
+        }
+    }
+}
+
+/// Asteapta dependintele din `startup.wait_for`. `Err` doar la expirare cu
+/// `on_timeout = "fail"`. Intrarile au fost validate de `StartupConfig::peek`.
+pub async fn wait_for_dependencies(cfg: &StartupConfig) -> anyhow::Result<()> {
+    let mut pending: Vec<Dependency> = cfg
+        .wait_for
+        .iter()
+        .filter_map(|entry| Dependency::parse(entry).ok())
+        .collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let started = Instant::now();
+    let timeout = Duration::from_secs(cfg.timeout_secs);
+    let poll = Duration::from_secs(cfg.poll_interval_secs);
+    display::log_info(&format!(
+        "Astept {} dependinte inainte de detectie (timeout {}s): {}",
+        pending.len(),
+        cfg.timeout_secs,
+        join(&pending)
+    ));
+
+    let mut last_progress = started;
+    loop {
+        let mut still_pending = Vec::new();
+        for dep in pending {
+            if dep.is_ready(poll).await {
+                display::log_info(&format!(
+                    "Dependinta disponibila: {} (dupa {:.1}s)",
+                    dep,
+                    started.elapsed().as_secs_f64()
+                ));
+            } else {
+                still_pending.push(dep);
+            }
+        }
+        pending = still_pending;
+        if pending.is_empty() {
+            display::log_info("Toate dependintele sunt disponibile — pornesc detectia");
+            return Ok(());
+        }
+
+        if started.elapsed() >= timeout {
+            let message = format!(
+                "Dependinte indisponibile dupa {}s: {}",
+                cfg.timeout_secs,
+                join(&pending)
+            );
+            if cfg.on_timeout == "continue" {
+                display::log_warning(&format!(
+                    "{} — pornesc totusi (startup.on_timeout = \"continue\")",
+                    message
+                ));
+                return Ok(());
+            }
+            anyhow::bail!("{} (startup.on_timeout = \"fail\")", message);
+        }
+
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            display::log_info(&format!(
+                "Astept inca: {} ({}s ramase)",
+                join(&pending),
+                timeout.saturating_sub(started.elapsed()).as_secs()
+            ));
+        }
+        tokio::time::sleep(poll).await;
+    }
+}
+
+fn join(deps: &[Dependency]) -> String {
+    deps.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependency_parse() {
+        assert_eq!(
+            Dependency::parse("tcp:siem.local:514"),
+            Ok(Dependency::Tcp("siem.local:514".to_string()))
+        );
+        assert_eq!(
+            Dependency::parse("tcp:[::1]:6514"),
+            Ok(Dependency::Tcp("[::1]:6514".to_string()))
+        );
+        assert_eq!(
+            Dependency::parse("file:/etc/ids-rs/assets.csv"),
+            Ok(Dependency::File("/etc/ids-rs/assets.csv".to_string()))
+        );
+        assert!(Dependency::parse("tcp:siem.local").is_err());
+        assert!(Dependency::parse("tcp::514").is_err());
+        assert!(Dependency::parse("tcp:siem.local:0").is_err());
+        assert!(Dependency::parse("file:").is_err());
+        assert!(Dependency::parse("http://siem.local").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_dependencies_timeout_policy() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut cfg = StartupConfig {
+            wait_for: vec![format!("tcp:{}", addr), "file:/".to_string()],
+            timeout_secs: 1,
+            poll_interval_secs: 1,
+            on_timeout: "fail".to_string(),
+        };
+        assert!(wait_for_dependencies(&cfg).await.is_ok());
+
+        cfg.wait_for = vec!["file:/nu/exista/ids-rs".to_string()];
+        assert!(wait_for_dependencies(&cfg).await.is_err());
+        cfg.on_timeout = "continue".to_string();
+        assert!(wait_for_dependencies(&cfg).await.is_ok());
+    }
+}
@@ -0,0 +1,187 @@
+// =============================================================================
+// torexit.rs - Noduri de Iesire Tor (enrichment.tor_exit_list_path)
+// =============================================================================
+//
+// O scanare venita prin Tor este un semnal in sine: sursa isi ascunde deliberat
+// originea, iar IP-ul vazut nu apartine atacatorului. Multe SOC-uri vor aceste
+// alerte separat (alta coada, alta prioritate).
+//
+// Lista nodurilor de iesire este descarcata periodic de un job extern (cron,
+// timer systemd) — IDS-RS nu face trafic spre Internet. Formate acceptate,
+// amestecate in acelasi fisier:
+//
+//   185.220.101.1                       (un IP pe linie)
+//   ExitAddress 185.220.101.1 2026-10-15 06:12:44
+//                                       (check.torproject.org/exit-addresses)
+//   # comentariu                        (ignorat, ca si liniile goale)
+//
+// Liniile din exit-addresses care nu sunt `ExitAddress` (ExitNode, Published,
+// LastStatus) sunt ignorate tacut; orice alta linie care nu este IP este sarita
+// cu un WARN. Fisierul este verificat la 30s (mtime + dimensiune) si reincarcat
+// la schimbare; un fisier disparut sau ilizibil pastreaza lista veche.
+//
+// O alerta a carei sursa este in lista primeste `tor_exit = true`, pe baza
+// careia clasificarea adauga `tor_exit_score`, iar `alerting.routing.tor_exit`
+// poate restrange destinatiile.
+//
+// =============================================================================
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Cate linii invalide sunt citate in avertisment (restul doar numarate).
+const MAX_WARNED_LINES: usize = 3;
+
+/// Multimea IP-urilor nodurilor de iesire. Goala cand calea nu este setata.
+#[derive(Default)]
+pub struct TorExitList {
+    ips: HashSet<IpAddr>,
+}
+
+impl TorExitList {
+    /// Incarca lista. `Err` doar cand fisierul nu poate fi citit; liniile
+    /// invalide sunt raportate in al doilea element (avertisment), nu fatale.
+    pub fn load(path: &str) -> Result<(Self, Option<String>)> {
+        if path.is_empty() {
+            return Ok((Self::default(), None));
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Nu pot citi lista de noduri Tor: {:?}", path))?;
+        let (list, invalid) = Self::parse(&content);
+        let warning = (!invalid.is_empty()).then(|| {
+            let shown: Vec<String> = invalid
+                .iter()
+                .take(MAX_WARNED_LINES)
+                .map(|(n, line)| format!("linia {}: {:?}", n, line))
+                .collect();
+            format!(
+                "Lista Tor {:?}: {} intrari invalide sarite ({}{})",
+                path,
+                invalid.len(),
+                shown.join(", "),
+                if invalid.len() > MAX_WARNED_LINES { ", ..." } else { "" }
+            )
+        });
+        Ok((list, warning))
+    }
+
+    /// Parseaza continutul; intoarce si liniile invalide (numar, text).
+    fn parse(content: &str) -> (Self, Vec<(usize, String)>) {
+        let mut ips = HashSet::new();
+        let mut invalid = Vec::new();
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.split_whitespace();
+            let candidate = match words.next() {
+                Some("ExitAddress") => words.next().unwrap_or_default(),
+                // Celelalte campuri ale formatului exit-addresses.
+                Some("ExitNode" | "Published" | "LastStatus") => continue,
+                Some(first) => first,
+                None => continue,
+            };
+            match candidate.parse::<IpAddr>() {
+                Ok(ip) => {
+                    ips.insert(ip);
+                }
+                Err(_) => invalid.push((i + 1, line.to_string())),
+            }
+        }
+        (Self { ips }, invalid)
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        self.ips.contains(ip)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ips.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ips.is_empty()
+    }
+}
+
+/// Detecteaza inlocuirea fisierului listei (mtime + dimensiune).
+pub struct TorExitWatcher {
+    path: PathBuf,
+    last: Option<(SystemTime, u64)>,
+}
+
+impl TorExitWatcher {
+    /// Amprenta initiala = starea de la incarcare (fara reload imediat).
+    pub fn new(path: &str) -> Self {
+        let path = PathBuf::from(path);
+        let last = fingerprint(&path);
+        Self { path, last }
+    }
+
+    /// True daca fisierul a fost modificat de la ultimul apel. Un fisier
+    /// disparut nu este o schimbare — lista veche ramane activa.
+    pub fn changed(&mut self) -> bool {
+        match fingerprint(&self.path) {
+            Some(current) if Some(current) != self.last => {
+                self.last = Some(current);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn fingerprint(path: &std::path::Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tor_exit_list_formats_and_invalid_lines() {
+        let content = "\
+# descarcat de cron
+185.220.101.1
+ExitNode 0011BD2485AD45D984EC4159C88FC066E5E3300E
+Published 2026-10-15 05:01:55
+LastStatus 2026-10-15 06:00:00
+ExitAddress 185.220.101.2 2026-10-15 06:12:44
+2a0b:f4c2::1
+
+nu-este-ip
+ExitAddress 999.1.1.1 2026-10-15 06:12:44
+";
+        let (list, invalid) = TorExitList::parse(content);
+        assert_eq!(list.len(), 3);
+        assert!(list.contains(&"185.220.101.1".parse().unwrap()));
+        assert!(list.contains(&"185.220.101.2".parse().unwrap()));
+        assert!(list.contains(&"2a0b:f4c2::1".parse().unwrap()));
+        assert!(!list.contains(&"10.0.0.1".parse().unwrap()));
+        assert_eq!(
+            invalid.iter().map(|(n, _)| *n).collect::<Vec<_>>(),
+            vec![9, 10]
+        );
+
+        // Reload la schimbare; fisierul disparut pastreaza lista.
+        let path = std::env::temp_dir().join(format!("ids-rs-tor-{}.txt", std::process::id()));
+        std::fs::write(&path, "185.220.101.1\n").unwrap();
+        let path_str = path.to_str().unwrap();
+        let mut watcher = TorExitWatcher::new(path_str);
+        assert!(!watcher.changed());
+        std::fs::write(&path, "185.220.101.1\n185.220.101.3\nx\n").unwrap();
+        assert!(watcher.changed());
+        let (list, warning) = TorExitList::load(path_str).unwrap();
+        assert_eq!(list.len(), 2);
+        assert!(warning.unwrap().contains("1 intrari invalide"));
+        std::fs::remove_file(&path).unwrap();
+        assert!(!watcher.changed());
+        assert!(TorExitList::load(path_str).is_err());
+    }
+}
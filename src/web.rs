@@ -6,29 +6,42 @@
 //   GET /           → pagina HTML cu graf D3.js force-directed al retelei
 //   GET /api/alerts → JSON cu ultimele N alerte din buffer-ul circular
 //   GET /api/graph  → JSON cu noduri (IP-uri) si muchii (conexiuni) pentru graf
+//   GET /config/detection → configurarea de detectie efectiva (JSON)
+//   PUT /config/detection → ajustare praguri la runtime (opt-in, doar in memorie)
+//   POST /alerts/{id}/false-positive → marcaj fals-pozitiv (opt-in, persistat)
+//   GET /incidents → incidentele deschise (opt-in, `[incidents]`)
+//   POST /incidents/{id}/close → rezolvarea unui incident
 //
 // Arhitectura:
 //   - Ruleaza ca task tokio separat, fara impact asupra detectiei
 //   - Datele vin dintr-un buffer circular Arc<Mutex<VecDeque<Alert>>>
 //   - Mutex-ul este tinut doar cateva microsecunde (push/clone)
-//   - Dashboard-ul este read-only — nu modifica starea detectorului
+//   - Dashboard-ul este read-only — exceptiile sunt PUT /config/detection,
+//     activat explicit prin `web_dashboard.allow_config_updates`, si marcajul
+//     fals-pozitiv, activat prin `false_positives.enabled`, si inchiderea
+//     incidentelor, activata prin `incidents.enabled`
 //
 // NOTA: D3.js este servit inline (nu de pe CDN) pentru a functiona in
 // retele izolate / air-gapped fara acces la internet.
 //
 // =============================================================================
 
-use crate::config::WebDashboardConfig;
-use crate::detector::{Alert, ScanType};
+use crate::alerter::Alerter;
+use crate::config::{AppConfig, DetectionConfig};
+use crate::detector::{Alert, Detector, ScanType};
 use crate::display;
+use crate::feedback::FalsePositiveStore;
+use crate::incidents::IncidentTracker;
+use arc_swap::ArcSwap;
 use axum::{
-    extract::{Query, State},
+    extract::{ConnectInfo, Query, State},
+    http::StatusCode,
     response::Html,
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::{Arc, Mutex};
 
 /// Buffer circular de alerte partajat intre detection loop si web server.
@@ -42,6 +55,18 @@ pub type AlertBuffer = Arc<Mutex<VecDeque<Alert>>>;
 #[derive(Clone)]
 struct AppState {
     alerts: AlertBuffer,
+    detector: Arc<Detector>,
+    /// Primeste detectia noua odata cu detectorul — textul alertelor
+    /// (ferestre, praguri) ramane consistent cu ce detecteaza.
+    alerter: Arc<Alerter>,
+    /// Configuratia activa (inlocuita la fiecare SIGHUP) — context pentru
+    /// validarea unui PUT (verificarile incrucisate detection ↔ cleanup).
+    config: Arc<ArcSwap<AppConfig>>,
+    allow_config_updates: bool,
+    /// Marcaje fals-pozitiv (`[false_positives]`); None = feedback dezactivat.
+    false_positives: Option<Arc<FalsePositiveStore>>,
+    /// Incidentele deschise (`[incidents]`); None = gruparea dezactivata.
+    incidents: Option<Arc<IncidentTracker>>,
 }
 
 // =============================================================================
@@ -49,11 +74,27 @@ struct AppState {
 // =============================================================================
 
 /// Porneste serverul HTTP al dashboard-ului pe un task tokio separat.
+///
+/// `live_config` este configuratia activa, inlocuita de bucla principala la
+/// fiecare SIGHUP; setarile serverului (bind, port) sunt citite o data, aici.
 pub async fn start_web_server(
-    config: &WebDashboardConfig,
+    live_config: Arc<ArcSwap<AppConfig>>,
     alerts: AlertBuffer,
+    detector: Arc<Detector>,
+    alerter: Arc<Alerter>,
+    false_positives: Option<Arc<FalsePositiveStore>>,
+    incidents: Option<Arc<IncidentTracker>>,
 ) -> anyhow::Result<tokio::task::JoinHandle<()>> {
-    let state = AppState { alerts };
+    let config = live_config.load().web_dashboard.clone();
+    let state = AppState {
+        alerts,
+        detector,
+        alerter,
+        config: live_config,
+        allow_config_updates: config.allow_config_updates,
+        false_positives,
+        incidents,
+    };
 
     let app = Router::new()
         .route("/", get(get_dashboard))
@@ -61,6 +102,13 @@ pub async fn start_web_server(
         .route("/api/alerts", get(get_alerts))
         .route("/api/graph", get(get_graph))
         .route("/api/ip/{ip}", get(get_ip_dossier))
+        .route(
+            "/config/detection",
+            get(get_detection_config).put(put_detection_config),
+        )
+        .route("/alerts/{id}/false-positive", post(post_false_positive))
+        .route("/incidents", get(get_incidents))
+        .route("/incidents/{id}/close", post(post_close_incident))
         .with_state(state);
 
     let bind_addr = format!("{}:{}", config.bind, config.port);
@@ -70,8 +118,11 @@ pub async fn start_web_server(
 
     display::log_info(&format!("Web dashboard activ: http://{}", bind_addr));
 
+    // `into_make_service_with_connect_info` expune adresa clientului
+    // (ConnectInfo) — necesara pentru jurnalul de audit al PUT-urilor.
     let handle = tokio::spawn(async move {
-        if let Err(e) = axum::serve(listener, app).await {
+        let service = app.into_make_service_with_connect_info::<SocketAddr>();
+        if let Err(e) = axum::serve(listener, service).await {
             display::log_error(&format!("Web dashboard server error: {:#}", e));
         }
     });
@@ -237,7 +288,7 @@ async fn get_graph(
         Some(ip) => alert_matches_ip(a, ip),
         None => true,
     }) {
-        let ts = alert.timestamp.to_rfc3339();
+        let ts = display::format_rfc3339(&alert.timestamp);
         let stype = alert.scan_type.to_string();
 
         // Acumulam date atacator. DistributedScan este inversat: sursele reale
@@ -267,9 +318,9 @@ async fn get_graph(
             }
         }
 
-        // LateralMovement: fiecare unique_dest e o tinta separata
+        // LateralMovement / SlowSweep: fiecare unique_dest e o tinta separata
         match alert.scan_type {
-            ScanType::LateralMovement => {
+            ScanType::LateralMovement | ScanType::SlowSweep => {
                 for dest in &alert.unique_dests {
                     let t = targets.entry(*dest).or_default();
                     t.alert_count += 1;
@@ -410,14 +461,14 @@ async fn get_ip_dossier(
 
     for alert in buffer.iter() {
         let is_src = match alert.scan_type {
-            ScanType::DistributedScan => {
+            ScanType::DistributedScan | ScanType::CoordinatedSubnet => {
                 alert.source_ip == ip || alert.unique_sources.contains(&ip)
             }
             _ => alert.source_ip == ip,
         };
         let is_dst = match alert.scan_type {
             ScanType::DistributedScan => alert.dest_ip == Some(ip),
-            ScanType::LateralMovement => {
+            ScanType::LateralMovement | ScanType::SlowSweep => {
                 alert.dest_ip == Some(ip) || alert.unique_dests.contains(&ip)
             }
             _ => alert.dest_ip == Some(ip),
@@ -443,7 +494,7 @@ async fn get_ip_dossier(
             }
             let peer = alert.dest_ip.map(|d| d.to_string()).unwrap_or_default();
             timeline.push(DossierEvent {
-                timestamp: alert.timestamp.to_rfc3339(),
+                timestamp: display::format_rfc3339(&alert.timestamp),
                 scan_type: stype.clone(),
                 role: "attacker",
                 peer_ip: peer,
@@ -459,7 +510,7 @@ async fn get_ip_dossier(
             }
             let peer = alert.source_ip.to_string();
             timeline.push(DossierEvent {
-                timestamp: alert.timestamp.to_rfc3339(),
+                timestamp: display::format_rfc3339(&alert.timestamp),
                 scan_type: stype,
                 role: "target",
                 peer_ip: peer,
@@ -501,6 +552,166 @@ async fn get_ip_dossier(
     Json(serde_json::json!(dossier))
 }
 
+// =============================================================================
+// Configurare detectie la runtime (GET/PUT /config/detection)
+// =============================================================================
+
+/// Raspunsul GET/PUT: configurarea efectiva + versiunea politicii.
+fn detection_response(detector: &Detector) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "policy_version": detector.policy_version(),
+        "detection": &*detector.config(),
+    }))
+}
+
+/// GET /config/detection — configurarea de detectie activa (inclusiv valorile implicite).
+async fn get_detection_config(State(state): State<AppState>) -> Json<serde_json::Value> {
+    detection_response(&state.detector)
+}
+
+/// PUT /config/detection — inlocuieste atomic sectiunea `[detection]` in memorie.
+///
+/// Body: sectiunea completa in JSON (acelasi format ca raspunsul GET, campul
+/// `detection`). Validata cu aceleasi reguli ca `config.toml`, in contextul
+/// configuratiei active (cea de dupa ultimul SIGHUP); la succes se aplica prin
+/// `Detector::update_config` (ArcSwap — starea de tracking ramane intacta) si
+/// `Alerter::update_detection`, si se returneaza configurarea efectiva.
+///
+/// Modificarea NU este scrisa in `config.toml`: un SIGHUP sau un restart
+/// revine la fisier. Fiecare PUT reusit lasa o intrare de audit in log.
+async fn put_detection_config(
+    State(state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    Json(new_detection): Json<DetectionConfig>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    if !state.allow_config_updates {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Actualizarea configurarii este dezactivata (web_dashboard.allow_config_updates = false)"
+                .to_string(),
+        ));
+    }
+
+    let validated = state
+        .config
+        .load()
+        .validate_detection(new_detection)
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, format!("{:#}", e)))?;
+
+    let old_policy = state.detector.policy_version();
+    state.detector.update_config(validated.clone());
+    state.alerter.update_detection(validated);
+    let new_policy = state.detector.policy_version();
+
+    display::log_reload(&format!(
+        "AUDIT: PUT /config/detection de la {} — politica {} → {} (doar in memorie)",
+        client.ip(),
+        old_policy,
+        new_policy
+    ));
+
+    Ok(detection_response(&state.detector))
+}
+
+// =============================================================================
+// Feedback fals-pozitiv (POST /alerts/{id}/false-positive)
+// =============================================================================
+
+/// POST /alerts/{id}/false-positive — marcheaza sursa si tipul alertei `id`
+/// ca fals-pozitiv.
+///
+/// Alerta trebuie sa fie inca in buffer-ul dashboard-ului (`max_alerts`). Marcajul
+/// este persistat in `false_positives.file` si se aplica alertelor viitoare cu
+/// aceeasi pereche (IP sursa, tip) timp de `period_hours`. Fiecare marcaj lasa
+/// o intrare de audit in log.
+async fn post_false_positive(
+    State(state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    axum::extract::Path(id): axum::extract::Path<u64>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let Some(store) = state.false_positives.as_ref() else {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Feedback-ul fals-pozitiv este dezactivat (false_positives.enabled = false)".to_string(),
+        ));
+    };
+
+    // Cautam sub lock doar alerta ceruta — clona ei, nu tot buffer-ul.
+    let alert = {
+        let guard = state.alerts.lock().unwrap_or_else(|e| e.into_inner());
+        guard.iter().find(|a| a.id == id).cloned()
+    };
+    let Some(alert) = alert else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("Alerta {} nu exista (sau a iesit din buffer-ul dashboard-ului)", id),
+        ));
+    };
+
+    let fp = store
+        .mark(&alert, Some(client.ip().to_string()))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", e)))?;
+
+    display::log_reload(&format!(
+        "AUDIT: POST /alerts/{}/false-positive de la {} — {} de la {} marcat fals-pozitiv",
+        id,
+        client.ip(),
+        fp.scan_type,
+        fp.source_ip
+    ));
+
+    Ok(Json(serde_json::json!(fp)))
+}
+
+// =============================================================================
+// Incidente (GET /incidents, POST /incidents/{id}/close)
+// =============================================================================
+
+/// Tracker-ul de incidente sau 403 cand gruparea este dezactivata.
+fn incident_tracker(state: &AppState) -> Result<&IncidentTracker, (StatusCode, String)> {
+    state.incidents.as_deref().ok_or_else(|| {
+        (
+            StatusCode::FORBIDDEN,
+            "Gruparea in incidente este dezactivata (incidents.enabled = false)".to_string(),
+        )
+    })
+}
+
+/// GET /incidents — incidentele deschise, cele mai recent active primele,
+/// fiecare cu alertele lui, momentul de start si severitatea curenta.
+async fn get_incidents(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let incidents = incident_tracker(&state)?.open_incidents(chrono::Local::now());
+    Ok(Json(serde_json::json!(incidents)))
+}
+
+/// POST /incidents/{id}/close — rezolva incidentul `id`. O alerta ulterioara a
+/// aceleiasi campanii deschide un incident nou. Fiecare inchidere lasa o
+/// intrare de audit in log.
+async fn post_close_incident(
+    State(state): State<AppState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    axum::extract::Path(id): axum::extract::Path<u64>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let Some(incident) = incident_tracker(&state)?.close(id, chrono::Local::now()) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("Incidentul {} nu este deschis (inchis deja sau expirat)", id),
+        ));
+    };
+
+    display::log_reload(&format!(
+        "AUDIT: POST /incidents/{}/close de la {} — incidentul {} ({} alerte) inchis",
+        id,
+        client.ip(),
+        incident.campaign,
+        incident.alert_count
+    ));
+
+    Ok(Json(serde_json::json!(incident)))
+}
+
 // =============================================================================
 // Dashboard HTML (imbeddeat via include_str!, functioneaza in retele air-gapped)
 // =============================================================================
@@ -515,3 +726,75 @@ async fn get_ip_dossier(
 ///
 /// D3.js v7 e servit separat ca asset static la `/static/d3.min.js` via `get_d3_js`.
 const DASHBOARD_HTML: &str = include_str!("../static/dashboard.html");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::LastErrors;
+
+    fn test_state(config: AppConfig, allow_config_updates: bool) -> AppState {
+        let alerter = Alerter::new(
+            config.alerting.clone(),
+            config.detection.clone(),
+            HashMap::new(),
+            Vec::new(),
+            Arc::new(LastErrors::new()),
+        )
+        .unwrap();
+        AppState {
+            alerts: Arc::new(Mutex::new(VecDeque::new())),
+            detector: Arc::new(Detector::new(config.detection.clone())),
+            alerter: Arc::new(alerter),
+            config: Arc::new(ArcSwap::from_pointee(config)),
+            allow_config_updates,
+            false_positives: None,
+            incidents: None,
+        }
+    }
+
+    fn client() -> ConnectInfo<SocketAddr> {
+        ConnectInfo("127.0.0.1:40000".parse().unwrap())
+    }
+
+    fn base_config() -> AppConfig {
+        let mut config = AppConfig::load("config.toml").unwrap();
+        config.cleanup.max_entry_age_secs = 600;
+        config.detection.slow_scan.carry_over = 0.0;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_put_detection_forbidden_by_default() {
+        let state = test_state(base_config(), false);
+        let detection = state.detector.config().as_ref().clone();
+        let err = put_detection_config(State(state), client(), Json(detection))
+            .await
+            .unwrap_err();
+        assert_eq!(err.0, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_put_detection_validates_against_live_config() {
+        let state = test_state(base_config(), true);
+        let mut detection = state.detector.config().as_ref().clone();
+        detection.slow_scan.time_window_mins = 20;
+
+        // Fereastra Slow Scan (1200s) peste cleanup.max_entry_age_secs (600s).
+        let err = put_detection_config(State(state.clone()), client(), Json(detection.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(err.0, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(err.1.contains("max_entry_age_secs"), "{}", err.1);
+
+        // Dupa un SIGHUP cu cleanup marit, acelasi PUT trece — si ajunge si la alerter.
+        let mut reloaded = base_config();
+        reloaded.cleanup.max_entry_age_secs = 3600;
+        state.config.store(Arc::new(reloaded));
+        let Json(body) = put_detection_config(State(state.clone()), client(), Json(detection))
+            .await
+            .unwrap();
+        assert_eq!(body["detection"]["slow_scan"]["time_window_mins"], 20);
+        assert_eq!(state.detector.config().slow_scan.time_window_mins, 20);
+        assert_eq!(state.alerter.detection().slow_scan.time_window_mins, 20);
+    }
+}
@@ -15,6 +15,7 @@ Preset-uri rapide (replay automat din fisierele sample pre-generate):
 
 Replay / sample (fisier la alegere):
   python tester.py replay tester/sample2_gaia.log
+  python tester.py replay tester/sample2_gaia.log --replay-speed 10
   python tester.py sample tester/sample2_gaia.log raw-gaia
 
 Generare dinamica (avansat):
@@ -41,6 +42,15 @@ from typing import Optional
 
 SCRIPT_DIR = os.path.dirname(os.path.abspath(__file__))
 
+# Timestamp-ul fix al header-elor syslog cand testerul ruleaza cu --seed:
+# doua rulari cu aceeasi samanta trimit exact aceleasi octeti.
+FIXED_SYSLOG_TS: Optional[str] = None
+
+
+def syslog_ts() -> str:
+    """Timestamp-ul header-ului syslog: ceasul curent sau cel fix (--seed)."""
+    return FIXED_SYSLOG_TS or time.strftime("%b %d %H:%M:%S")
+
 
 # =============================================================================
 # Generatoare de log-uri
@@ -65,7 +75,7 @@ def generate_cef_log(source_ip: str, dst_port: int, action: str = "drop", dest_i
     """Genereaza un log CEF (Common Event Format) realist cu syslog header."""
     severity = 5 if action == "drop" else 3
     name = "Drop" if action == "drop" else "Accept"
-    ts = time.strftime("%b %d %H:%M:%S")
+    ts = syslog_ts()
     return (
         f"<134>{ts} gw-checkpoint CEF:0|CheckPoint|VPN-1 & FireWall-1|R81.20|100|{name}|{severity}|"
         f"src={source_ip} dst={dest_ip} dpt={dst_port} proto=TCP act={action}"
@@ -77,7 +87,7 @@ def generate_gaia_cef_log(source_ip: str, dst_port: int, action: str = "drop", d
     severity = 5 if action == "drop" else 3
     action_cap = action.capitalize()
     proto = "6"  # TCP default
-    ts = time.strftime("%b %d %H:%M:%S")
+    ts = syslog_ts()
     return (
         f'<134>{ts} gw-checkpoint CEF:0|CheckPoint|FW-1|R77|100|'
         f'action="{action_cap}" src="{source_ip}" dst="{dest_ip}" '
@@ -161,7 +171,7 @@ def gaia_to_cef(parsed: dict) -> Optional[str]:
     severity = 5 if action == "drop" else 3
     name = action.capitalize()
 
-    ts = time.strftime("%b %d %H:%M:%S")
+    ts = syslog_ts()
     parts = [
         f"<134>{ts} gw-checkpoint CEF:0|CheckPoint|VPN-1 & FireWall-1|R81.20|{rule}|{name}|{severity}|",
         f"src={src} dst={dst}",
@@ -560,6 +570,28 @@ def simulate_normal(
     print(f"    IDS-RS NU ar trebui sa genereze alerte (sub prag)")
 
 
+_SYSLOG_TS_RE = re.compile(r"^(?:<\d+>)?([A-Z][a-z]{2})\s+(\d{1,2}) (\d{2}):(\d{2}):(\d{2})\s")
+_CEF_RT_RE = re.compile(r"\brt=(\d{13})\b")
+_MONTHS = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"]
+
+
+def _line_timestamp(line: str) -> Optional[float]:
+    """
+    Extrage momentul log-ului (secunde, relativ) pentru replay cu timing real.
+    Suporta header syslog ("Sep 3 15:10:54 ...") si CEF "rt=<epoch ms>".
+    Anul lipseste din syslog — conteaza doar diferentele dintre linii.
+    """
+    m = _CEF_RT_RE.search(line)
+    if m:
+        return int(m.group(1)) / 1000.0
+    m = _SYSLOG_TS_RE.match(line)
+    if m and m.group(1) in _MONTHS:
+        month = _MONTHS.index(m.group(1))
+        day, hh, mm, ss = (int(g) for g in m.groups()[1:])
+        return (((month * 31 + day) * 24 + hh) * 60 + mm) * 60 + ss
+    return None
+
+
 def replay_file(
     sock: socket.socket,
     host: str,
@@ -567,11 +599,24 @@ def replay_file(
     file_path: str,
     delay: float,
     batch_size: int,
+    replay_speed: Optional[float] = None,
 ) -> None:
     """
     Citeste un fisier cu log-uri si trimite fiecare linie catre IDS-RS.
     Formatul log-urilor trebuie sa corespunda parser-ului activ in config.toml.
+    Liniile care incep cu '#' sunt ignorate (antetul fisierelor scrise de
+    IDS-RS in `output.replay_dir`).
+
+    Cu `replay_speed`, pauzele dintre linii sunt cele din timestamp-urile
+    log-urilor, impartite la factor (10 = de 10x mai rapid). `delay` si
+    `batch_size` sunt ignorate. ATENTIE: IDS-RS masoara ferestrele dupa
+    momentul SOSIRII pachetului, nu dupa timestamp-ul din log — la factor N,
+    o fereastra de W secunde acopera N*W secunde din captura.
     """
+    if replay_speed is not None:
+        _replay_file_timed(sock, host, port, file_path, replay_speed)
+        return
+
     print(f"[*] Replay log-uri din: {file_path}")
     print(f"    Delay: {delay}s | Batch: {batch_size}")
     print(f"    Destinatie: {host}:{port}")
@@ -579,7 +624,7 @@ def replay_file(
 
     try:
         with open(file_path, "r", encoding="utf-8") as f:
-            lines = [line.rstrip("\n\r") for line in f if line.strip()]
+            lines = [line.rstrip("\n\r") for line in f if line.strip() and not line.startswith("#")]
     except FileNotFoundError:
         print(f"[!] Eroare: fisierul '{file_path}' nu exista.")
         sys.exit(1)
@@ -623,6 +668,55 @@ def replay_file(
     print(f"[+] Replay complet: {sent_count} log-uri trimise din '{file_path}'")
 
 
+def _replay_file_timed(
+    sock: socket.socket,
+    host: str,
+    port: int,
+    file_path: str,
+    speed: float,
+) -> None:
+    """Replay cu timing relativ pastrat din timestamp-uri, scalat cu `speed`."""
+    if speed <= 0:
+        print("[!] Eroare: --replay-speed trebuie sa fie > 0.")
+        sys.exit(1)
+
+    print(f"[*] Replay cu timing real din: {file_path}")
+    print(f"    Viteza: {speed}x | Destinatie: {host}:{port}")
+    print(f"    Nota: ferestrele IDS-RS acopera {speed}x mai mult timp din captura")
+    print()
+
+    try:
+        with open(file_path, "r", encoding="utf-8") as f:
+            lines = [line.rstrip("\n\r") for line in f if line.strip() and not line.startswith("#")]
+    except (FileNotFoundError, PermissionError) as e:
+        print(f"[!] Eroare: nu pot citi '{file_path}': {e}")
+        sys.exit(1)
+
+    total = len(lines)
+    prev_ts: Optional[float] = None
+    untimed = 0
+    start = time.monotonic()
+
+    for i, line in enumerate(lines):
+        ts = _line_timestamp(line)
+        if ts is None:
+            untimed += 1
+        elif prev_ts is not None and ts > prev_ts:
+            # Timestamp-uri descrescatoare (log-uri interclasate) → fara pauza.
+            time.sleep((ts - prev_ts) / speed)
+        if ts is not None:
+            prev_ts = ts
+
+        send_udp(sock, host, port, line)
+        print(f"  [{i + 1:>4}/{total}] {line[:70]}...")
+
+    elapsed = time.monotonic() - start
+    print()
+    print(f"[+] Replay complet: {total} log-uri in {elapsed:.1f}s ({speed}x)")
+    if untimed:
+        print(f"    {untimed} linii fara timestamp recunoscut — trimise fara pauza")
+
+
 # =============================================================================
 # Sample Mode
 # =============================================================================
@@ -1374,6 +1468,14 @@ def main() -> None:
         help="Portul UDP al IDS-RS (default: 5555)",
     )
 
+    root_parser.add_argument(
+        "--seed",
+        type=int,
+        default=None,
+        help="Samanta generatorului (IP-uri, porturi, timestamp syslog fix): "
+             "aceeasi samanta = acelasi trafic, octet cu octet. Implicit: aleatoare, afisata",
+    )
+
     subparsers = root_parser.add_subparsers(dest="command", help="Modul de testare")
     subparsers.required = True
 
@@ -1576,6 +1678,14 @@ def main() -> None:
         default=1,
         help="Linii per pachet UDP (default: 1)",
     )
+    replay_parser.add_argument(
+        "--replay-speed",
+        type=float,
+        default=None,
+        metavar="FACTOR",
+        help="Pastreaza timing-ul din timestamp-urile log-urilor, scalat cu FACTOR "
+             "(ex: 10 = de 10x mai rapid). Ignora --delay/--batch.",
+    )
 
     # --- sample ---
     sample_parser = subparsers.add_parser(
@@ -1606,11 +1716,20 @@ def main() -> None:
 
     args = root_parser.parse_args()
 
+    # Samanta: fixata (--seed) sau aleasa acum si afisata, ca o rulare
+    # interesanta sa poata fi repetata identic.
+    global FIXED_SYSLOG_TS
+    seed = args.seed if args.seed is not None else random.SystemRandom().randrange(2**32)
+    random.seed(seed)
+    if args.seed is not None:
+        FIXED_SYSLOG_TS = "Sep 03 15:12:00"
+
     # =========================================================================
     # Executie
     # =========================================================================
     print("=" * 60)
     print("  IDS-RS Tester")
+    print(f"  Samanta: {seed} (repetare: --seed {seed})")
     print("=" * 60)
     print()
 
@@ -1704,6 +1823,7 @@ def main() -> None:
                 file_path=args.file,
                 delay=args.delay,
                 batch_size=args.batch,
+                replay_speed=args.replay_speed,
             )
         elif args.command == "sample":
             run_sample(